@@ -0,0 +1,110 @@
+use std::f64::consts::PI;
+use crate::simulator::circuit::Circuit;
+
+/// phase flip of |0…0⟩ (I − 2|0⟩⟨0|) on every qubit of the register; with no
+/// qubits that is the global phase −1, so the circuit is empty
+pub fn zero_reflection(num_qubits: usize) -> Circuit {
+    let mut circuit = Circuit::new(num_qubits);
+    if num_qubits == 0 {
+        return circuit;
+    }
+    let controls: Vec<usize> = (0..num_qubits - 1).collect();
+    for q in 0..num_qubits {
+        circuit.x(q);
+    }
+    circuit.mcz(&controls, num_qubits - 1);
+    for q in 0..num_qubits {
+        circuit.x(q);
+    }
+    circuit
+}
+
+/// A followed by `iterations` rounds of Q = A S₀ A† S_χ
+///
+/// `state_prep` is A, preparing √a|good⟩ + √(1−a)|bad⟩ from |0…0⟩; `oracle` is
+/// the phase oracle S_χ flipping the sign of good states. After k rounds the
+/// good amplitude is sin((2k+1)θ) with sin²θ = a.
+pub fn amplitude_amplification(
+    state_prep: &Circuit,
+    oracle: &Circuit,
+    iterations: usize,
+) -> Circuit {
+    let n = state_prep.num_qubits();
+    assert_eq!(oracle.num_qubits(), n, "oracle width must match state preparation");
+    let prep_inverse = state_prep.inverse();
+    let reflection = zero_reflection(n);
+    let mut circuit = Circuit::new(n);
+    circuit.append(state_prep);
+    for _ in 0..iterations {
+        circuit.append(oracle);
+        circuit.append(&prep_inverse);
+        circuit.append(&reflection);
+        circuit.append(state_prep);
+    }
+    circuit
+}
+
+/// rounds maximizing success for initial success probability `a`: ⌊π/(4θ)⌋
+pub fn optimal_iterations(success_probability: f64) -> usize {
+    assert!(
+        success_probability > 0.0 && success_probability <= 1.0,
+        "probability must be in (0, 1]"
+    );
+    let theta = success_probability.sqrt().asin();
+    (PI / (4.0 * theta)).floor() as usize
+}
+
+/// Grover search: amplitude amplification over the uniform superposition
+pub fn grover(num_qubits: usize, oracle: &Circuit, num_marked: usize) -> Circuit {
+    let mut uniform = Circuit::new(num_qubits);
+    for q in 0..num_qubits {
+        uniform.h(q);
+    }
+    let a = num_marked as f64 / (1usize << num_qubits) as f64;
+    amplitude_amplification(&uniform, oracle, optimal_iterations(a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::oracle::Oracle;
+    use crate::simulator::register::Register;
+
+    #[test]
+    fn test_grover_finds_marked_state() {
+        let oracle = Oracle::from_marked(4, &[0b1011]);
+        let mut register = Register::new(4);
+        register.apply_circuit(&grover(4, &oracle.phase_circuit(), oracle.num_marked()));
+        assert!(register.probabilities()[0b1011] > 0.95);
+    }
+
+    #[test]
+    fn test_amplification_follows_sine_law() {
+        // A = RY(φ) on one qubit, good state |1⟩ with a = sin²(φ/2)
+        let phi = 0.3;
+        let mut prep = Circuit::new(1);
+        prep.ry(phi, 0);
+        let mut oracle = Circuit::new(1);
+        oracle.z(0);
+        for k in 0..4 {
+            let mut register = Register::new(1);
+            register.apply_circuit(&amplitude_amplification(&prep, &oracle, k));
+            let expected = ((2 * k + 1) as f64 * phi / 2.0).sin().powi(2);
+            assert!((register.probabilities()[1] - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_zero_reflection() {
+        // only |0…0⟩ changes sign; no qubits leaves just a global phase
+        let u = zero_reflection(2).to_unitary();
+        assert!((u[(0, 0)].re + 1.0).abs() < 1e-12 && (u[(3, 3)].re - 1.0).abs() < 1e-12);
+        assert!(zero_reflection(0).is_empty());
+    }
+
+    #[test]
+    fn test_optimal_iterations() {
+        assert_eq!(optimal_iterations(1.0 / 16.0), 3);
+        assert_eq!(optimal_iterations(0.25), 1);
+    }
+}
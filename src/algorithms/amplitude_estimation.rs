@@ -0,0 +1,178 @@
+use std::f64::consts::PI;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+use super::amplitude_amplification::{amplitude_amplification, zero_reflection};
+use super::qpe::{counting_value, phase_estimation};
+
+/// estimated success probability and the number of calls to A (or A†) spent
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmplitudeEstimate {
+    pub estimate: f64,
+    pub queries: usize,
+}
+
+/// Q = A S₀ A† S_χ
+///
+/// This is −Q of Brassard et al., so its eigenphases are ½ ± θ/π rather than
+/// ±θ/π (sin²θ = a); `qpe_amplitude_estimation` accounts for the shift.
+pub fn grover_operator(state_prep: &Circuit, oracle: &Circuit) -> Circuit {
+    let mut q = oracle.clone();
+    q.append(&state_prep.inverse());
+    q.append(&zero_reflection(state_prep.num_qubits()));
+    q.append(state_prep);
+    q
+}
+
+/// canonical amplitude estimation: QPE on Q with `num_counting` qubits
+pub fn qpe_amplitude_estimation(
+    state_prep: &Circuit,
+    oracle: &Circuit,
+    num_counting: usize,
+) -> AmplitudeEstimate {
+    let n = state_prep.num_qubits();
+    let q = grover_operator(state_prep, oracle);
+    let mut register = Register::new(n + num_counting);
+    register.apply_circuit(&phase_estimation(&q, state_prep, num_counting));
+    let (best, _) = register.distribution().top_k(1)[0];
+    let phase = counting_value(best, n) as f64 / (1usize << num_counting) as f64;
+    AmplitudeEstimate {
+        estimate: (PI * phase).cos().powi(2),
+        queries: 2 * ((1usize << num_counting) - 1) + 1,
+    }
+}
+
+/// quantum counting: estimated number of marked items among 2^n
+pub fn quantum_counting(num_qubits: usize, oracle: &Circuit, num_counting: usize) -> f64 {
+    let mut uniform = Circuit::new(num_qubits);
+    for q in 0..num_qubits {
+        uniform.h(q);
+    }
+    let estimate = qpe_amplitude_estimation(&uniform, oracle, num_counting).estimate;
+    estimate * (1usize << num_qubits) as f64
+}
+
+/// P(good) after `iterations` rounds of amplification
+fn good_probability(
+    state_prep: &Circuit,
+    oracle: &Circuit,
+    iterations: usize,
+    is_good: &impl Fn(usize) -> bool,
+) -> f64 {
+    let mut register = Register::new(state_prep.num_qubits());
+    register.apply_circuit(&amplitude_amplification(state_prep, oracle, iterations));
+    register
+        .probabilities()
+        .iter()
+        .enumerate()
+        .filter(|(k, _)| is_good(*k))
+        .map(|(_, p)| p)
+        .sum()
+}
+
+/// maximum-likelihood amplitude estimation (Suzuki et al., 2020)
+///
+/// Runs `shots` measurements after each power m in `powers` of Q and maximizes
+/// Π sin²((2m+1)θ)^h cos²((2m+1)θ)^(shots−h) over a grid in θ.
+pub fn ml_amplitude_estimation(
+    state_prep: &Circuit,
+    oracle: &Circuit,
+    is_good: impl Fn(usize) -> bool,
+    powers: &[usize],
+    shots: usize,
+    rng: &mut Rng,
+) -> AmplitudeEstimate {
+    let hits: Vec<usize> = powers
+        .iter()
+        .map(|&m| {
+            let p = good_probability(state_prep, oracle, m, &is_good);
+            (0..shots).filter(|_| rng.gen_bool(p)).count()
+        })
+        .collect();
+    let log_likelihood = |theta: f64| -> f64 {
+        powers
+            .iter()
+            .zip(&hits)
+            .map(|(&m, &h)| {
+                let angle = (2 * m + 1) as f64 * theta;
+                let p = angle.sin().powi(2).clamp(1e-15, 1.0 - 1e-15);
+                h as f64 * p.ln() + (shots - h) as f64 * (1.0 - p).ln()
+            })
+            .sum()
+    };
+    const GRID: usize = 20_000;
+    let theta = (0..=GRID)
+        .map(|k| k as f64 / GRID as f64 * PI / 2.0)
+        .max_by(|a, b| log_likelihood(*a).total_cmp(&log_likelihood(*b)))
+        .unwrap_or(0.0);
+    AmplitudeEstimate {
+        estimate: theta.sin().powi(2),
+        queries: powers.iter().map(|&m| shots * (2 * m + 1)).sum(),
+    }
+}
+
+/// classical baseline: fraction of good outcomes in `shots` samples of A|0⟩
+pub fn direct_sampling_estimate(
+    state_prep: &Circuit,
+    is_good: impl Fn(usize) -> bool,
+    shots: usize,
+    rng: &mut Rng,
+) -> AmplitudeEstimate {
+    let mut register = Register::new(state_prep.num_qubits());
+    register.apply_circuit(state_prep);
+    let counts = register.distribution().sample_counts(shots, rng);
+    let good: usize = counts.iter().filter(|(k, _)| is_good(**k)).map(|(_, c)| c).sum();
+    AmplitudeEstimate {
+        estimate: good as f64 / shots as f64,
+        queries: shots,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::oracle::Oracle;
+
+    fn rotation_prep(theta: f64) -> (Circuit, Circuit) {
+        // a = sin²θ with good state |1⟩
+        let mut prep = Circuit::new(1);
+        prep.ry(2.0 * theta, 0);
+        let mut oracle = Circuit::new(1);
+        oracle.z(0);
+        (prep, oracle)
+    }
+
+    #[test]
+    fn test_qpe_estimate_exact_on_grid() {
+        let (prep, oracle) = rotation_prep(PI / 8.0);
+        let result = qpe_amplitude_estimation(&prep, &oracle, 3);
+        assert!((result.estimate - (PI / 8.0).sin().powi(2)).abs() < 1e-10);
+        assert_eq!(result.queries, 15);
+    }
+
+    #[test]
+    fn test_quantum_counting() {
+        let oracle = Oracle::from_marked(4, &[0b0011, 0b0101, 0b1111]);
+        let count = quantum_counting(4, &oracle.phase_circuit(), 6);
+        assert!((count - 3.0).abs() < 0.5, "count = {}", count);
+        // marking |0…0⟩ makes the constant ANF term a relative phase under QPE
+        for marked in [&[0][..], &[0, 3, 5]] {
+            let oracle = Oracle::from_marked(4, marked);
+            let count = quantum_counting(4, &oracle.phase_circuit(), 6);
+            assert!((count - marked.len() as f64).abs() < 0.5, "count = {}", count);
+        }
+    }
+
+    #[test]
+    fn test_mlae_beats_budget_of_direct_sampling() {
+        let theta = 0.2f64.sqrt().asin();
+        let (prep, oracle) = rotation_prep(theta);
+        let mut rng = Rng::seed_from_u64(31);
+        let powers = [0, 1, 2, 4, 8];
+        let ml = ml_amplitude_estimation(&prep, &oracle, |k| k == 1, &powers, 100, &mut rng);
+        assert!((ml.estimate - 0.2).abs() < 0.01, "estimate = {}", ml.estimate);
+        let direct = direct_sampling_estimate(&prep, |k| k == 1, ml.queries, &mut rng);
+        assert_eq!(direct.queries, ml.queries);
+        assert!((direct.estimate - 0.2).abs() < 0.05);
+    }
+}
@@ -0,0 +1,235 @@
+use std::f64::consts::PI;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::{phase_matrix, Gate};
+use super::qft::qft_on;
+
+/// Cuccaro MAJ block
+fn majority(circuit: &mut Circuit, c: usize, b: usize, a: usize) {
+    circuit.cx(a, b).cx(a, c).mcx(&[c, b], a);
+}
+
+/// Cuccaro UMA block (2-CNOT version)
+fn unmajority(circuit: &mut Circuit, c: usize, b: usize, a: usize) {
+    circuit.mcx(&[c, b], a).cx(a, c).cx(c, b);
+}
+
+/// Cuccaro ripple-carry adder, b ← a + b with the carry-out in z
+///
+/// Layout: a = 0..n, b = n..2n, ancilla 2n (must be |0⟩), z = 2n+1.
+pub fn ripple_carry_adder(n: usize) -> Circuit {
+    assert!(n > 0, "adder needs at least one bit");
+    let a = |i: usize| i;
+    let b = |i: usize| n + i;
+    let ancilla = 2 * n;
+    let carry_out = 2 * n + 1;
+    let mut circuit = Circuit::new(2 * n + 2);
+    majority(&mut circuit, ancilla, b(0), a(0));
+    for i in 1..n {
+        majority(&mut circuit, a(i - 1), b(i), a(i));
+    }
+    circuit.cx(a(n - 1), carry_out);
+    for i in (1..n).rev() {
+        unmajority(&mut circuit, a(i - 1), b(i), a(i));
+    }
+    unmajority(&mut circuit, ancilla, b(0), a(0));
+    circuit
+}
+
+/// Fourier-space addition of the constant `a` to register `b` (little-endian)
+///
+/// `b` must already be QFT-transformed; each qubit j picks up e^{2πi·a·2^j/2^m}.
+/// With `controls` every rotation is conditioned on all of them.
+pub fn phi_add(num_qubits: usize, b: &[usize], a: u64, controls: &[usize]) -> Circuit {
+    let m = b.len() as u32;
+    let mut circuit = Circuit::new(num_qubits);
+    for (j, &q) in b.iter().enumerate() {
+        let angle = 2.0 * PI * ((a << j) % (1u64 << m)) as f64 / (1u64 << m) as f64;
+        if angle == 0.0 {
+            continue;
+        }
+        let mut qubits = controls.to_vec();
+        qubits.push(q);
+        circuit.push(Gate::Mcu(controls.len(), phase_matrix(angle).into()), &qubits);
+    }
+    circuit
+}
+
+/// Draper QFT adder, b ← a + b mod 2^n; layout a = 0..n, b = n..2n
+pub fn draper_adder(n: usize) -> Circuit {
+    let total = 2 * n;
+    let b: Vec<usize> = (n..total).collect();
+    let mut circuit = qft_on(total, &b);
+    for (j, &target) in b.iter().enumerate() {
+        for i in 0..n - j {
+            // a_i contributes 2^i·2^j/2^n of a full turn on b_j
+            let angle = 2.0 * PI / (1u64 << (n - i - j)) as f64;
+            circuit.cp(angle, i, target);
+        }
+    }
+    circuit.append(&qft_on(total, &b).inverse());
+    circuit
+}
+
+/// b ← b + a mod 2^n for a classical constant, in the computational basis
+pub fn qft_constant_adder(n: usize, a: u64) -> Circuit {
+    let b: Vec<usize> = (0..n).collect();
+    let mut circuit = qft_on(n, &b);
+    circuit.append(&phi_add(n, &b, a, &[]));
+    circuit.append(&qft_on(n, &b).inverse());
+    circuit
+}
+
+/// Beauregard φADD(a) mod N on a Fourier-space register `b` of n+1 qubits
+///
+/// Requires a, b < N; `ancilla` starts and ends in |0⟩. Only the additions of
+/// `a` are conditioned on `controls`.
+pub fn phi_add_mod(
+    num_qubits: usize,
+    b: &[usize],
+    ancilla: usize,
+    a: u64,
+    modulus: u64,
+    controls: &[usize],
+) -> Circuit {
+    let msb = *b.last().expect("register must not be empty");
+    let qft = qft_on(num_qubits, b);
+    let iqft = qft.inverse();
+    let add_a = phi_add(num_qubits, b, a, controls);
+    let add_n = phi_add(num_qubits, b, modulus, &[]);
+
+    let mut circuit = Circuit::new(num_qubits);
+    circuit.append(&add_a);
+    circuit.append(&add_n.inverse());
+    circuit.append(&iqft);
+    circuit.cx(msb, ancilla);
+    circuit.append(&qft);
+    circuit.append(&phi_add(num_qubits, b, modulus, &[ancilla]));
+    circuit.append(&add_a.inverse());
+    circuit.append(&iqft);
+    circuit.x(msb).cx(msb, ancilla).x(msb);
+    circuit.append(&qft);
+    circuit.append(&add_a);
+    circuit
+}
+
+/// b ← (a + b) mod N in the computational basis
+///
+/// Layout: b = 0..=n (n+1 qubits, b < N < 2^n), ancilla n+1.
+pub fn modular_adder(n: usize, a: u64, modulus: u64) -> Circuit {
+    let total = n + 2;
+    let b: Vec<usize> = (0..=n).collect();
+    let mut circuit = qft_on(total, &b);
+    circuit.append(&phi_add_mod(total, &b, n + 1, a % modulus, modulus, &[]));
+    circuit.append(&qft_on(total, &b).inverse());
+    circuit
+}
+
+/// CMULT(a) mod N: b ← b + a·x mod N when the control is set
+///
+/// Layout: control 0, x = 1..=n, b = n+1..=2n+1 (n+1 qubits), ancilla 2n+2.
+pub fn controlled_modular_multiplier(n: usize, a: u64, modulus: u64) -> Circuit {
+    let total = 2 * n + 3;
+    let x: Vec<usize> = (1..=n).collect();
+    let b: Vec<usize> = (n + 1..=2 * n + 1).collect();
+    let ancilla = 2 * n + 2;
+    let mut circuit = qft_on(total, &b);
+    for (i, &xi) in x.iter().enumerate() {
+        let addend = (a % modulus) * ((1u64 << i) % modulus) % modulus;
+        circuit.append(&phi_add_mod(total, &b, ancilla, addend, modulus, &[0, xi]));
+    }
+    circuit.append(&qft_on(total, &b).inverse());
+    circuit
+}
+
+/// a⁻¹ mod N, if gcd(a, N) = 1
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    let (mut old_r, mut r) = (a as i128 % modulus as i128, modulus as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    (old_r == 1).then(|| old_s.rem_euclid(modulus as i128) as u64)
+}
+
+/// controlled U_a: x ← a·x mod N in place, with the same layout as
+/// `controlled_modular_multiplier`; b and the ancilla return to |0⟩
+pub fn controlled_modular_multiply_in_place(n: usize, a: u64, modulus: u64) -> Circuit {
+    let inverse = mod_inverse(a, modulus).expect("a must be invertible mod N");
+    let mut circuit = controlled_modular_multiplier(n, a, modulus);
+    for i in 0..n {
+        let (x, b) = (1 + i, n + 1 + i);
+        circuit.mcx(&[0, x], b).mcx(&[0, b], x).mcx(&[0, x], b);
+    }
+    circuit.append(&controlled_modular_multiplier(n, inverse, modulus).inverse());
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::register::Register;
+
+    /// run `circuit` on basis state `input` and return the unique output index
+    fn run_basis(circuit: &Circuit, input: usize) -> usize {
+        let mut register = Register::new(circuit.num_qubits());
+        for q in 0..circuit.num_qubits() {
+            if input >> q & 1 == 1 {
+                register.apply_circuit(Circuit::new(circuit.num_qubits()).x(q));
+            }
+        }
+        register.apply_circuit(circuit);
+        let (outcome, p) = register.distribution().top_k(1)[0];
+        assert!((p - 1.0).abs() < 1e-8, "output is not a basis state");
+        outcome
+    }
+
+    #[test]
+    fn test_ripple_carry_exhaustive() {
+        let n = 3;
+        for a in 0..8usize {
+            for b in 0..8usize {
+                let out = run_basis(&ripple_carry_adder(n), a | b << n);
+                assert_eq!(out & 7, a);
+                assert_eq!(out >> n & 7, (a + b) % 8);
+                assert_eq!(out >> (2 * n + 1), (a + b) / 8);
+            }
+        }
+    }
+
+    #[test]
+    fn test_qft_adders() {
+        for a in 0..4usize {
+            for b in 0..4usize {
+                assert_eq!(run_basis(&draper_adder(2), a | b << 2) >> 2, (a + b) % 4);
+            }
+        }
+        assert_eq!(run_basis(&qft_constant_adder(3, 5), 6), (6 + 5) % 8);
+    }
+
+    #[test]
+    fn test_modular_adder() {
+        let modulus = 5;
+        for a in 0..modulus {
+            for b in 0..modulus {
+                let out = run_basis(&modular_adder(3, a, modulus), b as usize);
+                assert_eq!(out as u64, (a + b) % modulus, "a={} b={}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_controlled_multiply_in_place() {
+        let (n, a, modulus) = (4, 7, 15);
+        let circuit = controlled_modular_multiply_in_place(n, a, modulus);
+        for x in [1u64, 4, 11] {
+            let on = run_basis(&circuit, 1 | (x as usize) << 1);
+            assert_eq!(on, 1 | ((a * x % modulus) as usize) << 1);
+            let off = run_basis(&circuit, (x as usize) << 1);
+            assert_eq!(off, (x as usize) << 1);
+        }
+        assert_eq!(mod_inverse(7, 15), Some(13));
+        assert_eq!(mod_inverse(6, 15), None);
+    }
+}
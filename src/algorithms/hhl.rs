@@ -0,0 +1,126 @@
+use num_complex::Complex64;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::{ry_matrix, Gate};
+use crate::simulator::operator::Operator;
+use crate::simulator::register::Register;
+use super::qpe::phase_estimation;
+
+/// parameters of a single-qubit HHL instance
+///
+/// Eigenvalues of A must be positive and below 2π/`time`; they are resolved
+/// exactly when λ·time/2π is a multiple of 2^-`clock_qubits`. `c` must not
+/// exceed the smallest eigenvalue.
+#[derive(Debug, Clone)]
+pub struct HhlConfig {
+    pub clock_qubits: usize,
+    pub time: f64,
+    pub c: f64,
+}
+
+/// post-selected output of an HHL run
+#[derive(Debug, Clone)]
+pub struct HhlResult {
+    /// normalized |x⟩ ∝ A⁻¹|b⟩
+    pub solution: [Complex64; 2],
+    /// probability of measuring the ancilla in |1⟩
+    pub success_probability: f64,
+    pub circuit: Circuit,
+}
+
+/// RY/phase preparation of the normalized 2-vector `b` on qubit 0
+fn prepare_b(b: [Complex64; 2], num_qubits: usize) -> Circuit {
+    let norm = (b[0].norm_sqr() + b[1].norm_sqr()).sqrt();
+    let theta = 2.0 * (b[0].norm() / norm).clamp(0.0, 1.0).acos();
+    let mut circuit = Circuit::new(num_qubits);
+    circuit.ry(theta, 0).phase(b[1].arg() - b[0].arg(), 0);
+    circuit
+}
+
+/// build the full HHL circuit: b-register qubit 0, clock qubits 1..=m, ancilla m+1
+pub fn hhl_circuit(a: &Operator, b: [Complex64; 2], config: &HhlConfig) -> Circuit {
+    assert_eq!(a.num_qubits(), 1, "HHL here solves 2×2 systems");
+    let m = config.clock_qubits;
+    let total = m + 2;
+    let ancilla = m + 1;
+    let evolution = a.matrix().scaled(Complex64::new(0.0, config.time)).expm();
+    let mut unitary = Circuit::new(1);
+    unitary.push(Gate::Mcu(0, Operator::new(evolution)), &[0]);
+
+    let qpe = phase_estimation(&unitary, &Circuit::new(1), m);
+    let mut circuit = prepare_b(b, total);
+    circuit.append(&qpe);
+    // eigenvalue rotation: clock value y encodes λ = 2πy / (2^m t)
+    let clock: Vec<usize> = (1..=m).collect();
+    for y in 1..1usize << m {
+        let lambda = 2.0 * std::f64::consts::PI * y as f64 / ((1usize << m) as f64 * config.time);
+        let ratio = (config.c / lambda).min(1.0);
+        let flips: Vec<usize> = clock
+            .iter()
+            .copied()
+            .filter(|&q| (y >> (q - 1)) & 1 == 0)
+            .collect();
+        for &q in &flips {
+            circuit.x(q);
+        }
+        let mut qubits = clock.clone();
+        qubits.push(ancilla);
+        circuit.push(Gate::Mcu(m, ry_matrix(2.0 * ratio.asin()).into()), &qubits);
+        for &q in &flips {
+            circuit.x(q);
+        }
+    }
+    circuit.append(&qpe.inverse());
+    circuit
+}
+
+/// solve A|x⟩ ∝ |b⟩ for a 2×2 Hermitian positive-definite A
+pub fn hhl(a: &Operator, b: [Complex64; 2], config: &HhlConfig) -> HhlResult {
+    let circuit = hhl_circuit(a, b, config);
+    let ancilla = config.clock_qubits + 1;
+    let mut register = Register::new(config.clock_qubits + 2);
+    register.apply_circuit(&circuit);
+    let success_probability = register.postselect(ancilla, true);
+    // clock is uncomputed to |0…0⟩, so the solution sits at b-index | ancilla bit
+    let amps = register.amplitudes();
+    let offset = 1 << ancilla;
+    let x = [amps[offset], amps[offset | 1]];
+    let norm = (x[0].norm_sqr() + x[1].norm_sqr()).sqrt();
+    HhlResult {
+        solution: [x[0] / norm, x[1] / norm],
+        success_probability,
+        circuit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn c(re: f64) -> Complex64 {
+        Complex64::new(re, 0.0)
+    }
+
+    #[test]
+    fn test_solves_two_by_two_system() {
+        // eigenvalues 1 and 2; with t = π/2 and two clock qubits they read y = 1, 2
+        let a = Operator::from([[c(1.5), c(0.5)], [c(0.5), c(1.5)]]);
+        let b = [c(1.0), c(0.0)];
+        let config = HhlConfig {
+            clock_qubits: 2,
+            time: PI / 2.0,
+            c: 1.0,
+        };
+        let result = hhl(&a, b, &config);
+        // A⁻¹ b = (0.75, -0.25)
+        let expected = [c(0.75), c(-0.25)];
+        let norm = (0.75f64.powi(2) + 0.25f64.powi(2)).sqrt();
+        let overlap = (result.solution[0].conj() * expected[0]
+            + result.solution[1].conj() * expected[1])
+            .norm()
+            / norm;
+        assert!((overlap - 1.0).abs() < 1e-9, "overlap = {}", overlap);
+        // |b⟩ splits evenly over the eigenvectors: ½(C/1)² + ½(C/2)²
+        assert!((result.success_probability - 0.625).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,30 @@
+pub mod amplitude_amplification;
+pub mod arithmetic;
+pub mod amplitude_estimation;
+pub mod hhl;
+pub mod oracle;
+pub mod qdrift;
+pub mod qft;
+pub mod qpe;
+pub mod quantum_krylov;
+pub mod quantum_walk;
+pub mod swap_test;
+pub mod trotter;
+
+pub use amplitude_amplification::{amplitude_amplification, grover, optimal_iterations};
+pub use amplitude_estimation::{
+    ml_amplitude_estimation, qpe_amplitude_estimation, quantum_counting, AmplitudeEstimate,
+};
+pub use hhl::{hhl, HhlConfig, HhlResult};
+pub use oracle::Oracle;
+pub use qdrift::{
+    qdrift_circuit, qdrift_error_bound, qdrift_expectation, qdrift_samples_for, QdriftEstimate,
+};
+pub use qft::{inverse_qft, qft};
+pub use qpe::phase_estimation;
+pub use quantum_krylov::KrylovSubspace;
+pub use swap_test::{destructive_swap_test, swap_test, OverlapEstimate};
+pub use trotter::{
+    pauli_exponential, trotter_circuit, trotter_error, trotter_error_bound, trotter_steps_for,
+    TrotterOrder,
+};
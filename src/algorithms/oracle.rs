@@ -0,0 +1,159 @@
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::{identity_matrix, Gate};
+
+/// classical boolean function f: {0,1}^n → {0,1} with circuit synthesis
+///
+/// Circuits come from the algebraic normal form (f as an XOR of AND-monomials),
+/// so each monomial becomes a single multi-controlled Z or X with no extra
+/// negations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Oracle {
+    num_inputs: usize,
+    truth_table: Vec<bool>,
+}
+
+impl Oracle {
+    pub fn from_fn(num_inputs: usize, f: impl Fn(usize) -> bool) -> Self {
+        Self {
+            num_inputs,
+            truth_table: (0..1usize << num_inputs).map(f).collect(),
+        }
+    }
+
+    /// entry k is f(k); length must be 2^n
+    pub fn from_truth_table(truth_table: Vec<bool>) -> Self {
+        assert!(truth_table.len().is_power_of_two(), "truth table length must be a power of two");
+        Self {
+            num_inputs: truth_table.len().trailing_zeros() as usize,
+            truth_table,
+        }
+    }
+
+    /// marks exactly the listed inputs
+    pub fn from_marked(num_inputs: usize, marked: &[usize]) -> Self {
+        Self::from_fn(num_inputs, |x| marked.contains(&x))
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    pub fn is_marked(&self, x: usize) -> bool {
+        self.truth_table[x]
+    }
+
+    pub fn marked(&self) -> Vec<usize> {
+        (0..self.truth_table.len()).filter(|&x| self.truth_table[x]).collect()
+    }
+
+    pub fn num_marked(&self) -> usize {
+        self.truth_table.iter().filter(|&&b| b).count()
+    }
+
+    /// ANF coefficients via the binary Möbius transform: monomial m is present
+    /// when coefficient m is set
+    fn anf(&self) -> Vec<bool> {
+        let mut coeffs = self.truth_table.clone();
+        for bit in 0..self.num_inputs {
+            for m in 0..coeffs.len() {
+                if m >> bit & 1 == 1 {
+                    coeffs[m] ^= coeffs[m ^ (1 << bit)];
+                }
+            }
+        }
+        coeffs
+    }
+
+    fn variables(&self, monomial: usize) -> Vec<usize> {
+        (0..self.num_inputs).filter(|q| monomial >> q & 1 == 1).collect()
+    }
+
+    /// phase oracle |x⟩ → (−1)^f(x)|x⟩ on n qubits
+    ///
+    /// The constant monomial is emitted as −I rather than dropped: it is only
+    /// a global phase until the oracle is controlled, as in QPE.
+    pub fn phase_circuit(&self) -> Circuit {
+        let mut circuit = Circuit::new(self.num_inputs);
+        for (monomial, present) in self.anf().into_iter().enumerate() {
+            if !present {
+                continue;
+            }
+            if monomial == 0 {
+                if self.num_inputs > 0 {
+                    let minus = identity_matrix().map(|row| row.map(|a| -a));
+                    circuit.push(Gate::Mcu(0, minus.into()), &[0]);
+                }
+                continue;
+            }
+            let vars = self.variables(monomial);
+            let (target, controls) = vars.split_last().expect("non-constant monomial");
+            circuit.mcz(controls, *target);
+        }
+        circuit
+    }
+
+    /// bit-flip oracle |x⟩|y⟩ → |x⟩|y ⊕ f(x)⟩ with the output on qubit n
+    pub fn bit_flip_circuit(&self) -> Circuit {
+        let output = self.num_inputs;
+        let mut circuit = Circuit::new(self.num_inputs + 1);
+        for (monomial, present) in self.anf().into_iter().enumerate() {
+            if present {
+                circuit.mcx(&self.variables(monomial), output);
+            }
+        }
+        circuit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::register::Register;
+
+    #[test]
+    fn test_phase_oracle_signs() {
+        let oracle = Oracle::from_fn(3, |x| x.count_ones() == 2);
+        let mut register = Register::new(3);
+        register.apply_circuit(Circuit::new(3).h(0).h(1).h(2));
+        register.apply_circuit(&oracle.phase_circuit());
+        let amps = register.amplitudes();
+        // relative to |000⟩, marked states carry the opposite sign
+        for x in 0..8 {
+            let relative = amps[x] / amps[0];
+            let expected = if oracle.is_marked(x) { -1.0 } else { 1.0 };
+            assert!((relative.re - expected).abs() < 1e-10, "x = {}", x);
+        }
+    }
+
+    #[test]
+    fn test_phase_oracle_keeps_constant_sign() {
+        // f(0) = 1 puts a −1 on |000⟩ itself, not just a global phase
+        let oracle = Oracle::from_marked(3, &[0, 5]);
+        let mut register = Register::new(3);
+        register.apply_circuit(Circuit::new(3).h(0).h(1).h(2));
+        register.apply_circuit(&oracle.phase_circuit());
+        for (x, amp) in register.amplitudes().iter().enumerate() {
+            let expected = if oracle.is_marked(x) { -1.0 } else { 1.0 };
+            assert!((amp.re * 8f64.sqrt() - expected).abs() < 1e-10, "x = {}", x);
+        }
+    }
+
+    #[test]
+    fn test_bit_flip_oracle_from_truth_table() {
+        let table = vec![true, false, false, true, true, true, false, false];
+        let oracle = Oracle::from_truth_table(table.clone());
+        let circuit = oracle.bit_flip_circuit();
+        for (x, &fx) in table.iter().enumerate() {
+            let mut register = Register::new(4);
+            for q in 0..3 {
+                if x >> q & 1 == 1 {
+                    register.apply_circuit(Circuit::new(4).x(q));
+                }
+            }
+            register.apply_circuit(&circuit);
+            let expected = x | (fx as usize) << 3;
+            assert!((register.probabilities()[expected] - 1.0).abs() < 1e-10);
+        }
+        assert_eq!(oracle.num_marked(), 4);
+    }
+}
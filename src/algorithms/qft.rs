@@ -0,0 +1,63 @@
+use std::f64::consts::PI;
+use crate::simulator::circuit::Circuit;
+
+/// QFT on `qubits` (qubits[0] least significant): |x⟩ → Σ_y e^{2πixy/2^m}|y⟩/√2^m
+pub fn qft_on(num_qubits: usize, qubits: &[usize]) -> Circuit {
+    let m = qubits.len();
+    let mut circuit = Circuit::new(num_qubits);
+    for j in (0..m).rev() {
+        circuit.h(qubits[j]);
+        for k in (0..j).rev() {
+            circuit.cp(PI / (1u64 << (j - k)) as f64, qubits[k], qubits[j]);
+        }
+    }
+    for i in 0..m / 2 {
+        circuit.swap(qubits[i], qubits[m - 1 - i]);
+    }
+    circuit
+}
+
+/// QFT on the whole register
+pub fn qft(num_qubits: usize) -> Circuit {
+    let qubits: Vec<usize> = (0..num_qubits).collect();
+    qft_on(num_qubits, &qubits)
+}
+
+/// inverse QFT on the whole register
+pub fn inverse_qft(num_qubits: usize) -> Circuit {
+    qft(num_qubits).inverse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64;
+    use crate::simulator::register::Register;
+
+    #[test]
+    fn test_qft_matches_dft() {
+        let n = 3;
+        let dim = 1 << n;
+        let x = 5;
+        let mut register = Register::new(n);
+        register.apply_circuit(Circuit::new(n).x(0).x(2));
+        register.apply_circuit(&qft(n));
+        let expected: Vec<Complex64> = (0..dim)
+            .map(|y| {
+                let angle = 2.0 * PI * (x * y) as f64 / dim as f64;
+                Complex64::from_polar(1.0 / (dim as f64).sqrt(), angle)
+            })
+            .collect();
+        crate::assert_state_eq!(register, Register::from_amplitudes(expected), 1e-10);
+    }
+
+    #[test]
+    fn test_inverse_qft_roundtrip() {
+        let mut register = Register::new(4);
+        register.apply_circuit(Circuit::new(4).x(1).h(3));
+        let before = register.clone();
+        register.apply_circuit(&qft(4));
+        register.apply_circuit(&inverse_qft(4));
+        crate::assert_state_eq!(register, before, 1e-10);
+    }
+}
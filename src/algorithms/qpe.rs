@@ -0,0 +1,53 @@
+use crate::simulator::circuit::Circuit;
+use super::qft::qft_on;
+
+/// quantum phase estimation of `unitary` with `num_counting` counting qubits
+///
+/// The target register occupies qubits 0..n (prepared by `eigen_prep`) and the
+/// counting register qubits n..n+m, counting qubit j controlling U^(2^j). For
+/// U|ψ⟩ = e^{2πiφ}|ψ⟩ the counting register reads y ≈ φ·2^m.
+pub fn phase_estimation(unitary: &Circuit, eigen_prep: &Circuit, num_counting: usize) -> Circuit {
+    let n = unitary.num_qubits();
+    let total = n + num_counting;
+    let target: Vec<usize> = (0..n).collect();
+    let counting: Vec<usize> = (n..total).collect();
+    let unitary = unitary.remapped(total, &target);
+    let mut circuit = eigen_prep.remapped(total, &target);
+    for &c in &counting {
+        circuit.h(c);
+    }
+    for (j, &c) in counting.iter().enumerate() {
+        let controlled = unitary.controlled(c);
+        for _ in 0..1usize << j {
+            circuit.append(&controlled);
+        }
+    }
+    circuit.append(&qft_on(total, &counting).inverse());
+    circuit
+}
+
+/// counting-register value y of a full basis index from `phase_estimation`
+pub fn counting_value(outcome: usize, num_target: usize) -> usize {
+    outcome >> num_target
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use crate::simulator::register::Register;
+
+    #[test]
+    fn test_exact_phase() {
+        // P(2π·3/8)|1⟩ = e^{2πi·3/8}|1⟩
+        let mut unitary = Circuit::new(1);
+        unitary.phase(2.0 * PI * 3.0 / 8.0, 0);
+        let mut prep = Circuit::new(1);
+        prep.x(0);
+        let mut register = Register::new(4);
+        register.apply_circuit(&phase_estimation(&unitary, &prep, 3));
+        let (best, p) = register.distribution().top_k(1)[0];
+        assert_eq!(counting_value(best, 1), 3);
+        assert!((p - 1.0).abs() < 1e-10);
+    }
+}
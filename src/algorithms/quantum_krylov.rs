@@ -0,0 +1,183 @@
+use num_complex::Complex64;
+use crate::simulator::hamiltonian::Hamiltonian;
+use crate::simulator::matrix::Matrix;
+use crate::simulator::register::Register;
+use super::trotter::{trotter_circuit, TrotterOrder};
+
+/// Taylor terms below this norm end the series for e^{−iHτ}
+const TAYLOR_CUTOFF: f64 = 1e-14;
+
+/// projections of H onto the span of a few states: overlap S_jk = ⟨ψ_j|ψ_k⟩
+/// and Hamiltonian H_jk = ⟨ψ_j|H|ψ_k⟩
+#[derive(Debug, Clone)]
+pub struct KrylovSubspace {
+    pub overlap: Matrix,
+    pub hamiltonian: Matrix,
+}
+
+fn inner(a: &[Complex64], b: &[Complex64]) -> Complex64 {
+    a.iter().zip(b).map(|(x, y)| x.conj() * y).sum()
+}
+
+/// e^{−iHt}|ψ⟩ by Taylor series on sub-steps short enough that ‖H‖τ ≤ 1
+fn evolve(h: &Hamiltonian, amplitudes: &[Complex64], time: f64) -> Vec<Complex64> {
+    let substeps = (h.one_norm() * time.abs()).ceil().max(1.0) as usize;
+    let tau = time / substeps as f64;
+    let mut state = amplitudes.to_vec();
+    for _ in 0..substeps {
+        let mut term = state.clone();
+        for k in 1..64 {
+            let factor = Complex64::new(0.0, -tau / k as f64);
+            term = h.apply(&term).into_iter().map(|a| a * factor).collect();
+            state.iter_mut().zip(&term).for_each(|(s, t)| *s += t);
+            if inner(&term, &term).re.sqrt() < TAYLOR_CUTOFF {
+                break;
+            }
+        }
+    }
+    state
+}
+
+impl KrylovSubspace {
+    /// project `h` onto the span of `states`, which need not be orthogonal
+    pub fn from_states(h: &Hamiltonian, states: &[Register]) -> Self {
+        assert!(!states.is_empty(), "need at least one state");
+        let amplitudes: Vec<&[Complex64]> = states.iter().map(|s| s.amplitudes()).collect();
+        let applied: Vec<Vec<Complex64>> = amplitudes.iter().map(|a| h.apply(a)).collect();
+        let dim = states.len();
+        Self {
+            overlap: Matrix::from_fn(dim, dim, |j, k| inner(amplitudes[j], amplitudes[k])),
+            hamiltonian: Matrix::from_fn(dim, dim, |j, k| inner(amplitudes[j], &applied[k])),
+        }
+    }
+
+    /// real-time Krylov basis |ψ_k⟩ = e^{−iHk·dt}|ψ₀⟩, k = 0..dimension,
+    /// with exact time evolution
+    pub fn time_evolved(h: &Hamiltonian, initial: &Register, dimension: usize, dt: f64) -> Self {
+        let mut states = vec![initial.clone()];
+        while states.len() < dimension {
+            let last = states.last().unwrap().amplitudes();
+            states.push(Register::from_amplitudes(evolve(h, last, dt)));
+        }
+        Self::from_states(h, &states)
+    }
+
+    /// the same basis with each e^{−iH·dt} replaced by a product formula of
+    /// `steps` steps, as a device would prepare it
+    pub fn trotterized(
+        h: &Hamiltonian,
+        initial: &Register,
+        dimension: usize,
+        dt: f64,
+        steps: usize,
+        order: TrotterOrder,
+    ) -> Self {
+        let step = trotter_circuit(h, dt, steps, order);
+        let mut states = vec![initial.clone()];
+        while states.len() < dimension {
+            let mut next = states.last().unwrap().clone();
+            next.apply_circuit(&step);
+            states.push(next);
+        }
+        Self::from_states(h, &states)
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.overlap.rows()
+    }
+
+    /// generalized eigenvalues of H·c = E·S·c, ascending
+    ///
+    /// S is first diagonalized and directions with eigenvalue below
+    /// `threshold` dropped, since a nearly linearly dependent basis makes the
+    /// problem ill-conditioned; H is then solved in the remaining
+    /// S^{-1/2}-orthonormalized subspace.
+    pub fn solve(&self, threshold: f64) -> Vec<f64> {
+        let s = self.overlap.eigen();
+        let kept: Vec<usize> = (0..self.dimension()).filter(|&k| s.values[k] > threshold).collect();
+        if kept.is_empty() {
+            return Vec::new();
+        }
+        // columns v_k/√s_k of the kept overlap eigenvectors
+        let n = self.dimension();
+        let basis = Matrix::from_fn(n, kept.len(), |i, j| {
+            s.vectors[(i, kept[j])] / s.values[kept[j]].sqrt()
+        });
+        let projected = &(&basis.dagger() * &self.hamiltonian) * &basis;
+        // symmetrize away the round-off of the two products
+        let hermitian = Matrix::from_fn(kept.len(), kept.len(), |i, j| {
+            (projected[(i, j)] + projected[(j, i)].conj()) / 2.0
+        });
+        hermitian.eigen().values
+    }
+
+    /// lowest generalized eigenvalue, `None` when every direction is dropped
+    pub fn ground_energy(&self, threshold: f64) -> Option<f64> {
+        self.solve(threshold).first().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::circuit::Circuit;
+
+    fn real(x: f64) -> Complex64 {
+        Complex64::new(x, 0.0)
+    }
+
+    fn chain() -> Hamiltonian {
+        let mut h = Hamiltonian::new(3);
+        for s in ["ZZI", "IZZ"] {
+            h.add_term(real(-1.0), &s.parse().unwrap());
+        }
+        for s in ["XII", "IXI", "IIX"] {
+            h.add_term(real(-0.8), &s.parse().unwrap());
+        }
+        h.add_term(real(0.3), &"YIY".parse().unwrap());
+        h
+    }
+
+    fn initial() -> Register {
+        let mut register = Register::new(3);
+        register.apply_circuit(Circuit::new(3).h(0).h(1).h(2).ry(0.3, 1));
+        register
+    }
+
+    #[test]
+    fn test_time_evolved_subspace_converges_to_ground_energy() {
+        let h = chain();
+        let exact = h.ground_energy();
+        let errors: Vec<f64> = [2, 4, 6]
+            .iter()
+            .map(|&d| {
+                let subspace = KrylovSubspace::time_evolved(&h, &initial(), d, 0.4);
+                subspace.ground_energy(1e-10).unwrap() - exact
+            })
+            .collect();
+        // variational from above and improving with the dimension
+        assert!(errors.iter().all(|&e| e > -1e-9), "{:?}", errors);
+        assert!(errors[1] < errors[0] && errors[2] < 1e-4, "{:?}", errors);
+    }
+
+    #[test]
+    fn test_trotterized_subspace_tracks_exact_one() {
+        let h = chain();
+        let exact = KrylovSubspace::time_evolved(&h, &initial(), 5, 0.3);
+        let trotter =
+            KrylovSubspace::trotterized(&h, &initial(), 5, 0.3, 8, TrotterOrder::Second);
+        let (a, b) = (exact.ground_energy(1e-8).unwrap(), trotter.ground_energy(1e-8).unwrap());
+        assert!((a - b).abs() < 1e-2, "{} vs {}", a, b);
+    }
+
+    #[test]
+    fn test_threshold_drops_linearly_dependent_states() {
+        let h = chain();
+        let state = initial();
+        let subspace = KrylovSubspace::from_states(&h, &[state.clone(), state.clone()]);
+        let values = subspace.solve(1e-8);
+        assert_eq!(values.len(), 1);
+        assert!((values[0] - h.expectation(&state)).abs() < 1e-12);
+        assert!(KrylovSubspace::from_states(&h, &[Register::new(3)]).solve(2.0).is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+use num_complex::Complex64;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::matrix::Matrix;
+use crate::simulator::register::Register;
+
+/// initial coin state for a discrete-time walk
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoinState {
+    /// |0⟩: walker biased to the left
+    Zero,
+    /// (|0⟩ + i|1⟩)/√2: symmetric spreading
+    Symmetric,
+}
+
+/// position register on qubits 1..=k, coin on qubit 0; x → x+1 mod 2^k when
+/// the coin is |1⟩ and x → x−1 mod 2^k when it is |0⟩
+fn conditional_shift(position_qubits: usize) -> Circuit {
+    let total = position_qubits + 1;
+    let mut increment = Circuit::new(total);
+    for i in (0..position_qubits).rev() {
+        let mut controls = vec![0];
+        controls.extend(1..1 + i);
+        increment.mcx(&controls, 1 + i);
+    }
+    // decrement = X^⊗k · increment · X^⊗k, run with the coin flipped
+    let mut circuit = increment.clone();
+    circuit.x(0);
+    for q in 1..total {
+        circuit.x(q);
+    }
+    circuit.append(&increment);
+    for q in 1..total {
+        circuit.x(q);
+    }
+    circuit.x(0);
+    circuit
+}
+
+/// `steps` rounds of Hadamard coin + shift on a cycle of 2^k sites,
+/// starting at site `start`
+pub fn dtqw_cycle_circuit(
+    position_qubits: usize,
+    steps: usize,
+    start: usize,
+    coin: CoinState,
+) -> Circuit {
+    let total = position_qubits + 1;
+    let mut circuit = Circuit::new(total);
+    for q in 0..position_qubits {
+        if start >> q & 1 == 1 {
+            circuit.x(1 + q);
+        }
+    }
+    if coin == CoinState::Symmetric {
+        circuit.h(0).s(0);
+    }
+    let shift = conditional_shift(position_qubits);
+    for _ in 0..steps {
+        circuit.h(0);
+        circuit.append(&shift);
+    }
+    circuit
+}
+
+/// site probabilities after tracing out the coin
+pub fn position_distribution(register: &Register) -> Vec<f64> {
+    let mut probs = vec![0.0; 1 << (register.num_qubits() - 1)];
+    for (i, p) in register.probabilities().into_iter().enumerate() {
+        probs[i >> 1] += p;
+    }
+    probs
+}
+
+/// discrete-time walk on a cycle of 2^k sites
+pub fn dtqw_cycle(position_qubits: usize, steps: usize, start: usize, coin: CoinState) -> Vec<f64> {
+    let mut register = Register::new(position_qubits + 1);
+    register.apply_circuit(&dtqw_cycle_circuit(position_qubits, steps, start, coin));
+    position_distribution(&register)
+}
+
+/// discrete-time walk on the infinite line, as (displacement, probability)
+/// for displacements −steps..=steps
+///
+/// Simulated on a cycle wide enough that the walker never wraps around.
+pub fn dtqw_line(steps: usize, coin: CoinState) -> Vec<(i64, f64)> {
+    let sites = 2 * steps + 2;
+    let position_qubits = sites.next_power_of_two().trailing_zeros() as usize;
+    let origin = 1usize << (position_qubits - 1);
+    let probs = dtqw_cycle(position_qubits, steps, origin, coin);
+    (0..=2 * steps)
+        .map(|k| {
+            let x = k as i64 - steps as i64;
+            (x, probs[(origin as i64 + x) as usize])
+        })
+        .collect()
+}
+
+/// adjacency matrix of the cycle graph C_n
+pub fn cycle_adjacency(n: usize) -> Matrix {
+    Matrix::from_fn(n, n, |i, j| {
+        let neighbours = (i + 1) % n == j || (j + 1) % n == i;
+        Complex64::new(if neighbours && i != j { 1.0 } else { 0.0 }, 0.0)
+    })
+}
+
+/// adjacency matrix of the path graph P_n
+pub fn line_adjacency(n: usize) -> Matrix {
+    Matrix::from_fn(n, n, |i, j| Complex64::new(if i.abs_diff(j) == 1 { 1.0 } else { 0.0 }, 0.0))
+}
+
+/// continuous-time walk: site probabilities of e^{−iγAt}|start⟩
+pub fn ctqw(adjacency: &Matrix, gamma: f64, time: f64, start: usize) -> Vec<f64> {
+    let evolution = adjacency.scaled(Complex64::new(0.0, -gamma * time)).expm();
+    evolution.column(start).iter().map(|a| a.norm_sqr()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spread(dist: &[(i64, f64)]) -> f64 {
+        dist.iter().map(|(x, p)| (x * x) as f64 * p).sum::<f64>().sqrt()
+    }
+
+    #[test]
+    fn test_line_walk_symmetric_and_ballistic() {
+        let steps = 20;
+        let dist = dtqw_line(steps, CoinState::Symmetric);
+        let total: f64 = dist.iter().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-10);
+        for (x, p) in &dist {
+            if x % 2 != 0 {
+                assert!(p.abs() < 1e-12, "odd site {} occupied", x);
+            }
+        }
+        let left: f64 = dist.iter().filter(|(x, _)| *x < 0).map(|(_, p)| p).sum();
+        let right: f64 = dist.iter().filter(|(x, _)| *x > 0).map(|(_, p)| p).sum();
+        assert!((left - right).abs() < 1e-10);
+        // a classical walk spreads as √t ≈ 4.5; the Hadamard walk as ≈ 0.54 t
+        assert!(spread(&dist) > 9.0);
+    }
+
+    #[test]
+    fn test_zero_coin_is_biased() {
+        let dist = dtqw_line(10, CoinState::Zero);
+        let mean: f64 = dist.iter().map(|(x, p)| *x as f64 * p).sum();
+        assert!(mean.abs() > 1.0);
+    }
+
+    #[test]
+    fn test_ctqw_cycle_symmetric() {
+        let probs = ctqw(&cycle_adjacency(9), 1.0, 1.7, 0);
+        assert!((probs.iter().sum::<f64>() - 1.0).abs() < 1e-10);
+        for k in 1..9 {
+            assert!((probs[k] - probs[9 - k]).abs() < 1e-10);
+        }
+        let line = ctqw(&line_adjacency(5), 1.0, 0.0, 2);
+        assert!((line[2] - 1.0).abs() < 1e-12);
+    }
+}
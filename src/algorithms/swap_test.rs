@@ -0,0 +1,112 @@
+use crate::simulator::circuit::Circuit;
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+
+/// sampled estimate of |⟨a|b⟩|²
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlapEstimate {
+    pub overlap: f64,
+    pub std_error: f64,
+    pub shots: usize,
+}
+
+/// swap test on two n-qubit states
+///
+/// Layout: ancilla 0, a = 1..=n, b = n+1..=2n. The ancilla reads 0 with
+/// probability (1 + |⟨a|b⟩|²)/2.
+pub fn swap_test_circuit(n: usize) -> Circuit {
+    let mut circuit = Circuit::new(2 * n + 1);
+    circuit.h(0);
+    for i in 1..=n {
+        let (a, b) = (i, i + n);
+        circuit.mcx(&[0, a], b).mcx(&[0, b], a).mcx(&[0, a], b);
+    }
+    circuit.h(0);
+    circuit
+}
+
+/// destructive swap test: Bell-basis measurement of every (a_i, b_i) pair
+///
+/// Layout: a = 0..n, b = n..2n; no ancilla. (−1)^{Σ a_i b_i} over the
+/// measured bits has mean |⟨a|b⟩|².
+pub fn destructive_swap_test_circuit(n: usize) -> Circuit {
+    let mut circuit = Circuit::new(2 * n);
+    for i in 0..n {
+        circuit.cx(i, i + n).h(i);
+    }
+    circuit
+}
+
+fn estimate_from_signs(sum: f64, shots: usize) -> OverlapEstimate {
+    let mean = sum / shots as f64;
+    let variance = (1.0 - mean * mean).max(0.0);
+    OverlapEstimate {
+        overlap: mean,
+        std_error: (variance / shots as f64).sqrt(),
+        shots,
+    }
+}
+
+/// estimate |⟨a|b⟩|² from `shots` runs of the ancilla-based swap test
+pub fn swap_test(a: &Register, b: &Register, shots: usize, rng: &mut Rng) -> OverlapEstimate {
+    assert_eq!(a.num_qubits(), b.num_qubits(), "states must have equal size");
+    let mut register = Register::new(1).tensor(a).tensor(b);
+    register.apply_circuit(&swap_test_circuit(a.num_qubits()));
+    let p_one = register.prob_one(0);
+    // ancilla sign +1 for |0⟩, −1 for |1⟩; its mean is the overlap
+    let sum: f64 = (0..shots).map(|_| if rng.gen_bool(p_one) { -1.0 } else { 1.0 }).sum();
+    estimate_from_signs(sum, shots)
+}
+
+/// estimate |⟨a|b⟩|² with the ancilla-free destructive swap test
+pub fn destructive_swap_test(
+    a: &Register,
+    b: &Register,
+    shots: usize,
+    rng: &mut Rng,
+) -> OverlapEstimate {
+    let n = a.num_qubits();
+    assert_eq!(n, b.num_qubits(), "states must have equal size");
+    let mut register = a.tensor(b);
+    register.apply_circuit(&destructive_swap_test_circuit(n));
+    let counts = register.distribution().sample_counts(shots, rng);
+    let low = (1usize << n) - 1;
+    let sum: f64 = counts
+        .iter()
+        .map(|(&outcome, &count)| {
+            let parity = ((outcome & low) & (outcome >> n)).count_ones() % 2;
+            if parity == 0 {
+                count as f64
+            } else {
+                -(count as f64)
+            }
+        })
+        .sum();
+    estimate_from_signs(sum, shots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlap_estimates_match_exact() {
+        let mut rng = Rng::seed_from_u64(15);
+        let a = Register::haar_random(2, &mut rng);
+        let b = Register::haar_random(2, &mut rng);
+        let exact = a.inner(&b).norm_sqr();
+        let ancilla = swap_test(&a, &b, 20_000, &mut rng);
+        let destructive = destructive_swap_test(&a, &b, 20_000, &mut rng);
+        assert!((ancilla.overlap - exact).abs() < 4.0 * ancilla.std_error + 1e-3);
+        assert!((destructive.overlap - exact).abs() < 4.0 * destructive.std_error + 1e-3);
+    }
+
+    #[test]
+    fn test_identical_states_always_pass() {
+        let mut rng = Rng::seed_from_u64(16);
+        let a = Register::haar_random(3, &mut rng);
+        let estimate = destructive_swap_test(&a, &a, 500, &mut rng);
+        assert!((estimate.overlap - 1.0).abs() < 1e-12);
+        assert!((swap_test(&a, &a, 500, &mut rng).overlap - 1.0).abs() < 1e-12);
+    }
+}
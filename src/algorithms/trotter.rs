@@ -0,0 +1,229 @@
+use num_complex::Complex64;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::hamiltonian::Hamiltonian;
+use crate::simulator::matrix::Matrix;
+use crate::simulator::pauli::Pauli;
+use crate::simulator::pauli_string::PauliString;
+
+/// product formula used for each Trotter step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrotterOrder {
+    /// Lie–Trotter, S₁(τ) = Π_k e^{−iτH_k}
+    First,
+    /// symmetric Strang splitting, S₂(τ) = S₁(τ/2) then its mirror image
+    Second,
+    /// Suzuki's fractal S₄(τ) = S₂(pτ)² S₂((1 − 4p)τ) S₂(pτ)², p = 1/(4 − ∛4)
+    Fourth,
+}
+
+impl TrotterOrder {
+    /// error of one step scales as τ^{order + 1}
+    pub fn order(&self) -> u32 {
+        match self {
+            TrotterOrder::First => 1,
+            TrotterOrder::Second => 2,
+            TrotterOrder::Fourth => 4,
+        }
+    }
+}
+
+/// append e^{−iθP} for a plain Pauli string P: rotate every factor onto Z,
+/// collect the parity on the last qubit with a CX ladder, and apply RZ(2θ)
+pub fn pauli_exponential(circuit: &mut Circuit, string: &PauliString, theta: f64) {
+    let terms = string.terms();
+    let Some(&(last, _)) = terms.last() else {
+        // e^{−iθI} is a global phase
+        return;
+    };
+    let mut rotate = Circuit::new(circuit.num_qubits());
+    for &(q, p) in &terms {
+        for gate in p.basis_change() {
+            rotate.push(gate.clone(), &[q]);
+        }
+    }
+    for pair in terms.windows(2) {
+        rotate.cx(pair[0].0, pair[1].0);
+    }
+    circuit.append(&rotate);
+    circuit.rz(2.0 * theta, last);
+    circuit.append(&rotate.inverse());
+}
+
+/// (real coefficient, string) pairs of a Hermitian `h`, identity dropped
+pub(crate) fn rotations(h: &Hamiltonian) -> Vec<(f64, PauliString)> {
+    assert!(h.is_hermitian(1e-9), "Trotterization needs a Hermitian operator");
+    h.terms()
+        .into_iter()
+        .filter(|(_, string)| string.paulis().iter().any(|&p| p != Pauli::I))
+        .map(|(c, string)| (c.re, string))
+        .collect()
+}
+
+fn first_order(circuit: &mut Circuit, terms: &[(f64, PauliString)], tau: f64) {
+    for (c, string) in terms {
+        pauli_exponential(circuit, string, c * tau);
+    }
+}
+
+fn second_order(circuit: &mut Circuit, terms: &[(f64, PauliString)], tau: f64) {
+    for (c, string) in terms.iter().chain(terms.iter().rev()) {
+        pauli_exponential(circuit, string, c * tau / 2.0);
+    }
+}
+
+fn fourth_order(circuit: &mut Circuit, terms: &[(f64, PauliString)], tau: f64) {
+    let p = 1.0 / (4.0 - 4f64.cbrt());
+    for fraction in [p, p, 1.0 - 4.0 * p, p, p] {
+        second_order(circuit, terms, fraction * tau);
+    }
+}
+
+/// circuit approximating e^{−iHt} with `steps` repetitions of the product
+/// formula at τ = t/steps; identity terms are dropped as a global phase
+pub fn trotter_circuit(h: &Hamiltonian, time: f64, steps: usize, order: TrotterOrder) -> Circuit {
+    assert!(steps > 0, "need at least one Trotter step");
+    let terms = rotations(h);
+    let tau = time / steps as f64;
+    let mut circuit = Circuit::new(h.num_qubits());
+    for _ in 0..steps {
+        match order {
+            TrotterOrder::First => first_order(&mut circuit, &terms, tau),
+            TrotterOrder::Second => second_order(&mut circuit, &terms, tau),
+            TrotterOrder::Fourth => fourth_order(&mut circuit, &terms, tau),
+        }
+    }
+    circuit
+}
+
+/// spectral norm ‖U_trotter − e^{−iH′t}‖ with H′ the traceless part of H,
+/// by dense simulation (small registers only)
+pub fn trotter_error(h: &Hamiltonian, time: f64, steps: usize, order: TrotterOrder) -> f64 {
+    let mut traceless = Hamiltonian::new(h.num_qubits());
+    for (c, string) in rotations(h) {
+        traceless.add_term(Complex64::new(c, 0.0), &string);
+    }
+    let exact = traceless.matrix().scaled(Complex64::new(0.0, -time)).expm();
+    let approximate = trotter_circuit(h, time, steps, order).to_unitary();
+    let difference = &approximate + &exact.scaled(Complex64::new(-1.0, 0.0));
+    let gram: Matrix = &difference.dagger() * &difference;
+    gram.hermitian_eigenvalues().last().map_or(0.0, |v| v.max(0.0).sqrt())
+}
+
+/// a-priori upper bound on `trotter_error`, from nested commutators of the
+/// terms with every ‖·‖ bounded by the Pauli 1-norm
+///
+/// First and second order use the commutator bounds of Childs et al. (2021);
+/// fourth order falls back on Suzuki's 2·r·(10Λt/r)⁵/5! with Λ = Σ|c_k|,
+/// which is loose but keeps the 1/r⁴ scaling.
+pub fn trotter_error_bound(h: &Hamiltonian, time: f64, steps: usize, order: TrotterOrder) -> f64 {
+    assert!(steps > 0, "need at least one Trotter step");
+    let terms: Vec<Hamiltonian> = rotations(h)
+        .into_iter()
+        .map(|(c, string)| Hamiltonian::from_pauli(Complex64::new(c, 0.0), &string))
+        .collect();
+    let r = steps as f64;
+    // H_j paired with the sum of the terms after it
+    let tails: Vec<(&Hamiltonian, Hamiltonian)> = terms
+        .iter()
+        .enumerate()
+        .map(|(j, term)| {
+            let tail = terms[j + 1..]
+                .iter()
+                .fold(Hamiltonian::new(h.num_qubits()), |sum, t| &sum + t);
+            (term, tail)
+        })
+        .collect();
+    match order {
+        TrotterOrder::First => {
+            let alpha: f64 = tails.iter().map(|(hj, tail)| hj.commutator(tail).one_norm()).sum();
+            time * time / (2.0 * r) * alpha
+        }
+        TrotterOrder::Second => {
+            let (outer, inner) = tails.iter().fold((0.0, 0.0), |(outer, inner), (hj, tail)| {
+                let c = tail.commutator(hj);
+                (
+                    outer + tail.commutator(&c).one_norm(),
+                    inner + hj.commutator(&c).one_norm(),
+                )
+            });
+            time.powi(3) / (r * r) * (outer / 12.0 + inner / 24.0)
+        }
+        TrotterOrder::Fourth => {
+            let lambda: f64 = terms.iter().map(Hamiltonian::one_norm).sum();
+            2.0 * r * (10.0 * lambda * time / r).powi(5) / 120.0
+        }
+    }
+}
+
+/// fewest steps whose `trotter_error_bound` is at most `epsilon`
+pub fn trotter_steps_for(h: &Hamiltonian, time: f64, epsilon: f64, order: TrotterOrder) -> usize {
+    assert!(epsilon > 0.0, "target error must be positive");
+    // every bound is C/r^order
+    let c = trotter_error_bound(h, time, 1, order);
+    ((c / epsilon).powf(1.0 / order.order() as f64).ceil() as usize).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn real(x: f64) -> Complex64 {
+        Complex64::new(x, 0.0)
+    }
+
+    /// Heisenberg-like chain with a field, whose terms do not commute
+    fn chain() -> Hamiltonian {
+        let mut h = Hamiltonian::new(3);
+        for s in ["XXI", "IXX", "YYI", "IYY", "ZII", "IZI", "IIZ"] {
+            h.add_term(real(0.7), &s.parse().unwrap());
+        }
+        h.add_term(real(0.3), &"ZIZ".parse().unwrap());
+        h.add_term(real(2.0), &"III".parse().unwrap());
+        h
+    }
+
+    #[test]
+    fn test_pauli_exponential_matches_matrix_exponential() {
+        let string: PauliString = "XYZ".parse().unwrap();
+        let mut circuit = Circuit::new(3);
+        pauli_exponential(&mut circuit, &string, 0.37);
+        let exact = string.matrix().scaled(Complex64::new(0.0, -0.37)).expm();
+        crate::assert_unitary_eq!(circuit.to_unitary(), exact);
+    }
+
+    #[test]
+    fn test_error_shrinks_with_order_and_steps() {
+        let h = chain();
+        let time = 1.0;
+        let first = trotter_error(&h, time, 8, TrotterOrder::First);
+        let second = trotter_error(&h, time, 8, TrotterOrder::Second);
+        let fourth = trotter_error(&h, time, 8, TrotterOrder::Fourth);
+        assert!(first > second && second > fourth, "{} {} {}", first, second, fourth);
+        // doubling the steps divides the error by about 2^order
+        for order in [TrotterOrder::First, TrotterOrder::Second, TrotterOrder::Fourth] {
+            let ratio = trotter_error(&h, time, 4, order) / trotter_error(&h, time, 8, order);
+            let expected = 2f64.powi(order.order() as i32);
+            assert!((ratio / expected - 1.0).abs() < 0.25, "{:?}: {}", order, ratio);
+        }
+    }
+
+    #[test]
+    fn test_bounds_hold_and_choose_enough_steps() {
+        let h = chain();
+        for order in [TrotterOrder::First, TrotterOrder::Second, TrotterOrder::Fourth] {
+            for steps in [2, 5] {
+                let bound = trotter_error_bound(&h, 0.5, steps, order);
+                assert!(trotter_error(&h, 0.5, steps, order) <= bound, "{:?}", order);
+            }
+            let steps = trotter_steps_for(&h, 0.5, 1e-3, order);
+            assert!(trotter_error_bound(&h, 0.5, steps, order) <= 1e-3 * (1.0 + 1e-9));
+            assert!(trotter_error(&h, 0.5, steps, order) <= 1e-3);
+        }
+        // commuting terms leave no Trotter error
+        let mut ising = Hamiltonian::new(2);
+        ising.add_term(real(1.0), &"ZZ".parse().unwrap());
+        ising.add_term(real(0.4), &"ZI".parse().unwrap());
+        assert_eq!(trotter_error_bound(&ising, 1.0, 1, TrotterOrder::First), 0.0);
+        assert!(trotter_error(&ising, 1.0, 1, TrotterOrder::First) < 1e-9);
+    }
+}
@@ -0,0 +1,227 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+use crate::noise::{noisy_counts, NoiseModel};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::rng::Rng;
+use super::fit::{fit_exponential_decay, linear_fit};
+
+/// fitted Rabi oscillation P(1) = B − A·cos(ω·a) over drive amplitudes a
+#[derive(Debug, Clone)]
+pub struct RabiResult {
+    pub amplitudes: Vec<f64>,
+    pub excited: Vec<f64>,
+    /// rotation angle per unit amplitude, ω
+    pub rate: f64,
+    /// amplitude of a π pulse, π/ω
+    pub pi_amplitude: f64,
+}
+
+/// fitted Ramsey fringes P(1) = B + A·e^{−τ/T2*}·cos(δ·τ)
+#[derive(Debug, Clone)]
+pub struct RamseyResult {
+    pub delays: Vec<f64>,
+    pub excited: Vec<f64>,
+    /// fringe frequency δ in rad/ns
+    pub detuning: f64,
+    pub t2_star: f64,
+}
+
+/// fitted decay P = A·e^{−τ/T} + B for a T1 or Hahn-echo T2 experiment
+#[derive(Debug, Clone)]
+pub struct DecayResult {
+    pub delays: Vec<f64>,
+    /// P(1) for T1, P(0) for the echo
+    pub population: Vec<f64>,
+    pub amplitude: f64,
+    pub lifetime: f64,
+}
+
+/// `noise` with `id` gates lasting `delay` ns; without relaxation delays are free
+fn with_delay(noise: &NoiseModel, delay: f64) -> NoiseModel {
+    let mut noise = noise.clone();
+    if let Some(relaxation) = &mut noise.relaxation {
+        relaxation.times = relaxation.times.clone().with_gate("id", delay);
+    }
+    noise
+}
+
+fn excited_fraction(circuit: &Circuit, noise: &NoiseModel, shots: usize, rng: &mut Rng) -> f64 {
+    let counts = noisy_counts(circuit, noise, shots, rng);
+    *counts.get(&1).unwrap_or(&0) as f64 / shots as f64
+}
+
+/// least-squares y = slope·f(x) + intercept, plus the squared residual
+fn regress(xs: &[f64], ys: &[f64], f: impl Fn(f64) -> f64) -> (f64, f64, f64) {
+    let fx: Vec<f64> = xs.iter().map(|&x| f(x)).collect();
+    let (slope, intercept) = linear_fit(&fx, ys);
+    let residual = fx.iter().zip(ys).map(|(x, y)| (slope * x + intercept - y).powi(2)).sum();
+    (slope, intercept, residual)
+}
+
+/// argument in (0, max] minimising `cost`: a coarse grid, then repeated refinement
+fn minimise(max: f64, cost: impl Fn(f64) -> f64) -> f64 {
+    let mut step = max / 200.0;
+    let mut best = (f64::INFINITY, step);
+    for k in 1..=200 {
+        let c = cost(k as f64 * step);
+        if c < best.0 {
+            best = (c, k as f64 * step);
+        }
+    }
+    for _ in 0..20 {
+        step /= 4.0;
+        let centre = best.1;
+        for k in -4..=4 {
+            let x = centre + k as f64 * step;
+            let c = if x > 0.0 { cost(x) } else { f64::INFINITY };
+            if c < best.0 {
+                best = (c, x);
+            }
+        }
+    }
+    best.1
+}
+
+/// Nyquist limit π / (smallest spacing) of a sorted sweep
+fn max_frequency(xs: &[f64]) -> f64 {
+    let spacing = xs.windows(2).map(|w| w[1] - w[0]).fold(f64::INFINITY, f64::min);
+    PI / spacing
+}
+
+/// sweep drive amplitudes through Rx(`drive_scale`·a) and fit the π-pulse amplitude
+pub fn rabi_experiment(
+    noise: &NoiseModel,
+    amplitudes: &[f64],
+    drive_scale: f64,
+    shots: usize,
+    rng: &mut Rng,
+) -> RabiResult {
+    assert!(amplitudes.len() >= 3, "need at least three amplitudes");
+    let excited: Vec<f64> = amplitudes
+        .iter()
+        .map(|&a| excited_fraction(Circuit::new(1).rx(drive_scale * a, 0), noise, shots, rng))
+        .collect();
+    let rate = minimise(max_frequency(amplitudes), |w| {
+        regress(amplitudes, &excited, |a| (w * a).cos()).2
+    });
+    RabiResult {
+        amplitudes: amplitudes.to_vec(),
+        excited,
+        rate,
+        pi_amplitude: PI / rate,
+    }
+}
+
+/// X/2, delay τ, a virtual Rz(`detuning`·τ), X/2; fits fringe frequency and T2*
+pub fn ramsey_experiment(
+    noise: &NoiseModel,
+    delays: &[f64],
+    detuning: f64,
+    shots: usize,
+    rng: &mut Rng,
+) -> RamseyResult {
+    assert!(delays.len() >= 4, "need at least four delays");
+    let excited: Vec<f64> = delays
+        .iter()
+        .map(|&tau| {
+            let mut circuit = Circuit::new(1);
+            circuit.rx(FRAC_PI_2, 0).id(0).rz(detuning * tau, 0).rx(FRAC_PI_2, 0);
+            excited_fraction(&circuit, &with_delay(noise, tau), shots, rng)
+        })
+        .collect();
+    let span = delays[delays.len() - 1] - delays[0];
+    let fringe = |delta: f64, rate: f64| {
+        regress(delays, &excited, |tau| (-rate * tau).exp() * (delta * tau).cos()).2
+    };
+    let best_rate = |delta: f64| minimise(10.0 / span, |rate| fringe(delta, rate));
+    let delta = minimise(max_frequency(delays), |delta| fringe(delta, best_rate(delta)));
+    let t2_star = 1.0 / best_rate(delta);
+    RamseyResult {
+        delays: delays.to_vec(),
+        excited,
+        detuning: delta,
+        t2_star,
+    }
+}
+
+/// X, delay τ, measure; fits the decay of P(1) to T1
+pub fn t1_experiment(
+    noise: &NoiseModel,
+    delays: &[f64],
+    shots: usize,
+    rng: &mut Rng,
+) -> DecayResult {
+    let mut circuit = Circuit::new(1);
+    circuit.x(0).id(0);
+    let population: Vec<f64> = delays
+        .iter()
+        .map(|&tau| excited_fraction(&circuit, &with_delay(noise, tau), shots, rng))
+        .collect();
+    let (amplitude, decay) = fit_exponential_decay(delays, &population, 0.0);
+    DecayResult {
+        delays: delays.to_vec(),
+        population,
+        amplitude,
+        lifetime: -1.0 / decay.ln(),
+    }
+}
+
+/// Hahn echo X/2, τ/2, X, τ/2, X/2; fits the decay of P(0) towards 1/2 to T2
+pub fn t2_echo_experiment(
+    noise: &NoiseModel,
+    delays: &[f64],
+    shots: usize,
+    rng: &mut Rng,
+) -> DecayResult {
+    let population: Vec<f64> = delays
+        .iter()
+        .map(|&tau| {
+            let mut circuit = Circuit::new(1);
+            circuit.rx(FRAC_PI_2, 0).id(0).x(0).id(0).rx(FRAC_PI_2, 0);
+            1.0 - excited_fraction(&circuit, &with_delay(noise, tau / 2.0), shots, rng)
+        })
+        .collect();
+    let (amplitude, decay) = fit_exponential_decay(delays, &population, 0.5);
+    DecayResult {
+        delays: delays.to_vec(),
+        population,
+        amplitude,
+        lifetime: -1.0 / decay.ln(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::timing::GateTimes;
+
+    fn transmon() -> NoiseModel {
+        NoiseModel::ideal().with_relaxation(20_000.0, 15_000.0, GateTimes::superconducting())
+    }
+
+    #[test]
+    fn test_rabi_finds_pi_amplitude() {
+        let amplitudes: Vec<f64> = (0..25).map(|k| k as f64 * 0.1).collect();
+        let mut rng = Rng::seed_from_u64(66);
+        let result = rabi_experiment(&transmon(), &amplitudes, 2.5, 400, &mut rng);
+        assert!((result.pi_amplitude - PI / 2.5).abs() < 0.03, "{}", result.pi_amplitude);
+    }
+
+    #[test]
+    fn test_t1_and_echo_recover_lifetimes() {
+        let delays: Vec<f64> = (0..10).map(|k| k as f64 * 4000.0).collect();
+        let mut rng = Rng::seed_from_u64(67);
+        let t1 = t1_experiment(&transmon(), &delays, 500, &mut rng);
+        assert!((t1.lifetime / 20_000.0 - 1.0).abs() < 0.15, "T1 = {}", t1.lifetime);
+        let t2 = t2_echo_experiment(&transmon(), &delays, 500, &mut rng);
+        assert!((t2.lifetime / 15_000.0 - 1.0).abs() < 0.2, "T2 = {}", t2.lifetime);
+    }
+
+    #[test]
+    fn test_ramsey_fringes_give_detuning() {
+        let delays: Vec<f64> = (0..30).map(|k| k as f64 * 500.0).collect();
+        let mut rng = Rng::seed_from_u64(68);
+        let result = ramsey_experiment(&transmon(), &delays, 0.002, 400, &mut rng);
+        assert!((result.detuning - 0.002).abs() < 1e-4, "δ = {}", result.detuning);
+        assert!((result.t2_star / 15_000.0 - 1.0).abs() < 0.3, "T2* = {}", result.t2_star);
+    }
+}
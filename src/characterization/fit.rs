@@ -0,0 +1,44 @@
+/// least-squares line y = slope·x + intercept
+pub fn linear_fit(xs: &[f64], ys: &[f64]) -> (f64, f64) {
+    assert_eq!(xs.len(), ys.len(), "length mismatch");
+    assert!(xs.len() >= 2, "need at least two points");
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let sxx: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = xs.iter().zip(ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let slope = sxy / sxx;
+    (slope, mean_y - slope * mean_x)
+}
+
+/// fit y = A·p^x + B for a known asymptote B, returning (A, p)
+///
+/// Log-linear regression on y - B; points at or below the asymptote carry no
+/// decay information and are skipped.
+pub fn fit_exponential_decay(xs: &[f64], ys: &[f64], asymptote: f64) -> (f64, f64) {
+    let (fx, fy): (Vec<f64>, Vec<f64>) = xs
+        .iter()
+        .zip(ys)
+        .filter(|(_, &y)| y - asymptote > 1e-9)
+        .map(|(&x, &y)| (x, (y - asymptote).ln()))
+        .unzip();
+    if fx.len() < 2 {
+        return (0.0, 0.0);
+    }
+    let (slope, intercept) = linear_fit(&fx, &fy);
+    (intercept.exp(), slope.exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovers_exact_decay() {
+        let xs: Vec<f64> = (0..10).map(|k| (k * 5) as f64).collect();
+        let ys: Vec<f64> = xs.iter().map(|x| 0.4 * 0.97f64.powf(*x) + 0.5).collect();
+        let (a, p) = fit_exponential_decay(&xs, &ys, 0.5);
+        assert!((a - 0.4).abs() < 1e-9);
+        assert!((p - 0.97).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,17 @@
+pub mod calibration;
+pub mod fit;
+pub mod noise_learning;
+pub mod shadows;
+pub mod rb;
+pub mod xeb;
+
+pub use calibration::{
+    rabi_experiment, ramsey_experiment, t1_experiment, t2_echo_experiment, DecayResult,
+    RabiResult, RamseyResult,
+};
+pub use noise_learning::{
+    learn_sparse_pauli_lindblad, SparsePauliLindblad, SplConfig, SplFit,
+};
+pub use shadows::{ClassicalShadow, Snapshot};
+pub use rb::{randomized_benchmarking, RbConfig, RbResult};
+pub use xeb::{linear_xeb_fidelity, run_xeb, XebResult};
@@ -0,0 +1,271 @@
+use crate::noise::{noisy_counts, NoiseModel, PauliChannel};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::pauli::Pauli;
+use crate::simulator::pauli_string::PauliString;
+use crate::simulator::rng::Rng;
+use crate::simulator::tableau::CliffordTableau;
+use super::fit::fit_exponential_decay;
+
+/// sparse Pauli–Lindblad channel Λ = ∏_k (w_k·ρ + (1 − w_k)·P_k ρ P_k) with
+/// w_k = (1 + e^{−2λ_k})/2, generators P_k of weight one and two
+#[derive(Debug, Clone)]
+pub struct SparsePauliLindblad {
+    pub num_qubits: usize,
+    pub generators: Vec<PauliString>,
+    pub rates: Vec<f64>,
+}
+
+impl SparsePauliLindblad {
+    /// every weight-one Pauli and every weight-two Pauli on `pairs`, all rates zero
+    pub fn new(num_qubits: usize, pairs: &[(usize, usize)]) -> Self {
+        let nontrivial = &Pauli::ALL[1..];
+        let mut generators: Vec<PauliString> = (0..num_qubits)
+            .flat_map(|q| nontrivial.iter().map(move |&p| PauliString::single(num_qubits, q, p)))
+            .collect();
+        for &(a, b) in pairs {
+            assert!(a != b && a.max(b) < num_qubits, "invalid pair ({}, {})", a, b);
+            for &pa in nontrivial {
+                for &pb in nontrivial {
+                    generators.push(PauliString::from_terms(num_qubits, &[(a, pa), (b, pb)]));
+                }
+            }
+        }
+        let rates = vec![0.0; generators.len()];
+        Self {
+            num_qubits,
+            generators,
+            rates,
+        }
+    }
+
+    /// f_Q = exp(−2 Σ λ_k) over the generators anticommuting with Q
+    pub fn fidelity(&self, pauli: &PauliString) -> f64 {
+        let exponent: f64 = self
+            .generators
+            .iter()
+            .zip(&self.rates)
+            .filter(|(generator, _)| !generator.commutes_with(pauli))
+            .map(|(_, rate)| rate)
+            .sum();
+        (-2.0 * exponent).exp()
+    }
+
+    /// the full 4ⁿ-entry Pauli channel, e.g. to mitigate with `PauliChannel::inverse`
+    pub fn to_channel(&self) -> PauliChannel {
+        let fidelities: Vec<f64> = (0..1usize << (2 * self.num_qubits))
+            .map(|code| {
+                let paulis = (0..self.num_qubits).map(|q| Pauli::ALL[(code >> (2 * q)) & 3]);
+                self.fidelity(&PauliString::new(paulis.collect()))
+            })
+            .collect();
+        PauliChannel::from_fidelities(self.num_qubits, &fidelities)
+    }
+}
+
+/// repetition depths and shot budget for learning one layer
+#[derive(Debug, Clone)]
+pub struct SplConfig {
+    /// numbers of layer pairs U·U in each benchmark circuit
+    pub depths: Vec<usize>,
+    pub shots: usize,
+}
+
+impl Default for SplConfig {
+    fn default() -> Self {
+        Self {
+            depths: vec![1, 2, 4, 8, 16],
+            shots: 1000,
+        }
+    }
+}
+
+/// learned model and the pair fidelities f_Q·f_{UQU†} it was fitted to
+#[derive(Debug, Clone)]
+pub struct SplFit {
+    pub model: SparsePauliLindblad,
+    pub measured: Vec<(PauliString, f64)>,
+}
+
+/// ⟨Q⟩ estimated from counts in Q's eigenbasis
+fn parity_expectation(pauli: &PauliString, counts: &[(usize, usize)], shots: usize) -> f64 {
+    let mask = pauli.terms().iter().fold(0, |mask, &(q, _)| mask | (1 << q));
+    let signed: i64 = counts
+        .iter()
+        .map(|&(outcome, n)| {
+            let sign = if (outcome & mask).count_ones() % 2 == 0 { 1 } else { -1 };
+            sign * n as i64
+        })
+        .sum();
+    signed as f64 / shots as f64
+}
+
+/// prepare Q's +1 eigenstate, run `layer` 2·`depth` times, rotate Q onto Z
+fn benchmark_circuit(layer: &Circuit, pauli: &PauliString, depth: usize) -> Circuit {
+    let mut circuit = Circuit::new(layer.num_qubits());
+    for (q, p) in pauli.terms() {
+        for gate in p.basis_change().iter().rev() {
+            circuit.push(gate.inverse(), &[q]);
+        }
+    }
+    for _ in 0..2 * depth {
+        circuit.append(layer);
+    }
+    for (q, p) in pauli.terms() {
+        for gate in p.basis_change() {
+            circuit.push(gate.clone(), &[q]);
+        }
+    }
+    circuit
+}
+
+/// non-negative least squares by cyclic coordinate descent
+fn fit_rates(rows: &[Vec<f64>], targets: &[f64], num_rates: usize) -> Vec<f64> {
+    let mut rates = vec![0.0; num_rates];
+    let mut residual = targets.to_vec();
+    let norms: Vec<f64> = (0..num_rates)
+        .map(|k| rows.iter().map(|row| row[k] * row[k]).sum())
+        .collect();
+    for _ in 0..2000 {
+        for k in (0..num_rates).filter(|&k| norms[k] > 0.0) {
+            let gradient: f64 = rows.iter().zip(&residual).map(|(row, r)| row[k] * r).sum();
+            let updated = (rates[k] + gradient / norms[k]).max(0.0);
+            let step = updated - rates[k];
+            for (row, r) in rows.iter().zip(residual.iter_mut()) {
+                *r -= step * row[k];
+            }
+            rates[k] = updated;
+        }
+    }
+    rates
+}
+
+/// learn the sparse Pauli–Lindblad noise of one Clifford `layer` under `noise`
+///
+/// For each generator Q the benchmark prepares its eigenstate, repeats the
+/// layer in pairs and fits the decay of ⟨Q⟩; the decay rate is f_Q·f_{UQU†}
+/// independent of state preparation and readout error. The layer must be a
+/// single moment and self-inverse so that U·U returns Q to itself, and the
+/// noise must already be Pauli (as `NoiseModel` gate errors are) since no
+/// twirling is applied.
+pub fn learn_sparse_pauli_lindblad(
+    layer: &Circuit,
+    pairs: &[(usize, usize)],
+    noise: &NoiseModel,
+    config: &SplConfig,
+    rng: &mut Rng,
+) -> Result<SplFit, String> {
+    if layer.depth() > 1 {
+        return Err(format!("layer has depth {}, expected one moment", layer.depth()));
+    }
+    let tableau = CliffordTableau::from_circuit(layer)?;
+    let mut twice = layer.clone();
+    twice.append(layer);
+    if CliffordTableau::from_circuit(&twice)? != CliffordTableau::identity(layer.num_qubits()) {
+        return Err("layer is not self-inverse".to_string());
+    }
+    let mut model = SparsePauliLindblad::new(layer.num_qubits(), pairs);
+    let depths: Vec<f64> = config.depths.iter().map(|&d| d as f64).collect();
+    let mut measured = Vec::new();
+    let mut rows = Vec::new();
+    let mut targets = Vec::new();
+    for pauli in &model.generators {
+        let expectations: Vec<f64> = config
+            .depths
+            .iter()
+            .map(|&depth| {
+                let circuit = benchmark_circuit(layer, pauli, depth);
+                let counts: Vec<(usize, usize)> =
+                    noisy_counts(&circuit, noise, config.shots, rng).into_iter().collect();
+                parity_expectation(pauli, &counts, config.shots)
+            })
+            .collect();
+        let (_, decay) = fit_exponential_decay(&depths, &expectations, 0.0);
+        let decay = decay.clamp(1e-9, 1.0);
+        // −ln(f_Q·f_Q')/2 counts each generator once per side it anticommutes with
+        let image = tableau.conjugate(pauli);
+        let anticommuting = |g: &PauliString, p: &PauliString| !g.commutes_with(p) as u8 as f64;
+        rows.push(
+            model
+                .generators
+                .iter()
+                .map(|g| anticommuting(g, pauli) + anticommuting(g, &image))
+                .collect::<Vec<f64>>(),
+        );
+        targets.push(-decay.ln() / 2.0);
+        measured.push((pauli.clone(), decay));
+    }
+    model.rates = fit_rates(&rows, &targets, model.generators.len());
+    Ok(SplFit { model, measured })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_matches_generator_fidelities() {
+        let mut model = SparsePauliLindblad::new(2, &[(0, 1)]);
+        model.rates[0] = 0.02;
+        model.rates[5] = 0.01;
+        model.rates[9] = 0.03;
+        let channel = model.to_channel();
+        let fidelities = channel.fidelities();
+        for (code, f) in fidelities.iter().enumerate() {
+            let paulis = (0..2).map(|q| Pauli::ALL[(code >> (2 * q)) & 3]).collect();
+            assert!((model.fidelity(&PauliString::new(paulis)) - f).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_learns_single_qubit_depolarizing_on_pauli_layer() {
+        let mut layer = Circuit::new(2);
+        layer.x(0).z(1);
+        let p = 0.02;
+        let noise = NoiseModel::depolarizing(p, 0.0);
+        let mut rng = Rng::seed_from_u64(187);
+        let fit =
+            learn_sparse_pauli_lindblad(&layer, &[(0, 1)], &noise, &SplConfig::default(), &mut rng)
+                .unwrap();
+        let expected = 1.0 - 4.0 * p / 3.0;
+        for q in 0..2 {
+            for &pauli in &Pauli::ALL[1..] {
+                let f = fit.model.fidelity(&PauliString::single(2, q, pauli));
+                assert!((f - expected).abs() < 0.01, "{:?} on {}: {}", pauli, q, f);
+            }
+        }
+        let correlated: f64 = fit.model.rates[6..].iter().sum();
+        assert!(correlated < 0.01, "{:?}", fit.model.rates);
+    }
+
+    #[test]
+    fn test_fit_reproduces_cx_layer_pair_fidelities() {
+        let mut layer = Circuit::new(2);
+        layer.cx(0, 1);
+        let noise = NoiseModel::depolarizing(0.0, 0.03).with_readout_error(0.02);
+        let mut rng = Rng::seed_from_u64(188);
+        let fit =
+            learn_sparse_pauli_lindblad(&layer, &[(0, 1)], &noise, &SplConfig::default(), &mut rng)
+                .unwrap();
+        let tableau = CliffordTableau::from_circuit(&layer).unwrap();
+        // two-qubit depolarizing scales every non-identity Pauli by 1 − 16p/15
+        let expected = (1.0 - 16.0 * 0.03 / 15.0f64).powi(2);
+        for (pauli, measured) in &fit.measured {
+            let image = tableau.conjugate(pauli);
+            let predicted = fit.model.fidelity(pauli) * fit.model.fidelity(&image);
+            assert!((predicted - measured).abs() < 0.01, "{}: {}", pauli, predicted);
+            assert!((measured - expected).abs() < 0.015, "{}: {}", pauli, measured);
+        }
+    }
+
+    #[test]
+    fn test_rejects_layers_that_are_not_self_inverse() {
+        let mut rng = Rng::seed_from_u64(0);
+        let (noise, config) = (NoiseModel::ideal(), SplConfig::default());
+        let mut learn = |layer: &Circuit| {
+            learn_sparse_pauli_lindblad(layer, &[], &noise, &config, &mut rng).is_err()
+        };
+        assert!(learn(Circuit::new(1).s(0)));
+        assert!(learn(Circuit::new(1).t(0)));
+        assert!(learn(Circuit::new(1).h(0).h(0)));
+    }
+}
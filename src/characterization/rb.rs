@@ -0,0 +1,114 @@
+use crate::noise::{noisy_counts, NoiseModel};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::clifford::{find_clifford, single_qubit_cliffords};
+use crate::simulator::operator::Operator;
+use crate::simulator::rng::Rng;
+use super::fit::fit_exponential_decay;
+
+/// sequence lengths and sampling budget for an RB run
+#[derive(Debug, Clone)]
+pub struct RbConfig {
+    pub lengths: Vec<usize>,
+    pub sequences_per_length: usize,
+    pub shots: usize,
+}
+
+impl Default for RbConfig {
+    fn default() -> Self {
+        Self {
+            lengths: vec![1, 5, 10, 20, 40, 80],
+            sequences_per_length: 10,
+            shots: 100,
+        }
+    }
+}
+
+/// fitted decay F(m) = A·p^m + 1/2
+#[derive(Debug, Clone)]
+pub struct RbResult {
+    pub lengths: Vec<usize>,
+    /// mean probability of returning to |0⟩ at each length
+    pub survival: Vec<f64>,
+    pub amplitude: f64,
+    pub decay: f64,
+    /// average error per Clifford r = (1 - p)/2
+    pub error_per_clifford: f64,
+}
+
+/// `length` random Cliffords followed by the Clifford that inverts them
+pub fn rb_sequence(length: usize, rng: &mut Rng) -> Circuit {
+    let group = single_qubit_cliffords();
+    let mut circuit = Circuit::new(1);
+    let mut total = Operator::identity(1);
+    for _ in 0..length {
+        let clifford = &group[rng.gen_range(group.len())];
+        for gate in &clifford.gates {
+            circuit.push(gate.clone(), &[0]);
+        }
+        total = &clifford.matrix * &total;
+    }
+    let inverse = find_clifford(&group, &total.dagger()).expect("Clifford group is closed");
+    for gate in &group[inverse].gates {
+        circuit.push(gate.clone(), &[0]);
+    }
+    circuit
+}
+
+/// standard single-qubit randomized benchmarking under `noise`
+pub fn randomized_benchmarking(noise: &NoiseModel, config: &RbConfig, rng: &mut Rng) -> RbResult {
+    let survival: Vec<f64> = config
+        .lengths
+        .iter()
+        .map(|&length| {
+            let mut total = 0.0;
+            for _ in 0..config.sequences_per_length {
+                let circuit = rb_sequence(length, rng);
+                let counts = noisy_counts(&circuit, noise, config.shots, rng);
+                total += *counts.get(&0).unwrap_or(&0) as f64 / config.shots as f64;
+            }
+            total / config.sequences_per_length as f64
+        })
+        .collect();
+    let xs: Vec<f64> = config.lengths.iter().map(|&m| m as f64).collect();
+    let (amplitude, decay) = fit_exponential_decay(&xs, &survival, 0.5);
+    RbResult {
+        lengths: config.lengths.clone(),
+        survival,
+        amplitude,
+        decay,
+        error_per_clifford: (1.0 - decay) / 2.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::register::Register;
+
+    #[test]
+    fn test_sequence_returns_to_zero() {
+        let mut rng = Rng::seed_from_u64(8);
+        let circuit = rb_sequence(30, &mut rng);
+        let mut register = Register::new(1);
+        register.apply_circuit(&circuit);
+        assert!((register.probabilities()[0] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_error_rate_tracks_noise() {
+        let config = RbConfig {
+            lengths: vec![1, 10, 20, 40],
+            sequences_per_length: 8,
+            shots: 60,
+        };
+        let mut rng = Rng::seed_from_u64(21);
+        let ideal = randomized_benchmarking(&NoiseModel::ideal(), &config, &mut rng);
+        assert!(ideal.survival.iter().all(|&f| (f - 1.0).abs() < 1e-12));
+        let noise = NoiseModel::depolarizing(0.01, 0.0);
+        let noisy = randomized_benchmarking(&noise, &config, &mut rng);
+        // ~1.9 physical gates per Clifford, each failing with probability ~2/3·p
+        let r = noisy.error_per_clifford;
+        assert!(r > 0.003 && r < 0.03, "r = {}", r);
+        assert!(noisy.survival[0] > *noisy.survival.last().unwrap());
+    }
+}
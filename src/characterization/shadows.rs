@@ -0,0 +1,162 @@
+use crate::simulator::pauli::Pauli;
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+
+const MEASUREMENT_BASES: [Pauli; 3] = [Pauli::X, Pauli::Y, Pauli::Z];
+
+/// one randomized measurement: a Pauli basis per qubit and the observed bits
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub bases: Vec<Pauli>,
+    pub outcome: usize,
+}
+
+/// classical shadow from random local Pauli-basis measurements
+///
+/// Uses the local-Clifford ensemble of Huang, Kueng & Preskill: a k-body Pauli
+/// observable is estimated with variance ≤ 3^k, independent of register size.
+#[derive(Debug, Clone)]
+pub struct ClassicalShadow {
+    num_qubits: usize,
+    snapshots: Vec<Snapshot>,
+}
+
+impl ClassicalShadow {
+    /// measure `num_snapshots` fresh copies of `state` in random bases
+    pub fn collect(state: &Register, num_snapshots: usize, rng: &mut Rng) -> Self {
+        let num_qubits = state.num_qubits();
+        let snapshots = (0..num_snapshots)
+            .map(|_| {
+                let bases: Vec<Pauli> = (0..num_qubits)
+                    .map(|_| MEASUREMENT_BASES[rng.gen_range(3)])
+                    .collect();
+                let mut copy = state.clone();
+                for (q, basis) in bases.iter().enumerate() {
+                    for gate in basis.basis_change() {
+                        copy.apply_matrix2(q, gate.matrix2().expect("single-qubit gate"));
+                    }
+                }
+                let outcome = copy.distribution().sample(rng);
+                Snapshot { bases, outcome }
+            })
+            .collect();
+        Self { num_qubits, snapshots }
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    /// single-snapshot estimator of ⊗ P_q on the listed qubits
+    fn snapshot_value(&self, snapshot: &Snapshot, observable: &[(usize, Pauli)]) -> f64 {
+        let mut value = 1.0;
+        for &(q, pauli) in observable {
+            if pauli == Pauli::I {
+                continue;
+            }
+            if snapshot.bases[q] != pauli {
+                return 0.0;
+            }
+            let bit = (snapshot.outcome >> q) & 1;
+            value *= if bit == 0 { 3.0 } else { -3.0 };
+        }
+        value
+    }
+
+    /// panics unless every qubit of `observable` is in range and listed once
+    fn check_observable(&self, observable: &[(usize, Pauli)]) {
+        for (k, &(q, _)) in observable.iter().enumerate() {
+            assert!(q < self.num_qubits, "qubit {} out of range", q);
+            assert!(observable[..k].iter().all(|&(p, _)| p != q), "repeated qubit {}", q);
+        }
+    }
+
+    /// mean estimate of ⟨⊗ P_q⟩ for `observable` given as (qubit, Pauli) pairs
+    /// on distinct qubits
+    pub fn estimate(&self, observable: &[(usize, Pauli)]) -> f64 {
+        assert!(!self.snapshots.is_empty(), "no snapshots collected");
+        self.check_observable(observable);
+        self.snapshots
+            .iter()
+            .map(|s| self.snapshot_value(s, observable))
+            .sum::<f64>()
+            / self.snapshots.len() as f64
+    }
+
+    /// median-of-means estimate, robust to the heavy tails of the ±3^k values
+    pub fn estimate_median_of_means(
+        &self,
+        observable: &[(usize, Pauli)],
+        num_groups: usize,
+    ) -> f64 {
+        assert!(num_groups > 0 && num_groups <= self.snapshots.len(), "invalid group count");
+        self.check_observable(observable);
+        let group_size = self.snapshots.len() / num_groups;
+        let mut means: Vec<f64> = self
+            .snapshots
+            .chunks(group_size)
+            .take(num_groups)
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|s| self.snapshot_value(s, observable))
+                    .sum::<f64>()
+                    / group.len() as f64
+            })
+            .collect();
+        means.sort_by(f64::total_cmp);
+        let mid = means.len() / 2;
+        if means.len().is_multiple_of(2) {
+            0.5 * (means[mid - 1] + means[mid])
+        } else {
+            means[mid]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::testing::bell_state;
+
+    #[test]
+    fn test_bell_correlators() {
+        // |Φ+⟩ has ⟨XX⟩ = ⟨ZZ⟩ = 1, ⟨YY⟩ = -1 and ⟨Z⊗I⟩ = 0
+        let mut rng = Rng::seed_from_u64(17);
+        let shadow = ClassicalShadow::collect(&bell_state(), 6000, &mut rng);
+        let xx = shadow.estimate(&[(0, Pauli::X), (1, Pauli::X)]);
+        let yy = shadow.estimate(&[(0, Pauli::Y), (1, Pauli::Y)]);
+        let zz = shadow.estimate_median_of_means(&[(0, Pauli::Z), (1, Pauli::Z)], 10);
+        let z0 = shadow.estimate(&[(0, Pauli::Z)]);
+        assert!((xx - 1.0).abs() < 0.2, "XX = {}", xx);
+        assert!((yy + 1.0).abs() < 0.2, "YY = {}", yy);
+        assert!((zz - 1.0).abs() < 0.2, "ZZ = {}", zz);
+        assert!(z0.abs() < 0.15, "Z0 = {}", z0);
+    }
+
+    #[test]
+    fn test_identity_observable_is_one() {
+        let mut rng = Rng::seed_from_u64(2);
+        let shadow = ClassicalShadow::collect(&Register::new(3), 50, &mut rng);
+        assert_eq!(shadow.snapshots().len(), 50);
+        assert!((shadow.estimate(&[]) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "repeated qubit 0")]
+    fn test_repeated_observable_qubit_panics() {
+        let shadow = ClassicalShadow::collect(&Register::new(1), 10, &mut Rng::seed_from_u64(4));
+        shadow.estimate(&[(0, Pauli::X), (0, Pauli::Z)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "qubit 2 out of range")]
+    fn test_out_of_range_qubit_panics() {
+        let shadow = ClassicalShadow::collect(&Register::new(2), 10, &mut Rng::seed_from_u64(4));
+        shadow.estimate_median_of_means(&[(2, Pauli::Z)], 2);
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use crate::noise::{noisy_counts, NoiseModel};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::distribution::Distribution;
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+
+/// per-circuit and mean linear XEB fidelities
+#[derive(Debug, Clone)]
+pub struct XebResult {
+    pub fidelities: Vec<f64>,
+    pub mean_fidelity: f64,
+}
+
+/// append one of √X, √Y, √W on `q`, never repeating `previous`
+fn push_random_sqrt_gate(
+    circuit: &mut Circuit,
+    q: usize,
+    previous: Option<usize>,
+    rng: &mut Rng,
+) -> usize {
+    let mut choice = rng.gen_range(3);
+    if Some(choice) == previous {
+        choice = (choice + 1 + rng.gen_range(2)) % 3;
+    }
+    match choice {
+        0 => circuit.rx(PI / 2.0, q),
+        1 => circuit.ry(PI / 2.0, q),
+        // √W: π/2 rotation about (X + Y)/√2
+        _ => circuit.rz(-PI / 4.0, q).rx(PI / 2.0, q).rz(PI / 4.0, q),
+    };
+    choice
+}
+
+/// supremacy-style random circuit: `depth` cycles of random √X/√Y/√W on every
+/// qubit followed by CZs on alternating nearest-neighbour pairs of a line
+pub fn random_xeb_circuit(num_qubits: usize, depth: usize, rng: &mut Rng) -> Circuit {
+    let mut circuit = Circuit::new(num_qubits);
+    let mut previous = vec![None; num_qubits];
+    for cycle in 0..depth {
+        for (q, prev) in previous.iter_mut().enumerate() {
+            *prev = Some(push_random_sqrt_gate(&mut circuit, q, *prev, rng));
+        }
+        for q in (cycle % 2..num_qubits.saturating_sub(1)).step_by(2) {
+            circuit.cz(q, q + 1);
+        }
+    }
+    circuit
+}
+
+/// F = 2^n ⟨p_ideal(x)⟩_samples − 1
+pub fn linear_xeb_fidelity(ideal: &Distribution, counts: &BTreeMap<usize, usize>) -> f64 {
+    let shots: usize = counts.values().sum();
+    assert!(shots > 0, "no samples");
+    let mean_p: f64 = counts
+        .iter()
+        .map(|(&x, &c)| ideal.prob(x) * c as f64)
+        .sum::<f64>()
+        / shots as f64;
+    (1usize << ideal.num_qubits()) as f64 * mean_p - 1.0
+}
+
+/// value of F when sampling the ideal distribution itself: 2^n Σ p² − 1
+pub fn ideal_xeb_fidelity(ideal: &Distribution) -> f64 {
+    let collision: f64 = ideal.probabilities().iter().map(|p| p * p).sum();
+    (1usize << ideal.num_qubits()) as f64 * collision - 1.0
+}
+
+/// sample `num_circuits` random circuits on `noise` and score them by linear XEB
+pub fn run_xeb(
+    num_qubits: usize,
+    depth: usize,
+    num_circuits: usize,
+    shots: usize,
+    noise: &NoiseModel,
+    rng: &mut Rng,
+) -> XebResult {
+    let fidelities: Vec<f64> = (0..num_circuits)
+        .map(|_| {
+            let circuit = random_xeb_circuit(num_qubits, depth, rng);
+            let mut register = Register::new(num_qubits);
+            register.apply_circuit(&circuit);
+            let counts = noisy_counts(&circuit, noise, shots, rng);
+            linear_xeb_fidelity(&register.distribution(), &counts)
+        })
+        .collect();
+    let mean_fidelity = fidelities.iter().sum::<f64>() / fidelities.len().max(1) as f64;
+    XebResult {
+        fidelities,
+        mean_fidelity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ideal_samples_score_collision_value() {
+        let mut rng = Rng::seed_from_u64(12);
+        let circuit = random_xeb_circuit(4, 10, &mut rng);
+        let mut register = Register::new(4);
+        register.apply_circuit(&circuit);
+        let ideal = register.distribution();
+        let counts = ideal.sample_counts(20_000, &mut rng);
+        let f = linear_xeb_fidelity(&ideal, &counts);
+        assert!((f - ideal_xeb_fidelity(&ideal)).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_uniform_samples_score_zero() {
+        let mut rng = Rng::seed_from_u64(13);
+        let circuit = random_xeb_circuit(3, 8, &mut rng);
+        let mut register = Register::new(3);
+        register.apply_circuit(&circuit);
+        let uniform = Distribution::new(3, vec![1.0; 8]);
+        let counts = uniform.sample_counts(20_000, &mut rng);
+        assert!(linear_xeb_fidelity(&register.distribution(), &counts).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_noise_lowers_fidelity() {
+        let mut rng = Rng::seed_from_u64(14);
+        let ideal = run_xeb(3, 6, 4, 400, &NoiseModel::ideal(), &mut rng);
+        let noisy = run_xeb(3, 6, 4, 400, &NoiseModel::depolarizing(0.05, 0.1), &mut rng);
+        assert!(noisy.mean_fidelity < ideal.mean_fidelity);
+    }
+}
@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::Path;
+use crate::simulator::hamiltonian::Hamiltonian;
+use super::mapping::FermionMapping;
+use super::molecular::MolecularIntegrals;
+
+/// most spatial orbitals a FCIDUMP header may declare; the two-body table
+/// holds NORB⁴ values and the qubit Hamiltonian acts on 2·NORB qubits
+pub const MAX_FCIDUMP_ORBITALS: usize = 64;
+
+/// contents of a FCIDUMP integral file
+///
+/// Integral lines are `value i j k l` with 1-based orbital indices:
+/// all four non-zero give (ij|kl), k = l = 0 gives h_ij and all zero gives
+/// the core energy. Orbital-energy lines (`i 0 0 0`) are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fcidump {
+    pub integrals: MolecularIntegrals,
+    pub num_electrons: usize,
+    /// 2S, twice the spin projection
+    pub ms2: i64,
+}
+
+impl Fcidump {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let upper = text.to_ascii_uppercase();
+        let end = ["&END", "/"]
+            .iter()
+            .filter_map(|marker| upper.find(marker).map(|i| (i, marker.len())))
+            .min()
+            .ok_or("missing &END in FCIDUMP header")?;
+        let (header, body) = (&upper[..end.0], &text[end.0 + end.1..]);
+        let field = |name: &str| -> Option<String> {
+            let start = header
+                .match_indices(name)
+                .find(|(i, _)| header[i + name.len()..].trim_start().starts_with('='))?
+                .0;
+            let rest = header[start + name.len()..].trim_start()[1..].trim_start();
+            let value: String = rest
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '-' || *c == '+')
+                .collect();
+            Some(value)
+        };
+        let number = |name: &str| -> Result<i64, String> {
+            field(name)
+                .ok_or(format!("missing {} in FCIDUMP header", name))?
+                .parse::<i64>()
+                .map_err(|_| format!("invalid {} in FCIDUMP header", name))
+        };
+        let num_orbitals = usize::try_from(number("NORB")?)
+            .ok()
+            .filter(|&n| (1..=MAX_FCIDUMP_ORBITALS).contains(&n))
+            .ok_or(format!("NORB must be between 1 and {}", MAX_FCIDUMP_ORBITALS))?;
+        let num_electrons = usize::try_from(number("NELEC")?)
+            .ok()
+            .filter(|&n| n <= 2 * num_orbitals)
+            .ok_or("NELEC must be between 0 and 2·NORB")?;
+        let ms2 = if field("MS2").is_some() { number("MS2")? } else { 0 };
+
+        let mut integrals = MolecularIntegrals::new(num_orbitals, 0.0);
+        for (line_no, line) in body.lines().enumerate() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.is_empty() {
+                continue;
+            }
+            let bad = || format!("malformed integral line {}: '{}'", line_no + 1, line.trim());
+            if tokens.len() != 5 {
+                return Err(bad());
+            }
+            let value: f64 = tokens[0].replace(['D', 'd'], "E").parse().map_err(|_| bad())?;
+            let mut idx = [0usize; 4];
+            for (slot, token) in idx.iter_mut().zip(&tokens[1..]) {
+                *slot = token.parse().map_err(|_| bad())?;
+                if *slot > num_orbitals {
+                    return Err(format!("orbital index {} exceeds NORB", slot));
+                }
+            }
+            match idx {
+                [0, 0, 0, 0] => integrals.constant += value,
+                [i, j, 0, 0] if i > 0 && j > 0 => integrals.set_one_body(i - 1, j - 1, value),
+                [_, 0, 0, 0] => {}
+                [i, j, k, l] if i > 0 && j > 0 && k > 0 && l > 0 => {
+                    integrals.set_two_body(i - 1, j - 1, k - 1, l - 1, value)
+                }
+                _ => return Err(bad()),
+            }
+        }
+        Ok(Self {
+            integrals,
+            num_electrons,
+            ms2,
+        })
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let text = fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("cannot read {}: {}", path.as_ref().display(), e))?;
+        Self::parse(&text)
+    }
+
+    /// qubit Hamiltonian on 2·NORB qubits
+    pub fn qubit_hamiltonian(&self, mapping: FermionMapping) -> Hamiltonian {
+        let op = self.integrals.fermion_operator();
+        mapping.apply(&op, self.integrals.num_spin_orbitals())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const H2: &str = " &FCI NORB=  2,NELEC=  2,MS2=0,
+  ORBSYM=1,1,
+  ISYM=1,
+ &END
+  0.6757101548035161  1  1  1  1
+  0.1809311997842312  1  2  1  2
+  0.6645817302552968  1  1  2  2
+  0.6985737227320183  2  2  2  2
+ -1.2563390730032498  1  1  0  0
+ -0.4718960072811421  2  2  0  0
+ -0.5538  1  0  0  0
+  0.7199689944489797  0  0  0  0
+";
+
+    #[test]
+    fn test_parse_h2() {
+        let dump = Fcidump::parse(H2).unwrap();
+        assert_eq!((dump.integrals.num_orbitals(), dump.num_electrons, dump.ms2), (2, 2, 0));
+        assert_eq!(dump.integrals.two_body(1, 0, 1, 0), 0.1809311997842312);
+        let reference = MolecularIntegrals::h2_sto3g();
+        let energy = dump.qubit_hamiltonian(FermionMapping::JordanWigner).ground_energy();
+        let expected = FermionMapping::BravyiKitaev
+            .apply(&reference.fermion_operator(), 4)
+            .ground_energy();
+        assert!((energy - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert!(Fcidump::parse("&FCI NORB=2 &END").unwrap_err().contains("NELEC"));
+        let bad_index = "&FCI NORB=1,NELEC=2,/\n 1.0 2 2 0 0\n";
+        assert!(Fcidump::parse(bad_index).unwrap_err().contains("exceeds NORB"));
+        let fortran = "&FCI NORB=1,NELEC=2,/\n 1.5D-1 1 1 0 0\n";
+        assert_eq!(Fcidump::parse(fortran).unwrap().integrals.one_body(0, 0), 0.15);
+        for header in ["NORB=-1,NELEC=2", "NORB=0,NELEC=0", "NORB=100000,NELEC=2"] {
+            let error = Fcidump::parse(&format!("&FCI {} &END", header)).unwrap_err();
+            assert!(error.contains("NORB must be"), "{}", header);
+        }
+        for header in ["NORB=2,NELEC=-2", "NORB=2,NELEC=5"] {
+            let error = Fcidump::parse(&format!("&FCI {} &END", header)).unwrap_err();
+            assert!(error.contains("NELEC must be"), "{}", header);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use num_complex::Complex64;
+use std::collections::BTreeMap;
+use std::ops::{Add, Mul};
+
+/// ladder operator on a mode: (mode, true) is a†, (mode, false) is a
+pub type Ladder = (usize, bool);
+
+/// coefficients below this magnitude are dropped
+const CUTOFF: f64 = 1e-12;
+
+/// sum of products of fermionic ladder operators
+///
+/// Products are stored as written, left to right, without normal ordering.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FermionOperator {
+    terms: BTreeMap<Vec<Ladder>, Complex64>,
+}
+
+impl FermionOperator {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    /// c · Π ops
+    pub fn term(ops: &[Ladder], coefficient: Complex64) -> Self {
+        let mut op = Self::zero();
+        op.add_term(ops, coefficient);
+        op
+    }
+
+    /// OpenFermion-style label: "1^ 0" is a†_1 a_0, "" is the identity
+    pub fn parse(label: &str, coefficient: f64) -> Result<Self, String> {
+        let ops = label
+            .split_whitespace()
+            .map(|token| {
+                let (mode, creation) = match token.strip_suffix('^') {
+                    Some(mode) => (mode, true),
+                    None => (token, false),
+                };
+                mode.parse::<usize>()
+                    .map(|m| (m, creation))
+                    .map_err(|_| format!("invalid ladder operator '{}'", token))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::term(&ops, Complex64::new(coefficient, 0.0)))
+    }
+
+    pub fn creation(mode: usize) -> Self {
+        Self::term(&[(mode, true)], Complex64::new(1.0, 0.0))
+    }
+
+    pub fn annihilation(mode: usize) -> Self {
+        Self::term(&[(mode, false)], Complex64::new(1.0, 0.0))
+    }
+
+    /// n_p = a†_p a_p
+    pub fn number(mode: usize) -> Self {
+        Self::term(&[(mode, true), (mode, false)], Complex64::new(1.0, 0.0))
+    }
+
+    pub fn add_term(&mut self, ops: &[Ladder], coefficient: Complex64) -> &mut Self {
+        let entry = self.terms.entry(ops.to_vec()).or_insert(Complex64::new(0.0, 0.0));
+        *entry += coefficient;
+        if entry.norm() < CUTOFF {
+            self.terms.remove(ops);
+        }
+        self
+    }
+
+    pub fn terms(&self) -> impl Iterator<Item = (&[Ladder], Complex64)> {
+        self.terms.iter().map(|(ops, &c)| (ops.as_slice(), c))
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// one past the highest mode index used
+    pub fn num_modes(&self) -> usize {
+        self.terms
+            .keys()
+            .flatten()
+            .map(|&(mode, _)| mode + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn scaled(&self, c: Complex64) -> FermionOperator {
+        let mut op = Self::zero();
+        for (ops, coefficient) in self.terms() {
+            op.add_term(ops, coefficient * c);
+        }
+        op
+    }
+
+    /// reverse every product, swap a ↔ a† and conjugate the coefficients
+    pub fn hermitian_conjugate(&self) -> FermionOperator {
+        let mut op = Self::zero();
+        for (ops, c) in self.terms() {
+            let reversed: Vec<Ladder> = ops.iter().rev().map(|&(m, dag)| (m, !dag)).collect();
+            op.add_term(&reversed, c.conj());
+        }
+        op
+    }
+}
+
+impl Add for &FermionOperator {
+    type Output = FermionOperator;
+
+    fn add(self, rhs: &FermionOperator) -> FermionOperator {
+        let mut op = self.clone();
+        for (ops, c) in rhs.terms() {
+            op.add_term(ops, c);
+        }
+        op
+    }
+}
+
+impl Mul for &FermionOperator {
+    type Output = FermionOperator;
+
+    fn mul(self, rhs: &FermionOperator) -> FermionOperator {
+        let mut op = FermionOperator::zero();
+        for (a_ops, a) in self.terms() {
+            for (b_ops, b) in rhs.terms() {
+                let mut ops = a_ops.to_vec();
+                ops.extend_from_slice(b_ops);
+                op.add_term(&ops, a * b);
+            }
+        }
+        op
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_conjugate() {
+        let hop = FermionOperator::parse("2^ 0", 0.5).unwrap();
+        assert_eq!(hop.num_modes(), 3);
+        let back = hop.hermitian_conjugate();
+        assert_eq!(back, FermionOperator::parse("0^ 2", 0.5).unwrap());
+        assert!(FermionOperator::parse("1^ x", 1.0).is_err());
+    }
+
+    #[test]
+    fn test_product_concatenates() {
+        let product = &FermionOperator::creation(1) * &FermionOperator::annihilation(1);
+        assert_eq!(product, FermionOperator::number(1));
+        let sum = &product + &product.scaled(Complex64::new(-1.0, 0.0));
+        assert!(sum.is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+use num_complex::Complex64;
+use crate::simulator::hamiltonian::Hamiltonian;
+use crate::simulator::pauli::Pauli;
+use crate::simulator::pauli_string::PauliString;
+use super::fermion::FermionOperator;
+
+/// qubit image X_U Z_P (I ± Z_O)/2 of a ladder operator
+///
+/// The update set U holds the qubits flipped when the mode's occupation
+/// changes, the parity set P encodes the occupation parity of lower modes and
+/// the occupation set O encodes the mode's own occupation.
+fn ladder_image(
+    num_qubits: usize,
+    update: &[usize],
+    parity: &[usize],
+    occupation: &[usize],
+    creation: bool,
+) -> Hamiltonian {
+    let string = |qubits: &[usize], pauli: Pauli| {
+        let terms: Vec<(usize, Pauli)> = qubits.iter().map(|&q| (q, pauli)).collect();
+        PauliString::from_terms(num_qubits, &terms)
+    };
+    let flip_and_sign = &string(update, Pauli::X) * &string(parity, Pauli::Z);
+    let half = Complex64::new(0.5, 0.0);
+    let mut projector = Hamiltonian::identity(num_qubits, half);
+    let sign = if creation { half } else { -half };
+    projector.add_term(sign, &string(occupation, Pauli::Z));
+    &Hamiltonian::from_pauli(Complex64::new(1.0, 0.0), &flip_and_sign) * &projector
+}
+
+/// sum over terms of the products of mapped ladder operators
+fn transform(
+    op: &FermionOperator,
+    num_qubits: usize,
+    image: impl Fn(usize, bool) -> Hamiltonian,
+) -> Hamiltonian {
+    assert!(op.num_modes() <= num_qubits, "operator needs {} qubits", op.num_modes());
+    let mut result = Hamiltonian::new(num_qubits);
+    for (ops, c) in op.terms() {
+        let mut product = Hamiltonian::identity(num_qubits, c);
+        for &(mode, creation) in ops {
+            product = &product * &image(mode, creation);
+        }
+        result = &result + &product;
+    }
+    result
+}
+
+/// Jordan–Wigner: mode j ↦ qubit j with a Z string on qubits 0..j
+pub fn jordan_wigner(op: &FermionOperator, num_qubits: usize) -> Hamiltonian {
+    transform(op, num_qubits, |mode, creation| {
+        let lower: Vec<usize> = (0..mode).collect();
+        ladder_image(num_qubits, &[mode], &lower, &[mode], creation)
+    })
+}
+
+/// modes whose occupation qubit j's Fenwick-tree ancestors also store
+fn update_set(mode: usize, num_qubits: usize) -> Vec<usize> {
+    let mut set = Vec::new();
+    let mut index = mode + 1;
+    while index <= num_qubits {
+        set.push(index - 1);
+        index += index & index.wrapping_neg();
+    }
+    set
+}
+
+/// qubits whose parity gives the occupation of `mode`
+fn occupation_set(mode: usize) -> Vec<usize> {
+    let mut index = mode + 1;
+    let mut set = vec![mode];
+    let parent = index & (index - 1);
+    index -= 1;
+    while index != parent {
+        set.push(index - 1);
+        index &= index - 1;
+    }
+    set
+}
+
+/// qubits whose parity gives the occupation parity of modes below `mode`
+fn parity_set(mode: usize) -> Vec<usize> {
+    let mut set = Vec::new();
+    let mut index = mode;
+    while index > 0 {
+        set.push(index - 1);
+        index &= index - 1;
+    }
+    set
+}
+
+/// Bravyi–Kitaev on the Fenwick tree, giving O(log n) weight ladder images
+pub fn bravyi_kitaev(op: &FermionOperator, num_qubits: usize) -> Hamiltonian {
+    transform(op, num_qubits, |mode, creation| {
+        ladder_image(
+            num_qubits,
+            &update_set(mode, num_qubits),
+            &parity_set(mode),
+            &occupation_set(mode),
+            creation,
+        )
+    })
+}
+
+/// choice of fermion-to-qubit encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FermionMapping {
+    JordanWigner,
+    BravyiKitaev,
+}
+
+impl FermionMapping {
+    pub fn apply(&self, op: &FermionOperator, num_qubits: usize) -> Hamiltonian {
+        match self {
+            FermionMapping::JordanWigner => jordan_wigner(op, num_qubits),
+            FermionMapping::BravyiKitaev => bravyi_kitaev(op, num_qubits),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// {a_i, a_j†} = δ_ij and {a_i, a_j} = 0 under `map`
+    fn check_anticommutation(map: fn(&FermionOperator, usize) -> Hamiltonian, n: usize) {
+        let identity = Hamiltonian::identity(n, Complex64::new(1.0, 0.0));
+        for i in 0..n {
+            for j in 0..n {
+                let a = map(&FermionOperator::annihilation(i), n);
+                let b_dag = map(&FermionOperator::creation(j), n);
+                let b = map(&FermionOperator::annihilation(j), n);
+                let mixed = &(&a * &b_dag) + &(&b_dag * &a);
+                let expected = if i == j { identity.clone() } else { Hamiltonian::new(n) };
+                assert_eq!(mixed, expected, "{{a_{}, a_{}†}}", i, j);
+                assert!((&(&a * &b) + &(&b * &a)).is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonical_anticommutation() {
+        check_anticommutation(jordan_wigner, 4);
+        check_anticommutation(bravyi_kitaev, 5);
+    }
+
+    #[test]
+    fn test_number_operator_images() {
+        // JW: n_j = (I − Z_j)/2
+        let n1 = jordan_wigner(&FermionOperator::number(1), 3);
+        assert_eq!(n1.len(), 2);
+        assert!((n1.coefficient(&[Pauli::I, Pauli::Z, Pauli::I]).re + 0.5).abs() < 1e-12);
+        // BK of n_3 on four qubits is (I − Z_1 Z_2 Z_3)/2
+        let n3 = bravyi_kitaev(&FermionOperator::number(3), 4);
+        let zzz = [Pauli::I, Pauli::Z, Pauli::Z, Pauli::Z];
+        assert!((n3.coefficient(&zzz).re + 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_hopping_spectra_agree() {
+        let hop = FermionOperator::parse("0^ 2", -1.0).unwrap();
+        let h = &hop + &hop.hermitian_conjugate();
+        let jw = jordan_wigner(&h, 4).eigenvalues();
+        let bk = bravyi_kitaev(&h, 4).eigenvalues();
+        for (a, b) in jw.iter().zip(&bk) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub mod fcidump;
+pub mod fermion;
+pub mod mapping;
+pub mod molecular;
+
+pub use fcidump::{Fcidump, MAX_FCIDUMP_ORBITALS};
+pub use fermion::FermionOperator;
+pub use mapping::{bravyi_kitaev, jordan_wigner, FermionMapping};
+pub use molecular::MolecularIntegrals;
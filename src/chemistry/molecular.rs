@@ -0,0 +1,144 @@
+use num_complex::Complex64;
+use super::fermion::FermionOperator;
+
+/// spatial-orbital integrals of a molecular electronic Hamiltonian
+///
+/// Two-electron integrals use chemists' notation (pq|rs) over real orbitals,
+/// so they carry the usual 8-fold permutation symmetry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MolecularIntegrals {
+    num_orbitals: usize,
+    pub constant: f64,
+    one_body: Vec<f64>,
+    two_body: Vec<f64>,
+}
+
+impl MolecularIntegrals {
+    /// all integrals zero
+    pub fn new(num_orbitals: usize, constant: f64) -> Self {
+        Self {
+            num_orbitals,
+            constant,
+            one_body: vec![0.0; num_orbitals.pow(2)],
+            two_body: vec![0.0; num_orbitals.pow(4)],
+        }
+    }
+
+    /// H2 in STO-3G at 0.735 Å (two spatial orbitals, FCI energy −1.137306 Ha)
+    pub fn h2_sto3g() -> Self {
+        let mut integrals = Self::new(2, 0.719_968_994_448_979_7);
+        integrals.set_one_body(0, 0, -1.256_339_073_003_249_8);
+        integrals.set_one_body(1, 1, -0.471_896_007_281_142_1);
+        integrals.set_two_body(0, 0, 0, 0, 0.675_710_154_803_516_1);
+        integrals.set_two_body(1, 1, 1, 1, 0.698_573_722_732_018_3);
+        integrals.set_two_body(0, 0, 1, 1, 0.664_581_730_255_296_8);
+        integrals.set_two_body(0, 1, 0, 1, 0.180_931_199_784_231_2);
+        integrals
+    }
+
+    pub fn num_orbitals(&self) -> usize {
+        self.num_orbitals
+    }
+
+    /// h_pq
+    pub fn one_body(&self, p: usize, q: usize) -> f64 {
+        self.one_body[p * self.num_orbitals + q]
+    }
+
+    /// (pq|rs)
+    pub fn two_body(&self, p: usize, q: usize, r: usize, s: usize) -> f64 {
+        let n = self.num_orbitals;
+        self.two_body[((p * n + q) * n + r) * n + s]
+    }
+
+    /// set h_pq = h_qp
+    pub fn set_one_body(&mut self, p: usize, q: usize, value: f64) {
+        let n = self.num_orbitals;
+        self.one_body[p * n + q] = value;
+        self.one_body[q * n + p] = value;
+    }
+
+    /// set (pq|rs) and its symmetric partners
+    pub fn set_two_body(&mut self, p: usize, q: usize, r: usize, s: usize, value: f64) {
+        let n = self.num_orbitals;
+        for (a, b, c, d) in [
+            (p, q, r, s),
+            (q, p, r, s),
+            (p, q, s, r),
+            (q, p, s, r),
+            (r, s, p, q),
+            (s, r, p, q),
+            (r, s, q, p),
+            (s, r, q, p),
+        ] {
+            self.two_body[((a * n + b) * n + c) * n + d] = value;
+        }
+    }
+
+    /// number of spin orbitals; spin orbital 2p is p↑ and 2p+1 is p↓
+    pub fn num_spin_orbitals(&self) -> usize {
+        2 * self.num_orbitals
+    }
+
+    /// H = E₀ + Σ h_pq a†_pσ a_qσ + ½ Σ (pq|rs) a†_pσ a†_rτ a_sτ a_qσ
+    pub fn fermion_operator(&self) -> FermionOperator {
+        let n = self.num_orbitals;
+        let real = |x: f64| Complex64::new(x, 0.0);
+        let mut op = FermionOperator::term(&[], real(self.constant));
+        for p in 0..n {
+            for q in 0..n {
+                let h = self.one_body(p, q);
+                if h == 0.0 {
+                    continue;
+                }
+                for sigma in 0..2 {
+                    op.add_term(&[(2 * p + sigma, true), (2 * q + sigma, false)], real(h));
+                }
+            }
+        }
+        let indices = (0..n.pow(4)).map(|k| (k / n.pow(3), k / n.pow(2) % n, k / n % n, k % n));
+        for (p, q, r, s) in indices {
+            let v = self.two_body(p, q, r, s);
+            if v == 0.0 {
+                continue;
+            }
+            for sigma in 0..2 {
+                for tau in 0..2 {
+                    let (ps, qs, rt, st) = (2 * p + sigma, 2 * q + sigma, 2 * r + tau, 2 * s + tau);
+                    if ps == rt || qs == st {
+                        continue;
+                    }
+                    op.add_term(&[(ps, true), (rt, true), (st, false), (qs, false)], real(0.5 * v));
+                }
+            }
+        }
+        op
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chemistry::mapping::{bravyi_kitaev, jordan_wigner};
+
+    #[test]
+    fn test_h2_ground_energy() {
+        let h2 = MolecularIntegrals::h2_sto3g();
+        let fermionic = h2.fermion_operator();
+        let jw = jordan_wigner(&fermionic, h2.num_spin_orbitals());
+        assert!(jw.is_hermitian(1e-12));
+        assert!((jw.ground_energy() + 1.137_306).abs() < 1e-5, "{}", jw.ground_energy());
+        let bk = bravyi_kitaev(&fermionic, h2.num_spin_orbitals());
+        assert!((bk.ground_energy() - jw.ground_energy()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_two_body_symmetry() {
+        let mut integrals = MolecularIntegrals::new(3, 0.0);
+        integrals.set_two_body(0, 1, 2, 0, 0.3);
+        assert_eq!(integrals.two_body(2, 0, 1, 0), 0.3);
+        assert_eq!(integrals.two_body(0, 2, 0, 1), 0.3);
+        assert_eq!(integrals.two_body(0, 1, 0, 2), 0.3);
+        assert_eq!(integrals.two_body(0, 0, 1, 2), 0.0);
+    }
+}
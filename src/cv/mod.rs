@@ -0,0 +1,5 @@
+pub mod operators;
+pub mod state;
+
+pub use operators::{annihilation, beamsplitter, displacement, number, rotation, squeezing};
+pub use state::FockState;
@@ -0,0 +1,84 @@
+use num_complex::Complex64;
+use crate::simulator::matrix::Matrix;
+
+/// truncated annihilation operator a|n⟩ = √n |n−1⟩ on levels 0..cutoff
+pub fn annihilation(cutoff: usize) -> Matrix {
+    Matrix::from_fn(cutoff, cutoff, |i, j| {
+        Complex64::new(if j == i + 1 { (j as f64).sqrt() } else { 0.0 }, 0.0)
+    })
+}
+
+/// number operator a†a
+pub fn number(cutoff: usize) -> Matrix {
+    Matrix::from_fn(cutoff, cutoff, |i, j| {
+        Complex64::new(if i == j { i as f64 } else { 0.0 }, 0.0)
+    })
+}
+
+fn sum(a: &Matrix, b: &Matrix, cb: Complex64) -> Matrix {
+    a + &b.scaled(cb)
+}
+
+/// D(α) = exp(α a† − α* a)
+///
+/// Built from the truncated generator, so only amplitudes well below the
+/// cutoff are accurate.
+pub fn displacement(alpha: Complex64, cutoff: usize) -> Matrix {
+    let a = annihilation(cutoff);
+    sum(&a.dagger().scaled(alpha), &a, -alpha.conj()).expm()
+}
+
+/// S(z) = exp((z* a² − z a†²)/2)
+pub fn squeezing(z: Complex64, cutoff: usize) -> Matrix {
+    let a = annihilation(cutoff);
+    let a2 = &a * &a;
+    let ad2 = a2.dagger();
+    sum(&a2.scaled(z.conj() * 0.5), &ad2, -z * 0.5).expm()
+}
+
+/// R(φ) = exp(iφ a†a)
+pub fn rotation(phi: f64, cutoff: usize) -> Matrix {
+    Matrix::from_fn(cutoff, cutoff, |i, j| {
+        if i == j {
+            Complex64::from_polar(1.0, phi * i as f64)
+        } else {
+            Complex64::new(0.0, 0.0)
+        }
+    })
+}
+
+/// BS(θ, φ) = exp(θ(e^{iφ} a b† − e^{−iφ} a† b)) on two modes
+///
+/// The first mode is the least significant digit of the cutoff² index.
+/// θ = π/4 is a 50:50 splitter.
+pub fn beamsplitter(theta: f64, phi: f64, cutoff: usize) -> Matrix {
+    let a = Matrix::identity(cutoff).kron(&annihilation(cutoff));
+    let b = annihilation(cutoff).kron(&Matrix::identity(cutoff));
+    let forward = &a * &b.dagger();
+    let backward = &a.dagger() * &b;
+    let e = Complex64::from_polar(theta, phi);
+    sum(&forward.scaled(e), &backward, -e.conj()).expm()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commutator_away_from_cutoff() {
+        // [a, a†] = 1 except on the last level
+        let cutoff = 6;
+        let a = annihilation(cutoff);
+        let comm = &(&a * &a.dagger()) + &(&a.dagger() * &a).scaled(Complex64::new(-1.0, 0.0));
+        for n in 0..cutoff - 1 {
+            assert!((comm[(n, n)] - Complex64::new(1.0, 0.0)).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_gates_are_unitary() {
+        assert!(displacement(Complex64::new(0.8, -0.3), 10).is_unitary(1e-9));
+        assert!(squeezing(Complex64::new(0.4, 0.0), 10).is_unitary(1e-9));
+        assert!(beamsplitter(0.7, 0.2, 4).is_unitary(1e-9));
+    }
+}
@@ -0,0 +1,139 @@
+use num_complex::Complex64;
+use crate::simulator::matrix::Matrix;
+use crate::simulator::qudit::QuditState;
+use super::operators::{beamsplitter, displacement, rotation, squeezing};
+
+/// bosonic modes in the Fock basis truncated to photon numbers 0..cutoff
+///
+/// Each mode is a `cutoff`-level qudit; mode 0 is the least significant
+/// digit of the basis index.
+#[derive(Debug, Clone)]
+pub struct FockState {
+    cutoff: usize,
+    state: QuditState,
+}
+
+impl FockState {
+    /// vacuum on every mode
+    pub fn vacuum(num_modes: usize, cutoff: usize) -> Self {
+        Self {
+            cutoff,
+            state: QuditState::new(cutoff, num_modes),
+        }
+    }
+
+    /// |n_0, n_1, …⟩ with `photons[m]` photons in mode m
+    pub fn fock(photons: &[usize], cutoff: usize) -> Self {
+        let mut amplitudes = vec![Complex64::new(0.0, 0.0); cutoff.pow(photons.len() as u32)];
+        let index = photons.iter().rev().fold(0, |acc, &n| {
+            assert!(n < cutoff, "{} photons exceed the cutoff", n);
+            acc * cutoff + n
+        });
+        amplitudes[index] = Complex64::new(1.0, 0.0);
+        Self {
+            cutoff,
+            state: QuditState::from_amplitudes(cutoff, amplitudes),
+        }
+    }
+
+    pub fn cutoff(&self) -> usize {
+        self.cutoff
+    }
+
+    pub fn num_modes(&self) -> usize {
+        self.state.num_qudits()
+    }
+
+    pub fn state(&self) -> &QuditState {
+        &self.state
+    }
+
+    pub fn apply(&mut self, modes: &[usize], operator: &Matrix) -> &mut Self {
+        self.state.apply_operator(modes, operator);
+        self
+    }
+
+    pub fn displace(&mut self, mode: usize, alpha: Complex64) -> &mut Self {
+        let op = displacement(alpha, self.cutoff);
+        self.apply(&[mode], &op)
+    }
+
+    pub fn squeeze(&mut self, mode: usize, z: Complex64) -> &mut Self {
+        let op = squeezing(z, self.cutoff);
+        self.apply(&[mode], &op)
+    }
+
+    pub fn rotate(&mut self, mode: usize, phi: f64) -> &mut Self {
+        let op = rotation(phi, self.cutoff);
+        self.apply(&[mode], &op)
+    }
+
+    pub fn beamsplit(&mut self, a: usize, b: usize, theta: f64, phi: f64) -> &mut Self {
+        let op = beamsplitter(theta, phi, self.cutoff);
+        self.apply(&[a, b], &op)
+    }
+
+    /// P(n photons in `mode`) for n in 0..cutoff
+    pub fn photon_distribution(&self, mode: usize) -> Vec<f64> {
+        (0..self.cutoff).map(|n| self.state.prob_level(mode, n)).collect()
+    }
+
+    /// ⟨a†a⟩ on `mode`
+    pub fn mean_photon_number(&self, mode: usize) -> f64 {
+        self.photon_distribution(mode)
+            .iter()
+            .enumerate()
+            .map(|(n, p)| n as f64 * p)
+            .sum()
+    }
+
+    /// probability mass in the top Fock level of any mode, a truncation check
+    pub fn truncation_error(&self) -> f64 {
+        (0..self.num_modes())
+            .map(|m| self.photon_distribution(m)[self.cutoff - 1])
+            .fold(0.0, f64::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coherent_state_is_poissonian() {
+        let alpha = Complex64::new(0.9, 0.4);
+        let mut state = FockState::vacuum(1, 25);
+        state.displace(0, alpha);
+        let mean = alpha.norm_sqr();
+        let dist = state.photon_distribution(0);
+        let mut poisson = (-mean).exp();
+        for (n, p) in dist.iter().take(8).enumerate() {
+            if n > 0 {
+                poisson *= mean / n as f64;
+            }
+            assert!((p - poisson).abs() < 1e-8, "n = {}", n);
+        }
+        assert!((state.mean_photon_number(0) - mean).abs() < 1e-8);
+        assert!(state.truncation_error() < 1e-12);
+    }
+
+    #[test]
+    fn test_squeezed_vacuum_photon_number() {
+        let r = 0.5f64;
+        let mut state = FockState::vacuum(1, 40);
+        state.squeeze(0, Complex64::new(r, 0.0));
+        assert!((state.mean_photon_number(0) - r.sinh().powi(2)).abs() < 1e-6);
+        // only even photon numbers are populated
+        assert!(state.photon_distribution(0)[1] < 1e-12);
+    }
+
+    #[test]
+    fn test_hong_ou_mandel_dip() {
+        let mut state = FockState::fock(&[1, 1], 4);
+        state.beamsplit(0, 1, std::f64::consts::FRAC_PI_4, 0.0);
+        // |1,1⟩ → (|2,0⟩ − |0,2⟩)/√2: the coincidence amplitude vanishes
+        let coincidence = state.state().amplitudes()[1 + 4];
+        assert!(coincidence.norm() < 1e-9);
+        assert!((state.photon_distribution(0)[2] - 0.5).abs() < 1e-9);
+    }
+}
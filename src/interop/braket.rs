@@ -0,0 +1,595 @@
+use std::collections::BTreeMap;
+use std::f64::consts::FRAC_PI_4;
+use num_complex::Complex64;
+use super::json::Json;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::{
+    dagger, h_matrix, matmul, phase_matrix, ry_matrix, sdg_matrix, y_matrix, Gate, Matrix2,
+};
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+
+/// widest task simulated; state vectors and outcome indices give out beyond it
+pub const MAX_BRAKET_QUBITS: usize = 28;
+
+/// single-qubit factor of a tensor-product observable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Observable {
+    I,
+    X,
+    Y,
+    Z,
+    H,
+}
+
+impl Observable {
+    fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "i" => Observable::I,
+            "x" => Observable::X,
+            "y" => Observable::Y,
+            "z" => Observable::Z,
+            "h" => Observable::H,
+            _ => return Err(format!("unsupported observable '{}'", name)),
+        })
+    }
+
+    /// rotation taking the observable's eigenbasis to the Z basis
+    fn basis_rotation(self) -> Option<Matrix2> {
+        match self {
+            Observable::I | Observable::Z => None,
+            Observable::X => Some(h_matrix()),
+            Observable::Y => Some(matmul(&h_matrix(), &sdg_matrix())),
+            Observable::H => Some(ry_matrix(-FRAC_PI_4)),
+        }
+    }
+}
+
+/// requested result type of a Braket task
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResultType {
+    /// joint distribution of `targets`, first target most significant;
+    /// empty means all qubits
+    Probability { targets: Vec<usize> },
+    Expectation { observable: Vec<Observable>, targets: Vec<usize> },
+    Variance { observable: Vec<Observable>, targets: Vec<usize> },
+    /// per-shot eigenvalues of the observable
+    Sample { observable: Vec<Observable>, targets: Vec<usize> },
+    /// amplitudes of bitstrings written qubit 0 first
+    Amplitude { states: Vec<String> },
+}
+
+/// computed value of one result type
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResultValue {
+    Probability(Vec<f64>),
+    Expectation(f64),
+    Variance(f64),
+    Sample(Vec<f64>),
+    Amplitude(Vec<(String, Complex64)>),
+}
+
+/// circuit and result requests of a Braket task
+#[derive(Debug, Clone, PartialEq)]
+pub struct BraketTask {
+    pub circuit: Circuit,
+    pub results: Vec<ResultType>,
+}
+
+fn usize_field(value: &Json, key: &str) -> Result<usize, String> {
+    value.get(key).and_then(Json::as_usize).ok_or_else(|| format!("missing index '{}'", key))
+}
+
+fn usize_list(value: &Json, key: &str) -> Result<Vec<usize>, String> {
+    match value.get(key) {
+        None => Ok(Vec::new()),
+        Some(list) => list
+            .as_array()
+            .and_then(|items| items.iter().map(Json::as_usize).collect())
+            .ok_or_else(|| format!("'{}' must be a list of indices", key)),
+    }
+}
+
+fn observable_list(value: &Json, targets: &[usize]) -> Result<Vec<Observable>, String> {
+    let names = value
+        .get("observable")
+        .and_then(Json::as_array)
+        .ok_or("result needs an 'observable' list")?;
+    let observable: Vec<Observable> = names
+        .iter()
+        .map(|n| n.as_str().ok_or("Hermitian observables are not supported".to_string()))
+        .map(|n| n.and_then(Observable::parse))
+        .collect::<Result<_, _>>()?;
+    if observable.len() != targets.len() {
+        let (factors, targets) = (observable.len(), targets.len());
+        return Err(format!("{} observable factors for {} targets", factors, targets));
+    }
+    distinct_targets(targets)?;
+    Ok(observable)
+}
+
+/// tensor-product factors and probability bits must sit on different qubits
+fn distinct_targets(targets: &[usize]) -> Result<(), String> {
+    match (1..targets.len()).find(|&i| targets[..i].contains(&targets[i])) {
+        Some(i) => Err(format!("result repeats target {}", targets[i])),
+        None => Ok(()),
+    }
+}
+
+/// 2×2 matrix from JAQCD's `[[[re, im], …], …]`
+fn jaqcd_matrix(value: &Json) -> Result<Matrix2, String> {
+    let entry = |z: &Json| match z.as_array()? {
+        [re, im] => Some(Complex64::new(re.as_f64()?, im.as_f64()?)),
+        _ => None,
+    };
+    let row = |r: &Json| match r.as_array()? {
+        [a, b] => Some([entry(a)?, entry(b)?]),
+        _ => None,
+    };
+    match value.get("matrix").and_then(Json::as_array) {
+        Some([a, b]) => row(a).zip(row(b)).map(|(a, b)| [a, b]),
+        _ => None,
+    }
+    .ok_or_else(|| "only single-qubit 'unitary' instructions are supported".to_string())
+}
+
+fn v_matrix() -> Matrix2 {
+    let (p, m) = (Complex64::new(0.5, 0.5), Complex64::new(0.5, -0.5));
+    [[p, m], [m, p]]
+}
+
+/// append one JAQCD instruction to `ops`
+fn jaqcd_instruction(inst: &Json, ops: &mut Vec<(Gate, Vec<usize>)>) -> Result<(), String> {
+    let kind = inst.get("type").and_then(Json::as_str).ok_or("instruction without 'type'")?;
+    let angle = || inst.get("angle").and_then(Json::as_f64).ok_or("missing 'angle'");
+    let target = || usize_field(inst, "target");
+    let control = || usize_field(inst, "control");
+    let single = |gate: Gate| -> Result<(Gate, Vec<usize>), String> { Ok((gate, vec![target()?])) };
+    let op = match kind {
+        "i" => single(Gate::I)?,
+        "h" => single(Gate::H)?,
+        "x" => single(Gate::X)?,
+        "y" => single(Gate::Y)?,
+        "z" => single(Gate::Z)?,
+        "s" => single(Gate::S)?,
+        "si" => single(Gate::Sdg)?,
+        "t" => single(Gate::T)?,
+        "ti" => single(Gate::Tdg)?,
+        "v" => single(Gate::Mcu(0, v_matrix().into()))?,
+        "vi" => single(Gate::Mcu(0, dagger(&v_matrix()).into()))?,
+        "rx" => single(Gate::Rx(angle()?))?,
+        "ry" => single(Gate::Ry(angle()?))?,
+        "rz" => single(Gate::Rz(angle()?))?,
+        "phaseshift" => single(Gate::Phase(angle()?))?,
+        "cnot" => (Gate::Cx, vec![control()?, target()?]),
+        "cy" => (Gate::Mcu(1, y_matrix().into()), vec![control()?, target()?]),
+        "cz" => (Gate::Cz, vec![control()?, target()?]),
+        "cphaseshift" => (Gate::Mcu(1, phase_matrix(angle()?).into()), vec![control()?, target()?]),
+        "swap" => (Gate::Swap, usize_list(inst, "targets")?),
+        "ccnot" => {
+            let mut qubits = usize_list(inst, "controls")?;
+            qubits.push(target()?);
+            (Gate::Mcx(2), qubits)
+        }
+        "cswap" => {
+            let (c, t) = (control()?, usize_list(inst, "targets")?);
+            let [a, b] = t[..] else { return Err("cswap needs two targets".into()) };
+            ops.push((Gate::Cx, vec![b, a]));
+            ops.push((Gate::Mcx(2), vec![c, a, b]));
+            (Gate::Cx, vec![b, a])
+        }
+        "unitary" => {
+            let targets = usize_list(inst, "targets")?;
+            (Gate::Mcu(0, jaqcd_matrix(inst)?.into()), targets)
+        }
+        other => return Err(format!("unsupported JAQCD instruction '{}'", other)),
+    };
+    if op.1.len() != op.0.num_qubits() {
+        return Err(format!("'{}' expects {} qubits", kind, op.0.num_qubits()));
+    }
+    ops.push(op);
+    Ok(())
+}
+
+fn jaqcd_result(value: &Json) -> Result<ResultType, String> {
+    let kind = value.get("type").and_then(Json::as_str).ok_or("result without 'type'")?;
+    let targets = usize_list(value, "targets")?;
+    let observable = || observable_list(value, &targets);
+    Ok(match kind {
+        "probability" => {
+            distinct_targets(&targets)?;
+            ResultType::Probability { targets }
+        }
+        "expectation" => ResultType::Expectation { observable: observable()?, targets },
+        "variance" => ResultType::Variance { observable: observable()?, targets },
+        "sample" => ResultType::Sample { observable: observable()?, targets },
+        "amplitude" => {
+            let states = value
+                .get("states")
+                .and_then(Json::as_array)
+                .and_then(|s| s.iter().map(|x| x.as_str().map(str::to_string)).collect())
+                .ok_or("amplitude needs a 'states' list")?;
+            ResultType::Amplitude { states }
+        }
+        other => return Err(format!("unsupported result type '{}'", other)),
+    })
+}
+
+/// qubit index of an OpenQASM 3 operand such as `q[2]` or `q`
+fn qasm3_qubit(text: &str, registers: &BTreeMap<String, (usize, usize)>) -> Result<usize, String> {
+    let text = text.trim();
+    let (name, index) = match text.split_once('[') {
+        Some((name, rest)) => (name, rest.trim_end_matches(']').trim().parse().ok()),
+        None => (text, Some(0)),
+    };
+    match (registers.get(name.trim()), index) {
+        (Some(&(offset, size)), Some(i)) if i < size => Ok(offset + i),
+        _ => Err(format!("unknown qubit '{}'", text)),
+    }
+}
+
+/// result request from `#pragma braket result …`
+fn qasm3_result(
+    text: &str,
+    registers: &BTreeMap<String, (usize, usize)>,
+    num_qubits: usize,
+) -> Result<ResultType, String> {
+    let (kind, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let rest = rest.trim();
+    let observable = || -> Result<(Vec<Observable>, Vec<usize>), String> {
+        let mut factors = Vec::new();
+        let mut targets = Vec::new();
+        for factor in rest.split('@') {
+            let (name, operand) = factor
+                .trim()
+                .strip_suffix(')')
+                .and_then(|f| f.split_once('('))
+                .ok_or_else(|| format!("invalid observable '{}'", factor.trim()))?;
+            factors.push(Observable::parse(name.trim())?);
+            targets.push(qasm3_qubit(operand, registers)?);
+        }
+        distinct_targets(&targets)?;
+        Ok((factors, targets))
+    };
+    Ok(match kind {
+        "probability" => {
+            let targets: Vec<usize> = match rest {
+                "" | "all" => (0..num_qubits).collect(),
+                _ => rest.split(',').map(|q| qasm3_qubit(q, registers)).collect::<Result<_, _>>()?,
+            };
+            distinct_targets(&targets)?;
+            ResultType::Probability { targets }
+        }
+        "expectation" => {
+            let (observable, targets) = observable()?;
+            ResultType::Expectation { observable, targets }
+        }
+        "variance" => {
+            let (observable, targets) = observable()?;
+            ResultType::Variance { observable, targets }
+        }
+        "sample" => {
+            let (observable, targets) = observable()?;
+            ResultType::Sample { observable, targets }
+        }
+        "amplitude" => ResultType::Amplitude {
+            states: rest.split(',').map(|s| s.trim().trim_matches('"').to_string()).collect(),
+        },
+        other => return Err(format!("unsupported result type '{}'", other)),
+    })
+}
+
+impl BraketTask {
+    /// JAQCD program (`braket.ir.jaqcd.program`) or OpenQASM program
+    /// (`braket.ir.openqasm.program` with its `source`)
+    pub fn from_json(value: &Json) -> Result<BraketTask, String> {
+        let schema = value
+            .get("braketSchemaHeader")
+            .and_then(|h| h.get("name"))
+            .and_then(Json::as_str)
+            .unwrap_or("braket.ir.jaqcd.program");
+        match schema {
+            "braket.ir.openqasm.program" => {
+                let source = value.get("source").and_then(Json::as_str).ok_or("missing 'source'")?;
+                BraketTask::from_openqasm3(source)
+            }
+            "braket.ir.jaqcd.program" => {
+                let instructions = value
+                    .get("instructions")
+                    .and_then(Json::as_array)
+                    .ok_or("missing 'instructions'")?;
+                let mut ops = Vec::new();
+                for inst in instructions {
+                    jaqcd_instruction(inst, &mut ops)?;
+                }
+                let results = match value.get("results").and_then(Json::as_array) {
+                    Some(results) => results.iter().map(jaqcd_result).collect::<Result<_, _>>()?,
+                    None => Vec::new(),
+                };
+                let used = ops.iter().flat_map(|(_, q)| q.iter().copied());
+                let mut width = used.max().map_or(0, |q| q.saturating_add(1));
+                for result in &results {
+                    if let ResultType::Probability { targets }
+                    | ResultType::Expectation { targets, .. }
+                    | ResultType::Variance { targets, .. }
+                    | ResultType::Sample { targets, .. } = result
+                    {
+                        width = width.max(targets.iter().max().map_or(0, |q| q.saturating_add(1)));
+                    }
+                }
+                if width > MAX_BRAKET_QUBITS {
+                    return Err(format!("programs are limited to {} qubits", MAX_BRAKET_QUBITS));
+                }
+                let mut circuit = Circuit::new(width);
+                for (gate, qubits) in &ops {
+                    if (1..qubits.len()).any(|i| qubits[..i].contains(&qubits[i])) {
+                        return Err(format!("{} repeats a qubit", gate.name()));
+                    }
+                    circuit.push(gate.clone(), qubits);
+                }
+                Ok(BraketTask { circuit, results })
+            }
+            other => Err(format!("unsupported Braket program schema '{}'", other)),
+        }
+    }
+
+    /// Braket's OpenQASM 3 subset: `qubit[n]` declarations, qelib-style and
+    /// Braket gate names, measurements (ignored) and result pragmas
+    pub fn from_openqasm3(source: &str) -> Result<BraketTask, String> {
+        let mut registers = BTreeMap::new();
+        let mut num_qubits = 0usize;
+        let mut pragmas = Vec::new();
+        let mut qasm2 = String::from("OPENQASM 2.0;\n");
+        let mut body = String::new();
+        for line in source.lines() {
+            let line = line.split("//").next().unwrap_or("").trim();
+            match line.strip_prefix("#pragma braket result") {
+                Some(pragma) => pragmas.push(pragma.trim().to_string()),
+                None => body.push_str(line),
+            }
+            body.push('\n');
+        }
+        for statement in body.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            let (head, rest) = statement.split_once(char::is_whitespace).unwrap_or((statement, ""));
+            let rest = rest.trim();
+            let name_end = head.find('(').unwrap_or(head.len());
+            let translated = match &head[..name_end] {
+                "OPENQASM" | "include" | "bit" => continue,
+                _ if head.starts_with("bit[") || statement.contains("measure") => continue,
+                "qubit" => (rest.to_string(), 1),
+                _ if head.starts_with("qubit[") => {
+                    let size = head[6..].trim_end_matches(']').trim().parse().map_err(|_| {
+                        format!("invalid declaration '{}'", statement)
+                    })?;
+                    (rest.to_string(), size)
+                }
+                gate => {
+                    let alias = match gate {
+                        "cnot" => "cx",
+                        "si" => "sdg",
+                        "ti" => "tdg",
+                        "i" => "id",
+                        "phaseshift" => "p",
+                        "cphaseshift" => "cp",
+                        other => other,
+                    };
+                    qasm2.push_str(&format!("{}{} {};\n", alias, &head[name_end..], rest));
+                    continue;
+                }
+            };
+            let (name, size) = translated;
+            registers.insert(name.clone(), (num_qubits, size));
+            num_qubits = num_qubits
+                .checked_add(size)
+                .filter(|&n| n <= MAX_BRAKET_QUBITS)
+                .ok_or_else(|| format!("programs are limited to {} qubits", MAX_BRAKET_QUBITS))?;
+            qasm2.push_str(&format!("qreg {}[{}];\n", name, size));
+        }
+        let circuit = Circuit::from_qasm_with_limit(&qasm2, MAX_BRAKET_QUBITS)?;
+        let results = pragmas
+            .iter()
+            .map(|p| qasm3_result(p, &registers, num_qubits))
+            .collect::<Result<_, _>>()?;
+        Ok(BraketTask { circuit, results })
+    }
+
+    /// values of every requested result; `shots == 0` gives exact values and
+    /// rejects sample requests, as Braket simulators do
+    pub fn evaluate(&self, shots: usize, rng: &mut Rng) -> Result<Vec<ResultValue>, String> {
+        if self.circuit.num_qubits() > MAX_BRAKET_QUBITS {
+            return Err(format!("tasks are limited to {} qubits", MAX_BRAKET_QUBITS));
+        }
+        let mut state = Register::new(self.circuit.num_qubits());
+        state.apply_circuit(&self.circuit);
+        self.results.iter().map(|result| evaluate_one(&state, result, shots, rng)).collect()
+    }
+}
+
+/// state rotated into the observable's eigenbasis and the Z-parity mask
+fn rotated(state: &Register, observable: &[Observable], targets: &[usize]) -> (Register, usize) {
+    let mut rotated = state.clone();
+    let mut mask = 0;
+    for (&factor, &q) in observable.iter().zip(targets) {
+        if let Some(rotation) = factor.basis_rotation() {
+            rotated.apply_matrix2(q, rotation);
+        }
+        if factor != Observable::I {
+            mask |= 1 << q;
+        }
+    }
+    (rotated, mask)
+}
+
+fn eigenvalue(outcome: usize, mask: usize) -> f64 {
+    if (outcome & mask).count_ones().is_multiple_of(2) { 1.0 } else { -1.0 }
+}
+
+fn evaluate_one(
+    state: &Register,
+    result: &ResultType,
+    shots: usize,
+    rng: &mut Rng,
+) -> Result<ResultValue, String> {
+    let n = state.num_qubits();
+    let check = |targets: &[usize]| match targets.iter().find(|&&q| q >= n) {
+        Some(q) => Err(format!("target {} is outside the {}-qubit circuit", q, n)),
+        None => Ok(()),
+    };
+    Ok(match result {
+        ResultType::Probability { targets } => {
+            check(targets)?;
+            let targets: Vec<usize> =
+                if targets.is_empty() { (0..n).collect() } else { targets.clone() };
+            // first target most significant
+            let reversed: Vec<usize> = targets.iter().rev().copied().collect();
+            let exact = state.marginal(&reversed);
+            if shots == 0 {
+                ResultValue::Probability(exact.probabilities().to_vec())
+            } else {
+                let mut estimate = vec![0.0; 1 << targets.len()];
+                for (outcome, count) in exact.sample_counts(shots, rng) {
+                    estimate[outcome] = count as f64 / shots as f64;
+                }
+                ResultValue::Probability(estimate)
+            }
+        }
+        ResultType::Expectation { observable, targets }
+        | ResultType::Variance { observable, targets }
+        | ResultType::Sample { observable, targets } => {
+            check(targets)?;
+            let (rotated, mask) = rotated(state, observable, targets);
+            let distribution = rotated.distribution();
+            let samples: Option<Vec<f64>> = (shots > 0).then(|| {
+                let sampler = distribution.sampler();
+                (0..shots).map(|_| eigenvalue(sampler.sample(rng), mask)).collect()
+            });
+            let mean = match &samples {
+                Some(samples) => samples.iter().sum::<f64>() / shots as f64,
+                None => distribution
+                    .probabilities()
+                    .iter()
+                    .enumerate()
+                    .map(|(outcome, p)| p * eigenvalue(outcome, mask))
+                    .sum(),
+            };
+            match result {
+                ResultType::Expectation { .. } => ResultValue::Expectation(mean),
+                ResultType::Variance { .. } => {
+                    ResultValue::Variance(if mask == 0 { 0.0 } else { 1.0 - mean * mean })
+                }
+                _ => ResultValue::Sample(samples.ok_or("sample results need shots > 0")?),
+            }
+        }
+        ResultType::Amplitude { states } => {
+            if shots > 0 {
+                return Err("amplitude results are only available with shots = 0".into());
+            }
+            let amplitudes = states
+                .iter()
+                .map(|s| {
+                    let ours: String = s.chars().rev().collect();
+                    state.amplitude(&ours).map(|a| (s.clone(), a))
+                })
+                .collect::<Result<_, _>>()?;
+            ResultValue::Amplitude(amplitudes)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn test_jaqcd_bell_with_exact_results() {
+        let text = r#"{
+            "braketSchemaHeader": {"name": "braket.ir.jaqcd.program", "version": "1"},
+            "instructions": [{"type": "h", "target": 0},
+                             {"type": "cnot", "control": 0, "target": 1},
+                             {"type": "x", "target": 2}],
+            "results": [{"type": "probability", "targets": [0, 2]},
+                        {"type": "expectation", "observable": ["x", "x"], "targets": [0, 1]},
+                        {"type": "variance", "observable": ["z"], "targets": [0]},
+                        {"type": "amplitude", "states": ["111", "110"]}]}"#;
+        let task = BraketTask::from_json(&Json::parse(text).unwrap()).unwrap();
+        let values = task.evaluate(0, &mut Rng::seed_from_u64(0)).unwrap();
+        // P(q0 q2): "01" and "11", first target most significant
+        let ResultValue::Probability(p) = &values[0] else { panic!() };
+        assert!(p.iter().zip([0.0, 0.5, 0.0, 0.5]).all(|(a, b)| (a - b).abs() < 1e-12));
+        let ResultValue::Expectation(xx) = values[1] else { panic!() };
+        assert!((xx - 1.0).abs() < 1e-12);
+        let ResultValue::Variance(var) = values[2] else { panic!() };
+        assert!((var - 1.0).abs() < 1e-12);
+        let ResultValue::Amplitude(amplitudes) = &values[3] else { panic!() };
+        assert!((amplitudes[0].1.re - FRAC_1_SQRT_2).abs() < 1e-12);
+        assert!(amplitudes[1].1.norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_openqasm3_program_with_sampling() {
+        let source = "
+            OPENQASM 3.0;
+            qubit[2] q;
+            bit[2] b;
+            h q[0];
+            cnot q[0], q[1];
+            si q[1];
+            b = measure q;
+            #pragma braket result sample z(q[0]) @ z(q[1])
+            #pragma braket result expectation y(q[1])
+            #pragma braket result probability";
+        let program = Json::object([
+            ("braketSchemaHeader", Json::object([("name", "braket.ir.openqasm.program".into())])),
+            ("source", source.into()),
+        ]);
+        let task = BraketTask::from_json(&program).unwrap();
+        assert_eq!(task.circuit.len(), 3);
+        let values = task.evaluate(50, &mut Rng::seed_from_u64(3)).unwrap();
+        let ResultValue::Sample(samples) = &values[0] else { panic!() };
+        assert!(samples.len() == 50 && samples.iter().all(|&s| s == 1.0));
+        let ResultValue::Probability(p) = &values[2] else { panic!() };
+        assert!((p[0] + p[3] - 1.0).abs() < 1e-12);
+        assert!(task.evaluate(0, &mut Rng::seed_from_u64(3)).is_err());
+    }
+
+    #[test]
+    fn test_repeated_observable_targets_are_rejected() {
+        let text = r#"{
+            "braketSchemaHeader": {"name": "braket.ir.jaqcd.program", "version": "1"},
+            "instructions": [{"type": "h", "target": 0}],
+            "results": [{"type": "expectation", "observable": ["z", "x"], "targets": [0, 0]}]}"#;
+        let error = BraketTask::from_json(&Json::parse(text).unwrap()).unwrap_err();
+        assert!(error.contains("repeats target 0"), "{}", error);
+        let source = "qubit[1] q; h q[0];\n#pragma braket result expectation z(q[0]) @ x(q[0])";
+        let program = Json::object([
+            ("braketSchemaHeader", Json::object([("name", "braket.ir.openqasm.program".into())])),
+            ("source", source.into()),
+        ]);
+        assert!(BraketTask::from_json(&program).unwrap_err().contains("repeats target 0"));
+        let text = r#"{
+            "braketSchemaHeader": {"name": "braket.ir.jaqcd.program", "version": "1"},
+            "instructions": [{"type": "h", "target": 0}],
+            "results": [{"type": "probability", "targets": [0, 0]}]}"#;
+        let error = BraketTask::from_json(&Json::parse(text).unwrap()).unwrap_err();
+        assert!(error.contains("repeats target 0"), "{}", error);
+    }
+
+    #[test]
+    fn test_wide_programs_are_rejected() {
+        let targets = (0..65).map(|q| q.to_string()).collect::<Vec<_>>().join(", ");
+        let text = format!(
+            r#"{{"braketSchemaHeader": {{"name": "braket.ir.jaqcd.program", "version": "1"}},
+            "instructions": [], "results": [{{"type": "probability", "targets": [{}]}}]}}"#,
+            targets
+        );
+        assert!(BraketTask::from_json(&Json::parse(&text).unwrap()).is_err());
+        let program = Json::object([
+            ("braketSchemaHeader", Json::object([("name", "braket.ir.openqasm.program".into())])),
+            ("source", "qubit[40] q; h q[0];".into()),
+        ]);
+        assert!(BraketTask::from_json(&program).is_err());
+        let task = BraketTask { circuit: Circuit::new(MAX_BRAKET_QUBITS + 1), results: Vec::new() };
+        assert!(task.evaluate(0, &mut Rng::seed_from_u64(1)).is_err());
+    }
+}
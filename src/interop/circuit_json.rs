@@ -0,0 +1,227 @@
+use num_complex::Complex64;
+use super::json::Json;
+use crate::simulator::circuit::{is_identifier, Circuit, MarkerKind};
+use crate::simulator::gates::Gate;
+
+fn marker_position(marker: &Json) -> Option<usize> {
+    marker.get("position").and_then(Json::as_usize)
+}
+
+fn add_marker(circuit: &mut Circuit, m: usize, marker: &Json) -> Result<(), String> {
+    if let Some(text) = marker.get("annotation").and_then(Json::as_str) {
+        circuit.annotate(text);
+        return Ok(());
+    }
+    let qubits: Vec<usize> = marker
+        .get("barrier")
+        .and_then(Json::as_array)
+        .and_then(|q| q.iter().map(Json::as_usize).collect())
+        .ok_or_else(|| format!("marker {}: needs 'barrier' qubits or an 'annotation'", m))?;
+    let n = circuit.num_qubits();
+    let invalid = |(i, q): (usize, &usize)| *q >= n || qubits[..i].contains(q);
+    if qubits.is_empty() || qubits.iter().enumerate().any(invalid) {
+        return Err(format!("marker {}: barrier qubits out of range or repeated", m));
+    }
+    circuit.barrier(&qubits);
+    Ok(())
+}
+
+impl Circuit {
+    /// `{"num_qubits": n, "instructions": [{"gate", "qubits", "params"?,
+    /// "matrix"?}], "qubit_names"?: {name: index}, "markers"?: [{"position",
+    /// "barrier" | "annotation"}]}`; controlled unitaries carry their 2×2
+    /// target matrix as four `[re, im]` pairs in row-major order
+    pub fn to_json(&self) -> Json {
+        let instructions = self
+            .instructions()
+            .iter()
+            .map(|inst| {
+                let qubits = inst.qubits.iter().map(|&q| Json::from(q)).collect();
+                let mut entries =
+                    vec![("gate", inst.gate.name().into()), ("qubits", Json::Array(qubits))];
+                match inst.gate {
+                    Gate::Rx(a) | Gate::Ry(a) | Gate::Rz(a) | Gate::Phase(a) => {
+                        entries.push(("params", Json::Array(vec![a.into()])));
+                    }
+                    Gate::Mcu(_, ref m) => {
+                        let pairs = m
+                            .matrix()
+                            .as_slice()
+                            .iter()
+                            .map(|z| Json::Array(vec![z.re.into(), z.im.into()]))
+                            .collect();
+                        entries.push(("matrix", Json::Array(pairs)));
+                    }
+                    _ => {}
+                }
+                Json::object(entries)
+            })
+            .collect();
+        let mut fields = vec![
+            ("num_qubits", Json::from(self.num_qubits())),
+            ("instructions", Json::Array(instructions)),
+        ];
+        if self.has_qubit_names() {
+            let names = (0..self.num_qubits())
+                .filter_map(|q| self.qubit_name(q).map(|name| (name, Json::from(q))));
+            fields.push(("qubit_names", Json::object(names)));
+        }
+        if !self.markers().is_empty() {
+            let markers = self
+                .markers()
+                .iter()
+                .map(|marker| {
+                    let kind = match &marker.kind {
+                        MarkerKind::Barrier(qubits) => {
+                            let qubits = qubits.iter().map(|&q| Json::from(q)).collect();
+                            ("barrier", Json::Array(qubits))
+                        }
+                        MarkerKind::Annotation(text) => ("annotation", text.as_str().into()),
+                    };
+                    Json::object([("position", Json::from(marker.position)), kind])
+                })
+                .collect();
+            fields.push(("markers", Json::Array(markers)));
+        }
+        Json::object(fields)
+    }
+
+    /// inverse of `to_json`
+    pub fn from_json(value: &Json) -> Result<Circuit, String> {
+        let num_qubits = value
+            .get("num_qubits")
+            .and_then(Json::as_usize)
+            .ok_or("missing integer field 'num_qubits'")?;
+        let instructions = value
+            .get("instructions")
+            .and_then(Json::as_array)
+            .ok_or("missing array field 'instructions'")?;
+        let mut markers = value
+            .get("markers")
+            .map(|m| m.as_array().ok_or("'markers' must be an array"))
+            .transpose()?
+            .unwrap_or(&[])
+            .iter()
+            .enumerate()
+            .peekable();
+        let mut circuit = Circuit::new(num_qubits);
+        for (k, inst) in instructions.iter().enumerate() {
+            while let Some((m, marker)) = markers.next_if(|(_, m)| marker_position(m) == Some(k)) {
+                add_marker(&mut circuit, m, marker)?;
+            }
+            let context = |what: &str| format!("instruction {}: {}", k, what);
+            let name = inst.get("gate").and_then(Json::as_str).ok_or_else(|| context("no gate"))?;
+            let qubits: Vec<usize> = inst
+                .get("qubits")
+                .and_then(Json::as_array)
+                .and_then(|q| q.iter().map(Json::as_usize).collect())
+                .ok_or_else(|| context("qubits must be an array of indices"))?;
+            let param = || {
+                inst.get("params")
+                    .and_then(Json::as_array)
+                    .and_then(|p| p.first())
+                    .and_then(Json::as_f64)
+                    .ok_or_else(|| context("missing angle in 'params'"))
+            };
+            let controls = qubits.len().saturating_sub(1);
+            let gate = match name {
+                "id" => Gate::I,
+                "x" => Gate::X,
+                "y" => Gate::Y,
+                "z" => Gate::Z,
+                "h" => Gate::H,
+                "s" => Gate::S,
+                "sdg" => Gate::Sdg,
+                "t" => Gate::T,
+                "tdg" => Gate::Tdg,
+                "rx" => Gate::Rx(param()?),
+                "ry" => Gate::Ry(param()?),
+                "rz" => Gate::Rz(param()?),
+                "p" => Gate::Phase(param()?),
+                "cx" => Gate::Cx,
+                "cz" => Gate::Cz,
+                "swap" => Gate::Swap,
+                "mcx" => Gate::Mcx(controls),
+                "mcz" => Gate::Mcz(controls),
+                "mcu" => {
+                    let entries: Vec<Complex64> = inst
+                        .get("matrix")
+                        .and_then(Json::as_array)
+                        .filter(|m| m.len() == 4)
+                        .and_then(|m| {
+                            m.iter()
+                                .map(|z| match z.as_array()? {
+                                    [re, im] => Some(Complex64::new(re.as_f64()?, im.as_f64()?)),
+                                    _ => None,
+                                })
+                                .collect()
+                        })
+                        .ok_or_else(|| context("'matrix' must hold four [re, im] pairs"))?;
+                    Gate::Mcu(controls, [[entries[0], entries[1]], [entries[2], entries[3]]].into())
+                }
+                other => return Err(context(&format!("unknown gate '{}'", other))),
+            };
+            if qubits.len() != gate.num_qubits() {
+                return Err(context(&format!("{} takes {} qubits", name, gate.num_qubits())));
+            }
+            if qubits.iter().enumerate().any(|(i, q)| *q >= num_qubits || qubits[..i].contains(q)) {
+                return Err(context("qubit out of range or repeated"));
+            }
+            circuit.push(gate, &qubits);
+        }
+        for (m, marker) in markers {
+            if marker_position(marker) != Some(circuit.len()) {
+                return Err(format!("marker {}: position out of order or range", m));
+            }
+            add_marker(&mut circuit, m, marker)?;
+        }
+        if let Some(names) = value.get("qubit_names") {
+            let Json::Object(names) = names else {
+                return Err("'qubit_names' must be an object".to_string());
+            };
+            for (name, q) in names {
+                if !is_identifier(name) {
+                    return Err(format!("qubit name '{}' is not an identifier", name));
+                }
+                match q.as_usize() {
+                    Some(q) if q < num_qubits => circuit.name_qubit(q, name),
+                    _ => return Err(format!("qubit name '{}' needs an index in range", name)),
+                };
+            }
+        }
+        Ok(circuit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut circuit = Circuit::new(4);
+        circuit.h(0).rx(0.25, 1).cp(1.5, 0, 3).mcx(&[0, 1, 2], 3).swap(1, 2).sdg(3);
+        circuit.name_qubit(3, "ancilla").barrier(&[1, 2]).annotate("tail").x(2).annotate("end");
+        let text = circuit.to_json().to_string();
+        assert!(text.contains(r#"{"gate":"rx","params":[0.25],"qubits":[1]}"#));
+        let parsed = Circuit::from_json(&Json::parse(&text).unwrap()).unwrap();
+        assert_eq!(parsed, circuit);
+    }
+
+    #[test]
+    fn test_rejects_malformed_instructions() {
+        let parse = |text: &str| Circuit::from_json(&Json::parse(text).unwrap());
+        assert!(parse(r#"{"instructions": []}"#).unwrap_err().contains("num_qubits"));
+        let bad_gate = r#"{"num_qubits": 1, "instructions": [{"gate": "foo", "qubits": [0]}]}"#;
+        assert!(parse(bad_gate).unwrap_err().contains("unknown gate 'foo'"));
+        let bad_qubit = r#"{"num_qubits": 1, "instructions": [{"gate": "x", "qubits": [1]}]}"#;
+        assert!(parse(bad_qubit).unwrap_err().contains("out of range"));
+        let no_angle = r#"{"num_qubits": 1, "instructions": [{"gate": "rz", "qubits": [0]}]}"#;
+        assert!(parse(no_angle).unwrap_err().contains("params"));
+        let bad_name = r#"{"num_qubits": 1, "instructions": [], "qubit_names": {"1a": 0}}"#;
+        assert!(parse(bad_name).unwrap_err().contains("identifier"));
+        let late = r#"{"num_qubits": 1, "instructions": [],
+            "markers": [{"position": 2, "annotation": "a"}]}"#;
+        assert!(parse(late).unwrap_err().contains("position"));
+    }
+}
@@ -0,0 +1,387 @@
+use std::f64::consts::PI;
+use num_complex::Complex64;
+use super::json::Json;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::{
+    h_matrix, identity_matrix, phase_matrix, x_matrix, y_matrix, z_matrix, Gate, Matrix2,
+};
+use crate::simulator::operator::Operator;
+
+/// tolerance for recognising named gates from exponents and matrices
+const EPS: f64 = 1e-12;
+
+/// widest circuit imported; basis states are indexed by `usize`, so no
+/// wider circuit could be run
+pub const MAX_CIRQ_QUBITS: usize = usize::BITS as usize;
+
+fn typed<'a>(kind: &'a str, entries: impl IntoIterator<Item = (&'a str, Json)>) -> Json {
+    Json::object(std::iter::once(("cirq_type", Json::from(kind))).chain(entries))
+}
+
+fn pow_gate(kind: &str, exponent: f64) -> Json {
+    typed(kind, [("exponent", exponent.into()), ("global_shift", 0.0.into())])
+}
+
+fn complex_json(z: Complex64) -> Json {
+    typed("complex", [("real", z.re.into()), ("imag", z.im.into())])
+}
+
+fn matrix_gate(m: &Operator) -> Json {
+    let row = |i| Json::Array((0..m.dim()).map(|j| complex_json(m.get(i, j))).collect());
+    let rows = (0..m.dim()).map(row);
+    typed("MatrixGate", [("matrix", Json::Array(rows.collect())), ("qid_shape", vec_json(&[2]))])
+}
+
+fn vec_json(values: &[usize]) -> Json {
+    Json::Array(values.iter().map(|&v| v.into()).collect())
+}
+
+fn controlled(sub_gate: Json, controls: usize) -> Json {
+    let values = Json::Array(vec![vec_json(&[1]); controls]);
+    typed(
+        "ControlledGate",
+        [
+            ("sub_gate", sub_gate),
+            ("control_values", typed("ProductOfSums", [("data", values)])),
+            ("control_qid_shape", vec_json(&vec![2; controls])),
+        ],
+    )
+}
+
+/// Cirq gate object for a memqsim gate
+fn gate_json(gate: Gate) -> Json {
+    match gate {
+        Gate::I => typed("IdentityGate", [("qid_shape", vec_json(&[2]))]),
+        Gate::X | Gate::Mcx(0) => pow_gate("XPowGate", 1.0),
+        Gate::Y => pow_gate("YPowGate", 1.0),
+        Gate::Z | Gate::Mcz(0) => pow_gate("ZPowGate", 1.0),
+        Gate::H => pow_gate("HPowGate", 1.0),
+        Gate::S => pow_gate("ZPowGate", 0.5),
+        Gate::Sdg => pow_gate("ZPowGate", -0.5),
+        Gate::T => pow_gate("ZPowGate", 0.25),
+        Gate::Tdg => pow_gate("ZPowGate", -0.25),
+        Gate::Rx(theta) => typed("Rx", [("rads", theta.into())]),
+        Gate::Ry(theta) => typed("Ry", [("rads", theta.into())]),
+        Gate::Rz(theta) => typed("Rz", [("rads", theta.into())]),
+        Gate::Phase(lambda) => pow_gate("ZPowGate", lambda / PI),
+        Gate::Cx | Gate::Mcx(1) => pow_gate("CXPowGate", 1.0),
+        Gate::Cz | Gate::Mcz(1) => pow_gate("CZPowGate", 1.0),
+        Gate::Swap => pow_gate("SwapPowGate", 1.0),
+        Gate::Mcx(2) => pow_gate("CCXPowGate", 1.0),
+        Gate::Mcz(2) => pow_gate("CCZPowGate", 1.0),
+        Gate::Mcx(k) => controlled(pow_gate("XPowGate", 1.0), k),
+        Gate::Mcz(k) => controlled(pow_gate("ZPowGate", 1.0), k),
+        Gate::Mcu(0, m) => matrix_gate(&m),
+        Gate::Mcu(1, m) if m.get(0, 1).norm() < EPS && (m.get(0, 0) - 1.0).norm() < EPS => {
+            pow_gate("CZPowGate", m.get(1, 1).arg() / PI)
+        }
+        Gate::Mcu(k, m) => controlled(matrix_gate(&m), k),
+    }
+}
+
+/// P^t e^{iπts} for an involution P: (I + P)/2 + e^{iπt}(I − P)/2
+fn involution_power(p: Matrix2, exponent: f64, shift: f64) -> Matrix2 {
+    let phase = Complex64::from_polar(1.0, PI * exponent);
+    let global = Complex64::from_polar(1.0, PI * exponent * shift);
+    let id = identity_matrix();
+    let mut m = id;
+    for (r, row) in m.iter_mut().enumerate() {
+        for (c, z) in row.iter_mut().enumerate() {
+            *z = global * ((id[r][c] + p[r][c]) * 0.5 + phase * (id[r][c] - p[r][c]) * 0.5);
+        }
+    }
+    m
+}
+
+fn field(value: &Json, key: &str) -> Result<f64, String> {
+    value.get(key).and_then(Json::as_f64).ok_or_else(|| format!("missing number '{}'", key))
+}
+
+fn parse_matrix(value: &Json) -> Result<Matrix2, String> {
+    let entry = |z: &Json| -> Option<Complex64> {
+        match z {
+            Json::Number(re) => Some(Complex64::new(*re, 0.0)),
+            _ => Some(Complex64::new(z.get("real")?.as_f64()?, z.get("imag")?.as_f64()?)),
+        }
+    };
+    let rows = value.get("matrix").and_then(Json::as_array).filter(|rows| rows.len() == 2);
+    let parse_row = |row: &Json| -> Option<[Complex64; 2]> {
+        match row.as_array()? {
+            [a, b] => Some([entry(a)?, entry(b)?]),
+            _ => None,
+        }
+    };
+    rows.and_then(|rows| Some([parse_row(&rows[0])?, parse_row(&rows[1])?]))
+        .ok_or_else(|| "MatrixGate needs a 2×2 'matrix'".to_string())
+}
+
+/// memqsim gate for a Cirq gate object acting on `arity` qubits
+fn parse_gate(gate: &Json, arity: usize) -> Result<Gate, String> {
+    let kind = gate.get("cirq_type").and_then(Json::as_str).ok_or("gate without cirq_type")?;
+    let pow = || -> Result<(f64, f64), String> {
+        let shift = gate.get("global_shift").and_then(Json::as_f64).unwrap_or(0.0);
+        Ok((field(gate, "exponent")?, shift))
+    };
+    let whole = |t: f64| (t - 1.0).abs() < EPS;
+    Ok(match kind {
+        "IdentityGate" => Gate::I,
+        "Rx" => Gate::Rx(field(gate, "rads")?),
+        "Ry" => Gate::Ry(field(gate, "rads")?),
+        "Rz" => Gate::Rz(field(gate, "rads")?),
+        "XPowGate" | "YPowGate" | "HPowGate" => {
+            let (t, shift) = pow()?;
+            match kind {
+                _ if whole(t) && shift == 0.0 && kind == "XPowGate" => Gate::X,
+                _ if whole(t) && shift == 0.0 && kind == "YPowGate" => Gate::Y,
+                _ if whole(t) && shift == 0.0 => Gate::H,
+                "XPowGate" => Gate::Mcu(0, involution_power(x_matrix(), t, shift).into()),
+                "YPowGate" => Gate::Mcu(0, involution_power(y_matrix(), t, shift).into()),
+                _ => Gate::Mcu(0, involution_power(h_matrix(), t, shift).into()),
+            }
+        }
+        // the shift is a relative phase once the gate sits under a control
+        "ZPowGate" => match pow()? {
+            (t, shift) if shift != 0.0 => {
+                Gate::Mcu(0, involution_power(z_matrix(), t, shift).into())
+            }
+            (t, _) if whole(t) => Gate::Z,
+            (t, _) if (t - 0.5).abs() < EPS => Gate::S,
+            (t, _) if (t + 0.5).abs() < EPS => Gate::Sdg,
+            (t, _) if (t - 0.25).abs() < EPS => Gate::T,
+            (t, _) if (t + 0.25).abs() < EPS => Gate::Tdg,
+            (t, _) => Gate::Phase(PI * t),
+        },
+        // on a two- or three-qubit EigenGate the shift is a global phase and
+        // is dropped; inside the controlled block it would be a relative one
+        "CXPowGate" | "CNotPowGate" => match pow()? {
+            (t, _) if whole(t) => Gate::Cx,
+            (t, _) => Gate::Mcu(1, involution_power(x_matrix(), t, 0.0).into()),
+        },
+        "CZPowGate" => match pow()? {
+            (t, _) if whole(t) => Gate::Cz,
+            (t, _) => Gate::Mcu(1, phase_matrix(PI * t).into()),
+        },
+        "SwapPowGate" if whole(pow()?.0) => Gate::Swap,
+        "CCXPowGate" | "CCNotPowGate" if whole(pow()?.0) => Gate::Mcx(2),
+        "CCZPowGate" if whole(pow()?.0) => Gate::Mcz(2),
+        "MatrixGate" => Gate::Mcu(0, parse_matrix(gate)?.into()),
+        "ControlledGate" => {
+            let values = gate.get("control_values").ok_or("ControlledGate without controls")?;
+            let values = values.get("data").unwrap_or(values);
+            // one entry per control, either 1 or the singleton list [1]
+            let is_one = |c: &Json| match c.as_array() {
+                Some(list) => list == [Json::Number(1.0)],
+                None => c.as_f64() == Some(1.0),
+            };
+            let all_ones = values.as_array().is_some_and(|v| v.iter().all(is_one));
+            if !all_ones {
+                return Err("only controls on |1⟩ are supported".into());
+            }
+            let controls = values.as_array().map_or(0, <[Json]>::len);
+            match parse_gate(gate.get("sub_gate").ok_or("ControlledGate without sub_gate")?, 1)? {
+                Gate::X => Gate::Mcx(controls),
+                Gate::Z => Gate::Mcz(controls),
+                sub => match sub.matrix() {
+                    Some(m) => Gate::Mcu(controls, m),
+                    None => return Err("controlled multi-qubit gates are not supported".into()),
+                },
+            }
+        }
+        other => return Err(format!("unsupported Cirq gate '{}'", other)),
+    })
+    .and_then(|g: Gate| {
+        if g.num_qubits() == arity {
+            Ok(g)
+        } else {
+            Err(format!("{} acts on {} qubits, got {}", kind, g.num_qubits(), arity))
+        }
+    })
+}
+
+impl Circuit {
+    /// `cirq.to_json` form over `LineQubit`s, gates packed into moments as
+    /// early as their qubits allow
+    pub fn to_cirq_json(&self) -> Json {
+        let mut moments: Vec<Vec<Json>> = Vec::new();
+        let mut free = vec![0; self.num_qubits()];
+        for inst in self.instructions() {
+            let layer = inst.qubits.iter().map(|&q| free[q]).max().unwrap_or(0);
+            inst.qubits.iter().for_each(|&q| free[q] = layer + 1);
+            if layer == moments.len() {
+                moments.push(Vec::new());
+            }
+            let qubits =
+                inst.qubits.iter().map(|&q| typed("LineQubit", [("x", q.into())])).collect();
+            moments[layer].push(typed(
+                "GateOperation",
+                [("gate", gate_json(inst.gate.clone())), ("qubits", Json::Array(qubits))],
+            ));
+        }
+        let moments = moments
+            .into_iter()
+            .map(|ops| typed("Moment", [("operations", Json::Array(ops))]))
+            .collect();
+        typed("Circuit", [("moments", Json::Array(moments))])
+    }
+
+    /// read a `cirq.to_json` circuit on `LineQubit`s or `GridQubit`s
+    ///
+    /// Line qubit x becomes qubit x; grid qubits are numbered in row-major
+    /// order of the ones used. Measurements are skipped. Circuits wider than
+    /// `MAX_CIRQ_QUBITS` are errors.
+    pub fn from_cirq_json(value: &Json) -> Result<Circuit, String> {
+        let moments = value
+            .get("moments")
+            .and_then(Json::as_array)
+            .ok_or("expected a Circuit with 'moments'")?;
+        let mut operations = Vec::new();
+        for moment in moments {
+            let ops = moment.get("operations").and_then(Json::as_array).ok_or("bad Moment")?;
+            for op in ops {
+                let gate = op.get("gate").ok_or("operation without gate")?;
+                if gate.get("cirq_type").and_then(Json::as_str) == Some("MeasurementGate") {
+                    continue;
+                }
+                let qubits = op.get("qubits").and_then(Json::as_array).ok_or("no qubits")?;
+                let keys: Vec<(usize, usize)> = qubits
+                    .iter()
+                    .map(|q| match q.get("cirq_type").and_then(Json::as_str) {
+                        Some("LineQubit") => Some((0, q.get("x")?.as_usize()?)),
+                        Some("GridQubit") => {
+                            Some((q.get("row")?.as_usize()?, q.get("col")?.as_usize()?))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Option<_>>()
+                    .ok_or("qubits must be non-negative LineQubits or GridQubits")?;
+                let kinds: Vec<&str> =
+                    qubits.iter().filter_map(|q| q.get("cirq_type")?.as_str()).collect();
+                operations.push((parse_gate(gate, keys.len())?, keys, kinds));
+            }
+        }
+        let grid = operations.iter().any(|(_, _, kinds)| kinds.contains(&"GridQubit"));
+        if grid && operations.iter().any(|(_, _, kinds)| kinds.contains(&"LineQubit")) {
+            return Err("cannot mix LineQubits and GridQubits".into());
+        }
+        let mut used: Vec<(usize, usize)> =
+            operations.iter().flat_map(|(_, keys, _)| keys.iter().copied()).collect();
+        used.sort_unstable();
+        used.dedup();
+        let index = |key: &(usize, usize)| {
+            if grid { used.binary_search(key).unwrap() } else { key.1 }
+        };
+        let num_qubits = match used.last() {
+            _ if grid => used.len(),
+            None => 0,
+            Some(&(_, x)) => x.checked_add(1).ok_or(format!("LineQubit {} is out of range", x))?,
+        };
+        if num_qubits > MAX_CIRQ_QUBITS {
+            return Err(format!("circuits are limited to {} qubits", MAX_CIRQ_QUBITS));
+        }
+        let mut circuit = Circuit::new(num_qubits);
+        for (gate, keys, _) in &operations {
+            let qubits: Vec<usize> = keys.iter().map(index).collect();
+            if (1..qubits.len()).any(|i| qubits[..i].contains(&qubits[i])) {
+                return Err("operation repeats a qubit".into());
+            }
+            circuit.push(gate.clone(), &qubits);
+        }
+        Ok(circuit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::rz_matrix;
+
+    #[test]
+    fn test_round_trip_preserves_unitary_and_moments() {
+        let mut circuit = Circuit::new(4);
+        circuit.h(0).t(1).sdg(2).rx(0.4, 3).cx(0, 1).cp(0.9, 2, 3).mcx(&[0, 1, 2], 3);
+        circuit.mcz(&[3, 1], 0).swap(0, 2).phase(0.3, 1);
+        circuit.push(Gate::Mcu(2, h_matrix().into()), &[0, 1, 3]);
+        let json = circuit.to_cirq_json();
+        let moments = json.get("moments").and_then(Json::as_array).unwrap();
+        assert_eq!(moments[0].get("operations").and_then(Json::as_array).unwrap().len(), 4);
+        let parsed = Circuit::from_cirq_json(&Json::parse(&json.to_string()).unwrap()).unwrap();
+        crate::assert_unitary_eq!(parsed.to_unitary(), circuit.to_unitary());
+        assert_eq!(parsed.instructions()[1].gate, Gate::T);
+    }
+
+    #[test]
+    fn test_import_cirq_output() {
+        // cirq.to_json(cirq.Circuit(cirq.X(q0) ** 0.5, cirq.CZ(q0, q1) ** 0.5,
+        //     cirq.measure(q0, q1))) on GridQubits (3, 4) and (3, 5), abridged
+        let text = r#"{"cirq_type": "Circuit", "moments": [
+            {"cirq_type": "Moment", "operations": [{"cirq_type": "GateOperation",
+              "gate": {"cirq_type": "XPowGate", "exponent": 0.5, "global_shift": 0.0},
+              "qubits": [{"cirq_type": "GridQubit", "row": 3, "col": 4}]}]},
+            {"cirq_type": "Moment", "operations": [{"cirq_type": "GateOperation",
+              "gate": {"cirq_type": "CZPowGate", "exponent": 0.5, "global_shift": 0.0},
+              "qubits": [{"cirq_type": "GridQubit", "row": 3, "col": 4},
+                         {"cirq_type": "GridQubit", "row": 3, "col": 5}]}]},
+            {"cirq_type": "Moment", "operations": [{"cirq_type": "GateOperation",
+              "gate": {"cirq_type": "MeasurementGate", "num_qubits": 2, "key": "m"},
+              "qubits": [{"cirq_type": "GridQubit", "row": 3, "col": 4},
+                         {"cirq_type": "GridQubit", "row": 3, "col": 5}]}]}]}"#;
+        let circuit = Circuit::from_cirq_json(&Json::parse(text).unwrap()).unwrap();
+        assert_eq!((circuit.num_qubits(), circuit.len()), (2, 2));
+        let mut expected = Circuit::new(2);
+        expected.rx(PI / 2.0, 0).push(Gate::Mcu(1, phase_matrix(PI / 2.0).into()), &[0, 1]);
+        crate::assert_unitary_eq!(circuit.to_unitary(), expected.to_unitary());
+        let bad = r#"{"moments": [{"operations": [{"gate": {"cirq_type": "FSimGate"},
+            "qubits": [{"cirq_type": "LineQubit", "x": 0}]}]}]}"#;
+        let error = Circuit::from_cirq_json(&Json::parse(bad).unwrap()).unwrap_err();
+        assert!(error.contains("unsupported Cirq gate 'FSimGate'"));
+        let line = |x: &str| {
+            let text = format!(
+                r#"{{"moments": [{{"operations": [{{"gate": {{"cirq_type": "XPowGate",
+                    "exponent": 1.0}}, "qubits": [{{"cirq_type": "LineQubit", "x": {}}}]}}]}}]}}"#,
+                x
+            );
+            Circuit::from_cirq_json(&Json::parse(&text).unwrap())
+        };
+        assert!(line("1e30").unwrap_err().contains("out of range"));
+        let wide = line(&MAX_CIRQ_QUBITS.to_string()).unwrap_err();
+        assert!(wide.contains(&format!("limited to {} qubits", MAX_CIRQ_QUBITS)), "{}", wide);
+        assert_eq!(line(&(MAX_CIRQ_QUBITS - 1).to_string()).unwrap().num_qubits(), MAX_CIRQ_QUBITS);
+    }
+
+    #[test]
+    fn test_controlled_shifted_z_power_round_trips() {
+        // cirq.ControlledGate(cirq.rz(pi / 2)) spelled as a shifted ZPowGate
+        let sub = typed("ZPowGate", [("exponent", 0.5.into()), ("global_shift", (-0.5).into())]);
+        let qubits = (0..2usize).map(|x| typed("LineQubit", [("x", x.into())])).collect();
+        let operation =
+            typed("GateOperation", [("gate", controlled(sub, 1)), ("qubits", Json::Array(qubits))]);
+        let moment = typed("Moment", [("operations", Json::Array(vec![operation]))]);
+        let json = typed("Circuit", [("moments", Json::Array(vec![moment]))]);
+        let circuit = Circuit::from_cirq_json(&json).unwrap();
+        let mut expected = Circuit::new(2);
+        expected.push(Gate::Mcu(1, rz_matrix(PI / 2.0).into()), &[0, 1]);
+        assert!(circuit.equivalent_to(&expected, 1e-9));
+        let exported = Circuit::from_cirq_json(&circuit.to_cirq_json()).unwrap();
+        assert!(exported.equivalent_to(&expected, 1e-9));
+        // the controlled S it used to import as differs by a relative phase
+        let mut s = Circuit::new(2);
+        s.push(Gate::Mcu(1, phase_matrix(PI / 2.0).into()), &[0, 1]);
+        assert!(!circuit.equivalent_to(&s, 1e-9));
+    }
+
+    #[test]
+    fn test_shift_on_controlled_pow_gates_is_global() {
+        let import = |kind: &str, shift: f64| {
+            let gate = typed(kind, [("exponent", 0.5.into()), ("global_shift", shift.into())]);
+            let qubits = (0..2usize).map(|x| typed("LineQubit", [("x", x.into())])).collect();
+            let operation =
+                typed("GateOperation", [("gate", gate), ("qubits", Json::Array(qubits))]);
+            let moment = typed("Moment", [("operations", Json::Array(vec![operation]))]);
+            let json = typed("Circuit", [("moments", Json::Array(vec![moment]))]);
+            Circuit::from_cirq_json(&json).unwrap()
+        };
+        for kind in ["CXPowGate", "CZPowGate"] {
+            assert!(import(kind, -0.5).equivalent_to(&import(kind, 0.0), 1e-9), "{}", kind);
+        }
+    }
+}
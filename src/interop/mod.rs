@@ -0,0 +1,13 @@
+pub mod json;
+pub mod qasm;
+pub mod circuit_json;
+pub mod qiskit;
+pub mod cirq;
+pub mod quil;
+pub mod braket;
+
+pub use braket::{BraketTask, Observable, ResultType, ResultValue, MAX_BRAKET_QUBITS};
+pub use cirq::MAX_CIRQ_QUBITS;
+pub use json::Json;
+pub use quil::{Declaration, QuilProgram, MAX_QUIL_MEMORY, MAX_QUIL_QUBITS};
+pub use qiskit::{qiskit_result, QiskitExperiment};
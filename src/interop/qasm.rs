@@ -0,0 +1,529 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::f64::consts::{FRAC_PI_2, PI};
+use num_complex::Complex64;
+use crate::simulator::circuit::{Circuit, MarkerKind};
+use crate::simulator::gates::{phase_matrix, Gate, Matrix2};
+use crate::synthesis::euler::decompose_zyz;
+
+/// OpenQASM 2 U(θ, φ, λ) = Rz(φ) Ry(θ) Rz(λ) with phase e^{i(φ+λ)/2}
+fn u3_matrix(theta: f64, phi: f64, lambda: f64) -> Matrix2 {
+    let (c, s) = ((theta / 2.0).cos(), (theta / 2.0).sin());
+    [
+        [Complex64::new(c, 0.0), -Complex64::from_polar(s, lambda)],
+        [Complex64::from_polar(s, phi), Complex64::from_polar(c, phi + lambda)],
+    ]
+}
+
+/// deepest nesting of parentheses and signs in an expression
+const MAX_DEPTH: usize = 128;
+
+/// recursive-descent evaluator for gate parameter expressions
+pub(super) struct Expr<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    /// unary operands currently being parsed; every recursion passes one
+    depth: usize,
+}
+
+impl Expr<'_> {
+    pub(super) fn eval(text: &str) -> Result<f64, String> {
+        let mut expr = Expr { chars: text.chars().peekable(), depth: 0 };
+        let value = expr.sum()?;
+        expr.skip();
+        match expr.chars.next() {
+            None => Ok(value),
+            Some(c) => Err(format!("unexpected '{}' in expression '{}'", c, text)),
+        }
+    }
+
+    fn skip(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip();
+        self.chars.next_if_eq(&c).is_some()
+    }
+
+    fn sum(&mut self) -> Result<f64, String> {
+        let mut value = self.product()?;
+        loop {
+            if self.eat('+') {
+                value += self.product()?;
+            } else if self.eat('-') {
+                value -= self.product()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<f64, String> {
+        let mut value = self.power()?;
+        loop {
+            if self.eat('*') {
+                value *= self.power()?;
+            } else if self.eat('/') {
+                value /= self.power()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// `^` is right-associative; its operands are folded from the right
+    /// rather than recursed into, so a long chain cannot exhaust the stack
+    fn power(&mut self) -> Result<f64, String> {
+        let mut operands = vec![self.unary()?];
+        while self.eat('^') {
+            operands.push(self.unary()?);
+        }
+        let exponent = operands.pop().expect("power has an operand");
+        Ok(operands.into_iter().rev().fold(exponent, |exponent, base| base.powf(exponent)))
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        if self.depth == MAX_DEPTH {
+            return Err("expression nested too deeply".into());
+        }
+        self.depth += 1;
+        let value = self.signed();
+        self.depth -= 1;
+        value
+    }
+
+    fn signed(&mut self) -> Result<f64, String> {
+        if self.eat('-') {
+            return Ok(-self.unary()?);
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<f64, String> {
+        self.skip();
+        if self.eat('(') {
+            let value = self.sum()?;
+            return if self.eat(')') { Ok(value) } else { Err("missing ')'".into()) };
+        }
+        let mut token = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric() || *c == '.' || *c == '_') {
+            token.push(c);
+            // exponent sign of a literal such as 1e-3
+            let numeric = token.starts_with(|d: char| d.is_ascii_digit() || d == '.');
+            if (c == 'e' || c == 'E') && numeric {
+                if let Some(sign) = self.chars.next_if(|s| *s == '-' || *s == '+') {
+                    token.push(sign);
+                }
+            }
+        }
+        let function: Option<fn(f64) -> f64> = match token.as_str() {
+            "pi" => return Ok(PI),
+            "sin" => Some(f64::sin),
+            "cos" => Some(f64::cos),
+            "tan" => Some(f64::tan),
+            "exp" => Some(f64::exp),
+            "ln" => Some(f64::ln),
+            "sqrt" => Some(f64::sqrt),
+            _ => None,
+        };
+        match function {
+            Some(f) if self.eat('(') => {
+                let value = self.sum()?;
+                if !self.eat(')') {
+                    return Err("missing ')'".into());
+                }
+                Ok(f(value))
+            }
+            _ => token.parse().map_err(|_| format!("invalid parameter '{}'", token)),
+        }
+    }
+}
+
+/// `name[index]` or a bare register name
+fn parse_operand(text: &str) -> Result<(&str, Option<usize>), String> {
+    let text = text.trim();
+    match text.split_once('[') {
+        None => Ok((text, None)),
+        Some((name, rest)) => {
+            let index = rest
+                .strip_suffix(']')
+                .and_then(|i| i.trim().parse().ok())
+                .ok_or_else(|| format!("invalid operand '{}'", text))?;
+            Ok((name.trim(), Some(index)))
+        }
+    }
+}
+
+/// split "name(params) args" into its three parts
+pub(super) fn split_application(statement: &str) -> Result<(&str, Vec<&str>, &str), String> {
+    let name_end =
+        statement.find(|c: char| c == '(' || c.is_whitespace()).unwrap_or(statement.len());
+    let (name, rest) = statement.split_at(name_end);
+    let rest = rest.trim_start();
+    if let Some(inner) = rest.strip_prefix('(') {
+        let mut depth = 1;
+        let close = inner
+            .char_indices()
+            .find(|&(_, c)| {
+                depth += match c {
+                    '(' => 1,
+                    ')' => -1,
+                    _ => 0,
+                };
+                depth == 0
+            })
+            .map(|(i, _)| i)
+            .ok_or_else(|| format!("unbalanced parentheses in '{}'", statement))?;
+        let params = inner[..close].split(',').map(str::trim).collect();
+        return Ok((name, params, inner[close + 1..].trim()));
+    }
+    Ok((name, Vec::new(), rest))
+}
+
+/// gate and its parameter count for a qelib1 name
+fn qelib_gate(name: &str, params: &[f64]) -> Result<Gate, String> {
+    let arity = match name {
+        "id" | "x" | "y" | "z" | "h" | "s" | "sdg" | "t" | "tdg" => 0,
+        "cx" | "CX" | "cz" | "swap" | "ccx" => 0,
+        "rx" | "ry" | "rz" | "p" | "u1" | "cp" | "cu1" => 1,
+        "u2" => 2,
+        "u3" | "u" | "U" | "cu3" => 3,
+        _ => return Err(format!("unsupported gate '{}'", name)),
+    };
+    if params.len() != arity {
+        return Err(format!("gate '{}' takes {} parameters, got {}", name, arity, params.len()));
+    }
+    Ok(match name {
+        "id" => Gate::I,
+        "x" => Gate::X,
+        "y" => Gate::Y,
+        "z" => Gate::Z,
+        "h" => Gate::H,
+        "s" => Gate::S,
+        "sdg" => Gate::Sdg,
+        "t" => Gate::T,
+        "tdg" => Gate::Tdg,
+        "rx" => Gate::Rx(params[0]),
+        "ry" => Gate::Ry(params[0]),
+        "rz" => Gate::Rz(params[0]),
+        "p" | "u1" => Gate::Phase(params[0]),
+        "cx" | "CX" => Gate::Cx,
+        "cz" => Gate::Cz,
+        "swap" => Gate::Swap,
+        "ccx" => Gate::Mcx(2),
+        "cp" | "cu1" => Gate::Mcu(1, phase_matrix(params[0]).into()),
+        "u2" => Gate::Mcu(0, u3_matrix(FRAC_PI_2, params[0], params[1]).into()),
+        "cu3" => Gate::Mcu(1, u3_matrix(params[0], params[1], params[2]).into()),
+        _ => Gate::Mcu(0, u3_matrix(params[0], params[1], params[2]).into()),
+    })
+}
+
+impl Circuit {
+    /// parse an OpenQASM 2 program using qelib1 gates
+    ///
+    /// Quantum registers are laid out in declaration order; a gate on whole
+    /// registers is broadcast over their indices. Barriers are kept and
+    /// comments skipped. Measurements are checked against the classical
+    /// registers but only allowed once their qubits see no further gates, and
+    /// resets only before their qubits are used, so the circuit stays the
+    /// unitary the program runs.
+    pub fn from_qasm(text: &str) -> Result<Circuit, String> {
+        Circuit::from_qasm_with_limit(text, usize::MAX)
+    }
+
+    /// [`Circuit::from_qasm`] that fails as soon as the declared registers
+    /// add up to more than `max_qubits`, before anything is allocated for them
+    pub fn from_qasm_with_limit(text: &str, max_qubits: usize) -> Result<Circuit, String> {
+        let source: Vec<&str> =
+            text.lines().map(|line| line.split("//").next().unwrap_or("")).collect();
+        let source = source.join("\n");
+        let mut registers: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        let mut cregs: BTreeMap<String, usize> = BTreeMap::new();
+        let mut num_qubits: usize = 0;
+        let mut operations = Vec::new();
+        for statement in source.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            let keyword = statement.split_whitespace().next().unwrap_or("");
+            match keyword {
+                "OPENQASM" | "include" => continue,
+                "qreg" => {
+                    let (name, size) = parse_operand(&statement[4..])?;
+                    let size = size.ok_or_else(|| format!("qreg '{}' needs a size", name))?;
+                    if registers.insert(name.to_string(), (num_qubits, size)).is_some() {
+                        return Err(format!("qreg '{}' declared twice", name));
+                    }
+                    num_qubits = num_qubits
+                        .checked_add(size)
+                        .filter(|&n| n <= max_qubits)
+                        .ok_or_else(|| format!("registers exceed {} qubits", max_qubits))?;
+                }
+                "creg" => {
+                    let (name, size) = parse_operand(&statement[4..])?;
+                    let size = size.ok_or_else(|| format!("creg '{}' needs a size", name))?;
+                    if cregs.insert(name.to_string(), size).is_some() {
+                        return Err(format!("creg '{}' declared twice", name));
+                    }
+                }
+                _ => operations.push(statement),
+            }
+        }
+        let qubits_of = |arg: &str| -> Result<Vec<usize>, String> {
+            let (register, index) = parse_operand(arg)?;
+            let &(offset, size) =
+                registers.get(register).ok_or_else(|| format!("unknown qreg '{}'", register))?;
+            match index {
+                Some(i) if i < size => Ok(vec![offset + i]),
+                Some(i) => Err(format!("index {} out of range for '{}'", i, register)),
+                None => Ok((offset..offset + size).collect()),
+            }
+        };
+        let mut circuit = Circuit::new(num_qubits);
+        let (mut used, mut measured) = (BTreeSet::<usize>::new(), BTreeSet::new());
+        for statement in operations {
+            let (name, params, args) = split_application(statement)?;
+            if name == "measure" {
+                let (source, target) = args
+                    .split_once("->")
+                    .ok_or_else(|| format!("'{}' needs a '->' target", statement))?;
+                let qubits = qubits_of(source)?;
+                let (creg, index) = parse_operand(target)?;
+                let &size = cregs.get(creg).ok_or_else(|| format!("unknown creg '{}'", creg))?;
+                let bits = match index {
+                    Some(i) if i < size => 1,
+                    Some(i) => return Err(format!("index {} out of range for '{}'", i, creg)),
+                    None => size,
+                };
+                if bits != qubits.len() {
+                    return Err(format!("register sizes differ in '{}'", statement));
+                }
+                used.extend(&qubits);
+                measured.extend(qubits);
+                continue;
+            }
+            if name == "reset" {
+                if qubits_of(args)?.iter().any(|q| used.contains(q)) {
+                    return Err(format!("'{}': reset is only supported before any gate", statement));
+                }
+                continue;
+            }
+            let params: Vec<f64> = params.iter().map(|p| Expr::eval(p)).collect::<Result<_, _>>()?;
+            let operands: Vec<Vec<usize>> =
+                args.split(',').map(qubits_of).collect::<Result<_, _>>()?;
+            if name == "barrier" {
+                let mut qubits: Vec<usize> = Vec::new();
+                for q in operands.concat() {
+                    if !qubits.contains(&q) {
+                        qubits.push(q);
+                    }
+                }
+                circuit.barrier(&qubits);
+                continue;
+            }
+            let gate = qelib_gate(name, &params)?;
+            if operands.len() != gate.num_qubits() {
+                return Err(format!("'{}' expects {} qubits", statement, gate.num_qubits()));
+            }
+            let width = operands.iter().map(Vec::len).max().unwrap_or(1);
+            if operands.iter().any(|o| o.len() != 1 && o.len() != width) {
+                return Err(format!("register sizes differ in '{}'", statement));
+            }
+            for k in 0..width {
+                let qubits: Vec<usize> =
+                    operands.iter().map(|o| if o.len() == 1 { o[0] } else { o[k] }).collect();
+                if (1..qubits.len()).any(|i| qubits[..i].contains(&qubits[i])) {
+                    return Err(format!("repeated qubit in '{}'", statement));
+                }
+                if qubits.iter().any(|q| measured.contains(q)) {
+                    return Err(format!("'{}' acts on a measured qubit", statement));
+                }
+                used.extend(&qubits);
+                circuit.push(gate.clone(), &qubits);
+            }
+        }
+        Ok(circuit)
+    }
+
+    /// OpenQASM 2 text over one register `q`, or over one single-qubit
+    /// register per qubit named by its label when any qubit is named; global
+    /// phases of arbitrary single-qubit unitaries are dropped
+    pub fn to_qasm(&self) -> Result<String, String> {
+        let mut out = String::from("OPENQASM 2.0;\ninclude \"qelib1.inc\";\n");
+        let operands: Vec<String> = if self.has_qubit_names() {
+            let labels = self.qubit_labels();
+            for (k, label) in labels.iter().enumerate() {
+                if labels[..k].contains(label) {
+                    return Err(format!("qubit name '{}' clashes with a default label", label));
+                }
+                out.push_str(&format!("qreg {}[1];\n", label));
+            }
+            labels.iter().map(|label| format!("{}[0]", label)).collect()
+        } else {
+            out.push_str(&format!("qreg q[{}];\n", self.num_qubits()));
+            (0..self.num_qubits()).map(|i| format!("q[{}]", i)).collect()
+        };
+        let mut markers = self.markers().iter().peekable();
+        let mut write_markers = |out: &mut String, position: usize| {
+            while let Some(marker) = markers.next_if(|m| m.position == position) {
+                match &marker.kind {
+                    MarkerKind::Barrier(qubits) => {
+                        let q: Vec<&str> = qubits.iter().map(|&i| operands[i].as_str()).collect();
+                        out.push_str(&format!("barrier {};\n", q.join(",")));
+                    }
+                    MarkerKind::Annotation(text) => {
+                        out.push_str(&format!("// {}\n", text.replace('\n', " ")));
+                    }
+                }
+            }
+        };
+        for (k, inst) in self.instructions().iter().enumerate() {
+            write_markers(&mut out, k);
+            let q: Vec<&str> = inst.qubits.iter().map(|&i| operands[i].as_str()).collect();
+            let line = match inst.gate {
+                Gate::Rx(a) | Gate::Ry(a) | Gate::Rz(a) | Gate::Phase(a) => {
+                    format!("{}({}) {};", inst.gate.name(), a, q[0])
+                }
+                Gate::Mcx(0) => format!("x {};", q[0]),
+                Gate::Mcx(1) => format!("cx {},{};", q[0], q[1]),
+                Gate::Mcx(2) => format!("ccx {},{},{};", q[0], q[1], q[2]),
+                Gate::Mcz(0) => format!("z {};", q[0]),
+                Gate::Mcz(1) => format!("cz {},{};", q[0], q[1]),
+                Gate::Mcz(2) => format!("h {2};\nccx {0},{1},{2};\nh {2};", q[0], q[1], q[2]),
+                Gate::Mcu(0, ref m) => {
+                    let e = decompose_zyz(m);
+                    format!("u3({},{},{}) {};", e.theta, e.phi, e.lambda, q[0])
+                }
+                Gate::Mcu(1, ref m)
+                    if m.get(0, 1).norm() < 1e-12 && (m.get(0, 0) - 1.0).norm() < 1e-12 =>
+                {
+                    format!("cp({}) {},{};", m.get(1, 1).arg(), q[0], q[1])
+                }
+                Gate::Mcu(1, ref m) => {
+                    // U = e^{iα} U3(θ, φ, λ); the phase becomes a control phase
+                    let e = decompose_zyz(m);
+                    let alpha = e.global_phase - (e.phi + e.lambda) / 2.0;
+                    format!(
+                        "cu3({},{},{}) {},{};\np({}) {};",
+                        e.theta, e.phi, e.lambda, q[0], q[1], alpha, q[0]
+                    )
+                }
+                Gate::Mcx(_) | Gate::Mcz(_) | Gate::Mcu(..) => {
+                    return Err(format!(
+                        "{} with {} controls has no qelib1 equivalent",
+                        inst.gate.name(),
+                        inst.qubits.len() - 1
+                    ))
+                }
+                ref gate => format!("{} {};", gate.name(), q.join(",")),
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
+        write_markers(&mut out, self.len());
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_program_with_registers_and_expressions() {
+        let text = r#"
+            OPENQASM 2.0;
+            include "qelib1.inc";
+            qreg a[2];
+            qreg b[1];
+            creg c[3];
+            h a;            // broadcast over a[0], a[1]
+            cx a[0], b[0];
+            rz(-pi/4 + 2*0.5e-1) a[1];
+            u2(0, pi) b[0];
+            barrier a, b;
+            measure a[0] -> c[0];
+        "#;
+        let circuit = Circuit::from_qasm(text).unwrap();
+        assert_eq!(circuit.num_qubits(), 3);
+        let gates: Vec<&str> = circuit.instructions().iter().map(|i| i.gate.name()).collect();
+        assert_eq!(gates, ["h", "h", "cx", "rz", "mcu"]);
+        assert_eq!(circuit.instructions()[2].qubits, [0, 2]);
+        assert_eq!(circuit.instructions()[3].gate, Gate::Rz(-PI / 4.0 + 0.1));
+        // u2(0, π) is a Hadamard
+        let mut h = Circuit::new(3);
+        h.h(2);
+        let mut u2 = Circuit::new(3);
+        u2.push(circuit.instructions()[4].gate.clone(), &[2]);
+        crate::assert_unitary_eq!(u2.to_unitary(), h.to_unitary());
+    }
+
+    #[test]
+    fn test_export_round_trip_preserves_unitary() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).t(1).ry(0.3, 2).cp(0.7, 0, 2).mcx(&[0, 1], 2).mcz(&[2, 0], 1).swap(0, 1);
+        circuit.push(Gate::Mcu(1, u3_matrix(0.4, 1.1, -0.2).into()), &[1, 0]);
+        circuit.push(Gate::Mcu(0, u3_matrix(1.3, 0.2, 0.9).into()), &[2]);
+        let text = circuit.to_qasm().unwrap();
+        let parsed = Circuit::from_qasm(&text).unwrap();
+        crate::assert_unitary_eq!(parsed.to_unitary(), circuit.to_unitary());
+        assert!(Circuit::new(4).mcx(&[0, 1, 2], 3).to_qasm().is_err());
+    }
+
+    #[test]
+    fn test_export_named_qubits_as_registers() {
+        let mut circuit = Circuit::new(3);
+        circuit.name_qubit(0, "alice").name_qubit(2, "bob").h(0).cx(0, 2).x(1);
+        let text = circuit.to_qasm().unwrap();
+        assert!(text.contains("qreg alice[1];\nqreg q1[1];\nqreg bob[1];\n"));
+        assert!(text.contains("cx alice[0],bob[0];"));
+        let parsed = Circuit::from_qasm(&text).unwrap();
+        assert_eq!(parsed.instructions(), circuit.instructions());
+        circuit.name_qubit(2, "q1");
+        assert!(circuit.to_qasm().unwrap_err().contains("clashes"));
+    }
+
+    #[test]
+    fn test_barriers_round_trip_and_annotations_become_comments() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).barrier(&[]).annotate("bell pair").cx(0, 1).barrier(&[1]);
+        let text = circuit.to_qasm().unwrap();
+        assert!(text.contains("h q[0];\nbarrier q[0],q[1];\n// bell pair\ncx q[0],q[1];\n"));
+        assert!(text.ends_with("barrier q[1];\n"));
+        let parsed = Circuit::from_qasm(&text).unwrap();
+        let barriers: Vec<_> = circuit
+            .markers()
+            .iter()
+            .filter(|m| matches!(m.kind, MarkerKind::Barrier(_)))
+            .cloned()
+            .collect();
+        assert_eq!(parsed.markers(), barriers);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Circuit::from_qasm("qreg q[1]; foo q[0];").unwrap_err().contains("unsupported"));
+        assert!(Circuit::from_qasm("qreg q[1]; x r[0];").unwrap_err().contains("unknown qreg"));
+        assert!(Circuit::from_qasm("qreg q[1]; x q[1];").unwrap_err().contains("out of range"));
+        assert!(Circuit::from_qasm("qreg q[2]; rx(pi q[0];").is_err());
+        assert!(Circuit::from_qasm("qreg q[2]; cx q[0], q[0];").unwrap_err().contains("repeated"));
+        let measured = Circuit::from_qasm("qreg q[1]; creg c[1]; measure q[0] -> c[0]; h q[0];");
+        assert!(measured.unwrap_err().contains("measured qubit"));
+        let reset = Circuit::from_qasm("qreg q[1]; reset q[0]; x q[0]; reset q[0];");
+        assert!(reset.unwrap_err().contains("reset is only supported"));
+        let bounds = Circuit::from_qasm("qreg q[2]; creg c[1]; measure q[1] -> c[3];");
+        assert!(bounds.unwrap_err().contains("out of range for 'c'"));
+        assert!(Circuit::from_qasm("qreg q[2]; creg c[2]; measure q -> c; barrier q;").is_ok());
+        let deep = format!("qreg q[1]; rx({}1) q[0];", "-".repeat(200_000));
+        assert!(Circuit::from_qasm(&deep).unwrap_err().contains("too deeply"));
+        let chain = format!("qreg q[1]; rx({}1) q[0];", "1^".repeat(200_000));
+        assert!(Circuit::from_qasm(&chain).is_ok());
+        assert_eq!(Expr::eval("2^3^2").unwrap(), 512.0);
+        let huge = "qreg a[18446744073709551615]; qreg b[2];";
+        assert!(Circuit::from_qasm(huge).unwrap_err().contains("exceed"));
+        let wide = Circuit::from_qasm_with_limit("qreg q[100000000000]; x q[0];", 28);
+        assert!(wide.unwrap_err().contains("exceed 28 qubits"));
+        assert!(Circuit::from_qasm_with_limit("qreg q[2]; qreg r[1];", 3).is_ok());
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+use super::json::Json;
+use crate::simulator::report::RunResult;
+
+/// one executed circuit in a Qiskit result
+#[derive(Debug, Clone)]
+pub struct QiskitExperiment<'a> {
+    pub name: &'a str,
+    pub result: &'a RunResult,
+    pub seed: Option<u64>,
+}
+
+/// the per-experiment dict of Qiskit's `ExperimentResult`; counts use hex
+/// keys and one classical bit per qubit, register "c" measuring "q"
+fn experiment_json(experiment: &QiskitExperiment) -> Json {
+    let result = experiment.result;
+    let n = result.report.num_qubits;
+    let counts: BTreeMap<String, Json> =
+        result.counts.iter().map(|(&outcome, &k)| (format!("0x{:x}", outcome), k.into())).collect();
+    let labels = |register: &str| {
+        Json::Array((0..n).map(|i| Json::Array(vec![register.into(), i.into()])).collect())
+    };
+    let header = Json::object([
+        ("name", Json::from(experiment.name)),
+        ("n_qubits", n.into()),
+        ("memory_slots", n.into()),
+        ("qubit_labels", labels("q")),
+        ("clbit_labels", labels("c")),
+        ("qreg_sizes", Json::Array(vec![Json::Array(vec!["q".into(), n.into()])])),
+        ("creg_sizes", Json::Array(vec![Json::Array(vec!["c".into(), n.into()])])),
+    ]);
+    let mut entries = vec![
+        ("shots", Json::from(result.report.shots)),
+        ("success", true.into()),
+        ("status", "DONE".into()),
+        ("data", Json::object([("counts", Json::Object(counts))])),
+        ("header", header),
+        ("time_taken", result.report.wall_time.as_secs_f64().into()),
+    ];
+    if let Some(seed) = experiment.seed {
+        entries.push(("seed_simulator", (seed as f64).into()));
+    }
+    Json::object(entries)
+}
+
+/// `qiskit.result.Result.to_dict()`-shaped JSON, loadable with
+/// `Result.from_dict` so Qiskit's `get_counts` and plotting work unchanged
+pub fn qiskit_result(job_id: &str, experiments: &[QiskitExperiment]) -> Json {
+    let backend = experiments.first().map_or("statevector", |e| e.result.report.backend);
+    let time_taken: f64 =
+        experiments.iter().map(|e| e.result.report.wall_time.as_secs_f64()).sum();
+    Json::object([
+        ("backend_name", Json::from(format!("memqsim_{}", backend))),
+        ("backend_version", env!("CARGO_PKG_VERSION").into()),
+        ("qobj_id", job_id.into()),
+        ("job_id", job_id.into()),
+        ("success", true.into()),
+        ("status", "COMPLETED".into()),
+        ("date", Json::Null),
+        ("header", Json::object::<&str>([])),
+        ("time_taken", time_taken.into()),
+        ("results", Json::Array(experiments.iter().map(experiment_json).collect())),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::circuit::Circuit;
+    use crate::simulator::report::run;
+    use crate::simulator::rng::Rng;
+
+    #[test]
+    fn test_result_follows_qiskit_schema() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1);
+        let result = run(&circuit, 100, &mut Rng::seed_from_u64(4));
+        let experiment = QiskitExperiment { name: "bell", result: &result, seed: Some(4) };
+        let json = Json::parse(&qiskit_result("job-1", &[experiment]).to_string()).unwrap();
+        assert_eq!(json.get("backend_name").and_then(Json::as_str), Some("memqsim_statevector"));
+        assert_eq!(json.get("success"), Some(&Json::Bool(true)));
+        let first = &json.get("results").and_then(Json::as_array).unwrap()[0];
+        assert_eq!(first.get("shots").and_then(Json::as_usize), Some(100));
+        let counts = first.get("data").unwrap().get("counts").unwrap();
+        let (zero, three) = (counts.get("0x0").unwrap(), counts.get("0x3").unwrap());
+        assert_eq!(zero.as_usize().unwrap() + three.as_usize().unwrap(), 100);
+        let header = first.get("header").unwrap();
+        assert_eq!(header.get("memory_slots").and_then(Json::as_usize), Some(2));
+        assert_eq!(first.get("seed_simulator").and_then(Json::as_usize), Some(4));
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use super::qasm::{split_application, Expr};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::{phase_matrix, Gate};
+
+/// widest program parsed; `QuilProgram::memory` reads qubits out of a `usize`
+/// outcome, so higher indices could never be measured
+pub const MAX_QUIL_QUBITS: usize = usize::BITS as usize;
+
+/// largest DECLARE size, in elements, that `QuilProgram::memory` allocates
+pub const MAX_QUIL_MEMORY: usize = 1 << 16;
+
+/// classical memory region from a DECLARE
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Declaration {
+    pub name: String,
+    /// BIT, OCTET, INTEGER or REAL
+    pub kind: String,
+    pub size: usize,
+}
+
+/// unitary part of a Quil program plus its classical readout
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuilProgram {
+    pub circuit: Circuit,
+    pub declarations: Vec<Declaration>,
+    /// (qubit, memory region, offset) per MEASURE with a target
+    pub measurements: Vec<(usize, String, usize)>,
+}
+
+/// standard-gate name to gate and parameter count
+fn quil_gate(name: &str, params: &[f64]) -> Result<Gate, String> {
+    let arity = match name {
+        "RX" | "RY" | "RZ" | "PHASE" | "CPHASE" => 1,
+        _ => 0,
+    };
+    if params.len() != arity {
+        return Err(format!("{} takes {} parameters, got {}", name, arity, params.len()));
+    }
+    Ok(match name {
+        "I" => Gate::I,
+        "X" => Gate::X,
+        "Y" => Gate::Y,
+        "Z" => Gate::Z,
+        "H" => Gate::H,
+        "S" => Gate::S,
+        "T" => Gate::T,
+        "RX" => Gate::Rx(params[0]),
+        "RY" => Gate::Ry(params[0]),
+        "RZ" => Gate::Rz(params[0]),
+        "PHASE" => Gate::Phase(params[0]),
+        "CNOT" => Gate::Cx,
+        "CZ" => Gate::Cz,
+        "SWAP" => Gate::Swap,
+        "CCNOT" => Gate::Mcx(2),
+        "CPHASE" => Gate::Mcu(1, phase_matrix(params[0]).into()),
+        _ => return Err(format!("unsupported Quil gate '{}'", name)),
+    })
+}
+
+/// `name[offset]` or a bare name meaning offset 0
+fn parse_address(text: &str) -> Result<(String, usize), String> {
+    match text.split_once('[') {
+        None => Ok((text.to_string(), 0)),
+        Some((name, rest)) => rest
+            .strip_suffix(']')
+            .and_then(|i| i.parse().ok())
+            .map(|i| (name.to_string(), i))
+            .ok_or_else(|| format!("invalid memory reference '{}'", text)),
+    }
+}
+
+/// `gate` with one more control in front
+fn add_control(gate: Gate) -> Result<Gate, String> {
+    Ok(match gate {
+        Gate::X | Gate::Mcx(0) => Gate::Cx,
+        Gate::Z | Gate::Mcz(0) => Gate::Cz,
+        Gate::Cx => Gate::Mcx(2),
+        Gate::Cz => Gate::Mcz(2),
+        Gate::Mcx(k) => Gate::Mcx(k + 1),
+        Gate::Mcz(k) => Gate::Mcz(k + 1),
+        Gate::Mcu(k, m) => Gate::Mcu(k + 1, m),
+        Gate::Swap => return Err("CONTROLLED SWAP is not supported".into()),
+        single => Gate::Mcu(1, single.matrix().expect("single-qubit gate")),
+    })
+}
+
+impl QuilProgram {
+    /// parse a Quil program: standard gates with DAGGER/CONTROLLED modifiers,
+    /// DECLARE and terminal MEASURE
+    ///
+    /// Qubit indices are used as given, so the circuit is as wide as the
+    /// largest index plus one, up to `MAX_QUIL_QUBITS`. Memory names must be
+    /// unique and regions at most `MAX_QUIL_MEMORY` long. PRAGMA, RESET at
+    /// the start, HALT and comments are ignored; RESET after a gate or
+    /// measurement and a gate on an already measured qubit are errors.
+    pub fn parse(text: &str) -> Result<QuilProgram, String> {
+        let mut declarations: Vec<Declaration> = Vec::new();
+        let mut gates: Vec<(Gate, Vec<usize>)> = Vec::new();
+        let mut measurements = Vec::new();
+        let mut measured: Vec<usize> = Vec::new();
+        let statements = text
+            .lines()
+            .flat_map(|line| line.split('#').next().unwrap_or("").split(';'))
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        for (number, statement) in statements.enumerate() {
+            let context =
+                |message: String| format!("statement {} '{}': {}", number + 1, statement, message);
+            let words: Vec<&str> = statement.split_whitespace().collect();
+            match words[0] {
+                "PRAGMA" | "HALT" => {}
+                // every qubit starts in |0⟩, so only a leading RESET is a no-op
+                "RESET" if gates.is_empty() && measured.is_empty() => {}
+                "RESET" => return Err(context("RESET is only supported at the start".into())),
+                "DECLARE" => {
+                    let (name, kind) = match words[..] {
+                        [_, name, kind] => (name, kind),
+                        _ => return Err(context("expected DECLARE name TYPE[size]".into())),
+                    };
+                    let (kind_name, size) = parse_address(kind).map_err(context)?;
+                    let size = if kind.contains('[') { size } else { 1 };
+                    if size > MAX_QUIL_MEMORY {
+                        let limit = format!("regions are limited to {} elements", MAX_QUIL_MEMORY);
+                        return Err(context(limit));
+                    }
+                    if declarations.iter().any(|d| d.name == name) {
+                        return Err(context(format!("memory '{}' is declared twice", name)));
+                    }
+                    declarations.push(Declaration { name: name.into(), kind: kind_name, size });
+                }
+                "MEASURE" => {
+                    let qubit: usize = words
+                        .get(1)
+                        .and_then(|q| q.parse().ok())
+                        .ok_or_else(|| context("expected a qubit index".into()))?;
+                    measured.push(qubit);
+                    if let Some(target) = words.get(2) {
+                        let (region, offset) = parse_address(target).map_err(context)?;
+                        let declared = declarations.iter().find(|d| d.name == region);
+                        let problem = match declared {
+                            Some(d) if offset < d.size => None,
+                            Some(_) => Some(format!("offset {} out of range", offset)),
+                            None => Some(format!("undeclared memory '{}'", region)),
+                        };
+                        if let Some(problem) = problem {
+                            return Err(context(problem));
+                        }
+                        measurements.push((qubit, region, offset));
+                    }
+                }
+                _ => {
+                    let mut rest = statement;
+                    let (mut dagger, mut controls) = (false, 0);
+                    loop {
+                        if let Some(r) = rest.strip_prefix("DAGGER ") {
+                            dagger = !dagger;
+                            rest = r.trim_start();
+                        } else if let Some(r) = rest.strip_prefix("CONTROLLED ") {
+                            controls += 1;
+                            rest = r.trim_start();
+                        } else {
+                            break;
+                        }
+                    }
+                    let (name, params, args) = split_application(rest).map_err(context)?;
+                    let params: Vec<f64> = params
+                        .iter()
+                        .map(|p| Expr::eval(p))
+                        .collect::<Result<_, _>>()
+                        .map_err(context)?;
+                    let mut gate = quil_gate(name, &params).map_err(context)?;
+                    if dagger {
+                        gate = gate.inverse();
+                    }
+                    for _ in 0..controls {
+                        gate = add_control(gate).map_err(context)?;
+                    }
+                    let qubits: Vec<usize> = args
+                        .split_whitespace()
+                        .map(|q| q.parse().map_err(|_| context(format!("bad qubit '{}'", q))))
+                        .collect::<Result<_, _>>()?;
+                    if qubits.len() != gate.num_qubits() {
+                        let expected = gate.num_qubits();
+                        return Err(context(format!("expected {} qubits", expected)));
+                    }
+                    if (1..qubits.len()).any(|i| qubits[..i].contains(&qubits[i])) {
+                        return Err(context("repeated qubit".into()));
+                    }
+                    if let Some(q) = qubits.iter().find(|q| measured.contains(q)) {
+                        return Err(context(format!("qubit {} was already measured", q)));
+                    }
+                    gates.push((gate, qubits));
+                }
+            }
+        }
+        let used = gates.iter().flat_map(|(_, qubits)| qubits.iter()).chain(&measured);
+        let width = match used.max() {
+            None => 0,
+            Some(&q) if q < MAX_QUIL_QUBITS => q + 1,
+            Some(&q) => {
+                return Err(format!(
+                    "qubit {} is out of range; programs are limited to {} qubits",
+                    q, MAX_QUIL_QUBITS
+                ))
+            }
+        };
+        let mut circuit = Circuit::new(width);
+        for (gate, qubits) in &gates {
+            circuit.push(gate.clone(), qubits);
+        }
+        Ok(QuilProgram { circuit, declarations, measurements })
+    }
+
+    /// classical memory after measuring basis state `outcome` of the circuit
+    ///
+    /// Measurements into memory that is not declared, as a hand-built program
+    /// may have, are skipped; qubits past the width of `outcome` read 0.
+    pub fn memory(&self, outcome: usize) -> BTreeMap<String, Vec<u8>> {
+        let mut memory: BTreeMap<String, Vec<u8>> =
+            self.declarations.iter().map(|d| (d.name.clone(), vec![0; d.size])).collect();
+        for (qubit, region, offset) in &self.measurements {
+            let bit = u32::try_from(*qubit).ok().and_then(|q| outcome.checked_shr(q)).unwrap_or(0);
+            if let Some(cell) = memory.get_mut(region).and_then(|bits| bits.get_mut(*offset)) {
+                *cell = (bit & 1) as u8;
+            }
+        }
+        memory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_parse_bell_program_and_readout() {
+        let text = "
+            # Bell pair on qubits 0 and 2
+            DECLARE ro BIT[2]
+            H 0
+            CNOT 0 2
+            RZ(pi/2) 2; DAGGER RZ(pi/2) 2
+            MEASURE 0 ro[1]
+            MEASURE 2 ro[0]
+        ";
+        let program = QuilProgram::parse(text).unwrap();
+        assert_eq!(program.circuit.num_qubits(), 3);
+        assert_eq!(program.circuit.instructions()[3].gate, Gate::Rz(-PI / 2.0));
+        let ro = Declaration { name: "ro".into(), kind: "BIT".into(), size: 2 };
+        assert_eq!(program.declarations, [ro]);
+        let memory = program.memory(0b101);
+        assert_eq!(memory["ro"], [1, 1]);
+        assert_eq!(program.memory(0b001)["ro"], [0, 1]);
+    }
+
+    #[test]
+    fn test_controlled_modifier_matches_builder() {
+        let text = "CONTROLLED CONTROLLED X 2 0 1\nCONTROLLED RY(0.3) 1 0";
+        let program = QuilProgram::parse(text).unwrap();
+        let mut expected = Circuit::new(3);
+        expected.mcx(&[2, 0], 1);
+        expected.push(Gate::Mcu(1, Gate::Ry(0.3).matrix().unwrap()), &[1, 0]);
+        crate::assert_unitary_eq!(program.circuit.to_unitary(), expected.to_unitary());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(QuilProgram::parse("FOO 0").unwrap_err().contains("unsupported Quil gate"));
+        assert!(QuilProgram::parse("MEASURE 0 ro[0]").unwrap_err().contains("undeclared"));
+        assert!(QuilProgram::parse("MEASURE 0\nX 0").unwrap_err().contains("already measured"));
+        assert!(QuilProgram::parse("CNOT 1 1").unwrap_err().contains("repeated"));
+        assert!(QuilProgram::parse("RX 0").unwrap_err().contains("1 parameters"));
+        assert!(QuilProgram::parse("RESET\nX 0").is_ok());
+        let reset = QuilProgram::parse("DECLARE ro BIT[1]; X 0; RESET; MEASURE 0 ro[0]");
+        assert!(reset.unwrap_err().contains("only supported at the start"));
+        let huge = QuilProgram::parse("X 18446744073709551615").unwrap_err();
+        assert!(huge.contains("out of range"), "{}", huge);
+        let wide = QuilProgram::parse("DECLARE ro BIT[1]; MEASURE 70 ro[0]").unwrap_err();
+        assert!(wide.contains(&format!("limited to {} qubits", MAX_QUIL_QUBITS)), "{}", wide);
+        let last = QuilProgram::parse(&format!("MEASURE {}", MAX_QUIL_QUBITS - 1)).unwrap();
+        assert_eq!(last.circuit.num_qubits(), MAX_QUIL_QUBITS);
+        let big = QuilProgram::parse("DECLARE ro BIT[999999999992]").unwrap_err();
+        assert!(big.contains("limited to 65536 elements"), "{}", big);
+        let twice = QuilProgram::parse("DECLARE ro BIT[4]; DECLARE ro BIT[1]; MEASURE 0 ro[3]");
+        assert!(twice.unwrap_err().contains("declared twice"));
+    }
+
+    #[test]
+    fn test_memory_of_hand_built_program() {
+        let mut program = QuilProgram::parse("DECLARE ro BIT[1]").unwrap();
+        program.measurements.push((70, "ro".into(), 0));
+        program.measurements.push((0, "ro".into(), 5));
+        program.measurements.push((0, "other".into(), 0));
+        assert_eq!(program.memory(usize::MAX)["ro"], [0]);
+    }
+}
@@ -0,0 +1,20 @@
+pub mod simulator;
+pub mod noise;
+pub mod characterization;
+pub mod algorithms;
+pub mod protocols;
+pub mod mbqc;
+pub mod ml;
+pub mod variational;
+pub mod chemistry;
+pub mod cv;
+pub mod visualization;
+pub mod trace;
+pub mod synthesis;
+pub mod optimize;
+pub mod transpile;
+pub mod zx;
+pub mod interop;
+pub mod pulse;
+#[cfg(feature = "server")]
+pub mod server;
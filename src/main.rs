@@ -1,9 +1,96 @@
-mod simulator;
-
-use simulator::*;
+use memqsim::optimize::{simplify_gates, IdentityDatabase};
+use memqsim::simulator::*;
+use memqsim::visualization::tui::{render_frame, Stepper};
 use std::f64::consts::PI;
+use std::io::{self, BufRead, Write};
+
+/// widest QASM program the TUI loads; every frame lists the whole state
+const MAX_TUI_QUBITS: usize = 16;
+
+/// interactive stepper over the OpenQASM 2 program at `path`, or over a
+/// GHZ-plus-rotation demo circuit when no path is given
+fn run_tui(path: Option<String>) -> io::Result<()> {
+    let circuit = match path {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path, e)))?;
+            Circuit::from_qasm_with_limit(&text, MAX_TUI_QUBITS).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path, e))
+            })?
+        }
+        None => {
+            let mut circuit = Circuit::new(3);
+            circuit.h(0).cx(0, 1).cx(1, 2).ry(PI / 3.0, 0).cz(0, 2).t(1);
+            circuit
+        }
+    };
+    let mut stepper = Stepper::new(circuit);
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        // clear the screen and home the cursor
+        print!("\x1b[2J\x1b[H{}> ", render_frame(&stepper));
+        io::stdout().flush()?;
+        let Some(line) = lines.next() else { break };
+        match line?.trim() {
+            "q" => break,
+            "b" => {
+                stepper.back();
+            }
+            "r" => stepper.reset(),
+            _ => {
+                stepper.step();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// layered rotation-and-entangler circuit on `n` qubits, printing progress
+/// to stderr while it runs
+fn run_progress(n: usize) {
+    let mut circuit = Circuit::new(n);
+    for layer in 0..20 {
+        for q in 0..n {
+            circuit.ry(0.1 * (layer + q) as f64, q);
+        }
+        for q in (layer % 2..n.saturating_sub(1)).step_by(2) {
+            circuit.cx(q, q + 1);
+        }
+    }
+    let mut rng = Rng::seed_from_u64(7);
+    let result = run_with_progress(&circuit, 1000, &mut rng, |progress| {
+        eprint!("\r{}\x1b[K", progress);
+    });
+    eprintln!();
+    println!("{}", result.report);
+}
 
 fn main() {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("tui") => {
+            if let Err(e) = run_tui(args.next()) {
+                eprintln!("tui: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        #[cfg(feature = "server")]
+        Some("serve") => {
+            let addr = args.next().unwrap_or_else(|| "127.0.0.1:8787".to_string());
+            println!("memqsim serving on http://{}", addr);
+            memqsim::server::serve(addr).expect("server failed");
+            return;
+        }
+        Some("progress") => {
+            let n = args.next().and_then(|a| a.parse().ok()).unwrap_or(20);
+            run_progress(n);
+            return;
+        }
+        _ => {}
+    }
+
     println!("═══ Demo 1: Basic Gates ═══\n");
     let mut qubit = SingleQubit::new();
     qubit.display_with_message("Initial state: |0⟩");
@@ -66,5 +153,23 @@ fn main() {
     println!("  → Apply H (reverse)");
 
     qubit.display_with_message("\n  Final state (should be |0⟩):");
+    let sequence = [Gate::H, Gate::X, Gate::Y, Gate::Y, Gate::X, Gate::H];
+    let simplified = simplify_gates(&sequence, &IdentityDatabase::default());
+    println!("\n  H X Y Y X H simplifies to {} gates", simplified.len());
 
+    // Demo 6: Bell state on a register
+    println!("\n\n═══ Demo 6: Bell State ═══\n");
+    let mut register = Register::new(2);
+    register.display_with_message("Initial: |00⟩");
+    register.apply_gate(0, Gate::H.operator()).expect("H is one-qubit");
+    register.apply_controlled_gate(&[0], 1, Gate::X.operator()).expect("X is one-qubit");
+    register.display_with_message("After H(0), CNOT(0 → 1):");
+    let dist = register.distribution();
+    let mut rng = Rng::seed_from_u64(2025);
+    let counts = dist.sample_counts(1000, &mut rng);
+    println!("\n1000 shots:");
+    for (outcome, count) in &counts {
+        println!("  |{}⟩: {}", dist.format_outcome(*outcome), count);
+    }
+    println!("Entropy: {:.3} bits", dist.entropy());
 }
@@ -0,0 +1,157 @@
+use std::collections::BTreeSet;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::{Gate, Matrix2};
+use crate::synthesis::euler::decompose_zxz2;
+use super::pattern::{Command, Pattern};
+
+/// X^x Z^z still owed on a wire, each as a parity domain of outcomes
+#[derive(Debug, Clone, Default)]
+struct Byproduct {
+    x: BTreeSet<usize>,
+    z: BTreeSet<usize>,
+}
+
+/// symmetric difference in place: outcomes appearing twice cancel
+fn toggle(set: &mut BTreeSet<usize>, domain: &BTreeSet<usize>) {
+    for &node in domain {
+        if !set.remove(&node) {
+            set.insert(node);
+        }
+    }
+}
+
+/// J(α) angles, in application order, whose product is `matrix` up to phase
+fn single_qubit_angles(matrix: &Matrix2) -> Vec<f64> {
+    if matrix[0][1].norm() < 1e-12 && matrix[1][0].norm() < 1e-12 {
+        // P(α) = J(0)·J(α)
+        return vec![(matrix[1][1] / matrix[0][0]).arg(), 0.0];
+    }
+    // Rz(φ)Rx(θ)Rz(λ) ∝ P(φ)·H·P(θ)·H·P(λ) = J(0)·J(φ)·J(θ)·J(λ)
+    let zxz = decompose_zxz2(matrix);
+    vec![zxz.lambda, zxz.theta, zxz.phi, 0.0]
+}
+
+struct Builder {
+    pattern: Pattern,
+    current: Vec<usize>,
+    byproducts: Vec<Byproduct>,
+    next: usize,
+}
+
+impl Builder {
+    /// teleport `wire` one node along through J(α), folding its pending
+    /// byproduct into the measurement's domains
+    fn j(&mut self, wire: usize, alpha: f64) {
+        let (a, b) = (self.current[wire], self.next);
+        self.next += 1;
+        let owed = std::mem::take(&mut self.byproducts[wire]);
+        self.pattern.commands.push(Command::Prepare(b));
+        self.pattern.commands.push(Command::Entangle(a, b));
+        self.pattern.commands.push(Command::Measure {
+            node: a,
+            angle: -alpha,
+            s_domain: owed.x.iter().copied().collect(),
+            t_domain: owed.z.iter().copied().collect(),
+        });
+        // X_a passes through CZ(a, b) as X_a·Z_b
+        self.byproducts[wire] = Byproduct { x: BTreeSet::from([a]), z: owed.x };
+        self.current[wire] = b;
+    }
+
+    fn cz(&mut self, first: usize, second: usize) {
+        let (a, b) = (self.current[first], self.current[second]);
+        self.pattern.commands.push(Command::Entangle(a, b));
+        let (xa, xb) = (self.byproducts[first].x.clone(), self.byproducts[second].x.clone());
+        toggle(&mut self.byproducts[second].z, &xa);
+        toggle(&mut self.byproducts[first].z, &xb);
+    }
+}
+
+impl Pattern {
+    /// one-way pattern equal to `circuit` up to global phase: single-qubit
+    /// gates become chains of J(α) = H·P(α) steps, CZ an entangling edge, and
+    /// byproducts are pushed into later measurement angles, leaving Pauli
+    /// corrections only on the outputs
+    pub fn from_circuit(circuit: &Circuit) -> Result<Pattern, String> {
+        let n = circuit.num_qubits();
+        let mut builder = Builder {
+            pattern: Pattern {
+                inputs: (0..n).collect(),
+                outputs: Vec::new(),
+                commands: Vec::new(),
+            },
+            current: (0..n).collect(),
+            byproducts: vec![Byproduct::default(); n],
+            next: n,
+        };
+        for inst in circuit.instructions() {
+            match (&inst.gate, inst.qubits.as_slice()) {
+                (Gate::I, _) => {}
+                (Gate::H, &[q]) => builder.j(q, 0.0),
+                (Gate::Cz, &[a, b]) => builder.cz(a, b),
+                (Gate::Cx, &[c, t]) => {
+                    builder.j(t, 0.0);
+                    builder.cz(c, t);
+                    builder.j(t, 0.0);
+                }
+                (gate, &[q]) => {
+                    let matrix = gate
+                        .matrix2()
+                        .ok_or_else(|| format!("{} has no single-qubit matrix", gate.name()))?;
+                    for alpha in single_qubit_angles(&matrix) {
+                        builder.j(q, alpha);
+                    }
+                }
+                (gate, _) => {
+                    return Err(format!(
+                        "{} has no one-way translation; decompose it first",
+                        gate.name()
+                    ))
+                }
+            }
+        }
+        for wire in 0..n {
+            let node = builder.current[wire];
+            let owed = std::mem::take(&mut builder.byproducts[wire]);
+            let commands = &mut builder.pattern.commands;
+            commands.push(Command::CorrectX { node, domain: owed.x.into_iter().collect() });
+            commands.push(Command::CorrectZ { node, domain: owed.z.into_iter().collect() });
+        }
+        builder.pattern.outputs = builder.current;
+        Ok(builder.pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::register::Register;
+    use crate::simulator::rng::Rng;
+
+    #[test]
+    fn test_pattern_matches_circuit_for_every_outcome_branch() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).t(0).cx(0, 1).ry(0.7, 2).cz(1, 2).s(1).rx(0.3, 0).cx(2, 0).y(1);
+        let pattern = Pattern::from_circuit(&circuit).unwrap();
+        let mut expected = Register::new(3);
+        expected.apply_circuit(&circuit);
+        for seed in 0..10 {
+            let mut rng = Rng::seed_from_u64(seed);
+            let run = pattern.run(&Register::new(3), &mut rng).unwrap();
+            crate::assert_state_eq!(run.state, expected);
+        }
+        // later angles adapt to earlier outcomes
+        assert!(pattern.commands.iter().any(|c| matches!(
+            c,
+            Command::Measure { s_domain, .. } if !s_domain.is_empty()
+        )));
+    }
+
+    #[test]
+    fn test_unsupported_gates_are_reported() {
+        let mut circuit = Circuit::new(3);
+        circuit.mcx(&[0, 1], 2);
+        let error = Pattern::from_circuit(&circuit).unwrap_err();
+        assert!(error.contains("mcx"), "{}", error);
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::BTreeSet;
+use std::f64::consts::FRAC_PI_2;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::pauli::Pauli;
+use crate::simulator::pauli_string::PauliString;
+use crate::simulator::register::Register;
+use crate::transpile::CouplingMap;
+use super::pattern::{Command, Pattern};
+
+/// simple undirected graph whose nodes are qubits of a graph state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph {
+    adjacency: Vec<BTreeSet<usize>>,
+}
+
+impl Graph {
+    /// `num_nodes` isolated nodes
+    pub fn new(num_nodes: usize) -> Self {
+        Self {
+            adjacency: vec![BTreeSet::new(); num_nodes],
+        }
+    }
+
+    /// nodes 0 up to the largest endpoint in `edges`
+    pub fn from_edges(edges: &[(usize, usize)]) -> Self {
+        let num_nodes = edges.iter().map(|&(a, b)| a.max(b) + 1).max().unwrap_or(0);
+        edges.iter().fold(Self::new(num_nodes), |mut graph, &(a, b)| {
+            graph.add_edge(a, b);
+            graph
+        })
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn add_edge(&mut self, a: usize, b: usize) -> &mut Self {
+        assert!(a != b && a < self.num_nodes() && b < self.num_nodes(), "invalid edge");
+        self.adjacency[a].insert(b);
+        self.adjacency[b].insert(a);
+        self
+    }
+
+    /// add the edge if absent, remove it if present
+    pub fn toggle_edge(&mut self, a: usize, b: usize) -> &mut Self {
+        if !self.adjacency[a].remove(&b) {
+            self.add_edge(a, b);
+        } else {
+            self.adjacency[b].remove(&a);
+        }
+        self
+    }
+
+    pub fn has_edge(&self, a: usize, b: usize) -> bool {
+        self.adjacency[a].contains(&b)
+    }
+
+    pub fn neighbours(&self, v: usize) -> &BTreeSet<usize> {
+        &self.adjacency[v]
+    }
+
+    /// each edge once, smaller endpoint first
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        (0..self.num_nodes())
+            .flat_map(|a| self.adjacency[a].range(a + 1..).map(move |&b| (a, b)))
+            .collect()
+    }
+
+    /// complement the subgraph induced on the neighbourhood of `v`
+    pub fn local_complement(&self, v: usize) -> Graph {
+        let mut graph = self.clone();
+        let around: Vec<usize> = self.adjacency[v].iter().copied().collect();
+        for (k, &a) in around.iter().enumerate() {
+            for &b in &around[k + 1..] {
+                graph.toggle_edge(a, b);
+            }
+        }
+        graph
+    }
+
+    /// edge pivot τ_u τ_v τ_u along the edge (u, v)
+    pub fn pivot(&self, u: usize, v: usize) -> Graph {
+        assert!(self.has_edge(u, v), "pivot needs an edge");
+        self.local_complement(u).local_complement(v).local_complement(u)
+    }
+
+    /// stabilizer generators K_v = X_v ∏ Z_w over the neighbours w of v
+    pub fn stabilizers(&self) -> Vec<PauliString> {
+        (0..self.num_nodes())
+            .map(|v| {
+                let mut terms = vec![(v, Pauli::X)];
+                terms.extend(self.adjacency[v].iter().map(|&w| (w, Pauli::Z)));
+                PauliString::from_terms(self.num_nodes(), &terms)
+            })
+            .collect()
+    }
+
+    /// H on every node, then a CZ per edge
+    pub fn circuit(&self) -> Circuit {
+        let mut circuit = Circuit::new(self.num_nodes());
+        for v in 0..self.num_nodes() {
+            circuit.h(v);
+        }
+        for (a, b) in self.edges() {
+            circuit.cz(a, b);
+        }
+        circuit
+    }
+
+    /// local Clifford √(−iX_v) ∏ √(iZ_w) over neighbours w, which maps the
+    /// graph state to that of `local_complement(v)` up to global phase
+    pub fn local_complement_circuit(&self, v: usize) -> Circuit {
+        let mut circuit = Circuit::new(self.num_nodes());
+        circuit.rx(FRAC_PI_2, v);
+        for &w in &self.adjacency[v] {
+            circuit.rz(-FRAC_PI_2, w);
+        }
+        circuit
+    }
+
+    /// pattern preparing the graph state: every node in |+⟩, a CZ per edge
+    pub fn pattern(&self) -> Pattern {
+        let mut commands: Vec<Command> = (0..self.num_nodes()).map(Command::Prepare).collect();
+        commands.extend(self.edges().into_iter().map(|(a, b)| Command::Entangle(a, b)));
+        Pattern {
+            inputs: Vec::new(),
+            outputs: (0..self.num_nodes()).collect(),
+            commands,
+        }
+    }
+
+    /// graph state with node k on qubit k
+    pub fn state(&self) -> Register {
+        let mut state = Register::new(self.num_nodes());
+        state.apply_circuit(&self.circuit());
+        state
+    }
+}
+
+/// graph state of `edges`, nodes numbered up to the largest endpoint
+pub fn graph_state(edges: &[(usize, usize)]) -> Register {
+    Graph::from_edges(edges).state()
+}
+
+/// `rows` × `cols` cluster state, node r·cols + c at row r, column c
+pub fn cluster_state(rows: usize, cols: usize) -> Register {
+    let mut graph = Graph::new(rows * cols);
+    for &(a, b) in CouplingMap::grid(rows, cols).edges() {
+        graph.add_edge(a, b);
+    }
+    graph.state()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::rng::Rng;
+
+    #[test]
+    fn test_cluster_state_is_stabilized_by_its_generators() {
+        let (rows, cols) = (2, 3);
+        let state = cluster_state(rows, cols);
+        let graph = Graph::from_edges(CouplingMap::grid(rows, cols).edges());
+        for stabilizer in graph.stabilizers() {
+            assert!((state.expectation(&stabilizer.terms()) - 1.0).abs() < 1e-12);
+        }
+        // the executed pattern prepares the same state
+        let mut rng = Rng::seed_from_u64(0);
+        let run = graph.pattern().run(&Register::new(0), &mut rng).unwrap();
+        crate::assert_state_eq!(run.state, state);
+    }
+
+    #[test]
+    fn test_local_complement_toggles_neighbourhood_edges() {
+        // star centred on 0 becomes a complete graph, and back again
+        let star = Graph::from_edges(&[(0, 1), (0, 2), (0, 3)]);
+        let complete = star.local_complement(0);
+        assert_eq!(complete.edges().len(), 6);
+        assert_eq!(complete.local_complement(0), star);
+        // leaves have a single neighbour, so nothing changes
+        assert_eq!(star.local_complement(1), star);
+        let path = Graph::from_edges(&[(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(path.pivot(1, 2).pivot(1, 2), path);
+    }
+
+    #[test]
+    fn test_local_clifford_realises_local_complementation() {
+        let graph = Graph::from_edges(&[(0, 1), (1, 2), (2, 3), (3, 0), (1, 4)]);
+        for v in 0..graph.num_nodes() {
+            let mut state = graph.state();
+            state.apply_circuit(&graph.local_complement_circuit(v));
+            crate::assert_state_eq!(state, graph.local_complement(v).state());
+        }
+    }
+}
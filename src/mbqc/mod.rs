@@ -0,0 +1,6 @@
+pub mod compile;
+pub mod graph;
+pub mod pattern;
+
+pub use graph::{cluster_state, graph_state, Graph};
+pub use pattern::{Command, Execution, Pattern};
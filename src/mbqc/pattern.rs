@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+use num_complex::Complex64;
+use crate::simulator::gates::{h_matrix, phase_matrix, x_matrix, z_matrix};
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+
+/// one step of a measurement pattern; nodes are named by index
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// fresh node in |+⟩
+    Prepare(usize),
+    /// CZ between two nodes
+    Entangle(usize, usize),
+    /// XY-plane measurement onto (|0⟩ ± e^{iθ}|1⟩)/√2, outcome 1 for −, with
+    /// θ = (−1)^s·angle + t·π where s and t are the outcome parities of the domains
+    Measure {
+        node: usize,
+        angle: f64,
+        s_domain: Vec<usize>,
+        t_domain: Vec<usize>,
+    },
+    /// X byproduct correction, applied when the domain parity is odd
+    CorrectX { node: usize, domain: Vec<usize> },
+    /// Z byproduct correction, applied when the domain parity is odd
+    CorrectZ { node: usize, domain: Vec<usize> },
+}
+
+/// sequence of commands turning the input nodes' state into the outputs'
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Pattern {
+    pub inputs: Vec<usize>,
+    pub outputs: Vec<usize>,
+    pub commands: Vec<Command>,
+}
+
+/// output state, qubit j on `outputs[j]`, and every measurement outcome
+#[derive(Debug, Clone)]
+pub struct Execution {
+    pub state: Register,
+    pub outcomes: BTreeMap<usize, bool>,
+}
+
+/// live register with a slot per unmeasured node
+struct Nodes {
+    register: Register,
+    slots: Vec<usize>,
+}
+
+impl Nodes {
+    fn slot(&self, node: usize) -> Result<usize, String> {
+        self.slots
+            .iter()
+            .position(|&n| n == node)
+            .ok_or_else(|| format!("node {} is not live", node))
+    }
+
+    fn add(&mut self, node: usize) -> Result<(), String> {
+        if self.slots.contains(&node) {
+            return Err(format!("node {} prepared twice", node));
+        }
+        let plus = Complex64::new(FRAC_1_SQRT_2, 0.0);
+        self.register = self.register.tensor(&Register::from_amplitudes(vec![plus, plus]));
+        self.slots.push(node);
+        Ok(())
+    }
+
+    /// project `slot` onto `outcome` and drop it from the register
+    fn remove(&mut self, slot: usize, outcome: bool) {
+        self.register.postselect(slot, outcome);
+        let low = (1 << slot) - 1;
+        let kept: Vec<Complex64> = (0..1usize << (self.slots.len() - 1))
+            .map(|k| {
+                let full = (k & low) | ((k & !low) << 1) | (usize::from(outcome) << slot);
+                self.register.amplitudes()[full]
+            })
+            .collect();
+        self.register = Register::from_amplitudes(kept);
+        self.slots.remove(slot);
+    }
+}
+
+fn parity(domain: &[usize], outcomes: &BTreeMap<usize, bool>) -> Result<bool, String> {
+    domain.iter().try_fold(false, |acc, node| match outcomes.get(node) {
+        Some(&s) => Ok(acc ^ s),
+        None => Err(format!("node {} is used in a domain before it is measured", node)),
+    })
+}
+
+impl Pattern {
+    /// run on `input`, whose qubit j feeds `inputs[j]`
+    pub fn run(&self, input: &Register, rng: &mut Rng) -> Result<Execution, String> {
+        if input.num_qubits() != self.inputs.len() {
+            return Err(format!(
+                "pattern has {} inputs, state has {} qubits",
+                self.inputs.len(),
+                input.num_qubits()
+            ));
+        }
+        let mut nodes = Nodes {
+            register: input.clone(),
+            slots: self.inputs.clone(),
+        };
+        let mut outcomes = BTreeMap::new();
+        for command in &self.commands {
+            match command {
+                Command::Prepare(node) => nodes.add(*node)?,
+                Command::Entangle(a, b) => {
+                    let (a, b) = (nodes.slot(*a)?, nodes.slot(*b)?);
+                    nodes.register.apply_controlled_matrix2(&[a], b, z_matrix());
+                }
+                Command::Measure { node, angle, s_domain, t_domain } => {
+                    let sign = if parity(s_domain, &outcomes)? { -1.0 } else { 1.0 };
+                    let shift = if parity(t_domain, &outcomes)? { PI } else { 0.0 };
+                    let slot = nodes.slot(*node)?;
+                    // H·P(−θ) maps |±_θ⟩ to |0⟩ and |1⟩
+                    nodes.register.apply_matrix2(slot, phase_matrix(-(sign * angle + shift)));
+                    nodes.register.apply_matrix2(slot, h_matrix());
+                    let outcome = rng.gen_bool(nodes.register.prob_one(slot));
+                    nodes.remove(slot, outcome);
+                    outcomes.insert(*node, outcome);
+                }
+                Command::CorrectX { node, domain } => {
+                    if parity(domain, &outcomes)? {
+                        nodes.register.apply_matrix2(nodes.slot(*node)?, x_matrix());
+                    }
+                }
+                Command::CorrectZ { node, domain } => {
+                    if parity(domain, &outcomes)? {
+                        nodes.register.apply_matrix2(nodes.slot(*node)?, z_matrix());
+                    }
+                }
+            }
+        }
+        if nodes.slots.len() != self.outputs.len() {
+            return Err(format!(
+                "{} nodes left unmeasured for {} outputs",
+                nodes.slots.len(),
+                self.outputs.len()
+            ));
+        }
+        let order: Vec<usize> =
+            self.outputs.iter().map(|&node| nodes.slot(node)).collect::<Result<_, _>>()?;
+        let amplitudes = nodes.register.amplitudes();
+        let state = (0..amplitudes.len())
+            .map(|k| {
+                let source = order
+                    .iter()
+                    .enumerate()
+                    .fold(0, |index, (j, &slot)| index | (((k >> j) & 1) << slot));
+                amplitudes[source]
+            })
+            .collect();
+        Ok(Execution {
+            state: Register::from_amplitudes(state),
+            outcomes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::circuit::Circuit;
+
+    /// J(α) = H·P(α) teleported from node 0 to node 1
+    fn j_pattern(alpha: f64) -> Pattern {
+        Pattern {
+            inputs: vec![0],
+            outputs: vec![1],
+            commands: vec![
+                Command::Prepare(1),
+                Command::Entangle(0, 1),
+                Command::Measure { node: 0, angle: -alpha, s_domain: vec![], t_domain: vec![] },
+                Command::CorrectX { node: 1, domain: vec![0] },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_single_step_applies_j_for_both_outcomes() {
+        let mut input = Register::new(1);
+        input.apply_circuit(Circuit::new(1).ry(0.9, 0).rz(0.4, 0));
+        let mut expected = input.clone();
+        expected.apply_circuit(Circuit::new(1).phase(0.7, 0).h(0));
+        let mut seen = [false; 2];
+        for seed in 0..20 {
+            let mut rng = Rng::seed_from_u64(seed);
+            let run = j_pattern(0.7).run(&input, &mut rng).unwrap();
+            seen[usize::from(run.outcomes[&0])] = true;
+            crate::assert_state_eq!(run.state, expected);
+        }
+        assert_eq!(seen, [true, true]);
+    }
+
+    #[test]
+    fn test_invalid_patterns_are_rejected() {
+        let mut rng = Rng::seed_from_u64(1);
+        let mut pattern = j_pattern(0.0);
+        pattern.commands.pop();
+        pattern.commands.insert(0, Command::CorrectZ { node: 0, domain: vec![5] });
+        assert!(pattern.run(&Register::new(1), &mut rng).is_err());
+        let unmeasured = Pattern { inputs: vec![0], outputs: vec![], commands: vec![] };
+        assert!(unmeasured.run(&Register::new(1), &mut rng).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use std::f64::consts::PI;
+use crate::simulator::circuit::Circuit;
+
+/// rotation used by angle encoding
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RotationAxis {
+    X,
+    Y,
+    Z,
+}
+
+/// which qubit pairs interact in an entangling layer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Entanglement {
+    /// (i, i+1) for i = 0..n-1
+    Linear,
+    /// linear plus (n-1, 0)
+    Circular,
+    /// every pair i < j
+    Full,
+}
+
+impl Entanglement {
+    pub fn pairs(&self, num_qubits: usize) -> Vec<(usize, usize)> {
+        match self {
+            Entanglement::Linear => (0..num_qubits.saturating_sub(1)).map(|i| (i, i + 1)).collect(),
+            Entanglement::Circular => {
+                let mut pairs = Entanglement::Linear.pairs(num_qubits);
+                if num_qubits > 2 {
+                    pairs.push((num_qubits - 1, 0));
+                }
+                pairs
+            }
+            Entanglement::Full => (0..num_qubits)
+                .flat_map(|i| (i + 1..num_qubits).map(move |j| (i, j)))
+                .collect(),
+        }
+    }
+}
+
+/// one qubit per feature, rotated by the feature value
+pub fn angle_encoding(x: &[f64], axis: RotationAxis) -> Circuit {
+    let mut circuit = Circuit::new(x.len());
+    for (q, &value) in x.iter().enumerate() {
+        match axis {
+            RotationAxis::X => circuit.rx(value, q),
+            RotationAxis::Y => circuit.ry(value, q),
+            RotationAxis::Z => circuit.h(q).rz(value, q),
+        };
+    }
+    circuit
+}
+
+/// second-order Pauli-Z evolution feature map (Havlíček et al., 2019)
+///
+/// Each repetition applies H on every qubit, P(2x_i) on each qubit and
+/// P(2(π − x_i)(π − x_j)) between CNOTs on every entangled pair.
+pub fn zz_feature_map(x: &[f64], reps: usize, entanglement: Entanglement) -> Circuit {
+    let n = x.len();
+    let mut circuit = Circuit::new(n);
+    for _ in 0..reps {
+        for q in 0..n {
+            circuit.h(q);
+        }
+        for (q, &value) in x.iter().enumerate() {
+            circuit.phase(2.0 * value, q);
+        }
+        for (i, j) in entanglement.pairs(n) {
+            let angle = 2.0 * (PI - x[i]) * (PI - x[j]);
+            circuit.cx(i, j).phase(angle, j).cx(i, j);
+        }
+    }
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entanglement_pairs() {
+        assert_eq!(Entanglement::Linear.pairs(3), vec![(0, 1), (1, 2)]);
+        assert_eq!(Entanglement::Circular.pairs(3), vec![(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(Entanglement::Full.pairs(3).len(), 3);
+    }
+
+    #[test]
+    fn test_feature_map_shapes() {
+        let x = [0.1, 0.2, 0.3];
+        assert_eq!(angle_encoding(&x, RotationAxis::Y).len(), 3);
+        // per rep: 3 H + 3 P + 2 pairs × 3 gates
+        assert_eq!(zz_feature_map(&x, 2, Entanglement::Linear).len(), 2 * 12);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::simulator::circuit::Circuit;
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+
+/// fidelity kernel K(x, y) = |⟨φ(x)|φ(y)⟩|² from exact statevectors
+pub fn quantum_kernel(feature_map: &impl Fn(&[f64]) -> Circuit, x: &[f64], y: &[f64]) -> f64 {
+    let (cx, cy) = (feature_map(x), feature_map(y));
+    let mut a = Register::new(cx.num_qubits());
+    a.apply_circuit(&cx);
+    let mut b = Register::new(cy.num_qubits());
+    b.apply_circuit(&cy);
+    a.inner(&b).norm_sqr()
+}
+
+/// Gram matrix of the fidelity kernel over `data`
+pub fn kernel_matrix(feature_map: impl Fn(&[f64]) -> Circuit, data: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let states: Vec<Register> = data
+        .iter()
+        .map(|x| {
+            let circuit = feature_map(x);
+            let mut register = Register::new(circuit.num_qubits());
+            register.apply_circuit(&circuit);
+            register
+        })
+        .collect();
+    let n = states.len();
+    let mut gram = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        gram[i][i] = 1.0;
+        for j in i + 1..n {
+            let k = states[i].inner(&states[j]).norm_sqr();
+            gram[i][j] = k;
+            gram[j][i] = k;
+        }
+    }
+    gram
+}
+
+/// Gram matrix estimated by compute–uncompute: run φ(y) then φ(x)† and count
+/// how often all qubits read 0
+pub fn kernel_matrix_sampled(
+    feature_map: impl Fn(&[f64]) -> Circuit,
+    data: &[Vec<f64>],
+    shots: usize,
+    rng: &mut Rng,
+) -> Vec<Vec<f64>> {
+    let circuits: Vec<Circuit> = data.iter().map(|x| feature_map(x)).collect();
+    let n = circuits.len();
+    let mut gram = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        gram[i][i] = 1.0;
+        for j in i + 1..n {
+            let mut register = Register::new(circuits[j].num_qubits());
+            register.apply_circuit(&circuits[j]);
+            register.apply_circuit(&circuits[i].inverse());
+            let p_zero = register.probabilities()[0];
+            let hits = (0..shots).filter(|_| rng.gen_bool(p_zero)).count();
+            let k = hits as f64 / shots as f64;
+            gram[i][j] = k;
+            gram[j][i] = k;
+        }
+    }
+    gram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ml::feature_maps::{angle_encoding, zz_feature_map, Entanglement, RotationAxis};
+
+    #[test]
+    fn test_angle_kernel_closed_form() {
+        // RY encoding gives K = Π cos²((x_i − y_i)/2)
+        let map = |x: &[f64]| angle_encoding(x, RotationAxis::Y);
+        let (x, y): ([f64; 2], [f64; 2]) = ([0.3, 1.1], [0.9, -0.4]);
+        let expected: f64 = x.iter().zip(&y).map(|(a, b)| ((a - b) / 2.0).cos().powi(2)).product();
+        assert!((quantum_kernel(&map, &x, &y) - expected).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_sampled_gram_matches_exact() {
+        let map = |x: &[f64]| zz_feature_map(x, 2, Entanglement::Linear);
+        let data = vec![vec![0.1, 0.5], vec![1.2, 0.3], vec![2.0, 2.5]];
+        let exact = kernel_matrix(map, &data);
+        let mut rng = Rng::seed_from_u64(16);
+        let sampled = kernel_matrix_sampled(map, &data, 4000, &mut rng);
+        for i in 0..3 {
+            assert!((exact[i][i] - 1.0).abs() < 1e-12);
+            for j in 0..3 {
+                assert!((exact[i][j] - exact[j][i]).abs() < 1e-12);
+                assert!((exact[i][j] - sampled[i][j]).abs() < 0.03);
+            }
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod feature_maps;
+pub mod kernel;
+
+pub use feature_maps::{angle_encoding, zz_feature_map, Entanglement, RotationAxis};
+pub use kernel::{kernel_matrix, kernel_matrix_sampled, quantum_kernel};
@@ -0,0 +1,223 @@
+use std::fmt;
+use std::ptr;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+use super::model::NoiseModel;
+use super::trajectory::{run_trajectory_scaled, ErrorScale};
+
+/// an error source the budget can switch off on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ErrorSource {
+    /// depolarizing error after instruction k
+    Gate(usize),
+    /// idle depolarizing of a qubit
+    Idle(usize),
+    /// T1/T2 decay and frequency drift of a qubit
+    Relaxation(usize),
+}
+
+/// share of the infidelity owed to one source
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetEntry {
+    pub source: ErrorSource,
+    /// e.g. `cx q0, q1 (gate 3)` or `relaxation q2`
+    pub label: String,
+    /// qubits the source acts on
+    pub qubits: Vec<usize>,
+    /// infidelity removed by switching the source off
+    pub contribution: f64,
+}
+
+/// infidelity of a noisy run broken down by error source
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorBudget {
+    pub num_qubits: usize,
+    pub trajectories: usize,
+    /// 1 − ⟨ψ|ρ|ψ⟩ with every source on
+    pub infidelity: f64,
+    /// largest contribution first
+    pub entries: Vec<BudgetEntry>,
+}
+
+impl ErrorBudget {
+    /// sum of all contributions
+    pub fn attributed(&self) -> f64 {
+        self.entries.iter().map(|e| e.contribution).sum()
+    }
+
+    /// infidelity left to crosstalk, leakage and interplay between sources
+    pub fn unattributed(&self) -> f64 {
+        self.infidelity - self.attributed()
+    }
+
+    /// contributions summed per qubit, a gate's split evenly over its qubits
+    pub fn per_qubit(&self) -> Vec<f64> {
+        let mut totals = vec![0.0; self.num_qubits];
+        for entry in &self.entries {
+            let share = entry.contribution / entry.qubits.len() as f64;
+            entry.qubits.iter().for_each(|&q| totals[q] += share);
+        }
+        totals
+    }
+}
+
+/// `error_budget_scaled` with each source switched off
+pub fn error_budget(
+    circuit: &Circuit,
+    noise: &NoiseModel,
+    trajectories: usize,
+    rng: &mut Rng,
+) -> ErrorBudget {
+    error_budget_scaled(circuit, noise, trajectories, 0.0, rng)
+}
+
+/// re-run `circuit` with each gate error, idle error and qubit relaxation in
+/// turn scaled by `factor`, attributing the change in state infidelity
+///
+/// The change is extrapolated linearly to the whole source, (L − L_f)/(1 − f);
+/// a factor above 1 lifts weak sources out of the sampling noise. Every run
+/// replays the same random numbers, so the differences are far less noisy
+/// than the infidelities themselves. Readout error leaves the state alone
+/// and is not part of the budget.
+pub fn error_budget_scaled(
+    circuit: &Circuit,
+    noise: &NoiseModel,
+    trajectories: usize,
+    factor: f64,
+    rng: &mut Rng,
+) -> ErrorBudget {
+    assert!(trajectories > 0, "need at least one trajectory");
+    assert!(factor >= 0.0 && factor != 1.0, "factor must be non-negative and not 1");
+    let num_qubits = circuit.num_qubits();
+    let layers = circuit.layers();
+    let mut ideal = Register::new(num_qubits);
+    ideal.apply_circuit(circuit);
+    let seed = rng.next_u64();
+    let infidelity = |scale: &ErrorScale| {
+        let mut rng = Rng::seed_from_u64(seed);
+        let fidelity: f64 = (0..trajectories)
+            .map(|_| {
+                let state = run_trajectory_scaled(num_qubits, &layers, noise, scale, &mut rng);
+                ideal.inner(&state).norm_sqr()
+            })
+            .sum();
+        1.0 - fidelity / trajectories as f64
+    };
+    let unscaled = ErrorScale::unscaled(num_qubits, &layers);
+    let baseline = infidelity(&unscaled);
+    // (moment, slot) of every instruction
+    let mut slots = vec![(0, 0); circuit.len()];
+    for (m, moment) in layers.iter().enumerate() {
+        for (slot, &inst) in moment.iter().enumerate() {
+            let k = circuit.instructions().iter().position(|i| ptr::eq(i, inst));
+            slots[k.expect("layers hold the circuit's instructions")] = (m, slot);
+        }
+    }
+    let labels = circuit.qubit_labels();
+    let mut sources = Vec::new();
+    for (k, inst) in circuit.instructions().iter().enumerate() {
+        if noise.gate_error(inst.qubits.len()) > 0.0 {
+            let names: Vec<&str> = inst.qubits.iter().map(|&q| labels[q].as_str()).collect();
+            let label = format!("{} {} (gate {})", inst.gate.label(), names.join(", "), k);
+            sources.push((ErrorSource::Gate(k), label, inst.qubits.clone()));
+        }
+    }
+    for (q, name) in labels.iter().enumerate() {
+        if noise.idle_error > 0.0 {
+            sources.push((ErrorSource::Idle(q), format!("idle {}", name), vec![q]));
+        }
+        if noise.relaxation.is_some() {
+            sources.push((ErrorSource::Relaxation(q), format!("relaxation {}", name), vec![q]));
+        }
+    }
+    let mut entries: Vec<BudgetEntry> = sources
+        .into_iter()
+        .map(|(source, label, qubits)| {
+            let mut scale = unscaled.clone();
+            match source {
+                ErrorSource::Gate(k) => scale.gates[slots[k].0][slots[k].1] = factor,
+                ErrorSource::Idle(q) => scale.idle[q] = factor,
+                ErrorSource::Relaxation(q) => scale.relaxation[q] = factor,
+            }
+            let contribution = (baseline - infidelity(&scale)) / (1.0 - factor);
+            BudgetEntry { source, label, qubits, contribution }
+        })
+        .collect();
+    entries.sort_by(|a, b| b.contribution.total_cmp(&a.contribution));
+    ErrorBudget { num_qubits, trajectories, infidelity: baseline, entries }
+}
+
+impl fmt::Display for ErrorBudget {
+    /// a summary line, then one ranked line per source with its share of the
+    /// infidelity
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "infidelity {:.4} over {} trajectories, {:.4} attributed",
+            self.infidelity,
+            self.trajectories,
+            self.attributed()
+        )?;
+        for (rank, entry) in self.entries.iter().enumerate() {
+            let share = if self.infidelity > 0.0 {
+                100.0 * entry.contribution / self.infidelity
+            } else {
+                0.0
+            };
+            write!(
+                f,
+                "\n{:>3}. {:.4} {:>5.1}%  {}",
+                rank + 1,
+                entry.contribution,
+                share,
+                entry.label
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::timing::GateTimes;
+
+    #[test]
+    fn test_two_qubit_gates_lead_the_budget() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).cx(0, 1).cx(1, 2).t(2);
+        let noise = NoiseModel::depolarizing(0.005, 0.05);
+        let budget = error_budget(&circuit, &noise, 1000, &mut Rng::seed_from_u64(6));
+        assert_eq!(budget.entries.len(), 4);
+        let top: Vec<ErrorSource> = budget.entries[..2].iter().map(|e| e.source).collect();
+        assert!(top.contains(&ErrorSource::Gate(1)) && top.contains(&ErrorSource::Gate(2)));
+        // independent small errors add up to the whole infidelity
+        assert!(budget.unattributed().abs() < 0.01, "{}", budget);
+        assert!(budget.to_string().contains("cx q0, q1 (gate 1)"));
+    }
+
+    #[test]
+    fn test_relaxation_is_charged_to_the_excited_qubit() {
+        // |0⟩ neither decays nor dephases, so only qubit 0 loses fidelity
+        let mut circuit = Circuit::new(2);
+        circuit.x(0).id(0).id(1);
+        let noise = NoiseModel::ideal().with_relaxation(20_000.0, 15_000.0, GateTimes::default());
+        let budget = error_budget(&circuit, &noise, 400, &mut Rng::seed_from_u64(2));
+        assert_eq!(budget.entries[0].source, ErrorSource::Relaxation(0));
+        let per_qubit = budget.per_qubit();
+        assert!(per_qubit[0] > 0.0 && per_qubit[1].abs() < 1e-12);
+        assert!((per_qubit[0] - budget.infidelity).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_scaling_up_agrees_with_switching_off() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1);
+        let noise = NoiseModel::depolarizing(0.01, 0.02).with_idle_error(0.01);
+        let off = error_budget(&circuit, &noise, 2000, &mut Rng::seed_from_u64(8));
+        let doubled = error_budget_scaled(&circuit, &noise, 2000, 2.0, &mut Rng::seed_from_u64(8));
+        assert_eq!(off.entries.len(), 4);
+        assert!((off.attributed() - doubled.attributed()).abs() < 0.01, "{}\n{}", off, doubled);
+    }
+}
@@ -0,0 +1,381 @@
+use num_complex::Complex64;
+use crate::simulator::matrix::Matrix;
+use crate::simulator::pauli::Pauli;
+use crate::simulator::pauli_string::PauliString;
+
+/// quantum channel ρ ↦ Σ K ρ K† stored in Kraus form
+///
+/// Vectorization is row-major, vec(ρ)[i·d + j] = ρ_ij, so the
+/// superoperator of ρ ↦ AρB is A ⊗ Bᵀ. The Choi matrix is
+/// Σ_ij |i⟩⟨j| ⊗ E(|i⟩⟨j|) and the Pauli transfer matrix is
+/// R_ij = Tr(P_i E(P_j)) / d with n-qubit Paulis indexed in base 4, qubit 0
+/// the least significant digit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel {
+    dim: usize,
+    kraus: Vec<Matrix>,
+}
+
+fn zero() -> Complex64 {
+    Complex64::new(0.0, 0.0)
+}
+
+fn real(x: f64) -> Complex64 {
+    Complex64::new(x, 0.0)
+}
+
+/// all 4^n Pauli matrices on n qubits, in PTM order
+fn pauli_basis(num_qubits: usize) -> Vec<Matrix> {
+    (0..1usize << (2 * num_qubits))
+        .map(|j| {
+            let paulis = (0..num_qubits).map(|q| Pauli::ALL[(j >> (2 * q)) & 3]).collect();
+            PauliString::new(paulis).matrix()
+        })
+        .collect()
+}
+
+fn trace(m: &Matrix) -> Complex64 {
+    (0..m.rows()).map(|i| m[(i, i)]).sum()
+}
+
+/// d of a d² × d² matrix with d a power of two
+fn choi_dim(m: &Matrix) -> Option<usize> {
+    let n = m.rows();
+    let d = (n as f64).sqrt().round() as usize;
+    (m.cols() == n && d * d == n && d.is_power_of_two()).then_some(d)
+}
+
+/// Choi matrix of a superoperator: Λ[(i d + k), (j d + l)] = S[(k d + l), (i d + j)]
+fn superoperator_to_choi(s: &Matrix, d: usize) -> Matrix {
+    Matrix::from_fn(d * d, d * d, |r, c| {
+        let (i, k, j, l) = (r / d, r % d, c / d, c % d);
+        s[(k * d + l, i * d + j)]
+    })
+}
+
+/// superoperator of a 4^n × 4^n PTM, S = (1/d) Σ R_ij vec(P_i) vec(P_j)†
+fn ptm_to_superoperator(r: &Matrix) -> Result<Matrix, String> {
+    let n = r.rows();
+    let num_qubits = (n.trailing_zeros() / 2) as usize;
+    if r.cols() != n || 1usize << (2 * num_qubits) != n {
+        return Err("PTM must be 4^n × 4^n".into());
+    }
+    let d = 1usize << num_qubits;
+    let basis = pauli_basis(num_qubits);
+    Ok(Matrix::from_fn(d * d, d * d, |a, b| {
+        let mut total = zero();
+        for (i, pi) in basis.iter().enumerate() {
+            let x = pi.as_slice()[a];
+            if x == zero() {
+                continue;
+            }
+            for (j, pj) in basis.iter().enumerate() {
+                total += r[(i, j)] * x * pj.as_slice()[b].conj();
+            }
+        }
+        total / d as f64
+    }))
+}
+
+/// the map with Choi matrix `choi` is completely positive: the matrix is
+/// d² × d², Hermitian and has no eigenvalue below −`tolerance`
+pub fn is_cp_choi(choi: &Matrix, tolerance: f64) -> bool {
+    let n = choi.rows();
+    choi_dim(choi).is_some()
+        && (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .all(|(i, j)| (choi[(i, j)] - choi[(j, i)].conj()).norm() <= tolerance)
+        && choi.hermitian_eigenvalues()[0] >= -tolerance
+}
+
+/// the map with Choi matrix `choi` is trace preserving: tracing out the
+/// output leaves the identity on the input
+pub fn is_tp_choi(choi: &Matrix, tolerance: f64) -> bool {
+    let Some(d) = choi_dim(choi) else {
+        return false;
+    };
+    (0..d).flat_map(|i| (0..d).map(move |j| (i, j))).all(|(i, j)| {
+        let partial: Complex64 = (0..d).map(|k| choi[(i * d + k, j * d + k)]).sum();
+        (partial - real(if i == j { 1.0 } else { 0.0 })).norm() <= tolerance
+    })
+}
+
+pub fn is_cptp_choi(choi: &Matrix, tolerance: f64) -> bool {
+    is_tp_choi(choi, tolerance) && is_cp_choi(choi, tolerance)
+}
+
+/// `is_cptp_choi` for a Pauli transfer matrix, which must also be real
+pub fn is_cptp_ptm(ptm: &Matrix, tolerance: f64) -> bool {
+    let Ok(s) = ptm_to_superoperator(ptm) else {
+        return false;
+    };
+    let d = (ptm.rows() as f64).sqrt().round() as usize;
+    ptm.as_slice().iter().all(|x| x.im.abs() <= tolerance)
+        && is_cptp_choi(&superoperator_to_choi(&s, d), tolerance)
+}
+
+impl Channel {
+    /// panics unless the operators are square, equal-sized qubit operators
+    pub fn from_kraus(kraus: Vec<Matrix>) -> Self {
+        assert!(!kraus.is_empty(), "channel needs at least one Kraus operator");
+        let dim = kraus[0].rows();
+        assert!(dim.is_power_of_two(), "dimension must be a power of two");
+        for k in &kraus {
+            assert!(k.rows() == dim && k.cols() == dim, "Kraus operators must be {}×{}", dim, dim);
+        }
+        Self { dim, kraus }
+    }
+
+    pub fn identity(num_qubits: usize) -> Self {
+        Self::from_kraus(vec![Matrix::identity(1 << num_qubits)])
+    }
+
+    pub fn unitary(u: &Matrix) -> Self {
+        Self::from_kraus(vec![u.clone()])
+    }
+
+    /// (1 − p)ρ + (p/3)(XρX + YρY + ZρZ)
+    pub fn depolarizing(p: f64) -> Self {
+        assert!((0.0..=1.0).contains(&p), "invalid probability");
+        let mut kraus = vec![Matrix::identity(2).scaled(real((1.0 - p).sqrt()))];
+        for pauli in [Pauli::X, Pauli::Y, Pauli::Z] {
+            kraus.push(Matrix::from_matrix2(&pauli.matrix2()).scaled(real((p / 3.0).sqrt())));
+        }
+        Self::from_kraus(kraus)
+    }
+
+    /// (1 − p)ρ + p XρX
+    pub fn bit_flip(p: f64) -> Self {
+        assert!((0.0..=1.0).contains(&p), "invalid probability");
+        Self::from_kraus(vec![
+            Matrix::identity(2).scaled(real((1.0 - p).sqrt())),
+            Matrix::from_matrix2(&Pauli::X.matrix2()).scaled(real(p.sqrt())),
+        ])
+    }
+
+    /// energy relaxation |1⟩ → |0⟩ with probability γ
+    pub fn amplitude_damping(gamma: f64) -> Self {
+        assert!((0.0..=1.0).contains(&gamma), "invalid probability");
+        let mut k0 = Matrix::identity(2);
+        k0[(1, 1)] = real((1.0 - gamma).sqrt());
+        let mut k1 = Matrix::zeros(2, 2);
+        k1[(0, 1)] = real(gamma.sqrt());
+        Self::from_kraus(vec![k0, k1])
+    }
+
+    /// loss of coherence without energy exchange; off-diagonals scale by √(1 − λ)
+    pub fn phase_damping(lambda: f64) -> Self {
+        assert!((0.0..=1.0).contains(&lambda), "invalid probability");
+        let mut k0 = Matrix::identity(2);
+        k0[(1, 1)] = real((1.0 - lambda).sqrt());
+        let mut k1 = Matrix::zeros(2, 2);
+        k1[(1, 1)] = real(lambda.sqrt());
+        Self::from_kraus(vec![k0, k1])
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.dim.trailing_zeros() as usize
+    }
+
+    pub fn kraus(&self) -> &[Matrix] {
+        &self.kraus
+    }
+
+    /// E(ρ)
+    pub fn apply(&self, rho: &Matrix) -> Matrix {
+        let mut out = Matrix::zeros(self.dim, self.dim);
+        for k in &self.kraus {
+            out = &out + &(&(k * rho) * &k.dagger());
+        }
+        out
+    }
+
+    /// `other` after `self`
+    pub fn then(&self, other: &Channel) -> Channel {
+        assert_eq!(self.dim, other.dim, "dimension mismatch");
+        let kraus = other
+            .kraus
+            .iter()
+            .flat_map(|b| self.kraus.iter().map(move |a| b * a))
+            .collect();
+        Channel::from_kraus(kraus)
+    }
+
+    /// Choi matrix Σ_ij |i⟩⟨j| ⊗ E(|i⟩⟨j|)
+    pub fn choi(&self) -> Matrix {
+        let d = self.dim;
+        Matrix::from_fn(d * d, d * d, |r, c| {
+            let (i, k, j, l) = (r / d, r % d, c / d, c % d);
+            self.kraus.iter().map(|m| m[(k, i)] * m[(l, j)].conj()).sum()
+        })
+    }
+
+    /// Kraus operators from a Choi matrix by pivoted Cholesky; fails unless
+    /// the Choi matrix is Hermitian positive semidefinite
+    pub fn from_choi(choi: &Matrix) -> Result<Channel, String> {
+        let n = choi.rows();
+        let Some(d) = choi_dim(choi) else {
+            return Err("Choi matrix must be d² × d² with d a power of two".into());
+        };
+        let hermitian = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .all(|(i, j)| (choi[(i, j)] - choi[(j, i)].conj()).norm() < 1e-9);
+        if !hermitian {
+            return Err("Choi matrix is not Hermitian".into());
+        }
+        if choi.hermitian_eigenvalues()[0] < -1e-9 {
+            return Err("Choi matrix is not positive semidefinite (map is not CP)".into());
+        }
+        let mut rest = choi.clone();
+        let mut kraus = Vec::new();
+        loop {
+            let pivot = (0..n).max_by(|&a, &b| rest[(a, a)].re.total_cmp(&rest[(b, b)].re));
+            let Some(p) = pivot.filter(|&p| rest[(p, p)].re > 1e-12) else {
+                break;
+            };
+            let scale = rest[(p, p)].re.sqrt();
+            let v: Vec<Complex64> = (0..n).map(|i| rest[(i, p)] / scale).collect();
+            for i in 0..n {
+                for j in 0..n {
+                    rest[(i, j)] -= v[i] * v[j].conj();
+                }
+            }
+            kraus.push(Matrix::from_fn(d, d, |k, i| v[i * d + k]));
+        }
+        if kraus.is_empty() {
+            kraus.push(Matrix::zeros(d, d));
+        }
+        Ok(Channel::from_kraus(kraus))
+    }
+
+    /// superoperator S with vec(E(ρ)) = S vec(ρ)
+    pub fn superoperator(&self) -> Matrix {
+        let d2 = self.dim * self.dim;
+        let mut s = Matrix::zeros(d2, d2);
+        for k in &self.kraus {
+            let conj = Matrix::from_fn(self.dim, self.dim, |i, j| k[(i, j)].conj());
+            s = &s + &k.kron(&conj);
+        }
+        s
+    }
+
+    pub fn from_superoperator(s: &Matrix) -> Result<Channel, String> {
+        let n = s.rows();
+        let d = (n as f64).sqrt().round() as usize;
+        if s.cols() != n || d * d != n {
+            return Err("superoperator must be d² × d²".into());
+        }
+        Channel::from_choi(&superoperator_to_choi(s, d))
+    }
+
+    /// Pauli transfer matrix R_ij = Tr(P_i E(P_j)) / d, real for CPTP maps
+    pub fn ptm(&self) -> Matrix {
+        let basis = pauli_basis(self.num_qubits());
+        let images: Vec<Matrix> = basis.iter().map(|p| self.apply(p)).collect();
+        let d = real(self.dim as f64);
+        Matrix::from_fn(basis.len(), basis.len(), |i, j| {
+            trace(&(&basis[i] * &images[j])) / d
+        })
+    }
+
+    pub fn from_ptm(r: &Matrix) -> Result<Channel, String> {
+        Channel::from_superoperator(&ptm_to_superoperator(r)?)
+    }
+
+    /// Σ K†K = I within `tolerance`
+    pub fn is_trace_preserving(&self, tolerance: f64) -> bool {
+        let mut total = Matrix::zeros(self.dim, self.dim);
+        for k in &self.kraus {
+            total = &total + &(&k.dagger() * k);
+        }
+        let identity = Matrix::identity(self.dim);
+        total
+            .as_slice()
+            .iter()
+            .zip(identity.as_slice())
+            .all(|(a, b)| (a - b).norm() <= tolerance)
+    }
+
+    /// a Kraus-form channel is completely positive by construction, so this
+    /// is `is_trace_preserving`; use `is_cptp_choi` or `is_cptp_ptm` to
+    /// validate a map given as a matrix
+    pub fn is_cptp(&self, tolerance: f64) -> bool {
+        self.is_trace_preserving(tolerance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &Matrix, b: &Matrix) {
+        for (x, y) in a.as_slice().iter().zip(b.as_slice()) {
+            assert!((x - y).norm() < 1e-9, "{:?} vs {:?}", a, b);
+        }
+    }
+
+    /// |ψ⟩⟨ψ| for a generic single-qubit state
+    fn test_state() -> Matrix {
+        let v = [Complex64::new(0.6, 0.0), Complex64::new(0.48, 0.64)];
+        Matrix::from_fn(2, 2, |i, j| v[i] * v[j].conj())
+    }
+
+    #[test]
+    fn test_round_trips_preserve_action() {
+        let rho = test_state();
+        for channel in [
+            Channel::depolarizing(0.2),
+            Channel::amplitude_damping(0.3),
+            Channel::phase_damping(0.4).then(&Channel::bit_flip(0.1)),
+        ] {
+            assert!(channel.is_cptp(1e-9));
+            let expected = channel.apply(&rho);
+            let via_choi = Channel::from_choi(&channel.choi()).unwrap();
+            let via_super = Channel::from_superoperator(&channel.superoperator()).unwrap();
+            let via_ptm = Channel::from_ptm(&channel.ptm()).unwrap();
+            for other in [via_choi, via_super, via_ptm] {
+                assert_close(&other.apply(&rho), &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_depolarizing_ptm_is_diagonal() {
+        let p = 0.3;
+        let r = Channel::depolarizing(p).ptm();
+        let shrink = 1.0 - 4.0 * p / 3.0;
+        let expected = Matrix::from_fn(4, 4, |i, j| {
+            real(match (i, j) {
+                (0, 0) => 1.0,
+                (i, j) if i == j => shrink,
+                _ => 0.0,
+            })
+        });
+        assert_close(&r, &expected);
+    }
+
+    #[test]
+    fn test_validation() {
+        // the transpose map has Choi = SWAP, which has eigenvalue −1
+        let swap = Matrix::from_fn(4, 4, |r, c| {
+            real(if c == (r % 2) * 2 + r / 2 { 1.0 } else { 0.0 })
+        });
+        assert!(Channel::from_choi(&swap).unwrap_err().contains("not CP"));
+        assert!(is_tp_choi(&swap, 1e-9) && !is_cp_choi(&swap, 1e-9));
+        let leaky = Channel::from_kraus(vec![Matrix::identity(2).scaled(real(0.9))]);
+        assert!(is_cp_choi(&leaky.choi(), 1e-9) && !is_cptp_choi(&leaky.choi(), 1e-9));
+        assert!(!leaky.is_cptp(1e-9));
+        assert!(!is_cptp_choi(&Matrix::identity(3), 1e-9));
+        // the same transpose map as a PTM: Y ↦ −Y, trace preserving but not CP
+        let transpose = Matrix::from_fn(4, 4, |i, j| {
+            real(if i != j { 0.0 } else if i == 2 { -1.0 } else { 1.0 })
+        });
+        assert!(!is_cptp_ptm(&transpose, 1e-9));
+        assert!(is_cptp_ptm(&Channel::amplitude_damping(0.3).ptm(), 1e-9));
+        assert!(!is_cptp_ptm(&Matrix::identity(3), 1e-9));
+    }
+}
@@ -0,0 +1,196 @@
+use std::fmt;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::distribution::Distribution;
+use crate::simulator::pauli::Pauli;
+use crate::simulator::pauli_string::PauliString;
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+use super::model::NoiseModel;
+use super::trajectory::run_trajectory;
+
+/// one observable before and after noise
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObservableDelta {
+    pub observable: PauliString,
+    pub ideal: f64,
+    pub noisy: f64,
+}
+
+impl ObservableDelta {
+    /// noisy minus ideal
+    pub fn delta(&self) -> f64 {
+        self.noisy - self.ideal
+    }
+}
+
+/// how far a noisy execution of a circuit lands from the ideal one
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseComparison {
+    /// trajectories averaged for the noisy side
+    pub trajectories: usize,
+    /// ⟨ψ|ρ|ψ⟩ of the noisy state ρ against the ideal state ψ
+    pub fidelity: f64,
+    /// total variation distance of the measured distributions, with the
+    /// readout error applied to the noisy one
+    pub tvd: f64,
+    pub ideal: Distribution,
+    pub noisy: Distribution,
+    pub observables: Vec<ObservableDelta>,
+}
+
+impl NoiseComparison {
+    /// the observable moved furthest by the noise
+    pub fn worst_observable(&self) -> Option<&ObservableDelta> {
+        self.observables.iter().max_by(|a, b| a.delta().abs().total_cmp(&b.delta().abs()))
+    }
+}
+
+/// ⟨P⟩ for a Hermitian Pauli string, sign included
+fn value(register: &Register, observable: &PauliString) -> f64 {
+    (observable.coefficient() * register.expectation(&observable.terms())).re
+}
+
+/// each bit flipped independently with probability `p`
+fn apply_readout_error(probs: &mut [f64], num_qubits: usize, p: f64) {
+    for q in 0..num_qubits {
+        let bit = 1 << q;
+        for k in (0..probs.len()).filter(|k| k & bit == 0) {
+            let (stay, flip) = (probs[k], probs[k | bit]);
+            probs[k] = (1.0 - p) * stay + p * flip;
+            probs[k | bit] = (1.0 - p) * flip + p * stay;
+        }
+    }
+}
+
+/// `compare_ideal_noisy_with` on X, Y and Z of every qubit
+pub fn compare_ideal_noisy(
+    circuit: &Circuit,
+    noise: &NoiseModel,
+    shots: usize,
+    rng: &mut Rng,
+) -> NoiseComparison {
+    let observables: Vec<PauliString> = (0..circuit.num_qubits())
+        .flat_map(|q| {
+            [Pauli::X, Pauli::Y, Pauli::Z]
+                .map(|pauli| PauliString::single(circuit.num_qubits(), q, pauli))
+        })
+        .collect();
+    compare_ideal_noisy_with(circuit, noise, shots, &observables, rng)
+}
+
+/// ideal state vector against `shots` noisy trajectories of `circuit`
+///
+/// Every figure is averaged exactly over the trajectories rather than
+/// sampled from them, so only the trajectory count limits the precision.
+pub fn compare_ideal_noisy_with(
+    circuit: &Circuit,
+    noise: &NoiseModel,
+    shots: usize,
+    observables: &[PauliString],
+    rng: &mut Rng,
+) -> NoiseComparison {
+    assert!(shots > 0, "need at least one trajectory");
+    assert!(observables.iter().all(PauliString::is_hermitian), "observables must be Hermitian");
+    let num_qubits = circuit.num_qubits();
+    let mut ideal = Register::new(num_qubits);
+    ideal.apply_circuit(circuit);
+    let mut fidelity = 0.0;
+    let mut probs = vec![0.0; 1 << num_qubits];
+    let mut noisy_values = vec![0.0; observables.len()];
+    for _ in 0..shots {
+        let state = run_trajectory(circuit, noise, rng);
+        fidelity += ideal.inner(&state).norm_sqr();
+        for (total, p) in probs.iter_mut().zip(state.probabilities()) {
+            *total += p;
+        }
+        for (total, observable) in noisy_values.iter_mut().zip(observables) {
+            *total += value(&state, observable);
+        }
+    }
+    let weight = 1.0 / shots as f64;
+    probs.iter_mut().for_each(|p| *p *= weight);
+    apply_readout_error(&mut probs, num_qubits, noise.readout_error);
+    let (ideal_distribution, noisy) = (ideal.distribution(), Distribution::new(num_qubits, probs));
+    let observables = observables
+        .iter()
+        .zip(noisy_values)
+        .map(|(observable, total)| ObservableDelta {
+            observable: observable.clone(),
+            ideal: value(&ideal, observable),
+            noisy: total * weight,
+        })
+        .collect();
+    NoiseComparison {
+        trajectories: shots,
+        fidelity: fidelity * weight,
+        tvd: ideal_distribution.total_variation(&noisy),
+        ideal: ideal_distribution,
+        noisy,
+        observables,
+    }
+}
+
+impl fmt::Display for NoiseComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "fidelity {:.4}, TVD {:.4} over {} trajectories",
+            self.fidelity, self.tvd, self.trajectories
+        )?;
+        for o in &self.observables {
+            write!(
+                f,
+                "\n  {:>8}  ideal {:+.4}  noisy {:+.4}  Δ {:+.4}",
+                o.observable.to_string(),
+                o.ideal,
+                o.noisy,
+                o.delta()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::testing::bell;
+
+    #[test]
+    fn test_ideal_model_changes_nothing() {
+        let mut rng = Rng::seed_from_u64(4);
+        let comparison = compare_ideal_noisy(&bell(), &NoiseModel::ideal(), 5, &mut rng);
+        assert!((comparison.fidelity - 1.0).abs() < 1e-12 && comparison.tvd < 1e-12);
+        assert_eq!(comparison.observables.len(), 6);
+        assert!(comparison.observables.iter().all(|o| o.delta().abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_readout_error_only_moves_the_distribution() {
+        let mut circuit = Circuit::new(1);
+        circuit.x(0);
+        let noise = NoiseModel::ideal().with_readout_error(0.1);
+        let comparison = compare_ideal_noisy(&circuit, &noise, 3, &mut Rng::seed_from_u64(1));
+        assert!((comparison.fidelity - 1.0).abs() < 1e-12);
+        assert!((comparison.tvd - 0.1).abs() < 1e-12);
+        assert!((comparison.noisy.prob(0) - 0.1).abs() < 1e-12);
+        assert!(comparison.worst_observable().unwrap().delta().abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_depolarized_bell_pair() {
+        // two-qubit depolarizing p keeps the Bell state under XX, YY and ZZ
+        // (F = 1 − 4p/5) and flips ZZ under 8 of the 15 Paulis
+        let p = 0.2;
+        let noise = NoiseModel::depolarizing(0.0, p);
+        let observables = ["ZZ".parse().unwrap(), "XX".parse().unwrap()];
+        let mut rng = Rng::seed_from_u64(12);
+        let comparison = compare_ideal_noisy_with(&bell(), &noise, 2000, &observables, &mut rng);
+        assert!((comparison.fidelity - (1.0 - 0.8 * p)).abs() < 0.03);
+        let zz = &comparison.observables[0];
+        assert!((zz.ideal - 1.0).abs() < 1e-12);
+        assert!((zz.noisy - (1.0 - 16.0 * p / 15.0)).abs() < 0.05);
+        assert!(comparison.tvd > 0.05);
+        assert!(comparison.to_string().starts_with("fidelity 0."));
+    }
+}
@@ -0,0 +1,201 @@
+use std::fmt::Write;
+use crate::interop::json::Json;
+use crate::simulator::pauli::Pauli;
+
+/// the stochastic event behind a quantum jump
+#[derive(Debug, Clone, PartialEq)]
+pub enum JumpChannel {
+    /// depolarizing error after a gate, one Pauli per gate qubit
+    GateError(Vec<Pauli>),
+    /// depolarizing error on a qubit idle for the moment
+    IdleError(Pauli),
+    /// T1 decay |1⟩ → |0⟩, the emission of one photon
+    Decay,
+    /// pure-dephasing phase flip
+    Dephasing,
+    /// |1⟩ → |2⟩ leakage during a gate
+    Leakage,
+    /// |2⟩ → |1⟩ return of a leaked qubit
+    Seepage,
+}
+
+impl JumpChannel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JumpChannel::GateError(_) => "gate_error",
+            JumpChannel::IdleError(_) => "idle_error",
+            JumpChannel::Decay => "decay",
+            JumpChannel::Dephasing => "dephasing",
+            JumpChannel::Leakage => "leakage",
+            JumpChannel::Seepage => "seepage",
+        }
+    }
+
+    /// Pauli symbols of an error jump, empty for the others
+    fn paulis(&self) -> String {
+        match self {
+            JumpChannel::GateError(paulis) => paulis.iter().map(Pauli::symbol).collect(),
+            JumpChannel::IdleError(pauli) => pauli.symbol().to_string(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// one jump of one trajectory
+#[derive(Debug, Clone, PartialEq)]
+pub struct JumpRecord {
+    /// circuit moment the jump happened in
+    pub moment: usize,
+    /// ns since the start of the circuit: gate errors and leakage at the start
+    /// of their moment, relaxation and seepage at its end
+    pub time: f64,
+    pub qubits: Vec<usize>,
+    pub channel: JumpChannel,
+}
+
+/// jump records of many trajectories of the same circuit
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct JumpLog {
+    pub trajectories: Vec<Vec<JumpRecord>>,
+}
+
+impl JumpLog {
+    /// jumps through `channel` (compared by name) in each trajectory
+    pub fn counts(&self, channel: &str) -> Vec<usize> {
+        self.trajectories
+            .iter()
+            .map(|jumps| jumps.iter().filter(|j| j.channel.name() == channel).count())
+            .collect()
+    }
+
+    /// (mean, variance) of the per-trajectory count of `channel`
+    pub fn count_statistics(&self, channel: &str) -> (f64, f64) {
+        let counts = self.counts(channel);
+        let n = counts.len().max(1) as f64;
+        let mean = counts.iter().sum::<usize>() as f64 / n;
+        let variance = counts.iter().map(|&c| (c as f64 - mean).powi(2)).sum::<f64>() / n;
+        (mean, variance)
+    }
+
+    /// Mandel Q = Var/mean − 1 of the `channel` count: 0 for Poissonian,
+    /// negative for sub-Poissonian (antibunched) emission
+    pub fn mandel_q(&self, channel: &str) -> f64 {
+        let (mean, variance) = self.count_statistics(channel);
+        if mean == 0.0 {
+            0.0
+        } else {
+            variance / mean - 1.0
+        }
+    }
+
+    /// times between consecutive `channel` jumps within each trajectory
+    pub fn waiting_times(&self, channel: &str) -> Vec<f64> {
+        self.trajectories
+            .iter()
+            .flat_map(|jumps| {
+                let times: Vec<f64> = jumps
+                    .iter()
+                    .filter(|j| j.channel.name() == channel)
+                    .map(|j| j.time)
+                    .collect();
+                times.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// `trajectory,moment,time,channel,qubits,paulis` rows with a header;
+    /// qubits are separated by spaces
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("trajectory,moment,time,channel,qubits,paulis\n");
+        for (k, jumps) in self.trajectories.iter().enumerate() {
+            for j in jumps {
+                let qubits: Vec<String> = j.qubits.iter().map(|q| q.to_string()).collect();
+                writeln!(
+                    out,
+                    "{},{},{:.3},{},{},{}",
+                    k,
+                    j.moment,
+                    j.time,
+                    j.channel.name(),
+                    qubits.join(" "),
+                    j.channel.paulis()
+                )
+                .unwrap();
+            }
+        }
+        out
+    }
+
+    /// array of trajectories, each an array of jump objects
+    pub fn to_json(&self) -> Json {
+        let number = |x: usize| Json::Number(x as f64);
+        let record = |j: &JumpRecord| {
+            Json::object([
+                ("moment", number(j.moment)),
+                ("time", Json::Number(j.time)),
+                ("channel", Json::String(j.channel.name().to_string())),
+                ("qubits", Json::Array(j.qubits.iter().map(|&q| number(q)).collect())),
+                ("paulis", Json::String(j.channel.paulis())),
+            ])
+        };
+        Json::Array(
+            self.trajectories
+                .iter()
+                .map(|jumps| Json::Array(jumps.iter().map(record).collect()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jump(moment: usize, time: f64, channel: JumpChannel) -> JumpRecord {
+        JumpRecord {
+            moment,
+            time,
+            qubits: vec![0],
+            channel,
+        }
+    }
+
+    fn log() -> JumpLog {
+        JumpLog {
+            trajectories: vec![
+                vec![
+                    jump(0, 10.0, JumpChannel::Decay),
+                    jump(1, 15.0, JumpChannel::IdleError(Pauli::Y)),
+                    jump(2, 40.0, JumpChannel::Decay),
+                ],
+                vec![],
+                vec![jump(3, 70.0, JumpChannel::Decay)],
+            ],
+        }
+    }
+
+    #[test]
+    fn test_counts_and_waiting_times() {
+        let log = log();
+        assert_eq!(log.counts("decay"), vec![2, 0, 1]);
+        let (mean, variance) = log.count_statistics("decay");
+        assert!((mean - 1.0).abs() < 1e-12 && (variance - 2.0 / 3.0).abs() < 1e-12);
+        assert!((log.mandel_q("decay") + 1.0 / 3.0).abs() < 1e-12);
+        assert_eq!(log.waiting_times("decay"), vec![30.0]);
+        assert_eq!(JumpLog::default().mandel_q("decay"), 0.0);
+    }
+
+    #[test]
+    fn test_csv_and_json_export() {
+        let log = log();
+        let csv = log.to_csv();
+        assert_eq!(csv.lines().count(), 5);
+        assert!(csv.contains("0,1,15.000,idle_error,0,Y"));
+        let json = Json::parse(&log.to_json().to_string()).unwrap();
+        let trajectories = json.as_array().unwrap();
+        assert_eq!(trajectories.len(), 3);
+        let first = &trajectories[0].as_array().unwrap()[2];
+        assert_eq!(first.get("channel").and_then(Json::as_str), Some("decay"));
+        assert_eq!(first.get("time").and_then(Json::as_f64), Some(40.0));
+    }
+}
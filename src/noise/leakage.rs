@@ -0,0 +1,97 @@
+use crate::pulse::PulseOutcome;
+
+/// three-level transmon errors on selected qubits
+///
+/// Every gate on a selected qubit moves its |1⟩ population to |2⟩ with the
+/// leak probability. A leaked qubit sits outside the computational space:
+/// gates touching it are skipped, it reads out as 1, and each moment it
+/// returns to |1⟩ with the seepage probability.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leakage {
+    pub qubits: Vec<usize>,
+    /// chance per gate that |1⟩ leaks to |2⟩
+    pub leak: f64,
+    /// chance per moment that |2⟩ relaxes back to |1⟩
+    pub seepage: f64,
+}
+
+impl Leakage {
+    pub fn new(qubits: &[usize], leak: f64, seepage: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&leak) && (0.0..=1.0).contains(&seepage),
+            "invalid probability"
+        );
+        Self {
+            qubits: qubits.to_vec(),
+            leak,
+            seepage,
+        }
+    }
+
+    /// leak rate of a simulated drive pulse, all of its averaged leakage
+    /// attributed to the |1⟩ input
+    pub fn from_pulse(qubits: &[usize], outcome: &PulseOutcome, seepage: f64) -> Self {
+        Self::new(qubits, (2.0 * outcome.leakage).clamp(0.0, 1.0), seepage)
+    }
+
+    pub fn affects(&self, qubit: usize) -> bool {
+        self.qubits.contains(&qubit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use crate::noise::{run_noisy, NoiseModel};
+    use crate::pulse::{Pulse, Transmon};
+    use crate::simulator::circuit::Circuit;
+    use crate::simulator::rng::Rng;
+
+    #[test]
+    fn test_leaked_qubit_freezes_and_is_reported() {
+        // every X leaks, so qubit 0 ends in |2⟩ and the second X is skipped
+        let mut circuit = Circuit::new(2);
+        circuit.x(0).x(0).cx(0, 1);
+        let noise = NoiseModel::ideal().with_leakage(Leakage::new(&[0], 1.0, 0.0));
+        let mut rng = Rng::seed_from_u64(5);
+        let result = run_noisy(&circuit, &noise, 100, &mut rng);
+        assert_eq!(result.counts.get(&0b01), Some(&100));
+        assert_eq!(result.report.leaked_shots, vec![100, 0]);
+    }
+
+    #[test]
+    fn test_leak_rate_and_unselected_qubits() {
+        let mut circuit = Circuit::new(2);
+        circuit.x(0).x(1);
+        let noise = NoiseModel::ideal().with_leakage(Leakage::new(&[0], 0.2, 0.0));
+        let mut rng = Rng::seed_from_u64(6);
+        let result = run_noisy(&circuit, &noise, 4000, &mut rng);
+        let leaked = result.report.leaked_shots[0] as f64 / 4000.0;
+        assert!((leaked - 0.2).abs() < 0.03, "{}", leaked);
+        assert_eq!(result.report.leaked_shots[1], 0);
+        // leaked qubits read as 1, so the outcome is unchanged
+        assert_eq!(result.counts.get(&0b11), Some(&4000));
+    }
+
+    #[test]
+    fn test_no_leak_branch_shrinks_the_one_amplitude() {
+        // |+⟩ leaks half the time; otherwise K0 leaves |0⟩, so P(1) = 1/2
+        let mut circuit = Circuit::new(1);
+        circuit.h(0);
+        let noise = NoiseModel::ideal().with_leakage(Leakage::new(&[0], 1.0, 0.0));
+        let mut rng = Rng::seed_from_u64(7);
+        let result = run_noisy(&circuit, &noise, 4000, &mut rng);
+        let ones = *result.counts.get(&1).unwrap_or(&0) as f64 / 4000.0;
+        assert!((ones - 0.5).abs() < 0.03, "{}", ones);
+        assert_eq!(result.report.leaked_shots[0], result.counts[&1]);
+    }
+
+    #[test]
+    fn test_from_pulse_uses_simulated_leakage() {
+        let transmon = Transmon::with_anharmonicity(-2.0 * PI * 0.25);
+        let outcome = Pulse::gaussian(PI, 6.0, 1.5, None).simulate(&transmon, 400);
+        let leakage = Leakage::from_pulse(&[0], &outcome, 0.0);
+        assert!(leakage.leak > 0.0 && (leakage.leak - 2.0 * outcome.leakage).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,24 @@
+pub mod budget;
+pub mod channel;
+pub mod compare;
+pub mod crosstalk;
+pub mod jumps;
+pub mod leakage;
+pub mod model;
+pub mod pec;
+pub mod trajectory;
+
+pub use budget::{error_budget, error_budget_scaled, BudgetEntry, ErrorBudget, ErrorSource};
+pub use channel::{is_cp_choi, is_cptp_choi, is_cptp_ptm, is_tp_choi, Channel};
+pub use compare::{
+    compare_ideal_noisy, compare_ideal_noisy_with, NoiseComparison, ObservableDelta,
+};
+pub use crosstalk::{crosstalk_fidelity, crosstalk_free_layers, Crosstalk};
+pub use jumps::{JumpChannel, JumpLog, JumpRecord};
+pub use leakage::Leakage;
+pub use model::{NoiseModel, Relaxation};
+pub use pec::{pec_expectation, PauliChannel, PecEstimate, PecModel};
+pub use trajectory::{
+    noisy_counts, noisy_density_matrix, record_jumps, run_noisy, run_scheduled_trajectory,
+    run_trajectory, run_trajectory_layers, run_trajectory_recorded, scheduled_counts,
+};
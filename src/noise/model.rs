@@ -0,0 +1,138 @@
+use crate::simulator::timing::GateTimes;
+use super::crosstalk::Crosstalk;
+use super::leakage::Leakage;
+
+/// T1 amplitude damping plus the pure dephasing that completes T2, applied
+/// to every qubit for the duration of each circuit moment; times in ns
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relaxation {
+    pub t1: f64,
+    pub t2: f64,
+    /// gate durations setting each moment's length; `id` gates act as delays
+    pub times: GateTimes,
+    /// spread σ in rad/ns of a quasi-static frequency offset drawn per qubit
+    /// per trajectory; this part of the dephasing is refocused by echoes
+    pub detuning_spread: f64,
+}
+
+impl Relaxation {
+    /// damping probability γ and phase-flip probability over `dt` ns
+    pub fn probabilities(&self, dt: f64) -> (f64, f64) {
+        let gamma = 1.0 - (-dt / self.t1).exp();
+        let dephasing_rate = 1.0 / self.t2 - 0.5 / self.t1;
+        let phase_flip = (1.0 - (-dt * dephasing_rate).exp()) / 2.0;
+        (gamma, phase_flip)
+    }
+}
+
+/// gate and readout error rates applied during noisy execution
+///
+/// Depolarizing errors follow every gate: with probability p a uniformly random
+/// non-identity Pauli acts on the gate's qubits. Idle errors depolarize every
+/// qubit left untouched in a circuit moment. Readout errors flip each
+/// measured bit independently. With relaxation set, every moment also
+/// damps and dephases all qubits for its duration; with crosstalk set,
+/// neighbours driven by separate gates in a moment pick up a ZZ phase;
+/// with leakage set, selected qubits can leave the computational space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseModel {
+    pub depolarizing_1q: f64,
+    pub depolarizing_2q: f64,
+    pub idle_error: f64,
+    pub readout_error: f64,
+    pub relaxation: Option<Relaxation>,
+    pub crosstalk: Option<Crosstalk>,
+    pub leakage: Option<Leakage>,
+}
+
+impl NoiseModel {
+    /// no errors at all
+    pub fn ideal() -> Self {
+        Self {
+            depolarizing_1q: 0.0,
+            depolarizing_2q: 0.0,
+            idle_error: 0.0,
+            readout_error: 0.0,
+            relaxation: None,
+            crosstalk: None,
+            leakage: None,
+        }
+    }
+
+    pub fn depolarizing(p_1q: f64, p_2q: f64) -> Self {
+        assert!((0.0..=1.0).contains(&p_1q) && (0.0..=1.0).contains(&p_2q), "invalid probability");
+        Self {
+            depolarizing_1q: p_1q,
+            depolarizing_2q: p_2q,
+            idle_error: 0.0,
+            readout_error: 0.0,
+            relaxation: None,
+            crosstalk: None,
+            leakage: None,
+        }
+    }
+
+    pub fn with_readout_error(mut self, p: f64) -> Self {
+        assert!((0.0..=1.0).contains(&p), "invalid probability");
+        self.readout_error = p;
+        self
+    }
+
+    /// depolarizing probability per idle qubit per moment
+    pub fn with_idle_error(mut self, p: f64) -> Self {
+        assert!((0.0..=1.0).contains(&p), "invalid probability");
+        self.idle_error = p;
+        self
+    }
+
+    /// T1/T2 decay with moment lengths from `times`; needs T2 ≤ 2·T1
+    pub fn with_relaxation(mut self, t1: f64, t2: f64, times: GateTimes) -> Self {
+        assert!(t1 > 0.0 && t2 > 0.0 && t2 <= 2.0 * t1, "need 0 < T2 ≤ 2·T1");
+        self.relaxation = Some(Relaxation { t1, t2, times, detuning_spread: 0.0 });
+        self
+    }
+
+    /// quasi-static frequency noise of spread `sigma` rad/ns on top of relaxation
+    pub fn with_frequency_noise(mut self, sigma: f64) -> Self {
+        let relaxation = self.relaxation.as_mut().expect("frequency noise needs relaxation");
+        relaxation.detuning_spread = sigma;
+        self
+    }
+
+    /// coherent ZZ error between simultaneously driven neighbours
+    pub fn with_crosstalk(mut self, crosstalk: Crosstalk) -> Self {
+        self.crosstalk = Some(crosstalk);
+        self
+    }
+
+    /// three-level leakage on the qubits `leakage` selects
+    pub fn with_leakage(mut self, leakage: Leakage) -> Self {
+        self.leakage = Some(leakage);
+        self
+    }
+
+    /// depolarizing probability for a gate on `num_qubits` qubits
+    pub fn gate_error(&self, num_qubits: usize) -> f64 {
+        if num_qubits == 1 {
+            self.depolarizing_1q
+        } else {
+            self.depolarizing_2q
+        }
+    }
+
+    pub fn is_ideal(&self) -> bool {
+        self.depolarizing_1q == 0.0
+            && self.depolarizing_2q == 0.0
+            && self.idle_error == 0.0
+            && self.readout_error == 0.0
+            && self.relaxation.is_none()
+            && self.crosstalk.is_none()
+            && self.leakage.is_none()
+    }
+}
+
+impl Default for NoiseModel {
+    fn default() -> Self {
+        Self::ideal()
+    }
+}
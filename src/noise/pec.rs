@@ -0,0 +1,314 @@
+use std::collections::BTreeMap;
+use crate::simulator::circuit::{Circuit, Instruction};
+use crate::simulator::pauli::Pauli;
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+use super::model::NoiseModel;
+use super::trajectory::run_trajectory_with;
+
+/// Pauli error channel on k qubits: entry `code` is the probability of the
+/// Pauli with qubit j's factor `Pauli::ALL[(code >> 2j) & 3]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PauliChannel {
+    pub num_qubits: usize,
+    pub probabilities: Vec<f64>,
+}
+
+/// Paulis `a` and `b` (as codes) anticommute on an odd number of qubits
+fn anticommute(a: usize, b: usize, num_qubits: usize) -> bool {
+    (0..num_qubits).fold(false, |acc, j| {
+        let (p, q) = ((a >> (2 * j)) & 3, (b >> (2 * j)) & 3);
+        acc ^ (p != 0 && q != 0 && p != q)
+    })
+}
+
+/// Σ_Q ±values[Q], the sign − when P and Q anticommute
+fn pauli_transform(values: &[f64], num_qubits: usize) -> Vec<f64> {
+    (0..values.len())
+        .map(|p| {
+            values
+                .iter()
+                .enumerate()
+                .map(|(q, v)| if anticommute(p, q, num_qubits) { -v } else { *v })
+                .sum()
+        })
+        .collect()
+}
+
+impl PauliChannel {
+    pub fn new(num_qubits: usize, probabilities: Vec<f64>) -> Self {
+        assert_eq!(probabilities.len(), 1 << (2 * num_qubits), "need 4^k probabilities");
+        assert!(probabilities.iter().all(|&p| p >= 0.0), "negative probability");
+        let total: f64 = probabilities.iter().sum();
+        assert!((total - 1.0).abs() < 1e-9, "probabilities sum to {}", total);
+        Self {
+            num_qubits,
+            probabilities,
+        }
+    }
+
+    /// a non-identity Pauli with probability `p`, all of them equally likely,
+    /// as `NoiseModel` applies after each gate
+    pub fn depolarizing(num_qubits: usize, p: f64) -> Self {
+        let size = 1 << (2 * num_qubits);
+        let mut probabilities = vec![p / (size - 1) as f64; size];
+        probabilities[0] = 1.0 - p;
+        Self::new(num_qubits, probabilities)
+    }
+
+    /// the Pauli channel scaling each Pauli Q by `fidelities[Q]`
+    ///
+    /// Fidelities that no channel has, as estimates near the edge can be, give
+    /// negative probabilities; those are clamped to zero and the rest
+    /// renormalized, so the result is the nearest channel rather than exact.
+    pub fn from_fidelities(num_qubits: usize, fidelities: &[f64]) -> Self {
+        let size = fidelities.len() as f64;
+        let mut probabilities: Vec<f64> = pauli_transform(fidelities, num_qubits)
+            .into_iter()
+            .map(|p| (p / size).max(0.0))
+            .collect();
+        let total: f64 = probabilities.iter().sum();
+        assert!(total > 0.0, "fidelities describe no channel");
+        probabilities.iter_mut().for_each(|p| *p /= total);
+        Self::new(num_qubits, probabilities)
+    }
+
+    /// Pauli fidelities f_Q, the factor by which the channel scales Q
+    pub fn fidelities(&self) -> Vec<f64> {
+        pauli_transform(&self.probabilities, self.num_qubits)
+    }
+
+    /// quasi-probabilities η_P with Σ η_P·P ρ P undoing the channel;
+    /// negative entries are what make the inverse unphysical
+    pub fn inverse(&self) -> Vec<f64> {
+        let inverse_fidelities: Vec<f64> = self.fidelities().iter().map(|f| 1.0 / f).collect();
+        let size = inverse_fidelities.len() as f64;
+        pauli_transform(&inverse_fidelities, self.num_qubits)
+            .into_iter()
+            .map(|eta| eta / size)
+            .collect()
+    }
+
+    /// sampling overhead γ = Σ|η_P| of the inverse
+    pub fn gamma(&self) -> f64 {
+        self.inverse().iter().map(|eta| eta.abs()).sum()
+    }
+}
+
+/// learned Pauli noise per gate: a channel for each (gate name, qubits), with
+/// one- and two-qubit defaults for gates not listed
+#[derive(Debug, Clone, PartialEq)]
+pub struct PecModel {
+    pub one_qubit: PauliChannel,
+    pub two_qubit: PauliChannel,
+    pub gates: BTreeMap<(String, Vec<usize>), PauliChannel>,
+}
+
+impl PecModel {
+    pub fn new(one_qubit: PauliChannel, two_qubit: PauliChannel) -> Self {
+        assert_eq!((one_qubit.num_qubits, two_qubit.num_qubits), (1, 2), "channel sizes");
+        Self {
+            one_qubit,
+            two_qubit,
+            gates: BTreeMap::new(),
+        }
+    }
+
+    /// the gate errors of a depolarizing `NoiseModel`, as if learned exactly
+    pub fn from_noise(noise: &NoiseModel) -> Self {
+        Self::new(
+            PauliChannel::depolarizing(1, noise.gate_error(1)),
+            PauliChannel::depolarizing(2, noise.gate_error(2)),
+        )
+    }
+
+    pub fn with_gate(mut self, gate: &str, qubits: &[usize], channel: PauliChannel) -> Self {
+        assert_eq!(channel.num_qubits, qubits.len(), "channel size");
+        self.gates.insert((gate.to_string(), qubits.to_vec()), channel);
+        self
+    }
+
+    /// channel following `inst`; gates on three or more qubits need an entry
+    pub fn channel(&self, inst: &Instruction) -> Option<&PauliChannel> {
+        let key = (inst.gate.name().to_string(), inst.qubits.clone());
+        self.gates.get(&key).or(match inst.qubits.len() {
+            1 => Some(&self.one_qubit),
+            2 => Some(&self.two_qubit),
+            _ => None,
+        })
+    }
+}
+
+/// mitigated expectation value with its statistical spread
+#[derive(Debug, Clone)]
+pub struct PecEstimate {
+    pub value: f64,
+    pub std_error: f64,
+    /// product of the per-gate overheads; variance grows as γ²
+    pub gamma: f64,
+    pub samples: usize,
+}
+
+/// inverse quasi-distribution after one gate, normalised to probabilities
+struct Correction {
+    weights: Vec<f64>,
+    signs: Vec<f64>,
+}
+
+impl Correction {
+    fn new(channel: &PauliChannel) -> (Self, f64) {
+        let eta = channel.inverse();
+        let gamma: f64 = eta.iter().map(|e| e.abs()).sum();
+        let correction = Self {
+            weights: eta.iter().map(|e| e.abs() / gamma).collect(),
+            signs: eta.iter().map(|e| e.signum()).collect(),
+        };
+        (correction, gamma)
+    }
+
+    /// draw a Pauli, apply it to `qubits` and return its sign
+    fn apply(&self, register: &mut Register, qubits: &[usize], rng: &mut Rng) -> f64 {
+        let mut r = rng.next_f64();
+        let code = self
+            .weights
+            .iter()
+            .position(|&w| {
+                r -= w;
+                r < 0.0
+            })
+            .unwrap_or(self.weights.len() - 1);
+        for (j, &q) in qubits.iter().enumerate() {
+            let pauli = Pauli::ALL[(code >> (2 * j)) & 3];
+            if pauli != Pauli::I {
+                register.apply_matrix2(q, pauli.matrix2());
+            }
+        }
+        self.signs[code]
+    }
+}
+
+/// probabilistic error cancellation of ⟨observable⟩ after `circuit`
+///
+/// Each sample runs one noisy trajectory with a Pauli drawn from the inverse
+/// of each gate's learned channel inserted after it, and weighs the
+/// trajectory's exact expectation by γ times the product of the signs drawn.
+/// Readout error is not mitigated.
+pub fn pec_expectation(
+    circuit: &Circuit,
+    observable: &[(usize, Pauli)],
+    model: &PecModel,
+    noise: &NoiseModel,
+    samples: usize,
+    rng: &mut Rng,
+) -> Result<PecEstimate, String> {
+    assert!(samples >= 2, "need at least two samples");
+    let mut corrections = BTreeMap::new();
+    let mut gamma = 1.0;
+    for inst in circuit.instructions() {
+        let channel = model.channel(inst).ok_or_else(|| {
+            format!("no learned channel for {} on {:?}", inst.gate.name(), inst.qubits)
+        })?;
+        let key = (inst.gate.name(), inst.qubits.clone());
+        let (correction, overhead) = Correction::new(channel);
+        gamma *= overhead;
+        corrections.entry(key).or_insert(correction);
+    }
+    let values: Vec<f64> = (0..samples)
+        .map(|_| {
+            let mut sign = 1.0;
+            let mut insert = |inst: &Instruction, register: &mut Register, rng: &mut Rng| {
+                let correction = &corrections[&(inst.gate.name(), inst.qubits.clone())];
+                sign *= correction.apply(register, &inst.qubits, rng);
+            };
+            let register = run_trajectory_with(circuit, noise, rng, &mut insert);
+            gamma * sign * register.expectation(observable)
+        })
+        .collect();
+    let n = samples as f64;
+    let value = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - value).powi(2)).sum::<f64>() / (n - 1.0);
+    Ok(PecEstimate {
+        value,
+        std_error: (variance / n).sqrt(),
+        gamma,
+        samples,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::run_trajectory;
+
+    #[test]
+    fn test_depolarizing_inverse_matches_closed_form() {
+        let p = 0.06;
+        let channel = PauliChannel::depolarizing(1, p);
+        let f = 1.0 - 4.0 * p / 3.0;
+        let fidelities = channel.fidelities();
+        assert!((fidelities[0] - 1.0).abs() < 1e-12);
+        assert!(fidelities[1..].iter().all(|x| (x - f).abs() < 1e-12));
+        assert!((channel.gamma() - (3.0 / f - 1.0) / 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_inverse_composes_to_identity() {
+        let probabilities = (0..16).map(|k| if k == 0 { 0.85 } else { 0.01 }).collect();
+        let channel = PauliChannel::new(2, probabilities);
+        let eta = channel.inverse();
+        // composing Pauli channels convolves their coefficients over the group
+        let mut composed = [0.0; 16];
+        for (a, pa) in channel.probabilities.iter().enumerate() {
+            for (b, eb) in eta.iter().enumerate() {
+                composed[product(a, b)] += pa * eb;
+            }
+        }
+        assert!((composed[0] - 1.0).abs() < 1e-9);
+        assert!(composed[1..].iter().all(|c| c.abs() < 1e-9), "{:?}", composed);
+    }
+
+    /// code of the two-qubit Pauli P_a·P_b, up to phase
+    fn product(a: usize, b: usize) -> usize {
+        (0..2).fold(0, |code, j| {
+            let (p, q) = (Pauli::ALL[(a >> (2 * j)) & 3], Pauli::ALL[(b >> (2 * j)) & 3]);
+            let index = Pauli::ALL.iter().position(|&x| x == p.multiply(q).1).unwrap();
+            code | (index << (2 * j))
+        })
+    }
+
+    #[test]
+    fn test_from_fidelities_renormalizes_unphysical_estimates() {
+        let channel = PauliChannel::depolarizing(1, 0.1);
+        let rebuilt = PauliChannel::from_fidelities(1, &channel.fidelities());
+        for (a, b) in rebuilt.probabilities.iter().zip(&channel.probabilities) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        // f_X above 1 and f_I off by rounding, as a noisy fit can return
+        let channel = PauliChannel::from_fidelities(1, &[1.0 + 1e-7, 1.2, 0.5, 0.5]);
+        assert!(channel.probabilities.iter().all(|&p| p >= 0.0));
+        assert!((channel.probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_pec_removes_depolarizing_bias() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1).rz(0.4, 1).cx(0, 1).h(0);
+        let observable = [(0, Pauli::Z)];
+        let mut ideal = Register::new(2);
+        ideal.apply_circuit(&circuit);
+        let exact = ideal.expectation(&observable);
+        let noise = NoiseModel::depolarizing(0.02, 0.08);
+        let mut rng = Rng::seed_from_u64(186);
+        let noisy = (0..4000)
+            .map(|_| run_trajectory(&circuit, &noise, &mut rng).expectation(&observable))
+            .sum::<f64>()
+            / 4000.0;
+        let model = PecModel::from_noise(&noise);
+        let estimate =
+            pec_expectation(&circuit, &observable, &model, &noise, 4000, &mut rng).unwrap();
+        assert!(estimate.gamma > 1.0);
+        assert!((noisy - exact).abs() > 0.1, "noisy {} exact {}", noisy, exact);
+        let error = (estimate.value - exact).abs();
+        assert!(error < 4.0 * estimate.std_error, "{:?} vs {}", estimate, exact);
+    }
+}
@@ -0,0 +1,598 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+use num_complex::Complex64;
+use crate::simulator::circuit::{Circuit, Instruction};
+use crate::simulator::density::DensityMatrix;
+use crate::simulator::gates::phase_matrix;
+use crate::simulator::pauli::Pauli;
+use crate::simulator::register::Register;
+use crate::simulator::report::{statevector_bytes, RunReport, RunResult};
+use crate::simulator::rng::Rng;
+use crate::simulator::timing::ScheduledCircuit;
+use crate::trace;
+use super::crosstalk::apply_zz;
+use super::jumps::{JumpChannel, JumpLog, JumpRecord};
+use super::model::{NoiseModel, Relaxation};
+
+/// uniformly random non-identity Pauli on `num_qubits` qubits
+fn random_pauli(num_qubits: usize, rng: &mut Rng) -> Vec<Pauli> {
+    let choices = (1usize << (2 * num_qubits)) - 1;
+    let code = 1 + rng.gen_range(choices);
+    (0..num_qubits).map(|k| Pauli::ALL[(code >> (2 * k)) & 3]).collect()
+}
+
+fn apply_pauli(register: &mut Register, qubits: &[usize], paulis: &[Pauli]) {
+    for (&q, &pauli) in qubits.iter().zip(paulis) {
+        if pauli != Pauli::I {
+            register.apply_matrix2(q, pauli.matrix2());
+        }
+    }
+}
+
+/// random non-identity Pauli on `qubits`, returned factor by factor
+fn apply_random_pauli(register: &mut Register, qubits: &[usize], rng: &mut Rng) -> Vec<Pauli> {
+    let paulis = random_pauli(qubits.len(), rng);
+    apply_pauli(register, qubits, &paulis);
+    paulis
+}
+
+/// depolarizing error with probability `p`, returning the Pauli applied;
+/// with `aligned` the Pauli is drawn even when no error happens, so later
+/// random numbers do not depend on whether one did
+fn pauli_error(
+    register: &mut Register,
+    qubits: &[usize],
+    p: f64,
+    aligned: bool,
+    rng: &mut Rng,
+) -> Option<Vec<Pauli>> {
+    let hit = rng.gen_bool(p);
+    if !hit && !aligned {
+        return None;
+    }
+    let paulis = random_pauli(qubits.len(), rng);
+    if !hit {
+        return None;
+    }
+    apply_pauli(register, qubits, &paulis);
+    Some(paulis)
+}
+
+/// quantum-jump unravelling of T1/T2 decay on `qubit` over `dt` ns;
+/// returns whether it decayed and whether it took a phase flip
+fn relax(
+    register: &mut Register,
+    qubit: usize,
+    relaxation: &Relaxation,
+    dt: f64,
+    rng: &mut Rng,
+) -> (bool, bool) {
+    let (gamma, phase_flip) = relaxation.probabilities(dt);
+    let (zero, one) = (Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0));
+    let decayed = rng.gen_bool(gamma * register.prob_one(qubit));
+    if decayed {
+        register.apply_matrix2(qubit, [[zero, one], [zero, zero]]);
+    } else {
+        let survive = Complex64::new((1.0 - gamma).sqrt(), 0.0);
+        register.apply_matrix2(qubit, [[one, zero], [zero, survive]]);
+    }
+    register.normalize();
+    let dephased = rng.gen_bool(phase_flip);
+    if dephased {
+        register.apply_matrix2(qubit, Pauli::Z.matrix2());
+    }
+    (decayed, dephased)
+}
+
+/// per-qubit frequency offsets for one trajectory, zero without frequency noise
+fn draw_detunings(num_qubits: usize, noise: &NoiseModel, rng: &mut Rng) -> Vec<f64> {
+    match &noise.relaxation {
+        Some(relaxation) if relaxation.detuning_spread > 0.0 => {
+            (0..num_qubits).map(|_| relaxation.detuning_spread * rng.normal()).collect()
+        }
+        _ => vec![0.0; num_qubits],
+    }
+}
+
+/// relaxation and frequency drift of `qubit` over `dt` ns; returns the
+/// jumps of `relax`
+fn evolve(
+    register: &mut Register,
+    qubit: usize,
+    relaxation: &Relaxation,
+    detuning: f64,
+    dt: f64,
+    rng: &mut Rng,
+) -> (bool, bool) {
+    let jumps = relax(register, qubit, relaxation, dt, rng);
+    if detuning != 0.0 {
+        register.apply_matrix2(qubit, phase_matrix(detuning * dt));
+    }
+    jumps
+}
+
+/// one Monte Carlo trajectory of `circuit` from |0…0⟩ under `noise`,
+/// executed moment by moment
+pub fn run_trajectory(circuit: &Circuit, noise: &NoiseModel, rng: &mut Rng) -> Register {
+    run_trajectory_layers(circuit.num_qubits(), &circuit.layers(), noise, rng)
+}
+
+/// one trajectory with the moments given explicitly, so a different
+/// schedule of the same gates sees different idle and crosstalk errors
+pub fn run_trajectory_layers(
+    num_qubits: usize,
+    layers: &[Vec<&Instruction>],
+    noise: &NoiseModel,
+    rng: &mut Rng,
+) -> Register {
+    run_layers(num_qubits, layers, noise, rng, &mut |_, _, _| {}, None, None).0
+}
+
+/// one trajectory together with every quantum jump it took
+pub fn run_trajectory_recorded(
+    circuit: &Circuit,
+    noise: &NoiseModel,
+    rng: &mut Rng,
+) -> (Register, Vec<JumpRecord>) {
+    let mut jumps = Vec::new();
+    let layers = circuit.layers();
+    let mut no_hook = |_: &Instruction, _: &mut Register, _: &mut Rng| {};
+    let (register, _) =
+        run_layers(circuit.num_qubits(), &layers, noise, rng, &mut no_hook, Some(&mut jumps), None);
+    (register, jumps)
+}
+
+/// jump records of `trajectories` independent runs, for emission statistics
+pub fn record_jumps(
+    circuit: &Circuit,
+    noise: &NoiseModel,
+    trajectories: usize,
+    rng: &mut Rng,
+) -> JumpLog {
+    JumpLog {
+        trajectories: (0..trajectories)
+            .map(|_| run_trajectory_recorded(circuit, noise, rng).1)
+            .collect(),
+    }
+}
+
+/// one trajectory calling `after_gate` on every executed gate once its noise
+/// has been applied, so the hook acts as a noiseless insertion
+pub(crate) fn run_trajectory_with(
+    circuit: &Circuit,
+    noise: &NoiseModel,
+    rng: &mut Rng,
+    after_gate: &mut dyn FnMut(&Instruction, &mut Register, &mut Rng),
+) -> Register {
+    run_layers(circuit.num_qubits(), &circuit.layers(), noise, rng, after_gate, None, None).0
+}
+
+/// multipliers on error probabilities for one trajectory: per gate, laid out
+/// like the layers being run, and per qubit for idle errors and relaxation
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ErrorScale {
+    pub gates: Vec<Vec<f64>>,
+    pub idle: Vec<f64>,
+    /// scales the T1/T2 rates and frequency drift of each qubit
+    pub relaxation: Vec<f64>,
+}
+
+impl ErrorScale {
+    /// every error source at its model strength
+    pub fn unscaled(num_qubits: usize, layers: &[Vec<&Instruction>]) -> Self {
+        Self {
+            gates: layers.iter().map(|moment| vec![1.0; moment.len()]).collect(),
+            idle: vec![1.0; num_qubits],
+            relaxation: vec![1.0; num_qubits],
+        }
+    }
+}
+
+/// one trajectory with each error source scaled by `scale`; random numbers
+/// are drawn as for the unscaled model, so runs from the same seed stay in
+/// step until their errors differ
+pub(crate) fn run_trajectory_scaled(
+    num_qubits: usize,
+    layers: &[Vec<&Instruction>],
+    noise: &NoiseModel,
+    scale: &ErrorScale,
+    rng: &mut Rng,
+) -> Register {
+    run_layers(num_qubits, layers, noise, rng, &mut |_, _, _| {}, None, Some(scale)).0
+}
+
+/// one trajectory, also returning which qubits finished leaked to |2⟩;
+/// a leaked qubit is held at |1⟩ in the register so it reads out as 1.
+/// With `jumps` set every stochastic jump is appended to it.
+fn run_layers(
+    num_qubits: usize,
+    layers: &[Vec<&Instruction>],
+    noise: &NoiseModel,
+    rng: &mut Rng,
+    after_gate: &mut dyn FnMut(&Instruction, &mut Register, &mut Rng),
+    mut jumps: Option<&mut Vec<JumpRecord>>,
+    scale: Option<&ErrorScale>,
+) -> (Register, Vec<bool>) {
+    let mut register = Register::new(num_qubits);
+    let mut leaked = vec![false; num_qubits];
+    let detunings = draw_detunings(num_qubits, noise, rng);
+    // scaled runs keep their random numbers in step with each other
+    let aligned = scale.is_some();
+    // timestamps follow the relaxation gate times, or the defaults without them
+    let times = noise.relaxation.as_ref().map(|r| r.times.clone()).unwrap_or_default();
+    let mut clock = 0.0;
+    for (index, moment) in layers.iter().enumerate() {
+        let dt = moment.iter().map(|inst| times.duration(&inst.gate)).fold(0.0, f64::max);
+        let (start, end) = (clock, clock + dt);
+        clock = end;
+        let mut record = |time: f64, qubits: &[usize], channel: JumpChannel| {
+            if let Some(jumps) = jumps.as_deref_mut() {
+                jumps.push(JumpRecord {
+                    moment: index,
+                    time,
+                    qubits: qubits.to_vec(),
+                    channel,
+                });
+            }
+        };
+        let mut busy = vec![false; num_qubits];
+        for (slot, &instruction) in moment.iter().enumerate() {
+            for &q in &instruction.qubits {
+                busy[q] = true;
+            }
+            if instruction.qubits.iter().any(|&q| leaked[q]) {
+                continue;
+            }
+            register.apply_instruction(instruction);
+            let p = noise.gate_error(instruction.qubits.len());
+            if p > 0.0 {
+                let p = p * scale.map_or(1.0, |s| s.gates[index][slot]);
+                let qubits = &instruction.qubits;
+                if let Some(paulis) = pauli_error(&mut register, qubits, p, aligned, rng) {
+                    record(start, qubits, JumpChannel::GateError(paulis));
+                }
+            }
+            after_gate(instruction, &mut register, rng);
+            if let Some(leakage) = &noise.leakage {
+                for &q in instruction.qubits.iter().filter(|&&q| leakage.affects(q)) {
+                    if rng.gen_bool(leakage.leak * register.prob_one(q)) {
+                        register.postselect(q, true);
+                        leaked[q] = true;
+                        record(start, &[q], JumpChannel::Leakage);
+                    } else {
+                        // no-jump Kraus operator |0⟩⟨0| + √(1−p)|1⟩⟨1|
+                        let (zero, one) = (Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0));
+                        let survive = Complex64::new((1.0 - leakage.leak).sqrt(), 0.0);
+                        register.apply_matrix2(q, [[one, zero], [zero, survive]]);
+                        register.normalize();
+                    }
+                }
+            }
+        }
+        if noise.idle_error > 0.0 {
+            for q in (0..num_qubits).filter(|&q| !busy[q]) {
+                let p = noise.idle_error * scale.map_or(1.0, |s| s.idle[q]);
+                if let Some(paulis) = pauli_error(&mut register, &[q], p, aligned, rng) {
+                    record(start, &[q], JumpChannel::IdleError(paulis[0]));
+                }
+            }
+        }
+        if let Some(crosstalk) = &noise.crosstalk {
+            for (a, b, theta) in crosstalk.moment_phases(moment) {
+                apply_zz(&mut register, a, b, theta);
+            }
+        }
+        if let Some(relaxation) = &noise.relaxation {
+            for q in (0..num_qubits).filter(|&q| !leaked[q]) {
+                let dt = dt * scale.map_or(1.0, |s| s.relaxation[q]);
+                let (decayed, dephased) =
+                    evolve(&mut register, q, relaxation, detunings[q], dt, rng);
+                if decayed {
+                    record(end, &[q], JumpChannel::Decay);
+                }
+                if dephased {
+                    record(end, &[q], JumpChannel::Dephasing);
+                }
+            }
+        }
+        if let Some(leakage) = &noise.leakage {
+            for (q, flag) in leaked.iter_mut().enumerate().filter(|(_, l)| **l) {
+                *flag = !rng.gen_bool(leakage.seepage);
+                if !*flag {
+                    record(end, &[q], JumpChannel::Seepage);
+                }
+            }
+        }
+    }
+    (register, leaked)
+}
+
+/// one trajectory following explicit start times rather than moments: each
+/// qubit relaxes through its own idle gaps and gate durations, so gates
+/// placed inside idle windows act at the times the schedule gives them;
+/// crosstalk, leakage and idle depolarizing are moment-based and not applied
+pub fn run_scheduled_trajectory(
+    circuit: &Circuit,
+    schedule: &ScheduledCircuit,
+    noise: &NoiseModel,
+    rng: &mut Rng,
+) -> Register {
+    let num_qubits = circuit.num_qubits();
+    let mut register = Register::new(num_qubits);
+    let detunings = draw_detunings(num_qubits, noise, rng);
+    let mut clock = vec![0.0f64; num_qubits];
+    let mut order: Vec<usize> = (0..circuit.len()).collect();
+    order.sort_by(|&a, &b| schedule.timings[a].start.total_cmp(&schedule.timings[b].start));
+    for k in order {
+        let (instruction, timing) = (&circuit.instructions()[k], schedule.timings[k]);
+        if let Some(relaxation) = &noise.relaxation {
+            for &q in &instruction.qubits {
+                let idle = timing.start - clock[q];
+                evolve(&mut register, q, relaxation, detunings[q], idle, rng);
+            }
+        }
+        register.apply_instruction(instruction);
+        let p = noise.gate_error(instruction.qubits.len());
+        if p > 0.0 && rng.gen_bool(p) {
+            apply_random_pauli(&mut register, &instruction.qubits, rng);
+        }
+        for &q in &instruction.qubits {
+            if let Some(relaxation) = &noise.relaxation {
+                evolve(&mut register, q, relaxation, detunings[q], timing.duration, rng);
+            }
+            clock[q] = timing.end();
+        }
+    }
+    if let Some(relaxation) = &noise.relaxation {
+        for q in 0..num_qubits {
+            let idle = schedule.total - clock[q];
+            evolve(&mut register, q, relaxation, detunings[q], idle, rng);
+        }
+    }
+    register
+}
+
+/// mixed state after `circuit` under `noise`, averaged over `trajectories` runs
+pub fn noisy_density_matrix(
+    circuit: &Circuit,
+    noise: &NoiseModel,
+    trajectories: usize,
+    rng: &mut Rng,
+) -> DensityMatrix {
+    let states: Vec<Register> = (0..trajectories)
+        .map(|_| run_trajectory(circuit, noise, rng))
+        .collect();
+    DensityMatrix::mixture(&states)
+}
+
+/// sample `shots` measurement outcomes, one fresh trajectory per shot
+pub fn noisy_counts(
+    circuit: &Circuit,
+    noise: &NoiseModel,
+    shots: usize,
+    rng: &mut Rng,
+) -> BTreeMap<usize, usize> {
+    sample_shots(circuit, noise, shots, rng).0
+}
+
+/// `noisy_counts` with every trajectory following `schedule`
+pub fn scheduled_counts(
+    circuit: &Circuit,
+    schedule: &ScheduledCircuit,
+    noise: &NoiseModel,
+    shots: usize,
+    rng: &mut Rng,
+) -> BTreeMap<usize, usize> {
+    let run = |rng: &mut Rng| {
+        let register = run_scheduled_trajectory(circuit, schedule, noise, rng);
+        (register, vec![false; circuit.num_qubits()])
+    };
+    sample_with(circuit.num_qubits(), noise, shots, rng, run).0
+}
+
+/// counts, and per qubit the number of shots that ended leaked
+fn sample_shots(
+    circuit: &Circuit,
+    noise: &NoiseModel,
+    shots: usize,
+    rng: &mut Rng,
+) -> (BTreeMap<usize, usize>, Vec<usize>) {
+    let layers = circuit.layers();
+    let run = |rng: &mut Rng| {
+        run_layers(circuit.num_qubits(), &layers, noise, rng, &mut |_, _, _| {}, None, None)
+    };
+    sample_with(circuit.num_qubits(), noise, shots, rng, run)
+}
+
+/// measure one `trajectory` per shot, adding readout error
+fn sample_with(
+    num_qubits: usize,
+    noise: &NoiseModel,
+    shots: usize,
+    rng: &mut Rng,
+    mut trajectory: impl FnMut(&mut Rng) -> (Register, Vec<bool>),
+) -> (BTreeMap<usize, usize>, Vec<usize>) {
+    let mut counts = BTreeMap::new();
+    let mut leaked_shots = vec![0; num_qubits];
+    for shot in 0..shots {
+        let _span = trace::span("shot", || shot.to_string());
+        let (register, leaked) = trajectory(rng);
+        for (total, _) in leaked_shots.iter_mut().zip(leaked).filter(|(_, l)| *l) {
+            *total += 1;
+        }
+        let mut outcome = register.distribution().sample(rng);
+        if noise.readout_error > 0.0 {
+            for q in 0..num_qubits {
+                if rng.gen_bool(noise.readout_error) {
+                    outcome ^= 1 << q;
+                }
+            }
+        }
+        *counts.entry(outcome).or_insert(0) += 1;
+    }
+    (counts, leaked_shots)
+}
+
+/// `noisy_counts` with a run report; peak memory is one trajectory's state
+pub fn run_noisy(circuit: &Circuit, noise: &NoiseModel, shots: usize, rng: &mut Rng) -> RunResult {
+    let start = Instant::now();
+    let (counts, leaked_shots) = sample_shots(circuit, noise, shots, rng);
+    let mut report = RunReport::for_circuit("trajectory", circuit, shots);
+    if noise.leakage.is_some() {
+        report.leaked_shots = leaked_shots;
+    }
+    report.wall_time = start.elapsed();
+    report.peak_memory_bytes = statevector_bytes(circuit.num_qubits());
+    RunResult { counts, report }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::timing::{GateTimes, Schedule};
+
+    #[test]
+    fn test_ideal_noise_matches_exact() {
+        let mut circuit = Circuit::new(2);
+        circuit.x(0).cx(0, 1);
+        let mut rng = Rng::seed_from_u64(1);
+        let counts = noisy_counts(&circuit, &NoiseModel::ideal(), 200, &mut rng);
+        assert_eq!(counts.get(&0b11), Some(&200));
+    }
+
+    #[test]
+    fn test_full_depolarizing_randomizes() {
+        // p = 1 after X on |0⟩ leaves |1⟩ flipped by X or Y (→ 0) or Z (→ 1)
+        let mut circuit = Circuit::new(1);
+        circuit.x(0);
+        let mut rng = Rng::seed_from_u64(9);
+        let counts = noisy_counts(&circuit, &NoiseModel::depolarizing(1.0, 0.0), 3000, &mut rng);
+        let zeros = *counts.get(&0).unwrap_or(&0) as f64 / 3000.0;
+        assert!((zeros - 2.0 / 3.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_run_noisy_reports_backend() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).cx(0, 1).cx(1, 2);
+        let mut rng = Rng::seed_from_u64(3);
+        let result = run_noisy(&circuit, &NoiseModel::depolarizing(0.01, 0.02), 50, &mut rng);
+        assert_eq!(result.report.backend, "trajectory");
+        assert_eq!((result.report.shots, result.report.multi_qubit_gates), (50, 2));
+        assert_eq!(result.counts.values().sum::<usize>(), 50);
+    }
+
+    #[test]
+    fn test_noisy_bell_pair_loses_negativity() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1);
+        let mut rng = Rng::seed_from_u64(189);
+        let p = 0.3;
+        let rho = noisy_density_matrix(&circuit, &NoiseModel::depolarizing(0.0, p), 3000, &mut rng);
+        assert!((rho.trace() - 1.0).abs() < 1e-9);
+        // a Werner state of visibility v = 1 − 16p/15 has N = (3v − 1)/4
+        let v = 1.0 - 16.0 * p / 15.0;
+        assert!((rho.negativity(&[0]) - (3.0 * v - 1.0) / 4.0).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_idle_error_hits_waiting_qubits() {
+        // qubit 1 idles through three moments while qubit 0 is busy
+        let mut circuit = Circuit::new(2);
+        circuit.x(0).x(0).x(0);
+        let noise = NoiseModel::ideal().with_idle_error(0.3);
+        let mut rng = Rng::seed_from_u64(34);
+        let counts = noisy_counts(&circuit, &noise, 4000, &mut rng);
+        let flipped: usize = counts.iter().filter(|(k, _)| *k & 2 != 0).map(|(_, n)| n).sum();
+        // each idle step flips the bit with probability 0.3 · 2/3
+        let q = 0.2;
+        let expected = (1.0 - (1.0f64 - 2.0 * q).powi(3)) / 2.0;
+        assert!((flipped as f64 / 4000.0 - expected).abs() < 0.03);
+        assert!(counts.keys().all(|k| k & 1 == 1));
+    }
+
+    #[test]
+    fn test_readout_error_flips_bits() {
+        let circuit = Circuit::new(1);
+        let noise = NoiseModel::ideal().with_readout_error(0.25);
+        let mut rng = Rng::seed_from_u64(4);
+        let counts = noisy_counts(&circuit, &noise, 4000, &mut rng);
+        let ones = *counts.get(&1).unwrap_or(&0) as f64 / 4000.0;
+        assert!((ones - 0.25).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_scheduled_trajectory_matches_moments_without_frequency_noise() {
+        // an idle gap the moment view also sees: X, then a 300 ns cx elsewhere
+        let mut circuit = Circuit::new(3);
+        circuit.x(0).cx(1, 2).cx(1, 0);
+        let times = GateTimes::superconducting();
+        let schedule = circuit.schedule(&Schedule::asap(times.clone()));
+        let noise = NoiseModel::ideal().with_relaxation(400.0, 800.0, times);
+        let shots = 4000;
+        let mut rng = Rng::seed_from_u64(71);
+        let timed = scheduled_counts(&circuit, &schedule, &noise, shots, &mut rng);
+        let layered = noisy_counts(&circuit, &noise, shots, &mut rng);
+        let excited = |counts: &BTreeMap<usize, usize>| {
+            counts.iter().filter(|(k, _)| *k & 1 == 1).map(|(_, n)| n).sum::<usize>() as f64
+                / shots as f64
+        };
+        assert!((excited(&timed) - excited(&layered)).abs() < 0.04);
+    }
+
+    #[test]
+    fn test_recorded_jumps_follow_t1_decay() {
+        // X then ten 35 ns idles: at most one photon, emitted with
+        // probability 1 − e^{−385/T1}; T2 = 2·T1 leaves no pure dephasing
+        let mut circuit = Circuit::new(1);
+        circuit.x(0);
+        for _ in 0..10 {
+            circuit.push(crate::simulator::gates::Gate::I, &[0]);
+        }
+        let noise = NoiseModel::ideal().with_relaxation(200.0, 400.0, GateTimes::default());
+        let mut rng = Rng::seed_from_u64(200);
+        let log = record_jumps(&circuit, &noise, 2000, &mut rng);
+        let counts = log.counts("decay");
+        assert!(counts.iter().all(|&c| c <= 1));
+        assert_eq!(log.counts("dephasing").iter().sum::<usize>(), 0);
+        let p = 1.0 - (-385.0f64 / 200.0).exp();
+        let (mean, _) = log.count_statistics("decay");
+        assert!((mean - p).abs() < 0.03, "{}", mean);
+        // a single emitter is antibunched: Q = −p
+        assert!((log.mandel_q("decay") + p).abs() < 0.03);
+        for jump in log.trajectories.iter().flatten() {
+            assert_eq!(jump.time, 35.0 * (jump.moment + 1) as f64);
+        }
+    }
+
+    #[test]
+    fn test_recorded_gate_errors_name_their_paulis() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1);
+        let mut rng = Rng::seed_from_u64(201);
+        let noise = NoiseModel::depolarizing(0.0, 1.0);
+        let (_, jumps) = run_trajectory_recorded(&circuit, &noise, &mut rng);
+        assert_eq!(jumps.len(), 1);
+        assert_eq!((jumps[0].moment, jumps[0].qubits.clone()), (1, vec![0, 1]));
+        match &jumps[0].channel {
+            JumpChannel::GateError(paulis) => {
+                assert_eq!(paulis.len(), 2);
+                assert!(paulis.iter().any(|&p| p != Pauli::I));
+            }
+            other => panic!("unexpected jump {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_relaxation_decays_excited_state() {
+        // X then a 50 ns delay with T1 = 50 ns: P(1) = e^{-35/50 - 50/50}
+        let mut circuit = Circuit::new(1);
+        circuit.x(0).id(0);
+        let times = GateTimes::superconducting().with_gate("id", 50.0);
+        let noise = NoiseModel::ideal().with_relaxation(50.0, 100.0, times);
+        let mut rng = Rng::seed_from_u64(12);
+        let counts = noisy_counts(&circuit, &noise, 4000, &mut rng);
+        let excited = *counts.get(&1).unwrap_or(&0) as f64 / 4000.0;
+        assert!((excited - (-1.7f64).exp()).abs() < 0.03, "{}", excited);
+    }
+}
@@ -0,0 +1,140 @@
+use std::f64::consts::PI;
+use crate::simulator::circuit::{Circuit, MarkerKind};
+use crate::simulator::gates::Gate;
+
+/// remove adjacent gate/inverse pairs on identical qubits, repeatedly
+///
+/// Two instructions are adjacent when nothing in between touches any of their
+/// qubits, so `cx(0, 1) h(2) cx(0, 1)` cancels and cancellations cascade.
+/// Nothing cancels across a barrier on one of its qubits; annotations are
+/// transparent.
+pub fn cancel_inverse_pairs(circuit: &Circuit) -> Circuit {
+    let instructions = circuit.instructions();
+    let mut gates: Vec<Option<Gate>> =
+        instructions.iter().map(|inst| Some(inst.gate.clone())).collect();
+    // live instruction indices per qubit since its last barrier, most recent last
+    let mut stacks: Vec<Vec<usize>> = vec![Vec::new(); circuit.num_qubits()];
+    let mut markers = circuit.markers().iter().peekable();
+    for (k, inst) in instructions.iter().enumerate() {
+        while let Some(marker) = markers.next_if(|marker| marker.position == k) {
+            if let MarkerKind::Barrier(qubits) = &marker.kind {
+                qubits.iter().for_each(|&q| stacks[q].clear());
+            }
+        }
+        let previous = stacks[inst.qubits[0]].last().copied();
+        let cancels = previous.is_some_and(|p| {
+            let other = &instructions[p];
+            other.qubits == inst.qubits
+                && other.gate.inverse() == inst.gate
+                && inst.qubits.iter().all(|&q| stacks[q].last() == Some(&p))
+        });
+        if cancels {
+            gates[previous.expect("checked above")] = None;
+            gates[k] = None;
+            for &q in &inst.qubits {
+                stacks[q].pop();
+            }
+        } else {
+            for &q in &inst.qubits {
+                stacks[q].push(k);
+            }
+        }
+    }
+    circuit.rewritten(&gates)
+}
+
+/// merge adjacent rotations about the same axis on the same qubit, dropping
+/// those that become trivial up to global phase; barriers on the qubit are
+/// not crossed, annotations are
+pub fn fuse_rotations(circuit: &Circuit) -> Circuit {
+    let mut gates: Vec<Option<Gate>> = Vec::with_capacity(circuit.len());
+    // index of a rotation that is still the last gate on its qubit
+    let mut open: Vec<Option<usize>> = vec![None; circuit.num_qubits()];
+    let mut markers = circuit.markers().iter().peekable();
+    for (k, inst) in circuit.instructions().iter().enumerate() {
+        while let Some(marker) = markers.next_if(|marker| marker.position == k) {
+            if let MarkerKind::Barrier(qubits) = &marker.kind {
+                qubits.iter().for_each(|&q| open[q] = None);
+            }
+        }
+        let q = inst.qubits[0];
+        if let Some(r) = open[q] {
+            let merged = match (gates[r].as_ref().expect("open rotations are kept"), &inst.gate) {
+                (Gate::Rx(a), Gate::Rx(b)) => Some(Gate::Rx(a + b)),
+                (Gate::Ry(a), Gate::Ry(b)) => Some(Gate::Ry(a + b)),
+                (Gate::Rz(a), Gate::Rz(b)) => Some(Gate::Rz(a + b)),
+                (Gate::Phase(a), Gate::Phase(b)) => Some(Gate::Phase(a + b)),
+                _ => None,
+            };
+            if let Some(gate) = merged {
+                gates[r] = Some(gate);
+                gates.push(None);
+                continue;
+            }
+        }
+        for &qubit in &inst.qubits {
+            open[qubit] = None;
+        }
+        if matches!(inst.gate, Gate::Rx(_) | Gate::Ry(_) | Gate::Rz(_) | Gate::Phase(_)) {
+            open[q] = Some(k);
+        }
+        gates.push(Some(inst.gate.clone()));
+    }
+    for gate in &mut gates {
+        if let Some(Gate::Rx(a) | Gate::Ry(a) | Gate::Rz(a) | Gate::Phase(a)) = *gate {
+            let r = a.rem_euclid(2.0 * PI);
+            if r < 1e-12 || 2.0 * PI - r < 1e-12 {
+                *gate = None;
+            }
+        }
+    }
+    circuit.rewritten(&gates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cascading_cancellation() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).cx(0, 1).t(2).s(1).sdg(1).cx(0, 1).h(0).cx(1, 0).tdg(2);
+        let reduced = cancel_inverse_pairs(&circuit);
+        assert_eq!(reduced.len(), 1);
+        assert_eq!(reduced.instructions()[0].qubits, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_fuse_rotations() {
+        let mut circuit = Circuit::new(2);
+        circuit.rz(0.3, 0).rx(0.1, 1).rz(0.4, 0).cx(0, 1).rz(0.2, 0).rz(-0.2, 0).rx(0.5, 1);
+        let fused = fuse_rotations(&circuit);
+        assert_eq!(fused.len(), 4);
+        assert!(matches!(fused.instructions()[0].gate, Gate::Rz(a) if (a - 0.7).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_barriers_block_only_their_qubits() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).barrier(&[0]).h(0).rz(0.2, 1).annotate("half").rz(0.3, 1);
+        let cancelled = cancel_inverse_pairs(&circuit);
+        assert_eq!((cancelled.len(), cancelled.markers()), (4, circuit.markers()));
+        // the annotation is no barrier, and it stays after the fused rotation
+        let fused = fuse_rotations(&circuit);
+        assert_eq!(fused.len(), 3);
+        assert!(matches!(fused.instructions()[2].gate, Gate::Rz(a) if (a - 0.5).abs() < 1e-12));
+        assert_eq!(fused.markers().len(), 2);
+        let mut other = Circuit::new(2);
+        other.h(0).annotate("step").h(0).rz(0.1, 0).barrier(&[1]).rz(0.2, 0);
+        assert_eq!(cancel_inverse_pairs(&other).len(), 2);
+        assert_eq!(fuse_rotations(&other).len(), 3);
+        assert_eq!(fuse_rotations(&other).markers(), other.markers());
+    }
+
+    #[test]
+    fn test_blocked_pairs_stay() {
+        let mut circuit = Circuit::new(2);
+        circuit.cx(0, 1).h(1).cx(0, 1).cx(1, 0);
+        assert_eq!(cancel_inverse_pairs(&circuit).len(), 4);
+    }
+}
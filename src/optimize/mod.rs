@@ -0,0 +1,12 @@
+pub mod cancellation;
+pub mod phase_folding;
+pub mod pipeline;
+pub mod report;
+pub mod simplify;
+pub mod templates;
+
+pub use cancellation::{cancel_inverse_pairs, fuse_rotations};
+pub use phase_folding::phase_fold;
+pub use report::{OptimizationReport, OptimizationResult};
+pub use simplify::{simplify_gates, simplify_single_qubit_runs, IdentityDatabase};
+pub use templates::{apply_templates, default_templates, Template};
@@ -0,0 +1,186 @@
+use std::collections::{BTreeSet, HashMap};
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::Gate;
+use super::cancellation::cancel_inverse_pairs;
+use super::report::{OptimizationReport, OptimizationResult};
+
+/// XOR of path variables
+type Parity = BTreeSet<usize>;
+
+/// phase angle λ of a diagonal single-qubit gate ∝ diag(1, e^{iλ})
+fn phase_angle(gate: &Gate) -> Option<f64> {
+    Some(match *gate {
+        Gate::Z | Gate::Mcz(0) => PI,
+        Gate::S => FRAC_PI_2,
+        Gate::Sdg => -FRAC_PI_2,
+        Gate::T => FRAC_PI_4,
+        Gate::Tdg => -FRAC_PI_4,
+        Gate::Rz(theta) => theta,
+        Gate::Phase(lambda) => lambda,
+        _ => return None,
+    })
+}
+
+/// gates realizing diag(1, e^{iλ}) up to global phase, preferring Clifford+T names
+fn phase_gates(lambda: f64) -> Vec<Gate> {
+    let turns = lambda.rem_euclid(2.0 * PI) / FRAC_PI_4;
+    let eighth = turns.round();
+    if (turns - eighth).abs() > 1e-9 {
+        return vec![Gate::Phase(lambda)];
+    }
+    match eighth as usize % 8 {
+        0 => vec![],
+        1 => vec![Gate::T],
+        2 => vec![Gate::S],
+        3 => vec![Gate::S, Gate::T],
+        4 => vec![Gate::Z],
+        5 => vec![Gate::Z, Gate::T],
+        6 => vec![Gate::Sdg],
+        _ => vec![Gate::Tdg],
+    }
+}
+
+/// where a merged phase term is emitted
+struct Term {
+    instruction: usize,
+    qubit: usize,
+    complemented: bool,
+    angle: f64,
+}
+
+/// merge phase gates acting on equal parities (Tpar-style phase folding)
+///
+/// Every qubit carries an affine parity of path variables; CX, X and SWAP
+/// update the parities and any other non-diagonal gate starts a fresh
+/// variable. In the sum-over-paths picture all phase terms multiply, so
+/// rotations on the same parity merge wherever they occur; each merged term
+/// is emitted at its first occurrence. Inverse pairs exposed by the removed
+/// phases are cancelled afterwards. The result equals the input up to
+/// global phase. Barriers split the circuit into separately folded parts.
+pub fn phase_fold(circuit: &Circuit) -> OptimizationResult {
+    let out = circuit.map_segments(fold_segment);
+    OptimizationResult {
+        report: OptimizationReport::new("phase folding", circuit, &out),
+        circuit: out,
+    }
+}
+
+fn fold_segment(circuit: &Circuit) -> Circuit {
+    let n = circuit.num_qubits();
+    let mut parity: Vec<Parity> = (0..n).map(|q| Parity::from([q])).collect();
+    let mut constant = vec![false; n];
+    let mut next_variable = n;
+    let mut terms: Vec<Term> = Vec::new();
+    let mut by_parity: HashMap<Parity, usize> = HashMap::new();
+    let mut folded = vec![false; circuit.len()];
+
+    for (k, inst) in circuit.instructions().iter().enumerate() {
+        let q = &inst.qubits;
+        if let Some(lambda) = phase_angle(&inst.gate) {
+            // e^{iλ(1 ⊕ p)} ∝ e^{−iλ p}
+            let signed = if constant[q[0]] { -lambda } else { lambda };
+            let term = *by_parity.entry(parity[q[0]].clone()).or_insert_with(|| {
+                terms.push(Term {
+                    instruction: k,
+                    qubit: q[0],
+                    complemented: constant[q[0]],
+                    angle: 0.0,
+                });
+                terms.len() - 1
+            });
+            terms[term].angle += signed;
+            folded[k] = true;
+            continue;
+        }
+        match inst.gate {
+            Gate::X | Gate::Mcx(0) => constant[q[0]] ^= true,
+            Gate::Cx | Gate::Mcx(1) => {
+                let control = parity[q[0]].clone();
+                parity[q[1]] = parity[q[1]].symmetric_difference(&control).copied().collect();
+                constant[q[1]] ^= constant[q[0]];
+            }
+            Gate::Swap => {
+                parity.swap(q[0], q[1]);
+                constant.swap(q[0], q[1]);
+            }
+            Gate::I | Gate::Cz | Gate::Mcz(_) => {}
+            _ => {
+                // the target of any other gate leaves the affine picture
+                let target = *q.last().expect("gate has qubits");
+                parity[target] = Parity::from([next_variable]);
+                constant[target] = false;
+                next_variable += 1;
+            }
+        }
+    }
+
+    let mut emit_at: HashMap<usize, &Term> = HashMap::new();
+    for term in &terms {
+        emit_at.insert(term.instruction, term);
+    }
+    let mut out = Circuit::new(n);
+    for (k, inst) in circuit.instructions().iter().enumerate() {
+        if !folded[k] {
+            out.push(inst.gate.clone(), &inst.qubits);
+        } else if let Some(term) = emit_at.get(&k) {
+            let angle = if term.complemented { -term.angle } else { term.angle };
+            for gate in phase_gates(angle) {
+                out.push(gate, &[term.qubit]);
+            }
+        }
+    }
+    cancel_inverse_pairs(&out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::rng::Rng;
+    use crate::simulator::testing::ApproxEq;
+
+    #[test]
+    fn test_merges_across_cnots() {
+        // T on q1 sees parity x0⊕x1 both times; the two T gates become one S
+        let mut circuit = Circuit::new(2);
+        circuit.t(0).cx(0, 1).t(1).cx(0, 1).cx(0, 1).t(1).cx(0, 1).tdg(0);
+        let result = phase_fold(&circuit);
+        assert_eq!(result.report.before.t_count, 4);
+        assert_eq!(result.circuit.t_count(), 0);
+        assert_eq!(result.report.t_count_saved(), 4);
+        assert!(result.circuit.to_unitary().approx_eq(&circuit.to_unitary(), 1e-10));
+    }
+
+    #[test]
+    fn test_complemented_and_fresh_variables() {
+        // X flips the parity so T·X·T·X folds to a phase; H blocks merging
+        let mut circuit = Circuit::new(1);
+        circuit.t(0).x(0).t(0).x(0).h(0).t(0);
+        let result = phase_fold(&circuit);
+        assert_eq!(result.circuit.t_count(), 1);
+        assert!(result.circuit.to_unitary().approx_eq(&circuit.to_unitary(), 1e-10));
+    }
+
+    #[test]
+    fn test_random_circuits_preserved() {
+        let mut rng = Rng::seed_from_u64(56);
+        for _ in 0..20 {
+            let mut circuit = Circuit::new(3);
+            for _ in 0..30 {
+                let (a, b) = (rng.gen_range(3), rng.gen_range(3));
+                match rng.gen_range(6) {
+                    0 => circuit.t(a),
+                    1 => circuit.tdg(a),
+                    2 => circuit.h(a),
+                    3 => circuit.x(a),
+                    4 => circuit.rz(0.3, a),
+                    _ if a != b => circuit.cx(a, b),
+                    _ => circuit.s(a),
+                };
+            }
+            let result = phase_fold(&circuit);
+            assert!(result.circuit.t_count() <= circuit.t_count());
+            assert!(result.circuit.to_unitary().approx_eq(&circuit.to_unitary(), 1e-9));
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use crate::simulator::circuit::Circuit;
+use crate::simulator::metrics::CircuitMetrics;
+use super::cancellation::{cancel_inverse_pairs, fuse_rotations};
+use super::phase_folding::phase_fold;
+use super::report::{OptimizationReport, OptimizationResult};
+use super::simplify::{simplify_single_qubit_runs, IdentityDatabase};
+use super::templates::{apply_templates, default_templates};
+
+/// two-qubit gates first, then T gates, then everything else
+pub(crate) fn cost(metrics: &CircuitMetrics) -> (usize, usize, usize) {
+    (metrics.two_qubit_count + metrics.multi_qubit_count, metrics.t_count, metrics.gate_count)
+}
+
+impl Circuit {
+    /// cancellation, rotation fusion, single-qubit run simplification, template
+    /// rewriting and phase folding, repeated while the cost drops; equal to
+    /// the input up to global phase
+    pub fn optimize(&self) -> OptimizationResult {
+        let templates = default_templates();
+        let database = IdentityDatabase::default();
+        let mut best = self.clone();
+        loop {
+            let mut round = cancel_inverse_pairs(&best);
+            round = fuse_rotations(&round);
+            round = simplify_single_qubit_runs(&round, &database);
+            round = apply_templates(&round, &templates);
+            round = phase_fold(&round).circuit;
+            if cost(&round.metrics()) >= cost(&best.metrics()) {
+                break;
+            }
+            best = round;
+        }
+        OptimizationResult {
+            report: OptimizationReport::new("optimize", self, &best),
+            circuit: best,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulator::circuit::Circuit;
+    use crate::simulator::rng::Rng;
+    use crate::simulator::testing::ApproxEq;
+
+    #[test]
+    fn test_optimize_shrinks_and_preserves() {
+        let mut rng = Rng::seed_from_u64(57);
+        for _ in 0..20 {
+            let mut circuit = Circuit::new(3);
+            for _ in 0..40 {
+                let (a, b) = (rng.gen_range(3), rng.gen_range(3));
+                match rng.gen_range(7) {
+                    0 => circuit.t(a),
+                    1 => circuit.h(a),
+                    2 => circuit.s(a),
+                    3 => circuit.rz(0.4, a),
+                    4 => circuit.x(a),
+                    _ if a != b => circuit.cx(a, b),
+                    _ => circuit.z(a),
+                };
+            }
+            let result = circuit.optimize();
+            assert!(result.circuit.len() <= circuit.len());
+            assert!(result.circuit.to_unitary().approx_eq(&circuit.to_unitary(), 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_optimize_keeps_barriers() {
+        let mut circuit = Circuit::new(1);
+        circuit.t(0).barrier(&[]).tdg(0).annotate("done");
+        let result = circuit.optimize();
+        assert_eq!(result.circuit, circuit);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(1).cx(0, 1).h(1).rz(0.2, 0).rz(0.3, 0).t(1).t(1);
+        let result = circuit.optimize();
+        assert_eq!(result.circuit.len(), 3);
+        assert_eq!(result.report.t_count_saved(), 2);
+        assert!(result.report.to_string().starts_with("optimize:"));
+    }
+}
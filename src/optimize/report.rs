@@ -0,0 +1,44 @@
+use std::fmt;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::metrics::CircuitMetrics;
+
+/// circuit metrics before and after an optimization pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizationReport {
+    pub pass: &'static str,
+    pub before: CircuitMetrics,
+    pub after: CircuitMetrics,
+}
+
+impl OptimizationReport {
+    pub fn new(pass: &'static str, before: &Circuit, after: &Circuit) -> Self {
+        Self {
+            pass,
+            before: before.metrics(),
+            after: after.metrics(),
+        }
+    }
+
+    pub fn t_count_saved(&self) -> isize {
+        self.before.t_count as isize - self.after.t_count as isize
+    }
+
+    pub fn two_qubit_saved(&self) -> isize {
+        self.before.two_qubit_count as isize - self.after.two_qubit_count as isize
+    }
+}
+
+impl fmt::Display for OptimizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.pass)?;
+        writeln!(f, "  before: {}", self.before)?;
+        write!(f, "  after:  {}", self.after)
+    }
+}
+
+/// optimized circuit with its report
+#[derive(Debug, Clone)]
+pub struct OptimizationResult {
+    pub circuit: Circuit,
+    pub report: OptimizationReport,
+}
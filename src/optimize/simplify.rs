@@ -0,0 +1,300 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::f64::consts::FRAC_PI_4;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::Gate;
+use crate::simulator::rotation::AxisAngle;
+use crate::synthesis::clifford_t::exact_clifford_t;
+use crate::synthesis::euler::decompose_zyz;
+
+/// named gates the database words are spelled with, Cliffords first so that
+/// among equally short words the one with fewer T gates is found first
+const ALPHABET: [Gate; 8] =
+    [Gate::X, Gate::Y, Gate::Z, Gate::H, Gate::S, Gate::Sdg, Gate::T, Gate::Tdg];
+
+/// quaternions are compared on this grid
+const RESOLUTION: f64 = 1e6;
+
+/// rotation angles this close to a multiple of π/4 are snapped onto it
+const ANGLE_TOLERANCE: f64 = 1e-9;
+
+/// rotation up to global phase, as a quantized quaternion with the sign of
+/// its first non-zero component made positive
+fn key(rotation: &AxisAngle) -> [i64; 4] {
+    let (w, [x, y, z]) = rotation.quaternion();
+    let mut q = [w, x, y, z];
+    if q.iter().find(|c| c.abs() > 1.0 / RESOLUTION).is_some_and(|&c| c < 0.0) {
+        q = q.map(|c| -c);
+    }
+    q.map(|c| (c * RESOLUTION).round() as i64)
+}
+
+/// shortest word over H, S, T, their inverses and the Paulis for every
+/// single-qubit rotation reachable within `depth` gates, up to global phase
+#[derive(Debug, Clone)]
+pub struct IdentityDatabase {
+    depth: usize,
+    words: HashMap<[i64; 4], Vec<Gate>>,
+}
+
+impl IdentityDatabase {
+    /// breadth-first enumeration, so every stored word is minimal
+    pub fn new(depth: usize) -> Self {
+        let mut words = HashMap::from([(key(&AxisAngle::identity()), Vec::new())]);
+        let mut queue = VecDeque::from([(AxisAngle::identity(), Vec::new())]);
+        while let Some((rotation, word)) = queue.pop_front() {
+            if word.len() == depth {
+                continue;
+            }
+            for gate in ALPHABET {
+                let next = rotation.then(&AxisAngle::from_gate(&gate).expect("single-qubit"));
+                if let Entry::Vacant(slot) = words.entry(key(&next)) {
+                    let mut longer: Vec<Gate> = word.clone();
+                    longer.push(gate);
+                    slot.insert(longer.clone());
+                    queue.push_back((next, longer));
+                }
+            }
+        }
+        Self { depth, words }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// number of distinct rotations stored
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// the minimal word for `rotation`, in application order
+    pub fn lookup(&self, rotation: &AxisAngle) -> Option<&[Gate]> {
+        self.words.get(&key(rotation)).map(Vec::as_slice)
+    }
+}
+
+impl Default for IdentityDatabase {
+    /// depth 4, which covers all 24 Cliffords
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+/// replace windows of `gates` by shorter database words until none is left
+fn peephole(mut gates: Vec<Gate>, database: &IdentityDatabase) -> Vec<Gate> {
+    let mut changed = true;
+    while changed {
+        changed = false;
+        'windows: for width in (2..=gates.len().min(database.depth() + 2)).rev() {
+            for start in 0..=gates.len() - width {
+                let window = &gates[start..start + width];
+                let rotation = AxisAngle::from_gates(window).expect("single-qubit gates");
+                if let Some(word) = database.lookup(&rotation).filter(|w| w.len() < width) {
+                    gates.splice(start..start + width, word.to_vec());
+                    changed = true;
+                    break 'windows;
+                }
+            }
+        }
+    }
+    gates
+}
+
+/// `angle`, or the multiple of π/4 it lies within `ANGLE_TOLERANCE` of
+fn snap(angle: f64) -> f64 {
+    let nearest = (angle / FRAC_PI_4).round() * FRAC_PI_4;
+    if (angle - nearest).abs() < ANGLE_TOLERANCE {
+        nearest
+    } else {
+        angle
+    }
+}
+
+/// a single Rx, Ry or Rz when the rotation axis is a coordinate axis
+fn axis_rotation(rotation: &AxisAngle) -> Option<Gate> {
+    let k = rotation.axis.iter().position(|a| a.abs() > 1.0 - 1e-12)?;
+    let angle = snap(rotation.axis[k].signum() * rotation.angle);
+    Some([Gate::Rx, Gate::Ry, Gate::Rz][k](angle))
+}
+
+/// form of the product: nothing for the identity, the database word when
+/// the product is short, an exact Clifford+T word shortened by the database
+/// when one exists, a single rotation about a coordinate axis, and ZYZ Euler
+/// rotations otherwise
+fn product_form(rotation: &AxisAngle, database: &IdentityDatabase) -> Vec<Gate> {
+    if rotation.angle < ANGLE_TOLERANCE {
+        return Vec::new();
+    }
+    if let Some(word) = database.lookup(rotation) {
+        return word.to_vec();
+    }
+    let matrix = rotation.matrix();
+    if let Some(sequence) = exact_clifford_t(&matrix) {
+        return peephole(sequence.gates, database);
+    }
+    if let Some(gate) = axis_rotation(rotation) {
+        return vec![gate];
+    }
+    let euler = decompose_zyz(&matrix);
+    let (lambda, theta, phi) = (snap(euler.lambda), snap(euler.theta), snap(euler.phi));
+    [Gate::Rz(lambda), Gate::Ry(theta), Gate::Rz(phi)]
+        .into_iter()
+        .filter(|g| !matches!(g, Gate::Rz(a) if *a == 0.0))
+        .collect()
+}
+
+/// a run of single-qubit gates rewritten from its product up to global
+/// phase, never longer than the input; panics on multi-qubit gates
+///
+/// The rewrite (see `product_form`) replaces the run only when it is
+/// shorter, or as long with fewer T gates, so simplifying a result again
+/// returns it unchanged.
+pub fn simplify_gates(gates: &[Gate], database: &IdentityDatabase) -> Vec<Gate> {
+    let rotation = AxisAngle::from_gates(gates).expect("single-qubit gates only");
+    let rewritten = product_form(&rotation, database);
+    let fewer_t = cost(&rewritten).0 < cost(gates).0;
+    if rewritten.len() < gates.len() || (rewritten.len() == gates.len() && fewer_t) {
+        rewritten
+    } else {
+        gates.to_vec()
+    }
+}
+
+/// T gates first, then gate count
+fn cost(gates: &[Gate]) -> (usize, usize) {
+    (gates.iter().filter(|g| matches!(g, Gate::T | Gate::Tdg)).count(), gates.len())
+}
+
+/// `simplify_gates` on every maximal run of single-qubit gates, keeping a
+/// run as it is unless the simplified form is cheaper; barriers end runs
+pub fn simplify_single_qubit_runs(circuit: &Circuit, database: &IdentityDatabase) -> Circuit {
+    circuit.map_segments(|segment| simplify_segment(segment, database))
+}
+
+fn simplify_segment(circuit: &Circuit, database: &IdentityDatabase) -> Circuit {
+    let mut out = Circuit::new(circuit.num_qubits());
+    let mut runs: Vec<Vec<Gate>> = vec![Vec::new(); circuit.num_qubits()];
+    // instruction index at which each qubit's current run began
+    let mut starts = vec![0; circuit.num_qubits()];
+    let flush = |out: &mut Circuit, run: &mut Vec<Gate>, q: usize| {
+        if run.is_empty() {
+            return;
+        }
+        let simplified = simplify_gates(run, database);
+        let best = if cost(&simplified) < cost(run) { &simplified } else { &*run };
+        for gate in best {
+            out.push(gate.clone(), &[q]);
+        }
+        run.clear();
+    };
+    for (k, inst) in circuit.instructions().iter().enumerate() {
+        if inst.gate.num_qubits() == 1 {
+            let q = inst.qubits[0];
+            if runs[q].is_empty() {
+                starts[q] = k;
+            }
+            runs[q].push(inst.gate.clone());
+            continue;
+        }
+        for &q in &inst.qubits {
+            flush(&mut out, &mut runs[q], q);
+        }
+        out.push(inst.gate.clone(), &inst.qubits);
+    }
+    // trailing runs in the order they started, so neighbouring gates stay
+    // adjacent for later pattern matching
+    let mut trailing: Vec<usize> = (0..circuit.num_qubits()).collect();
+    trailing.sort_by_key(|&q| starts[q]);
+    for q in trailing {
+        flush(&mut out, &mut runs[q], q);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+    use crate::simulator::rng::Rng;
+
+    fn same_up_to_phase(a: &[Gate], b: &[Gate]) -> bool {
+        let (a, b) = (AxisAngle::from_gates(a).unwrap(), AxisAngle::from_gates(b).unwrap());
+        a.same_rotation(&b, 1e-9)
+    }
+
+    #[test]
+    fn test_database_words_are_minimal() {
+        let database = IdentityDatabase::default();
+        // (HS)³ is a global phase
+        let cliffords = IdentityDatabase::new(3);
+        let h_s = [Gate::H, Gate::S, Gate::H, Gate::S, Gate::H, Gate::S];
+        assert_eq!(cliffords.lookup(&AxisAngle::from_gates(&h_s).unwrap()), Some(&[][..]));
+        let tt = AxisAngle::from_gates(&[const { Gate::T }; 2]).unwrap();
+        assert_eq!(database.lookup(&tt).unwrap(), [Gate::S]);
+        let hzh = AxisAngle::from_gates(&[Gate::H, Gate::Z, Gate::H]).unwrap();
+        assert_eq!(database.lookup(&hzh).unwrap(), [Gate::X]);
+    }
+
+    #[test]
+    fn test_simplify_to_shorter_forms() {
+        let database = IdentityDatabase::default();
+        // Demo 5: H X Y followed by its reversal is the identity
+        let demo = [Gate::H, Gate::X, Gate::Y, Gate::Y, Gate::X, Gate::H];
+        assert!(simplify_gates(&demo, &database).is_empty());
+        assert_eq!(simplify_gates(&[const { Gate::T }; 3], &database), [Gate::Z, Gate::Tdg]);
+        let quarter_turns = [Gate::Rz(FRAC_PI_4), Gate::Rz(FRAC_PI_4)];
+        assert_eq!(simplify_gates(&quarter_turns, &database), [Gate::S]);
+        // merged rotations about the same axis, and a generic product
+        let merged = simplify_gates(&[Gate::Rx(0.2), Gate::X, Gate::Rx(0.3)], &database);
+        assert!(matches!(merged[..], [Gate::Rx(_)]), "{:?}", merged);
+        let generic = [Gate::Rx(0.2), Gate::H, Gate::Ry(0.7), Gate::T];
+        let simplified = simplify_gates(&generic, &database);
+        assert!(simplified.len() <= 3 && same_up_to_phase(&simplified, &generic));
+        // the form depends only on the product
+        let long = [Gate::H, Gate::T, Gate::H, Gate::T, Gate::H, Gate::S, Gate::H, Gate::Z];
+        let other = [long.to_vec(), vec![Gate::X, Gate::X]].concat();
+        let simplified = simplify_gates(&long, &database);
+        assert_eq!(simplified, simplify_gates(&other, &database));
+        assert!(same_up_to_phase(&simplified, &long));
+    }
+
+    #[test]
+    fn test_simplify_is_never_longer_and_idempotent() {
+        let database = IdentityDatabase::default();
+        // a generic product needs three Euler rotations; two gates stay as they are
+        let pair = [Gate::Rx(0.3), Gate::Ry(0.5)];
+        assert_eq!(simplify_gates(&pair, &database), pair);
+        assert_eq!(simplify_gates(&[Gate::Rz(FRAC_PI_2)], &database), [Gate::Rz(FRAC_PI_2)]);
+        let mut rng = Rng::seed_from_u64(1);
+        for _ in 0..500 {
+            let gates: Vec<Gate> = (0..1 + rng.gen_range(4))
+                .map(|_| match rng.gen_range(5) {
+                    0 => Gate::Rx(rng.gen_range(8) as f64 * 0.4 - 1.0),
+                    1 => Gate::Ry(rng.gen_range(8) as f64 * 0.4 - 1.0),
+                    2 => Gate::Rz(rng.gen_range(8) as f64 * FRAC_PI_4),
+                    3 => Gate::H,
+                    _ => Gate::T,
+                })
+                .collect();
+            let simplified = simplify_gates(&gates, &database);
+            assert!(simplified.len() <= gates.len(), "{:?} -> {:?}", gates, simplified);
+            assert!(same_up_to_phase(&simplified, &gates));
+            assert_eq!(simplify_gates(&simplified, &database), simplified, "{:?}", gates);
+        }
+    }
+
+    #[test]
+    fn test_simplify_runs_in_circuit() {
+        let database = IdentityDatabase::default();
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).s(0).s(0).h(0).cx(0, 1).t(1).h(1).h(1).t(1).rz(0.4, 0).rz(0.1, 0);
+        let simplified = simplify_single_qubit_runs(&circuit, &database);
+        assert_eq!(simplified.len(), 4);
+        assert!(simplified.equivalent_to(&circuit, 1e-9));
+    }
+}
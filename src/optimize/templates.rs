@@ -0,0 +1,280 @@
+use crate::simulator::circuit::{Circuit, Instruction};
+
+/// circuit identity `pattern` = `replacement` on template qubits 0..k
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    name: &'static str,
+    pattern: Circuit,
+    replacement: Circuit,
+    /// every order of the pattern's gates that keeps each qubit's sequence
+    orderings: Vec<Vec<usize>>,
+}
+
+/// linear extensions of the per-qubit gate order of `circuit`
+fn orderings(circuit: &Circuit) -> Vec<Vec<usize>> {
+    fn extend(circuit: &Circuit, used: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        let instructions = circuit.instructions();
+        if used.len() == instructions.len() {
+            out.push(used.clone());
+            return;
+        }
+        for j in 0..instructions.len() {
+            let ready = !used.contains(&j)
+                && (0..j).all(|i| {
+                    used.contains(&i)
+                        || !instructions[i].qubits.iter().any(|q| {
+                            instructions[j].qubits.contains(q)
+                        })
+                });
+            if ready {
+                used.push(j);
+                extend(circuit, used, out);
+                used.pop();
+            }
+        }
+    }
+    let mut out = Vec::new();
+    extend(circuit, &mut Vec::new(), &mut out);
+    out
+}
+
+impl Template {
+    /// panics unless the replacement has fewer gates than the pattern, which
+    /// keeps repeated matching terminating
+    pub fn new(name: &'static str, pattern: Circuit, replacement: Circuit) -> Self {
+        assert_eq!(pattern.num_qubits(), replacement.num_qubits(), "qubit count mismatch");
+        assert!(replacement.len() < pattern.len(), "template {} does not shrink", name);
+        let orderings = orderings(&pattern);
+        Self { name, pattern, replacement, orderings }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn pattern(&self) -> &Circuit {
+        &self.pattern
+    }
+
+    pub fn replacement(&self) -> &Circuit {
+        &self.replacement
+    }
+}
+
+fn template(name: &'static str, k: usize, build: impl Fn(&mut Circuit, &mut Circuit)) -> Template {
+    let (mut pattern, mut replacement) = (Circuit::new(k), Circuit::new(k));
+    build(&mut pattern, &mut replacement);
+    Template::new(name, pattern, replacement)
+}
+
+/// built-in identities, exact up to global phase
+pub fn default_templates() -> Vec<Template> {
+    vec![
+        template("hxh", 1, |p, r| {
+            p.h(0).x(0).h(0);
+            r.z(0);
+        }),
+        template("hzh", 1, |p, r| {
+            p.h(0).z(0).h(0);
+            r.x(0);
+        }),
+        template("hyh", 1, |p, r| {
+            p.h(0).y(0).h(0);
+            r.y(0);
+        }),
+        template("tt", 1, |p, r| {
+            p.t(0).t(0);
+            r.s(0);
+        }),
+        template("tdg-tdg", 1, |p, r| {
+            p.tdg(0).tdg(0);
+            r.sdg(0);
+        }),
+        template("ss", 1, |p, r| {
+            p.s(0).s(0);
+            r.z(0);
+        }),
+        template("sdg-sdg", 1, |p, r| {
+            p.sdg(0).sdg(0);
+            r.z(0);
+        }),
+        template("zs", 1, |p, r| {
+            p.z(0).s(0);
+            r.sdg(0);
+        }),
+        template("sz", 1, |p, r| {
+            p.s(0).z(0);
+            r.sdg(0);
+        }),
+        template("z-sdg", 1, |p, r| {
+            p.z(0).sdg(0);
+            r.s(0);
+        }),
+        template("sdg-z", 1, |p, r| {
+            p.sdg(0).z(0);
+            r.s(0);
+        }),
+        template("h-cx-h", 2, |p, r| {
+            p.h(1).cx(0, 1).h(1);
+            r.cz(0, 1);
+        }),
+        template("h-cz-h", 2, |p, r| {
+            p.h(1).cz(0, 1).h(1);
+            r.cx(0, 1);
+        }),
+        template("reversed-cx", 2, |p, r| {
+            p.h(0).h(1).cx(0, 1).h(0).h(1);
+            r.cx(1, 0);
+        }),
+        template("cx-x-target", 2, |p, r| {
+            p.cx(0, 1).x(1).cx(0, 1);
+            r.x(1);
+        }),
+        template("cx-z-control", 2, |p, r| {
+            p.cx(0, 1).z(0).cx(0, 1);
+            r.z(0);
+        }),
+        template("cx-swap", 2, |p, r| {
+            p.cx(0, 1).cx(1, 0).cx(0, 1);
+            r.swap(0, 1);
+        }),
+    ]
+}
+
+/// bind `inst` to template instruction `want`, extending `mapping` injectively
+fn bind(inst: &Instruction, want: &Instruction, mapping: &mut [Option<usize>]) -> bool {
+    if inst.gate != want.gate || inst.qubits.len() != want.qubits.len() {
+        return false;
+    }
+    let mut trial = mapping.to_vec();
+    for (&actual, &t) in inst.qubits.iter().zip(&want.qubits) {
+        match trial[t] {
+            Some(q) if q != actual => return false,
+            Some(_) => {}
+            None if trial.contains(&Some(actual)) => return false,
+            None => trial[t] = Some(actual),
+        }
+    }
+    mapping.copy_from_slice(&trial);
+    true
+}
+
+/// indices matching the pattern gates in `order` starting at `start`, with
+/// the qubit mapping
+///
+/// Instructions on unmapped qubits are skipped; the match fails if anything
+/// between the first and last matched instruction touches a mapped qubit.
+fn match_at(
+    instructions: &[Instruction],
+    start: usize,
+    template: &Template,
+    order: &[usize],
+) -> Option<(Vec<usize>, Vec<usize>)> {
+    let pattern: Vec<&Instruction> =
+        order.iter().map(|&j| &template.pattern.instructions()[j]).collect();
+    let mut mapping = vec![None; template.pattern.num_qubits()];
+    if !bind(&instructions[start], pattern[0], &mut mapping) {
+        return None;
+    }
+    let mut matched = vec![start];
+    let mut position = start;
+    for &want in &pattern[1..] {
+        loop {
+            position += 1;
+            let inst = instructions.get(position)?;
+            let touches = inst.qubits.iter().any(|q| mapping.contains(&Some(*q)));
+            if bind(inst, want, &mut mapping) {
+                matched.push(position);
+                break;
+            }
+            if touches {
+                return None;
+            }
+        }
+    }
+    let qubits: Vec<usize> =
+        mapping.iter().map(|q| q.expect("every template qubit is used")).collect();
+    let blocked = (start..position)
+        .filter(|k| !matched.contains(k))
+        .any(|k| instructions[k].qubits.iter().any(|q| qubits.contains(q)));
+    (!blocked).then_some((matched, qubits))
+}
+
+/// replace template matches until none remain, each at its first matched
+/// gate; matches never span a barrier
+pub fn apply_templates(circuit: &Circuit, templates: &[Template]) -> Circuit {
+    circuit.map_segments(|segment| apply_to_segment(segment, templates))
+}
+
+fn apply_to_segment(circuit: &Circuit, templates: &[Template]) -> Circuit {
+    let mut instructions = circuit.instructions().to_vec();
+    'search: loop {
+        for start in 0..instructions.len() {
+            for template in templates {
+                let found = template
+                    .orderings
+                    .iter()
+                    .find_map(|order| match_at(&instructions, start, template, order));
+                let Some((matched, qubits)) = found else {
+                    continue;
+                };
+                let replacement = template.replacement.instructions().iter().map(|inst| {
+                    Instruction {
+                        gate: inst.gate.clone(),
+                        qubits: inst.qubits.iter().map(|&t| qubits[t]).collect(),
+                    }
+                });
+                let mut next: Vec<Instruction> = Vec::with_capacity(instructions.len());
+                for (k, inst) in instructions.iter().enumerate() {
+                    if k == start {
+                        next.extend(replacement.clone());
+                    }
+                    if !matched.contains(&k) {
+                        next.push(inst.clone());
+                    }
+                }
+                instructions = next;
+                continue 'search;
+            }
+        }
+        break;
+    }
+    let mut out = Circuit::new(circuit.num_qubits());
+    for inst in &instructions {
+        out.push(inst.gate.clone(), &inst.qubits);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::testing::ApproxEq;
+
+    #[test]
+    fn test_templates_are_identities() {
+        for t in default_templates() {
+            let (p, r) = (t.pattern().to_unitary(), t.replacement().to_unitary());
+            assert!(p.approx_eq(&r, 1e-10), "template {} is wrong", t.name());
+        }
+    }
+
+    #[test]
+    fn test_matches_through_unrelated_gates() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(1).h(2).t(0).cx(2, 1).h(2).h(1).cx(0, 2).h(0).x(0).h(0);
+        let rewritten = apply_templates(&circuit, &default_templates());
+        let names: Vec<&str> = rewritten.instructions().iter().map(|i| i.gate.name()).collect();
+        assert_eq!(names, ["cx", "t", "cx", "z"]);
+        assert_eq!(rewritten.instructions()[0].qubits, vec![1, 2]);
+        assert!(rewritten.to_unitary().approx_eq(&circuit.to_unitary(), 1e-10));
+    }
+
+    #[test]
+    fn test_blocked_match_is_rejected() {
+        // the cx on (0, 1) sits between the two halves of h-cx-h on qubit 1
+        let mut circuit = Circuit::new(3);
+        circuit.h(1).cx(2, 1).cx(0, 1).h(1);
+        assert_eq!(apply_templates(&circuit, &default_templates()).len(), 4);
+    }
+}
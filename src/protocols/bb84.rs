@@ -0,0 +1,121 @@
+use crate::simulator::gates::{h_matrix, x_matrix};
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+
+/// BB84 run parameters
+#[derive(Debug, Clone)]
+pub struct Bb84Config {
+    /// qubits Alice sends
+    pub num_qubits: usize,
+    /// Eve measures every qubit in a random basis and resends her result
+    pub eavesdropper: bool,
+    /// independent bit-flip probability on the channel
+    pub channel_flip_probability: f64,
+    /// QBER above which Alice and Bob abort
+    pub abort_threshold: f64,
+}
+
+impl Default for Bb84Config {
+    fn default() -> Self {
+        Self {
+            num_qubits: 1000,
+            eavesdropper: false,
+            channel_flip_probability: 0.0,
+            abort_threshold: 0.11,
+        }
+    }
+}
+
+/// sifted keys and error statistics
+#[derive(Debug, Clone)]
+pub struct Bb84Result {
+    pub alice_key: Vec<bool>,
+    pub bob_key: Vec<bool>,
+    /// quantum bit error rate over the sifted key
+    pub qber: f64,
+    /// false when the QBER exceeded the abort threshold
+    pub secure: bool,
+}
+
+/// encode `bit` in the Z (false) or X (true) basis
+fn prepare(bit: bool, diagonal: bool) -> Register {
+    let mut qubit = Register::new(1);
+    if bit {
+        qubit.apply_matrix2(0, x_matrix());
+    }
+    if diagonal {
+        qubit.apply_matrix2(0, h_matrix());
+    }
+    qubit
+}
+
+/// measure in the Z (false) or X (true) basis
+fn measure(qubit: &mut Register, diagonal: bool, rng: &mut Rng) -> bool {
+    if diagonal {
+        qubit.apply_matrix2(0, h_matrix());
+    }
+    qubit.measure(0, rng)
+}
+
+/// prepare-and-measure BB84 with optional intercept-resend eavesdropping
+pub fn bb84(config: &Bb84Config, rng: &mut Rng) -> Bb84Result {
+    let mut alice_key = Vec::new();
+    let mut bob_key = Vec::new();
+    for _ in 0..config.num_qubits {
+        let bit = rng.gen_bool(0.5);
+        let alice_basis = rng.gen_bool(0.5);
+        let mut qubit = prepare(bit, alice_basis);
+        if config.eavesdropper {
+            let eve_basis = rng.gen_bool(0.5);
+            let intercepted = measure(&mut qubit, eve_basis, rng);
+            qubit = prepare(intercepted, eve_basis);
+        }
+        if rng.gen_bool(config.channel_flip_probability) {
+            qubit.apply_matrix2(0, x_matrix());
+        }
+        let bob_basis = rng.gen_bool(0.5);
+        let received = measure(&mut qubit, bob_basis, rng);
+        // sifting: keep rounds where the publicly announced bases agree
+        if alice_basis == bob_basis {
+            alice_key.push(bit);
+            bob_key.push(received);
+        }
+    }
+    let errors = alice_key.iter().zip(&bob_key).filter(|(a, b)| a != b).count();
+    let qber = errors as f64 / alice_key.len().max(1) as f64;
+    Bb84Result {
+        alice_key,
+        bob_key,
+        qber,
+        secure: qber <= config.abort_threshold,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_channel_agrees() {
+        let mut rng = Rng::seed_from_u64(84);
+        let result = bb84(&Bb84Config::default(), &mut rng);
+        assert_eq!(result.alice_key, result.bob_key);
+        assert!(result.secure);
+        // about half the rounds survive sifting
+        assert!((result.alice_key.len() as f64 / 1000.0 - 0.5).abs() < 0.06);
+    }
+
+    #[test]
+    fn test_eavesdropper_detected() {
+        let mut rng = Rng::seed_from_u64(85);
+        let config = Bb84Config {
+            num_qubits: 4000,
+            eavesdropper: true,
+            ..Bb84Config::default()
+        };
+        let result = bb84(&config, &mut rng);
+        // intercept-resend produces a 25% error rate
+        assert!((result.qber - 0.25).abs() < 0.03, "qber = {}", result.qber);
+        assert!(!result.secure);
+    }
+}
@@ -0,0 +1,124 @@
+use std::f64::consts::FRAC_PI_4;
+use crate::noise::{noisy_counts, NoiseModel};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::rng::Rng;
+
+/// CHSH measurement settings, angles in the X–Z plane measured from Z
+#[derive(Debug, Clone)]
+pub struct ChshConfig {
+    pub alice: [f64; 2],
+    pub bob: [f64; 2],
+    /// shots per setting pair
+    pub shots: usize,
+}
+
+impl Default for ChshConfig {
+    /// Tsirelson-optimal angles for |Φ+⟩, reaching S = 2√2
+    fn default() -> Self {
+        Self {
+            alice: [0.0, 2.0 * FRAC_PI_4],
+            bob: [FRAC_PI_4, -FRAC_PI_4],
+            shots: 1000,
+        }
+    }
+}
+
+/// measured correlators E(a_i, b_j) and S with one-sigma error bars
+#[derive(Debug, Clone)]
+pub struct ChshResult {
+    pub correlators: [[f64; 2]; 2],
+    pub correlator_errors: [[f64; 2]; 2],
+    /// S = E(a0,b0) + E(a0,b1) + E(a1,b0) − E(a1,b1)
+    pub s: f64,
+    pub s_error: f64,
+}
+
+impl ChshResult {
+    /// |S| exceeds the local-hidden-variable bound of 2 by `sigmas` error bars
+    pub fn violates_local_bound(&self, sigmas: f64) -> bool {
+        self.s.abs() - 2.0 > sigmas * self.s_error
+    }
+}
+
+/// |Φ+⟩ on qubits 0 and 1, each rotated so a Z measurement reads the
+/// observable cos θ·Z + sin θ·X at Alice's angle `a` and Bob's angle `b`
+pub fn chsh_circuit(a: f64, b: f64) -> Circuit {
+    let mut circuit = Circuit::new(2);
+    circuit.h(0).cx(0, 1).ry(-a, 0).ry(-b, 1);
+    circuit
+}
+
+/// run all four setting pairs under `noise` (`NoiseModel::ideal()` for an
+/// ideal backend) and estimate S from the outcome parities
+pub fn chsh_experiment(config: &ChshConfig, noise: &NoiseModel, rng: &mut Rng) -> ChshResult {
+    assert!(config.shots > 0, "need at least one shot");
+    let mut correlators = [[0.0; 2]; 2];
+    let mut correlator_errors = [[0.0; 2]; 2];
+    for (i, &a) in config.alice.iter().enumerate() {
+        for (j, &b) in config.bob.iter().enumerate() {
+            let counts = noisy_counts(&chsh_circuit(a, b), noise, config.shots, rng);
+            let agree: usize = counts
+                .iter()
+                .filter(|(&outcome, _)| outcome == 0b00 || outcome == 0b11)
+                .map(|(_, &n)| n)
+                .sum();
+            let e = (2.0 * agree as f64 - config.shots as f64) / config.shots as f64;
+            correlators[i][j] = e;
+            // each shot is a ±1 variable with variance 1 − E²
+            correlator_errors[i][j] = ((1.0 - e * e) / config.shots as f64).sqrt();
+        }
+    }
+    let s = correlators[0][0] + correlators[0][1] + correlators[1][0] - correlators[1][1];
+    let s_error = correlator_errors.iter().flatten().map(|e| e * e).sum::<f64>().sqrt();
+    ChshResult {
+        correlators,
+        correlator_errors,
+        s,
+        s_error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::SQRT_2;
+
+    #[test]
+    fn test_ideal_backend_reaches_tsirelson_bound() {
+        let mut rng = Rng::seed_from_u64(188);
+        let result = chsh_experiment(&ChshConfig::default(), &NoiseModel::ideal(), &mut rng);
+        assert!((result.s - 2.0 * SQRT_2).abs() < 4.0 * result.s_error, "{:?}", result);
+        assert!(result.violates_local_bound(5.0));
+    }
+
+    #[test]
+    fn test_depolarizing_noise_shrinks_s() {
+        let p = 0.2;
+        let noise = NoiseModel::depolarizing(0.0, p);
+        let config = ChshConfig {
+            shots: 4000,
+            ..ChshConfig::default()
+        };
+        let mut rng = Rng::seed_from_u64(189);
+        let result = chsh_experiment(&config, &noise, &mut rng);
+        // the CX error leaves a Werner state with visibility 1 − 16p/15
+        let expected = 2.0 * SQRT_2 * (1.0 - 16.0 * p / 15.0);
+        assert!((result.s - expected).abs() < 4.0 * result.s_error, "{:?}", result);
+        let heavy = chsh_experiment(&config, &NoiseModel::depolarizing(0.0, 0.5), &mut rng);
+        assert!(!heavy.violates_local_bound(0.0), "{:?}", heavy);
+    }
+
+    #[test]
+    fn test_aligned_settings_give_perfect_correlation() {
+        let config = ChshConfig {
+            alice: [0.3, 1.1],
+            bob: [0.3, 1.1],
+            shots: 200,
+        };
+        let mut rng = Rng::seed_from_u64(190);
+        let result = chsh_experiment(&config, &NoiseModel::ideal(), &mut rng);
+        assert_eq!(result.correlators[0][0], 1.0);
+        assert_eq!(result.correlators[1][1], 1.0);
+        assert_eq!(result.correlator_errors[0][0], 0.0);
+    }
+}
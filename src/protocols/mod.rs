@@ -0,0 +1,7 @@
+pub mod superdense;
+pub mod bb84;
+pub mod chsh;
+
+pub use superdense::{superdense_circuit, superdense_coding};
+pub use bb84::{bb84, Bb84Config, Bb84Result};
+pub use chsh::{chsh_circuit, chsh_experiment, ChshConfig, ChshResult};
@@ -0,0 +1,46 @@
+use crate::simulator::circuit::Circuit;
+use crate::simulator::register::Register;
+use crate::simulator::rng::Rng;
+
+/// full superdense-coding round trip for two classical bits
+///
+/// Qubit 0 is Alice's half of |Φ+⟩, qubit 1 Bob's. Alice encodes (b0, b1) with
+/// Z^b0 X^b1 on her qubit and sends it; Bob decodes with CNOT then H, so the
+/// register ends in |b0 b1⟩ read as qubit 0 = b0, qubit 1 = b1. The qubits
+/// are named `alice` and `bob`.
+pub fn superdense_circuit(bits: (bool, bool)) -> Circuit {
+    let mut circuit = Circuit::new(2);
+    circuit.name_qubit(0, "alice").name_qubit(1, "bob");
+    let (alice, bob) = (circuit["alice"], circuit["bob"]);
+    circuit.h(alice).cx(alice, bob);
+    if bits.1 {
+        circuit.x(alice);
+    }
+    if bits.0 {
+        circuit.z(alice);
+    }
+    circuit.cx(alice, bob).h(alice);
+    circuit
+}
+
+/// send two bits with one qubit and return what Bob measures
+pub fn superdense_coding(bits: (bool, bool), rng: &mut Rng) -> (bool, bool) {
+    let mut register = Register::new(2);
+    register.apply_circuit(&superdense_circuit(bits));
+    let b0 = register.measure(0, rng);
+    let b1 = register.measure(1, rng);
+    (b0, b1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_messages_decoded() {
+        let mut rng = Rng::seed_from_u64(1);
+        for bits in [(false, false), (false, true), (true, false), (true, true)] {
+            assert_eq!(superdense_coding(bits, &mut rng), bits);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::f64::consts::PI;
+use num_complex::Complex64;
+use crate::simulator::matrix::Matrix;
+use crate::simulator::operator::Operator;
+
+/// time profile Ω(t) of the in-phase drive quadrature, in rad/ns
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Envelope {
+    Constant { amplitude: f64 },
+    /// centred Gaussian of width `sigma` ns
+    Gaussian { amplitude: f64, sigma: f64 },
+    /// Gaussian plus a quadrature `beta`·dΩ/dt; beta ≈ −1/α suppresses
+    /// leakage to |2⟩ and −1/(2α) the accompanying phase error
+    Drag { amplitude: f64, sigma: f64, beta: f64 },
+}
+
+/// resonant-frame drive on one transmon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pulse {
+    pub envelope: Envelope,
+    /// length in ns
+    pub duration: f64,
+    /// qubit minus drive frequency, rad/ns
+    pub detuning: f64,
+    /// drive phase; 0 rotates about X, π/2 about Y
+    pub phase: f64,
+}
+
+/// driven system: a two-level qubit or a three-level transmon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transmon {
+    pub levels: usize,
+    /// ω₁₂ − ω₀₁ in rad/ns (negative for transmons)
+    pub anharmonicity: f64,
+}
+
+impl Transmon {
+    /// ideal two-level system
+    pub fn qubit() -> Self {
+        Self { levels: 2, anharmonicity: 0.0 }
+    }
+
+    /// three levels with the given anharmonicity
+    pub fn with_anharmonicity(anharmonicity: f64) -> Self {
+        Self { levels: 3, anharmonicity }
+    }
+}
+
+/// effective gate of a pulse on the computational subspace
+#[derive(Debug, Clone, PartialEq)]
+pub struct PulseOutcome {
+    /// ⟨i|U|j⟩ for i, j ∈ {0, 1}; not unitary when population leaks
+    pub unitary: Operator,
+    /// population left outside {|0⟩, |1⟩}, averaged over both inputs
+    pub leakage: f64,
+}
+
+impl Envelope {
+    /// (Ω_x, Ω_y) at time `t` of a pulse lasting `duration`
+    fn quadratures(&self, t: f64, duration: f64) -> (f64, f64) {
+        let gaussian = |amplitude: f64, sigma: f64| {
+            let x = t - duration / 2.0;
+            let value = amplitude * (-x * x / (2.0 * sigma * sigma)).exp();
+            (value, -x / (sigma * sigma) * value)
+        };
+        match *self {
+            Envelope::Constant { amplitude } => (amplitude, 0.0),
+            Envelope::Gaussian { amplitude, sigma } => (gaussian(amplitude, sigma).0, 0.0),
+            Envelope::Drag { amplitude, sigma, beta } => {
+                let (value, slope) = gaussian(amplitude, sigma);
+                (value, beta * slope)
+            }
+        }
+    }
+}
+
+impl Pulse {
+    /// constant drive of `duration` ns rotating by `angle` about X
+    pub fn rabi(angle: f64, duration: f64) -> Self {
+        let envelope = Envelope::Constant { amplitude: angle / duration };
+        Self { envelope, duration, detuning: 0.0, phase: 0.0 }
+    }
+
+    /// Gaussian whose area over `duration` is `angle`; with `beta` set the
+    /// DRAG quadrature is added
+    pub fn gaussian(angle: f64, duration: f64, sigma: f64, beta: Option<f64>) -> Self {
+        let steps = 2000;
+        let dt = duration / steps as f64;
+        let area: f64 = (0..steps)
+            .map(|k| {
+                let x = (k as f64 + 0.5) * dt - duration / 2.0;
+                (-x * x / (2.0 * sigma * sigma)).exp() * dt
+            })
+            .sum();
+        let amplitude = angle / area;
+        let envelope = match beta {
+            None => Envelope::Gaussian { amplitude, sigma },
+            Some(beta) => Envelope::Drag { amplitude, sigma, beta },
+        };
+        Self { envelope, duration, detuning: 0.0, phase: 0.0 }
+    }
+
+    pub fn with_detuning(mut self, detuning: f64) -> Self {
+        self.detuning = detuning;
+        self
+    }
+
+    pub fn with_phase(mut self, phase: f64) -> Self {
+        self.phase = phase;
+        self
+    }
+
+    /// H(t) = Δ n + α/2 n(n−1) + ½(ε b† + ε* b), ε = (Ω_x + iΩ_y) e^{iφ}
+    fn hamiltonian(&self, device: &Transmon, t: f64) -> Matrix {
+        let (x, y) = self.envelope.quadratures(t, self.duration);
+        let epsilon = Complex64::new(x, y) * Complex64::from_polar(1.0, self.phase);
+        Matrix::from_fn(device.levels, device.levels, |i, j| {
+            if i == j {
+                let n = i as f64;
+                Complex64::new(self.detuning * n + device.anharmonicity / 2.0 * n * (n - 1.0), 0.0)
+            } else if i == j + 1 {
+                // ⟨n+1|b†|n⟩ = √(n+1)
+                epsilon * (i as f64).sqrt() / 2.0
+            } else if j == i + 1 {
+                epsilon.conj() * (j as f64).sqrt() / 2.0
+            } else {
+                Complex64::new(0.0, 0.0)
+            }
+        })
+    }
+
+    /// propagate over `steps` midpoint slices and project onto the qubit
+    pub fn simulate(&self, device: &Transmon, steps: usize) -> PulseOutcome {
+        assert!((2..=3).contains(&device.levels), "two or three levels");
+        assert!(steps > 0, "need at least one step");
+        let dt = self.duration / steps as f64;
+        let mut propagator = Matrix::identity(device.levels);
+        for k in 0..steps {
+            let h = self.hamiltonian(device, (k as f64 + 0.5) * dt);
+            let step = h.scaled(Complex64::new(0.0, -dt)).expm();
+            propagator = &step * &propagator;
+        }
+        let unitary = [
+            [propagator[(0, 0)], propagator[(0, 1)]],
+            [propagator[(1, 0)], propagator[(1, 1)]],
+        ];
+        let kept: f64 = unitary.iter().flatten().map(|z| z.norm_sqr()).sum();
+        PulseOutcome { unitary: Operator::from(unitary), leakage: 1.0 - kept / 2.0 }
+    }
+}
+
+/// average gate fidelity of a (possibly leaky) 2×2 block against `target`,
+/// ignoring global phase: (Tr M M† + |Tr M|²) / 6 with M = target† U
+pub fn average_gate_fidelity(unitary: &Operator, target: &Operator) -> f64 {
+    assert!(unitary.num_qubits() == 1 && target.num_qubits() == 1, "one-qubit operators");
+    let m = &target.dagger() * unitary;
+    (m.norm().powi(2) + m.trace().norm_sqr()) / 6.0
+}
+
+/// Rabi frequency that completes a π rotation in `duration` ns
+pub fn pi_pulse_amplitude(duration: f64) -> f64 {
+    PI / duration
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::Gate;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_resonant_drive_gives_rotation() {
+        let qubit = Transmon::qubit();
+        let outcome = Pulse::rabi(FRAC_PI_2, 20.0).simulate(&qubit, 50);
+        let rx = Gate::Rx(FRAC_PI_2).operator();
+        assert!(average_gate_fidelity(&outcome.unitary, &rx) > 1.0 - 1e-12);
+        assert!(outcome.leakage.abs() < 1e-12);
+        let shaped = Pulse::gaussian(PI, 40.0, 8.0, None).simulate(&qubit, 400);
+        assert!(average_gate_fidelity(&shaped.unitary, &Gate::X.operator()) > 1.0 - 1e-6);
+        // detuning by half the Rabi frequency spoils the π pulse
+        let detuned = Pulse::rabi(PI, 20.0).with_detuning(pi_pulse_amplitude(20.0) / 2.0);
+        let unitary = detuned.simulate(&qubit, 200).unitary;
+        let fidelity = average_gate_fidelity(&unitary, &Gate::X.operator());
+        assert!(fidelity < 0.95);
+    }
+
+    #[test]
+    fn test_drag_reduces_leakage_on_a_transmon() {
+        // 250 MHz anharmonicity, 6 ns π pulse
+        let alpha = -2.0 * PI * 0.25;
+        let transmon = Transmon::with_anharmonicity(alpha);
+        let run = |beta| Pulse::gaussian(PI, 6.0, 1.5, beta).simulate(&transmon, 600);
+        let (plain, drag, half) = (run(None), run(Some(-1.0 / alpha)), run(Some(-0.5 / alpha)));
+        assert!(plain.leakage > 1e-2, "leakage {}", plain.leakage);
+        assert!(drag.leakage < plain.leakage / 3.0, "{} vs {}", drag.leakage, plain.leakage);
+        let x = Gate::X.operator();
+        let infidelity = |o: &PulseOutcome| 1.0 - average_gate_fidelity(&o.unitary, &x);
+        assert!(infidelity(&half) < infidelity(&plain) / 10.0);
+    }
+}
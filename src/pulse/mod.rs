@@ -0,0 +1,5 @@
+pub mod drive;
+
+pub use drive::{
+    average_gate_fidelity, pi_pulse_amplitude, Envelope, Pulse, PulseOutcome, Transmon,
+};
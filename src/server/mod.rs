@@ -0,0 +1,473 @@
+//! local REST backend: submit a circuit, poll the job, fetch its counts
+//!
+//! | method | path               | body / result                               |
+//! |--------|--------------------|---------------------------------------------|
+//! | POST   | `/jobs`            | QASM text, or JSON `{"qasm" \| "circuit", "shots"?, "seed"?}` |
+//! | GET    | `/jobs/{id}`       | `{"job_id", "status", "error"?}`            |
+//! | GET    | `/jobs/{id}/result`| `{"job_id", "shots", "counts", "report"}`   |
+//! | GET    | `/jobs/{id}/result?format=qiskit` | Qiskit `Result.to_dict()` JSON |
+//! | DELETE | `/jobs/{id}`       | cancels a queued or running job             |
+
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use crate::interop::{qiskit_result, Json, QiskitExperiment};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::report::{run_with_handle, RunHandle, RunResult};
+use crate::simulator::rng::Rng;
+
+/// shots taken when a submission does not say
+pub const DEFAULT_SHOTS: usize = 1024;
+
+/// largest request body accepted, in bytes
+pub const MAX_BODY_BYTES: usize = 16 << 20;
+
+/// widest circuit the server will run on its state vector
+pub const MAX_SERVER_QUBITS: usize = 28;
+
+/// most shots one submission may ask for
+pub const MAX_SERVER_SHOTS: usize = 1 << 20;
+
+/// most jobs the table keeps; later submissions are refused
+pub const MAX_JOBS: usize = 4096;
+
+/// most jobs simulated at once, each on its own worker thread
+pub const MAX_RUNNING_JOBS: usize = 8;
+
+/// how long a connection may stall while sending its request
+pub const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// longest request line or header line accepted, in bytes
+pub const MAX_HEADER_LINE_BYTES: usize = 8 << 10;
+
+/// most header lines one request may send
+pub const MAX_HEADERS: usize = 100;
+
+/// most connections served at once; later ones are answered 503 and closed
+pub const MAX_CONNECTIONS: usize = 64;
+
+#[derive(Debug, Clone)]
+enum JobStatus {
+    Running,
+    Done(RunResult),
+    Failed(String),
+}
+
+struct Job {
+    num_qubits: usize,
+    seed: u64,
+    status: JobStatus,
+    handle: RunHandle,
+}
+
+/// job table shared between the listener and the worker threads
+#[derive(Clone, Default)]
+pub struct Server {
+    jobs: Arc<Mutex<Vec<Job>>>,
+}
+
+/// circuit and run settings of a submission
+fn parse_submission(body: &str) -> Result<(Circuit, usize, u64), String> {
+    if body.trim_start().starts_with("OPENQASM") {
+        return Ok((Circuit::from_qasm_with_limit(body, MAX_SERVER_QUBITS)?, DEFAULT_SHOTS, 0));
+    }
+    let request = Json::parse(body)?;
+    let circuit = match (request.get("qasm").and_then(Json::as_str), request.get("circuit")) {
+        (Some(qasm), None) => Circuit::from_qasm_with_limit(qasm, MAX_SERVER_QUBITS)?,
+        (None, Some(circuit)) => Circuit::from_json(circuit)?,
+        _ => return Err("give exactly one of 'qasm' or 'circuit'".into()),
+    };
+    let field = |name: &str, default: usize| match request.get(name) {
+        None => Ok(default),
+        Some(value) => value.as_usize().ok_or(format!("'{}' must be a non-negative integer", name)),
+    };
+    Ok((circuit, field("shots", DEFAULT_SHOTS)?, field("seed", 0)? as u64))
+}
+
+fn error(status: u16, message: impl Into<String>) -> (u16, Json) {
+    (status, Json::object([("error", Json::from(message.into()))]))
+}
+
+/// one line of the request head, or `None` once it runs past
+/// `MAX_HEADER_LINE_BYTES` without ending
+fn read_head_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    let limit = MAX_HEADER_LINE_BYTES as u64 + 1;
+    let read = reader.take(limit).read_line(&mut line)?;
+    Ok((read < limit as usize || line.ends_with('\n')).then_some(line))
+}
+
+/// method, target and content length of a request, or `None` when its head
+/// has a line or more header lines than allowed
+fn read_head(reader: &mut impl BufRead) -> io::Result<Option<(String, String, usize)>> {
+    let Some(request_line) = read_head_line(reader)? else { return Ok(None) };
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let path = parts.next().unwrap_or("").to_string();
+    let mut content_length = 0;
+    for _ in 0..=MAX_HEADERS {
+        let Some(header) = read_head_line(reader)? else { return Ok(None) };
+        if header.trim().is_empty() {
+            return Ok(Some((method, path, content_length)));
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// write `status` and `json` as the whole HTTP response
+fn respond(stream: &mut TcpStream, (status, json): (u16, Json)) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Payload Too Large",
+    };
+    let body = json.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// one of the `MAX_CONNECTIONS` slots, given back when its connection ends
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// route one request to a status code and JSON body
+    pub fn handle(&self, method: &str, target: &str, body: &str) -> (u16, Json) {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let qiskit = query.split('&').any(|pair| pair == "format=qiskit");
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match (method, segments.as_slice()) {
+            ("POST", ["jobs"]) => self.submit(body),
+            (_, ["jobs", id, rest @ ..]) => {
+                let Some(id) = id.parse::<usize>().ok().filter(|&id| id < self.len()) else {
+                    return error(404, format!("no job '{}'", id));
+                };
+                match (method, rest) {
+                    ("GET", []) => (200, self.status(id)),
+                    ("GET", ["result"]) => self.result(id, qiskit),
+                    ("DELETE", []) => {
+                        self.jobs.lock().unwrap()[id].handle.cancel();
+                        (202, self.status(id))
+                    }
+                    _ => error(405, format!("{} not allowed on {}", method, path)),
+                }
+            }
+            _ => error(404, format!("no route for {} {}", method, path)),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.jobs.lock().unwrap().len()
+    }
+
+    fn submit(&self, body: &str) -> (u16, Json) {
+        let (circuit, shots, seed) = match parse_submission(body) {
+            Ok(submission) => submission,
+            Err(message) => return error(400, message),
+        };
+        if circuit.num_qubits() > MAX_SERVER_QUBITS {
+            return error(413, format!("circuits are limited to {} qubits", MAX_SERVER_QUBITS));
+        }
+        if shots > MAX_SERVER_SHOTS {
+            return error(413, format!("jobs are limited to {} shots", MAX_SERVER_SHOTS));
+        }
+        let handle = RunHandle::new();
+        let id = {
+            let mut jobs = self.jobs.lock().unwrap();
+            if jobs.len() == MAX_JOBS {
+                return error(503, format!("the server keeps at most {} jobs", MAX_JOBS));
+            }
+            let running = jobs.iter().filter(|job| matches!(job.status, JobStatus::Running));
+            if running.count() == MAX_RUNNING_JOBS {
+                return error(503, format!("{} jobs are already running", MAX_RUNNING_JOBS));
+            }
+            let status = JobStatus::Running;
+            let num_qubits = circuit.num_qubits();
+            jobs.push(Job { num_qubits, seed, status, handle: handle.clone() });
+            jobs.len() - 1
+        };
+        let jobs = Arc::clone(&self.jobs);
+        thread::spawn(move || {
+            let mut rng = Rng::seed_from_u64(seed);
+            let status = match run_with_handle(&circuit, shots, &mut rng, &handle, |_| {}) {
+                Ok(result) => JobStatus::Done(result),
+                Err(message) => JobStatus::Failed(message),
+            };
+            jobs.lock().unwrap()[id].status = status;
+        });
+        (202, self.status(id))
+    }
+
+    fn status(&self, id: usize) -> Json {
+        let jobs = self.jobs.lock().unwrap();
+        let mut entries = vec![("job_id", Json::from(id))];
+        let status = match &jobs[id].status {
+            JobStatus::Running => "running",
+            JobStatus::Done(_) => "done",
+            JobStatus::Failed(message) => {
+                entries.push(("error", message.as_str().into()));
+                "failed"
+            }
+        };
+        entries.push(("status", status.into()));
+        Json::object(entries)
+    }
+
+    fn result(&self, id: usize, qiskit: bool) -> (u16, Json) {
+        let (width, seed, result) = {
+            let jobs = self.jobs.lock().unwrap();
+            match &jobs[id].status {
+                JobStatus::Done(result) => (jobs[id].num_qubits, jobs[id].seed, result.clone()),
+                _ => {
+                    drop(jobs);
+                    return (409, self.status(id));
+                }
+            }
+        };
+        if qiskit {
+            let name = format!("job-{}", id);
+            let experiment = QiskitExperiment { name: &name, result: &result, seed: Some(seed) };
+            return (200, qiskit_result(&id.to_string(), &[experiment]));
+        }
+        let counts: BTreeMap<String, Json> = result
+            .counts
+            .iter()
+            .map(|(&outcome, &n)| (format!("{:0width$b}", outcome, width = width), n.into()))
+            .collect();
+        let report = &result.report;
+        let report = Json::object([
+            ("backend", Json::from(report.backend)),
+            ("num_qubits", report.num_qubits.into()),
+            ("depth", report.depth.into()),
+            ("total_gates", report.total_gates().into()),
+            ("wall_time_s", report.wall_time.as_secs_f64().into()),
+            ("peak_memory_bytes", report.peak_memory_bytes.into()),
+        ]);
+        let shots = result.report.shots;
+        let entries = [
+            ("job_id", Json::from(id)),
+            ("shots", shots.into()),
+            ("counts", Json::Object(counts)),
+            ("report", report),
+        ];
+        (200, Json::object(entries))
+    }
+
+    /// answer one HTTP/1.1 request on `stream` and close it
+    fn serve_connection(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let response = match read_head(&mut reader)? {
+            None => error(431, "request line or headers too large"),
+            Some((_, _, length)) if length > MAX_BODY_BYTES => {
+                error(413, "request body too large")
+            }
+            Some((method, path, length)) => {
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body)?;
+                match String::from_utf8(body) {
+                    Ok(body) => self.handle(&method, &path, &body),
+                    Err(_) => error(400, "body is not UTF-8"),
+                }
+            }
+        };
+        respond(&mut stream, response)
+    }
+
+    /// accept connections on `listener` until it fails, serving at most
+    /// `MAX_CONNECTIONS` of them at once
+    pub fn listen(&self, listener: TcpListener) -> io::Result<()> {
+        let open = Arc::new(AtomicUsize::new(0));
+        for stream in listener.incoming() {
+            let mut stream = stream?;
+            if open.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                open.fetch_sub(1, Ordering::SeqCst);
+                let busy = format!("the server handles at most {} connections", MAX_CONNECTIONS);
+                // best effort: the client may already be gone
+                let _ = respond(&mut stream, error(503, busy));
+                continue;
+            }
+            let slot = ConnectionSlot(Arc::clone(&open));
+            let server = self.clone();
+            thread::spawn(move || {
+                let _slot = slot;
+                server.serve_connection(stream)
+            });
+        }
+        Ok(())
+    }
+}
+
+/// bind `addr` and serve forever
+pub fn serve(addr: impl ToSocketAddrs) -> io::Result<()> {
+    Server::new().listen(TcpListener::bind(addr)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait_done(server: &Server, id: usize) -> Json {
+        for _ in 0..500 {
+            let (_, status) = server.handle("GET", &format!("/jobs/{}", id), "");
+            if status.get("status").and_then(Json::as_str) != Some("running") {
+                return status;
+            }
+            thread::sleep(Duration::from_millis(2));
+        }
+        panic!("job {} did not finish", id);
+    }
+
+    #[test]
+    fn test_submit_poll_and_fetch_counts() {
+        let server = Server::new();
+        let qasm = r#"{"qasm": "OPENQASM 2.0; qreg q[2]; h q[0]; cx q[0], q[1];", "shots": 200}"#;
+        let (code, job) = server.handle("POST", "/jobs", qasm);
+        assert_eq!((code, job.get("job_id").and_then(Json::as_usize)), (202, Some(0)));
+        assert_eq!(wait_done(&server, 0).get("status").and_then(Json::as_str), Some("done"));
+        let (code, result) = server.handle("GET", "/jobs/0/result", "");
+        assert_eq!(code, 200);
+        let counts = result.get("counts").unwrap();
+        let total: usize = ["00", "11"].iter().filter_map(|k| counts.get(k)?.as_usize()).sum();
+        assert_eq!(total, 200);
+        let (_, qiskit) = server.handle("GET", "/jobs/0/result?format=qiskit", "");
+        let experiment = &qiskit.get("results").and_then(Json::as_array).unwrap()[0];
+        let counts = experiment.get("data").unwrap().get("counts").unwrap();
+        assert_eq!(counts.get("0x3"), result.get("counts").unwrap().get("11"));
+
+        let mut circuit = Circuit::new(1);
+        circuit.x(0);
+        let body = Json::object([("circuit", circuit.to_json()), ("shots", 5usize.into())]);
+        let (_, job) = server.handle("POST", "/jobs", &body.to_string());
+        assert_eq!(job.get("job_id").and_then(Json::as_usize), Some(1));
+        wait_done(&server, 1);
+        let (_, result) = server.handle("GET", "/jobs/1/result", "");
+        assert_eq!(result.get("counts").unwrap().get("1").and_then(Json::as_usize), Some(5));
+    }
+
+    #[test]
+    fn test_errors_and_routing() {
+        let server = Server::new();
+        assert_eq!(server.handle("POST", "/jobs", "{").0, 400);
+        assert_eq!(server.handle("POST", "/jobs", r#"{"shots": 3}"#).0, 400);
+        assert_eq!(server.handle("POST", "/jobs", &"[".repeat(200_000)).0, 400);
+        assert_eq!(server.handle("GET", "/jobs/7", "").0, 404);
+        assert_eq!(server.handle("GET", "/nothing", "").0, 404);
+        server.handle("POST", "/jobs", "OPENQASM 2.0; qreg q[1]; h q[0];");
+        assert_eq!(server.handle("PUT", "/jobs/0", "").0, 405);
+        let (code, json) = server.handle("POST", "/jobs", "OPENQASM 2.0; qreg q[100000000000];");
+        assert_eq!(code, 400);
+        assert!(json.get("error").and_then(Json::as_str).unwrap().contains("exceed 28 qubits"));
+        let qasm = "OPENQASM 2.0; qreg q[18446744073709551615]; qreg r[1];";
+        assert_eq!(server.handle("POST", "/jobs", qasm).0, 400);
+        let greedy = r#"{"qasm": "OPENQASM 2.0; qreg q[1];", "shots": 1000000000}"#;
+        assert_eq!(server.handle("POST", "/jobs", greedy).0, 413);
+    }
+
+    #[test]
+    fn test_running_jobs_are_capped() {
+        let server = Server::new();
+        for _ in 0..MAX_RUNNING_JOBS {
+            let handle = RunHandle::new();
+            let job = Job { num_qubits: 1, seed: 0, status: JobStatus::Running, handle };
+            server.jobs.lock().unwrap().push(job);
+        }
+        assert_eq!(server.handle("POST", "/jobs", "OPENQASM 2.0; qreg q[1];").0, 503);
+    }
+
+    #[test]
+    fn test_http_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = Server::new();
+        let background = server.clone();
+        thread::spawn(move || background.listen(listener));
+        let request = |method: &str, path: &str, body: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            let length = body.len();
+            let head = format!("{} {} HTTP/1.1\r\nContent-Length: {}\r\n", method, path, length);
+            write!(stream, "{}\r\n{}", head, body).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = request("POST", "/jobs", "OPENQASM 2.0;\nqreg q[1];\nx q[0];\n");
+        assert!(response.starts_with("HTTP/1.1 202 Accepted"));
+        wait_done(&server, 0);
+        let response = request("GET", "/jobs/0/result", "");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains(r#""counts":{"1":1024}"#));
+    }
+
+    #[test]
+    fn test_request_head_is_bounded() {
+        let head = |text: String| read_head(&mut text.as_bytes()).unwrap();
+        let request = "GET /jobs/0 HTTP/1.1\r\nContent-Length: 3\r\n\r\nabc".to_string();
+        assert_eq!(head(request), Some(("GET".into(), "/jobs/0".into(), 3)));
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_HEADER_LINE_BYTES));
+        assert_eq!(head(long_line), None);
+        let long_header = format!("GET / HTTP/1.1\r\nX: {}", "a".repeat(1 << 20));
+        assert_eq!(head(long_header), None);
+        let many = format!("GET / HTTP/1.1\r\n{}\r\n", "X: y\r\n".repeat(MAX_HEADERS + 1));
+        assert_eq!(head(many), None);
+        let enough = format!("GET / HTTP/1.1\r\n{}\r\n", "X: y\r\n".repeat(MAX_HEADERS));
+        assert!(head(enough).is_some());
+    }
+
+    #[test]
+    fn test_connections_are_capped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let background = Server::new();
+        thread::spawn(move || background.listen(listener));
+        let idle: Vec<TcpStream> =
+            (0..MAX_CONNECTIONS).map(|_| TcpStream::connect(addr).unwrap()).collect();
+        let mut response = String::new();
+        TcpStream::connect(addr).unwrap().read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable"));
+        drop(idle);
+        for _ in 0..500 {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET /jobs/0 HTTP/1.1\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            if response.starts_with("HTTP/1.1 404") {
+                return;
+            }
+            thread::sleep(Duration::from_millis(2));
+        }
+        panic!("connection slots were not given back");
+    }
+}
@@ -0,0 +1,220 @@
+use std::fs;
+use std::path::Path;
+use num_complex::Complex64;
+use super::register::Register;
+
+const MAGIC: &[u8; 4] = b"MQSV";
+const VERSION: u8 = 1;
+const FLAG_COMPRESSED: u8 = 1;
+const HEADER_LEN: usize = 7;
+
+/// widest state a compressed checkpoint may expand to, since a few bytes of
+/// zero runs can otherwise claim any amount of memory
+const MAX_COMPRESSED_QUBITS: usize = 30;
+
+fn push_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn push_amplitude(out: &mut Vec<u8>, a: Complex64) {
+    out.extend_from_slice(&a.re.to_le_bytes());
+    out.extend_from_slice(&a.im.to_le_bytes());
+}
+
+/// cursor over a checkpoint payload
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], String> {
+        let slice = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| "checkpoint is truncated".to_string())?;
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte < 0x80 {
+                return Ok(value);
+            }
+        }
+        Err("malformed run length".into())
+    }
+
+    fn amplitude(&mut self) -> Result<Complex64, String> {
+        let bytes = self.take(16)?;
+        let re = f64::from_le_bytes(bytes[..8].try_into().unwrap());
+        let im = f64::from_le_bytes(bytes[8..].try_into().unwrap());
+        Ok(Complex64::new(re, im))
+    }
+}
+
+impl Register {
+    /// binary checkpoint: "MQSV", version, flags, qubit count, then the
+    /// amplitudes as little-endian (re, im) f64 pairs
+    ///
+    /// Compressed checkpoints store alternating varint runs of exact zeros
+    /// and of literal amplitudes, so sparse states shrink losslessly.
+    pub fn to_bytes(&self, compress: bool) -> Vec<u8> {
+        let amplitudes = self.amplitudes();
+        let mut out = Vec::with_capacity(HEADER_LEN + 16 * amplitudes.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&[VERSION, u8::from(compress), self.num_qubits() as u8]);
+        if !compress {
+            amplitudes.iter().for_each(|&a| push_amplitude(&mut out, a));
+            return out;
+        }
+        let mut i = 0;
+        while i < amplitudes.len() {
+            let zeros = amplitudes[i..].iter().take_while(|a| **a == Complex64::default()).count();
+            let literals =
+                amplitudes[i + zeros..].iter().take_while(|a| **a != Complex64::default()).count();
+            push_varint(&mut out, zeros as u64);
+            push_varint(&mut out, literals as u64);
+            for &a in &amplitudes[i + zeros..i + zeros + literals] {
+                push_amplitude(&mut out, a);
+            }
+            i += zeros + literals;
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Register, String> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err("not a memqsim state checkpoint".into());
+        }
+        let header = reader.take(3)?;
+        let (version, flags, num_qubits) = (header[0], header[1], usize::from(header[2]));
+        if version != VERSION {
+            return Err(format!("unsupported checkpoint version {}", version));
+        }
+        let compressed = flags & FLAG_COMPRESSED != 0;
+        let max_qubits = if compressed { MAX_COMPRESSED_QUBITS } else { usize::BITS as usize - 1 };
+        if num_qubits > max_qubits {
+            return Err(format!("{} qubits is out of range", num_qubits));
+        }
+        let len = 1usize << num_qubits;
+        let mut amplitudes = Vec::new();
+        if !compressed {
+            // the size is checked before anything is allocated for it
+            let expected = len.checked_mul(16).and_then(|n| n.checked_add(HEADER_LEN));
+            if expected != Some(bytes.len()) {
+                return Err(format!(
+                    "{} qubits need {} bytes of amplitudes, found {}",
+                    num_qubits,
+                    len.saturating_mul(16),
+                    bytes.len() - HEADER_LEN
+                ));
+            }
+            amplitudes.reserve_exact(len);
+            for _ in 0..len {
+                amplitudes.push(reader.amplitude()?);
+            }
+        } else {
+            while amplitudes.len() < len {
+                let zeros = reader.varint()? as usize;
+                let literals = reader.varint()? as usize;
+                if zeros.saturating_add(literals) > len - amplitudes.len() {
+                    return Err("run overflows the state vector".into());
+                }
+                if bytes.len() - reader.pos < literals.saturating_mul(16) {
+                    return Err("checkpoint is truncated".into());
+                }
+                amplitudes
+                    .try_reserve(zeros + literals)
+                    .map_err(|_| format!("cannot allocate a {}-qubit state", num_qubits))?;
+                amplitudes.resize(amplitudes.len() + zeros, Complex64::default());
+                for _ in 0..literals {
+                    amplitudes.push(reader.amplitude()?);
+                }
+            }
+        }
+        if reader.pos != bytes.len() {
+            return Err("trailing bytes after the state vector".into());
+        }
+        Ok(Register::from_amplitudes(amplitudes))
+    }
+
+    /// write an uncompressed checkpoint
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        self.write_checkpoint(path.as_ref(), false)
+    }
+
+    /// write a checkpoint with zero runs compressed
+    pub fn save_compressed(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        self.write_checkpoint(path.as_ref(), true)
+    }
+
+    fn write_checkpoint(&self, path: &Path, compress: bool) -> Result<(), String> {
+        fs::write(path, self.to_bytes(compress))
+            .map_err(|e| format!("cannot write {}: {}", path.display(), e))
+    }
+
+    /// read a checkpoint written by `save` or `save_compressed`
+    pub fn load(path: impl AsRef<Path>) -> Result<Register, String> {
+        let bytes = fs::read(path.as_ref())
+            .map_err(|e| format!("cannot read {}: {}", path.as_ref().display(), e))?;
+        Register::from_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::circuit::Circuit;
+
+    #[test]
+    fn test_round_trip_through_files() {
+        let mut circuit = Circuit::new(5);
+        circuit.h(0).cx(0, 3).ry(0.7, 4).t(4);
+        let mut register = Register::new(5);
+        register.apply_circuit(&circuit);
+        let dir = std::env::temp_dir();
+        let (raw, packed) = (dir.join("memqsim_raw.mqsv"), dir.join("memqsim_packed.mqsv"));
+        register.save(&raw).unwrap();
+        register.save_compressed(&packed).unwrap();
+        for path in [raw, packed] {
+            let loaded = Register::load(&path).unwrap();
+            assert_eq!(loaded.amplitudes(), register.amplitudes());
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_compression_shrinks_sparse_states() {
+        let mut register = Register::new(12);
+        register.apply_circuit(Circuit::new(12).h(0).cx(0, 11));
+        let (raw, packed) = (register.to_bytes(false), register.to_bytes(true));
+        assert_eq!(raw.len(), 7 + 16 * 4096);
+        assert!(packed.len() < 64);
+        assert_eq!(Register::from_bytes(&packed).unwrap().amplitudes(), register.amplitudes());
+    }
+
+    #[test]
+    fn test_rejects_corrupt_checkpoints() {
+        let bytes = Register::new(3).to_bytes(false);
+        let short = Register::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(short.contains("need 128 bytes"), "{}", short);
+        assert!(Register::from_bytes(b"JUNK\x01\x00\x01").is_err());
+        // headers claiming huge states fail before allocating them
+        assert!(Register::from_bytes(b"MQSV\x01\x00\x28").unwrap_err().contains("need"));
+        assert!(Register::from_bytes(b"MQSV\x01\x01\x28").unwrap_err().contains("out of range"));
+        let packed = Register::new(3).to_bytes(true);
+        let cut = Register::from_bytes(&packed[..packed.len() - 1]).unwrap_err();
+        assert!(cut.contains("truncated"), "{}", cut);
+        assert!(Register::load("/nonexistent/state.mqsv").unwrap_err().contains("cannot read"));
+    }
+}
@@ -0,0 +1,551 @@
+use std::collections::BTreeMap;
+use std::ops::Index;
+use num_complex::Complex64;
+use super::gates::{phase_matrix, Gate};
+use super::matrix::Matrix;
+use super::register::Register;
+
+/// widest circuit `to_unitary` will expand (a 4096 × 4096 matrix)
+pub const MAX_UNITARY_QUBITS: usize = 12;
+
+/// one gate applied to specific qubits
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub gate: Gate,
+    pub qubits: Vec<usize>,
+}
+
+/// non-gate content of a circuit
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkerKind {
+    /// optimizer passes never move or merge gates across it; no effect on
+    /// the state
+    Barrier(Vec<usize>),
+    /// free text such as a protocol step
+    Annotation(String),
+}
+
+/// a barrier or annotation sitting just before instruction `position`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+    pub position: usize,
+    pub kind: MarkerKind,
+}
+
+/// ordered list of gates on a fixed number of qubits
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circuit {
+    num_qubits: usize,
+    instructions: Vec<Instruction>,
+    /// qubit names, looked up with `circuit["alice"]`
+    names: BTreeMap<String, usize>,
+    /// barriers and annotations in position order
+    markers: Vec<Marker>,
+}
+
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl Circuit {
+    pub fn new(num_qubits: usize) -> Self {
+        Self {
+            num_qubits,
+            instructions: Vec::new(),
+            names: BTreeMap::new(),
+            markers: Vec::new(),
+        }
+    }
+
+    /// name qubit `q`, replacing any earlier name of it; names are unique
+    /// identifiers (ASCII letters, digits and `_`, not starting with a digit)
+    pub fn name_qubit(&mut self, q: usize, name: &str) -> &mut Self {
+        assert!(q < self.num_qubits, "qubit {} out of range", q);
+        assert!(is_identifier(name), "'{}' is not an identifier", name);
+        assert!(
+            self.names.get(name).is_none_or(|&other| other == q),
+            "name '{}' is already taken",
+            name
+        );
+        self.names.retain(|_, &mut other| other != q);
+        self.names.insert(name.to_string(), q);
+        self
+    }
+
+    pub fn qubit_name(&self, q: usize) -> Option<&str> {
+        self.names.iter().find(|(_, &other)| other == q).map(|(name, _)| name.as_str())
+    }
+
+    /// index of the qubit called `name`
+    pub fn qubit(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// the name of every qubit, `q<index>` for unnamed ones
+    pub fn qubit_labels(&self) -> Vec<String> {
+        let mut labels: Vec<String> = (0..self.num_qubits).map(|q| format!("q{}", q)).collect();
+        for (name, &q) in &self.names {
+            labels[q] = name.clone();
+        }
+        labels
+    }
+
+    pub fn has_qubit_names(&self) -> bool {
+        !self.names.is_empty()
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    /// append `gate` on `qubits`
+    pub fn push(&mut self, gate: Gate, qubits: &[usize]) -> &mut Self {
+        assert_eq!(qubits.len(), gate.num_qubits(), "wrong qubit count for {}", gate.name());
+        if let Gate::Mcu(_, operator) = &gate {
+            assert_eq!(operator.num_qubits(), 1, "mcu needs a one-qubit operator");
+        }
+        for (k, &q) in qubits.iter().enumerate() {
+            assert!(q < self.num_qubits, "qubit {} out of range", q);
+            assert!(!qubits[..k].contains(&q), "repeated qubit {}", q);
+        }
+        self.instructions.push(Instruction {
+            gate,
+            qubits: qubits.to_vec(),
+        });
+        self
+    }
+
+    /// barrier on `qubits`, or on every qubit when empty
+    pub fn barrier(&mut self, qubits: &[usize]) -> &mut Self {
+        for (k, &q) in qubits.iter().enumerate() {
+            assert!(q < self.num_qubits, "qubit {} out of range", q);
+            assert!(!qubits[..k].contains(&q), "repeated qubit {}", q);
+        }
+        let qubits =
+            if qubits.is_empty() { (0..self.num_qubits).collect() } else { qubits.to_vec() };
+        self.mark(MarkerKind::Barrier(qubits))
+    }
+
+    /// free-text note at the current position, kept by serialization and
+    /// drawing
+    pub fn annotate(&mut self, text: &str) -> &mut Self {
+        self.mark(MarkerKind::Annotation(text.to_string()))
+    }
+
+    fn mark(&mut self, kind: MarkerKind) -> &mut Self {
+        let position = self.instructions.len();
+        self.markers.push(Marker { position, kind });
+        self
+    }
+
+    pub fn markers(&self) -> &[Marker] {
+        &self.markers
+    }
+
+    /// the gates between markers as separate circuits, each paired with the
+    /// markers that follow it; the last group is followed by none
+    pub fn split_at_markers(&self) -> Vec<(Circuit, &[Marker])> {
+        let mut groups = Vec::new();
+        let (mut start, mut m) = (0, 0);
+        loop {
+            let end = self.markers.get(m).map_or(self.len(), |marker| marker.position);
+            let first = m;
+            while self.markers.get(m).is_some_and(|marker| marker.position == end) {
+                m += 1;
+            }
+            let mut segment = Circuit::new(self.num_qubits);
+            segment.instructions = self.instructions[start..end].to_vec();
+            groups.push((segment, &self.markers[first..m]));
+            if first == m {
+                return groups;
+            }
+            start = end;
+        }
+    }
+
+    /// `pass` on every stretch of gates between markers, with the markers and
+    /// qubit names carried over, so no rewrite crosses a barrier
+    pub fn map_segments(&self, mut pass: impl FnMut(&Circuit) -> Circuit) -> Circuit {
+        let mut out = Circuit::new(self.num_qubits);
+        out.names = self.names.clone();
+        for (segment, markers) in self.split_at_markers() {
+            out.instructions.extend(pass(&segment).instructions);
+            for marker in markers {
+                out.mark(marker.kind.clone());
+            }
+        }
+        out
+    }
+
+    /// instruction k replaced by `gates[k]` on the same qubits, or dropped
+    /// where it is `None`; markers keep their place among the survivors and
+    /// qubit names carry over
+    pub fn rewritten(&self, gates: &[Option<Gate>]) -> Circuit {
+        assert_eq!(gates.len(), self.len(), "one entry per instruction");
+        let mut out = Circuit::new(self.num_qubits);
+        out.names = self.names.clone();
+        let mut markers = self.markers.iter().peekable();
+        for (k, (inst, gate)) in self.instructions.iter().zip(gates).enumerate() {
+            while let Some(marker) = markers.next_if(|marker| marker.position == k) {
+                out.mark(marker.kind.clone());
+            }
+            if let Some(gate) = gate {
+                out.push(gate.clone(), &inst.qubits);
+            }
+        }
+        for marker in markers {
+            out.mark(marker.kind.clone());
+        }
+        out
+    }
+
+    /// append every instruction and marker of `other`
+    pub fn append(&mut self, other: &Circuit) -> &mut Self {
+        assert!(other.num_qubits <= self.num_qubits, "appended circuit is wider");
+        let offset = self.instructions.len();
+        self.instructions.extend(other.instructions.iter().cloned());
+        self.markers.extend(other.markers.iter().map(|marker| Marker {
+            position: marker.position + offset,
+            kind: marker.kind.clone(),
+        }));
+        self
+    }
+
+    /// reversed circuit of inverse gates
+    pub fn inverse(&self) -> Circuit {
+        Circuit {
+            num_qubits: self.num_qubits,
+            names: self.names.clone(),
+            markers: self
+                .markers
+                .iter()
+                .rev()
+                .map(|marker| Marker {
+                    position: self.len() - marker.position,
+                    kind: marker.kind.clone(),
+                })
+                .collect(),
+            instructions: self
+                .instructions
+                .iter()
+                .rev()
+                .map(|inst| Instruction {
+                    gate: inst.gate.inverse(),
+                    qubits: inst.qubits.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// same gates on a wider register, qubit q moved to `mapping[q]` along
+    /// with its name
+    pub fn remapped(&self, num_qubits: usize, mapping: &[usize]) -> Circuit {
+        assert_eq!(mapping.len(), self.num_qubits, "mapping must cover every qubit");
+        let mut circuit = Circuit::new(num_qubits);
+        for (name, &q) in &self.names {
+            circuit.name_qubit(mapping[q], name);
+        }
+        for inst in &self.instructions {
+            let qubits: Vec<usize> = inst.qubits.iter().map(|&q| mapping[q]).collect();
+            circuit.push(inst.gate.clone(), &qubits);
+        }
+        circuit.markers = self
+            .markers
+            .iter()
+            .map(|marker| Marker {
+                position: marker.position,
+                kind: match &marker.kind {
+                    MarkerKind::Barrier(qubits) => {
+                        MarkerKind::Barrier(qubits.iter().map(|&q| mapping[q]).collect())
+                    }
+                    annotation => annotation.clone(),
+                },
+            })
+            .collect();
+        circuit
+    }
+
+    /// every gate conditioned on `control`, which the circuit must not touch
+    pub fn controlled(&self, control: usize) -> Circuit {
+        let mut circuit = Circuit::new(self.num_qubits);
+        circuit.names = self.names.clone();
+        let mut markers = self.markers.iter().peekable();
+        for (k, inst) in self.instructions.iter().enumerate() {
+            while let Some(marker) = markers.next_if(|marker| marker.position == k) {
+                circuit.mark(marker.kind.clone());
+            }
+            assert!(
+                !inst.qubits.contains(&control),
+                "control qubit {} is used by the circuit",
+                control
+            );
+            let mut qubits = vec![control];
+            qubits.extend(&inst.qubits);
+            match inst.gate {
+                Gate::I => {}
+                Gate::X | Gate::Cx | Gate::Mcx(_) => {
+                    circuit.push(Gate::Mcx(qubits.len() - 1), &qubits);
+                }
+                Gate::Z | Gate::Cz | Gate::Mcz(_) => {
+                    circuit.push(Gate::Mcz(qubits.len() - 1), &qubits);
+                }
+                Gate::Swap => {
+                    let (a, b) = (inst.qubits[0], inst.qubits[1]);
+                    circuit.mcx(&[control, a], b).mcx(&[control, b], a).mcx(&[control, a], b);
+                }
+                Gate::Mcu(n, ref operator) => {
+                    circuit.push(Gate::Mcu(n + 1, operator.clone()), &qubits);
+                }
+                ref gate => {
+                    let operator = gate.matrix().expect("single-qubit gate has a matrix");
+                    circuit.push(Gate::Mcu(1, operator), &qubits);
+                }
+            }
+        }
+        for marker in markers {
+            circuit.mark(marker.kind.clone());
+        }
+        circuit
+    }
+
+    /// full 2^n × 2^n matrix, column k being the image of basis state |k⟩
+    pub fn to_unitary(&self) -> Matrix {
+        assert!(
+            self.num_qubits <= MAX_UNITARY_QUBITS,
+            "to_unitary is limited to {} qubits",
+            MAX_UNITARY_QUBITS
+        );
+        let dim = 1 << self.num_qubits;
+        let mut unitary = Matrix::zeros(dim, dim);
+        for k in 0..dim {
+            let mut basis = vec![Complex64::new(0.0, 0.0); dim];
+            basis[k] = Complex64::new(1.0, 0.0);
+            let mut register = Register::from_amplitudes(basis);
+            register.apply_circuit(self);
+            for (i, &a) in register.amplitudes().iter().enumerate() {
+                unitary[(i, k)] = a;
+            }
+        }
+        unitary
+    }
+
+    pub fn is_clifford(&self) -> bool {
+        self.instructions.iter().all(|inst| inst.gate.is_clifford())
+    }
+
+    pub fn id(&mut self, q: usize) -> &mut Self {
+        self.push(Gate::I, &[q])
+    }
+
+    pub fn x(&mut self, q: usize) -> &mut Self {
+        self.push(Gate::X, &[q])
+    }
+
+    pub fn y(&mut self, q: usize) -> &mut Self {
+        self.push(Gate::Y, &[q])
+    }
+
+    pub fn z(&mut self, q: usize) -> &mut Self {
+        self.push(Gate::Z, &[q])
+    }
+
+    pub fn h(&mut self, q: usize) -> &mut Self {
+        self.push(Gate::H, &[q])
+    }
+
+    pub fn s(&mut self, q: usize) -> &mut Self {
+        self.push(Gate::S, &[q])
+    }
+
+    pub fn sdg(&mut self, q: usize) -> &mut Self {
+        self.push(Gate::Sdg, &[q])
+    }
+
+    pub fn t(&mut self, q: usize) -> &mut Self {
+        self.push(Gate::T, &[q])
+    }
+
+    pub fn tdg(&mut self, q: usize) -> &mut Self {
+        self.push(Gate::Tdg, &[q])
+    }
+
+    pub fn rx(&mut self, theta: f64, q: usize) -> &mut Self {
+        self.push(Gate::Rx(theta), &[q])
+    }
+
+    pub fn ry(&mut self, theta: f64, q: usize) -> &mut Self {
+        self.push(Gate::Ry(theta), &[q])
+    }
+
+    pub fn rz(&mut self, theta: f64, q: usize) -> &mut Self {
+        self.push(Gate::Rz(theta), &[q])
+    }
+
+    pub fn phase(&mut self, lambda: f64, q: usize) -> &mut Self {
+        self.push(Gate::Phase(lambda), &[q])
+    }
+
+    pub fn cx(&mut self, control: usize, target: usize) -> &mut Self {
+        self.push(Gate::Cx, &[control, target])
+    }
+
+    pub fn cz(&mut self, control: usize, target: usize) -> &mut Self {
+        self.push(Gate::Cz, &[control, target])
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) -> &mut Self {
+        self.push(Gate::Swap, &[a, b])
+    }
+
+    /// controlled phase diag(1, 1, 1, e^{iλ})
+    pub fn cp(&mut self, lambda: f64, control: usize, target: usize) -> &mut Self {
+        self.push(Gate::Mcu(1, phase_matrix(lambda).into()), &[control, target])
+    }
+
+    /// multi-controlled X
+    pub fn mcx(&mut self, controls: &[usize], target: usize) -> &mut Self {
+        let mut qubits = controls.to_vec();
+        qubits.push(target);
+        self.push(Gate::Mcx(controls.len()), &qubits)
+    }
+
+    /// multi-controlled Z
+    pub fn mcz(&mut self, controls: &[usize], target: usize) -> &mut Self {
+        let mut qubits = controls.to_vec();
+        qubits.push(target);
+        self.push(Gate::Mcz(controls.len()), &qubits)
+    }
+}
+
+impl Index<&str> for Circuit {
+    type Output = usize;
+
+    /// index of the qubit called `name`; panics if there is none
+    fn index(&self, name: &str) -> &usize {
+        self.names.get(name).unwrap_or_else(|| panic!("no qubit named '{}'", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::register::Register;
+
+    #[test]
+    fn test_builder_chain() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1).rz(0.3, 1);
+        assert_eq!(circuit.len(), 3);
+        assert_eq!(circuit.instructions()[1].qubits, vec![0, 1]);
+        assert!(!circuit.is_clifford());
+    }
+
+    #[test]
+    fn test_named_qubits() {
+        let mut circuit = Circuit::new(3);
+        circuit.name_qubit(0, "alice").name_qubit(2, "bob");
+        let (alice, bob) = (circuit["alice"], circuit["bob"]);
+        circuit.h(alice).cx(alice, bob);
+        assert_eq!(circuit.instructions()[1].qubits, [0, 2]);
+        assert_eq!(circuit.qubit_labels(), ["alice", "q1", "bob"]);
+        // renaming frees the old name, and names travel with the qubits
+        circuit.name_qubit(0, "carol");
+        assert_eq!((circuit.qubit("alice"), circuit.qubit_name(0)), (None, Some("carol")));
+        assert_eq!(circuit.inverse().qubit("bob"), Some(2));
+        assert_eq!(circuit.remapped(4, &[3, 1, 0]).qubit_labels(), ["bob", "q1", "q2", "carol"]);
+    }
+
+    #[test]
+    fn test_markers_follow_the_gates() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).barrier(&[]).annotate("entangle").cx(0, 1).barrier(&[1]).x(1);
+        assert_eq!(circuit.markers()[0].kind, MarkerKind::Barrier(vec![0, 1]));
+        let groups = circuit.split_at_markers();
+        let sizes: Vec<(usize, usize)> = groups.iter().map(|(c, m)| (c.len(), m.len())).collect();
+        assert_eq!(sizes, [(1, 2), (1, 1), (1, 0)]);
+        // the inverse mirrors the positions; appending shifts them
+        let inverse = circuit.inverse();
+        let positions: Vec<usize> = inverse.markers().iter().map(|m| m.position).collect();
+        assert_eq!(positions, [1, 2, 2]);
+        let mut doubled = circuit.clone();
+        doubled.append(&circuit);
+        assert_eq!(doubled.markers()[3].position, 4);
+        assert_eq!(circuit.map_segments(|segment| segment.clone()), circuit);
+        let dropped = circuit.rewritten(&[None, Some(Gate::Cz), None]);
+        let positions: Vec<usize> = dropped.markers().iter().map(|m| m.position).collect();
+        assert_eq!((dropped.len(), positions), (1, vec![0, 0, 1]));
+        let remapped = circuit.remapped(3, &[2, 0]);
+        assert_eq!(remapped.markers()[2].kind, MarkerKind::Barrier(vec![0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "already taken")]
+    fn test_duplicate_qubit_name_panics() {
+        Circuit::new(2).name_qubit(0, "a").name_qubit(1, "a");
+    }
+
+    #[test]
+    fn test_inverse_undoes_circuit() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).t(1).cx(0, 2).ry(0.7, 1).s(2).swap(0, 1);
+        let mut register = Register::new(3);
+        register.apply_circuit(&circuit);
+        register.apply_circuit(&circuit.inverse());
+        crate::assert_state_eq!(register, Register::new(3));
+    }
+
+    #[test]
+    fn test_controlled_acts_only_when_control_set() {
+        let mut base = Circuit::new(3);
+        base.h(0).cx(0, 1).swap(0, 1).ry(0.4, 0);
+        let controlled = base.controlled(2);
+        let mut off = Register::new(3);
+        off.apply_circuit(&controlled);
+        crate::assert_state_eq!(off, Register::new(3));
+
+        let mut on = Register::new(3);
+        on.apply_circuit(Circuit::new(3).x(2));
+        on.apply_circuit(&controlled);
+        let mut expected = Register::new(3);
+        expected.apply_circuit(Circuit::new(3).x(2));
+        expected.apply_circuit(&base);
+        crate::assert_state_eq!(on, expected);
+    }
+
+    #[test]
+    fn test_to_unitary() {
+        let mut cx = Circuit::new(2);
+        cx.cx(0, 1);
+        // control is qubit 0 (the low bit): |01⟩ ↔ |11⟩
+        let u = cx.to_unitary();
+        let one = Complex64::new(1.0, 0.0);
+        assert_eq!((u[(0, 0)], u[(3, 1)], u[(2, 2)], u[(1, 3)]), (one, one, one, one));
+
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).t(1).cx(0, 2).ry(0.3, 1).swap(1, 2);
+        let u = circuit.to_unitary();
+        assert!(u.is_unitary(1e-12));
+        let round_trip = &circuit.inverse().to_unitary() * &u;
+        crate::assert_unitary_eq!(round_trip, Matrix::identity(8));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_out_of_range_qubit() {
+        Circuit::new(1).cx(0, 1);
+    }
+}
@@ -0,0 +1,58 @@
+use super::gates::Gate;
+use super::operator::Operator;
+use super::testing::ApproxEq;
+
+/// element of the single-qubit Clifford group as a shortest H/S word
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clifford1q {
+    pub gates: Vec<Gate>,
+    pub matrix: Operator,
+}
+
+/// all 24 single-qubit Cliffords (up to global phase), identity first
+///
+/// Built breadth-first from {H, S}, so each word is as short as possible.
+pub fn single_qubit_cliffords() -> Vec<Clifford1q> {
+    let mut group = vec![Clifford1q {
+        gates: Vec::new(),
+        matrix: Operator::identity(1),
+    }];
+    let mut frontier = 0;
+    while frontier < group.len() {
+        for gate in [Gate::H, Gate::S] {
+            let base = &group[frontier];
+            let matrix = &gate.operator() * &base.matrix;
+            if group.iter().all(|c| !c.matrix.approx_eq(&matrix, 1e-9)) {
+                let mut gates = base.gates.clone();
+                gates.push(gate);
+                group.push(Clifford1q { gates, matrix });
+            }
+        }
+        frontier += 1;
+    }
+    group
+}
+
+/// index in `group` of the Clifford equal to `matrix` up to phase
+pub fn find_clifford(group: &[Clifford1q], matrix: &Operator) -> Option<usize> {
+    group.iter().position(|c| c.matrix.approx_eq(matrix, 1e-9))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_order() {
+        assert_eq!(single_qubit_cliffords().len(), 24);
+    }
+
+    #[test]
+    fn test_closed_under_inverse() {
+        let group = single_qubit_cliffords();
+        for c in &group {
+            assert!(find_clifford(&group, &c.matrix.dagger()).is_some());
+        }
+        assert!(find_clifford(&group, &Gate::X.operator()).is_some());
+    }
+}
@@ -0,0 +1,505 @@
+use std::collections::BTreeSet;
+use std::f64::consts::FRAC_PI_2;
+use super::circuit::{Circuit, Instruction};
+use super::distribution::Distribution;
+use super::gates::{
+    dagger, h_matrix, identity_matrix, matmul, rz_matrix, s_matrix, x_matrix, z_matrix, Gate,
+    Matrix2,
+};
+use super::hybrid::projector;
+use super::register::Register;
+
+/// most cut-term combinations a `CutCircuit` accepts; every fragment is
+/// simulated once per combination of its cuts' terms and every probability
+/// sums one product per combination, so each cut multiplies the work by ~10
+pub const MAX_CUT_TERMS: usize = 1 << 20;
+
+/// cut of `qubit`'s wire just before instruction `position`: the state is
+/// measured in the Z, X and Y bases on one side and re-prepared in their
+/// eigenstates on the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WireCut {
+    pub qubit: usize,
+    pub position: usize,
+}
+
+/// when a cut term acts on its wire segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Start,
+    /// in place of the cut gate
+    Gate(usize),
+    End,
+}
+
+#[derive(Debug, Clone)]
+struct Action {
+    segment: usize,
+    slot: Slot,
+    matrix: Matrix2,
+}
+
+/// one product term of a cut with its quasi-probability coefficient
+#[derive(Debug, Clone)]
+struct Term {
+    coefficient: f64,
+    actions: Vec<Action>,
+}
+
+/// ops applied first to last, as one matrix
+fn chain(ops: &[Matrix2]) -> Matrix2 {
+    ops.iter().fold(identity_matrix(), |product, m| matmul(m, &product))
+}
+
+/// Z eigenvalue of `bit`
+fn sign(bit: usize) -> f64 {
+    if bit == 0 { 1.0 } else { -1.0 }
+}
+
+/// ρ = Σ_e ⟨e|ρ|e⟩·|e⟩⟨e| + ½·Σ_{P=X,Y} Σ_{e,f} ±⟨e_P|ρ|e_P⟩·|f_P⟩⟨f_P|
+/// over Z, X and Y eigenstates: the projection onto |e_P⟩ ends `upstream`
+/// and |f_P⟩ is prepared at the start of `downstream`
+fn wire_terms(upstream: usize, downstream: usize) -> Vec<Term> {
+    // V_P with V_P|e⟩ = |e_P⟩
+    let bases = [identity_matrix(), h_matrix(), matmul(&s_matrix(), &h_matrix())];
+    let mut terms = Vec::new();
+    for (k, basis) in bases.iter().enumerate() {
+        for (e, f) in (0..2).flat_map(|e| (0..2).map(move |f| (e, f))) {
+            let coefficient = match k {
+                0 if e != f => continue,
+                0 => 1.0,
+                _ => 0.5 * sign(e) * sign(f),
+            };
+            let flip = if f == 1 { x_matrix() } else { identity_matrix() };
+            terms.push(Term {
+                coefficient,
+                actions: vec![
+                    Action {
+                        segment: upstream,
+                        slot: Slot::End,
+                        matrix: chain(&[dagger(basis), projector(e)]),
+                    },
+                    Action {
+                        segment: downstream,
+                        slot: Slot::Start,
+                        matrix: chain(&[flip, *basis]),
+                    },
+                ],
+            });
+        }
+    }
+    terms
+}
+
+/// quasi-probability expansion of a CZ between segments `a` and `b`, or of a
+/// CX with target `b` by conjugating that side with H
+///
+/// CZ is e^{iπ/4·Z⊗Z} followed by RZ(π/2) on both qubits, up to phase. The
+/// channel of e^{iθ·Z⊗Z} is cos²θ·id + sin²θ·(Z⊗Z) plus cosθ·sinθ times a
+/// Z measurement on one side, weighted by its outcome, against e^{±iπ/4·Z}
+/// on the other (Mitarai and Fujii).
+fn gate_terms(gate: Gate, a: usize, b: usize, position: usize) -> Vec<Term> {
+    let (plus, minus) = (rz_matrix(-FRAC_PI_2), rz_matrix(FRAC_PI_2));
+    let mut sides: Vec<(f64, Matrix2, Matrix2)> =
+        vec![(0.5, identity_matrix(), identity_matrix()), (0.5, z_matrix(), z_matrix())];
+    for e in 0..2 {
+        let s = 0.5 * sign(e);
+        sides.push((s, projector(e), plus));
+        sides.push((-s, projector(e), minus));
+        sides.push((s, plus, projector(e)));
+        sides.push((-s, minus, projector(e)));
+    }
+    let wrap = if gate == Gate::Cx { h_matrix() } else { identity_matrix() };
+    sides
+        .into_iter()
+        .map(|(coefficient, on_a, on_b)| Term {
+            coefficient,
+            actions: vec![
+                Action { segment: a, slot: Slot::Gate(position), matrix: chain(&[on_a, minus]) },
+                Action {
+                    segment: b,
+                    slot: Slot::Gate(position),
+                    matrix: chain(&[wrap, on_b, minus, wrap]),
+                },
+            ],
+        })
+        .collect()
+}
+
+/// step a mixed-radix counter, least significant digit first; false once it
+/// wraps around
+fn advance(digits: &mut [usize], radices: &[usize]) -> bool {
+    for (digit, &radix) in digits.iter_mut().zip(radices) {
+        *digit += 1;
+        if *digit < radix {
+            return true;
+        }
+        *digit = 0;
+    }
+    false
+}
+
+/// each qubit wire divided into segments at its wire cuts
+struct Segments {
+    offsets: Vec<usize>,
+    /// sorted cut positions per qubit
+    positions: Vec<Vec<usize>>,
+}
+
+impl Segments {
+    fn len(&self) -> usize {
+        self.positions.iter().map(|p| p.len() + 1).sum()
+    }
+
+    /// segment of qubit `q` that instruction `i` acts on
+    fn at(&self, q: usize, i: usize) -> usize {
+        self.offsets[q] + self.positions[q].iter().filter(|&&p| p <= i).count()
+    }
+
+    /// segment holding the final state of qubit `q`
+    fn last(&self, q: usize) -> usize {
+        self.offsets[q] + self.positions[q].len()
+    }
+}
+
+/// fragment of every segment and its qubit within the fragment
+struct Layout {
+    fragment: Vec<usize>,
+    local: Vec<usize>,
+    widths: Vec<usize>,
+}
+
+impl Layout {
+    /// segments joined by uncut gates share a fragment, numbered in order of
+    /// their first segment
+    fn new(circuit: &Circuit, segments: &Segments, cut_gates: &BTreeSet<usize>) -> Self {
+        fn root(parent: &mut [usize], mut s: usize) -> usize {
+            while parent[s] != s {
+                parent[s] = parent[parent[s]];
+                s = parent[s];
+            }
+            s
+        }
+        let mut parent: Vec<usize> = (0..segments.len()).collect();
+        for (i, inst) in circuit.instructions().iter().enumerate() {
+            if cut_gates.contains(&i) {
+                continue;
+            }
+            let first = root(&mut parent, segments.at(inst.qubits[0], i));
+            for &q in &inst.qubits[1..] {
+                let other = root(&mut parent, segments.at(q, i));
+                parent[other] = first;
+            }
+        }
+        let mut layout = Layout { fragment: Vec::new(), local: Vec::new(), widths: Vec::new() };
+        let mut fragment_of_root = vec![usize::MAX; segments.len()];
+        for s in 0..segments.len() {
+            let r = root(&mut parent, s);
+            if fragment_of_root[r] == usize::MAX {
+                fragment_of_root[r] = layout.widths.len();
+                layout.widths.push(0);
+            }
+            let f = fragment_of_root[r];
+            layout.fragment.push(f);
+            layout.local.push(layout.widths[f]);
+            layout.widths[f] += 1;
+        }
+        layout
+    }
+}
+
+/// independently simulated piece of a cut circuit
+#[derive(Debug, Clone)]
+struct Fragment {
+    width: usize,
+    /// original qubits whose final segment lies here, lowest bit first
+    outputs: Vec<usize>,
+    /// cuts with terms acting here
+    cuts: Vec<usize>,
+    /// signed output probabilities for every combination of those cuts'
+    /// terms, the first cut varying fastest
+    table: Vec<Vec<f64>>,
+}
+
+impl Fragment {
+    /// simulate fragment `f` once per combination of the terms acting on it
+    fn simulate(
+        f: usize,
+        circuit: &Circuit,
+        segments: &Segments,
+        layout: &Layout,
+        cuts: &[Vec<Term>],
+        cut_gates: &BTreeSet<usize>,
+    ) -> Self {
+        let here = |segment: usize| layout.fragment[segment] == f;
+        let outputs: Vec<usize> =
+            (0..circuit.num_qubits()).filter(|&q| here(segments.last(q))).collect();
+        let touching: Vec<usize> = (0..cuts.len())
+            .filter(|&c| cuts[c][0].actions.iter().any(|a| here(a.segment)))
+            .collect();
+        let radices: Vec<usize> = touching.iter().map(|&c| cuts[c].len()).collect();
+        let mut choice = vec![0; touching.len()];
+        let mut table = Vec::new();
+        loop {
+            let actions: Vec<&Action> = touching
+                .iter()
+                .zip(&choice)
+                .flat_map(|(&c, &t)| &cuts[c][t].actions)
+                .filter(|a| here(a.segment))
+                .collect();
+            let act = |register: &mut Register, slot: Slot| {
+                for action in actions.iter().filter(|a| a.slot == slot) {
+                    register.apply_matrix2(layout.local[action.segment], action.matrix);
+                }
+            };
+            let mut register = Register::new(layout.widths[f]);
+            act(&mut register, Slot::Start);
+            for (i, inst) in circuit.instructions().iter().enumerate() {
+                if cut_gates.contains(&i) {
+                    act(&mut register, Slot::Gate(i));
+                } else if here(segments.at(inst.qubits[0], i)) {
+                    let qubits = inst.qubits.iter().map(|&q| layout.local[segments.at(q, i)]);
+                    let local = Instruction { gate: inst.gate.clone(), qubits: qubits.collect() };
+                    register.apply_instruction(&local);
+                }
+            }
+            act(&mut register, Slot::End);
+            let mut marginal = vec![0.0; 1 << outputs.len()];
+            for (k, p) in register.probabilities().into_iter().enumerate() {
+                let x = outputs.iter().enumerate().fold(0, |x, (bit, &q)| {
+                    x | ((k >> layout.local[segments.last(q)]) & 1) << bit
+                });
+                marginal[x] += p;
+            }
+            table.push(marginal);
+            if !advance(&mut choice, &radices) {
+                break;
+            }
+        }
+        Fragment { width: layout.widths[f], outputs, cuts: touching, table }
+    }
+
+    /// entry of this fragment's outputs within an outcome of the whole circuit
+    fn entry(&self, outcome: usize) -> usize {
+        self.outputs.iter().enumerate().fold(0, |x, (bit, &q)| x | ((outcome >> q) & 1) << bit)
+    }
+}
+
+/// circuit split by wire and gate cuts into fragments that are simulated on
+/// their own, their output quasi-probabilities recombined term by term
+///
+/// Each qubit wire is divided into segments at its wire cuts; segments joined
+/// by an uncut multi-qubit gate share a fragment. Memory is one dense state
+/// per fragment and each outcome probability sums one product per
+/// combination of cut terms, so circuits far wider than a state vector can
+/// hold stay tractable with a few cuts.
+#[derive(Debug, Clone)]
+pub struct CutCircuit {
+    num_qubits: usize,
+    /// term coefficients of every cut
+    coefficients: Vec<Vec<f64>>,
+    fragments: Vec<Fragment>,
+}
+
+impl CutCircuit {
+    /// cut `circuit` at `wire_cuts` and at the CX or CZ instructions listed
+    /// in `gate_cuts`; fails when the cuts need more than [`MAX_CUT_TERMS`]
+    /// term combinations
+    pub fn new(
+        circuit: &Circuit,
+        wire_cuts: &[WireCut],
+        gate_cuts: &[usize],
+    ) -> Result<Self, String> {
+        let num_qubits = circuit.num_qubits();
+        let instructions = circuit.instructions();
+        let mut positions = vec![Vec::new(); num_qubits];
+        for cut in wire_cuts {
+            if cut.qubit >= num_qubits || cut.position > instructions.len() {
+                return Err(format!("wire cut {:?} is outside the circuit", cut));
+            }
+            if positions[cut.qubit].contains(&cut.position) {
+                return Err(format!("wire cut {:?} is listed twice", cut));
+            }
+            positions[cut.qubit].push(cut.position);
+        }
+        positions.iter_mut().for_each(|p| p.sort_unstable());
+        let offsets = positions
+            .iter()
+            .scan(0, |next, p| {
+                let offset = *next;
+                *next += p.len() + 1;
+                Some(offset)
+            })
+            .collect();
+        let segments = Segments { offsets, positions };
+        let mut cut_gates = BTreeSet::new();
+        for &i in gate_cuts {
+            let inst = instructions.get(i).ok_or_else(|| format!("no instruction {}", i))?;
+            if !matches!(inst.gate, Gate::Cx | Gate::Cz) {
+                return Err(format!("cannot cut {} at {}: only cx and cz", inst.gate.name(), i));
+            }
+            if !cut_gates.insert(i) {
+                return Err(format!("gate cut {} is listed twice", i));
+            }
+        }
+        let layout = Layout::new(circuit, &segments, &cut_gates);
+        let mut cuts: Vec<Vec<Term>> = Vec::new();
+        for (q, p) in segments.positions.iter().enumerate() {
+            let first = segments.offsets[q];
+            cuts.extend((0..p.len()).map(|k| wire_terms(first + k, first + k + 1)));
+        }
+        for &i in &cut_gates {
+            let q = &instructions[i].qubits;
+            let (a, b) = (segments.at(q[0], i), segments.at(q[1], i));
+            cuts.push(gate_terms(instructions[i].gate.clone(), a, b, i));
+        }
+        let num_terms = cuts.iter().try_fold(1usize, |n, terms| n.checked_mul(terms.len()));
+        if num_terms.is_none_or(|n| n > MAX_CUT_TERMS) {
+            return Err(format!(
+                "{} cuts exceed the budget of {} term combinations",
+                cuts.len(),
+                MAX_CUT_TERMS
+            ));
+        }
+        let fragments = (0..layout.widths.len())
+            .map(|f| Fragment::simulate(f, circuit, &segments, &layout, &cuts, &cut_gates))
+            .collect();
+        let coefficients =
+            cuts.iter().map(|terms| terms.iter().map(|t| t.coefficient).collect()).collect();
+        Ok(Self { num_qubits, coefficients, fragments })
+    }
+
+    /// gate cuts on every gate crossing between qubits `cut − 1` and `cut`
+    pub fn bipartition(circuit: &Circuit, cut: usize) -> Result<Self, String> {
+        if cut == 0 || cut >= circuit.num_qubits() {
+            return Err(format!("cut {} must leave qubits on both sides", cut));
+        }
+        let crossing: Vec<usize> = (0..circuit.len())
+            .filter(|&i| {
+                let qubits = &circuit.instructions()[i].qubits;
+                qubits.iter().any(|&q| q < cut) && qubits.iter().any(|&q| q >= cut)
+            })
+            .collect();
+        Self::new(circuit, &[], &crossing)
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn num_fragments(&self) -> usize {
+        self.fragments.len()
+    }
+
+    /// qubits simulated per fragment, wire segments counted separately
+    pub fn fragment_widths(&self) -> Vec<usize> {
+        self.fragments.iter().map(|f| f.width).collect()
+    }
+
+    /// products summed for each probability: the term counts of all cuts
+    pub fn num_terms(&self) -> usize {
+        self.coefficients.iter().map(Vec::len).product()
+    }
+
+    /// probability of measuring `outcome` on the uncut circuit
+    pub fn probability(&self, outcome: usize) -> f64 {
+        let radices: Vec<usize> = self.coefficients.iter().map(Vec::len).collect();
+        let entries: Vec<usize> = self.fragments.iter().map(|f| f.entry(outcome)).collect();
+        let mut choice = vec![0; radices.len()];
+        let mut total = 0.0;
+        loop {
+            let mut value: f64 =
+                self.coefficients.iter().zip(&choice).map(|(terms, &t)| terms[t]).product();
+            for (fragment, &x) in self.fragments.iter().zip(&entries) {
+                if value == 0.0 {
+                    break;
+                }
+                let index =
+                    fragment.cuts.iter().rev().fold(0, |index, &c| index * radices[c] + choice[c]);
+                value *= fragment.table[index][x];
+            }
+            total += value;
+            if !advance(&mut choice, &radices) {
+                return total;
+            }
+        }
+    }
+
+    /// every outcome probability, for circuits narrow enough to list them;
+    /// rounding below zero is clipped
+    pub fn distribution(&self) -> Distribution {
+        let probs = (0..1usize << self.num_qubits).map(|x| self.probability(x).max(0.0)).collect();
+        Distribution::new(self.num_qubits, probs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_matches_state_vector(cut: &CutCircuit, circuit: &Circuit) {
+        let mut register = Register::new(circuit.num_qubits());
+        register.apply_circuit(circuit);
+        for (x, p) in register.probabilities().into_iter().enumerate() {
+            let recombined = cut.probability(x);
+            assert!((recombined - p).abs() < 1e-9, "outcome {}: {} vs {}", x, recombined, p);
+        }
+    }
+
+    #[test]
+    fn test_wire_cut_splits_ghz_chain() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).cx(0, 1).t(1).cx(1, 2).ry(0.3, 2);
+        let cut = CutCircuit::new(&circuit, &[WireCut { qubit: 1, position: 3 }], &[]).unwrap();
+        assert_eq!(cut.fragment_widths(), [2, 2]);
+        assert_eq!(cut.num_terms(), 10);
+        assert_matches_state_vector(&cut, &circuit);
+        let mut register = Register::new(3);
+        register.apply_circuit(&circuit);
+        assert!(cut.distribution().total_variation(&register.distribution()) < 1e-9);
+    }
+
+    #[test]
+    fn test_gate_cuts_match_state_vector() {
+        let mut circuit = Circuit::new(4);
+        circuit.h(0).ry(0.7, 1).cx(0, 1).cx(1, 2).rx(0.4, 3).cz(3, 0).h(2).cx(2, 3).t(1);
+        let cut = CutCircuit::bipartition(&circuit, 2).unwrap();
+        assert_eq!((cut.num_fragments(), cut.num_terms()), (2, 100));
+        assert_matches_state_vector(&cut, &circuit);
+        // both kinds of cut together, with a wire cut inside a fragment
+        let wire = [WireCut { qubit: 0, position: 5 }];
+        let mixed = CutCircuit::new(&circuit, &wire, &[3]).unwrap();
+        assert_matches_state_vector(&mixed, &circuit);
+        let mut swapped = Circuit::new(2);
+        swapped.h(0).swap(0, 1);
+        assert!(CutCircuit::bipartition(&swapped, 1).unwrap_err().contains("only cx and cz"));
+    }
+
+    #[test]
+    fn test_too_many_cuts_are_refused() {
+        let mut circuit = Circuit::new(5);
+        for _ in 0..8 {
+            circuit.cx(1, 2);
+        }
+        circuit.h(0).h(3).h(4).cx(0, 1).cx(3, 4).h(2);
+        let error = CutCircuit::bipartition(&circuit, 2).unwrap_err();
+        assert!(error.contains("8 cuts exceed"), "{}", error);
+        assert!(CutCircuit::new(&circuit, &[], &[0, 1, 2]).is_ok());
+    }
+
+    #[test]
+    fn test_wide_ghz_beyond_a_state_vector() {
+        // 36 qubits in three 12-qubit fragments joined by two cut CX gates
+        let mut circuit = Circuit::new(36);
+        circuit.h(0);
+        for q in 1..36 {
+            circuit.cx(q - 1, q);
+        }
+        let cut = CutCircuit::new(&circuit, &[], &[12, 24]).unwrap();
+        assert_eq!(cut.fragment_widths(), [12, 12, 12]);
+        let all_ones = (1usize << 36) - 1;
+        assert!((cut.probability(0) - 0.5).abs() < 1e-9);
+        assert!((cut.probability(all_ones) - 0.5).abs() < 1e-9);
+        assert!(cut.probability(1 << 20).abs() < 1e-9);
+    }
+}
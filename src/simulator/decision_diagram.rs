@@ -0,0 +1,479 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::Instant;
+use num_complex::Complex64;
+use super::circuit::{Circuit, Instruction};
+use super::gates::{x_matrix, z_matrix, Gate, Matrix2};
+use super::operator::Operator;
+use super::register::Register;
+use super::report::{RunReport, RunResult};
+use super::rng::Rng;
+
+/// edge weights below this magnitude are treated as zero
+const WEIGHT_EPS: f64 = 1e-13;
+
+/// resolution of the weight rounding used to share nodes
+const WEIGHT_GRID: f64 = 1e10;
+
+const TERMINAL: usize = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Edge {
+    node: usize,
+    weight: Complex64,
+}
+
+impl Edge {
+    const ZERO: Edge = Edge { node: TERMINAL, weight: Complex64 { re: 0.0, im: 0.0 } };
+
+    fn is_zero(&self) -> bool {
+        self.weight.norm() < WEIGHT_EPS
+    }
+
+    fn scaled(self, factor: Complex64) -> Edge {
+        let weight = self.weight * factor;
+        if weight.norm() < WEIGHT_EPS { Edge::ZERO } else { Edge { node: self.node, weight } }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    /// qubit this node branches on; the root holds the highest qubit
+    qubit: usize,
+    children: [Edge; 2],
+}
+
+type Rounded = (i64, i64);
+
+type AddMemo = HashMap<(usize, usize, Rounded), Edge>;
+
+/// per-gate caches, keyed by node id with the incoming weight factored out
+#[derive(Default)]
+struct GateMemo {
+    apply: HashMap<usize, Edge>,
+    project: HashMap<usize, Edge>,
+    add: AddMemo,
+}
+
+fn rounded(w: Complex64) -> Rounded {
+    ((w.re * WEIGHT_GRID).round() as i64, (w.im * WEIGHT_GRID).round() as i64)
+}
+
+/// state vector stored as a quantum multiple-valued decision diagram
+///
+/// Every node splits on one qubit, from qubit n−1 at the root down to qubit 0,
+/// and identical sub-vectors up to a complex factor are shared: a node's
+/// larger child weight is normalized to 1 and the factor moves onto the
+/// incoming edge. Structured states such as GHZ or QFT outputs of basis
+/// states stay linear in the qubit count.
+#[derive(Debug, Clone)]
+pub struct DecisionDiagram {
+    num_qubits: usize,
+    nodes: Vec<Node>,
+    unique: HashMap<(usize, usize, Rounded, usize, Rounded), usize>,
+    root: Edge,
+    /// node count that triggers the next garbage collection
+    collect_at: usize,
+    peak_nodes: usize,
+}
+
+impl DecisionDiagram {
+    /// |0…0⟩
+    pub fn new(num_qubits: usize) -> Self {
+        let terminal = Node { qubit: usize::MAX, children: [Edge::ZERO; 2] };
+        let mut dd = Self {
+            num_qubits,
+            nodes: vec![terminal],
+            unique: HashMap::new(),
+            root: Edge { node: TERMINAL, weight: Complex64::new(1.0, 0.0) },
+            collect_at: 1 << 12,
+            peak_nodes: 1,
+        };
+        for q in 0..num_qubits {
+            dd.root = dd.make_node(q, dd.root, Edge::ZERO);
+        }
+        dd
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// nodes reachable from the root, the terminal included
+    pub fn num_nodes(&self) -> usize {
+        self.reachable().len()
+    }
+
+    /// largest node table held so far, in bytes
+    pub fn peak_memory_bytes(&self) -> usize {
+        self.peak_nodes.max(self.nodes.len()) * std::mem::size_of::<Node>()
+    }
+
+    fn make_node(&mut self, qubit: usize, e0: Edge, e1: Edge) -> Edge {
+        let e0 = if e0.is_zero() { Edge::ZERO } else { e0 };
+        let e1 = if e1.is_zero() { Edge::ZERO } else { e1 };
+        if e0.is_zero() && e1.is_zero() {
+            return Edge::ZERO;
+        }
+        let norm = if e0.weight.norm() + WEIGHT_EPS >= e1.weight.norm() {
+            e0.weight
+        } else {
+            e1.weight
+        };
+        let children = [e0.scaled(norm.inv()), e1.scaled(norm.inv())];
+        let key = (
+            qubit,
+            children[0].node,
+            rounded(children[0].weight),
+            children[1].node,
+            rounded(children[1].weight),
+        );
+        let node = match self.unique.get(&key) {
+            Some(&id) => id,
+            None => {
+                self.nodes.push(Node { qubit, children });
+                self.unique.insert(key, self.nodes.len() - 1);
+                self.nodes.len() - 1
+            }
+        };
+        Edge { node, weight: norm }
+    }
+
+    fn add(&mut self, a: Edge, b: Edge, memo: &mut AddMemo) -> Edge {
+        if a.is_zero() {
+            return b;
+        }
+        if b.is_zero() {
+            return a;
+        }
+        if a.node == b.node {
+            return Edge { node: a.node, weight: Complex64::new(1.0, 0.0) }
+                .scaled(a.weight + b.weight);
+        }
+        let ratio = b.weight / a.weight;
+        let key = (a.node, b.node, rounded(ratio));
+        if let Some(&e) = memo.get(&key) {
+            return e.scaled(a.weight);
+        }
+        let (na, nb) = (self.nodes[a.node], self.nodes[b.node]);
+        let c0 = self.add(na.children[0], nb.children[0].scaled(ratio), memo);
+        let c1 = self.add(na.children[1], nb.children[1].scaled(ratio), memo);
+        let e = self.make_node(na.qubit, c0, c1);
+        memo.insert(key, e);
+        e.scaled(a.weight)
+    }
+
+    /// component of `e` where every qubit in `controls` is |1⟩
+    fn project(&mut self, e: Edge, controls: &[usize], memo: &mut HashMap<usize, Edge>) -> Edge {
+        let lowest = controls.iter().copied().min().unwrap_or(usize::MAX);
+        if e.is_zero() || e.node == TERMINAL || self.nodes[e.node].qubit < lowest {
+            return e;
+        }
+        if let Some(&r) = memo.get(&e.node) {
+            return r.scaled(e.weight);
+        }
+        let node = self.nodes[e.node];
+        let c0 = if controls.contains(&node.qubit) {
+            Edge::ZERO
+        } else {
+            self.project(node.children[0], controls, memo)
+        };
+        let c1 = self.project(node.children[1], controls, memo);
+        let r = self.make_node(node.qubit, c0, c1);
+        memo.insert(e.node, r);
+        r.scaled(e.weight)
+    }
+
+    /// apply a one-qubit operator to `target` where every control is |1⟩;
+    /// wider operators are rejected and change nothing
+    pub fn apply_controlled_gate(
+        &mut self,
+        controls: &[usize],
+        target: usize,
+        operator: impl Into<Operator>,
+    ) -> Result<(), String> {
+        self.apply_controlled_matrix2(controls, target, operator.into().one_qubit_matrix2()?);
+        Ok(())
+    }
+
+    /// `apply_controlled_gate` with a 2×2 array
+    pub(crate) fn apply_controlled_matrix2(
+        &mut self,
+        controls: &[usize],
+        target: usize,
+        matrix: Matrix2,
+    ) {
+        assert!(target < self.num_qubits, "target qubit out of range");
+        assert!(
+            controls.iter().all(|&c| c < self.num_qubits && c != target),
+            "invalid control qubit"
+        );
+        let lower: Vec<usize> = controls.iter().copied().filter(|&c| c < target).collect();
+        let mut memo = GateMemo::default();
+        let root = self.root;
+        self.root = self.apply_edge(root, controls, &lower, target, &matrix, &mut memo);
+        if self.nodes.len() > self.collect_at {
+            self.collect_garbage();
+        }
+    }
+
+    fn apply_edge(
+        &mut self,
+        e: Edge,
+        controls: &[usize],
+        lower: &[usize],
+        target: usize,
+        matrix: &Matrix2,
+        memo: &mut GateMemo,
+    ) -> Edge {
+        if e.is_zero() {
+            return e;
+        }
+        if let Some(&r) = memo.apply.get(&e.node) {
+            return r.scaled(e.weight);
+        }
+        let node = self.nodes[e.node];
+        let [c0, c1] = node.children;
+        let r = if node.qubit > target {
+            let n0 = if controls.contains(&node.qubit) {
+                c0
+            } else {
+                self.apply_edge(c0, controls, lower, target, matrix, memo)
+            };
+            let n1 = self.apply_edge(c1, controls, lower, target, matrix, memo);
+            self.make_node(node.qubit, n0, n1)
+        } else {
+            let one = Complex64::new(1.0, 0.0);
+            // with controls below the target only their |1…1⟩ part is rotated:
+            // c' = c + (U − I) P c
+            let (p0, p1, d00, d11, keep) = if lower.is_empty() {
+                (c0, c1, matrix[0][0], matrix[1][1], false)
+            } else {
+                let p0 = self.project(c0, lower, &mut memo.project);
+                let p1 = self.project(c1, lower, &mut memo.project);
+                (p0, p1, matrix[0][0] - one, matrix[1][1] - one, true)
+            };
+            let n0 = self.add(p0.scaled(d00), p1.scaled(matrix[0][1]), &mut memo.add);
+            let n1 = self.add(p0.scaled(matrix[1][0]), p1.scaled(d11), &mut memo.add);
+            let (n0, n1) = if keep {
+                (self.add(c0, n0, &mut memo.add), self.add(c1, n1, &mut memo.add))
+            } else {
+                (n0, n1)
+            };
+            self.make_node(node.qubit, n0, n1)
+        };
+        memo.apply.insert(e.node, r);
+        r.scaled(e.weight)
+    }
+
+    pub fn apply_instruction(&mut self, instruction: &Instruction) {
+        let q = &instruction.qubits;
+        match instruction.gate {
+            Gate::Cx => self.apply_controlled_matrix2(&[q[0]], q[1], x_matrix()),
+            Gate::Cz => self.apply_controlled_matrix2(&[q[0]], q[1], z_matrix()),
+            Gate::Swap => {
+                self.apply_controlled_matrix2(&[q[0]], q[1], x_matrix());
+                self.apply_controlled_matrix2(&[q[1]], q[0], x_matrix());
+                self.apply_controlled_matrix2(&[q[0]], q[1], x_matrix());
+            }
+            Gate::Mcx(n) => self.apply_controlled_matrix2(&q[..n], q[n], x_matrix()),
+            Gate::Mcz(n) => self.apply_controlled_matrix2(&q[..n], q[n], z_matrix()),
+            Gate::Mcu(n, ref operator) => {
+                let matrix = operator.to_matrix2().expect("one-qubit operator");
+                self.apply_controlled_matrix2(&q[..n], q[n], matrix);
+            }
+            ref gate => {
+                let matrix = gate.matrix2().expect("single-qubit gate has a matrix");
+                self.apply_controlled_matrix2(&[], q[0], matrix);
+            }
+        }
+    }
+
+    pub fn apply_circuit(&mut self, circuit: &Circuit) {
+        assert!(circuit.num_qubits() <= self.num_qubits, "circuit is wider than the diagram");
+        for instruction in circuit.instructions() {
+            self.apply_instruction(instruction);
+        }
+    }
+
+    fn reachable(&self) -> Vec<usize> {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![self.root.node];
+        while let Some(id) = stack.pop() {
+            if std::mem::replace(&mut seen[id], true) {
+                continue;
+            }
+            order.push(id);
+            if id != TERMINAL {
+                stack.extend(self.nodes[id].children.iter().map(|c| c.node));
+            }
+        }
+        order
+    }
+
+    /// drop nodes no longer reachable from the root
+    fn collect_garbage(&mut self) {
+        self.peak_nodes = self.peak_nodes.max(self.nodes.len());
+        let mut live = self.reachable();
+        // children before parents, so remapped ids are known when needed
+        live.sort_by_key(|&id| if id == TERMINAL { 0 } else { self.nodes[id].qubit + 1 });
+        let mut remap = HashMap::with_capacity(live.len());
+        let (old, mut nodes) = (std::mem::take(&mut self.nodes), Vec::with_capacity(live.len()));
+        self.unique.clear();
+        for id in live {
+            let mut node = old[id];
+            if id != TERMINAL {
+                for child in &mut node.children {
+                    child.node = remap[&child.node];
+                }
+                let [c0, c1] = node.children;
+                let key = (node.qubit, c0.node, rounded(c0.weight), c1.node, rounded(c1.weight));
+                self.unique.insert(key, nodes.len());
+            }
+            remap.insert(id, nodes.len());
+            nodes.push(node);
+        }
+        self.nodes = nodes;
+        self.root.node = remap[&self.root.node];
+        self.collect_at = (2 * self.nodes.len()).max(1 << 12);
+    }
+
+    /// ⟨index|ψ⟩, qubit q being bit q of `index`
+    pub fn amplitude(&self, index: usize) -> Complex64 {
+        let mut e = self.root;
+        let mut amplitude = Complex64::new(1.0, 0.0);
+        while !e.is_zero() && e.node != TERMINAL {
+            amplitude *= e.weight;
+            let node = self.nodes[e.node];
+            e = node.children[(index >> node.qubit) & 1];
+        }
+        if e.is_zero() { Complex64::new(0.0, 0.0) } else { amplitude * e.weight }
+    }
+
+    /// squared norm of the sub-vector below each reachable node
+    fn norms(&self) -> HashMap<usize, f64> {
+        let mut live = self.reachable();
+        live.sort_by_key(|&id| if id == TERMINAL { 0 } else { self.nodes[id].qubit + 1 });
+        let mut norms = HashMap::with_capacity(live.len());
+        for id in live {
+            let norm = if id == TERMINAL {
+                1.0
+            } else {
+                let children = self.nodes[id].children;
+                children
+                    .iter()
+                    .filter(|c| !c.is_zero())
+                    .map(|c| c.weight.norm_sqr() * norms[&c.node])
+                    .sum()
+            };
+            norms.insert(id, norm);
+        }
+        norms
+    }
+
+    /// `shots` outcomes drawn top-down through the diagram, O(n) per shot
+    pub fn sample_counts(&self, shots: usize, rng: &mut Rng) -> BTreeMap<usize, usize> {
+        assert!(self.num_qubits <= usize::BITS as usize, "outcomes do not fit in usize");
+        let norms = self.norms();
+        let mut counts = BTreeMap::new();
+        for _ in 0..shots {
+            let (mut e, mut outcome) = (self.root, 0usize);
+            while e.node != TERMINAL {
+                let node = self.nodes[e.node];
+                let weight = |c: &Edge| {
+                    if c.is_zero() { 0.0 } else { c.weight.norm_sqr() * norms[&c.node] }
+                };
+                let (w0, w1) = (weight(&node.children[0]), weight(&node.children[1]));
+                let bit = rng.gen_bool(w1 / (w0 + w1));
+                outcome |= usize::from(bit) << node.qubit;
+                e = node.children[usize::from(bit)];
+            }
+            *counts.entry(outcome).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// dense copy of the state
+    pub fn to_register(&self) -> Register {
+        let amplitudes = (0..1usize << self.num_qubits).map(|i| self.amplitude(i)).collect();
+        Register::from_amplitudes(amplitudes)
+    }
+}
+
+/// simulate once on a decision diagram and sample `shots` outcomes
+pub fn run_decision_diagram(circuit: &Circuit, shots: usize, rng: &mut Rng) -> RunResult {
+    let start = Instant::now();
+    let mut dd = DecisionDiagram::new(circuit.num_qubits());
+    dd.apply_circuit(circuit);
+    let counts = dd.sample_counts(shots, rng);
+    let mut report = RunReport::for_circuit("decision_diagram", circuit, shots);
+    report.wall_time = start.elapsed();
+    report.peak_memory_bytes = dd.peak_memory_bytes();
+    RunResult { counts, report }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::qft::qft;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn test_matches_statevector() {
+        let mut rng = Rng::seed_from_u64(61);
+        let mut circuit = Circuit::new(5);
+        for _ in 0..60 {
+            let (a, b, c) = (rng.gen_range(5), rng.gen_range(5), rng.gen_range(5));
+            match rng.gen_range(6) {
+                0 => circuit.h(a),
+                1 => circuit.t(a),
+                2 => circuit.ry(rng.next_f64() * 3.0, a),
+                3 if a != b => circuit.cx(b, a),
+                4 if a != b && b != c && a != c => circuit.mcx(&[c, a], b),
+                _ if a != b => circuit.swap(a, b),
+                _ => circuit.s(a),
+            };
+        }
+        let mut dd = DecisionDiagram::new(5);
+        dd.apply_circuit(&circuit);
+        let mut register = Register::new(5);
+        register.apply_circuit(&circuit);
+        crate::assert_state_eq!(dd.to_register(), register);
+        assert!(dd.apply_controlled_gate(&[0], 1, Gate::Cx.operator()).is_err());
+        crate::assert_state_eq!(dd.to_register(), register);
+    }
+
+    #[test]
+    fn test_ghz_far_beyond_dense_limit() {
+        let n = 60;
+        let mut circuit = Circuit::new(n);
+        circuit.h(0);
+        for q in 0..n - 1 {
+            circuit.cx(q, q + 1);
+        }
+        let mut rng = Rng::seed_from_u64(62);
+        let result = run_decision_diagram(&circuit, 200, &mut rng);
+        assert_eq!(result.counts.len(), 2);
+        assert!(result.counts.keys().all(|&k| k == 0 || k == (1 << n) - 1));
+        assert_eq!(result.report.backend, "decision_diagram");
+
+        let mut dd = DecisionDiagram::new(n);
+        dd.apply_circuit(&circuit);
+        assert!(dd.num_nodes() <= 2 * n + 1);
+        assert!((dd.amplitude((1 << n) - 1).re - FRAC_1_SQRT_2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_qft_of_basis_state_stays_small() {
+        let n = 32;
+        let mut circuit = Circuit::new(n);
+        circuit.x(0).x(5).x(31);
+        circuit.append(&qft(n));
+        let mut dd = DecisionDiagram::new(n);
+        dd.apply_circuit(&circuit);
+        // a product state needs one node per qubit
+        assert_eq!(dd.num_nodes(), n + 1);
+        let uniform = (0.5f64).powf(n as f64 / 2.0);
+        assert!((dd.amplitude(12345).norm() - uniform).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,196 @@
+use num_complex::Complex64;
+use super::matrix::Matrix;
+use super::register::Register;
+
+/// mixed n-qubit state ρ, basis index bit q for qubit q
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensityMatrix {
+    num_qubits: usize,
+    matrix: Matrix,
+}
+
+/// |ψ⟩⟨ψ| for amplitudes ψ
+fn outer(amplitudes: &[Complex64]) -> Matrix {
+    let dim = amplitudes.len();
+    Matrix::from_fn(dim, dim, |i, j| amplitudes[i] * amplitudes[j].conj())
+}
+
+impl DensityMatrix {
+    /// panics unless `matrix` is square with a power-of-two dimension
+    pub fn from_matrix(matrix: Matrix) -> Self {
+        assert_eq!(matrix.rows(), matrix.cols(), "density matrix must be square");
+        assert!(matrix.rows().is_power_of_two(), "dimension must be a power of two");
+        Self {
+            num_qubits: matrix.rows().trailing_zeros() as usize,
+            matrix,
+        }
+    }
+
+    pub fn pure(state: &Register) -> Self {
+        Self::from_matrix(outer(state.amplitudes()))
+    }
+
+    /// equal-weight mixture Σ |ψ_k⟩⟨ψ_k| / K of `states`
+    pub fn mixture(states: &[Register]) -> Self {
+        assert!(!states.is_empty(), "mixture needs at least one state");
+        let dim = states[0].amplitudes().len();
+        let weight = Complex64::new(1.0 / states.len() as f64, 0.0);
+        let mut matrix = Matrix::zeros(dim, dim);
+        for state in states {
+            assert_eq!(state.amplitudes().len(), dim, "qubit count mismatch");
+            matrix = &matrix + &outer(state.amplitudes()).scaled(weight);
+        }
+        Self::from_matrix(matrix)
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    pub fn trace(&self) -> f64 {
+        (0..self.matrix.rows()).map(|i| self.matrix[(i, i)].re).sum()
+    }
+
+    /// Tr(ρ·O) for an observable on all qubits
+    pub fn expectation(&self, observable: &Matrix) -> f64 {
+        let product = &self.matrix * observable;
+        (0..product.rows()).map(|i| product[(i, i)].re).sum()
+    }
+
+    /// reduced state on `keep`, whose k-th entry becomes qubit k
+    pub fn partial_trace(&self, keep: &[usize]) -> DensityMatrix {
+        let traced: Vec<usize> = (0..self.num_qubits).filter(|q| !keep.contains(q)).collect();
+        let embed = |kept: usize, rest: usize| {
+            let place = |qubits: &[usize], bits: usize| {
+                qubits
+                    .iter()
+                    .enumerate()
+                    .fold(0, |index, (k, &q)| index | (((bits >> k) & 1) << q))
+            };
+            place(keep, kept) | place(&traced, rest)
+        };
+        let dim = 1 << keep.len();
+        let matrix = Matrix::from_fn(dim, dim, |i, j| {
+            (0..1usize << traced.len())
+                .map(|r| self.matrix[(embed(i, r), embed(j, r))])
+                .sum()
+        });
+        DensityMatrix::from_matrix(matrix)
+    }
+
+    /// ρ^{T_A}: transpose the indices of the qubits in `subsystem`
+    pub fn partial_transpose(&self, subsystem: &[usize]) -> Matrix {
+        let mask = subsystem.iter().fold(0, |mask, &q| mask | (1 << q));
+        let dim = self.matrix.rows();
+        Matrix::from_fn(dim, dim, |i, j| {
+            let (row, col) = ((i & !mask) | (j & mask), (j & !mask) | (i & mask));
+            self.matrix[(row, col)]
+        })
+    }
+
+    /// trace norm ‖ρ^{T_A}‖₁, the sum of |eigenvalues| of the partial transpose
+    fn partial_transpose_norm(&self, subsystem: &[usize]) -> f64 {
+        let values = self.partial_transpose(subsystem).hermitian_eigenvalues();
+        values.iter().map(|v| v.abs()).sum()
+    }
+
+    /// N = (‖ρ^{T_A}‖₁ − 1)/2; positive only for entangled states (PPT criterion)
+    pub fn negativity(&self, subsystem: &[usize]) -> f64 {
+        ((self.partial_transpose_norm(subsystem) - 1.0) / 2.0).max(0.0)
+    }
+
+    /// E_N = log₂ ‖ρ^{T_A}‖₁, an upper bound on distillable entanglement
+    pub fn log_negativity(&self, subsystem: &[usize]) -> f64 {
+        self.partial_transpose_norm(subsystem).log2().max(0.0)
+    }
+}
+
+/// observable W with Tr(W·σ) ≥ 0 for every state σ separable across the cut
+#[derive(Debug, Clone)]
+pub struct EntanglementWitness {
+    pub observable: Matrix,
+}
+
+impl EntanglementWitness {
+    /// W = α·I − |ψ⟩⟨ψ| with α the largest squared Schmidt coefficient of
+    /// `target` across `subsystem`, the best fidelity any separable state reaches
+    pub fn fidelity(target: &Register, subsystem: &[usize]) -> Self {
+        let alpha = DensityMatrix::pure(target)
+            .partial_trace(subsystem)
+            .matrix()
+            .hermitian_eigenvalues()
+            .into_iter()
+            .fold(0.0, f64::max);
+        let projector = outer(target.amplitudes());
+        let dim = projector.rows();
+        let observable = Matrix::from_fn(dim, dim, |i, j| {
+            let diagonal = if i == j { alpha } else { 0.0 };
+            Complex64::new(diagonal, 0.0) - projector[(i, j)]
+        });
+        Self { observable }
+    }
+
+    pub fn value(&self, state: &DensityMatrix) -> f64 {
+        state.expectation(&self.observable)
+    }
+
+    /// a negative witness value certifies entanglement
+    pub fn detects(&self, state: &DensityMatrix) -> bool {
+        self.value(state) < -1e-12
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::testing::bell_state as bell;
+
+    /// p·|Φ+⟩⟨Φ+| + (1 − p)·I/4
+    fn werner(p: f64) -> DensityMatrix {
+        let pure = DensityMatrix::pure(&bell());
+        let matrix = Matrix::from_fn(4, 4, |i, j| {
+            let noise = if i == j { (1.0 - p) / 4.0 } else { 0.0 };
+            pure.matrix()[(i, j)] * p + noise
+        });
+        DensityMatrix::from_matrix(matrix)
+    }
+
+    #[test]
+    fn test_bell_state_has_one_ebit_of_negativity() {
+        let rho = DensityMatrix::pure(&bell());
+        assert!((rho.negativity(&[0]) - 0.5).abs() < 1e-9);
+        assert!((rho.log_negativity(&[1]) - 1.0).abs() < 1e-9);
+        let product = DensityMatrix::pure(&Register::new(2));
+        assert!(product.negativity(&[0]).abs() < 1e-9);
+        let reduced = rho.partial_trace(&[1]);
+        assert!((reduced.matrix()[(0, 0)].re - 0.5).abs() < 1e-12);
+        assert!(reduced.matrix()[(0, 1)].norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_werner_states_entangled_above_one_third() {
+        for (p, entangled) in [(0.2, false), (1.0 / 3.0, false), (0.5, true), (0.9, true)] {
+            let rho = werner(p);
+            assert_eq!(rho.negativity(&[0]) > 1e-9, entangled, "p = {}", p);
+            let expected = ((3.0 * p - 1.0) / 4.0).max(0.0);
+            assert!((rho.negativity(&[0]) - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_fidelity_witness_matches_bell_threshold() {
+        let witness = EntanglementWitness::fidelity(&bell(), &[0]);
+        // ⟨W⟩ = 1/2 − F with F = (1 + 3p)/4, negative once p > 1/3
+        for p in [0.0, 0.3, 0.4, 1.0] {
+            let value = witness.value(&werner(p));
+            assert!((value - (0.5 - (1.0 + 3.0 * p) / 4.0)).abs() < 1e-9);
+            assert_eq!(witness.detects(&werner(p)), p > 1.0 / 3.0);
+        }
+        let product = DensityMatrix::pure(&Register::new(2));
+        assert!(!witness.detects(&product));
+    }
+}
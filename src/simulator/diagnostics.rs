@@ -0,0 +1,212 @@
+use std::fmt;
+use super::density::DensityMatrix;
+use super::pauli::Pauli;
+use super::register::Register;
+use super::single_qubit::SingleQubit;
+
+/// scalar summaries of a state, pure or mixed; entropies in bits
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateDiagnostics {
+    /// Tr ρ², 1 for pure states and 2⁻ⁿ for the maximally mixed one
+    pub purity: f64,
+    /// 1 − Tr ρ²
+    pub linear_entropy: f64,
+    pub von_neumann_entropy: f64,
+    /// Σ_{i≠j} |ρ_ij| in the computational basis
+    pub l1_coherence: f64,
+    /// S(diag ρ) − S(ρ)
+    pub relative_entropy_coherence: f64,
+    /// length of each qubit's reduced Bloch vector, 1 when unentangled and pure
+    pub bloch_lengths: Vec<f64>,
+}
+
+impl fmt::Display for StateDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Purity: {:.4} (linear entropy {:.4})", self.purity, self.linear_entropy)?;
+        writeln!(f, "Von Neumann entropy: {:.4} bits", self.von_neumann_entropy)?;
+        writeln!(
+            f,
+            "Coherence: l1 {:.4}, relative entropy {:.4} bits",
+            self.l1_coherence, self.relative_entropy_coherence
+        )?;
+        let lengths: Vec<String> = self
+            .bloch_lengths
+            .iter()
+            .enumerate()
+            .map(|(q, r)| format!("q{}: {:.3}", q, r))
+            .collect();
+        write!(f, "Bloch lengths: {}", lengths.join(", "))
+    }
+}
+
+fn shannon(probabilities: impl Iterator<Item = f64>) -> f64 {
+    probabilities.filter(|&p| p > 1e-15).map(|p| -p * p.log2()).sum()
+}
+
+fn length(v: [f64; 3]) -> f64 {
+    v.iter().map(|c| c * c).sum::<f64>().sqrt()
+}
+
+impl DensityMatrix {
+    pub fn purity(&self) -> f64 {
+        let m = self.matrix();
+        (0..m.rows())
+            .flat_map(|i| (0..m.cols()).map(move |j| (i, j)))
+            .map(|(i, j)| m[(i, j)].norm_sqr())
+            .sum()
+    }
+
+    pub fn von_neumann_entropy(&self) -> f64 {
+        shannon(self.matrix().hermitian_eigenvalues().into_iter())
+    }
+
+    pub fn l1_coherence(&self) -> f64 {
+        let m = self.matrix();
+        (0..m.rows())
+            .flat_map(|i| (0..m.cols()).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| m[(i, j)].norm())
+            .sum()
+    }
+
+    pub fn relative_entropy_coherence(&self) -> f64 {
+        let m = self.matrix();
+        let diagonal = shannon((0..m.rows()).map(|i| m[(i, i)].re));
+        (diagonal - self.von_neumann_entropy()).max(0.0)
+    }
+
+    /// (⟨X⟩, ⟨Y⟩, ⟨Z⟩) of qubit `q`'s reduced state
+    pub fn bloch_vector(&self, q: usize) -> [f64; 3] {
+        let reduced = self.partial_trace(&[q]);
+        let m = reduced.matrix();
+        [2.0 * m[(0, 1)].re, -2.0 * m[(0, 1)].im, m[(0, 0)].re - m[(1, 1)].re]
+    }
+
+    pub fn diagnostics(&self) -> StateDiagnostics {
+        let purity = self.purity();
+        StateDiagnostics {
+            purity,
+            linear_entropy: 1.0 - purity,
+            von_neumann_entropy: self.von_neumann_entropy(),
+            l1_coherence: self.l1_coherence(),
+            relative_entropy_coherence: self.relative_entropy_coherence(),
+            bloch_lengths: (0..self.num_qubits()).map(|q| length(self.bloch_vector(q))).collect(),
+        }
+    }
+
+    /// diagonal probabilities followed by the scalar diagnostics
+    pub fn display_extended(&self) {
+        let m = self.matrix();
+        let parts: Vec<String> = (0..m.rows())
+            .filter(|&k| m[(k, k)].re > 1e-10)
+            .map(|k| format!("|{:0w$b}⟩: {:.1}%", k, m[(k, k)].re * 100.0, w = self.num_qubits()))
+            .collect();
+        println!("Probabilities: {}", parts.join(", "));
+        println!("{}", self.diagnostics());
+    }
+}
+
+impl Register {
+    /// always 1 for a normalized state vector
+    pub fn purity(&self) -> f64 {
+        self.amplitudes().iter().map(|a| a.norm_sqr()).sum::<f64>().powi(2)
+    }
+
+    /// (Σ|ψ_i|)² − 1, from |ρ_ij| = |ψ_i|·|ψ_j|
+    pub fn l1_coherence(&self) -> f64 {
+        self.amplitudes().iter().map(|a| a.norm()).sum::<f64>().powi(2) - 1.0
+    }
+
+    /// for a pure state the Shannon entropy of its outcome distribution
+    pub fn relative_entropy_coherence(&self) -> f64 {
+        self.distribution().entropy()
+    }
+
+    /// diagnostics without forming the 4ⁿ-entry density matrix
+    pub fn diagnostics(&self) -> StateDiagnostics {
+        let purity = self.purity();
+        let bloch_lengths = (0..self.num_qubits())
+            .map(|q| {
+                let v = [Pauli::X, Pauli::Y, Pauli::Z].map(|p| self.expectation(&[(q, p)]));
+                length(v)
+            })
+            .collect();
+        StateDiagnostics {
+            purity,
+            linear_entropy: 1.0 - purity,
+            von_neumann_entropy: 0.0,
+            l1_coherence: self.l1_coherence(),
+            relative_entropy_coherence: self.relative_entropy_coherence(),
+            bloch_lengths,
+        }
+    }
+
+    /// ket expansion and probabilities followed by the scalar diagnostics
+    pub fn display_extended(&self) {
+        self.display();
+        println!("{}", self.diagnostics());
+    }
+}
+
+impl SingleQubit {
+    pub fn bloch_length(&self) -> f64 {
+        length(self.bloch_vector())
+    }
+
+    /// 2|αβ|, the l1 coherence of a qubit
+    pub fn l1_coherence(&self) -> f64 {
+        2.0 * (self.alpha * self.beta).norm()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::circuit::Circuit;
+
+    #[test]
+    fn test_pure_and_mixed_diagnostics_agree() {
+        let mut register = Register::new(3);
+        register.apply_circuit(Circuit::new(3).h(0).cx(0, 1).ry(0.7, 2));
+        let pure = register.diagnostics();
+        let dense = DensityMatrix::pure(&register).diagnostics();
+        assert!((pure.purity - 1.0).abs() < 1e-12 && (dense.purity - 1.0).abs() < 1e-9);
+        assert!(dense.von_neumann_entropy.abs() < 1e-6);
+        assert!((pure.l1_coherence - dense.l1_coherence).abs() < 1e-9);
+        assert!((pure.relative_entropy_coherence - dense.relative_entropy_coherence).abs() < 1e-6);
+        for (a, b) in pure.bloch_lengths.iter().zip(&dense.bloch_lengths) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        // the Bell pair's qubits are maximally mixed, qubit 2 is pure
+        assert!(pure.bloch_lengths[0] < 1e-9 && (pure.bloch_lengths[2] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_maximally_mixed_state() {
+        let states: Vec<Register> = (0..4)
+            .map(|k| {
+                let mut register = Register::new(2);
+                for q in (0..2).filter(|q| k >> q & 1 == 1) {
+                    register.apply_gate(q, crate::simulator::gates::x_matrix()).unwrap();
+                }
+                register
+            })
+            .collect();
+        let diagnostics = DensityMatrix::mixture(&states).diagnostics();
+        assert!((diagnostics.purity - 0.25).abs() < 1e-12);
+        assert!((diagnostics.von_neumann_entropy - 2.0).abs() < 1e-9);
+        assert_eq!(diagnostics.l1_coherence, 0.0);
+        assert!(diagnostics.to_string().contains("Purity: 0.2500"));
+    }
+
+    #[test]
+    fn test_plus_state_is_maximally_coherent() {
+        let mut register = Register::new(2);
+        register.apply_circuit(Circuit::new(2).h(0).h(1));
+        assert!((register.l1_coherence() - 3.0).abs() < 1e-12);
+        assert!((register.relative_entropy_coherence() - 2.0).abs() < 1e-12);
+        let mut qubit = SingleQubit::new();
+        qubit.apply_gate(crate::simulator::gates::h_matrix()).unwrap();
+        assert!((qubit.l1_coherence() - 1.0).abs() < 1e-12);
+        assert!((qubit.bloch_length() - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt;
+use super::circuit::{Circuit, Instruction};
+
+/// one structural change between two circuits
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffEntry {
+    /// instruction `index` of the new circuit has no counterpart in the old
+    Added { index: usize, instruction: Instruction },
+    /// instruction `index` of the old circuit has no counterpart in the new
+    Removed { index: usize, instruction: Instruction },
+    /// the same instruction at another place in the gate order
+    Moved {
+        from: usize,
+        to: usize,
+        instruction: Instruction,
+    },
+}
+
+/// gate-level difference from an old circuit to a new one
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitDiff {
+    pub old_qubits: usize,
+    pub new_qubits: usize,
+    /// instructions kept in the same relative order
+    pub unchanged: usize,
+    /// changes in new-circuit order, removals where they used to be
+    pub entries: Vec<DiffEntry>,
+    /// qubit labels of the new circuit, for display
+    labels: Vec<String>,
+}
+
+impl CircuitDiff {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.old_qubits == self.new_qubits
+    }
+
+    fn count(&self, pick: impl Fn(&DiffEntry) -> bool) -> usize {
+        self.entries.iter().filter(|e| pick(e)).count()
+    }
+
+    pub fn added(&self) -> usize {
+        self.count(|e| matches!(e, DiffEntry::Added { .. }))
+    }
+
+    pub fn removed(&self) -> usize {
+        self.count(|e| matches!(e, DiffEntry::Removed { .. }))
+    }
+
+    pub fn moved(&self) -> usize {
+        self.count(|e| matches!(e, DiffEntry::Moved { .. }))
+    }
+
+    fn text(&self, instruction: &Instruction) -> String {
+        let label = |q: usize| self.labels.get(q).cloned().unwrap_or_else(|| format!("q{}", q));
+        let qubits: Vec<String> = instruction.qubits.iter().map(|&q| label(q)).collect();
+        format!("{} {}", instruction.gate.label(), qubits.join(", "))
+    }
+}
+
+/// instructions of a longest common subsequence, as (old, new) index pairs
+fn common_subsequence(old: &[Instruction], new: &[Instruction]) -> Vec<(usize, usize)> {
+    let (n, m) = (old.len(), new.len());
+    // lengths[i][j] = LCS of old[i..] and new[j..]
+    let mut lengths = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut pairs = Vec::new();
+    while i < n && j < m {
+        if old[i] == new[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+impl Circuit {
+    /// structural diff from `self` to `other`: instructions outside a longest
+    /// common subsequence are added or removed, and a removed instruction
+    /// that reappears unchanged elsewhere counts as moved
+    ///
+    /// Instructions compare exactly, parameters included; markers and qubit
+    /// names are not compared.
+    pub fn diff(&self, other: &Circuit) -> CircuitDiff {
+        let (old, new) = (self.instructions(), other.instructions());
+        let pairs = common_subsequence(old, new);
+        let mut kept_old = vec![false; old.len()];
+        let mut kept_new = vec![false; new.len()];
+        for &(i, j) in &pairs {
+            kept_old[i] = true;
+            kept_new[j] = true;
+        }
+        // pair leftover instructions that are equal as moves, in order
+        let mut moved_from: Vec<Option<usize>> = vec![None; new.len()];
+        let mut taken = kept_old.clone();
+        for j in (0..new.len()).filter(|&j| !kept_new[j]) {
+            if let Some(i) = (0..old.len()).find(|&i| !taken[i] && old[i] == new[j]) {
+                taken[i] = true;
+                moved_from[j] = Some(i);
+            }
+        }
+        let moved_old: Vec<bool> = (0..old.len()).map(|i| taken[i] && !kept_old[i]).collect();
+        let mut entries = Vec::new();
+        let mut i = 0;
+        let mut removals_up_to = |end: usize, entries: &mut Vec<DiffEntry>| {
+            while i < end {
+                if !kept_old[i] && !moved_old[i] {
+                    entries.push(DiffEntry::Removed { index: i, instruction: old[i].clone() });
+                }
+                i += 1;
+            }
+        };
+        let mut next_pair = pairs.iter().peekable();
+        for j in 0..new.len() {
+            if let Some(&(pi, _)) = next_pair.next_if(|&&(_, pj)| pj == j) {
+                removals_up_to(pi + 1, &mut entries);
+                continue;
+            }
+            let instruction = new[j].clone();
+            entries.push(match moved_from[j] {
+                Some(from) => DiffEntry::Moved { from, to: j, instruction },
+                None => DiffEntry::Added { index: j, instruction },
+            });
+        }
+        removals_up_to(old.len(), &mut entries);
+        CircuitDiff {
+            old_qubits: self.num_qubits(),
+            new_qubits: other.num_qubits(),
+            unchanged: pairs.len(),
+            entries,
+            labels: other.qubit_labels(),
+        }
+    }
+}
+
+impl fmt::Display for CircuitDiff {
+    /// a summary line, then `+`, `-` and `~` lines with instruction indices
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes ({} unchanged)", self.unchanged);
+        }
+        write!(
+            f,
+            "{} unchanged, {} added, {} removed, {} moved",
+            self.unchanged,
+            self.added(),
+            self.removed(),
+            self.moved()
+        )?;
+        if self.old_qubits != self.new_qubits {
+            write!(f, "\nqubits: {} → {}", self.old_qubits, self.new_qubits)?;
+        }
+        for entry in &self.entries {
+            match entry {
+                DiffEntry::Added { index, instruction } => {
+                    write!(f, "\n+ [{}] {}", index, self.text(instruction))?
+                }
+                DiffEntry::Removed { index, instruction } => {
+                    write!(f, "\n- [{}] {}", index, self.text(instruction))?
+                }
+                DiffEntry::Moved { from, to, instruction } => {
+                    write!(f, "\n~ [{} → {}] {}", from, to, self.text(instruction))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimize::{cancel_inverse_pairs, fuse_rotations};
+    use crate::simulator::gates::Gate;
+
+    #[test]
+    fn test_identical_circuits() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1);
+        let diff = circuit.diff(&circuit.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "no changes (2 unchanged)");
+    }
+
+    #[test]
+    fn test_added_removed_and_moved() {
+        let mut old = Circuit::new(2);
+        old.h(0).t(1).cx(0, 1).rz(0.5, 1);
+        let mut new = Circuit::new(2);
+        new.h(0).cx(0, 1).x(0).t(1).rz(0.5, 1);
+        new.name_qubit(1, "bob");
+        let diff = old.diff(&new);
+        assert_eq!((diff.unchanged, diff.added(), diff.removed(), diff.moved()), (3, 1, 0, 1));
+        assert_eq!(
+            diff.to_string(),
+            "3 unchanged, 1 added, 0 removed, 1 moved\n+ [2] x q0\n~ [1 → 3] t bob"
+        );
+    }
+
+    #[test]
+    fn test_diff_of_an_optimization_pass() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(1).h(1).rz(0.2, 0).rz(0.3, 0).cx(0, 1);
+        let optimized = fuse_rotations(&cancel_inverse_pairs(&circuit));
+        let diff = circuit.diff(&optimized);
+        assert_eq!((diff.unchanged, diff.added(), diff.removed()), (1, 1, 4));
+        let fused = Instruction { gate: Gate::Rz(0.5), qubits: vec![0] };
+        assert!(diff.entries.contains(&DiffEntry::Added { index: 0, instruction: fused }));
+        assert!(diff.to_string().contains("- [0] h q1"));
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+use super::rng::Rng;
+
+/// probability distribution over computational basis outcomes
+///
+/// Outcome `k` is the basis state whose bit `q` is qubit `q` (qubit 0 is the
+/// least significant bit), matching `Register` indexing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    num_qubits: usize,
+    probs: Vec<f64>,
+}
+
+impl Distribution {
+    /// `probs` must have 2^num_qubits entries; they are renormalized
+    pub fn new(num_qubits: usize, probs: Vec<f64>) -> Self {
+        assert_eq!(probs.len(), 1 << num_qubits, "expected 2^n probabilities");
+        let total: f64 = probs.iter().sum();
+        let probs = if total > 1e-10 {
+            probs.into_iter().map(|p| p / total).collect()
+        } else {
+            probs
+        };
+        Self { num_qubits, probs }
+    }
+
+    /// empirical distribution from sampled counts
+    pub fn from_counts(num_qubits: usize, counts: &BTreeMap<usize, usize>) -> Self {
+        let mut probs = vec![0.0; 1 << num_qubits];
+        for (&outcome, &count) in counts {
+            probs[outcome] += count as f64;
+        }
+        Self::new(num_qubits, probs)
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probs
+    }
+
+    pub fn prob(&self, outcome: usize) -> f64 {
+        self.probs[outcome]
+    }
+
+    /// draw one outcome
+    pub fn sample(&self, rng: &mut Rng) -> usize {
+        let r = rng.next_f64();
+        let mut acc = 0.0;
+        for (outcome, &p) in self.probs.iter().enumerate() {
+            acc += p;
+            if r < acc {
+                return outcome;
+            }
+        }
+        // rounding left r past the last bucket
+        self.probs.iter().rposition(|&p| p > 0.0).unwrap_or(0)
+    }
+
+    /// cumulative table for drawing many outcomes by binary search
+    pub fn sampler(&self) -> Sampler {
+        let cdf: Vec<f64> = self
+            .probs
+            .iter()
+            .scan(0.0, |acc, &p| {
+                *acc += p;
+                Some(*acc)
+            })
+            .collect();
+        let last = self.probs.iter().rposition(|&p| p > 0.0).unwrap_or(0);
+        Sampler { cdf, last }
+    }
+
+    /// draw `shots` outcomes and tally them
+    pub fn sample_counts(&self, shots: usize, rng: &mut Rng) -> BTreeMap<usize, usize> {
+        let sampler = self.sampler();
+        let mut counts = BTreeMap::new();
+        for _ in 0..shots {
+            *counts.entry(sampler.sample(rng)).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// k most probable outcomes, highest first
+    pub fn top_k(&self, k: usize) -> Vec<(usize, f64)> {
+        let mut ranked: Vec<(usize, f64)> = self.probs.iter().copied().enumerate().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked.truncate(k);
+        ranked
+    }
+
+    /// Shannon entropy in bits
+    pub fn entropy(&self) -> f64 {
+        self.probs
+            .iter()
+            .filter(|&&p| p > 1e-15)
+            .map(|&p| -p * p.log2())
+            .sum()
+    }
+
+    /// total-variation distance: ½ Σ |p - q|
+    pub fn total_variation(&self, other: &Distribution) -> f64 {
+        assert_eq!(self.num_qubits, other.num_qubits, "qubit count mismatch");
+        0.5 * self
+            .probs
+            .iter()
+            .zip(&other.probs)
+            .map(|(p, q)| (p - q).abs())
+            .sum::<f64>()
+    }
+
+    /// outcome as a bitstring, most significant qubit first
+    pub fn format_outcome(&self, outcome: usize) -> String {
+        format!("{:0width$b}", outcome, width = self.num_qubits)
+    }
+
+    /// outcome as `label=bit` pairs in qubit order, e.g. `alice=1 bob=0`
+    /// with the labels of `Circuit::qubit_labels`
+    pub fn format_labeled(&self, outcome: usize, labels: &[String]) -> String {
+        assert_eq!(labels.len(), self.num_qubits, "need one label per qubit");
+        let bits: Vec<String> = labels
+            .iter()
+            .enumerate()
+            .map(|(q, label)| format!("{}={}", label, (outcome >> q) & 1))
+            .collect();
+        bits.join(" ")
+    }
+
+    pub fn display(&self) {
+        let parts: Vec<String> = self
+            .probs
+            .iter()
+            .enumerate()
+            .filter(|(_, &p)| p > 1e-10)
+            .map(|(k, &p)| format!("|{}⟩: {:.1}%", self.format_outcome(k), p * 100.0))
+            .collect();
+        println!("Probabilities: {}", parts.join(", "));
+    }
+}
+
+/// precomputed CDF of a distribution
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    cdf: Vec<f64>,
+    last: usize,
+}
+
+impl Sampler {
+    /// draw one outcome in O(log N)
+    pub fn sample(&self, rng: &mut Rng) -> usize {
+        let r = rng.next_f64();
+        // rounding can leave r past the last nonzero bucket
+        self.cdf.partition_point(|&c| c <= r).min(self.last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entropy_uniform() {
+        let dist = Distribution::new(2, vec![0.25; 4]);
+        assert!((dist.entropy() - 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_top_k_and_tvd() {
+        let a = Distribution::new(2, vec![0.5, 0.0, 0.0, 0.5]);
+        let b = Distribution::new(2, vec![0.25; 4]);
+        assert_eq!(a.top_k(2), vec![(0, 0.5), (3, 0.5)]);
+        assert!((a.total_variation(&b) - 0.5).abs() < 1e-10);
+        assert!(a.total_variation(&a).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_labeled_outcomes() {
+        let dist = Distribution::new(2, vec![0.25; 4]);
+        let labels = ["alice".to_string(), "bob".to_string()];
+        assert_eq!(dist.format_outcome(0b01), "01");
+        assert_eq!(dist.format_labeled(0b01, &labels), "alice=1 bob=0");
+    }
+
+    #[test]
+    fn test_sample_counts_match_probabilities() {
+        let dist = Distribution::new(1, vec![0.2, 0.8]);
+        let mut rng = Rng::seed_from_u64(1);
+        let counts = dist.sample_counts(10_000, &mut rng);
+        let empirical = Distribution::from_counts(1, &counts);
+        assert!(dist.total_variation(&empirical) < 0.02);
+        assert!(dist.sample(&mut rng) < 2);
+    }
+}
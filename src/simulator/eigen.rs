@@ -0,0 +1,140 @@
+use num_complex::Complex64;
+use super::matrix::Matrix;
+
+/// spectral decomposition M = V·diag(λ)·V† of a Hermitian matrix, eigenvalues
+/// ascending and eigenvectors the columns of a unitary V
+#[derive(Debug, Clone)]
+pub struct Eigen {
+    pub values: Vec<f64>,
+    pub vectors: Matrix,
+}
+
+impl Eigen {
+    /// normalized eigenvector of `values[k]`
+    pub fn vector(&self, k: usize) -> Vec<Complex64> {
+        self.vectors.column(k)
+    }
+
+    /// f(M) = V·diag(f(λ))·V†, e.g. a square root or a projector
+    pub fn map(&self, f: impl Fn(f64) -> f64) -> Matrix {
+        let n = self.values.len();
+        let weights: Vec<f64> = self.values.iter().map(|&v| f(v)).collect();
+        Matrix::from_fn(n, n, |i, j| {
+            (0..n)
+                .map(|k| self.vectors[(i, k)] * weights[k] * self.vectors[(j, k)].conj())
+                .sum()
+        })
+    }
+}
+
+impl Matrix {
+    /// eigenvalues and eigenvectors of a Hermitian matrix
+    ///
+    /// Complex cyclic Jacobi: each pivot b = |b|·e^{iφ} is first made real by
+    /// a phase on column q, then zeroed by a real Givens rotation.
+    pub fn eigen(&self) -> Eigen {
+        assert_eq!(self.rows(), self.cols(), "eigen needs a square matrix");
+        let n = self.rows();
+        let mut a = self.clone();
+        let mut v = Matrix::identity(n);
+        for _sweep in 0..100 {
+            let off: f64 = (0..n)
+                .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+                .map(|(i, j)| a[(i, j)].norm_sqr())
+                .sum();
+            if off < 1e-24 {
+                break;
+            }
+            for p in 0..n {
+                for q in p + 1..n {
+                    let b = a[(p, q)];
+                    if b.norm() < 1e-300 {
+                        continue;
+                    }
+                    let phase = Complex64::from_polar(1.0, -b.arg());
+                    for k in 0..n {
+                        a[(k, q)] *= phase;
+                        v[(k, q)] *= phase;
+                    }
+                    for k in 0..n {
+                        a[(q, k)] *= phase.conj();
+                    }
+                    let apq = a[(p, q)].re;
+                    let theta = (a[(q, q)].re - a[(p, p)].re) / (2.0 * apq);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let s = t * c;
+                    for m in [&mut a, &mut v] {
+                        for k in 0..n {
+                            let (mkp, mkq) = (m[(k, p)], m[(k, q)]);
+                            m[(k, p)] = mkp * c - mkq * s;
+                            m[(k, q)] = mkp * s + mkq * c;
+                        }
+                    }
+                    for k in 0..n {
+                        let (apk, aqk) = (a[(p, k)], a[(q, k)]);
+                        a[(p, k)] = apk * c - aqk * s;
+                        a[(q, k)] = apk * s + aqk * c;
+                    }
+                }
+            }
+        }
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&i, &j| a[(i, i)].re.total_cmp(&a[(j, j)].re));
+        Eigen {
+            values: order.iter().map(|&k| a[(k, k)].re).collect(),
+            vectors: Matrix::from_fn(n, n, |i, j| v[(i, order[j])]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::random::random_unitary;
+    use crate::simulator::rng::Rng;
+
+    fn random_hermitian(dim: usize, rng: &mut Rng) -> Matrix {
+        let g = Matrix::from_fn(dim, dim, |_, _| Complex64::new(rng.normal(), rng.normal()));
+        &g + &g.dagger()
+    }
+
+    fn max_difference(a: &Matrix, b: &Matrix) -> f64 {
+        a.as_slice().iter().zip(b.as_slice()).map(|(x, y)| (x - y).norm()).fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_decomposition_reconstructs_matrix() {
+        let mut rng = Rng::seed_from_u64(192);
+        for dim in [1, 2, 5, 8] {
+            let h = random_hermitian(dim, &mut rng);
+            let eigen = h.eigen();
+            assert!(eigen.vectors.is_unitary(1e-9));
+            assert!(eigen.values.windows(2).all(|w| w[0] <= w[1]));
+            assert!(max_difference(&eigen.map(|x| x), &h) < 1e-9);
+            let expected = h.hermitian_eigenvalues();
+            for (a, b) in eigen.values.iter().zip(&expected) {
+                assert!((a - b).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_degenerate_spectrum_keeps_orthonormal_vectors() {
+        // U·diag(1, 1, 1, −2)·U† has a threefold eigenvalue
+        let mut rng = Rng::seed_from_u64(193);
+        let u = random_unitary(2, &mut rng);
+        let d = Matrix::from_fn(4, 4, |i, j| {
+            let value = if i == 3 { -2.0 } else { 1.0 };
+            Complex64::new(if i == j { value } else { 0.0 }, 0.0)
+        });
+        let eigen = (&(&u * &d) * &u.dagger()).eigen();
+        assert!(eigen.vectors.is_unitary(1e-9));
+        assert!((eigen.values[0] + 2.0).abs() < 1e-9);
+        assert!(eigen.values[1..].iter().all(|v| (v - 1.0).abs() < 1e-9));
+        // the square root squares back to the matrix's positive part
+        let positive = eigen.map(|x| x.max(0.0));
+        let root = eigen.map(|x| x.max(0.0).sqrt());
+        assert!(max_difference(&(&root * &root), &positive) < 1e-9);
+    }
+}
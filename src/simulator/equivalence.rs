@@ -0,0 +1,80 @@
+use super::circuit::Circuit;
+use super::register::Register;
+use super::rng::Rng;
+use super::testing::ApproxEq;
+
+/// up to this width circuits are compared by their full unitaries
+pub const EXACT_EQUIVALENCE_QUBITS: usize = 8;
+
+/// Haar-random probe states used above the exact limit
+const PROBES: usize = 8;
+
+impl Circuit {
+    /// same operator up to a global phase
+    ///
+    /// Narrow circuits compare unitaries entrywise within `tolerance`. Wider
+    /// ones run both circuits on random states from a fixed seed and require
+    /// 1 − |⟨Uψ|Vψ⟩| ≤ `tolerance` for every probe; a per-qubit phase
+    /// difference lowers that overlap for generic ψ, so it is still caught.
+    pub fn equivalent_to(&self, other: &Circuit, tolerance: f64) -> bool {
+        if self.num_qubits() != other.num_qubits() {
+            return false;
+        }
+        if self.num_qubits() <= EXACT_EQUIVALENCE_QUBITS {
+            return self.to_unitary().approx_eq(&other.to_unitary(), tolerance);
+        }
+        let mut rng = Rng::seed_from_u64(0x5eed_c1c0);
+        (0..PROBES).all(|_| {
+            let probe = Register::haar_random(self.num_qubits(), &mut rng);
+            let mut a = probe.clone();
+            a.apply_circuit(self);
+            let mut b = probe;
+            b.apply_circuit(other);
+            1.0 - a.inner(&b).norm() <= tolerance
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_identities() {
+        // HZH = X, and CZ = (I⊗H) CX (I⊗H)
+        let mut hzh = Circuit::new(1);
+        hzh.h(0).z(0).h(0);
+        let mut x = Circuit::new(1);
+        x.x(0);
+        assert!(hzh.equivalent_to(&x, 1e-9));
+        let mut cz = Circuit::new(2);
+        cz.cz(0, 1);
+        let mut via_cx = Circuit::new(2);
+        via_cx.h(1).cx(0, 1).h(1);
+        assert!(cz.equivalent_to(&via_cx, 1e-9));
+        // global phase is ignored: RZ(θ) = e^{-iθ/2} P(θ)
+        let (mut rz, mut p) = (Circuit::new(1), Circuit::new(1));
+        rz.rz(0.7, 0);
+        p.phase(0.7, 0);
+        assert!(rz.equivalent_to(&p, 1e-9));
+        assert!(!x.equivalent_to(&p, 1e-9));
+    }
+
+    #[test]
+    fn test_wide_circuits_use_probes() {
+        let n = EXACT_EQUIVALENCE_QUBITS + 2;
+        let mut ladder = Circuit::new(n);
+        for q in 0..n - 1 {
+            ladder.cx(q, q + 1);
+        }
+        let mut via_cz = Circuit::new(n);
+        for q in 0..n - 1 {
+            via_cz.h(q + 1).cz(q, q + 1).h(q + 1);
+        }
+        assert!(ladder.equivalent_to(&via_cz, 1e-9));
+        // a relative phase on one qubit must be detected
+        let mut phased = ladder.clone();
+        phased.s(3);
+        assert!(!ladder.equivalent_to(&phased, 1e-6));
+    }
+}
@@ -0,0 +1,109 @@
+use super::pauli::Pauli;
+use super::register::Register;
+use super::rng::Rng;
+
+/// finite-shot estimate of an expectation value
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectationEstimate {
+    pub mean: f64,
+    pub std_error: f64,
+    pub shots: usize,
+}
+
+impl Register {
+    /// copy of the state rotated so every listed Pauli becomes Z; each qubit
+    /// may appear once
+    fn rotated_to_z(&self, observable: &[(usize, Pauli)]) -> (Register, usize) {
+        let mut rotated = self.clone();
+        let mut mask = 0;
+        for (k, &(q, pauli)) in observable.iter().enumerate() {
+            assert!(q < self.num_qubits(), "qubit {} out of range", q);
+            assert!(observable[..k].iter().all(|&(p, _)| p != q), "repeated qubit {}", q);
+            if pauli == Pauli::I {
+                continue;
+            }
+            for gate in pauli.basis_change() {
+                rotated.apply_matrix2(q, gate.matrix2().expect("single-qubit gate"));
+            }
+            mask |= 1 << q;
+        }
+        (rotated, mask)
+    }
+
+    /// exact ⟨⊗ P_q⟩ for `observable` given as (qubit, Pauli) pairs on
+    /// distinct qubits
+    pub fn expectation(&self, observable: &[(usize, Pauli)]) -> f64 {
+        let (rotated, mask) = self.rotated_to_z(observable);
+        rotated
+            .probabilities()
+            .iter()
+            .enumerate()
+            .map(|(k, p)| if (k & mask).count_ones() % 2 == 0 { *p } else { -*p })
+            .sum()
+    }
+
+    /// ⟨⊗ P_q⟩ estimated from `shots` ±1 outcomes, drawn binomially from the
+    /// exact probability of the +1 eigenspace
+    pub fn expectation_sampled(
+        &self,
+        observable: &[(usize, Pauli)],
+        shots: usize,
+        rng: &mut Rng,
+    ) -> ExpectationEstimate {
+        assert!(shots > 0, "need at least one shot");
+        let p_plus = (1.0 + self.expectation(observable)) / 2.0;
+        let plus = (0..shots).filter(|_| rng.gen_bool(p_plus)).count();
+        let mean = 2.0 * plus as f64 / shots as f64 - 1.0;
+        let variance = (1.0 - mean * mean).max(0.0);
+        ExpectationEstimate {
+            mean,
+            std_error: (variance / shots as f64).sqrt(),
+            shots,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::{h_matrix, ry_matrix, x_matrix};
+
+    #[test]
+    fn test_exact_bell_correlators() {
+        let mut register = Register::new(2);
+        register.apply_gate(0, h_matrix()).unwrap();
+        register.apply_controlled_gate(&[0], 1, x_matrix()).unwrap();
+        assert!((register.expectation(&[(0, Pauli::X), (1, Pauli::X)]) - 1.0).abs() < 1e-12);
+        assert!((register.expectation(&[(0, Pauli::Y), (1, Pauli::Y)]) + 1.0).abs() < 1e-12);
+        assert!(register.expectation(&[(1, Pauli::Z)]).abs() < 1e-12);
+        assert!((register.expectation(&[]) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "repeated qubit 0")]
+    fn test_repeated_observable_qubit_panics() {
+        Register::new(1).expectation(&[(0, Pauli::X), (0, Pauli::X)]);
+    }
+
+    #[test]
+    fn test_sampled_error_shrinks_with_shots() {
+        // RY(θ)|0⟩ has ⟨Z⟩ = cos θ
+        let mut register = Register::new(1);
+        register.apply_gate(0, ry_matrix(1.1)).unwrap();
+        let exact = 1.1f64.cos();
+        let mut rng = Rng::seed_from_u64(20);
+        let few = register.expectation_sampled(&[(0, Pauli::Z)], 100, &mut rng);
+        let many = register.expectation_sampled(&[(0, Pauli::Z)], 10_000, &mut rng);
+        assert!(many.std_error < few.std_error / 5.0);
+        assert!((many.mean - exact).abs() < 4.0 * many.std_error);
+        assert_eq!(many.shots, 10_000);
+    }
+
+    #[test]
+    fn test_eigenstate_has_no_shot_noise() {
+        let mut rng = Rng::seed_from_u64(1);
+        let estimate = Register::new(3).expectation_sampled(&[(2, Pauli::Z)], 50, &mut rng);
+        assert_eq!(estimate.mean, 1.0);
+        assert_eq!(estimate.std_error, 0.0);
+    }
+}
@@ -0,0 +1,456 @@
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4, SQRT_2};
+use num_complex::Complex64;
+use super::circuit::{Circuit, Instruction};
+use super::gates::Gate;
+use super::register::Register;
+
+/// most stabilizer terms a decomposition may grow to
+pub const MAX_STABILIZER_TERMS: usize = 1 << 16;
+
+fn i_pow(k: u32) -> Complex64 {
+    [
+        Complex64::new(1.0, 0.0),
+        Complex64::new(0.0, 1.0),
+        Complex64::new(-1.0, 0.0),
+        Complex64::new(0.0, -1.0),
+    ][(k % 4) as usize]
+}
+
+fn bits(mask: u64) -> impl Iterator<Item = usize> {
+    (0..64).filter(move |&j| mask >> j & 1 == 1)
+}
+
+fn parity(mask: u64) -> u32 {
+    mask.count_ones() % 2
+}
+
+/// stabilizer state in CH form, ω U_C U_H |s⟩ (Bravyi et al. 2019)
+///
+/// U_C is a product of S, CZ and CX gates, kept as the tableau
+/// U_C⁻¹ Z_p U_C = Z^{G_p} and U_C⁻¹ X_p U_C = i^{γ_p} X^{F_p} Z^{M_p}, with row p
+/// of G, F and M stored as a bit mask. U_H puts a Hadamard on every qubit of
+/// `v`, and `omega` carries the exact global factor.
+#[derive(Debug, Clone, PartialEq)]
+struct ChForm {
+    g: Vec<u64>,
+    f: Vec<u64>,
+    m: Vec<u64>,
+    gamma: Vec<u32>,
+    v: u64,
+    s: u64,
+    omega: Complex64,
+}
+
+impl ChForm {
+    fn zero(n: usize) -> Self {
+        let unit: Vec<u64> = (0..n).map(|p| 1 << p).collect();
+        Self {
+            g: unit.clone(),
+            f: unit,
+            m: vec![0; n],
+            gamma: vec![0; n],
+            v: 0,
+            s: 0,
+            omega: Complex64::new(1.0, 0.0),
+        }
+    }
+
+    fn s_gate(&mut self, q: usize) {
+        self.m[q] ^= self.g[q];
+        self.gamma[q] = (self.gamma[q] + 3) % 4;
+    }
+
+    fn cz(&mut self, q: usize, r: usize) {
+        self.m[q] ^= self.g[r];
+        self.m[r] ^= self.g[q];
+    }
+
+    fn cx(&mut self, control: usize, target: usize) {
+        let (q, r) = (control, target);
+        self.gamma[q] = (self.gamma[q] + self.gamma[r] + 2 * parity(self.m[q] & self.f[r])) % 4;
+        self.g[r] ^= self.g[q];
+        self.f[q] ^= self.f[r];
+        self.m[q] ^= self.m[r];
+    }
+
+    /// U_C ← U_C S_q
+    fn right_s(&mut self, q: usize) {
+        for p in 0..self.f.len() {
+            if self.f[p] >> q & 1 == 1 {
+                self.m[p] ^= 1 << q;
+                self.gamma[p] = (self.gamma[p] + 3) % 4;
+            }
+        }
+    }
+
+    /// U_C ← U_C CZ_{q,r}
+    fn right_cz(&mut self, q: usize, r: usize) {
+        for p in 0..self.f.len() {
+            let (fq, fr) = (self.f[p] >> q & 1, self.f[p] >> r & 1);
+            self.gamma[p] = (self.gamma[p] + 2 * (fq & fr) as u32) % 4;
+            self.m[p] ^= (fr << q) | (fq << r);
+        }
+    }
+
+    /// U_C ← U_C CX_{control → target}
+    fn right_cx(&mut self, control: usize, target: usize) {
+        let (q, r) = (control, target);
+        for p in 0..self.f.len() {
+            self.g[p] ^= (self.g[p] >> r & 1) << q;
+            self.f[p] ^= (self.f[p] >> q & 1) << r;
+            self.m[p] ^= (self.m[p] >> r & 1) << q;
+        }
+    }
+
+    /// P U_H |s⟩ = i^k U_H |t⟩ for P = i^γ X^a Z^b; returns (k, t)
+    fn pauli_on_basis(&self, gamma: u32, a: u64, b: u64) -> (u32, u64) {
+        let v = self.v;
+        // U_H swaps X and Z on the Hadamard qubits: X^a Z^b → (−1)^{a·b} X^b Z^a
+        let (a1, b1) = ((a & !v) | (b & v), (b & !v) | (a & v));
+        let k = gamma + 2 * (parity(a & b & v) + parity(b1 & self.s));
+        (k % 4, self.s ^ a1)
+    }
+
+    fn h(&mut self, q: usize) {
+        // H = (X + Z)/√2, pulled back through U_C and U_H onto |s⟩
+        let (k1, t) = self.pauli_on_basis(self.gamma[q], self.f[q], self.m[q]);
+        let (k2, u) = self.pauli_on_basis(0, 0, self.g[q]);
+        self.omega *= i_pow(k1) * FRAC_1_SQRT_2;
+        self.superpose(t, u, (k2 + 4 - k1) % 4);
+    }
+
+    /// bring U_C U_H (|t⟩ + i^δ |u⟩) back to CH form
+    fn superpose(&mut self, mut t: u64, mut u: u64, delta: u32) {
+        if t == u {
+            self.omega *= Complex64::new(1.0, 0.0) + i_pow(delta);
+            self.s = t;
+            return;
+        }
+        let d = t ^ u;
+        let outside = d & !self.v;
+        let q = if outside != 0 { outside.trailing_zeros() } else { d.trailing_zeros() } as usize;
+        // clear the other differing bits with CX_{q→j} on the basis states,
+        // which U_H turns into CX, CZ or a reversed CX
+        for j in bits(d).filter(|&j| j != q) {
+            match (self.v >> q & 1, self.v >> j & 1) {
+                (0, 0) => self.right_cx(q, j),
+                (0, _) => self.right_cz(q, j),
+                _ => self.right_cx(j, q),
+            }
+            t ^= (t >> q & 1) << j;
+            u ^= (u >> q & 1) << j;
+        }
+        // |a⟩ + i^δ|1−a⟩ on q equals i^{aδ}(|0⟩ + i^{δ'}|1⟩), δ' = ±δ
+        let flipped = t >> q & 1 == 1;
+        let delta = if flipped {
+            self.omega *= i_pow(delta);
+            (4 - delta) % 4
+        } else {
+            delta
+        };
+        self.s = t & !(1 << q);
+        if self.v >> q & 1 == 0 {
+            // |0⟩ + i^δ|1⟩ = √2 S^δ H |0⟩
+            self.omega *= SQRT_2;
+            for _ in 0..delta {
+                self.right_s(q);
+            }
+            self.v |= 1 << q;
+        } else {
+            // H(|0⟩ + i^δ|1⟩) for δ = 0‥3:
+            // √2|0⟩, √2 e^{iπ/4} S† H|0⟩, √2|1⟩, √2 e^{−iπ/4} S H|0⟩
+            self.omega *= SQRT_2;
+            match delta {
+                0 | 2 => {
+                    self.v &= !(1 << q);
+                    self.s |= u64::from(delta == 2) << q;
+                }
+                1 => {
+                    self.omega *= Complex64::from_polar(1.0, FRAC_PI_4);
+                    (0..3).for_each(|_| self.right_s(q));
+                }
+                _ => {
+                    self.omega *= Complex64::from_polar(1.0, -FRAC_PI_4);
+                    self.right_s(q);
+                }
+            }
+        }
+    }
+
+    fn amplitude(&self, x: u64) -> Complex64 {
+        // ⟨x|U_C = ⟨0|U_C⁻¹ X^x U_C, expanded as one Pauli i^μ X^a Z^b
+        let (mut mu, mut a, mut b) = (0u32, 0u64, 0u64);
+        for p in bits(x) {
+            mu += self.gamma[p] + 2 * parity(b & self.f[p]);
+            a ^= self.f[p];
+            b ^= self.m[p];
+        }
+        if (a ^ self.s) & !self.v != 0 {
+            return Complex64::new(0.0, 0.0);
+        }
+        let sign = 2 * (parity(a & b) + parity(a & self.s & self.v));
+        let scale = FRAC_1_SQRT_2.powi(self.v.count_ones() as i32);
+        self.omega * i_pow(mu + sign) * scale
+    }
+}
+
+/// terms-doubling phase rotations `circuit` needs, or `None` if a gate is
+/// beyond the stabilizer sum; 2^branches terms are created
+pub fn stabilizer_branches(circuit: &Circuit) -> Option<usize> {
+    let branches = |theta: f64| {
+        let quarter = theta / FRAC_PI_2;
+        usize::from((quarter - quarter.round()).abs() >= 1e-12)
+    };
+    circuit.instructions().iter().try_fold(0, |total, inst| {
+        let extra = match inst.gate {
+            Gate::T | Gate::Tdg => 1,
+            Gate::Phase(theta) | Gate::Rz(theta) | Gate::Rx(theta) | Gate::Ry(theta) => {
+                branches(theta)
+            }
+            Gate::Mcx(2) | Gate::Mcz(2) => 7,
+            Gate::Mcx(_) | Gate::Mcz(_) | Gate::Mcu(..) => return None,
+            _ => 0,
+        };
+        Some(total + extra)
+    })
+}
+
+/// weighted sum of CH-form stabilizer states
+///
+/// Clifford gates update every term in place; a non-Clifford phase
+/// diag(1, e^{iθ}) = a·I + b·Z doubles the term count, so t T gates cost 2^t
+/// terms on any width up to 64 qubits. Amplitudes are exact, global phase
+/// included.
+#[derive(Debug, Clone)]
+pub struct ExtendedStabilizer {
+    num_qubits: usize,
+    terms: Vec<(Complex64, ChForm)>,
+}
+
+impl ExtendedStabilizer {
+    /// |0…0⟩
+    pub fn new(num_qubits: usize) -> Self {
+        assert!(num_qubits <= 64, "CH form is limited to 64 qubits");
+        Self { num_qubits, terms: vec![(Complex64::new(1.0, 0.0), ChForm::zero(num_qubits))] }
+    }
+
+    pub fn from_circuit(circuit: &Circuit) -> Result<Self, String> {
+        let mut state = Self::new(circuit.num_qubits());
+        for inst in circuit.instructions() {
+            state.apply_instruction(inst)?;
+        }
+        Ok(state)
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn num_terms(&self) -> usize {
+        self.terms.len()
+    }
+
+    fn each(&mut self, op: impl Fn(&mut ChForm)) {
+        for (_, term) in &mut self.terms {
+            op(term);
+        }
+    }
+
+    fn scale(&mut self, factor: Complex64) {
+        for (c, _) in &mut self.terms {
+            *c *= factor;
+        }
+    }
+
+    /// diag(1, e^{iθ}) on `q`, as S powers when θ is a multiple of π/2
+    fn phase(&mut self, theta: f64, q: usize) -> Result<(), String> {
+        let quarter = theta / FRAC_PI_2;
+        if (quarter - quarter.round()).abs() < 1e-12 {
+            let k = (quarter.round() as i64).rem_euclid(4);
+            self.each(|t| (0..k).for_each(|_| t.s_gate(q)));
+            return Ok(());
+        }
+        if 2 * self.terms.len() > MAX_STABILIZER_TERMS {
+            return Err(format!("more than {} stabilizer terms", MAX_STABILIZER_TERMS));
+        }
+        let e = Complex64::from_polar(1.0, theta);
+        let (a, b) = ((1.0 + e) / 2.0, (1.0 - e) / 2.0);
+        let mut flipped: Vec<(Complex64, ChForm)> = self.terms.clone();
+        for (c, term) in &mut flipped {
+            *c *= b;
+            term.s_gate(q);
+            term.s_gate(q);
+        }
+        self.scale(a);
+        self.terms.extend(flipped);
+        Ok(())
+    }
+
+    fn hadamard(&mut self, q: usize) {
+        self.each(|t| t.h(q));
+    }
+
+    /// X = H Z H exactly
+    fn x(&mut self, q: usize) {
+        self.each(|t| {
+            t.h(q);
+            t.s_gate(q);
+            t.s_gate(q);
+            t.h(q);
+        });
+    }
+
+    /// CCZ through the 7-T network
+    fn ccz(&mut self, a: usize, b: usize, c: usize) -> Result<(), String> {
+        let t = FRAC_PI_4;
+        self.each(|s| s.cx(b, c));
+        self.phase(-t, c)?;
+        self.each(|s| s.cx(a, c));
+        self.phase(t, c)?;
+        self.each(|s| s.cx(b, c));
+        self.phase(-t, c)?;
+        self.each(|s| s.cx(a, c));
+        self.phase(t, b)?;
+        self.phase(t, c)?;
+        self.each(|s| s.cx(a, b));
+        self.phase(t, a)?;
+        self.phase(-t, b)?;
+        self.each(|s| s.cx(a, b));
+        Ok(())
+    }
+
+    /// Clifford gates, phase rotations and up to two-controlled X/Z
+    pub fn apply_instruction(&mut self, instruction: &Instruction) -> Result<(), String> {
+        let q = &instruction.qubits;
+        match instruction.gate {
+            Gate::I => {}
+            Gate::H => self.hadamard(q[0]),
+            Gate::X | Gate::Mcx(0) => self.x(q[0]),
+            Gate::Y => {
+                // Y = i X Z
+                self.phase(std::f64::consts::PI, q[0])?;
+                self.x(q[0]);
+                self.scale(Complex64::new(0.0, 1.0));
+            }
+            Gate::Z | Gate::Mcz(0) => self.phase(std::f64::consts::PI, q[0])?,
+            Gate::S => self.phase(FRAC_PI_2, q[0])?,
+            Gate::Sdg => self.phase(-FRAC_PI_2, q[0])?,
+            Gate::T => self.phase(FRAC_PI_4, q[0])?,
+            Gate::Tdg => self.phase(-FRAC_PI_4, q[0])?,
+            Gate::Phase(theta) => self.phase(theta, q[0])?,
+            Gate::Rz(theta) => {
+                self.phase(theta, q[0])?;
+                self.scale(Complex64::from_polar(1.0, -theta / 2.0));
+            }
+            Gate::Rx(theta) | Gate::Ry(theta) => {
+                // Rx = H Rz H and Ry = S Rx S†
+                let y = matches!(instruction.gate, Gate::Ry(_));
+                if y {
+                    self.phase(-FRAC_PI_2, q[0])?;
+                }
+                self.hadamard(q[0]);
+                self.phase(theta, q[0])?;
+                self.scale(Complex64::from_polar(1.0, -theta / 2.0));
+                self.hadamard(q[0]);
+                if y {
+                    self.phase(FRAC_PI_2, q[0])?;
+                }
+            }
+            Gate::Cx | Gate::Mcx(1) => self.each(|t| t.cx(q[0], q[1])),
+            Gate::Cz | Gate::Mcz(1) => self.each(|t| t.cz(q[0], q[1])),
+            Gate::Swap => self.each(|t| {
+                t.cx(q[0], q[1]);
+                t.cx(q[1], q[0]);
+                t.cx(q[0], q[1]);
+            }),
+            Gate::Mcz(2) => self.ccz(q[0], q[1], q[2])?,
+            Gate::Mcx(2) => {
+                self.hadamard(q[2]);
+                self.ccz(q[0], q[1], q[2])?;
+                self.hadamard(q[2]);
+            }
+            ref gate => {
+                return Err(format!("gate {} is not supported by the stabilizer sum", gate.name()))
+            }
+        }
+        Ok(())
+    }
+
+    /// ⟨index|ψ⟩, qubit q being bit q of `index`
+    pub fn amplitude(&self, index: u64) -> Complex64 {
+        self.terms.iter().map(|(c, term)| c * term.amplitude(index)).sum()
+    }
+
+    /// dense copy of the state
+    pub fn to_register(&self) -> Register {
+        let amplitudes = (0..1u64 << self.num_qubits).map(|x| self.amplitude(x)).collect();
+        Register::from_amplitudes(amplitudes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::rng::Rng;
+
+    #[test]
+    fn test_amplitudes_match_statevector_exactly() {
+        let mut rng = Rng::seed_from_u64(65);
+        for _ in 0..5 {
+            let mut circuit = Circuit::new(4);
+            for _ in 0..40 {
+                let (a, b, c) = (rng.gen_range(4), rng.gen_range(4), rng.gen_range(4));
+                match rng.gen_range(10) {
+                    0 | 1 => circuit.h(a),
+                    2 => circuit.s(a),
+                    3 => circuit.y(a),
+                    4 if rng.gen_bool(0.3) => circuit.t(a),
+                    5 if rng.gen_bool(0.2) => circuit.ry(0.4, a),
+                    6 if a != b => circuit.cz(a, b),
+                    7 if a != b && b != c && a != c => circuit.mcx(&[a, b], c),
+                    8 if a != b => circuit.swap(a, b),
+                    _ if a != b => circuit.cx(a, b),
+                    _ => circuit.x(a),
+                };
+            }
+            let state = ExtendedStabilizer::from_circuit(&circuit).unwrap();
+            let mut register = Register::new(4);
+            register.apply_circuit(&circuit);
+            for (x, &expected) in register.amplitudes().iter().enumerate() {
+                assert!((state.amplitude(x as u64) - expected).norm() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wide_clifford_circuit_with_few_t_gates() {
+        let n = 60;
+        let mut circuit = Circuit::new(n);
+        circuit.h(0);
+        for q in 0..n - 1 {
+            circuit.cx(q, q + 1);
+        }
+        circuit.t(3).t(30).h(59).t(59).h(59);
+        let state = ExtendedStabilizer::from_circuit(&circuit).unwrap();
+        assert_eq!(state.num_terms(), 8);
+        assert_eq!(stabilizer_branches(&circuit), Some(3));
+        // T T on the |1…1⟩ branch is S; the last qubit sees H T H
+        let all_ones = u64::MAX >> 4;
+        let ones_branch = Complex64::new(0.0, FRAC_1_SQRT_2);
+        let htb = (Complex64::new(1.0, 0.0) + Complex64::from_polar(1.0, FRAC_PI_4)) / 2.0;
+        assert!((state.amplitude(all_ones) - ones_branch * htb).norm() < 1e-12);
+        assert!(state.amplitude(1).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_term_limit_and_unsupported_gate() {
+        let mut circuit = Circuit::new(1);
+        for _ in 0..17 {
+            circuit.h(0).t(0);
+        }
+        assert!(ExtendedStabilizer::from_circuit(&circuit).unwrap_err().contains("terms"));
+        let mut toffoli3 = Circuit::new(4);
+        toffoli3.mcx(&[0, 1, 2], 3);
+        assert!(ExtendedStabilizer::from_circuit(&toffoli3).is_err());
+    }
+}
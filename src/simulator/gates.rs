@@ -1,101 +1,317 @@
 use num_complex::Complex64;
-use std::f64::consts::PI;
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+use super::operator::Operator;
 use super::single_qubit::SingleQubit;
 
 // Common constants
 const I: Complex64 = Complex64::new(0.0, 1.0);
-const SQRT2_INV: f64 = 0.7071067811865476; // 1/√2
+const SQRT2_INV: f64 = FRAC_1_SQRT_2; // 1/√2
 
-/// Pauli-X gate (NOT gate)
-/// Flips |0⟩ ↔ |1⟩
-pub fn x_gate(qubit: &mut SingleQubit) {
-    let matrix = [
+/// 2×2 matrix, row-major, for the crate's fixed-size single-qubit
+/// arithmetic; the public API takes and returns `Operator`s
+pub(crate) type Matrix2 = [[Complex64; 2]; 2];
+
+/// Pauli-X matrix
+pub(crate) fn x_matrix() -> Matrix2 {
+    [
         [Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0)],
         [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
-    ];
-    qubit.apply_gate(matrix);
+    ]
 }
 
-/// Pauli-Y gate
-pub fn y_gate(qubit: &mut SingleQubit) {
-    let matrix = [
+/// Pauli-Y matrix
+pub(crate) fn y_matrix() -> Matrix2 {
+    [
         [Complex64::new(0.0, 0.0), Complex64::new(0.0, -1.0)],
         [Complex64::new(0.0, 1.0), Complex64::new(0.0, 0.0)],
-    ];
-    qubit.apply_gate(matrix);
+    ]
 }
 
-/// Pauli-Z gate
-/// Applies phase flip: |0⟩ → |0⟩, |1⟩ → -|1⟩
-pub fn z_gate(qubit: &mut SingleQubit) {
-    let matrix = [
+/// Pauli-Z matrix
+pub(crate) fn z_matrix() -> Matrix2 {
+    [
         [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
         [Complex64::new(0.0, 0.0), Complex64::new(-1.0, 0.0)],
-    ];
-    qubit.apply_gate(matrix);
+    ]
 }
 
-/// Hadamard gate
-/// Creates superposition: |0⟩ → (|0⟩ + |1⟩)/√2
-pub fn h_gate(qubit: &mut SingleQubit) {
-    let matrix = [
+/// Hadamard matrix
+pub(crate) fn h_matrix() -> Matrix2 {
+    [
         [Complex64::new(SQRT2_INV, 0.0), Complex64::new(SQRT2_INV, 0.0)],
         [Complex64::new(SQRT2_INV, 0.0), Complex64::new(-SQRT2_INV, 0.0)],
-    ];
-    qubit.apply_gate(matrix);
+    ]
 }
 
-/// Rotation around X-axis by angle theta
-pub fn rx_gate(qubit: &mut SingleQubit, theta: f64) {
+/// RX(θ) matrix
+pub(crate) fn rx_matrix(theta: f64) -> Matrix2 {
     let cos = (theta / 2.0).cos();
     let sin = (theta / 2.0).sin();
-    let matrix = [
+    [
         [Complex64::new(cos, 0.0), Complex64::new(0.0, -sin)],
         [Complex64::new(0.0, -sin), Complex64::new(cos, 0.0)],
-    ];
-    qubit.apply_gate(matrix);
+    ]
 }
 
-/// Rotation around Y-axis by angle theta
-pub fn ry_gate(qubit: &mut SingleQubit, theta: f64) {
+/// RY(θ) matrix
+pub(crate) fn ry_matrix(theta: f64) -> Matrix2 {
     let cos = (theta / 2.0).cos();
     let sin = (theta / 2.0).sin();
-    let matrix = [
+    [
         [Complex64::new(cos, 0.0), Complex64::new(-sin, 0.0)],
         [Complex64::new(sin, 0.0), Complex64::new(cos, 0.0)],
-    ];
-    qubit.apply_gate(matrix);
+    ]
 }
 
-/// Rotation around Z-axis by angle theta
-pub fn rz_gate(qubit: &mut SingleQubit, theta: f64) {
+/// RZ(θ) matrix
+pub(crate) fn rz_matrix(theta: f64) -> Matrix2 {
     let exp_neg = Complex64::new(0.0, -theta / 2.0).exp();
     let exp_pos = Complex64::new(0.0, theta / 2.0).exp();
-    let matrix = [
+    [
         [exp_neg, Complex64::new(0.0, 0.0)],
         [Complex64::new(0.0, 0.0), exp_pos],
-    ];
-    qubit.apply_gate(matrix);
+    ]
 }
 
-/// Phase gate (S gate)
-/// Applies: |0⟩ → |0⟩, |1⟩ → i|1⟩
-pub fn s_gate(qubit: &mut SingleQubit) {
-    let matrix = [
+/// S matrix
+pub(crate) fn s_matrix() -> Matrix2 {
+    [
         [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
         [Complex64::new(0.0, 0.0), I],
-    ];
-    qubit.apply_gate(matrix);
+    ]
 }
 
-/// T gate (π/8 gate)
-pub fn t_gate(qubit: &mut SingleQubit) {
+/// T matrix
+pub(crate) fn t_matrix() -> Matrix2 {
     let phase = Complex64::new(0.0, PI / 4.0).exp();
-    let matrix = [
+    [
         [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
         [Complex64::new(0.0, 0.0), phase],
-    ];
-    qubit.apply_gate(matrix);
+    ]
+}
+
+/// S† matrix
+pub(crate) fn sdg_matrix() -> Matrix2 {
+    [
+        [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
+        [Complex64::new(0.0, 0.0), -I],
+    ]
+}
+
+/// T† matrix
+pub(crate) fn tdg_matrix() -> Matrix2 {
+    phase_matrix(-PI / 4.0)
+}
+
+/// phase matrix diag(1, e^{iλ})
+pub(crate) fn phase_matrix(lambda: f64) -> Matrix2 {
+    [
+        [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
+        [Complex64::new(0.0, 0.0), Complex64::new(0.0, lambda).exp()],
+    ]
+}
+
+/// identity matrix
+pub(crate) fn identity_matrix() -> Matrix2 {
+    [
+        [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
+        [Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0)],
+    ]
+}
+
+/// a · b
+pub(crate) fn matmul(a: &Matrix2, b: &Matrix2) -> Matrix2 {
+    let mut out = [[Complex64::new(0.0, 0.0); 2]; 2];
+    for (i, row) in out.iter_mut().enumerate() {
+        for (j, entry) in row.iter_mut().enumerate() {
+            *entry = a[i][0] * b[0][j] + a[i][1] * b[1][j];
+        }
+    }
+    out
+}
+
+/// conjugate transpose
+pub(crate) fn dagger(m: &Matrix2) -> Matrix2 {
+    [
+        [m[0][0].conj(), m[1][0].conj()],
+        [m[0][1].conj(), m[1][1].conj()],
+    ]
+}
+
+/// gate as circuit element
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gate {
+    I,
+    X,
+    Y,
+    Z,
+    H,
+    S,
+    Sdg,
+    T,
+    Tdg,
+    Rx(f64),
+    Ry(f64),
+    Rz(f64),
+    Phase(f64),
+    /// controlled-X, qubits = [control, target]
+    Cx,
+    /// controlled-Z, qubits = [control, target]
+    Cz,
+    Swap,
+    /// X with n controls, qubits = [controls…, target]
+    Mcx(usize),
+    /// Z with n controls, qubits = [controls…, target]
+    Mcz(usize),
+    /// arbitrary one-qubit unitary with n controls, qubits = [controls…, target]
+    Mcu(usize, Operator),
+}
+
+impl Gate {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Gate::I => "id",
+            Gate::X => "x",
+            Gate::Y => "y",
+            Gate::Z => "z",
+            Gate::H => "h",
+            Gate::S => "s",
+            Gate::Sdg => "sdg",
+            Gate::T => "t",
+            Gate::Tdg => "tdg",
+            Gate::Rx(_) => "rx",
+            Gate::Ry(_) => "ry",
+            Gate::Rz(_) => "rz",
+            Gate::Phase(_) => "p",
+            Gate::Cx => "cx",
+            Gate::Cz => "cz",
+            Gate::Swap => "swap",
+            Gate::Mcx(_) => "mcx",
+            Gate::Mcz(_) => "mcz",
+            Gate::Mcu(..) => "mcu",
+        }
+    }
+
+    /// name with the rotation angle, e.g. "rx(1.571)"
+    pub fn label(&self) -> String {
+        match self {
+            Gate::Rx(a) | Gate::Ry(a) | Gate::Rz(a) | Gate::Phase(a) => {
+                format!("{}({:.3})", self.name(), a)
+            }
+            _ => self.name().to_string(),
+        }
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        match self {
+            Gate::Cx | Gate::Cz | Gate::Swap => 2,
+            Gate::Mcx(controls) | Gate::Mcz(controls) | Gate::Mcu(controls, _) => controls + 1,
+            _ => 1,
+        }
+    }
+
+    /// one-qubit operator of single-qubit gates
+    pub fn matrix(&self) -> Option<Operator> {
+        self.matrix2().map(Operator::from)
+    }
+
+    /// `matrix` as a 2×2 array
+    pub(crate) fn matrix2(&self) -> Option<Matrix2> {
+        Some(match self {
+            Gate::I => identity_matrix(),
+            Gate::X => x_matrix(),
+            Gate::Y => y_matrix(),
+            Gate::Z => z_matrix(),
+            Gate::H => h_matrix(),
+            Gate::S => s_matrix(),
+            Gate::Sdg => sdg_matrix(),
+            Gate::T => t_matrix(),
+            Gate::Tdg => tdg_matrix(),
+            Gate::Rx(theta) => rx_matrix(*theta),
+            Gate::Ry(theta) => ry_matrix(*theta),
+            Gate::Rz(theta) => rz_matrix(*theta),
+            Gate::Phase(lambda) => phase_matrix(*lambda),
+            Gate::Mcx(0) => x_matrix(),
+            Gate::Mcz(0) => z_matrix(),
+            Gate::Mcu(0, operator) => return operator.to_matrix2(),
+            Gate::Cx | Gate::Cz | Gate::Swap | Gate::Mcx(_) | Gate::Mcz(_) | Gate::Mcu(..) => {
+                return None
+            }
+        })
+    }
+
+    pub fn inverse(&self) -> Gate {
+        match self.clone() {
+            Gate::S => Gate::Sdg,
+            Gate::Sdg => Gate::S,
+            Gate::T => Gate::Tdg,
+            Gate::Tdg => Gate::T,
+            Gate::Rx(theta) => Gate::Rx(-theta),
+            Gate::Ry(theta) => Gate::Ry(-theta),
+            Gate::Rz(theta) => Gate::Rz(-theta),
+            Gate::Phase(lambda) => Gate::Phase(-lambda),
+            Gate::Mcu(controls, operator) => Gate::Mcu(controls, operator.dagger()),
+            other => other,
+        }
+    }
+
+    pub fn is_clifford(&self) -> bool {
+        match self {
+            Gate::T | Gate::Tdg | Gate::Rx(_) | Gate::Ry(_) | Gate::Rz(_) | Gate::Phase(_) => false,
+            Gate::Mcx(controls) | Gate::Mcz(controls) => *controls <= 1,
+            Gate::Mcu(..) => false,
+            _ => true,
+        }
+    }
+}
+
+/// Pauli-X gate (NOT gate)
+/// Flips |0⟩ ↔ |1⟩
+pub fn x_gate(qubit: &mut SingleQubit) {
+    qubit.apply_matrix2(x_matrix());
+}
+
+/// Pauli-Y gate
+pub fn y_gate(qubit: &mut SingleQubit) {
+    qubit.apply_matrix2(y_matrix());
+}
+
+/// Pauli-Z gate
+/// Applies phase flip: |0⟩ → |0⟩, |1⟩ → -|1⟩
+pub fn z_gate(qubit: &mut SingleQubit) {
+    qubit.apply_matrix2(z_matrix());
+}
+
+/// Hadamard gate
+/// Creates superposition: |0⟩ → (|0⟩ + |1⟩)/√2
+pub fn h_gate(qubit: &mut SingleQubit) {
+    qubit.apply_matrix2(h_matrix());
+}
+
+/// Rotation around X-axis by angle theta
+pub fn rx_gate(qubit: &mut SingleQubit, theta: f64) {
+    qubit.apply_matrix2(rx_matrix(theta));
+}
+
+/// Rotation around Y-axis by angle theta
+pub fn ry_gate(qubit: &mut SingleQubit, theta: f64) {
+    qubit.apply_matrix2(ry_matrix(theta));
+}
+
+/// Rotation around Z-axis by angle theta
+pub fn rz_gate(qubit: &mut SingleQubit, theta: f64) {
+    qubit.apply_matrix2(rz_matrix(theta));
+}
+
+/// Phase gate (S gate)
+/// Applies: |0⟩ → |0⟩, |1⟩ → i|1⟩
+pub fn s_gate(qubit: &mut SingleQubit) {
+    qubit.apply_matrix2(s_matrix());
+}
+
+/// T gate (π/8 gate)
+pub fn t_gate(qubit: &mut SingleQubit) {
+    qubit.apply_matrix2(t_matrix());
 }
 
 #[cfg(test)]
@@ -0,0 +1,282 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use num_complex::Complex64;
+use super::circuit::Circuit;
+use super::register::Register;
+use super::rng::Rng;
+use super::testing::approx_eq_up_to_phase;
+
+/// set to rewrite golden files instead of comparing against them
+pub const UPDATE_VAR: &str = "MEMQSIM_UPDATE_GOLDENS";
+
+const HEADER: &str = "memqsim snapshot v1";
+
+/// amplitudes below this magnitude are left out of snapshots
+const ZERO: f64 = 1e-12;
+
+/// reproducible output of a run: the final state and counts sampled with a
+/// fixed seed, in a line-based text form meant to be checked in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub num_qubits: usize,
+    pub seed: u64,
+    /// non-zero amplitudes of the final state by basis index; empty when
+    /// only counts were recorded
+    pub amplitudes: Vec<(usize, Complex64)>,
+    pub counts: BTreeMap<usize, usize>,
+}
+
+impl Snapshot {
+    /// run `circuit` from |0…0⟩ and sample `shots` outcomes seeded by `seed`
+    pub fn capture(circuit: &Circuit, shots: usize, seed: u64) -> Self {
+        let mut register = Register::new(circuit.num_qubits());
+        register.apply_circuit(circuit);
+        let counts = register.distribution().sample_counts(shots, &mut Rng::seed_from_u64(seed));
+        let amplitudes = register
+            .amplitudes()
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.norm() > ZERO)
+            .map(|(k, &a)| (k, a))
+            .collect();
+        Self { num_qubits: circuit.num_qubits(), seed, amplitudes, counts }
+    }
+
+    /// counts from any backend, e.g. a noisy or decision-diagram run
+    pub fn from_counts(num_qubits: usize, seed: u64, counts: BTreeMap<usize, usize>) -> Self {
+        Self { num_qubits, seed, amplitudes: Vec::new(), counts }
+    }
+
+    pub fn shots(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    fn bits(&self, index: usize) -> String {
+        format!("{:0width$b}", index, width = self.num_qubits)
+    }
+
+    /// read the text written by `Display`; bitstrings must be as long as the
+    /// `qubits` line before them says
+    pub fn parse(text: &str) -> Result<Snapshot, String> {
+        let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        match lines.next() {
+            Some((_, line)) if line.trim() == HEADER => {}
+            _ => return Err(format!("expected \"{}\" on the first line", HEADER)),
+        }
+        let mut snapshot = Snapshot::from_counts(0, 0, BTreeMap::new());
+        for (n, line) in lines {
+            let width = snapshot.num_qubits;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let at = |e: String| format!("line {}: {}", n + 1, e);
+            let field = |k: usize| fields.get(k).copied().ok_or_else(|| at("missing field".into()));
+            let bad = |what: &str, field: &str| at(format!("bad {} {:?}", what, field));
+            let number = |k: usize| field(k).and_then(|f| f.parse().map_err(|_| bad("number", f)));
+            let index = || {
+                let f = field(1)?;
+                if f.len() != width {
+                    return Err(at(format!("bitstring {:?} is not {} bits long", f, width)));
+                }
+                usize::from_str_radix(f, 2).map_err(|_| bad("bitstring", f))
+            };
+            match fields[0] {
+                "qubits" => {
+                    let f = field(1)?;
+                    snapshot.num_qubits = f.parse().map_err(|_| bad("qubit count", f))?;
+                }
+                "seed" => {
+                    let f = field(1)?;
+                    snapshot.seed = f.parse().map_err(|_| bad("seed", f))?;
+                }
+                "amplitude" => {
+                    let amplitude = Complex64::new(number(2)?, number(3)?);
+                    snapshot.amplitudes.push((index()?, amplitude));
+                }
+                "count" => {
+                    let f = field(2)?;
+                    snapshot.counts.insert(index()?, f.parse().map_err(|_| bad("count", f))?);
+                }
+                other => return Err(at(format!("unknown entry {:?}", other))),
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// `Ok` when `self` reproduces `golden`: same qubits and seed, amplitudes
+    /// within `tolerance` up to global phase, and identical counts
+    ///
+    /// The error lists every difference, one per line.
+    pub fn compare(&self, golden: &Snapshot, tolerance: f64) -> Result<(), String> {
+        let mut problems = Vec::new();
+        if (self.num_qubits, self.seed) != (golden.num_qubits, golden.seed) {
+            problems.push(format!(
+                "ran {} qubits with seed {}, golden has {} qubits with seed {}",
+                self.num_qubits, self.seed, golden.num_qubits, golden.seed
+            ));
+        }
+        if !golden.amplitudes.is_empty() {
+            // both states vanish off the listed indices, so compare only there
+            let support: Vec<usize> = self
+                .amplitudes
+                .iter()
+                .chain(&golden.amplitudes)
+                .map(|&(k, _)| k)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            let len = support.len();
+            let on_support = |amplitudes: &[(usize, Complex64)]| {
+                let mut state = vec![Complex64::default(); len];
+                for &(k, a) in amplitudes {
+                    state[support.binary_search(&k).expect("index is in the support")] = a;
+                }
+                state
+            };
+            let (actual, expected) = (on_support(&self.amplitudes), on_support(&golden.amplitudes));
+            if !approx_eq_up_to_phase(&expected, &actual, tolerance) {
+                let change: Vec<f64> =
+                    expected.iter().zip(&actual).map(|(e, a)| (e - a).norm()).collect();
+                let worst = (0..len).max_by(|&i, &j| change[i].total_cmp(&change[j])).unwrap_or(0);
+                problems.push(format!(
+                    "state differs beyond {} (largest change at {}: {} vs golden {})",
+                    tolerance,
+                    self.bits(support[worst]),
+                    actual[worst],
+                    expected[worst]
+                ));
+            }
+        }
+        let outcomes: BTreeSet<usize> =
+            self.counts.keys().chain(golden.counts.keys()).copied().collect();
+        for outcome in outcomes {
+            let (actual, expected) = (self.counts.get(&outcome), golden.counts.get(&outcome));
+            if actual != expected {
+                problems.push(format!(
+                    "count of {}: {} vs golden {}",
+                    self.bits(outcome),
+                    actual.copied().unwrap_or(0),
+                    expected.copied().unwrap_or(0)
+                ));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("\n"))
+        }
+    }
+
+    /// compare against the golden file at `path`, writing it instead when it
+    /// does not exist yet or `MEMQSIM_UPDATE_GOLDENS` is set
+    pub fn check(&self, path: impl AsRef<Path>, tolerance: f64) -> Result<(), String> {
+        let path = path.as_ref();
+        if !path.exists() || std::env::var_os(UPDATE_VAR).is_some() {
+            if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)
+                    .map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+            }
+            return fs::write(path, self.to_string())
+                .map_err(|e| format!("cannot write {}: {}", path.display(), e));
+        }
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        let golden = Snapshot::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        self.compare(&golden, tolerance).map_err(|e| {
+            format!("{} does not match (set {}=1 to update):\n{}", path.display(), UPDATE_VAR, e)
+        })
+    }
+}
+
+impl fmt::Display for Snapshot {
+    /// header, qubits and seed, then `amplitude <bits> <re> <im>` and
+    /// `count <bits> <n>` lines, bitstrings most significant qubit first
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "qubits {}", self.num_qubits)?;
+        writeln!(f, "seed {}", self.seed)?;
+        for &(k, a) in &self.amplitudes {
+            writeln!(f, "amplitude {} {:.15e} {:.15e}", self.bits(k), a.re, a.im)?;
+        }
+        for (&outcome, &n) in &self.counts {
+            writeln!(f, "count {} {}", self.bits(outcome), n)?;
+        }
+        Ok(())
+    }
+}
+
+/// assert a snapshot matches its golden file, creating the file on first use
+///
+/// `assert_golden!(snapshot, path)` or `assert_golden!(snapshot, path, tolerance)`
+#[macro_export]
+macro_rules! assert_golden {
+    ($snapshot:expr, $path:expr $(,)?) => {
+        $crate::assert_golden!($snapshot, $path, $crate::simulator::testing::DEFAULT_TOLERANCE)
+    };
+    ($snapshot:expr, $path:expr, $tol:expr $(,)?) => {{
+        if let Err(message) = $crate::simulator::golden::Snapshot::check(&$snapshot, $path, $tol) {
+            panic!("golden mismatch: {}", message);
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::testing::bell;
+
+    #[test]
+    fn test_capture_is_deterministic_and_round_trips() {
+        let snapshot = Snapshot::capture(&bell(), 200, 7);
+        assert_eq!(snapshot, Snapshot::capture(&bell(), 200, 7));
+        assert_eq!(snapshot.shots(), 200);
+        assert_eq!(snapshot.amplitudes.len(), 2);
+        let text = snapshot.to_string();
+        assert!(text.starts_with("memqsim snapshot v1\nqubits 2\nseed 7\namplitude 00 "));
+        let parsed = Snapshot::parse(&text).unwrap();
+        assert!(parsed.compare(&snapshot, 1e-12).is_ok());
+        assert_eq!(parsed.counts, snapshot.counts);
+        assert!(Snapshot::parse("qubits 2").is_err());
+        let long = format!("{}\nqubits 2\namplitude {} 1 0", HEADER, "1".repeat(40));
+        assert!(Snapshot::parse(&long).unwrap_err().contains("is not 2 bits long"));
+        let early = format!("{}\ncount 01 5\nqubits 2", HEADER);
+        assert!(Snapshot::parse(&early).unwrap_err().contains("is not 0 bits long"));
+    }
+
+    #[test]
+    fn test_compare_reports_regressions() {
+        let golden = Snapshot::capture(&bell(), 100, 1);
+        let mut phase_flipped = bell();
+        phase_flipped.z(0);
+        let error = Snapshot::capture(&phase_flipped, 100, 1).compare(&golden, 1e-9).unwrap_err();
+        assert!(error.starts_with("state differs"), "{}", error);
+        let reseeded = Snapshot::capture(&bell(), 100, 2).compare(&golden, 1e-9).unwrap_err();
+        assert!(reseeded.contains("seed 2") && reseeded.contains("count of 00"));
+        // a global phase is not a regression
+        let mut global = bell();
+        global.x(0).z(0).x(0).z(0);
+        assert!(Snapshot::capture(&global, 100, 1).compare(&golden, 1e-9).is_ok());
+        // far-apart indices are compared without a dense state between them
+        let one = Complex64::new(1.0, 0.0);
+        let mut far = Snapshot::from_counts(64, 1, BTreeMap::new());
+        far.amplitudes.push((usize::MAX, one));
+        let mut near = far.clone();
+        near.amplitudes[0].0 = 0;
+        assert!(far.compare(&far, 1e-9).is_ok());
+        assert!(near.compare(&far, 1e-9).unwrap_err().contains(&"1".repeat(64)));
+    }
+
+    #[test]
+    fn test_golden_file_is_written_then_checked() {
+        let path = std::env::temp_dir().join("memqsim_golden").join("bell.snap");
+        let _ = fs::remove_file(&path);
+        assert_golden!(Snapshot::capture(&bell(), 50, 3), &path);
+        assert!(path.exists());
+        assert_golden!(Snapshot::capture(&bell(), 50, 3), &path);
+        let mut other = Circuit::new(2);
+        other.h(0).h(1);
+        let error = Snapshot::capture(&other, 50, 3).check(&path, 1e-9).unwrap_err();
+        assert!(error.contains("does not match") && error.contains(UPDATE_VAR));
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+use super::circuit::Circuit;
+use super::pauli::Pauli;
+
+/// two Pauli strings (indexed by qubit) agree wherever both act non-trivially
+pub fn qubit_wise_commute(a: &[Pauli], b: &[Pauli]) -> bool {
+    a.iter()
+        .zip(b)
+        .all(|(&p, &q)| p == Pauli::I || q == Pauli::I || p == q)
+}
+
+/// Pauli strings that can be read from one measurement setting
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasurementGroup {
+    /// measured basis per qubit; `Pauli::I` where no member acts
+    pub basis: Vec<Pauli>,
+    /// indices into the grouped term list
+    pub members: Vec<usize>,
+}
+
+impl MeasurementGroup {
+    /// basis-change circuit to run before a computational-basis measurement
+    pub fn circuit(&self) -> Circuit {
+        let mut circuit = Circuit::new(self.basis.len());
+        for (q, pauli) in self.basis.iter().enumerate() {
+            for gate in pauli.basis_change() {
+                circuit.push(gate.clone(), &[q]);
+            }
+        }
+        circuit
+    }
+
+    /// ⟨term⟩ from counts measured after `circuit()`
+    pub fn term_expectation(&self, term: &[Pauli], counts: &BTreeMap<usize, usize>) -> f64 {
+        assert!(qubit_wise_commute(term, &self.basis), "term is not measured by this group");
+        let mask: usize = term
+            .iter()
+            .enumerate()
+            .filter(|(_, &p)| p != Pauli::I)
+            .map(|(q, _)| 1 << q)
+            .sum();
+        let shots: usize = counts.values().sum();
+        let signed: i64 = counts
+            .iter()
+            .map(|(&k, &n)| {
+                let n = n as i64;
+                if (k & mask).count_ones().is_multiple_of(2) { n } else { -n }
+            })
+            .sum();
+        signed as f64 / shots as f64
+    }
+}
+
+/// greedy partition of `terms` into qubit-wise commuting groups
+///
+/// Heavier strings are placed first, each into the first compatible group.
+pub fn group_qubit_wise(terms: &[Vec<Pauli>]) -> Vec<MeasurementGroup> {
+    let num_qubits = terms.first().map_or(0, Vec::len);
+    assert!(terms.iter().all(|t| t.len() == num_qubits), "terms must have equal length");
+    let weight = |t: &Vec<Pauli>| t.iter().filter(|&&p| p != Pauli::I).count();
+    let mut order: Vec<usize> = (0..terms.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(weight(&terms[i])));
+
+    let mut groups: Vec<MeasurementGroup> = Vec::new();
+    for i in order {
+        let term = &terms[i];
+        match groups.iter_mut().find(|g| qubit_wise_commute(&g.basis, term)) {
+            Some(group) => {
+                for (b, &p) in group.basis.iter_mut().zip(term) {
+                    if p != Pauli::I {
+                        *b = p;
+                    }
+                }
+                group.members.push(i);
+            }
+            None => groups.push(MeasurementGroup {
+                basis: term.clone(),
+                members: vec![i],
+            }),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::register::Register;
+    use crate::simulator::rng::Rng;
+    use Pauli::{I, X, Y, Z};
+
+    fn h2_terms() -> Vec<Vec<Pauli>> {
+        // two-qubit H2 Hamiltonian terms (qubit 0 first)
+        vec![vec![I, I], vec![Z, I], vec![I, Z], vec![Z, Z], vec![X, X], vec![Y, Y]]
+    }
+
+    #[test]
+    fn test_grouping_h2() {
+        let groups = group_qubit_wise(&h2_terms());
+        assert_eq!(groups.len(), 3);
+        let mut covered: Vec<usize> = groups.iter().flat_map(|g| g.members.clone()).collect();
+        covered.sort();
+        assert_eq!(covered, (0..6).collect::<Vec<_>>());
+        assert!(groups.iter().any(|g| g.basis == vec![Z, Z] && g.members.len() == 4));
+    }
+
+    #[test]
+    fn test_group_estimates_match_exact() {
+        let mut state = Register::new(2);
+        state.apply_circuit(Circuit::new(2).ry(0.7, 0).cx(0, 1).rx(0.4, 1));
+        let terms = h2_terms();
+        let mut rng = Rng::seed_from_u64(21);
+        for group in group_qubit_wise(&terms) {
+            let mut rotated = state.clone();
+            rotated.apply_circuit(&group.circuit());
+            let counts = rotated.distribution().sample_counts(20_000, &mut rng);
+            for &m in &group.members {
+                let observable: Vec<(usize, Pauli)> =
+                    terms[m].iter().copied().enumerate().collect();
+                let exact = state.expectation(&observable);
+                let estimate = group.term_expectation(&terms[m], &counts);
+                assert!((estimate - exact).abs() < 0.03, "term {:?}", terms[m]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_qubit_wise_commutation() {
+        assert!(qubit_wise_commute(&[X, I, Z], &[X, Y, I]));
+        assert!(!qubit_wise_commute(&[X, X], &[Y, Y]));
+    }
+}
@@ -0,0 +1,385 @@
+use num_complex::Complex64;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Add, Mul};
+use super::matrix::Matrix;
+use super::pauli::Pauli;
+use super::pauli_string::PauliString;
+use super::register::Register;
+use super::rng::Rng;
+use super::sparse::SparseMatrix;
+use super::krylov::{lanczos, lanczos_eigenpairs};
+
+/// coefficients below this magnitude are dropped
+const CUTOFF: f64 = 1e-12;
+
+/// weighted sum of Pauli strings Σ c_k P_k on a fixed number of qubits
+///
+/// String prefactors are folded into the coefficients, so every stored
+/// string is a plain tensor product of Paulis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hamiltonian {
+    num_qubits: usize,
+    terms: BTreeMap<Vec<Pauli>, Complex64>,
+}
+
+impl Hamiltonian {
+    /// the zero operator
+    pub fn new(num_qubits: usize) -> Self {
+        Self {
+            num_qubits,
+            terms: BTreeMap::new(),
+        }
+    }
+
+    pub fn from_pauli(coefficient: Complex64, string: &PauliString) -> Self {
+        let mut h = Self::new(string.num_qubits());
+        h.add_term(coefficient, string);
+        h
+    }
+
+    /// c · I
+    pub fn identity(num_qubits: usize, coefficient: Complex64) -> Self {
+        Self::from_pauli(coefficient, &PauliString::identity(num_qubits))
+    }
+
+    /// add c · P, merging with an existing P term
+    pub fn add_term(&mut self, coefficient: Complex64, string: &PauliString) -> &mut Self {
+        assert_eq!(string.num_qubits(), self.num_qubits, "qubit count mismatch");
+        let value = coefficient * string.coefficient();
+        let key = string.paulis().to_vec();
+        let entry = self.terms.entry(key.clone()).or_insert(Complex64::new(0.0, 0.0));
+        *entry += value;
+        if entry.norm() < CUTOFF {
+            self.terms.remove(&key);
+        }
+        self
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// (coefficient, string) pairs in a fixed order
+    pub fn terms(&self) -> Vec<(Complex64, PauliString)> {
+        self.terms
+            .iter()
+            .map(|(paulis, &c)| (c, PauliString::new(paulis.clone())))
+            .collect()
+    }
+
+    /// coefficient of the plain tensor product `paulis`
+    pub fn coefficient(&self, paulis: &[Pauli]) -> Complex64 {
+        self.terms.get(paulis).copied().unwrap_or(Complex64::new(0.0, 0.0))
+    }
+
+    pub fn scaled(&self, c: Complex64) -> Hamiltonian {
+        let mut h = Hamiltonian::new(self.num_qubits);
+        for (coefficient, string) in self.terms() {
+            h.add_term(coefficient * c, &string);
+        }
+        h
+    }
+
+    /// Σ |c_k|, an upper bound on the spectral norm
+    pub fn one_norm(&self) -> f64 {
+        self.terms.values().map(|c| c.norm()).sum()
+    }
+
+    /// [self, other], keeping only the anticommuting pairs of strings
+    pub fn commutator(&self, other: &Hamiltonian) -> Hamiltonian {
+        assert_eq!(self.num_qubits, other.num_qubits, "qubit count mismatch");
+        let mut h = Hamiltonian::new(self.num_qubits);
+        for (a, p) in self.terms() {
+            for (b, q) in other.terms() {
+                if let Some(product) = p.anticommuting_product(&q) {
+                    h.add_term(a * b * 2.0, &product);
+                }
+            }
+        }
+        h
+    }
+
+    /// coefficients are real within `tolerance`
+    pub fn is_hermitian(&self, tolerance: f64) -> bool {
+        self.terms.values().all(|c| c.im.abs() <= tolerance)
+    }
+
+    /// dense 2^n × 2^n matrix
+    pub fn matrix(&self) -> Matrix {
+        let dim = 1 << self.num_qubits;
+        let mut m = Matrix::zeros(dim, dim);
+        for (c, string) in self.terms() {
+            m = &m + &string.matrix().scaled(c);
+        }
+        m
+    }
+
+    /// each term c·P as (c·i^{#Y}, flip, sign): P maps |j⟩ to
+    /// (−1)^{|j ∧ sign|}·|j ⊕ flip⟩, flip the X/Y mask and sign the Y/Z mask
+    fn term_masks(&self) -> Vec<(Complex64, usize, usize)> {
+        self.terms
+            .iter()
+            .map(|(paulis, &c)| {
+                let mask = |wanted: &[Pauli]| {
+                    paulis
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, p)| wanted.contains(p))
+                        .fold(0usize, |m, (q, _)| m | (1 << q))
+                };
+                let y_count = paulis.iter().filter(|&&p| p == Pauli::Y).count();
+                let c = c * Complex64::i().powu(y_count as u32);
+                (c, mask(&[Pauli::X, Pauli::Y]), mask(&[Pauli::Y, Pauli::Z]))
+            })
+            .collect()
+    }
+
+    /// H|ψ⟩ without forming any matrix: one pass over the amplitudes per term
+    pub fn apply(&self, amplitudes: &[Complex64]) -> Vec<Complex64> {
+        assert_eq!(amplitudes.len(), 1 << self.num_qubits, "dimension mismatch");
+        let mut out = vec![Complex64::new(0.0, 0.0); amplitudes.len()];
+        for (c, flip, sign) in self.term_masks() {
+            for (j, &a) in amplitudes.iter().enumerate() {
+                let value = if (j & sign).count_ones() % 2 == 1 { -c } else { c };
+                out[j ^ flip] += value * a;
+            }
+        }
+        out
+    }
+
+    /// CSR form with at most one entry per term in each row
+    pub fn sparse_matrix(&self) -> SparseMatrix {
+        let masks = self.term_masks();
+        let dim = 1 << self.num_qubits;
+        SparseMatrix::from_row_fn(dim, dim, |i| {
+            masks
+                .iter()
+                .map(|&(c, flip, sign)| {
+                    let j = i ^ flip;
+                    let value = if (j & sign).count_ones() % 2 == 1 { -c } else { c };
+                    (j, value)
+                })
+                .collect()
+        })
+    }
+
+    /// lowest eigenpair from Lanczos on the sparse matrix, for registers well
+    /// past the reach of dense diagonalization
+    pub fn sparse_ground_state(&self, rng: &mut Rng) -> (f64, Register) {
+        assert!(self.is_hermitian(1e-9), "ground state needs a Hermitian operator");
+        let sparse = self.sparse_matrix();
+        let (energy, vector) = lanczos(sparse.rows(), |v| sparse.apply(v), 40, 1e-9, rng);
+        (energy, Register::from_amplitudes(vector))
+    }
+
+    /// the `count` lowest eigenpairs by matrix-free restarted Lanczos; memory
+    /// is a few dozen state vectors, so 20+ qubit spin chains get exact
+    /// reference energies
+    pub fn lowest_eigenstates(&self, count: usize, rng: &mut Rng) -> Vec<(f64, Register)> {
+        assert!(self.is_hermitian(1e-9), "eigenstates need a Hermitian operator");
+        lanczos_eigenpairs(1 << self.num_qubits, |v| self.apply(v), count, 40, 1e-9, rng)
+            .into_iter()
+            .map(|(energy, vector)| (energy, Register::from_amplitudes(vector)))
+            .collect()
+    }
+
+    /// ⟨ψ|H|ψ⟩ for Hermitian H
+    pub fn expectation(&self, state: &Register) -> f64 {
+        assert_eq!(state.num_qubits(), self.num_qubits, "qubit count mismatch");
+        self.terms()
+            .iter()
+            .map(|(c, string)| c.re * state.expectation(&string.terms()))
+            .sum()
+    }
+
+    /// spectrum of a Hermitian H by exact diagonalization, ascending
+    pub fn eigenvalues(&self) -> Vec<f64> {
+        assert!(self.is_hermitian(1e-9), "eigenvalues need a Hermitian operator");
+        self.matrix().hermitian_eigenvalues()
+    }
+
+    pub fn ground_energy(&self) -> f64 {
+        self.eigenvalues()[0]
+    }
+
+    /// lowest eigenvalue and its eigenvector, by exact diagonalization
+    pub fn ground_state(&self) -> (f64, Register) {
+        assert!(self.is_hermitian(1e-9), "ground state needs a Hermitian operator");
+        let eigen = self.matrix().eigen();
+        (eigen.values[0], Register::from_amplitudes(eigen.vector(0)))
+    }
+
+    /// E₁ − E₀, zero for a degenerate ground state
+    pub fn spectral_gap(&self) -> f64 {
+        let values = self.eigenvalues();
+        values.get(1).map_or(0.0, |e1| e1 - values[0])
+    }
+}
+
+impl Add for &Hamiltonian {
+    type Output = Hamiltonian;
+
+    fn add(self, rhs: &Hamiltonian) -> Hamiltonian {
+        let mut h = self.clone();
+        for (c, string) in rhs.terms() {
+            h.add_term(c, &string);
+        }
+        h
+    }
+}
+
+impl Mul for &Hamiltonian {
+    type Output = Hamiltonian;
+
+    fn mul(self, rhs: &Hamiltonian) -> Hamiltonian {
+        assert_eq!(self.num_qubits, rhs.num_qubits, "qubit count mismatch");
+        let mut h = Hamiltonian::new(self.num_qubits);
+        for (a, p) in self.terms() {
+            for (b, q) in rhs.terms() {
+                h.add_term(a * b, &(&p * &q));
+            }
+        }
+        h
+    }
+}
+
+impl fmt::Display for Hamiltonian {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+        let lines: Vec<String> = self
+            .terms()
+            .iter()
+            .map(|(c, string)| {
+                let label: String = string.paulis().iter().rev().map(Pauli::symbol).collect();
+                format!("({:.6}) {}", c, label)
+            })
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn real(x: f64) -> Complex64 {
+        Complex64::new(x, 0.0)
+    }
+
+    #[test]
+    fn test_terms_merge_and_cancel() {
+        let mut h = Hamiltonian::new(2);
+        h.add_term(real(0.5), &"XZ".parse().unwrap());
+        h.add_term(real(0.25), &"-XZ".parse().unwrap());
+        assert!((h.coefficient(&[Pauli::Z, Pauli::X]) - real(0.25)).norm() < 1e-15);
+        h.add_term(real(0.25), &"-XZ".parse().unwrap());
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn test_product_of_sums() {
+        // (X + iY)(X − iY) = 2I + 2Z on one qubit
+        let mut a = Hamiltonian::new(1);
+        a.add_term(real(1.0), &"X".parse().unwrap());
+        a.add_term(Complex64::i(), &"Y".parse().unwrap());
+        let b = Hamiltonian::from_pauli(real(1.0), &"X".parse().unwrap())
+            .add(&Hamiltonian::from_pauli(-Complex64::i(), &"Y".parse().unwrap()));
+        let product = &a * &b;
+        assert_eq!(product.len(), 2);
+        assert!((product.coefficient(&[Pauli::I]) - real(2.0)).norm() < 1e-12);
+        assert!((product.coefficient(&[Pauli::Z]) - real(2.0)).norm() < 1e-12);
+        // [X, Y] = 2iZ, and the commutator matches AB − BA
+        let x = Hamiltonian::from_pauli(real(1.0), &"X".parse().unwrap());
+        let y = Hamiltonian::from_pauli(real(1.0), &"Y".parse().unwrap());
+        let commutator = x.commutator(&y);
+        assert!((commutator.coefficient(&[Pauli::Z]) - Complex64::new(0.0, 2.0)).norm() < 1e-12);
+        assert_eq!(&commutator + &(&y * &x), &x * &y);
+        assert!((a.one_norm() - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ising_ground_energy_and_expectation() {
+        // −ZZ − 0.5(XI + IX) on two qubits
+        let mut h = Hamiltonian::new(2);
+        h.add_term(real(-1.0), &"ZZ".parse().unwrap());
+        h.add_term(real(-0.5), &"XI".parse().unwrap());
+        h.add_term(real(-0.5), &"IX".parse().unwrap());
+        // spectrum is −√2, −1, 1, √2
+        let values = h.eigenvalues();
+        assert!((values[0] + 2f64.sqrt()).abs() < 1e-9, "{:?}", values);
+        assert!((values[3] - 2f64.sqrt()).abs() < 1e-9);
+        assert!((h.expectation(&Register::new(2)) + 1.0).abs() < 1e-12);
+        let (energy, ground) = h.ground_state();
+        assert!((energy - values[0]).abs() < 1e-9);
+        assert!((h.expectation(&ground) - energy).abs() < 1e-9);
+        assert!((h.spectral_gap() - (2f64.sqrt() - 1.0)).abs() < 1e-9);
+    }
+
+    /// −Σ Z_i Z_{i+1} − g·Σ X_i plus a Y·Y coupling, periodic
+    fn spin_chain(n: usize, g: f64) -> Hamiltonian {
+        let mut h = Hamiltonian::new(n);
+        for q in 0..n {
+            let next = (q + 1) % n;
+            h.add_term(real(-1.0), &PauliString::from_terms(n, &[(q, Pauli::Z), (next, Pauli::Z)]));
+            h.add_term(real(0.3), &PauliString::from_terms(n, &[(q, Pauli::Y), (next, Pauli::Y)]));
+            h.add_term(real(-g), &PauliString::single(n, q, Pauli::X));
+        }
+        h
+    }
+
+    #[test]
+    fn test_sparse_matrix_matches_dense() {
+        let mut h = spin_chain(3, 0.7);
+        h.add_term(Complex64::new(0.0, 0.2), &"XYZ".parse().unwrap());
+        let sparse = h.sparse_matrix();
+        let dense = h.matrix();
+        assert!(sparse.nnz() < 64);
+        for i in 0..8 {
+            for j in 0..8 {
+                assert!((sparse.get(i, j) - dense[(i, j)]).norm() < 1e-12);
+            }
+        }
+        let mut state = Register::new(3);
+        state.apply_circuit(crate::simulator::circuit::Circuit::new(3).h(0).ry(0.4, 1).cx(0, 2));
+        let h = spin_chain(3, 0.7);
+        assert!((h.sparse_matrix().expectation(&state) - h.expectation(&state)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sparse_ground_state_matches_exact_diagonalization() {
+        let h = spin_chain(6, 1.1);
+        let mut rng = Rng::seed_from_u64(194);
+        let (energy, ground) = h.sparse_ground_state(&mut rng);
+        assert!((energy - h.ground_energy()).abs() < 1e-8);
+        assert!((h.expectation(&ground) - energy).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_matrix_free_eigenstates_match_spectrum() {
+        let h = spin_chain(5, 0.6);
+        let mut state = Register::new(5);
+        state.apply_circuit(crate::simulator::circuit::Circuit::new(5).h(0).ry(0.9, 3).cx(0, 4));
+        let dense = h.matrix().apply(state.amplitudes());
+        for (a, b) in h.apply(state.amplitudes()).iter().zip(&dense) {
+            assert!((a - b).norm() < 1e-12);
+        }
+        let mut rng = Rng::seed_from_u64(195);
+        let states = h.lowest_eigenstates(3, &mut rng);
+        let spectrum = h.eigenvalues();
+        for (k, (energy, state)) in states.iter().enumerate() {
+            assert!((energy - spectrum[k]).abs() < 1e-7, "level {}", k);
+            assert!((h.expectation(state) - energy).abs() < 1e-7);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use num_complex::Complex64;
+use super::circuit::{Circuit, Instruction};
+use super::gates::{identity_matrix, x_matrix, y_matrix, z_matrix, Gate, Matrix2};
+use super::register::Register;
+
+/// most Feynman paths a hybrid simulation will enumerate
+pub const MAX_PATHS: usize = 1 << 20;
+
+/// controlled 2×2 operator on one partition, in that partition's numbering
+#[derive(Debug, Clone)]
+struct LocalOp {
+    controls: Vec<usize>,
+    target: usize,
+    matrix: Matrix2,
+}
+
+impl LocalOp {
+    fn single(target: usize, matrix: Matrix2) -> Self {
+        Self { controls: Vec::new(), target, matrix }
+    }
+}
+
+/// one product term of a cut gate: operators for the low and high partition
+type Term = [Vec<LocalOp>; 2];
+
+/// |bit⟩⟨bit|
+pub(super) fn projector(bit: usize) -> Matrix2 {
+    let (zero, one) = (Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0));
+    if bit == 0 { [[one, zero], [zero, zero]] } else { [[zero, zero], [zero, one]] }
+}
+
+fn scaled(matrix: Matrix2, factor: f64) -> Matrix2 {
+    matrix.map(|row| row.map(|a| a * factor))
+}
+
+/// register split at `cut`: qubits below it form partition 0, the rest 1
+struct Split {
+    cut: usize,
+}
+
+impl Split {
+    fn side(&self, q: usize) -> usize {
+        usize::from(q >= self.cut)
+    }
+
+    fn local(&self, q: usize) -> usize {
+        if q < self.cut { q } else { q - self.cut }
+    }
+
+    /// partition of an instruction that stays on one side
+    fn home(&self, inst: &Instruction) -> Option<usize> {
+        let side = self.side(inst.qubits[0]);
+        inst.qubits.iter().all(|&q| self.side(q) == side).then_some(side)
+    }
+
+    /// sum-of-products expansion of a gate that crosses the cut
+    ///
+    /// A controlled gate whose m controls on the far side read |1…1⟩ or not
+    /// splits into m + 1 terms of projectors; a swap is ½ Σ_P P ⊗ P.
+    fn terms(&self, inst: &Instruction) -> Vec<Term> {
+        let q = &inst.qubits;
+        let (controls, target, matrix) = match inst.gate {
+            Gate::Swap => {
+                let (sa, sb) = (self.side(q[0]), self.side(q[1]));
+                return [identity_matrix(), x_matrix(), y_matrix(), z_matrix()]
+                    .into_iter()
+                    .map(|p| {
+                        let mut term: Term = [Vec::new(), Vec::new()];
+                        term[sa].push(LocalOp::single(self.local(q[0]), scaled(p, 0.5)));
+                        term[sb].push(LocalOp::single(self.local(q[1]), p));
+                        term
+                    })
+                    .collect();
+            }
+            Gate::Cx => (&q[..1], q[1], x_matrix()),
+            Gate::Cz => (&q[..1], q[1], z_matrix()),
+            Gate::Mcx(k) => (&q[..k], q[k], x_matrix()),
+            Gate::Mcz(k) => (&q[..k], q[k], z_matrix()),
+            Gate::Mcu(k, ref operator) => {
+                (&q[..k], q[k], operator.to_matrix2().expect("one-qubit operator"))
+            }
+            ref gate => unreachable!("single-qubit gate {} cannot cross the cut", gate.name()),
+        };
+        let home = self.side(target);
+        let near: Vec<usize> =
+            controls.iter().filter(|&&c| self.side(c) == home).map(|&c| self.local(c)).collect();
+        let far: Vec<usize> =
+            controls.iter().filter(|&&c| self.side(c) != home).map(|&c| self.local(c)).collect();
+        let mut terms = Vec::with_capacity(far.len() + 1);
+        // far controls 1…1 0 ⊗ I for every position of the first 0
+        for j in 0..far.len() {
+            let mut term: Term = [Vec::new(), Vec::new()];
+            for (i, &c) in far[..=j].iter().enumerate() {
+                term[1 - home].push(LocalOp::single(c, projector(usize::from(i < j))));
+            }
+            terms.push(term);
+        }
+        let mut term: Term = [Vec::new(), Vec::new()];
+        term[1 - home] = far.iter().map(|&c| LocalOp::single(c, projector(1))).collect();
+        term[home].push(LocalOp { controls: near, target: self.local(target), matrix });
+        terms.push(term);
+        terms
+    }
+}
+
+/// Feynman paths a cut at `cut` creates: the product of the term counts of
+/// every gate crossing it, saturating
+pub fn cut_paths(circuit: &Circuit, cut: usize) -> usize {
+    let split = Split { cut };
+    circuit
+        .instructions()
+        .iter()
+        .filter(|inst| split.home(inst).is_none())
+        .fold(1usize, |paths, inst| paths.saturating_mul(split.terms(inst).len()))
+}
+
+/// Schrödinger–Feynman hybrid of `circuit` cut between qubits `cut − 1` and
+/// `cut`
+///
+/// Each partition is simulated as a dense state of its own width, and every
+/// gate crossing the cut is expanded into product terms whose combinations
+/// are summed over as Feynman paths. Memory is 2^cut + 2^(n − cut) amplitudes
+/// per level of branching; time grows with the number of paths.
+pub struct SchrodingerFeynman<'a> {
+    circuit: &'a Circuit,
+    split: Split,
+}
+
+impl<'a> SchrodingerFeynman<'a> {
+    pub fn new(circuit: &'a Circuit, cut: usize) -> Result<Self, String> {
+        if cut == 0 || cut >= circuit.num_qubits() {
+            return Err(format!("cut {} must leave qubits on both sides", cut));
+        }
+        let hybrid = Self { circuit, split: Split { cut } };
+        if hybrid.num_paths() > MAX_PATHS {
+            return Err(format!("{} paths exceed the limit of {}", hybrid.num_paths(), MAX_PATHS));
+        }
+        Ok(hybrid)
+    }
+
+    /// gates that cross the cut
+    pub fn num_cut_gates(&self) -> usize {
+        self.circuit.instructions().iter().filter(|inst| self.split.home(inst).is_none()).count()
+    }
+
+    pub fn num_paths(&self) -> usize {
+        cut_paths(self.circuit, self.split.cut)
+    }
+
+    /// run every path from instruction `k` on, handing each final pair of
+    /// partition states to `visit`
+    fn walk(&self, k: usize, mut halves: [Register; 2], visit: &mut dyn FnMut(&[Register; 2])) {
+        let instructions = self.circuit.instructions();
+        for (offset, inst) in instructions[k..].iter().enumerate() {
+            if let Some(side) = self.split.home(inst) {
+                let qubits = inst.qubits.iter().map(|&q| self.split.local(q)).collect();
+                halves[side].apply_instruction(&Instruction { gate: inst.gate.clone(), qubits });
+                continue;
+            }
+            for term in self.split.terms(inst) {
+                let mut branch = halves.clone();
+                for (side, ops) in term.iter().enumerate() {
+                    for op in ops {
+                        branch[side].apply_controlled_matrix2(&op.controls, op.target, op.matrix);
+                    }
+                }
+                self.walk(k + offset + 1, branch, visit);
+            }
+            return;
+        }
+        visit(&halves);
+    }
+
+    fn start(&self) -> [Register; 2] {
+        let cut = self.split.cut;
+        [Register::new(cut), Register::new(self.circuit.num_qubits() - cut)]
+    }
+
+    /// amplitudes ⟨x|C|0…0⟩ of the listed basis states
+    pub fn amplitudes(&self, indices: &[usize]) -> Vec<Complex64> {
+        let cut = self.split.cut;
+        let mut sums = vec![Complex64::new(0.0, 0.0); indices.len()];
+        self.walk(0, self.start(), &mut |[low, high]| {
+            for (sum, &x) in sums.iter_mut().zip(indices) {
+                *sum += low.amplitudes()[x & ((1 << cut) - 1)] * high.amplitudes()[x >> cut];
+            }
+        });
+        sums
+    }
+
+    /// full output state, summed path by path
+    pub fn state(&self) -> Register {
+        let cut = self.split.cut;
+        let mut sum = vec![Complex64::new(0.0, 0.0); 1 << self.circuit.num_qubits()];
+        self.walk(0, self.start(), &mut |[low, high]| {
+            for (h, &b) in high.amplitudes().iter().enumerate() {
+                for (l, &a) in low.amplitudes().iter().enumerate() {
+                    sum[(h << cut) | l] += a * b;
+                }
+            }
+        });
+        Register::from_amplitudes(sum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::rng::Rng;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn test_hybrid_state_matches_statevector() {
+        let mut rng = Rng::seed_from_u64(64);
+        let mut circuit = Circuit::new(6);
+        for _ in 0..40 {
+            let (a, b, c) = (rng.gen_range(6), rng.gen_range(6), rng.gen_range(6));
+            match rng.gen_range(7) {
+                0 => circuit.h(a),
+                1 => circuit.t(a),
+                2 => circuit.ry(rng.next_f64() * 3.0, a),
+                3 if a != b => circuit.cx(b, a),
+                4 if a != b && b != c && a != c => circuit.mcx(&[c, a], b),
+                5 if a != b => circuit.swap(a, b),
+                _ if a != b => circuit.cp(0.8, a, b),
+                _ => circuit.s(a),
+            };
+        }
+        let hybrid = SchrodingerFeynman::new(&circuit, 3).unwrap();
+        assert!(hybrid.num_cut_gates() > 0);
+        let mut register = Register::new(6);
+        register.apply_circuit(&circuit);
+        crate::assert_state_eq!(hybrid.state(), register);
+        let picked = hybrid.amplitudes(&[5, 40]);
+        assert!((picked[1] - register.amplitudes()[40]).norm() < 1e-10);
+    }
+
+    #[test]
+    fn test_wide_ghz_with_one_cut_gate() {
+        let n = 32;
+        let mut circuit = Circuit::new(n);
+        circuit.h(0);
+        for q in 0..n - 1 {
+            circuit.cx(q, q + 1);
+        }
+        // two 16-qubit halves instead of one 32-qubit state
+        let hybrid = SchrodingerFeynman::new(&circuit, 16).unwrap();
+        assert_eq!((hybrid.num_cut_gates(), hybrid.num_paths()), (1, 2));
+        let amplitudes = hybrid.amplitudes(&[0, (1 << n) - 1, 1 << 16]);
+        assert!((amplitudes[0].re - FRAC_1_SQRT_2).abs() < 1e-12);
+        assert!((amplitudes[1].re - FRAC_1_SQRT_2).abs() < 1e-12);
+        assert!(amplitudes[2].norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_path_limit() {
+        let mut circuit = Circuit::new(4);
+        for _ in 0..21 {
+            circuit.cz(1, 2);
+        }
+        let error = SchrodingerFeynman::new(&circuit, 2).err().unwrap();
+        assert!(error.contains("paths exceed"));
+        assert!(SchrodingerFeynman::new(&circuit, 4).is_err());
+    }
+}
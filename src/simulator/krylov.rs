@@ -0,0 +1,171 @@
+use num_complex::Complex64;
+use super::matrix::Matrix;
+use super::rng::Rng;
+
+fn inner(a: &[Complex64], b: &[Complex64]) -> Complex64 {
+    a.iter().zip(b).map(|(x, y)| x.conj() * y).sum()
+}
+
+fn norm(v: &[Complex64]) -> f64 {
+    v.iter().map(|a| a.norm_sqr()).sum::<f64>().sqrt()
+}
+
+/// complex Gaussian vector, overlapping every eigenvector almost surely
+fn random_start(dim: usize, rng: &mut Rng) -> Vec<Complex64> {
+    (0..dim).map(|_| Complex64::new(rng.normal(), rng.normal())).collect()
+}
+
+/// Krylov cycles before giving up on the residual tolerance
+const MAX_RESTARTS: usize = 200;
+
+/// lowest eigenpair of the Hermitian operator `apply` on `dim` amplitudes
+///
+/// Restarted Lanczos with full reorthogonalization: each cycle builds an
+/// orthonormal Krylov basis of at most `krylov_dim` vectors, so memory stays
+/// at `krylov_dim` state vectors, and restarts from its best Ritz vector until
+/// the residual β_m·|y_m| drops below `tolerance` or the Krylov space closes.
+pub fn lanczos(
+    dim: usize,
+    mut apply: impl FnMut(&[Complex64]) -> Vec<Complex64>,
+    krylov_dim: usize,
+    tolerance: f64,
+    rng: &mut Rng,
+) -> (f64, Vec<Complex64>) {
+    deflated_lanczos(dim, &mut apply, &[], krylov_dim, tolerance, rng)
+}
+
+/// the `count` lowest eigenpairs, ascending, found one at a time: each run
+/// keeps its Krylov space orthogonal to the eigenvectors already locked
+pub fn lanczos_eigenpairs(
+    dim: usize,
+    mut apply: impl FnMut(&[Complex64]) -> Vec<Complex64>,
+    count: usize,
+    krylov_dim: usize,
+    tolerance: f64,
+    rng: &mut Rng,
+) -> Vec<(f64, Vec<Complex64>)> {
+    assert!(count <= dim, "cannot find {} eigenpairs in dimension {}", count, dim);
+    let mut locked: Vec<Vec<Complex64>> = Vec::new();
+    let mut values = Vec::new();
+    for _ in 0..count {
+        let (value, vector) =
+            deflated_lanczos(dim, &mut apply, &locked, krylov_dim, tolerance, rng);
+        values.push(value);
+        locked.push(vector);
+    }
+    values.into_iter().zip(locked).collect()
+}
+
+/// remove the components of `w` along every vector in `against`, twice so the
+/// result is orthogonal to machine precision
+fn orthogonalize(w: &mut [Complex64], against: &[&[Complex64]]) {
+    for _ in 0..2 {
+        for b in against {
+            let c = inner(b, w);
+            w.iter_mut().zip(b.iter()).for_each(|(x, y)| *x -= c * y);
+        }
+    }
+}
+
+fn normalized(v: Vec<Complex64>) -> Vec<Complex64> {
+    let n = norm(&v);
+    v.into_iter().map(|a| a / n).collect()
+}
+
+fn deflated_lanczos(
+    dim: usize,
+    apply: &mut dyn FnMut(&[Complex64]) -> Vec<Complex64>,
+    locked: &[Vec<Complex64>],
+    krylov_dim: usize,
+    tolerance: f64,
+    rng: &mut Rng,
+) -> (f64, Vec<Complex64>) {
+    assert!(dim > locked.len() && krylov_dim > 1, "Lanczos needs a non-empty space");
+    let locked: Vec<&[Complex64]> = locked.iter().map(|v| v.as_slice()).collect();
+    let limit = krylov_dim.min(dim - locked.len());
+    let mut start = random_start(dim, rng);
+    for cycle in 0..MAX_RESTARTS {
+        orthogonalize(&mut start, &locked);
+        let mut basis = vec![normalized(start)];
+        let (mut alphas, mut betas) = (Vec::new(), Vec::new());
+        loop {
+            let k = basis.len() - 1;
+            let mut w = apply(&basis[k]);
+            alphas.push(inner(&basis[k], &w).re);
+            let against: Vec<&[Complex64]> =
+                locked.iter().copied().chain(basis.iter().map(|v| v.as_slice())).collect();
+            orthogonalize(&mut w, &against);
+            let beta = norm(&w);
+            let full = basis.len() == limit;
+            if beta < 1e-10 || full || basis.len() % 5 == 0 {
+                let m = alphas.len();
+                let t = Matrix::from_fn(m, m, |i, j| {
+                    let value = match i.abs_diff(j) {
+                        0 => alphas[i],
+                        1 => betas[i.min(j)],
+                        _ => 0.0,
+                    };
+                    Complex64::new(value, 0.0)
+                });
+                let eigen = t.eigen();
+                let y = eigen.vector(0);
+                let converged = beta < 1e-10 || beta * y[m - 1].norm() < tolerance;
+                if converged || full {
+                    let mut vector = vec![Complex64::new(0.0, 0.0); dim];
+                    for (b, c) in basis.iter().zip(&y) {
+                        vector.iter_mut().zip(b).for_each(|(x, v)| *x += c * v);
+                    }
+                    if converged || cycle + 1 == MAX_RESTARTS {
+                        return (eigen.values[0], normalized(vector));
+                    }
+                    start = vector;
+                    break;
+                }
+            }
+            betas.push(beta);
+            basis.push(w.into_iter().map(|a| a / beta).collect());
+        }
+    }
+    unreachable!("the last cycle always returns")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lanczos_finds_lowest_diagonal_entry() {
+        let diagonal: Vec<f64> = (0..50).map(|k| ((k * 37) % 50) as f64 - 7.5).collect();
+        let apply = |v: &[Complex64]| v.iter().zip(&diagonal).map(|(a, d)| a * d).collect();
+        let mut rng = Rng::seed_from_u64(194);
+        // a Krylov space of 8 forces several restarts
+        let (value, vector) = lanczos(50, apply, 8, 1e-10, &mut rng);
+        assert!((value + 7.5).abs() < 1e-9);
+        assert!((vector[0].norm() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_lanczos_closes_on_small_space() {
+        // X on one qubit: the Krylov space closes after two vectors
+        let apply = |v: &[Complex64]| vec![v[1], v[0]];
+        let mut rng = Rng::seed_from_u64(195);
+        let (value, vector) = lanczos(2, apply, 10, 1e-12, &mut rng);
+        assert!((value + 1.0).abs() < 1e-12);
+        assert!((vector[0] + vector[1]).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_eigenpairs_resolve_degenerate_levels() {
+        let diagonal = [3.0, -1.0, 2.0, -1.0, 0.5, 4.0];
+        let apply = |v: &[Complex64]| v.iter().zip(&diagonal).map(|(a, d)| a * d).collect();
+        let mut rng = Rng::seed_from_u64(196);
+        let pairs = lanczos_eigenpairs(6, apply, 4, 20, 1e-10, &mut rng);
+        let values: Vec<f64> = pairs.iter().map(|(v, _)| *v).collect();
+        for (value, expected) in values.iter().zip([-1.0, -1.0, 0.5, 2.0]) {
+            assert!((value - expected).abs() < 1e-9, "{:?}", values);
+        }
+        // the two −1 eigenvectors span the degenerate subspace orthogonally
+        assert!(inner(&pairs[0].1, &pairs[1].1).norm() < 1e-9);
+        assert!((pairs[0].1[1].norm_sqr() + pairs[1].1[1].norm_sqr() - 1.0).abs() < 1e-9);
+    }
+}
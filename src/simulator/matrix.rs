@@ -0,0 +1,356 @@
+use num_complex::Complex64;
+use std::ops::{Add, Index, IndexMut, Mul};
+use super::gates::Matrix2;
+use super::testing::{approx_eq_up_to_phase, ApproxEq};
+
+/// dense complex matrix, row-major
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<Complex64>,
+}
+
+impl Matrix {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![Complex64::new(0.0, 0.0); rows * cols],
+        }
+    }
+
+    pub fn identity(dim: usize) -> Self {
+        let mut m = Self::zeros(dim, dim);
+        for i in 0..dim {
+            m[(i, i)] = Complex64::new(1.0, 0.0);
+        }
+        m
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> Complex64) -> Self {
+        let data = (0..rows * cols).map(|k| f(k / cols, k % cols)).collect();
+        Self { rows, cols, data }
+    }
+
+    pub(crate) fn from_matrix2(m: &Matrix2) -> Self {
+        Self::from_fn(2, 2, |i, j| m[i][j])
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn as_slice(&self) -> &[Complex64] {
+        &self.data
+    }
+
+    /// column `j` as a vector
+    pub fn column(&self, j: usize) -> Vec<Complex64> {
+        (0..self.rows).map(|i| self[(i, j)]).collect()
+    }
+
+    /// conjugate transpose
+    pub fn dagger(&self) -> Matrix {
+        Self::from_fn(self.cols, self.rows, |i, j| self[(j, i)].conj())
+    }
+
+    /// Kronecker product self ⊗ other
+    pub fn kron(&self, other: &Matrix) -> Matrix {
+        Self::from_fn(self.rows * other.rows, self.cols * other.cols, |i, j| {
+            self[(i / other.rows, j / other.cols)] * other[(i % other.rows, j % other.cols)]
+        })
+    }
+
+    /// matrix–vector product
+    pub fn apply(&self, v: &[Complex64]) -> Vec<Complex64> {
+        assert_eq!(v.len(), self.cols, "dimension mismatch");
+        (0..self.rows)
+            .map(|i| (0..self.cols).map(|j| self[(i, j)] * v[j]).sum())
+            .collect()
+    }
+
+    /// every entry multiplied by `c`
+    pub fn scaled(&self, c: Complex64) -> Matrix {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|x| x * c).collect(),
+        }
+    }
+
+    /// matrix exponential e^M by scaling and squaring with a Taylor series
+    pub fn expm(&self) -> Matrix {
+        assert_eq!(self.rows, self.cols, "expm needs a square matrix");
+        let norm = (0..self.rows)
+            .map(|i| (0..self.cols).map(|j| self[(i, j)].norm()).sum::<f64>())
+            .fold(0.0, f64::max);
+        let squarings = if norm > 0.5 { (norm / 0.5).log2().ceil() as u32 } else { 0 };
+        let x = self.scaled(Complex64::new(1.0 / 2f64.powi(squarings as i32), 0.0));
+        let mut result = Matrix::identity(self.rows);
+        let mut term = Matrix::identity(self.rows);
+        for k in 1..=20 {
+            term = (&term * &x).scaled(Complex64::new(1.0 / k as f64, 0.0));
+            result = &result + &term;
+        }
+        for _ in 0..squarings {
+            result = &result * &result;
+        }
+        result
+    }
+
+    /// QR factorization by modified Gram–Schmidt (full column rank assumed);
+    /// R has a positive real diagonal
+    pub fn qr(&self) -> (Matrix, Matrix) {
+        let mut q = self.clone();
+        let mut r = Matrix::zeros(self.cols, self.cols);
+        for j in 0..self.cols {
+            for k in 0..j {
+                let dot: Complex64 = (0..self.rows).map(|i| q[(i, k)].conj() * q[(i, j)]).sum();
+                r[(k, j)] = dot;
+                for i in 0..self.rows {
+                    let qik = q[(i, k)];
+                    q[(i, j)] -= dot * qik;
+                }
+            }
+            let norm = (0..self.rows).map(|i| q[(i, j)].norm_sqr()).sum::<f64>().sqrt();
+            r[(j, j)] = Complex64::new(norm, 0.0);
+            for i in 0..self.rows {
+                q[(i, j)] /= norm;
+            }
+        }
+        (q, r)
+    }
+
+    /// determinant by Gaussian elimination with partial pivoting
+    pub fn determinant(&self) -> Complex64 {
+        assert_eq!(self.rows, self.cols, "determinant needs a square matrix");
+        let n = self.rows;
+        let mut a = self.clone();
+        let mut det = Complex64::new(1.0, 0.0);
+        for col in 0..n {
+            let pivot = (col..n)
+                .max_by(|&x, &y| a[(x, col)].norm().total_cmp(&a[(y, col)].norm()))
+                .expect("non-empty column");
+            if a[(pivot, col)].norm() == 0.0 {
+                return Complex64::new(0.0, 0.0);
+            }
+            if pivot != col {
+                for j in 0..n {
+                    a.data.swap(pivot * n + j, col * n + j);
+                }
+                det = -det;
+            }
+            let p = a[(col, col)];
+            det *= p;
+            for i in col + 1..n {
+                let factor = a[(i, col)] / p;
+                for j in col..n {
+                    let v = a[(col, j)];
+                    a[(i, j)] -= factor * v;
+                }
+            }
+        }
+        det
+    }
+
+    /// eigenvalues of a Hermitian matrix in ascending order
+    ///
+    /// Cyclic Jacobi on the real symmetric embedding [[A, −B], [B, A]] of
+    /// A + iB, whose spectrum is that of the matrix with every value doubled.
+    pub fn hermitian_eigenvalues(&self) -> Vec<f64> {
+        assert_eq!(self.rows, self.cols, "eigenvalues need a square matrix");
+        let n = self.rows;
+        let m = 2 * n;
+        let mut a = vec![0.0; m * m];
+        for i in 0..n {
+            for j in 0..n {
+                let z = self[(i, j)];
+                a[i * m + j] = z.re;
+                a[i * m + j + n] = -z.im;
+                a[(i + n) * m + j] = z.im;
+                a[(i + n) * m + j + n] = z.re;
+            }
+        }
+        for _sweep in 0..100 {
+            let off: f64 = (0..m)
+                .flat_map(|i| (0..m).filter(move |&j| j != i).map(move |j| (i, j)))
+                .map(|(i, j)| a[i * m + j] * a[i * m + j])
+                .sum();
+            if off < 1e-24 {
+                break;
+            }
+            for p in 0..m {
+                for q in p + 1..m {
+                    let apq = a[p * m + q];
+                    if apq.abs() < 1e-300 {
+                        continue;
+                    }
+                    let theta = (a[q * m + q] - a[p * m + p]) / (2.0 * apq);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let s = t * c;
+                    for k in 0..m {
+                        let (akp, akq) = (a[k * m + p], a[k * m + q]);
+                        a[k * m + p] = c * akp - s * akq;
+                        a[k * m + q] = s * akp + c * akq;
+                    }
+                    for k in 0..m {
+                        let (apk, aqk) = (a[p * m + k], a[q * m + k]);
+                        a[p * m + k] = c * apk - s * aqk;
+                        a[q * m + k] = s * apk + c * aqk;
+                    }
+                }
+            }
+        }
+        let mut values: Vec<f64> = (0..m).map(|i| a[i * m + i]).collect();
+        values.sort_by(f64::total_cmp);
+        values.into_iter().step_by(2).collect()
+    }
+
+    /// U†U = I within tolerance
+    pub fn is_unitary(&self, tolerance: f64) -> bool {
+        if self.rows != self.cols {
+            return false;
+        }
+        let product = &self.dagger() * self;
+        let identity = Matrix::identity(self.rows);
+        product
+            .data
+            .iter()
+            .zip(&identity.data)
+            .all(|(a, b)| (a - b).norm() <= tolerance)
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = Complex64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &Complex64 {
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Complex64 {
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+impl Mul for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, rhs: &Matrix) -> Matrix {
+        assert_eq!(self.cols, rhs.rows, "dimension mismatch");
+        let mut out = Matrix::zeros(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(i, k)];
+                if a == Complex64::new(0.0, 0.0) {
+                    continue;
+                }
+                for j in 0..rhs.cols {
+                    out[(i, j)] += a * rhs[(k, j)];
+                }
+            }
+        }
+        out
+    }
+}
+
+impl Add for &Matrix {
+    type Output = Matrix;
+
+    fn add(self, rhs: &Matrix) -> Matrix {
+        assert!(self.rows == rhs.rows && self.cols == rhs.cols, "dimension mismatch");
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().zip(&rhs.data).map(|(a, b)| a + b).collect(),
+        }
+    }
+}
+
+impl ApproxEq for Matrix {
+    fn approx_eq(&self, other: &Self, tolerance: f64) -> bool {
+        self.rows == other.rows
+            && self.cols == other.cols
+            && approx_eq_up_to_phase(&self.data, &other.data, tolerance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::{h_matrix, x_matrix};
+
+    #[test]
+    fn test_kron_dimensions_and_unitarity() {
+        let hx = Matrix::from_matrix2(&h_matrix()).kron(&Matrix::from_matrix2(&x_matrix()));
+        assert_eq!((hx.rows(), hx.cols()), (4, 4));
+        assert!(hx.is_unitary(1e-10));
+    }
+
+    #[test]
+    fn test_dagger_inverts_unitary() {
+        let h = Matrix::from_matrix2(&h_matrix());
+        assert!(h.is_unitary(1e-12));
+        assert_eq!(h.dagger(), h);
+        crate::assert_unitary_eq!(&h * &h, Matrix::identity(2), 1e-12);
+    }
+
+    #[test]
+    fn test_expm_of_pauli_rotation() {
+        // e^{-iθX/2} = RX(θ)
+        let theta = 1.3;
+        let generator = Matrix::from_matrix2(&x_matrix()).scaled(Complex64::new(0.0, -theta / 2.0));
+        let expected = Matrix::from_matrix2(&crate::simulator::gates::rx_matrix(theta));
+        for (a, b) in generator.expm().as_slice().iter().zip(expected.as_slice()) {
+            assert!((a - b).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_hermitian_eigenvalues() {
+        // Y has eigenvalues ±1; diag(3, −2) ⊗ I is already diagonal
+        let y = Matrix::from_matrix2(&crate::simulator::gates::y_matrix());
+        let values = y.hermitian_eigenvalues();
+        assert!((values[0] + 1.0).abs() < 1e-10 && (values[1] - 1.0).abs() < 1e-10);
+        let d = Matrix::from_fn(2, 2, |i, j| {
+            Complex64::new(if i != j { 0.0 } else if i == 0 { 3.0 } else { -2.0 }, 0.0)
+        });
+        let values = d.kron(&Matrix::identity(2)).hermitian_eigenvalues();
+        assert_eq!(values.len(), 4);
+        for (v, e) in values.iter().zip([-2.0, -2.0, 3.0, 3.0]) {
+            assert!((v - e).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_determinant() {
+        let h = Matrix::from_matrix2(&h_matrix());
+        let x = Matrix::from_matrix2(&x_matrix());
+        assert!((h.determinant() + 1.0).norm() < 1e-12);
+        // det(A ⊗ B) = det(A)^2 det(B)^2 for 2×2 factors
+        assert!((h.kron(&x).determinant() - 1.0).norm() < 1e-12);
+        assert_eq!(Matrix::zeros(3, 3).determinant(), Complex64::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_qr_reconstructs() {
+        let a = Matrix::from_fn(3, 3, |i, j| {
+            let diag = if i == j { 4.0 } else { 0.0 };
+            Complex64::new((i + 2 * j) as f64 + diag, (i * j) as f64)
+        });
+        let (q, r) = a.qr();
+        assert!(q.is_unitary(1e-10));
+        let qr = &q * &r;
+        for (x, y) in qr.as_slice().iter().zip(a.as_slice()) {
+            assert!((x - y).norm() < 1e-10);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use num_complex::Complex64;
+use super::gates::dagger;
+use super::matrix::Matrix;
+use super::operator::Operator;
+use super::pauli::Pauli;
+use super::register::Register;
+use super::rng::Rng;
+
+/// generalized measurement given by Kraus operators K_m with Σ K_m†K_m = I
+///
+/// Outcome m has probability ⟨ψ|K_m†K_m|ψ⟩ and leaves K_m|ψ⟩ normalized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Povm {
+    num_qubits: usize,
+    kraus: Vec<Matrix>,
+}
+
+impl Povm {
+    /// panics unless the operators are square, equally sized and complete
+    pub fn new(kraus: Vec<Matrix>) -> Self {
+        assert!(!kraus.is_empty(), "POVM needs at least one outcome");
+        let dim = kraus[0].rows();
+        assert!(dim.is_power_of_two(), "Kraus dimension must be a power of two");
+        let mut total = Matrix::zeros(dim, dim);
+        for k in &kraus {
+            assert!(k.rows() == dim && k.cols() == dim, "Kraus operators must be {}×{}", dim, dim);
+            total = &total + &(&k.dagger() * k);
+        }
+        let identity = Matrix::identity(dim);
+        let complete = total
+            .as_slice()
+            .iter()
+            .zip(identity.as_slice())
+            .all(|(a, b)| (a - b).norm() < 1e-9);
+        assert!(complete, "Kraus operators do not sum to the identity");
+        Self {
+            num_qubits: dim.trailing_zeros() as usize,
+            kraus,
+        }
+    }
+
+    /// projective measurement onto the columns of `basis`
+    pub fn projective(basis: &Matrix) -> Self {
+        assert!(basis.is_unitary(1e-9), "basis must be unitary");
+        let kraus = (0..basis.cols())
+            .map(|m| {
+                let v = basis.column(m);
+                Matrix::from_fn(basis.rows(), basis.rows(), |i, j| v[i] * v[j].conj())
+            })
+            .collect();
+        Self::new(kraus)
+    }
+
+    /// computational-basis measurement of `num_qubits` qubits
+    pub fn computational(num_qubits: usize) -> Self {
+        Self::projective(&Matrix::identity(1 << num_qubits))
+    }
+
+    /// symmetric three-outcome qubit POVM with elements (2/3)|ψ_m⟩⟨ψ_m|, the
+    /// |ψ_m⟩ spaced 120° apart on the X–Z great circle
+    pub fn trine() -> Self {
+        let kraus = (0..3)
+            .map(|m| {
+                let theta = 2.0 * std::f64::consts::PI * m as f64 / 3.0;
+                let v = [(theta / 2.0).cos(), (theta / 2.0).sin()];
+                let scale = (2.0f64 / 3.0).sqrt();
+                Matrix::from_fn(2, 2, |i, j| Complex64::new(scale * v[i] * v[j], 0.0))
+            })
+            .collect();
+        Self::new(kraus)
+    }
+
+    /// weak Z measurement of strength s ∈ [0, 1]
+    ///
+    /// K_0 = diag(√((1+s)/2), √((1−s)/2)) and K_1 = diag(√((1−s)/2), √((1+s)/2)):
+    /// s = 0 learns nothing and leaves the state alone, s = 1 is a full
+    /// projective collapse.
+    pub fn weak_z(strength: f64) -> Self {
+        assert!((0.0..=1.0).contains(&strength), "strength must lie in [0, 1]");
+        let (strong, weak) = (((1.0 + strength) / 2.0).sqrt(), ((1.0 - strength) / 2.0).sqrt());
+        let diagonal = |a: f64, b: f64| {
+            let mut k = Matrix::zeros(2, 2);
+            k[(0, 0)] = Complex64::new(a, 0.0);
+            k[(1, 1)] = Complex64::new(b, 0.0);
+            k
+        };
+        Self::new(vec![diagonal(strong, weak), diagonal(weak, strong)])
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn num_outcomes(&self) -> usize {
+        self.kraus.len()
+    }
+
+    pub fn kraus(&self) -> &[Matrix] {
+        &self.kraus
+    }
+
+    /// effects E_m = K_m†K_m
+    pub fn effects(&self) -> Vec<Matrix> {
+        self.kraus.iter().map(|k| &k.dagger() * k).collect()
+    }
+
+    /// outcome probabilities on `qubits` of `state`, without disturbing it
+    pub fn probabilities(&self, state: &Register, qubits: &[usize]) -> Vec<f64> {
+        self.kraus
+            .iter()
+            .map(|k| {
+                let mut branch = state.clone();
+                branch.apply_operator(qubits, k);
+                branch.amplitudes().iter().map(|a| a.norm_sqr()).sum()
+            })
+            .collect()
+    }
+}
+
+impl Register {
+    /// apply an arbitrary 2^k × 2^k operator to `qubits` without renormalizing;
+    /// `qubits[0]` is the least significant bit of the operator's index
+    pub fn apply_operator(&mut self, qubits: &[usize], operator: &Matrix) {
+        let dim = 1 << qubits.len();
+        assert!(operator.rows() == dim && operator.cols() == dim, "operator size mismatch");
+        for (k, &q) in qubits.iter().enumerate() {
+            assert!(q < self.num_qubits(), "qubit {} out of range", q);
+            assert!(!qubits[..k].contains(&q), "repeated qubit {}", q);
+        }
+        let mask: usize = qubits.iter().map(|&q| 1 << q).sum();
+        let offset = |local: usize| -> usize {
+            qubits
+                .iter()
+                .enumerate()
+                .filter(|(b, _)| local >> b & 1 == 1)
+                .map(|(_, &q)| 1 << q)
+                .sum()
+        };
+        let offsets: Vec<usize> = (0..dim).map(offset).collect();
+        let mut amplitudes = self.amplitudes().to_vec();
+        for base in (0..amplitudes.len()).filter(|i| i & mask == 0) {
+            let local: Vec<Complex64> = offsets.iter().map(|&o| amplitudes[base | o]).collect();
+            for (&o, value) in offsets.iter().zip(operator.apply(&local)) {
+                amplitudes[base | o] = value;
+            }
+        }
+        self.set_amplitudes(amplitudes);
+    }
+
+    /// project `qubits` with `projector` and renormalize; returns the
+    /// probability of the projected outcome
+    pub fn project(&mut self, qubits: &[usize], projector: &Matrix) -> f64 {
+        self.apply_operator(qubits, projector);
+        let probability = self.amplitudes().iter().map(|a| a.norm_sqr()).sum();
+        self.normalize();
+        probability
+    }
+
+    /// sample a POVM outcome on `qubits`; the state becomes K_m|ψ⟩ normalized
+    pub fn measure_povm(&mut self, povm: &Povm, qubits: &[usize], rng: &mut Rng) -> usize {
+        assert_eq!(qubits.len(), povm.num_qubits(), "POVM acts on {} qubits", povm.num_qubits());
+        let probabilities = povm.probabilities(self, qubits);
+        let mut r = rng.next_f64();
+        let mut outcome = probabilities.len() - 1;
+        for (m, p) in probabilities.iter().enumerate() {
+            if r < *p {
+                outcome = m;
+                break;
+            }
+            r -= p;
+        }
+        self.project(qubits, &povm.kraus()[outcome]);
+        outcome
+    }
+
+    /// partial collapse of `qubit` towards the observed Z outcome; see
+    /// [`Povm::weak_z`]
+    pub fn weak_measure(&mut self, qubit: usize, strength: f64, rng: &mut Rng) -> bool {
+        self.measure_povm(&Povm::weak_z(strength), &[qubit], rng) == 1
+    }
+
+    /// projective measurement of `qubit` onto the columns of `basis`; returns
+    /// `true` for the second column, and the qubit is left in that vector.
+    /// A basis that is not one-qubit is rejected before anything is measured.
+    pub fn measure_in_basis(
+        &mut self,
+        qubit: usize,
+        basis: &Operator,
+        rng: &mut Rng,
+    ) -> Result<bool, String> {
+        let basis = basis.one_qubit_matrix2()?;
+        self.apply_matrix2(qubit, dagger(&basis));
+        let outcome = self.measure(qubit, rng);
+        self.apply_matrix2(qubit, basis);
+        Ok(outcome)
+    }
+
+    /// measure a single-qubit Pauli observable; `true` means eigenvalue −1
+    pub fn measure_pauli(&mut self, qubit: usize, pauli: Pauli, rng: &mut Rng) -> bool {
+        for gate in pauli.basis_change() {
+            self.apply_matrix2(qubit, gate.matrix2().expect("single-qubit gate"));
+        }
+        let outcome = self.measure(qubit, rng);
+        for gate in pauli.basis_change().iter().rev() {
+            self.apply_matrix2(qubit, gate.inverse().matrix2().expect("single-qubit gate"));
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::{h_matrix, x_matrix, Gate};
+
+    #[test]
+    fn test_pauli_measurement_leaves_eigenstate() {
+        let mut rng = Rng::seed_from_u64(25);
+        for _ in 0..10 {
+            let mut register = Register::new(1);
+            let minus = register.measure_pauli(0, Pauli::X, &mut rng);
+            // re-measuring X must repeat the first outcome
+            assert_eq!(register.measure_pauli(0, Pauli::X, &mut rng), minus);
+        }
+        let mut plus = Register::new(1);
+        plus.apply_gate(0, h_matrix()).unwrap();
+        assert!(!plus.measure_in_basis(0, &Gate::H.operator(), &mut rng).unwrap());
+        crate::assert_state_eq!(plus, Register::from_amplitudes(vec![Complex64::new(1.0, 0.0); 2]));
+    }
+
+    #[test]
+    fn test_projector_on_bell_pair() {
+        let mut register = Register::new(2);
+        register.apply_gate(0, h_matrix()).unwrap();
+        register.apply_controlled_gate(&[0], 1, x_matrix()).unwrap();
+        let mut one = Matrix::zeros(2, 2);
+        one[(1, 1)] = Complex64::new(1.0, 0.0);
+        let p = register.project(&[1], &one);
+        assert!((p - 0.5).abs() < 1e-12);
+        assert!((register.probabilities()[3] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_trine_statistics() {
+        let povm = Povm::trine();
+        let probs = povm.probabilities(&Register::new(1), &[0]);
+        // |0⟩ has overlap 1, 1/4, 1/4 with the trine states, scaled by 2/3
+        assert!((probs[0] - 2.0 / 3.0).abs() < 1e-12);
+        assert!((probs[1] - 1.0 / 6.0).abs() < 1e-12 && (probs[2] - 1.0 / 6.0).abs() < 1e-12);
+        let mut rng = Rng::seed_from_u64(3);
+        let hits = (0..3000)
+            .filter(|_| Register::new(1).measure_povm(&povm, &[0], &mut rng) == 0)
+            .count();
+        assert!((hits as f64 / 3000.0 - 2.0 / 3.0).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_weak_measurement_limits() {
+        let mut rng = Rng::seed_from_u64(19);
+        let mut plus = Register::new(1);
+        plus.apply_gate(0, h_matrix()).unwrap();
+
+        let mut untouched = plus.clone();
+        untouched.weak_measure(0, 0.0, &mut rng);
+        crate::assert_state_eq!(untouched, plus);
+
+        let mut collapsed = plus.clone();
+        let one = collapsed.weak_measure(0, 1.0, &mut rng);
+        assert!((collapsed.prob_one(0) - if one { 1.0 } else { 0.0 }).abs() < 1e-12);
+
+        // intermediate strength: P(1) moves to (1 ± s)/2 but stays mixed
+        let mut partial = plus.clone();
+        let one = partial.weak_measure(0, 0.6, &mut rng);
+        let expected = if one { 0.8 } else { 0.2 };
+        assert!((partial.prob_one(0) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "do not sum to the identity")]
+    fn test_incomplete_povm_rejected() {
+        Povm::new(vec![Matrix::identity(2).scaled(Complex64::new(0.5, 0.0))]);
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use super::circuit::{Circuit, Instruction};
+use super::gates::Gate;
+
+/// size and cost figures for comparing decompositions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitMetrics {
+    pub width: usize,
+    pub gate_count: usize,
+    pub depth: usize,
+    pub t_count: usize,
+    pub two_qubit_count: usize,
+    pub multi_qubit_count: usize,
+}
+
+impl fmt::Display for CircuitMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "width {}, gates {}, depth {}, T-count {}, 2q {}, multi-qubit {}",
+            self.width,
+            self.gate_count,
+            self.depth,
+            self.t_count,
+            self.two_qubit_count,
+            self.multi_qubit_count
+        )
+    }
+}
+
+impl Circuit {
+    /// instruction count per gate name
+    pub fn gate_counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for inst in self.instructions() {
+            *counts.entry(inst.gate.name()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// qubits touched by at least one instruction
+    pub fn width(&self) -> usize {
+        let mut used = vec![false; self.num_qubits()];
+        for inst in self.instructions() {
+            for &q in &inst.qubits {
+                used[q] = true;
+            }
+        }
+        used.into_iter().filter(|&u| u).count()
+    }
+
+    /// T and T† gates
+    pub fn t_count(&self) -> usize {
+        self.instructions()
+            .iter()
+            .filter(|inst| matches!(inst.gate, Gate::T | Gate::Tdg))
+            .count()
+    }
+
+    /// instructions acting on exactly two qubits
+    pub fn two_qubit_count(&self) -> usize {
+        self.instructions().iter().filter(|inst| inst.qubits.len() == 2).count()
+    }
+
+    /// instructions acting on two or more qubits
+    pub fn multi_qubit_gate_count(&self) -> usize {
+        self.instructions().iter().filter(|inst| inst.qubits.len() > 1).count()
+    }
+
+    /// ASAP layer of every instruction (1-based) and, for each, the earlier
+    /// instruction it waits on
+    fn asap_schedule(&self) -> (Vec<usize>, Vec<Option<usize>>) {
+        let mut last: Vec<Option<usize>> = vec![None; self.num_qubits()];
+        let mut level = Vec::with_capacity(self.len());
+        let mut parent = Vec::with_capacity(self.len());
+        for (k, inst) in self.instructions().iter().enumerate() {
+            let before = inst
+                .qubits
+                .iter()
+                .filter_map(|&q| last[q])
+                .max_by_key(|&j| level[j]);
+            level.push(before.map_or(1, |j| level[j] + 1));
+            parent.push(before);
+            for &q in &inst.qubits {
+                last[q] = Some(k);
+            }
+        }
+        (level, parent)
+    }
+
+    /// number of layers when every gate is scheduled as early as possible
+    pub fn depth(&self) -> usize {
+        self.asap_schedule().0.into_iter().max().unwrap_or(0)
+    }
+
+    /// instructions grouped into moments of gates on disjoint qubits, each
+    /// gate in the earliest moment its qubits allow
+    pub fn layers(&self) -> Vec<Vec<&Instruction>> {
+        let (level, _) = self.asap_schedule();
+        let mut layers: Vec<Vec<&Instruction>> = vec![Vec::new(); self.depth()];
+        for (inst, l) in self.instructions().iter().zip(level) {
+            layers[l - 1].push(inst);
+        }
+        layers
+    }
+
+    /// instruction indices along one longest dependency chain, in order
+    pub fn critical_path(&self) -> Vec<usize> {
+        let (level, parent) = self.asap_schedule();
+        let mut current = (0..level.len()).max_by_key(|&k| (level[k], std::cmp::Reverse(k)));
+        let mut path = Vec::new();
+        while let Some(k) = current {
+            path.push(k);
+            current = parent[k];
+        }
+        path.reverse();
+        path
+    }
+
+    pub fn metrics(&self) -> CircuitMetrics {
+        CircuitMetrics {
+            width: self.width(),
+            gate_count: self.len(),
+            depth: self.depth(),
+            t_count: self.t_count(),
+            two_qubit_count: self.two_qubit_count(),
+            multi_qubit_count: self.multi_qubit_gate_count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_statistics() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).h(1).cx(0, 1).t(2).cx(1, 2).h(0);
+        assert_eq!(circuit.depth(), 3);
+        assert_eq!(circuit.multi_qubit_gate_count(), 2);
+        let counts = circuit.gate_counts();
+        assert_eq!((counts["h"], counts["cx"], counts["t"]), (3, 2, 1));
+        assert_eq!(Circuit::new(4).depth(), 0);
+    }
+
+    #[test]
+    fn test_toffoli_decomposition_metrics() {
+        // standard 7-T Clifford+T Toffoli
+        let mut circuit = Circuit::new(4);
+        circuit.h(2).cx(1, 2).tdg(2).cx(0, 2).t(2).cx(1, 2).tdg(2).cx(0, 2);
+        circuit.t(1).t(2).h(2).cx(0, 1).t(0).tdg(1).cx(0, 1);
+        let metrics = circuit.metrics();
+        assert_eq!(metrics.width, 3);
+        assert_eq!((metrics.t_count, metrics.two_qubit_count), (7, 6));
+        assert_eq!(metrics.gate_count, 15);
+        assert_eq!(metrics.depth, circuit.critical_path().len());
+    }
+
+    #[test]
+    fn test_layers_are_disjoint_moments() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).h(1).cx(0, 1).t(2).cx(1, 2).h(0);
+        let layers = circuit.layers();
+        let names: Vec<Vec<&str>> = layers
+            .iter()
+            .map(|moment| moment.iter().map(|inst| inst.gate.name()).collect())
+            .collect();
+        assert_eq!(names, vec![vec!["h", "h", "t"], vec!["cx"], vec!["cx", "h"]]);
+        for moment in &layers {
+            let mut qubits: Vec<usize> = moment.iter().flat_map(|i| i.qubits.clone()).collect();
+            let total = qubits.len();
+            qubits.sort();
+            qubits.dedup();
+            assert_eq!(qubits.len(), total);
+        }
+        assert_eq!(layers.len(), circuit.depth());
+    }
+
+    #[test]
+    fn test_critical_path_follows_dependencies() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).t(0).x(2).cx(0, 1).cx(1, 2);
+        // h(0) → t(0) → cx(0,1) → cx(1,2) beats x(2) → cx(1,2)
+        assert_eq!(circuit.critical_path(), vec![0, 1, 3, 4]);
+        assert!(Circuit::new(2).critical_path().is_empty());
+    }
+}
@@ -1,5 +1,75 @@
 pub mod single_qubit;
 pub mod gates;
+pub mod rotation;
+pub mod rng;
+pub mod register;
+pub mod density;
+pub mod diagnostics;
+pub mod distribution;
+pub mod testing;
+pub mod matrix;
+pub mod eigen;
+pub mod operator;
+pub mod sparse;
+pub mod krylov;
+pub mod circuit;
+pub mod property;
+pub mod random;
+pub mod pauli;
+pub mod pauli_string;
+pub mod hamiltonian;
+pub mod qudit;
+pub mod equivalence;
+pub mod diff;
+pub mod metrics;
+pub mod report;
+pub mod clifford;
+pub mod tableau;
+pub mod measurement;
+pub mod expectation;
+pub mod grouping;
+pub mod path_sum;
+pub mod decision_diagram;
+pub mod tensor_network;
+pub mod hybrid;
+pub mod cutting;
+pub mod extended_stabilizer;
+pub mod planner;
+pub mod checkpoint;
+pub mod golden;
+pub mod timing;
 
-pub use single_qubit::SingleQubit;
+pub use single_qubit::{RecordedQubit, SingleQubit};
 pub use gates::*;
+pub use rng::Rng;
+pub use rotation::AxisAngle;
+pub use register::Register;
+pub use distribution::{Distribution, Sampler};
+pub use testing::ApproxEq;
+pub use matrix::Matrix;
+pub use operator::Operator;
+pub use sparse::SparseMatrix;
+pub use circuit::{Circuit, Instruction, Marker, MarkerKind};
+pub use diff::{CircuitDiff, DiffEntry};
+pub use random::random_unitary;
+pub use pauli::Pauli;
+pub use pauli_string::PauliString;
+pub use hamiltonian::Hamiltonian;
+pub use qudit::QuditState;
+pub use metrics::CircuitMetrics;
+pub use report::{
+    run, run_with_callback, run_with_handle, run_with_progress, Progress, RunHandle, RunReport,
+    RunResult, ShotResult,
+};
+pub use measurement::Povm;
+pub use expectation::ExpectationEstimate;
+pub use grouping::{group_qubit_wise, MeasurementGroup};
+pub use path_sum::PathSum;
+pub use decision_diagram::{run_decision_diagram, DecisionDiagram};
+pub use tensor_network::TensorNetwork;
+pub use hybrid::{cut_paths, SchrodingerFeynman};
+pub use cutting::{CutCircuit, WireCut, MAX_CUT_TERMS};
+pub use extended_stabilizer::{stabilizer_branches, ExtendedStabilizer};
+pub use planner::{plan_backend, Backend, BackendPlan};
+pub use golden::Snapshot;
+pub use timing::{GateTimes, IdleWindow, Schedule, SchedulePolicy, ScheduledCircuit, Timing};
@@ -0,0 +1,345 @@
+use std::borrow::Cow;
+use std::ops::{Add, Mul, Sub};
+use num_complex::Complex64;
+use super::circuit::Circuit;
+use super::eigen::Eigen;
+use super::gates::{Gate, Matrix2};
+use super::matrix::Matrix;
+use super::sparse::SparseMatrix;
+
+#[derive(Debug, Clone)]
+enum Storage {
+    Dense(Matrix),
+    Sparse(SparseMatrix),
+}
+
+/// square linear operator on n qubits, basis index bit q for qubit q
+///
+/// Stored dense or sparse; algebra between two sparse operators stays
+/// sparse, anything involving a dense one is dense. Equality compares
+/// entries, whatever the storage.
+#[derive(Debug, Clone)]
+pub struct Operator {
+    num_qubits: usize,
+    storage: Storage,
+}
+
+fn check_dimension(rows: usize, cols: usize) -> usize {
+    assert_eq!(rows, cols, "operator must be square");
+    assert!(rows.is_power_of_two(), "dimension must be a power of two");
+    rows.trailing_zeros() as usize
+}
+
+impl Operator {
+    /// dense operator; panics unless `matrix` is square with a power-of-two
+    /// dimension
+    pub fn new(matrix: Matrix) -> Self {
+        Self {
+            num_qubits: check_dimension(matrix.rows(), matrix.cols()),
+            storage: Storage::Dense(matrix),
+        }
+    }
+
+    /// sparse operator, with the same shape requirements as `new`
+    pub fn sparse(matrix: SparseMatrix) -> Self {
+        Self {
+            num_qubits: check_dimension(matrix.rows(), matrix.cols()),
+            storage: Storage::Sparse(matrix),
+        }
+    }
+
+    pub fn identity(num_qubits: usize) -> Self {
+        Self::new(Matrix::identity(1 << num_qubits))
+    }
+
+    pub fn zeros(num_qubits: usize) -> Self {
+        Self::new(Matrix::zeros(1 << num_qubits, 1 << num_qubits))
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn dim(&self) -> usize {
+        1 << self.num_qubits
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        matches!(self.storage, Storage::Sparse(_))
+    }
+
+    /// entry (i, j)
+    pub fn get(&self, i: usize, j: usize) -> Complex64 {
+        match &self.storage {
+            Storage::Dense(m) => m[(i, j)],
+            Storage::Sparse(m) => m.get(i, j),
+        }
+    }
+
+    /// dense matrix, borrowed when stored dense
+    pub fn matrix(&self) -> Cow<'_, Matrix> {
+        match &self.storage {
+            Storage::Dense(m) => Cow::Borrowed(m),
+            Storage::Sparse(m) => Cow::Owned(m.to_dense()),
+        }
+    }
+
+    pub fn into_matrix(self) -> Matrix {
+        match self.storage {
+            Storage::Dense(m) => m,
+            Storage::Sparse(m) => m.to_dense(),
+        }
+    }
+
+    /// the same operator stored sparse
+    pub fn to_sparse(&self) -> Operator {
+        match &self.storage {
+            Storage::Dense(m) => Operator::sparse(SparseMatrix::from_dense(m)),
+            Storage::Sparse(_) => self.clone(),
+        }
+    }
+
+    /// the same operator stored dense
+    pub fn to_dense(&self) -> Operator {
+        Operator::new(self.matrix().into_owned())
+    }
+
+    /// the 2×2 array of a one-qubit operator, `None` otherwise
+    pub(crate) fn to_matrix2(&self) -> Option<Matrix2> {
+        (self.num_qubits == 1)
+            .then(|| [[self.get(0, 0), self.get(0, 1)], [self.get(1, 0), self.get(1, 1)]])
+    }
+
+    /// `to_matrix2` for the one-qubit gate entry points, naming the width of
+    /// any other operator
+    pub(crate) fn one_qubit_matrix2(&self) -> Result<Matrix2, String> {
+        self.to_matrix2().ok_or_else(|| {
+            format!("expected a one-qubit operator, got {} qubits", self.num_qubits)
+        })
+    }
+
+    /// operator–vector product
+    pub fn apply(&self, v: &[Complex64]) -> Vec<Complex64> {
+        match &self.storage {
+            Storage::Dense(m) => m.apply(v),
+            Storage::Sparse(m) => m.apply(v),
+        }
+    }
+
+    /// self ⊗ other: `other` acts on the low qubits, `self` on the ones above
+    pub fn kron(&self, other: &Operator) -> Operator {
+        match (&self.storage, &other.storage) {
+            (Storage::Sparse(a), Storage::Sparse(b)) => Operator::sparse(a.kron(b)),
+            _ => Operator::new(self.matrix().kron(&other.matrix())),
+        }
+    }
+
+    pub fn dagger(&self) -> Operator {
+        match &self.storage {
+            Storage::Dense(m) => Operator::new(m.dagger()),
+            Storage::Sparse(m) => Operator::sparse(m.dagger()),
+        }
+    }
+
+    pub fn scaled(&self, c: Complex64) -> Operator {
+        match &self.storage {
+            Storage::Dense(m) => Operator::new(m.scaled(c)),
+            Storage::Sparse(m) => Operator::sparse(m.scaled(c)),
+        }
+    }
+
+    pub fn trace(&self) -> Complex64 {
+        (0..self.dim()).map(|i| self.get(i, i)).sum()
+    }
+
+    /// Frobenius norm √Tr(A†A)
+    pub fn norm(&self) -> f64 {
+        let squares: f64 = match &self.storage {
+            Storage::Dense(m) => m.as_slice().iter().map(|a| a.norm_sqr()).sum(),
+            Storage::Sparse(m) => {
+                (0..m.rows()).flat_map(|i| m.row(i)).map(|(_, a)| a.norm_sqr()).sum()
+            }
+        };
+        squares.sqrt()
+    }
+
+    /// [A, B] = AB − BA
+    pub fn commutator(&self, other: &Operator) -> Operator {
+        &(self * other) - &(other * self)
+    }
+
+    /// {A, B} = AB + BA
+    pub fn anticommutator(&self, other: &Operator) -> Operator {
+        &(self * other) + &(other * self)
+    }
+
+    /// ‖[A, B]‖ below `tolerance`
+    pub fn commutes_with(&self, other: &Operator, tolerance: f64) -> bool {
+        self.commutator(other).norm() < tolerance
+    }
+
+    pub fn is_hermitian(&self, tolerance: f64) -> bool {
+        (self - &self.dagger()).norm() < tolerance
+    }
+
+    pub fn is_unitary(&self, tolerance: f64) -> bool {
+        (&(&self.dagger() * self) - &Operator::identity(self.num_qubits)).norm() < tolerance
+    }
+
+    /// spectral decomposition of a Hermitian operator
+    pub fn eigen(&self) -> Eigen {
+        self.matrix().eigen()
+    }
+}
+
+impl PartialEq for Operator {
+    fn eq(&self, other: &Operator) -> bool {
+        match (&self.storage, &other.storage) {
+            (Storage::Dense(a), Storage::Dense(b)) => a == b,
+            (Storage::Sparse(a), Storage::Sparse(b)) => a == b,
+            _ => {
+                let n = self.dim();
+                self.num_qubits == other.num_qubits
+                    && (0..n).all(|i| (0..n).all(|j| self.get(i, j) == other.get(i, j)))
+            }
+        }
+    }
+}
+
+impl From<Matrix> for Operator {
+    fn from(matrix: Matrix) -> Self {
+        Operator::new(matrix)
+    }
+}
+
+impl From<SparseMatrix> for Operator {
+    fn from(matrix: SparseMatrix) -> Self {
+        Operator::sparse(matrix)
+    }
+}
+
+impl From<Matrix2> for Operator {
+    fn from(matrix: Matrix2) -> Self {
+        Operator::new(Matrix::from_matrix2(&matrix))
+    }
+}
+
+impl From<&Matrix2> for Operator {
+    fn from(matrix: &Matrix2) -> Self {
+        Operator::from(*matrix)
+    }
+}
+
+impl Add for &Operator {
+    type Output = Operator;
+
+    fn add(self, rhs: &Operator) -> Operator {
+        match (&self.storage, &rhs.storage) {
+            (Storage::Sparse(a), Storage::Sparse(b)) => Operator::sparse(a + b),
+            _ => Operator::new(&*self.matrix() + &*rhs.matrix()),
+        }
+    }
+}
+
+impl Sub for &Operator {
+    type Output = Operator;
+
+    fn sub(self, rhs: &Operator) -> Operator {
+        self + &rhs.scaled(Complex64::new(-1.0, 0.0))
+    }
+}
+
+impl Mul for &Operator {
+    type Output = Operator;
+
+    fn mul(self, rhs: &Operator) -> Operator {
+        match (&self.storage, &rhs.storage) {
+            (Storage::Sparse(a), Storage::Sparse(b)) => Operator::sparse(a * b),
+            _ => Operator::new(&*self.matrix() * &*rhs.matrix()),
+        }
+    }
+}
+
+impl Gate {
+    /// full 2^k × 2^k unitary, operator qubit j being the gate's j-th qubit
+    pub fn operator(&self) -> Operator {
+        let k = self.num_qubits();
+        let qubits: Vec<usize> = (0..k).collect();
+        let mut circuit = Circuit::new(k);
+        circuit.push(self.clone(), &qubits);
+        Operator::new(circuit.to_unitary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::register::Register;
+    use crate::simulator::gates::{h_matrix, x_matrix, y_matrix, z_matrix};
+    use crate::simulator::random::random_unitary;
+    use crate::simulator::rng::Rng;
+
+    #[test]
+    fn test_pauli_algebra() {
+        let [x, y, z] = [x_matrix(), y_matrix(), z_matrix()].map(Operator::from);
+        // [X, Y] = 2iZ and {X, Y} = 0
+        let expected = z.scaled(Complex64::new(0.0, 2.0));
+        assert!((&x.commutator(&y) - &expected).norm() < 1e-12);
+        assert!(x.anticommutator(&y).norm() < 1e-12);
+        assert!(x.commutes_with(&x, 1e-12) && !x.commutes_with(&z, 1e-12));
+        assert!((&x * &x).is_unitary(1e-12) && y.is_hermitian(1e-12));
+        assert_eq!(x.to_matrix2(), Some(x_matrix()));
+        assert_eq!(x.kron(&z).num_qubits(), 2);
+        assert!(x.kron(&z).trace().norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_gate_operator_matches_circuit_unitary() {
+        let cx = Gate::Cx.operator();
+        // control qubit 0 is the low bit: |01⟩ ↦ |11⟩
+        assert_eq!(cx.get(3, 1), Complex64::new(1.0, 0.0));
+        let h = Gate::H.operator();
+        assert_eq!(h.to_matrix2(), Some(h_matrix()));
+        let ccz = Gate::Mcz(2).operator();
+        assert_eq!(ccz.get(7, 7), Complex64::new(-1.0, 0.0));
+        assert!((&ccz.dagger() - &ccz).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_sparse_storage_matches_dense() {
+        let (x, z) = (Operator::from(x_matrix()), Operator::from(z_matrix()));
+        let (sx, sz) = (x.to_sparse(), z.to_sparse());
+        let xz = sx.kron(&sz);
+        assert!(xz.is_sparse() && (&xz * &xz).is_sparse());
+        assert_eq!(xz, x.kron(&z));
+        assert_eq!(&sx * &z, &x * &z);
+        assert!(!(&sx * &z).is_sparse());
+        let v: Vec<Complex64> = (0..4).map(|k| Complex64::new(k as f64, 1.0)).collect();
+        assert_eq!(xz.apply(&v), x.kron(&z).apply(&v));
+        let mut register = Register::new(1);
+        register.apply_gate(0, sx).unwrap();
+        assert_eq!(register.amplitudes()[1], Complex64::new(1.0, 0.0));
+    }
+
+    #[test]
+    fn test_relabelled_operator_matches_instructions() {
+        let mut rng = Rng::seed_from_u64(193);
+        let u = Operator::new(random_unitary(2, &mut rng));
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).h(1).ry(0.4, 2).cx(0, 2);
+        let mut expected = Register::new(3);
+        expected.apply_circuit(&circuit);
+        let mut register = expected.clone();
+        expected.apply_operator(&[2, 0], &u.matrix());
+        // the same operator relabelled: swap its qubits, then apply on [0, 2]
+        let swap = Gate::Swap.operator();
+        register.apply_operator(&[0, 2], &(&(&swap * &u) * &swap).matrix());
+        assert!(register.approx_eq(&expected, 1e-10));
+        let mut via_gate = Register::new(3);
+        via_gate.apply_circuit(&circuit);
+        via_gate.apply_operator(&[1], &Gate::X.operator().matrix());
+        let mut direct = Register::new(3);
+        direct.apply_circuit(circuit.x(1));
+        assert!(via_gate.approx_eq(&direct, 1e-12));
+    }
+}
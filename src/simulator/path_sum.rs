@@ -0,0 +1,336 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::f64::consts::FRAC_PI_4;
+use super::circuit::Circuit;
+use super::gates::Gate;
+
+/// product of boolean variables, as sorted indices; empty is the constant 1
+type Monomial = Vec<usize>;
+
+/// boolean polynomial over GF(2): XOR of distinct monomials
+type BoolPoly = BTreeSet<Monomial>;
+
+fn times(a: &[usize], b: &[usize]) -> Monomial {
+    let mut m: Monomial = a.iter().chain(b).copied().collect();
+    m.sort_unstable();
+    m.dedup();
+    m
+}
+
+fn without(m: &[usize], v: usize) -> Monomial {
+    m.iter().copied().filter(|&w| w != v).collect()
+}
+
+fn toggle(p: &mut BoolPoly, m: Monomial) {
+    if !p.remove(&m) {
+        p.insert(m);
+    }
+}
+
+fn bool_product(a: &BoolPoly, b: &BoolPoly) -> BoolPoly {
+    let mut p = BoolPoly::new();
+    for x in a {
+        for y in b {
+            toggle(&mut p, times(x, y));
+        }
+    }
+    p
+}
+
+/// sum-over-paths form of a Clifford+T circuit
+///
+/// |x⟩ ↦ Σ_y ω^{P(x, y)} |f(x, y)⟩ with ω = e^{iπ/4}: `phase` is P with
+/// coefficients in Z_8, `outputs` holds one boolean polynomial per qubit,
+/// variables 0..n are the inputs and the rest are path variables introduced by
+/// Hadamards. Normalization and global phase are not tracked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathSum {
+    num_variables: usize,
+    phase: BTreeMap<Monomial, u8>,
+    outputs: Vec<BoolPoly>,
+    path: BTreeSet<usize>,
+}
+
+impl PathSum {
+    pub fn identity(num_qubits: usize) -> Self {
+        Self {
+            num_variables: num_qubits,
+            phase: BTreeMap::new(),
+            outputs: (0..num_qubits).map(|q| BoolPoly::from([vec![q]])).collect(),
+            path: BTreeSet::new(),
+        }
+    }
+
+    /// path sum of `circuit`; gates outside Clifford+T (and multi-controlled
+    /// X/Z) are rejected
+    pub fn from_circuit(circuit: &Circuit) -> Result<Self, String> {
+        let mut sum = Self::identity(circuit.num_qubits());
+        for inst in circuit.instructions() {
+            sum.apply(inst.gate.clone(), &inst.qubits)?;
+        }
+        Ok(sum)
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn num_path_variables(&self) -> usize {
+        self.path.len()
+    }
+
+    fn add_term(&mut self, m: Monomial, k: u8) {
+        let entry = self.phase.entry(m.clone()).or_insert(0);
+        *entry = (*entry + k) % 8;
+        if *entry == 0 {
+            self.phase.remove(&m);
+        }
+    }
+
+    /// add k · factor · f, lifting the XOR in f to Z_8 through
+    /// a ⊕ b ⊕ c = a + b + c − 2(ab + ac + bc) + 4abc (longer products vanish)
+    fn add_lifted(&mut self, k: u8, factor: &[usize], f: &BoolPoly) {
+        let terms: Vec<&Monomial> = f.iter().collect();
+        for (i, a) in terms.iter().enumerate() {
+            self.add_term(times(factor, a), k);
+            for (j, b) in terms.iter().enumerate().skip(i + 1) {
+                let ab = times(a, b);
+                self.add_term(times(factor, &ab), (6 * k) % 8);
+                for c in &terms[j + 1..] {
+                    self.add_term(times(factor, &times(&ab, c)), (4 * k) % 8);
+                }
+            }
+        }
+    }
+
+    fn phase_gate(&mut self, k: u8, q: usize) {
+        let f = self.outputs[q].clone();
+        self.add_lifted(k, &[], &f);
+    }
+
+    fn controls_product(&self, controls: &[usize]) -> BoolPoly {
+        controls
+            .iter()
+            .fold(BoolPoly::from([vec![]]), |p, &c| bool_product(&p, &self.outputs[c]))
+    }
+
+    /// append one gate; angles must be multiples of π/4
+    pub fn apply(&mut self, gate: Gate, qubits: &[usize]) -> Result<(), String> {
+        let eighths = |theta: f64| -> Result<u8, String> {
+            let k = theta / FRAC_PI_4;
+            if (k - k.round()).abs() > 1e-9 {
+                return Err(format!("angle {} is not a multiple of π/4", theta));
+            }
+            Ok((k.round() as i64).rem_euclid(8) as u8)
+        };
+        match gate {
+            Gate::I => {}
+            Gate::X => toggle(&mut self.outputs[qubits[0]], vec![]),
+            Gate::Y => {
+                self.phase_gate(4, qubits[0]);
+                toggle(&mut self.outputs[qubits[0]], vec![]);
+            }
+            Gate::Z => self.phase_gate(4, qubits[0]),
+            Gate::S => self.phase_gate(2, qubits[0]),
+            Gate::Sdg => self.phase_gate(6, qubits[0]),
+            Gate::T => self.phase_gate(1, qubits[0]),
+            Gate::Tdg => self.phase_gate(7, qubits[0]),
+            Gate::Rz(theta) | Gate::Phase(theta) => self.phase_gate(eighths(theta)?, qubits[0]),
+            Gate::H => {
+                let y = self.num_variables;
+                self.num_variables += 1;
+                self.path.insert(y);
+                let wire = BoolPoly::from([vec![y]]);
+                for m in std::mem::replace(&mut self.outputs[qubits[0]], wire) {
+                    self.add_term(times(&m, &[y]), 4);
+                }
+            }
+            Gate::Cx | Gate::Mcx(_) => {
+                let (target, controls) = qubits.split_last().expect("gate has a target");
+                for m in self.controls_product(controls) {
+                    toggle(&mut self.outputs[*target], m);
+                }
+            }
+            Gate::Cz | Gate::Mcz(_) => {
+                let all = self.controls_product(qubits);
+                self.add_lifted(4, &[], &all);
+            }
+            Gate::Swap => self.outputs.swap(qubits[0], qubits[1]),
+            other => return Err(format!("gate {} has no path-sum translation", other.name())),
+        }
+        Ok(())
+    }
+
+    /// replace variable `z` by `value` in the phase and the outputs
+    fn substitute(&mut self, z: usize, value: &BoolPoly) {
+        let terms: Vec<(Monomial, u8)> = self
+            .phase
+            .iter()
+            .filter(|(m, _)| m.contains(&z))
+            .map(|(m, &k)| (m.clone(), k))
+            .collect();
+        for (m, k) in terms {
+            self.phase.remove(&m);
+            self.add_lifted(k, &without(&m, z), value);
+        }
+        for f in &mut self.outputs {
+            let hits: Vec<Monomial> = f.iter().filter(|m| m.contains(&z)).cloned().collect();
+            for m in hits {
+                toggle(f, m.clone());
+                let rest = without(&m, z);
+                for v in value {
+                    toggle(f, times(&rest, v));
+                }
+            }
+        }
+    }
+
+    /// try to sum out path variable `y`; true if it was removed
+    fn reduce(&mut self, y: usize) -> bool {
+        if self.outputs.iter().any(|f| f.iter().any(|m| m.contains(&y))) {
+            return false;
+        }
+        let quotient: Vec<(Monomial, u8)> = self
+            .phase
+            .iter()
+            .filter(|(m, _)| m.contains(&y))
+            .map(|(m, &k)| (without(m, y), k))
+            .collect();
+        let linear = quotient.iter().find(|(m, _)| m.is_empty()).map(|&(_, k)| k);
+        let rest: BoolPoly = quotient
+            .iter()
+            .filter(|(m, k)| !m.is_empty() && *k == 4)
+            .map(|(m, _)| m.clone())
+            .collect();
+        let others = quotient.len() - rest.len() - usize::from(linear.is_some());
+        if others > 0 {
+            return false;
+        }
+        match linear {
+            // Σ_y (−1)^{y·(z ⊕ Q)} forces z = Q
+            None | Some(4) => {
+                let mut q = rest;
+                if linear.is_some() {
+                    q.insert(vec![]);
+                }
+                if q.is_empty() {
+                    self.path.remove(&y);
+                    return true;
+                }
+                let z = q.iter().find_map(|m| match m.as_slice() {
+                    [z] if self.path.contains(z)
+                        && q.iter().filter(|m| m.contains(z)).count() == 1 =>
+                    {
+                        Some(*z)
+                    }
+                    _ => None,
+                });
+                let Some(z) = z else { return false };
+                self.phase.retain(|m, _| !m.contains(&y));
+                let mut value = q;
+                value.remove(&vec![z]);
+                self.substitute(z, &value);
+                self.path.remove(&y);
+                self.path.remove(&z);
+                true
+            }
+            // Σ_y i^{±y} (−1)^{y·Q} = √2 ω^{±(1 − 2Q)}
+            Some(k @ (2 | 6)) => {
+                self.phase.retain(|m, _| !m.contains(&y));
+                let sign = if k == 2 { 1 } else { 7 };
+                self.add_term(vec![], sign);
+                self.add_lifted((6 * sign) % 8, &[], &rest);
+                self.path.remove(&y);
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    /// apply the elimination, HH and ω rules until none fires
+    pub fn simplify(&mut self) {
+        loop {
+            let path: Vec<usize> = self.path.iter().copied().collect();
+            if !path.into_iter().any(|y| self.reduce(y)) {
+                break;
+            }
+        }
+    }
+
+    /// `Some(true)` for the identity up to global phase, `Some(false)` for a
+    /// fully reduced sum that is not, `None` while path variables remain
+    pub fn is_identity(&self) -> Option<bool> {
+        if !self.path.is_empty() {
+            return None;
+        }
+        let wires = self.outputs.iter().enumerate().all(|(q, f)| *f == BoolPoly::from([vec![q]]));
+        Some(wires && self.phase.keys().all(|m| m.is_empty()))
+    }
+}
+
+impl Circuit {
+    /// symbolic equivalence check of Clifford+T circuits up to global phase
+    ///
+    /// Reduces the path sum of `other⁻¹ · self` without building any matrix,
+    /// so the cost follows the gate count rather than 2^n. The rewrite rules
+    /// are complete for Clifford circuits; with T gates a sum can get stuck,
+    /// which is reported as `Ok(None)`.
+    pub fn path_sum_equivalent(&self, other: &Circuit) -> Result<Option<bool>, String> {
+        if self.num_qubits() != other.num_qubits() {
+            return Ok(Some(false));
+        }
+        let mut sum = PathSum::from_circuit(self)?;
+        for inst in other.inverse().instructions() {
+            sum.apply(inst.gate.clone(), &inst.qubits)?;
+        }
+        sum.simplify();
+        Ok(sum.is_identity())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toffoli_decomposition_is_proved() {
+        let mut toffoli = Circuit::new(3);
+        toffoli.mcx(&[0, 1], 2);
+        let mut clifford_t = Circuit::new(3);
+        clifford_t.h(2).cx(1, 2).tdg(2).cx(0, 2).t(2).cx(1, 2).tdg(2).cx(0, 2);
+        clifford_t.t(1).t(2).h(2).cx(0, 1).t(0).tdg(1).cx(0, 1);
+        assert_eq!(toffoli.path_sum_equivalent(&clifford_t), Ok(Some(true)));
+        let mut broken = clifford_t.clone();
+        broken.t(0);
+        assert_eq!(toffoli.path_sum_equivalent(&broken), Ok(Some(false)));
+    }
+
+    #[test]
+    fn test_clifford_identities() {
+        // HSH = S† H S† up to phase, and H on both sides turns CX around
+        let (mut a, mut b) = (Circuit::new(2), Circuit::new(2));
+        a.h(0).s(0).h(0).h(0).h(1).cx(0, 1).h(0).h(1);
+        b.sdg(0).h(0).sdg(0).cx(1, 0);
+        assert_eq!(a.path_sum_equivalent(&b), Ok(Some(true)));
+        let mut swapped = Circuit::new(2);
+        swapped.swap(0, 1);
+        assert_eq!(Circuit::new(2).path_sum_equivalent(&swapped), Ok(Some(false)));
+        let mut rotation = Circuit::new(1);
+        rotation.ry(0.3, 0);
+        assert!(rotation.path_sum_equivalent(&rotation).is_err());
+    }
+
+    #[test]
+    fn test_wide_circuit_without_unitary() {
+        // 24 qubits is beyond a dense comparison
+        let n = 24;
+        let (mut ladder, mut via_cz) = (Circuit::new(n), Circuit::new(n));
+        for q in 0..n - 1 {
+            ladder.cx(q, q + 1).t(q + 1);
+            via_cz.h(q + 1).cz(q, q + 1).h(q + 1).t(q + 1);
+        }
+        assert_eq!(ladder.path_sum_equivalent(&via_cz), Ok(Some(true)));
+        let mut shifted = via_cz.clone();
+        shifted.s(17);
+        assert_eq!(ladder.path_sum_equivalent(&shifted), Ok(Some(false)));
+    }
+}
@@ -0,0 +1,100 @@
+use super::gates::{identity_matrix, x_matrix, y_matrix, z_matrix, Gate, Matrix2};
+use super::operator::Operator;
+
+/// single-qubit Pauli operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Pauli {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+impl Pauli {
+    pub const ALL: [Pauli; 4] = [Pauli::I, Pauli::X, Pauli::Y, Pauli::Z];
+
+    pub fn matrix(&self) -> Operator {
+        Operator::from(self.matrix2())
+    }
+
+    /// `matrix` as a 2×2 array
+    pub(crate) fn matrix2(&self) -> Matrix2 {
+        match self {
+            Pauli::I => identity_matrix(),
+            Pauli::X => x_matrix(),
+            Pauli::Y => y_matrix(),
+            Pauli::Z => z_matrix(),
+        }
+    }
+
+    pub fn gate(&self) -> Gate {
+        match self {
+            Pauli::I => Gate::I,
+            Pauli::X => Gate::X,
+            Pauli::Y => Gate::Y,
+            Pauli::Z => Gate::Z,
+        }
+    }
+
+    /// gates rotating this Pauli's eigenbasis onto the computational basis
+    pub fn basis_change(&self) -> &'static [Gate] {
+        match self {
+            Pauli::X => &[Gate::H],
+            Pauli::Y => &[Gate::Sdg, Gate::H],
+            Pauli::I | Pauli::Z => &[],
+        }
+    }
+
+    /// product self·other = i^k · P, returned as (k mod 4, P)
+    pub fn multiply(self, other: Pauli) -> (u8, Pauli) {
+        use Pauli::*;
+        match (self, other) {
+            (I, p) | (p, I) => (0, p),
+            (a, b) if a == b => (0, I),
+            (X, Y) => (1, Z),
+            (Y, Z) => (1, X),
+            (Z, X) => (1, Y),
+            (Y, X) => (3, Z),
+            (Z, Y) => (3, X),
+            (X, Z) => (3, Y),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn from_symbol(symbol: char) -> Option<Pauli> {
+        match symbol {
+            'I' => Some(Pauli::I),
+            'X' => Some(Pauli::X),
+            'Y' => Some(Pauli::Y),
+            'Z' => Some(Pauli::Z),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            Pauli::I => 'I',
+            Pauli::X => 'X',
+            Pauli::Y => 'Y',
+            Pauli::Z => 'Z',
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::register::Register;
+    use crate::simulator::gates::h_matrix;
+
+    #[test]
+    fn test_basis_change_maps_plus_to_zero() {
+        // |+⟩ is the +1 eigenstate of X, so after the basis change it reads |0⟩
+        let mut register = Register::new(1);
+        register.apply_gate(0, h_matrix()).unwrap();
+        for gate in Pauli::X.basis_change() {
+            register.apply_gate(0, gate.matrix().unwrap()).unwrap();
+        }
+        assert!((register.probabilities()[0] - 1.0).abs() < 1e-10);
+    }
+}
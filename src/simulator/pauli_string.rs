@@ -0,0 +1,233 @@
+use num_complex::Complex64;
+use std::fmt;
+use std::ops::Mul;
+use std::str::FromStr;
+use super::circuit::Circuit;
+use super::matrix::Matrix;
+use super::pauli::Pauli;
+
+/// n-qubit Pauli operator i^phase · P_{n-1} ⊗ … ⊗ P_0
+///
+/// `paulis[q]` acts on qubit q. Labels are written most significant qubit
+/// first, like kets: "XZ" is X on qubit 1 and Z on qubit 0.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PauliString {
+    phase: u8,
+    paulis: Vec<Pauli>,
+}
+
+impl PauliString {
+    pub fn new(paulis: Vec<Pauli>) -> Self {
+        Self { phase: 0, paulis }
+    }
+
+    pub fn identity(num_qubits: usize) -> Self {
+        Self::new(vec![Pauli::I; num_qubits])
+    }
+
+    /// `pauli` on `qubit`, identity elsewhere
+    pub fn single(num_qubits: usize, qubit: usize, pauli: Pauli) -> Self {
+        assert!(qubit < num_qubits, "qubit {} out of range", qubit);
+        let mut paulis = vec![Pauli::I; num_qubits];
+        paulis[qubit] = pauli;
+        Self::new(paulis)
+    }
+
+    /// from (qubit, Pauli) pairs on `num_qubits` qubits
+    pub fn from_terms(num_qubits: usize, terms: &[(usize, Pauli)]) -> Self {
+        let mut string = Self::identity(num_qubits);
+        for &(q, p) in terms {
+            string = &string * &Self::single(num_qubits, q, p);
+        }
+        string
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.paulis.len()
+    }
+
+    pub fn paulis(&self) -> &[Pauli] {
+        &self.paulis
+    }
+
+    /// k in the prefactor i^k
+    pub fn phase(&self) -> u8 {
+        self.phase
+    }
+
+    pub fn coefficient(&self) -> Complex64 {
+        Complex64::i().powu(self.phase as u32)
+    }
+
+    pub fn with_phase(mut self, phase: u8) -> Self {
+        self.phase = phase % 4;
+        self
+    }
+
+    /// number of non-identity factors
+    pub fn weight(&self) -> usize {
+        self.paulis.iter().filter(|&&p| p != Pauli::I).count()
+    }
+
+    /// non-identity factors as (qubit, Pauli) pairs
+    pub fn terms(&self) -> Vec<(usize, Pauli)> {
+        self.paulis
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(_, p)| p != Pauli::I)
+            .collect()
+    }
+
+    pub fn commutes_with(&self, other: &PauliString) -> bool {
+        assert_eq!(self.num_qubits(), other.num_qubits(), "qubit count mismatch");
+        let anticommuting = self
+            .paulis
+            .iter()
+            .zip(&other.paulis)
+            .filter(|&(&a, &b)| a != Pauli::I && b != Pauli::I && a != b)
+            .count();
+        anticommuting.is_multiple_of(2)
+    }
+
+    /// self·other when the two anticommute, `None` when they commute; the
+    /// commutator [self, other] is then twice this product
+    pub fn anticommuting_product(&self, other: &PauliString) -> Option<PauliString> {
+        (!self.commutes_with(other)).then(|| self * other)
+    }
+
+    /// Hermitian conjugate; flips ±i prefactors
+    pub fn adjoint(&self) -> PauliString {
+        Self {
+            phase: (4 - self.phase) % 4,
+            paulis: self.paulis.clone(),
+        }
+    }
+
+    pub fn is_hermitian(&self) -> bool {
+        self.phase.is_multiple_of(2)
+    }
+
+    /// dense 2^n × 2^n matrix, prefactor included
+    pub fn matrix(&self) -> Matrix {
+        let mut m = Matrix::identity(1).scaled(self.coefficient());
+        for p in self.paulis.iter().rev() {
+            m = m.kron(&Matrix::from_matrix2(&p.matrix2()));
+        }
+        m
+    }
+
+    /// circuit applying the Pauli factors; the prefactor is a global phase and
+    /// is dropped
+    pub fn circuit(&self) -> Circuit {
+        let mut circuit = Circuit::new(self.num_qubits());
+        for (q, p) in self.terms() {
+            circuit.push(p.gate(), &[q]);
+        }
+        circuit
+    }
+}
+
+impl Mul for &PauliString {
+    type Output = PauliString;
+
+    fn mul(self, rhs: &PauliString) -> PauliString {
+        assert_eq!(self.num_qubits(), rhs.num_qubits(), "qubit count mismatch");
+        let mut phase = self.phase + rhs.phase;
+        let paulis = self
+            .paulis
+            .iter()
+            .zip(&rhs.paulis)
+            .map(|(&a, &b)| {
+                let (k, p) = a.multiply(b);
+                phase += k;
+                p
+            })
+            .collect();
+        PauliString {
+            phase: phase % 4,
+            paulis,
+        }
+    }
+}
+
+impl fmt::Display for PauliString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = ["+", "+i", "-", "-i"][self.phase as usize];
+        let label: String = self.paulis.iter().rev().map(Pauli::symbol).collect();
+        write!(f, "{}{}", prefix, label)
+    }
+}
+
+impl FromStr for PauliString {
+    type Err = String;
+
+    /// optional sign "+", "-", "i", "+i" or "-i" followed by I/X/Y/Z, most
+    /// significant qubit first
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (phase, label) = [("-i", 3), ("+i", 1), ("i", 1), ("-", 2), ("+", 0)]
+            .iter()
+            .find_map(|&(prefix, k)| s.strip_prefix(prefix).map(|rest| (k, rest)))
+            .unwrap_or((0, s));
+        let paulis = label
+            .chars()
+            .rev()
+            .map(|c| Pauli::from_symbol(c).ok_or_else(|| format!("invalid Pauli '{}'", c)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(PauliString::new(paulis).with_phase(phase))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(label: &str) -> PauliString {
+        label.parse().unwrap()
+    }
+
+    #[test]
+    fn test_products_track_phase() {
+        assert_eq!(&p("X") * &p("Y"), p("iZ"));
+        assert_eq!(&p("Y") * &p("X"), p("-iZ"));
+        assert_eq!(&p("XY") * &p("YX"), p("ZZ"));
+        assert_eq!((&p("-iXZ") * &p("-iXZ")).to_string(), "-II");
+    }
+
+    #[test]
+    fn test_commutation() {
+        assert!(p("XX").commutes_with(&p("ZZ")));
+        assert!(!p("XI").commutes_with(&p("ZI")));
+        assert_eq!(p("XI").anticommuting_product(&p("ZI")), Some(p("-iYI")));
+        assert_eq!(p("XX").anticommuting_product(&p("YY")), None);
+        // [XI, ZI] = XI·ZI − ZI·XI = 2·(−iYI)
+        let (x, z) = (p("XI").matrix(), p("ZI").matrix());
+        let commutator = &(&x * &z) + &(&z * &x).scaled(Complex64::new(-1.0, 0.0));
+        let twice = p("XI").anticommuting_product(&p("ZI")).unwrap().matrix();
+        let mut pairs = commutator.as_slice().iter().zip(twice.as_slice());
+        assert!(pairs.all(|(c, t)| (c - 2.0 * t).norm() < 1e-12));
+    }
+
+    #[test]
+    fn test_matrix_matches_product() {
+        let (a, b) = (p("XYZ"), p("-ZZY"));
+        let product = &a.matrix() * &b.matrix();
+        let expected = (&a * &b).matrix();
+        for (x, y) in product.as_slice().iter().zip(expected.as_slice()) {
+            assert!((x - y).norm() < 1e-12);
+        }
+        // qubit 0 is the rightmost label character
+        assert_eq!(p("XZ").paulis(), &[Pauli::Z, Pauli::X]);
+        assert!("XQ".parse::<PauliString>().is_err());
+    }
+
+    #[test]
+    fn test_circuit_applies_string() {
+        use crate::simulator::register::Register;
+        let string = p("XIY");
+        let mut register = Register::new(3);
+        register.apply_circuit(&string.circuit());
+        let expected = string.matrix().apply(Register::new(3).amplitudes());
+        crate::assert_state_eq!(register, Register::from_amplitudes(expected));
+    }
+}
@@ -0,0 +1,222 @@
+use std::fmt;
+use num_complex::Complex64;
+use super::circuit::Circuit;
+use super::decision_diagram::DecisionDiagram;
+use super::extended_stabilizer::{stabilizer_branches, ExtendedStabilizer};
+use super::hybrid::{cut_paths, SchrodingerFeynman};
+use super::register::Register;
+use super::report::statevector_bytes;
+use super::tensor_network::TensorNetwork;
+
+/// widest circuit the planner sends to the dense state vector (256 MiB)
+pub const PLANNER_DENSE_QUBITS: usize = 24;
+
+/// most phase branches before the stabilizer sum is considered too large
+pub const PLANNER_STABILIZER_BRANCHES: usize = 10;
+
+/// largest tensor rank the planner accepts for contraction
+pub const PLANNER_TENSOR_RANK: usize = 20;
+
+/// most Schrödinger–Feynman paths the planner accepts
+pub const PLANNER_PATHS: usize = 1 << 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    StateVector,
+    DecisionDiagram,
+    ExtendedStabilizer,
+    TensorNetwork,
+    /// partitions below and from `cut` simulated densely
+    SchrodingerFeynman { cut: usize },
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backend::StateVector => write!(f, "statevector"),
+            Backend::DecisionDiagram => write!(f, "decision_diagram"),
+            Backend::ExtendedStabilizer => write!(f, "extended_stabilizer"),
+            Backend::TensorNetwork => write!(f, "tensor_network"),
+            Backend::SchrodingerFeynman { cut } => write!(f, "schrodinger_feynman (cut {})", cut),
+        }
+    }
+}
+
+/// chosen backend and the reasoning that led to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackendPlan {
+    pub backend: Backend,
+    pub reasons: Vec<String>,
+}
+
+impl fmt::Display for BackendPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "backend: {}", self.backend)?;
+        for reason in &self.reasons {
+            write!(f, "\n  - {}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// cut with the fewest Feynman paths whose halves both fit densely
+fn best_cut(circuit: &Circuit) -> Option<(usize, usize)> {
+    let n = circuit.num_qubits();
+    (1..n)
+        .filter(|&cut| cut <= PLANNER_DENSE_QUBITS && n - cut <= PLANNER_DENSE_QUBITS)
+        .map(|cut| (cut_paths(circuit, cut), cut))
+        .min()
+        .map(|(paths, cut)| (cut, paths))
+}
+
+/// pick a backend from the circuit's width, stabilizer branch count,
+/// contraction width and best partition, in that order; `forced` overrides
+/// the choice
+pub fn plan_backend(circuit: &Circuit, forced: Option<Backend>) -> BackendPlan {
+    let n = circuit.num_qubits();
+    let mut reasons = Vec::new();
+    if let Some(backend) = forced {
+        reasons.push("forced by the caller".to_string());
+        return BackendPlan { backend, reasons };
+    }
+    if n <= PLANNER_DENSE_QUBITS {
+        reasons.push(format!(
+            "{} qubits fit a dense state of {} bytes",
+            n,
+            statevector_bytes(n)
+        ));
+        return BackendPlan { backend: Backend::StateVector, reasons };
+    }
+    reasons.push(format!("{} qubits exceed the dense limit of {}", n, PLANNER_DENSE_QUBITS));
+
+    match stabilizer_branches(circuit) {
+        Some(branches) if branches <= PLANNER_STABILIZER_BRANCHES && n <= 64 => {
+            reasons.push(format!(
+                "Clifford gates plus {} phase branches give {} stabilizer terms",
+                branches,
+                1usize << branches
+            ));
+            return BackendPlan { backend: Backend::ExtendedStabilizer, reasons };
+        }
+        Some(branches) => reasons.push(format!(
+            "{} non-Clifford phase branches are too many for a stabilizer sum",
+            branches
+        )),
+        None => reasons.push("gates beyond Clifford+phases rule out a stabilizer sum".into()),
+    }
+
+    let width = TensorNetwork::from_circuit(circuit).contraction_width();
+    if width <= PLANNER_TENSOR_RANK {
+        reasons.push(format!("greedy contraction needs tensors of rank {} only", width));
+        return BackendPlan { backend: Backend::TensorNetwork, reasons };
+    }
+    reasons.push(format!("contraction width {} is above {}", width, PLANNER_TENSOR_RANK));
+
+    match best_cut(circuit) {
+        Some((cut, paths)) if paths <= PLANNER_PATHS => {
+            reasons.push(format!("cutting at qubit {} leaves {} Feynman paths", cut, paths));
+            return BackendPlan { backend: Backend::SchrodingerFeynman { cut }, reasons };
+        }
+        Some((cut, paths)) => {
+            reasons.push(format!("the best cut (qubit {}) still has {} paths", cut, paths))
+        }
+        None => reasons.push("no cut leaves two halves of dense size".into()),
+    }
+
+    reasons.push("falling back to a decision diagram, which exploits any structure".into());
+    BackendPlan { backend: Backend::DecisionDiagram, reasons }
+}
+
+impl BackendPlan {
+    /// amplitudes ⟨x|C|0…0⟩ of the listed basis states on the chosen backend
+    pub fn amplitudes(
+        &self,
+        circuit: &Circuit,
+        indices: &[usize],
+    ) -> Result<Vec<Complex64>, String> {
+        match self.backend {
+            Backend::StateVector => {
+                let mut register = Register::new(circuit.num_qubits());
+                register.apply_circuit(circuit);
+                Ok(indices.iter().map(|&x| register.amplitudes()[x]).collect())
+            }
+            Backend::DecisionDiagram => {
+                let mut dd = DecisionDiagram::new(circuit.num_qubits());
+                dd.apply_circuit(circuit);
+                Ok(indices.iter().map(|&x| dd.amplitude(x)).collect())
+            }
+            Backend::ExtendedStabilizer => {
+                let state = ExtendedStabilizer::from_circuit(circuit)?;
+                Ok(indices.iter().map(|&x| state.amplitude(x as u64)).collect())
+            }
+            Backend::TensorNetwork => {
+                let network = TensorNetwork::from_circuit(circuit);
+                let n = circuit.num_qubits();
+                indices
+                    .iter()
+                    .map(|&x| {
+                        let bits: Vec<bool> = (0..n).map(|q| x >> q & 1 == 1).collect();
+                        network.amplitude(&bits)
+                    })
+                    .collect()
+            }
+            Backend::SchrodingerFeynman { cut } => {
+                Ok(SchrodingerFeynman::new(circuit, cut)?.amplitudes(indices))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn test_small_circuits_run_densely_unless_forced() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).cx(0, 1).t(1).ry(0.3, 2);
+        let plan = plan_backend(&circuit, None);
+        assert_eq!(plan.backend, Backend::StateVector);
+        let forced = plan_backend(&circuit, Some(Backend::DecisionDiagram));
+        assert!(forced.to_string().contains("forced by the caller"));
+        let (a, b) = (plan.amplitudes(&circuit, &[3]), forced.amplitudes(&circuit, &[3]));
+        assert!((a.unwrap()[0] - b.unwrap()[0]).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_wide_clifford_goes_to_stabilizer() {
+        let n = 40;
+        let mut circuit = Circuit::new(n);
+        circuit.h(0);
+        for q in 0..n - 1 {
+            circuit.cx(q, q + 1);
+        }
+        circuit.s(7);
+        let plan = plan_backend(&circuit, None);
+        assert_eq!(plan.backend, Backend::ExtendedStabilizer);
+        let amplitudes = plan.amplitudes(&circuit, &[(1 << n) - 1]).unwrap();
+        assert!((amplitudes[0] - Complex64::new(0.0, FRAC_1_SQRT_2)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_shallow_rotations_go_to_tensor_network() {
+        let n = 30;
+        let mut circuit = Circuit::new(n);
+        for q in 0..n {
+            circuit.ry(0.1 * q as f64 + 0.05, q);
+        }
+        for q in (0..n - 1).step_by(2) {
+            circuit.cx(q, q + 1);
+        }
+        let plan = plan_backend(&circuit, None);
+        assert_eq!(plan.backend, Backend::TensorNetwork, "{}", plan);
+        assert!(plan.reasons.iter().any(|r| r.contains("phase branches")));
+        let amplitude = plan.amplitudes(&circuit, &[0]).unwrap()[0];
+        let expected: f64 = (0..n)
+            .step_by(2)
+            .map(|q| ((0.1 * q as f64 + 0.05) / 2.0).cos() * ((0.1 * q as f64 + 0.15) / 2.0).cos())
+            .product();
+        assert!((amplitude.re - expected).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt::Debug;
+use super::circuit::Circuit;
+use super::gates::Gate;
+use super::matrix::Matrix;
+use super::random::random_unitary;
+use super::register::Register;
+use super::rng::Rng;
+
+/// generator of random test inputs
+///
+/// Closures `Fn(&mut Rng) -> T` are strategies too, so ad-hoc inputs need no
+/// new type; they do not shrink.
+pub trait Strategy {
+    type Value: Debug;
+    fn generate(&self, rng: &mut Rng) -> Self::Value;
+
+    /// simpler variants of `value` to try when it fails a property, most
+    /// promising first; none by default
+    fn shrink(&self, _value: &Self::Value) -> Vec<Self::Value> {
+        Vec::new()
+    }
+}
+
+impl<T: Debug, F: Fn(&mut Rng) -> T> Strategy for F {
+    type Value = T;
+
+    fn generate(&self, rng: &mut Rng) -> T {
+        self(rng)
+    }
+}
+
+/// Haar-random n-qubit registers
+#[derive(Debug, Clone, Copy)]
+pub struct States {
+    pub num_qubits: usize,
+}
+
+/// Haar-random n-qubit unitaries (QR of a complex Ginibre matrix)
+#[derive(Debug, Clone, Copy)]
+pub struct Unitaries {
+    pub num_qubits: usize,
+}
+
+/// random circuits over {H, S, S†, X, Y, Z, CX, CZ, SWAP}
+#[derive(Debug, Clone, Copy)]
+pub struct CliffordCircuits {
+    pub num_qubits: usize,
+    pub num_gates: usize,
+}
+
+pub fn states(num_qubits: usize) -> States {
+    States { num_qubits }
+}
+
+pub fn unitaries(num_qubits: usize) -> Unitaries {
+    Unitaries { num_qubits }
+}
+
+pub fn clifford_circuits(num_qubits: usize, num_gates: usize) -> CliffordCircuits {
+    CliffordCircuits { num_qubits, num_gates }
+}
+
+impl Strategy for States {
+    type Value = Register;
+
+    fn generate(&self, rng: &mut Rng) -> Register {
+        Register::haar_random(self.num_qubits, rng)
+    }
+}
+
+impl Strategy for Unitaries {
+    type Value = Matrix;
+
+    fn generate(&self, rng: &mut Rng) -> Matrix {
+        random_unitary(self.num_qubits, rng)
+    }
+}
+
+impl Strategy for CliffordCircuits {
+    type Value = Circuit;
+
+    fn generate(&self, rng: &mut Rng) -> Circuit {
+        const SINGLE: [Gate; 6] = [Gate::H, Gate::S, Gate::Sdg, Gate::X, Gate::Y, Gate::Z];
+        const DOUBLE: [Gate; 3] = [Gate::Cx, Gate::Cz, Gate::Swap];
+        let mut circuit = Circuit::new(self.num_qubits);
+        for _ in 0..self.num_gates {
+            if self.num_qubits >= 2 && rng.gen_bool(0.3) {
+                let gate = DOUBLE[rng.gen_range(DOUBLE.len())].clone();
+                let a = rng.gen_range(self.num_qubits);
+                let b = (a + 1 + rng.gen_range(self.num_qubits - 1)) % self.num_qubits;
+                circuit.push(gate, &[a, b]);
+            } else {
+                let gate = SINGLE[rng.gen_range(SINGLE.len())].clone();
+                circuit.push(gate, &[rng.gen_range(self.num_qubits)]);
+            }
+        }
+        circuit
+    }
+
+    /// the circuit with one qubit removed (its gates dropped, higher qubits
+    /// moved down), then with one gate dropped
+    fn shrink(&self, circuit: &Circuit) -> Vec<Circuit> {
+        let instructions = circuit.instructions();
+        let narrower = (0..circuit.num_qubits()).map(|removed| {
+            let mut smaller = Circuit::new(circuit.num_qubits() - 1);
+            for inst in instructions.iter().filter(|inst| !inst.qubits.contains(&removed)) {
+                let qubits: Vec<usize> =
+                    inst.qubits.iter().map(|&q| if q > removed { q - 1 } else { q }).collect();
+                smaller.push(inst.gate.clone(), &qubits);
+            }
+            smaller
+        });
+        let shorter = (0..instructions.len()).map(|dropped| {
+            let mut smaller = Circuit::new(circuit.num_qubits());
+            for (k, inst) in instructions.iter().enumerate() {
+                if k != dropped {
+                    smaller.push(inst.gate.clone(), &inst.qubits);
+                }
+            }
+            smaller
+        });
+        narrower.chain(shorter).collect()
+    }
+}
+
+/// upper bound on accepted shrinking steps, so a strategy whose candidates
+/// never get simpler cannot loop forever
+const MAX_SHRINK_STEPS: usize = 1000;
+
+/// greedily replace a failing `value` by its first failing shrink until none
+/// fails; returns the simplest failure and the steps taken
+fn shrink_failure<S: Strategy>(
+    strategy: &S,
+    mut value: S::Value,
+    property: &impl Fn(&S::Value) -> bool,
+) -> (S::Value, usize) {
+    let mut steps = 0;
+    while steps < MAX_SHRINK_STEPS {
+        match strategy.shrink(&value).into_iter().find(|candidate| !property(candidate)) {
+            Some(simpler) => {
+                value = simpler;
+                steps += 1;
+            }
+            None => break,
+        }
+    }
+    (value, steps)
+}
+
+/// run `property` on `cases` generated inputs; case k uses seed `seed + k`
+///
+/// Panics with the failing case and its seed so it can be replayed, showing
+/// the failure after shrinking it as far as the strategy allows.
+pub fn check<S: Strategy>(
+    cases: usize,
+    seed: u64,
+    strategy: &S,
+    property: impl Fn(&S::Value) -> bool,
+) {
+    for case in 0..cases {
+        let case_seed = seed.wrapping_add(case as u64);
+        let mut rng = Rng::seed_from_u64(case_seed);
+        let value = strategy.generate(&mut rng);
+        if !property(&value) {
+            let (value, steps) = shrink_failure(strategy, value, &property);
+            panic!(
+                "property failed on case {} (seed {}), shrunk in {} steps:\n{:#?}",
+                case, case_seed, steps, value
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_unitaries_are_unitary() {
+        check(20, 0, &unitaries(2), |u| u.is_unitary(1e-10));
+    }
+
+    #[test]
+    fn test_clifford_circuits_preserve_norm() {
+        let strategy = |rng: &mut Rng| {
+            let state = states(3).generate(rng);
+            (state, clifford_circuits(3, 20).generate(rng))
+        };
+        check(20, 100, &strategy, |(state, circuit)| {
+            let mut evolved = state.clone();
+            evolved.apply_circuit(circuit);
+            let norm: f64 = evolved.probabilities().iter().sum();
+            circuit.is_clifford() && (norm - 1.0).abs() < 1e-10
+        });
+    }
+
+    #[test]
+    fn test_failing_circuits_shrink_to_a_minimal_case() {
+        let strategy = clifford_circuits(4, 30);
+        let has_cz = |c: &Circuit| c.instructions().iter().any(|inst| inst.gate == Gate::Cz);
+        let (seed, failing) = (0..)
+            .map(|seed| (seed, strategy.generate(&mut Rng::seed_from_u64(seed))))
+            .find(|(_, c)| has_cz(c))
+            .unwrap();
+        let (shrunk, steps) = shrink_failure(&strategy, failing, &|c: &Circuit| !has_cz(c));
+        assert!(steps > 0, "seed {}", seed);
+        assert_eq!(shrunk.num_qubits(), 2);
+        assert_eq!(shrunk.instructions().len(), 1);
+        assert_eq!(shrunk.instructions()[0].gate, Gate::Cz);
+    }
+
+    #[test]
+    #[should_panic(expected = "shrunk in 2 steps")]
+    fn test_failure_reports_shrunk_case() {
+        // three idle qubits shrink to the narrowest failing width, one
+        check(1, 0, &clifford_circuits(3, 0), |c| c.num_qubits() == 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "property failed on case 0 (seed 5)")]
+    fn test_failure_reports_seed() {
+        check(3, 5, &states(1), |_| false);
+    }
+}
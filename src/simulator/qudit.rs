@@ -0,0 +1,287 @@
+use num_complex::Complex64;
+use std::f64::consts::PI;
+use super::matrix::Matrix;
+use super::rng::Rng;
+use super::testing::approx_eq_up_to_phase;
+
+/// generalized Pauli X: |k⟩ → |k+1 mod d⟩
+pub fn qudit_x(dim: usize) -> Matrix {
+    Matrix::from_fn(dim, dim, |i, j| {
+        Complex64::new(if i == (j + 1) % dim { 1.0 } else { 0.0 }, 0.0)
+    })
+}
+
+/// generalized Pauli Z (clock): |k⟩ → ω^k|k⟩ with ω = e^{2πi/d}
+pub fn qudit_z(dim: usize) -> Matrix {
+    Matrix::from_fn(dim, dim, |i, j| {
+        if i == j {
+            Complex64::from_polar(1.0, 2.0 * PI * i as f64 / dim as f64)
+        } else {
+            Complex64::new(0.0, 0.0)
+        }
+    })
+}
+
+/// quantum Fourier transform F_{jk} = ω^{jk}/√d, the qudit Hadamard
+pub fn qudit_fourier(dim: usize) -> Matrix {
+    let scale = 1.0 / (dim as f64).sqrt();
+    Matrix::from_fn(dim, dim, |j, k| {
+        Complex64::from_polar(scale, 2.0 * PI * (j * k % dim) as f64 / dim as f64)
+    })
+}
+
+/// state of n d-level systems: Σ c_k |k⟩
+///
+/// Qudit 0 is the least significant base-d digit of the basis index.
+#[derive(Debug, Clone)]
+pub struct QuditState {
+    dim: usize,
+    num_qudits: usize,
+    amplitudes: Vec<Complex64>,
+}
+
+impl QuditState {
+    /// |0…0⟩
+    pub fn new(dim: usize, num_qudits: usize) -> Self {
+        assert!(dim >= 2, "qudit dimension must be at least 2");
+        let mut amplitudes = vec![Complex64::new(0.0, 0.0); dim.pow(num_qudits as u32)];
+        amplitudes[0] = Complex64::new(1.0, 0.0);
+        Self {
+            dim,
+            num_qudits,
+            amplitudes,
+        }
+    }
+
+    /// will normalize; length must be a power of `dim`
+    pub fn from_amplitudes(dim: usize, amplitudes: Vec<Complex64>) -> Self {
+        assert!(dim >= 2, "qudit dimension must be at least 2");
+        let mut num_qudits = 0;
+        let mut size = 1;
+        while size < amplitudes.len() {
+            size *= dim;
+            num_qudits += 1;
+        }
+        assert_eq!(size, amplitudes.len(), "amplitude count must be a power of {}", dim);
+        let mut state = Self {
+            dim,
+            num_qudits,
+            amplitudes,
+        };
+        state.normalize();
+        state
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    pub fn num_qudits(&self) -> usize {
+        self.num_qudits
+    }
+
+    pub fn amplitudes(&self) -> &[Complex64] {
+        &self.amplitudes
+    }
+
+    pub fn normalize(&mut self) {
+        let norm = self.amplitudes.iter().map(|a| a.norm_sqr()).sum::<f64>().sqrt();
+        if norm > 1e-10 {
+            for a in self.amplitudes.iter_mut() {
+                *a /= norm;
+            }
+        }
+    }
+
+    /// equal up to global phase
+    pub fn approx_eq(&self, other: &Self, tolerance: f64) -> bool {
+        self.dim == other.dim
+            && approx_eq_up_to_phase(&self.amplitudes, &other.amplitudes, tolerance)
+    }
+
+    fn stride(&self, qudit: usize) -> usize {
+        assert!(qudit < self.num_qudits, "qudit {} out of range", qudit);
+        self.dim.pow(qudit as u32)
+    }
+
+    /// value of `qudit`'s digit in basis index `k`
+    fn digit(&self, k: usize, qudit: usize) -> usize {
+        k / self.stride(qudit) % self.dim
+    }
+
+    /// apply a d×d matrix to one qudit
+    pub fn apply_gate(&mut self, qudit: usize, matrix: &Matrix) {
+        let d = self.dim;
+        assert!(matrix.rows() == d && matrix.cols() == d, "gate must be {}×{}", d, d);
+        let stride = self.stride(qudit);
+        for base in (0..self.amplitudes.len()).filter(|&k| (k / stride).is_multiple_of(d)) {
+            let local: Vec<Complex64> =
+                (0..d).map(|j| self.amplitudes[base + j * stride]).collect();
+            for (j, value) in matrix.apply(&local).into_iter().enumerate() {
+                self.amplitudes[base + j * stride] = value;
+            }
+        }
+    }
+
+    /// apply a d^k × d^k operator to `qudits` without renormalizing;
+    /// `qudits[0]` is the least significant digit of the operator's index
+    pub fn apply_operator(&mut self, qudits: &[usize], operator: &Matrix) {
+        let d = self.dim;
+        let size = d.pow(qudits.len() as u32);
+        assert!(operator.rows() == size && operator.cols() == size, "operator size mismatch");
+        for (k, &q) in qudits.iter().enumerate() {
+            assert!(!qudits[..k].contains(&q), "repeated qudit {}", q);
+        }
+        let strides: Vec<usize> = qudits.iter().map(|&q| self.stride(q)).collect();
+        let offsets: Vec<usize> = (0..size)
+            .map(|local| {
+                strides
+                    .iter()
+                    .enumerate()
+                    .map(|(b, s)| local / d.pow(b as u32) % d * s)
+                    .sum()
+            })
+            .collect();
+        let bases: Vec<usize> = (0..self.amplitudes.len())
+            .filter(|&k| strides.iter().all(|&s| (k / s).is_multiple_of(d)))
+            .collect();
+        for base in bases {
+            let local: Vec<Complex64> =
+                offsets.iter().map(|&o| self.amplitudes[base + o]).collect();
+            for (&o, value) in offsets.iter().zip(operator.apply(&local)) {
+                self.amplitudes[base + o] = value;
+            }
+        }
+    }
+
+    /// generalized CNOT: |a⟩|b⟩ → |a⟩|b + a mod d⟩
+    pub fn apply_sum(&mut self, control: usize, target: usize) {
+        assert_ne!(control, target, "control and target must differ");
+        let stride = self.stride(target);
+        let mut amplitudes = vec![Complex64::new(0.0, 0.0); self.amplitudes.len()];
+        for (k, &a) in self.amplitudes.iter().enumerate() {
+            let (c, t) = (self.digit(k, control), self.digit(k, target));
+            let shifted = (t + c) % self.dim;
+            amplitudes[k - t * stride + shifted * stride] = a;
+        }
+        self.amplitudes = amplitudes;
+    }
+
+    /// probability of reading `level` on `qudit`
+    pub fn prob_level(&self, qudit: usize, level: usize) -> f64 {
+        self.amplitudes
+            .iter()
+            .enumerate()
+            .filter(|&(k, _)| self.digit(k, qudit) == level)
+            .map(|(_, a)| a.norm_sqr())
+            .sum()
+    }
+
+    pub fn probabilities(&self) -> Vec<f64> {
+        self.amplitudes.iter().map(|a| a.norm_sqr()).collect()
+    }
+
+    /// projective measurement of one qudit; the state collapses
+    pub fn measure(&mut self, qudit: usize, rng: &mut Rng) -> usize {
+        let mut r = rng.next_f64();
+        let mut outcome = self.dim - 1;
+        for level in 0..self.dim {
+            let p = self.prob_level(qudit, level);
+            if r < p {
+                outcome = level;
+                break;
+            }
+            r -= p;
+        }
+        for k in 0..self.amplitudes.len() {
+            if self.digit(k, qudit) != outcome {
+                self.amplitudes[k] = Complex64::new(0.0, 0.0);
+            }
+        }
+        self.normalize();
+        outcome
+    }
+
+    /// state in ket notation, most significant qudit first
+    pub fn display(&self) {
+        let terms: Vec<String> = self
+            .amplitudes
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.norm_sqr() > 1e-10)
+            .map(|(k, a)| {
+                let digits: Vec<String> = (0..self.num_qudits)
+                    .rev()
+                    .map(|q| self.digit(k, q).to_string())
+                    .collect();
+                format!("{:.3}|{}⟩", a, digits.join(","))
+            })
+            .collect();
+        println!("State: {}", terms.join(" + "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weyl_relations() {
+        for d in [3, 4, 5] {
+            let (x, z, f) = (qudit_x(d), qudit_z(d), qudit_fourier(d));
+            assert!(f.is_unitary(1e-12));
+            // ZX = ω XZ
+            let omega = Complex64::from_polar(1.0, 2.0 * PI / d as f64);
+            let lhs = &z * &x;
+            let rhs = (&x * &z).scaled(omega);
+            // F X F† = Z
+            let conjugated = &(&f * &x) * &f.dagger();
+            for ((a, b), (c, e)) in lhs
+                .as_slice()
+                .iter()
+                .zip(rhs.as_slice())
+                .zip(conjugated.as_slice().iter().zip(z.as_slice()))
+            {
+                assert!((a - b).norm() < 1e-12 && (c - e).norm() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_qutrit_bell_state_correlations() {
+        let mut rng = Rng::seed_from_u64(25);
+        for _ in 0..20 {
+            let mut state = QuditState::new(3, 2);
+            state.apply_gate(0, &qudit_fourier(3));
+            state.apply_sum(0, 1);
+            assert!((state.prob_level(1, 2) - 1.0 / 3.0).abs() < 1e-12);
+            let first = state.measure(0, &mut rng);
+            assert_eq!(state.measure(1, &mut rng), first);
+        }
+    }
+
+    #[test]
+    fn test_two_qudit_operator_matches_sum() {
+        // SUM as a 9×9 permutation, control is the least significant digit
+        let sum = Matrix::from_fn(9, 9, |i, j| {
+            let (c, t) = (j % 3, j / 3);
+            Complex64::new(if i == c + 3 * ((t + c) % 3) { 1.0 } else { 0.0 }, 0.0)
+        });
+        let mut expected = QuditState::new(3, 3);
+        expected.apply_gate(2, &qudit_fourier(3));
+        let mut actual = expected.clone();
+        expected.apply_sum(2, 0);
+        actual.apply_operator(&[2, 0], &sum);
+        assert!(actual.approx_eq(&expected, 1e-12));
+    }
+
+    #[test]
+    fn test_shift_cycles() {
+        let mut state = QuditState::new(3, 1);
+        state.apply_gate(0, &qudit_x(3));
+        assert!((state.probabilities()[1] - 1.0).abs() < 1e-12);
+        state.apply_gate(0, &qudit_x(3));
+        state.apply_gate(0, &qudit_x(3));
+        assert!(state.approx_eq(&QuditState::new(3, 1), 1e-12));
+    }
+}
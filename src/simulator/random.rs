@@ -0,0 +1,76 @@
+use num_complex::Complex64;
+use super::circuit::Circuit;
+use super::matrix::Matrix;
+use super::register::Register;
+use super::rng::Rng;
+use super::single_qubit::SingleQubit;
+use super::tableau::CliffordTableau;
+
+/// standard complex Gaussian sample
+pub fn complex_gaussian(rng: &mut Rng) -> Complex64 {
+    Complex64::new(rng.normal(), rng.normal())
+}
+
+/// Haar-random n-qubit unitary (QR of a complex Ginibre matrix)
+pub fn random_unitary(num_qubits: usize, rng: &mut Rng) -> Matrix {
+    let dim = 1 << num_qubits;
+    let ginibre = Matrix::from_fn(dim, dim, |_, _| complex_gaussian(rng));
+    // Gram–Schmidt gives R a positive diagonal, so Q is Haar distributed
+    ginibre.qr().0
+}
+
+/// uniformly random n-qubit Clifford as a circuit of H, S, CX and SWAP
+pub fn random_clifford(num_qubits: usize, rng: &mut Rng) -> Circuit {
+    CliffordTableau::random(num_qubits, rng).to_circuit()
+}
+
+impl SingleQubit {
+    /// Haar-random state (uniform on the Bloch sphere)
+    pub fn random(rng: &mut Rng) -> Self {
+        Self::from_amplitudes(complex_gaussian(rng), complex_gaussian(rng))
+    }
+}
+
+impl Register {
+    /// Haar-random n-qubit state
+    pub fn haar_random(num_qubits: usize, rng: &mut Rng) -> Self {
+        let amplitudes = (0..1usize << num_qubits)
+            .map(|_| complex_gaussian(rng))
+            .collect();
+        Self::from_amplitudes(amplitudes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_qubit_covers_bloch_sphere() {
+        // ⟨Z⟩ is uniform on [-1, 1] for Haar states, so its mean is ~0
+        let mut rng = Rng::seed_from_u64(11);
+        let n = 4000;
+        let mean_z: f64 = (0..n)
+            .map(|_| {
+                let q = SingleQubit::random(&mut rng);
+                q.prob_zero() - q.prob_one()
+            })
+            .sum::<f64>()
+            / n as f64;
+        assert!(mean_z.abs() < 0.05);
+    }
+
+    #[test]
+    fn test_haar_state_is_normalized() {
+        let mut rng = Rng::seed_from_u64(3);
+        let register = Register::haar_random(4, &mut rng);
+        let total: f64 = register.probabilities().iter().sum();
+        assert!((total - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_random_unitary_is_unitary() {
+        let mut rng = Rng::seed_from_u64(5);
+        assert!(random_unitary(3, &mut rng).is_unitary(1e-10));
+    }
+}
@@ -0,0 +1,584 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use num_complex::Complex64;
+use super::circuit::{Circuit, Instruction};
+use super::distribution::Distribution;
+use super::gates::{x_matrix, z_matrix, Gate, Matrix2};
+use super::operator::Operator;
+use super::rng::Rng;
+use super::testing::approx_eq_up_to_phase;
+use crate::trace;
+
+/// outcome ranked by probability, then by lower index
+#[derive(PartialEq)]
+struct Ranked(f64, usize);
+
+impl Eq for Ranked {}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(other.1.cmp(&self.1))
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// n-qubit state vector: Σ c_k |k⟩
+///
+/// Qubit 0 is the least significant bit of the basis index; kets are printed
+/// most significant qubit first (|q_{n-1} … q_0⟩).
+#[derive(Debug, Clone)]
+pub struct Register {
+    num_qubits: usize,
+    amplitudes: Vec<Complex64>,
+    /// gates applied since recording started, `None` when not recording
+    history: Option<Vec<Instruction>>,
+    /// undone instructions, most recently undone last
+    redo: Vec<Instruction>,
+}
+
+impl Register {
+    /// |0…0⟩
+    pub fn new(num_qubits: usize) -> Self {
+        let mut amplitudes = vec![Complex64::new(0.0, 0.0); 1 << num_qubits];
+        amplitudes[0] = Complex64::new(1.0, 0.0);
+        Self {
+            num_qubits,
+            amplitudes,
+            history: None,
+            redo: Vec::new(),
+        }
+    }
+
+    /// will normalize; length must be a power of two
+    pub fn from_amplitudes(amplitudes: Vec<Complex64>) -> Self {
+        assert!(
+            amplitudes.len().is_power_of_two(),
+            "amplitude count must be a power of two"
+        );
+        let num_qubits = amplitudes.len().trailing_zeros() as usize;
+        let mut register = Self {
+            num_qubits,
+            amplitudes,
+            history: None,
+            redo: Vec::new(),
+        };
+        register.normalize();
+        register
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn amplitudes(&self) -> &[Complex64] {
+        &self.amplitudes
+    }
+
+    /// replace the amplitudes as-is, without normalizing
+    pub(crate) fn set_amplitudes(&mut self, amplitudes: Vec<Complex64>) {
+        assert_eq!(amplitudes.len(), self.amplitudes.len(), "amplitude count mismatch");
+        self.amplitudes = amplitudes;
+        self.forget_history();
+    }
+
+    /// ensure Σ |c_k|² = 1; clears the undo history like a measurement
+    pub fn normalize(&mut self) {
+        self.forget_history();
+        let norm = self
+            .amplitudes
+            .iter()
+            .map(|a| a.norm_sqr())
+            .sum::<f64>()
+            .sqrt();
+        if norm > 1e-10 {
+            for a in self.amplitudes.iter_mut() {
+                *a /= norm;
+            }
+        }
+    }
+
+    /// joint state with `self` on the low qubits and `other` above them
+    pub fn tensor(&self, other: &Register) -> Register {
+        let low = self.amplitudes.len();
+        let amplitudes = (0..low * other.amplitudes.len())
+            .map(|k| self.amplitudes[k % low] * other.amplitudes[k / low])
+            .collect();
+        Register {
+            num_qubits: self.num_qubits + other.num_qubits,
+            amplitudes,
+            history: None,
+            redo: Vec::new(),
+        }
+    }
+
+    /// ⟨self|other⟩
+    pub fn inner(&self, other: &Register) -> Complex64 {
+        assert_eq!(self.num_qubits, other.num_qubits, "qubit count mismatch");
+        self.amplitudes
+            .iter()
+            .zip(&other.amplitudes)
+            .map(|(a, b)| a.conj() * b)
+            .sum()
+    }
+
+    /// equal up to global phase
+    pub fn approx_eq(&self, other: &Self, tolerance: f64) -> bool {
+        self.num_qubits == other.num_qubits
+            && approx_eq_up_to_phase(&self.amplitudes, &other.amplitudes, tolerance)
+    }
+
+    /// apply a one-qubit operator to `target`, recorded as a custom
+    /// `Mcu(0, operator)`; wider operators are rejected and change nothing
+    pub fn apply_gate(
+        &mut self,
+        target: usize,
+        operator: impl Into<Operator>,
+    ) -> Result<(), String> {
+        self.apply_controlled_gate(&[], target, operator)
+    }
+
+    /// apply a one-qubit operator to `target` on the subspace where every
+    /// control is |1⟩, recorded as a custom `Mcu`; wider operators are
+    /// rejected and change nothing
+    pub fn apply_controlled_gate(
+        &mut self,
+        controls: &[usize],
+        target: usize,
+        operator: impl Into<Operator>,
+    ) -> Result<(), String> {
+        let operator = operator.into();
+        let matrix = operator.one_qubit_matrix2()?;
+        let qubits: Vec<usize> = controls.iter().chain([&target]).copied().collect();
+        self.record(Gate::Mcu(controls.len(), operator), &qubits);
+        self.controlled_kernel(controls, target, matrix);
+        Ok(())
+    }
+
+    /// `apply_gate` with a 2×2 array
+    pub(crate) fn apply_matrix2(&mut self, target: usize, matrix: Matrix2) {
+        self.apply_controlled_matrix2(&[], target, matrix);
+    }
+
+    /// `apply_controlled_gate` with a 2×2 array
+    pub(crate) fn apply_controlled_matrix2(
+        &mut self,
+        controls: &[usize],
+        target: usize,
+        matrix: Matrix2,
+    ) {
+        let qubits: Vec<usize> = controls.iter().chain([&target]).copied().collect();
+        self.record(Gate::Mcu(controls.len(), matrix.into()), &qubits);
+        self.controlled_kernel(controls, target, matrix);
+    }
+
+    fn controlled_kernel(&mut self, controls: &[usize], target: usize, matrix: Matrix2) {
+        let _span = trace::span("kernel", || {
+            format!("2x2 target {} controls {:?}", target, controls)
+        });
+        assert!(target < self.num_qubits, "target qubit out of range");
+        let control_mask = controls.iter().fold(0usize, |mask, &c| {
+            assert!(c < self.num_qubits && c != target, "invalid control qubit");
+            mask | (1 << c)
+        });
+        let bit = 1 << target;
+        for i in 0..self.amplitudes.len() {
+            if i & bit != 0 || i & control_mask != control_mask {
+                continue;
+            }
+            let j = i | bit;
+            let a0 = self.amplitudes[i];
+            let a1 = self.amplitudes[j];
+            self.amplitudes[i] = matrix[0][0] * a0 + matrix[0][1] * a1;
+            self.amplitudes[j] = matrix[1][0] * a0 + matrix[1][1] * a1;
+        }
+    }
+
+    /// exchange two qubits
+    pub fn apply_swap(&mut self, a: usize, b: usize) {
+        self.record(Gate::Swap, &[a, b]);
+        self.swap_kernel(a, b);
+    }
+
+    fn swap_kernel(&mut self, a: usize, b: usize) {
+        let _span = trace::span("kernel", || format!("swap {} {}", a, b));
+        assert!(a < self.num_qubits && b < self.num_qubits, "qubit out of range");
+        let (bit_a, bit_b) = (1 << a, 1 << b);
+        for i in 0..self.amplitudes.len() {
+            // visit each |…1_a…0_b…⟩ ↔ |…0_a…1_b…⟩ pair once
+            if i & bit_a != 0 && i & bit_b == 0 {
+                self.amplitudes.swap(i, i ^ bit_a ^ bit_b);
+            }
+        }
+    }
+
+    pub fn apply_instruction(&mut self, instruction: &Instruction) {
+        let _span = trace::span("gate", || {
+            format!("{} {:?}", instruction.gate.name(), instruction.qubits)
+        });
+        self.record(instruction.gate.clone(), &instruction.qubits);
+        self.apply_unrecorded(instruction);
+    }
+
+    fn apply_unrecorded(&mut self, instruction: &Instruction) {
+        let q = &instruction.qubits;
+        match instruction.gate {
+            Gate::Cx => self.controlled_kernel(&[q[0]], q[1], x_matrix()),
+            Gate::Cz => self.controlled_kernel(&[q[0]], q[1], z_matrix()),
+            Gate::Swap => self.swap_kernel(q[0], q[1]),
+            Gate::Mcx(n) => self.controlled_kernel(&q[..n], q[n], x_matrix()),
+            Gate::Mcz(n) => self.controlled_kernel(&q[..n], q[n], z_matrix()),
+            Gate::Mcu(n, ref operator) => {
+                let matrix = operator.to_matrix2().expect("one-qubit operator");
+                self.controlled_kernel(&q[..n], q[n], matrix);
+            }
+            ref gate => {
+                let matrix = gate.matrix2().expect("single-qubit gate has a matrix");
+                self.controlled_kernel(&[], q[0], matrix);
+            }
+        }
+    }
+
+    fn record(&mut self, gate: Gate, qubits: &[usize]) {
+        if let Some(history) = &mut self.history {
+            history.push(Instruction {
+                gate,
+                qubits: qubits.to_vec(),
+            });
+            self.redo.clear();
+        }
+    }
+
+    /// drop the history and redo stack once the state changed non-unitarily
+    fn forget_history(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+        self.redo.clear();
+    }
+
+    /// record every gate applied from now on, keeping any earlier history
+    ///
+    /// Only unitary gates are recorded. Measurements, postselection,
+    /// normalization and direct amplitude edits cannot be undone, so they
+    /// empty the history and the redo stack; recording carries on after them.
+    pub fn start_recording(&mut self) {
+        self.history.get_or_insert_with(Vec::new);
+    }
+
+    /// stop recording and hand back the history; nothing is left to redo
+    pub fn stop_recording(&mut self) -> Vec<Instruction> {
+        self.redo.clear();
+        self.history.take().unwrap_or_default()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.history.is_some()
+    }
+
+    /// instructions applied while recording, in order; empty when not recording
+    pub fn history(&self) -> &[Instruction] {
+        self.history.as_deref().unwrap_or(&[])
+    }
+
+    /// revert the last recorded instruction by applying its inverse; `None`
+    /// when nothing is recorded
+    pub fn undo(&mut self) -> Option<Instruction> {
+        let instruction = self.history.as_mut()?.pop()?;
+        self.apply_unrecorded(&Instruction {
+            gate: instruction.gate.inverse(),
+            qubits: instruction.qubits.clone(),
+        });
+        self.redo.push(instruction.clone());
+        Some(instruction)
+    }
+
+    /// re-apply the last undone instruction; any newly applied gate clears
+    /// the redo stack
+    pub fn redo(&mut self) -> Option<Instruction> {
+        let instruction = self.redo.pop()?;
+        self.apply_unrecorded(&instruction);
+        self.history.get_or_insert_with(Vec::new).push(instruction.clone());
+        Some(instruction)
+    }
+
+    /// the history as a circuit over the whole register, to replay, invert or
+    /// export
+    pub fn history_circuit(&self) -> Circuit {
+        let mut circuit = Circuit::new(self.num_qubits);
+        for instruction in self.history() {
+            circuit.push(instruction.gate.clone(), &instruction.qubits);
+        }
+        circuit
+    }
+
+    /// run every instruction in order
+    pub fn apply_circuit(&mut self, circuit: &Circuit) {
+        assert!(circuit.num_qubits() <= self.num_qubits, "circuit is wider than register");
+        let _span = trace::span("circuit", || {
+            format!("{} qubits, {} gates", circuit.num_qubits(), circuit.len())
+        });
+        for instruction in circuit.instructions() {
+            self.apply_instruction(instruction);
+        }
+    }
+
+    /// project `qubit` onto `outcome` and renormalize; returns the probability
+    /// of that outcome (the state is left unnormalized if it was zero)
+    pub fn postselect(&mut self, qubit: usize, outcome: bool) -> f64 {
+        assert!(qubit < self.num_qubits, "qubit out of range");
+        let bit = 1 << qubit;
+        let mut probability = 0.0;
+        for (i, a) in self.amplitudes.iter_mut().enumerate() {
+            if (i & bit != 0) == outcome {
+                probability += a.norm_sqr();
+            } else {
+                *a = Complex64::new(0.0, 0.0);
+            }
+        }
+        self.normalize();
+        probability
+    }
+
+    /// probability of reading |1⟩ on `qubit`
+    pub fn prob_one(&self, qubit: usize) -> f64 {
+        let bit = 1 << qubit;
+        self.amplitudes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i & bit != 0)
+            .map(|(_, a)| a.norm_sqr())
+            .sum()
+    }
+
+    /// projective Z measurement of one qubit; the state collapses
+    pub fn measure(&mut self, qubit: usize, rng: &mut Rng) -> bool {
+        let outcome = rng.gen_bool(self.prob_one(qubit));
+        self.postselect(qubit, outcome);
+        outcome
+    }
+
+    /// basis index of a bitstring written with qubit 0 rightmost, as in
+    /// `Distribution::format_outcome`
+    fn parse_bitstring(&self, bitstring: &str) -> Result<usize, String> {
+        if bitstring.len() != self.num_qubits {
+            return Err(format!(
+                "bitstring '{}' has {} bits, register has {} qubits",
+                bitstring,
+                bitstring.len(),
+                self.num_qubits
+            ));
+        }
+        bitstring.chars().try_fold(0, |index, c| match c {
+            '0' => Ok(index << 1),
+            '1' => Ok(index << 1 | 1),
+            _ => Err(format!("invalid character '{}' in bitstring '{}'", c, bitstring)),
+        })
+    }
+
+    /// amplitude ⟨x|ψ⟩ of a single outcome
+    pub fn amplitude(&self, bitstring: &str) -> Result<Complex64, String> {
+        Ok(self.amplitudes[self.parse_bitstring(bitstring)?])
+    }
+
+    /// probability of measuring exactly `bitstring`
+    pub fn prob_of(&self, bitstring: &str) -> Result<f64, String> {
+        Ok(self.amplitude(bitstring)?.norm_sqr())
+    }
+
+    /// distribution over `qubits` alone, summing out the rest; bit j of an
+    /// outcome is `qubits[j]`
+    pub fn marginal(&self, qubits: &[usize]) -> Distribution {
+        assert!(qubits.iter().all(|&q| q < self.num_qubits), "qubit out of range");
+        let mut probs = vec![0.0; 1 << qubits.len()];
+        for (i, a) in self.amplitudes.iter().enumerate() {
+            let outcome = qubits
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (j, &q)| acc | (i >> q & 1) << j);
+            probs[outcome] += a.norm_sqr();
+        }
+        Distribution::new(qubits.len(), probs)
+    }
+
+    /// k most probable basis states, highest first, in one pass with a
+    /// k-element heap instead of sorting all 2^n probabilities
+    pub fn top_outcomes(&self, k: usize) -> Vec<(usize, f64)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (i, a) in self.amplitudes.iter().enumerate() {
+            heap.push(Reverse(Ranked(a.norm_sqr(), i)));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        heap.into_sorted_vec().into_iter().map(|Reverse(Ranked(p, i))| (i, p)).collect()
+    }
+
+    pub fn probabilities(&self) -> Vec<f64> {
+        self.amplitudes.iter().map(|a| a.norm_sqr()).collect()
+    }
+
+    /// measurement distribution over all qubits
+    pub fn distribution(&self) -> Distribution {
+        Distribution::new(self.num_qubits, self.probabilities())
+    }
+
+    /// state in ket notation, skipping zero amplitudes
+    pub fn display(&self) {
+        let terms: Vec<String> = self
+            .amplitudes
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.norm_sqr() > 1e-10)
+            .map(|(k, a)| format!("{:.3}|{:0width$b}⟩", a, k, width = self.num_qubits))
+            .collect();
+        println!("State: {}", terms.join(" + "));
+        self.distribution().display();
+    }
+
+    pub fn display_with_message(&self, message: &str) {
+        println!("\n{}", message);
+        self.display();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::{h_matrix, x_matrix};
+    use crate::simulator::testing::bell_state;
+
+    #[test]
+    fn test_initial_state() {
+        let register = Register::new(3);
+        assert_eq!(register.amplitudes().len(), 8);
+        assert!((register.probabilities()[0] - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_measure_collapses_partner() {
+        let mut rng = Rng::seed_from_u64(6);
+        for _ in 0..20 {
+            let mut register = Register::new(2);
+            register.apply_gate(0, h_matrix()).unwrap();
+            register.apply_controlled_gate(&[0], 1, x_matrix()).unwrap();
+            let first = register.measure(0, &mut rng);
+            assert_eq!(register.measure(1, &mut rng), first);
+        }
+    }
+
+    #[test]
+    fn test_bell_state_distribution() {
+        let mut register = Register::new(2);
+        register.apply_gate(0, h_matrix()).unwrap();
+        register.apply_controlled_gate(&[0], 1, x_matrix()).unwrap();
+        let dist = register.distribution();
+        assert!((dist.prob(0b00) - 0.5).abs() < 1e-10);
+        assert!((dist.prob(0b11) - 0.5).abs() < 1e-10);
+        assert!((dist.entropy() - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_amplitude_queries_and_marginal() {
+        let mut register = Register::new(3);
+        register.apply_gate(0, h_matrix()).unwrap();
+        register.apply_controlled_gate(&[0], 2, x_matrix()).unwrap();
+        assert!((register.amplitude("101").unwrap().re - 0.5f64.sqrt()).abs() < 1e-10);
+        assert!(register.prob_of("001").unwrap() < 1e-10);
+        assert!(register.prob_of("10").unwrap_err().contains("2 bits"));
+        assert!(register.amplitude("1x1").is_err());
+        let marginal = register.marginal(&[2, 1]);
+        assert!((marginal.prob(0b01) - 0.5).abs() < 1e-10);
+        assert!((marginal.prob(0b00) - 0.5).abs() < 1e-10);
+        assert!(marginal.prob(0b10) < 1e-10);
+    }
+
+    #[test]
+    fn test_top_outcomes_match_full_sort() {
+        let mut rng = Rng::seed_from_u64(11);
+        let amplitudes =
+            (0..64).map(|_| Complex64::new(rng.next_f64(), rng.next_f64() - 0.5)).collect();
+        let mut register = Register::from_amplitudes(amplitudes);
+        register.normalize();
+        let top = register.top_outcomes(5);
+        let sorted = register.distribution().top_k(5);
+        assert!(top.iter().zip(&sorted).all(|(a, b)| a.0 == b.0 && (a.1 - b.1).abs() < 1e-12));
+        assert_eq!(Register::new(2).top_outcomes(2), vec![(0, 1.0), (1, 0.0)]);
+        assert!(register.top_outcomes(0).is_empty());
+    }
+
+    #[test]
+    fn test_history_records_and_replays() {
+        let mut register = Register::new(3);
+        register.start_recording();
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).cx(0, 1).rz(0.3, 2);
+        register.apply_circuit(&circuit);
+        register.apply_controlled_gate(&[1], 2, x_matrix()).unwrap();
+        register.apply_swap(0, 2);
+        let history = register.history();
+        assert_eq!(history.len(), 5);
+        assert_eq!(history[..3], circuit.instructions()[..]);
+        assert!(matches!(history[3].gate, Gate::Mcu(1, _)) && history[3].qubits == [1, 2]);
+        // replaying reproduces the state and the inverse undoes it
+        let mut replay = Register::new(3);
+        replay.apply_circuit(&register.history_circuit());
+        assert!(replay.approx_eq(&register, 1e-12));
+        register.apply_circuit(&register.history_circuit().inverse());
+        assert!(register.approx_eq(&Register::new(3), 1e-12));
+        assert_eq!(register.stop_recording().len(), 10);
+        assert!(register.history().is_empty() && !register.is_recording());
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut register = Register::new(2);
+        register.start_recording();
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1).t(1);
+        register.apply_circuit(&circuit);
+        let bell = bell_state();
+        assert_eq!(register.undo().map(|i| i.gate), Some(Gate::T));
+        assert!(register.approx_eq(&bell, 1e-12));
+        register.undo();
+        register.undo();
+        assert!(register.undo().is_none() && register.approx_eq(&Register::new(2), 1e-12));
+        while register.redo().is_some() {}
+        assert_eq!(register.history(), circuit.instructions());
+        register.undo();
+        register.apply_gate(0, x_matrix()).unwrap();
+        assert!(register.redo().is_none());
+    }
+
+    #[test]
+    fn test_measurement_is_an_undo_barrier() {
+        let mut register = Register::new(1);
+        register.start_recording();
+        register.apply_gate(0, h_matrix()).unwrap();
+        register.postselect(0, false);
+        assert!(register.history().is_empty() && register.undo().is_none());
+        assert!(register.approx_eq(&Register::new(1), 1e-12));
+        register.apply_gate(0, x_matrix()).unwrap();
+        register.undo();
+        let mut rng = Rng::seed_from_u64(1);
+        register.measure(0, &mut rng);
+        assert!(register.redo().is_none() && register.is_recording());
+    }
+
+    #[test]
+    fn test_wider_operators_are_rejected() {
+        let mut register = Register::new(2);
+        register.start_recording();
+        let error = register.apply_gate(0, Gate::Swap.operator()).unwrap_err();
+        assert!(error.contains("got 2 qubits"), "{}", error);
+        assert!(register.apply_controlled_gate(&[0], 1, Operator::identity(2)).is_err());
+        let mut rng = Rng::seed_from_u64(3);
+        assert!(register.measure_in_basis(0, &Operator::identity(3), &mut rng).is_err());
+        assert!(register.history().is_empty());
+        assert!(register.approx_eq(&Register::new(2), 1e-12));
+    }
+}
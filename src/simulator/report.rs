@@ -0,0 +1,347 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use num_complex::Complex64;
+use super::circuit::Circuit;
+use super::register::Register;
+use super::rng::Rng;
+
+/// execution statistics returned with every run
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunReport {
+    pub backend: &'static str,
+    pub num_qubits: usize,
+    pub shots: usize,
+    pub gate_counts: BTreeMap<&'static str, usize>,
+    pub depth: usize,
+    pub multi_qubit_gates: usize,
+    pub wall_time: Duration,
+    /// bytes held by the simulator's state at its largest (amplitude storage)
+    pub peak_memory_bytes: usize,
+    /// shots ending with each qubit leaked to |2⟩; empty without a leakage model
+    pub leaked_shots: Vec<usize>,
+}
+
+impl RunReport {
+    /// circuit statistics with zero timing, to be filled in by a backend
+    pub fn for_circuit(backend: &'static str, circuit: &Circuit, shots: usize) -> Self {
+        Self {
+            backend,
+            num_qubits: circuit.num_qubits(),
+            shots,
+            gate_counts: circuit.gate_counts(),
+            depth: circuit.depth(),
+            multi_qubit_gates: circuit.multi_qubit_gate_count(),
+            wall_time: Duration::ZERO,
+            peak_memory_bytes: 0,
+            leaked_shots: Vec::new(),
+        }
+    }
+
+    pub fn total_gates(&self) -> usize {
+        self.gate_counts.values().sum()
+    }
+}
+
+impl fmt::Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "backend: {}", self.backend)?;
+        writeln!(f, "qubits: {}, shots: {}", self.num_qubits, self.shots)?;
+        writeln!(
+            f,
+            "gates: {} (multi-qubit {}), depth {}",
+            self.total_gates(),
+            self.multi_qubit_gates,
+            self.depth
+        )?;
+        let counts: Vec<String> =
+            self.gate_counts.iter().map(|(name, n)| format!("{}: {}", name, n)).collect();
+        writeln!(f, "by type: {}", counts.join(", "))?;
+        write!(
+            f,
+            "wall time: {:?}, peak state memory: {} bytes",
+            self.wall_time, self.peak_memory_bytes
+        )?;
+        if !self.leaked_shots.is_empty() {
+            write!(f, "\nleaked shots per qubit: {:?}", self.leaked_shots)?;
+        }
+        Ok(())
+    }
+}
+
+/// measurement counts with the report of the run that produced them
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunResult {
+    pub counts: BTreeMap<usize, usize>,
+    pub report: RunReport,
+}
+
+/// bytes of amplitude storage for an n-qubit state vector
+pub fn statevector_bytes(num_qubits: usize) -> usize {
+    (1usize << num_qubits) * std::mem::size_of::<Complex64>()
+}
+
+/// simulate once on the state vector and sample `shots` outcomes
+pub fn run(circuit: &Circuit, shots: usize, rng: &mut Rng) -> RunResult {
+    let start = Instant::now();
+    let mut register = Register::new(circuit.num_qubits());
+    register.apply_circuit(circuit);
+    let counts = register.distribution().sample_counts(shots, rng);
+    let mut report = RunReport::for_circuit("statevector", circuit, shots);
+    report.wall_time = start.elapsed();
+    report.peak_memory_bytes = statevector_bytes(circuit.num_qubits());
+    RunResult { counts, report }
+}
+
+/// one sampled outcome, delivered as soon as it is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShotResult {
+    /// 0-based position of the shot in the run
+    pub shot: usize,
+    pub outcome: usize,
+}
+
+/// how far a run has got, passed to progress hooks after every gate and
+/// every shot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub gates_applied: usize,
+    pub total_gates: usize,
+    pub shots_completed: usize,
+    pub total_shots: usize,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// completed share of the run, counting gates and shots as one unit each
+    pub fn fraction(&self) -> f64 {
+        let total = self.total_gates + self.total_shots;
+        if total == 0 {
+            return 1.0;
+        }
+        (self.gates_applied + self.shots_completed) as f64 / total as f64
+    }
+
+    /// remaining time extrapolated from the rate so far
+    pub fn eta(&self) -> Option<Duration> {
+        let fraction = self.fraction();
+        (fraction > 0.0).then(|| self.elapsed.mul_f64((1.0 - fraction) / fraction))
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "gates {}/{}, shots {}/{} ({:.0}%)",
+            self.gates_applied,
+            self.total_gates,
+            self.shots_completed,
+            self.total_shots,
+            100.0 * self.fraction()
+        )?;
+        match self.eta() {
+            Some(eta) => write!(f, ", eta {:.1}s", eta.as_secs_f64()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// cooperative cancellation flag shared between a run and its controller;
+/// clones refer to the same flag
+#[derive(Debug, Clone, Default)]
+pub struct RunHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl RunHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ask the run to stop at the next gate or shot boundary
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// state-vector run reporting progress and streaming shots, checking
+/// `handle` between gates and between shots
+fn run_observed(
+    circuit: &Circuit,
+    shots: usize,
+    rng: &mut Rng,
+    handle: &RunHandle,
+    on_progress: &mut dyn FnMut(&Progress),
+    on_shot: &mut dyn FnMut(ShotResult) -> ControlFlow<()>,
+) -> Result<RunResult, String> {
+    let cancelled = |progress: &Progress| {
+        format!(
+            "run cancelled after {} of {} gates and {} of {} shots",
+            progress.gates_applied, progress.total_gates, progress.shots_completed, shots
+        )
+    };
+    let start = Instant::now();
+    let mut progress = Progress {
+        gates_applied: 0,
+        total_gates: circuit.len(),
+        shots_completed: 0,
+        total_shots: shots,
+        elapsed: Duration::ZERO,
+    };
+    let mut register = Register::new(circuit.num_qubits());
+    for instruction in circuit.instructions() {
+        if handle.is_cancelled() {
+            return Err(cancelled(&progress));
+        }
+        register.apply_instruction(instruction);
+        progress.gates_applied += 1;
+        progress.elapsed = start.elapsed();
+        on_progress(&progress);
+    }
+    let sampler = register.distribution().sampler();
+    let mut counts = BTreeMap::new();
+    while progress.shots_completed < shots {
+        if handle.is_cancelled() {
+            return Err(cancelled(&progress));
+        }
+        let outcome = sampler.sample(rng);
+        *counts.entry(outcome).or_insert(0) += 1;
+        progress.shots_completed += 1;
+        progress.elapsed = start.elapsed();
+        on_progress(&progress);
+        if on_shot(ShotResult { shot: progress.shots_completed - 1, outcome }).is_break() {
+            break;
+        }
+    }
+    let mut report = RunReport::for_circuit("statevector", circuit, progress.shots_completed);
+    report.wall_time = start.elapsed();
+    report.peak_memory_bytes = statevector_bytes(circuit.num_qubits());
+    Ok(RunResult { counts, report })
+}
+
+/// like `run`, handing each shot to `callback` as it is sampled;
+/// `ControlFlow::Break` stops early and the report records the shots taken
+pub fn run_with_callback(
+    circuit: &Circuit,
+    shots: usize,
+    rng: &mut Rng,
+    mut callback: impl FnMut(ShotResult) -> ControlFlow<()>,
+) -> RunResult {
+    run_observed(circuit, shots, rng, &RunHandle::new(), &mut |_| {}, &mut callback)
+        .expect("a fresh handle is never cancelled")
+}
+
+/// like `run`, calling `on_progress` after every gate and every shot
+pub fn run_with_progress(
+    circuit: &Circuit,
+    shots: usize,
+    rng: &mut Rng,
+    mut on_progress: impl FnMut(&Progress),
+) -> RunResult {
+    run_with_handle(circuit, shots, rng, &RunHandle::new(), &mut on_progress)
+        .expect("a fresh handle is never cancelled")
+}
+
+/// like `run_with_progress`, aborting with an error once `handle` is
+/// cancelled (from another thread or from the progress hook itself)
+pub fn run_with_handle(
+    circuit: &Circuit,
+    shots: usize,
+    rng: &mut Rng,
+    handle: &RunHandle,
+    mut on_progress: impl FnMut(&Progress),
+) -> Result<RunResult, String> {
+    let mut on_shot = |_| ControlFlow::Continue(());
+    run_observed(circuit, shots, rng, handle, &mut on_progress, &mut on_shot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_report() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1);
+        let mut rng = Rng::seed_from_u64(32);
+        let result = run(&circuit, 100, &mut rng);
+        assert_eq!(result.counts.values().sum::<usize>(), 100);
+        let report = &result.report;
+        assert_eq!((report.backend, report.total_gates(), report.depth), ("statevector", 2, 2));
+        assert_eq!(report.peak_memory_bytes, 4 * 16);
+        assert!(report.to_string().contains("by type: cx: 1, h: 1"));
+    }
+
+    #[test]
+    fn test_callback_streams_shots_and_stops_early() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).cx(0, 1).ry(0.9, 2);
+        let batch = run(&circuit, 200, &mut Rng::seed_from_u64(5));
+        let mut seen = Vec::new();
+        let streamed = run_with_callback(&circuit, 200, &mut Rng::seed_from_u64(5), |shot| {
+            seen.push(shot);
+            ControlFlow::Continue(())
+        });
+        assert_eq!(streamed.counts, batch.counts);
+        assert_eq!((seen.len(), seen[199].shot), (200, 199));
+
+        // stop once outcome |011⟩ has been seen ten times
+        let mut hits = 0;
+        let early = run_with_callback(&circuit, 1000, &mut Rng::seed_from_u64(5), |shot| {
+            hits += usize::from(shot.outcome == 0b011);
+            if hits == 10 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        });
+        assert_eq!(early.counts[&0b011], 10);
+        assert!(early.report.shots < 1000);
+        assert_eq!(early.counts.values().sum::<usize>(), early.report.shots);
+    }
+
+    #[test]
+    fn test_progress_hook_counts_gates_then_shots() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1).t(1);
+        let mut updates = Vec::new();
+        let result = run_with_progress(&circuit, 5, &mut Rng::seed_from_u64(9), |p| {
+            updates.push(*p)
+        });
+        assert_eq!(result.report.shots, 5);
+        assert_eq!(updates.len(), 8);
+        assert_eq!((updates[2].gates_applied, updates[2].shots_completed), (3, 0));
+        let last = updates[7];
+        assert_eq!((last.fraction(), last.eta()), (1.0, Some(Duration::ZERO)));
+        assert!(updates[3].to_string().starts_with("gates 3/3, shots 1/5 (50%)"));
+    }
+
+    #[test]
+    fn test_cancellation_between_gates_and_shots() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1).t(1).h(1);
+        let handle = RunHandle::new();
+        let watcher = handle.clone();
+        let error = run_with_handle(&circuit, 10, &mut Rng::seed_from_u64(1), &handle, |p| {
+            if p.gates_applied == 2 {
+                watcher.cancel();
+            }
+        })
+        .unwrap_err();
+        assert_eq!(error, "run cancelled after 2 of 4 gates and 0 of 10 shots");
+
+        let handle = RunHandle::new();
+        let error = run_with_handle(&circuit, 10, &mut Rng::seed_from_u64(1), &handle, |p| {
+            if p.shots_completed == 3 {
+                handle.cancel();
+            }
+        })
+        .unwrap_err();
+        assert!(error.ends_with("3 of 10 shots"));
+        assert!(handle.is_cancelled());
+    }
+}
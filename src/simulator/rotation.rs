@@ -0,0 +1,191 @@
+use std::f64::consts::PI;
+use num_complex::Complex64;
+use super::gates::{Gate, Matrix2};
+use super::operator::Operator;
+
+/// U = e^{iφ}·(cos(θ/2)·I − i·sin(θ/2)·n·σ), a rotation by θ about the unit
+/// axis n up to the global phase φ
+///
+/// Canonical form: θ ∈ [0, π] and φ ∈ (−π, π]. A θ > π rotation is the
+/// θ' = 2π − θ rotation about −n with φ shifted by π.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisAngle {
+    pub axis: [f64; 3],
+    pub angle: f64,
+    pub phase: f64,
+}
+
+fn wrap(phase: f64) -> f64 {
+    let wrapped = phase.rem_euclid(2.0 * PI);
+    if wrapped > PI {
+        wrapped - 2.0 * PI
+    } else {
+        wrapped
+    }
+}
+
+impl AxisAngle {
+    /// canonicalized rotation; a zero axis is only allowed with a zero angle
+    pub fn new(axis: [f64; 3], angle: f64, phase: f64) -> Self {
+        let norm = axis.iter().map(|a| a * a).sum::<f64>().sqrt();
+        let half = angle / 2.0;
+        let vector = if norm > 0.0 {
+            axis.map(|a| a / norm * half.sin())
+        } else {
+            assert!(half.sin().abs() < 1e-12, "rotation needs a non-zero axis");
+            [0.0; 3]
+        };
+        Self::from_quaternion(half.cos(), vector, phase)
+    }
+
+    pub fn identity() -> Self {
+        Self::new([0.0, 0.0, 1.0], 0.0, 0.0)
+    }
+
+    /// canonical form of e^{iφ}·(w·I − i·v·σ) for a unit quaternion (w, v)
+    fn from_quaternion(w: f64, v: [f64; 3], phase: f64) -> Self {
+        let (w, v, phase) = if w < 0.0 { (-w, v.map(|x| -x), phase + PI) } else { (w, v, phase) };
+        let s = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let angle = 2.0 * s.atan2(w);
+        let axis = if s > 1e-12 { v.map(|x| x / s) } else { [0.0, 0.0, 1.0] };
+        Self { axis, angle, phase: wrap(phase) }
+    }
+
+    /// unit quaternion (w, v) with the rotation equal to e^{iφ}·(w·I − i·v·σ)
+    pub fn quaternion(&self) -> (f64, [f64; 3]) {
+        let half = self.angle / 2.0;
+        (half.cos(), self.axis.map(|a| a * half.sin()))
+    }
+
+    /// axis–angle form of any one-qubit unitary; panics on wider operators
+    pub fn from_matrix(operator: &Operator) -> Self {
+        Self::from_matrix2(&operator.to_matrix2().expect("one-qubit operator"))
+    }
+
+    /// `from_matrix` for a 2×2 array
+    pub(crate) fn from_matrix2(matrix: &Matrix2) -> Self {
+        let det = matrix[0][0] * matrix[1][1] - matrix[0][1] * matrix[1][0];
+        let phase = det.arg() / 2.0;
+        let unphase = Complex64::from_polar(1.0, -phase);
+        // V = e^{−iφ}U = [[w − iz, −y − ix], [y − ix, w + iz]]
+        let (v00, v01) = (matrix[0][0] * unphase, matrix[0][1] * unphase);
+        let (v10, v11) = (matrix[1][0] * unphase, matrix[1][1] * unphase);
+        let w = (v00.re + v11.re) / 2.0;
+        let v = [-(v01.im + v10.im) / 2.0, (v10.re - v01.re) / 2.0, (v11.im - v00.im) / 2.0];
+        Self::from_quaternion(w, v, phase)
+    }
+
+    pub fn from_gate(gate: &Gate) -> Option<Self> {
+        gate.matrix2().map(|m| Self::from_matrix2(&m))
+    }
+
+    /// the net rotation of a run of single-qubit gates, first gate first;
+    /// `None` if any gate acts on more than one qubit
+    pub fn from_gates(gates: &[Gate]) -> Option<Self> {
+        gates.iter().try_fold(Self::identity(), |net, gate| {
+            Self::from_gate(gate).map(|r| net.then(&r))
+        })
+    }
+
+    pub fn matrix(&self) -> Operator {
+        Operator::from(self.matrix2())
+    }
+
+    /// `matrix` as a 2×2 array
+    pub(crate) fn matrix2(&self) -> Matrix2 {
+        let (w, [x, y, z]) = self.quaternion();
+        let p = Complex64::from_polar(1.0, self.phase);
+        [
+            [p * Complex64::new(w, -z), p * Complex64::new(-y, -x)],
+            [p * Complex64::new(y, -x), p * Complex64::new(w, z)],
+        ]
+    }
+
+    /// the product self·other: `other` acts first
+    pub fn compose(&self, other: &AxisAngle) -> AxisAngle {
+        let (a0, a) = self.quaternion();
+        let (b0, b) = other.quaternion();
+        // (a0 − i a·σ)(b0 − i b·σ) = a0b0 − a·b − i(a0 b + b0 a + a × b)·σ
+        let dot = a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+        let cross = [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ];
+        let v = [0, 1, 2].map(|k| a0 * b[k] + b0 * a[k] + cross[k]);
+        Self::from_quaternion(a0 * b0 - dot, v, self.phase + other.phase)
+    }
+
+    /// self followed by `next`, i.e. next·self
+    pub fn then(&self, next: &AxisAngle) -> AxisAngle {
+        next.compose(self)
+    }
+
+    pub fn inverse(&self) -> AxisAngle {
+        Self::new(self.axis, -self.angle, -self.phase)
+    }
+
+    /// same rotation within `tolerance`, ignoring the global phase
+    pub fn same_rotation(&self, other: &AxisAngle, tolerance: f64) -> bool {
+        let (a0, a) = self.quaternion();
+        let (b0, b) = other.quaternion();
+        let overlap = a0 * b0 + a.iter().zip(&b).map(|(x, y)| x * y).sum::<f64>();
+        1.0 - overlap.abs() < tolerance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4, FRAC_PI_8};
+    use crate::simulator::gates::{h_matrix, matmul, rx_matrix, ry_matrix, rz_matrix};
+
+    fn assert_close(r: AxisAngle, axis: [f64; 3], angle: f64, phase: f64) {
+        let distance = r.axis.iter().zip(&axis).map(|(a, b)| (a - b).abs()).fold(0.0, f64::max);
+        assert!(distance < 1e-12, "axis {:?} vs {:?}", r.axis, axis);
+        assert!((r.angle - angle).abs() < 1e-12, "angle {} vs {}", r.angle, angle);
+        assert!((r.phase - phase).abs() < 1e-12, "phase {} vs {}", r.phase, phase);
+    }
+
+    #[test]
+    fn test_gate_rotations_have_expected_axes() {
+        let theta = 0.8;
+        assert_close(AxisAngle::from_matrix2(&rx_matrix(theta)), [1.0, 0.0, 0.0], theta, 0.0);
+        assert_close(AxisAngle::from_matrix2(&ry_matrix(theta)), [0.0, 1.0, 0.0], theta, 0.0);
+        assert_close(AxisAngle::from_matrix2(&rz_matrix(theta)), [0.0, 0.0, 1.0], theta, 0.0);
+        let h = [FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2];
+        assert_close(AxisAngle::from_matrix2(&h_matrix()), h, PI, FRAC_PI_2);
+        let z = [0.0, 0.0, 1.0];
+        assert_close(AxisAngle::from_gate(&Gate::S).unwrap(), z, FRAC_PI_2, FRAC_PI_4);
+        assert_close(AxisAngle::from_gate(&Gate::T).unwrap(), z, FRAC_PI_4, FRAC_PI_8);
+        // RX(3π/2) is canonically RX(−π/2) = π/2 about −x with a phase of π
+        let r = AxisAngle::from_matrix2(&rx_matrix(3.0 * FRAC_PI_2));
+        assert_close(r, [-1.0, 0.0, 0.0], FRAC_PI_2, PI);
+    }
+
+    #[test]
+    fn test_matrix_round_trip_and_composition() {
+        let a = AxisAngle::new([1.0, 2.0, -0.5], 1.3, 0.4);
+        let b = AxisAngle::new([0.0, -1.0, 1.0], 2.9, -1.1);
+        crate::assert_unitary_eq!(AxisAngle::from_matrix(&a.matrix()).matrix(), a.matrix());
+        let product = matmul(&a.matrix2(), &b.matrix2());
+        let composed = a.compose(&b).matrix2();
+        for (row, expected) in composed.iter().zip(&product) {
+            for (x, y) in row.iter().zip(expected) {
+                assert!((x - y).norm() < 1e-12);
+            }
+        }
+        assert!(a.then(&a.inverse()).same_rotation(&AxisAngle::identity(), 1e-12));
+        assert!((a.then(&a.inverse()).phase).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fusing_a_gate_run() {
+        // H·Z·H = X up to phase, and T⁸ is the identity
+        let x = AxisAngle::from_gates(&[Gate::H, Gate::Z, Gate::H]).unwrap();
+        assert!(x.same_rotation(&AxisAngle::from_gate(&Gate::X).unwrap(), 1e-12));
+        let t8 = AxisAngle::from_gates(&[const { Gate::T }; 8]).unwrap();
+        assert!(t8.same_rotation(&AxisAngle::identity(), 1e-12));
+        assert!(AxisAngle::from_gates(&[Gate::H, Gate::Cx]).is_none());
+    }
+}
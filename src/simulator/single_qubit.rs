@@ -1,4 +1,9 @@
 use num_complex::Complex64;
+use super::circuit::Circuit;
+use super::gates::{Gate, Matrix2};
+use super::operator::Operator;
+use super::rng::Rng;
+use super::testing::approx_eq_up_to_phase;
 
 /// single qubit quantum state: α|0⟩ + β|1⟩
 #[derive(Debug, Clone)]
@@ -46,7 +51,26 @@ impl SingleQubit {
         self.beta.norm_sqr()
     }
 
-    pub fn apply_gate(&mut self, matrix: [[Complex64; 2]; 2]) {
+    /// Bloch vector (⟨X⟩, ⟨Y⟩, ⟨Z⟩)
+    pub fn bloch_vector(&self) -> [f64; 3] {
+        let cross = self.alpha.conj() * self.beta;
+        [2.0 * cross.re, 2.0 * cross.im, self.prob_zero() - self.prob_one()]
+    }
+
+    /// equal up to global phase
+    pub fn approx_eq(&self, other: &Self, tolerance: f64) -> bool {
+        approx_eq_up_to_phase(&[self.alpha, self.beta], &[other.alpha, other.beta], tolerance)
+    }
+
+    /// apply a one-qubit operator; wider operators are rejected and leave the
+    /// state as it was
+    pub fn apply_gate(&mut self, operator: impl Into<Operator>) -> Result<(), String> {
+        self.apply_matrix2(operator.into().one_qubit_matrix2()?);
+        Ok(())
+    }
+
+    /// `apply_gate` with a 2×2 array
+    pub(crate) fn apply_matrix2(&mut self, matrix: Matrix2) {
         let new_alpha = matrix[0][0] * self.alpha + matrix[0][1] * self.beta;
         let new_beta = matrix[1][0] * self.alpha + matrix[1][1] * self.beta;
         self.alpha = new_alpha;
@@ -75,15 +99,127 @@ impl Default for SingleQubit {
     }
 }
 
+/// single qubit that keeps the gates applied through it, so the state can be
+/// reproduced, inverted, exported as a circuit or stepped back and forth
+#[derive(Debug, Clone, Default)]
+pub struct RecordedQubit {
+    qubit: SingleQubit,
+    /// applied gates, in order
+    history: Vec<Gate>,
+    /// undone gates, most recently undone last
+    redo: Vec<Gate>,
+}
+
+impl RecordedQubit {
+    /// start recording from `qubit`; earlier gates are not known
+    pub fn new(qubit: SingleQubit) -> Self {
+        Self { qubit, history: Vec::new(), redo: Vec::new() }
+    }
+
+    pub fn qubit(&self) -> &SingleQubit {
+        &self.qubit
+    }
+
+    /// the state and the recorded gates
+    pub fn into_parts(self) -> (SingleQubit, Vec<Gate>) {
+        (self.qubit, self.history)
+    }
+
+    /// apply a single-qubit gate, recorded by name and parameter; clears the
+    /// redo stack. Multi-qubit gates are rejected and leave the qubit as it was.
+    pub fn apply(&mut self, gate: Gate) -> Result<(), String> {
+        let matrix = gate
+            .matrix2()
+            .ok_or_else(|| format!("{} is not a single-qubit gate", gate.label()))?;
+        self.qubit.apply_matrix2(matrix);
+        self.history.push(gate);
+        self.redo.clear();
+        Ok(())
+    }
+
+    /// apply a one-qubit operator, recorded as a custom `Mcu(0, operator)`;
+    /// wider operators are rejected
+    pub fn apply_gate(&mut self, operator: impl Into<Operator>) -> Result<(), String> {
+        self.apply(Gate::Mcu(0, operator.into()))
+    }
+
+    /// project onto `outcome` and renormalize, returning its probability;
+    /// the collapse cannot be undone, so the history and redo stack are cleared
+    pub fn postselect(&mut self, outcome: bool) -> f64 {
+        let zero = Complex64::new(0.0, 0.0);
+        let probability = if outcome {
+            self.qubit.alpha = zero;
+            self.qubit.prob_one()
+        } else {
+            self.qubit.beta = zero;
+            self.qubit.prob_zero()
+        };
+        self.qubit.normalize();
+        self.history.clear();
+        self.redo.clear();
+        probability
+    }
+
+    /// projective Z measurement; like [`RecordedQubit::postselect`] it ends
+    /// the undoable history
+    pub fn measure(&mut self, rng: &mut Rng) -> bool {
+        let outcome = rng.gen_bool(self.qubit.prob_one());
+        self.postselect(outcome);
+        outcome
+    }
+
+    /// recorded gates, in order
+    pub fn history(&self) -> &[Gate] {
+        &self.history
+    }
+
+    /// revert the last recorded gate by applying its inverse; `None` when
+    /// nothing is recorded
+    pub fn undo(&mut self) -> Option<Gate> {
+        let gate = self.history.pop()?;
+        // `apply` only records single-qubit gates, whose inverses are too
+        self.qubit.apply_matrix2(gate.inverse().matrix2().expect("single-qubit gate"));
+        self.redo.push(gate.clone());
+        Some(gate)
+    }
+
+    /// re-apply the last undone gate
+    pub fn redo(&mut self) -> Option<Gate> {
+        let gate = self.redo.pop()?;
+        self.qubit.apply_matrix2(gate.matrix2().expect("single-qubit gate"));
+        self.history.push(gate.clone());
+        Some(gate)
+    }
+
+    /// the history as a one-qubit circuit, to replay, invert or export
+    pub fn history_circuit(&self) -> Circuit {
+        let mut circuit = Circuit::new(1);
+        for gate in &self.history {
+            circuit.push(gate.clone(), &[0]);
+        }
+        circuit
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_initial_state() {
-        let qubit = SingleQubit::new();
+        let mut qubit = SingleQubit::new();
         assert!((qubit.prob_zero() - 1.0).abs() < 1e-10);
         assert!(qubit.prob_one().abs() < 1e-10);
+        assert!(qubit.apply_gate(Gate::Cz.operator()).unwrap_err().contains("got 2 qubits"));
+        assert!((qubit.prob_zero() - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_bloch_vector_of_plus_i() {
+        let s = std::f64::consts::FRAC_1_SQRT_2;
+        let qubit = SingleQubit::from_amplitudes(Complex64::new(s, 0.0), Complex64::new(0.0, s));
+        let [x, y, z] = qubit.bloch_vector();
+        assert!(x.abs() < 1e-12 && (y - 1.0).abs() < 1e-12 && z.abs() < 1e-12);
     }
 
     #[test]
@@ -96,4 +232,63 @@ mod tests {
         let total_prob = qubit.prob_zero() + qubit.prob_one();
         assert!((total_prob - 1.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_history_replays_and_inverts() {
+        use crate::simulator::gates::{h_gate, x_matrix};
+        let mut plus = SingleQubit::new();
+        h_gate(&mut plus);
+        let mut qubit = RecordedQubit::new(plus.clone());
+        assert!(qubit.history().is_empty());
+        qubit.apply(Gate::Rx(0.4)).unwrap();
+        qubit.apply(Gate::T).unwrap();
+        qubit.apply_gate(x_matrix()).unwrap();
+        assert_eq!(qubit.history()[..2], [Gate::Rx(0.4), Gate::T]);
+        assert!(matches!(qubit.history()[2], Gate::Mcu(0, _)));
+        assert!(qubit.apply(Gate::Cx).is_err());
+        assert!(qubit.apply_gate(Gate::Swap.operator()).is_err());
+        assert_eq!(qubit.history().len(), 3);
+        // undoing the recorded part leaves H|0⟩
+        let inverse = qubit.history_circuit().inverse();
+        for instruction in inverse.instructions() {
+            qubit.apply(instruction.gate.clone()).unwrap();
+        }
+        let (state, history) = qubit.into_parts();
+        assert!(state.approx_eq(&plus, 1e-12));
+        assert_eq!(history.len(), 6);
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut qubit = RecordedQubit::new(SingleQubit::new_one());
+        assert_eq!(qubit.undo(), None);
+        qubit.apply(Gate::H).unwrap();
+        qubit.apply(Gate::S).unwrap();
+        let after = qubit.qubit().clone();
+        assert_eq!(qubit.undo(), Some(Gate::S));
+        assert_eq!(qubit.undo(), Some(Gate::H));
+        assert_eq!(qubit.undo(), None);
+        assert!(qubit.qubit().approx_eq(&SingleQubit::new_one(), 1e-12));
+        assert_eq!(qubit.redo(), Some(Gate::H));
+        assert_eq!(qubit.redo(), Some(Gate::S));
+        assert!(qubit.qubit().approx_eq(&after, 1e-12) && qubit.history() == [Gate::H, Gate::S]);
+        // a new gate after an undo discards the redo stack
+        qubit.undo();
+        qubit.apply(Gate::X).unwrap();
+        assert_eq!(qubit.redo(), None);
+    }
+
+    #[test]
+    fn test_measurement_clears_history() {
+        let mut qubit = RecordedQubit::default();
+        qubit.apply(Gate::H).unwrap();
+        assert!((qubit.postselect(false) - 0.5).abs() < 1e-12);
+        assert_eq!(qubit.undo(), None);
+        assert!(qubit.qubit().approx_eq(&SingleQubit::new(), 1e-12));
+        qubit.apply(Gate::X).unwrap();
+        qubit.undo();
+        let mut rng = Rng::seed_from_u64(3);
+        assert!(!qubit.measure(&mut rng));
+        assert!(qubit.redo().is_none() && qubit.history().is_empty());
+    }
 }
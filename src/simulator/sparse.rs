@@ -0,0 +1,220 @@
+use std::ops::{Add, Mul};
+use num_complex::Complex64;
+use super::matrix::Matrix;
+use super::register::Register;
+
+/// compressed sparse row matrix: row i's entries are
+/// `col_indices[row_offsets[i]..row_offsets[i + 1]]`, columns ascending
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix {
+    rows: usize,
+    cols: usize,
+    row_offsets: Vec<usize>,
+    col_indices: Vec<usize>,
+    values: Vec<Complex64>,
+}
+
+impl SparseMatrix {
+    /// build row by row; `row(i)` may repeat a column, repeats are summed
+    /// and exact zeros dropped
+    pub fn from_row_fn(
+        rows: usize,
+        cols: usize,
+        mut row: impl FnMut(usize) -> Vec<(usize, Complex64)>,
+    ) -> Self {
+        let mut row_offsets = Vec::with_capacity(rows + 1);
+        let mut col_indices = Vec::new();
+        let mut values = Vec::new();
+        row_offsets.push(0);
+        for i in 0..rows {
+            let mut entries = row(i);
+            entries.sort_by_key(|&(j, _)| j);
+            let mut merged: Vec<(usize, Complex64)> = Vec::with_capacity(entries.len());
+            for (j, value) in entries {
+                assert!(j < cols, "column {} out of range", j);
+                match merged.last_mut() {
+                    Some((last, sum)) if *last == j => *sum += value,
+                    _ => merged.push((j, value)),
+                }
+            }
+            for (j, value) in merged.into_iter().filter(|&(_, v)| v != Complex64::new(0.0, 0.0)) {
+                col_indices.push(j);
+                values.push(value);
+            }
+            row_offsets.push(col_indices.len());
+        }
+        Self {
+            rows,
+            cols,
+            row_offsets,
+            col_indices,
+            values,
+        }
+    }
+
+    /// (row, col, value) entries in any order
+    pub fn from_triplets(
+        rows: usize,
+        cols: usize,
+        triplets: impl IntoIterator<Item = (usize, usize, Complex64)>,
+    ) -> Self {
+        let mut buckets = vec![Vec::new(); rows];
+        for (i, j, value) in triplets {
+            assert!(i < rows, "row {} out of range", i);
+            buckets[i].push((j, value));
+        }
+        Self::from_row_fn(rows, cols, |i| std::mem::take(&mut buckets[i]))
+    }
+
+    pub fn from_dense(matrix: &Matrix) -> Self {
+        Self::from_row_fn(matrix.rows(), matrix.cols(), |i| {
+            (0..matrix.cols()).map(|j| (j, matrix[(i, j)])).collect()
+        })
+    }
+
+    pub fn identity(dim: usize) -> Self {
+        Self::from_row_fn(dim, dim, |i| vec![(i, Complex64::new(1.0, 0.0))])
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// number of stored entries
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// (column, value) pairs of row `i`
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (usize, Complex64)> + '_ {
+        let range = self.row_offsets[i]..self.row_offsets[i + 1];
+        self.col_indices[range.clone()].iter().copied().zip(self.values[range].iter().copied())
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> Complex64 {
+        let range = self.row_offsets[i]..self.row_offsets[i + 1];
+        match self.col_indices[range.clone()].binary_search(&j) {
+            Ok(k) => self.values[range.start + k],
+            Err(_) => Complex64::new(0.0, 0.0),
+        }
+    }
+
+    /// matrix–vector product
+    pub fn apply(&self, v: &[Complex64]) -> Vec<Complex64> {
+        assert_eq!(v.len(), self.cols, "dimension mismatch");
+        (0..self.rows).map(|i| self.row(i).map(|(j, a)| a * v[j]).sum()).collect()
+    }
+
+    /// ⟨ψ|A|ψ⟩ for Hermitian A
+    pub fn expectation(&self, state: &Register) -> f64 {
+        let psi = state.amplitudes();
+        let a_psi = self.apply(psi);
+        psi.iter().zip(&a_psi).map(|(a, b)| (a.conj() * b).re).sum()
+    }
+
+    pub fn dagger(&self) -> SparseMatrix {
+        let triplets = (0..self.rows).flat_map(|i| self.row(i).map(move |(j, a)| (j, i, a.conj())));
+        SparseMatrix::from_triplets(self.cols, self.rows, triplets)
+    }
+
+    pub fn scaled(&self, c: Complex64) -> SparseMatrix {
+        Self::from_row_fn(self.rows, self.cols, |i| self.row(i).map(|(j, a)| (j, c * a)).collect())
+    }
+
+    /// self ⊗ other
+    pub fn kron(&self, other: &SparseMatrix) -> SparseMatrix {
+        Self::from_row_fn(self.rows * other.rows, self.cols * other.cols, |i| {
+            let (outer, inner) = (i / other.rows, i % other.rows);
+            self.row(outer)
+                .flat_map(|(j, a)| other.row(inner).map(move |(k, b)| (j * other.cols + k, a * b)))
+                .collect()
+        })
+    }
+
+    pub fn to_dense(&self) -> Matrix {
+        let mut m = Matrix::zeros(self.rows, self.cols);
+        for i in 0..self.rows {
+            for (j, a) in self.row(i) {
+                m[(i, j)] = a;
+            }
+        }
+        m
+    }
+}
+
+impl Add for &SparseMatrix {
+    type Output = SparseMatrix;
+
+    fn add(self, rhs: &SparseMatrix) -> SparseMatrix {
+        assert_eq!((self.rows, self.cols), (rhs.rows, rhs.cols), "dimension mismatch");
+        SparseMatrix::from_row_fn(self.rows, self.cols, |i| self.row(i).chain(rhs.row(i)).collect())
+    }
+}
+
+impl Mul for &SparseMatrix {
+    type Output = SparseMatrix;
+
+    fn mul(self, rhs: &SparseMatrix) -> SparseMatrix {
+        assert_eq!(self.cols, rhs.rows, "dimension mismatch");
+        SparseMatrix::from_row_fn(self.rows, rhs.cols, |i| {
+            self.row(i).flat_map(|(k, a)| rhs.row(k).map(move |(j, b)| (j, a * b))).collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(re: f64, im: f64) -> Complex64 {
+        Complex64::new(re, im)
+    }
+
+    #[test]
+    fn test_triplets_merge_and_round_trip() {
+        let m = SparseMatrix::from_triplets(
+            3,
+            3,
+            [(2, 0, c(1.0, 0.0)), (0, 1, c(0.0, 1.0)), (2, 0, c(0.5, 0.0)), (1, 1, c(2.0, 0.0))],
+        );
+        assert_eq!(m.nnz(), 3);
+        assert_eq!(m.get(2, 0), c(1.5, 0.0));
+        assert_eq!(m.get(2, 2), c(0.0, 0.0));
+        assert_eq!(SparseMatrix::from_dense(&m.to_dense()), m);
+        assert_eq!(m.dagger().get(1, 0), c(0.0, -1.0));
+        // cancelled entries are not stored
+        let zero = SparseMatrix::from_triplets(2, 2, [(0, 0, c(1.0, 0.0)), (0, 0, c(-1.0, 0.0))]);
+        assert_eq!(zero.nnz(), 0);
+    }
+
+    #[test]
+    fn test_apply_matches_dense_product() {
+        let dense = Matrix::from_fn(4, 4, |i, j| {
+            if (i + j) % 3 == 0 {
+                c(i as f64, j as f64)
+            } else {
+                c(0.0, 0.0)
+            }
+        });
+        let sparse = SparseMatrix::from_dense(&dense);
+        let v: Vec<Complex64> = (0..4).map(|k| c(1.0, -(k as f64))).collect();
+        assert_eq!(sparse.apply(&v), dense.apply(&v));
+        assert_eq!(SparseMatrix::identity(4).apply(&v), v);
+    }
+
+    #[test]
+    fn test_algebra_matches_dense() {
+        let a = Matrix::from_fn(2, 2, |i, j| if i == j { c(0.0, 0.0) } else { c(1.0, i as f64) });
+        let b = Matrix::from_fn(2, 2, |i, j| c((i + 2 * j) as f64, 0.0));
+        let (sa, sb) = (SparseMatrix::from_dense(&a), SparseMatrix::from_dense(&b));
+        assert_eq!((&sa * &sb).to_dense(), &a * &b);
+        assert_eq!((&sa + &sb).to_dense(), &a + &b);
+        assert_eq!(sa.kron(&sb).to_dense(), a.kron(&b));
+        assert_eq!(sa.scaled(c(0.0, 2.0)).to_dense(), a.scaled(c(0.0, 2.0)));
+        assert_eq!(SparseMatrix::identity(2).kron(&sa).nnz(), 4);
+    }
+}
@@ -0,0 +1,440 @@
+use std::fmt;
+use super::circuit::{Circuit, Instruction};
+use super::gates::Gate;
+use super::pauli::Pauli;
+use super::pauli_string::PauliString;
+use super::rng::Rng;
+
+/// n-qubit Clifford as its Aaronson–Gottesman tableau: row i is the image
+/// of X_i (a destabilizer), row n + i the image of Z_i (a stabilizer), each
+/// stored as x and z bits per qubit plus a sign bit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliffordTableau {
+    num_qubits: usize,
+    x: Vec<Vec<bool>>,
+    z: Vec<Vec<bool>>,
+    sign: Vec<bool>,
+}
+
+fn identity_matrix(n: usize) -> Vec<Vec<bool>> {
+    (0..n).map(|i| (0..n).map(|j| i == j).collect()).collect()
+}
+
+/// (row, col) pairs strictly below the diagonal, row by row
+fn below_diagonal(n: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..n).flat_map(|row| (0..row).map(move |col| (row, col)))
+}
+
+/// random lower-triangular bits below the diagonal, mirrored when `symmetric`
+fn fill_lower(matrix: &mut [Vec<bool>], symmetric: bool, rng: &mut Rng) {
+    for (i, j) in below_diagonal(matrix.len()) {
+        let bit = rng.gen_bool(0.5);
+        matrix[i][j] = bit;
+        if symmetric {
+            matrix[j][i] = bit;
+        }
+    }
+}
+
+/// inverse of a unit lower-triangular matrix over GF(2)
+fn invert_lower(matrix: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let n = matrix.len();
+    let mut inverse = identity_matrix(n);
+    for (row, col) in below_diagonal(n) {
+        inverse[row][col] = (col..row).fold(false, |acc, k| {
+            acc ^ (matrix[row][k] & inverse[k][col])
+        });
+    }
+    inverse
+}
+
+fn multiply(a: &[Vec<bool>], b: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    a.iter()
+        .map(|row| {
+            (0..b[0].len())
+                .map(|j| row.iter().zip(b).fold(false, |acc, (&r, col)| acc ^ (r & col[j])))
+                .collect()
+        })
+        .collect()
+}
+
+/// the 2n × 2n symplectic block [[Δ, 0], [ΓΔ, (Δ⁻¹)ᵀ]]
+fn symplectic_block(delta: &[Vec<bool>], gamma: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let n = delta.len();
+    let product = multiply(gamma, delta);
+    let inverse = invert_lower(delta);
+    (0..2 * n)
+        .map(|row| {
+            (0..2 * n)
+                .map(|col| match (row < n, col < n) {
+                    (true, true) => delta[row][col],
+                    (true, false) => false,
+                    (false, true) => product[row - n][col],
+                    (false, false) => inverse[col - n][row - n],
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// quantum Mallows sample: which qubits get a Hadamard and the permutation
+fn sample_mallows(n: usize, rng: &mut Rng) -> (Vec<bool>, Vec<usize>) {
+    let mut hadamard = vec![false; n];
+    let mut permutation = Vec::with_capacity(n);
+    let mut remaining: Vec<usize> = (0..n).collect();
+    for (i, h) in hadamard.iter_mut().enumerate() {
+        let m = (n - i) as i32;
+        let r = rng.next_f64();
+        let eps = 4f64.powi(-m);
+        let index = -((r + (1.0 - r) * eps).log2().ceil()) as i32;
+        *h = index < m;
+        let k = if index < m { index } else { 2 * m - index - 1 };
+        permutation.push(remaining.remove(k as usize));
+    }
+    (hadamard, permutation)
+}
+
+impl CliffordTableau {
+    pub fn identity(num_qubits: usize) -> Self {
+        let n = num_qubits;
+        Self {
+            num_qubits,
+            x: (0..2 * n).map(|row| (0..n).map(|q| row == q).collect()).collect(),
+            z: (0..2 * n).map(|row| (0..n).map(|q| row == n + q).collect()).collect(),
+            sign: vec![false; 2 * n],
+        }
+    }
+
+    /// uniformly random element of the n-qubit Clifford group (up to global
+    /// phase), via the Bravyi–Maslov canonical form F₁·H·S·F₂
+    pub fn random(num_qubits: usize, rng: &mut Rng) -> Self {
+        let n = num_qubits;
+        let (hadamard, permutation) = sample_mallows(n, rng);
+        let mut layers = Vec::with_capacity(2);
+        for _ in 0..2 {
+            let mut gamma = vec![vec![false; n]; n];
+            for (i, row) in gamma.iter_mut().enumerate() {
+                row[i] = rng.gen_bool(0.5);
+            }
+            fill_lower(&mut gamma, true, rng);
+            let mut delta = identity_matrix(n);
+            fill_lower(&mut delta, false, rng);
+            layers.push(symplectic_block(&delta, &gamma));
+        }
+        let (first, second) = (&layers[0], &layers[1]);
+        let mut table: Vec<Vec<bool>> = (0..2 * n)
+            .map(|row| {
+                let source = if row < n { permutation[row] } else { n + permutation[row - n] };
+                second[source].clone()
+            })
+            .collect();
+        for q in (0..n).filter(|&q| hadamard[q]) {
+            table.swap(q, n + q);
+        }
+        let table = multiply(first, &table);
+        Self {
+            num_qubits,
+            x: table.iter().map(|row| row[..n].to_vec()).collect(),
+            z: table.iter().map(|row| row[n..].to_vec()).collect(),
+            sign: (0..2 * n).map(|_| rng.gen_bool(0.5)).collect(),
+        }
+    }
+
+    /// tableau of a circuit of H, S, S†, Paulis, CX, CZ and SWAP
+    pub fn from_circuit(circuit: &Circuit) -> Result<Self, String> {
+        let mut tableau = Self::identity(circuit.num_qubits());
+        for inst in circuit.instructions() {
+            tableau.apply_instruction(inst)?;
+        }
+        Ok(tableau)
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    fn row(&self, row: usize) -> PauliString {
+        let paulis = (0..self.num_qubits)
+            .map(|q| match (self.x[row][q], self.z[row][q]) {
+                (false, false) => Pauli::I,
+                (true, false) => Pauli::X,
+                (true, true) => Pauli::Y,
+                (false, true) => Pauli::Z,
+            })
+            .collect();
+        PauliString::new(paulis).with_phase(if self.sign[row] { 2 } else { 0 })
+    }
+
+    /// image of X_q under the Clifford
+    pub fn destabilizer(&self, q: usize) -> PauliString {
+        self.row(q)
+    }
+
+    /// image of Z_q under the Clifford
+    pub fn stabilizer(&self, q: usize) -> PauliString {
+        self.row(self.num_qubits + q)
+    }
+
+    /// U·P·U†, built from the images of each factor (Y = i·X·Z)
+    pub fn conjugate(&self, pauli: &PauliString) -> PauliString {
+        pauli.terms().into_iter().fold(
+            PauliString::identity(self.num_qubits).with_phase(pauli.phase()),
+            |image, (q, p)| {
+                let factor = match p {
+                    Pauli::X => self.destabilizer(q),
+                    Pauli::Z => self.stabilizer(q),
+                    _ => {
+                        let xz = &self.destabilizer(q) * &self.stabilizer(q);
+                        let phase = xz.phase() + 1;
+                        xz.with_phase(phase)
+                    }
+                };
+                &image * &factor
+            },
+        )
+    }
+
+    /// symplectic form: images of X_i and Z_j anticommute exactly when i = j,
+    /// and every other pair commutes
+    pub fn is_valid(&self) -> bool {
+        let n = self.num_qubits;
+        (0..2 * n).all(|a| {
+            (a + 1..2 * n).all(|b| {
+                let product = (0..n).fold(false, |acc, q| {
+                    acc ^ (self.x[a][q] & self.z[b][q]) ^ (self.z[a][q] & self.x[b][q])
+                });
+                product == (b == a + n)
+            })
+        })
+    }
+
+    pub fn apply_h(&mut self, q: usize) -> &mut Self {
+        for row in 0..2 * self.num_qubits {
+            self.sign[row] ^= self.x[row][q] & self.z[row][q];
+            std::mem::swap(&mut self.x[row][q], &mut self.z[row][q]);
+        }
+        self
+    }
+
+    pub fn apply_s(&mut self, q: usize) -> &mut Self {
+        for row in 0..2 * self.num_qubits {
+            self.sign[row] ^= self.x[row][q] & self.z[row][q];
+            self.z[row][q] ^= self.x[row][q];
+        }
+        self
+    }
+
+    pub fn apply_cx(&mut self, control: usize, target: usize) -> &mut Self {
+        for row in 0..2 * self.num_qubits {
+            let (xc, zc) = (self.x[row][control], self.z[row][control]);
+            let (xt, zt) = (self.x[row][target], self.z[row][target]);
+            self.sign[row] ^= xc & zt & !(xt ^ zc);
+            self.x[row][target] ^= xc;
+            self.z[row][control] ^= zt;
+        }
+        self
+    }
+
+    pub fn apply_swap(&mut self, a: usize, b: usize) -> &mut Self {
+        for row in 0..2 * self.num_qubits {
+            self.x[row].swap(a, b);
+            self.z[row].swap(a, b);
+        }
+        self
+    }
+
+    /// conjugate by a Pauli, which only flips the signs of anticommuting rows
+    pub fn apply_pauli(&mut self, q: usize, pauli: Pauli) -> &mut Self {
+        for row in 0..2 * self.num_qubits {
+            let flips = match pauli {
+                Pauli::I => false,
+                Pauli::X => self.z[row][q],
+                Pauli::Z => self.x[row][q],
+                Pauli::Y => self.x[row][q] ^ self.z[row][q],
+            };
+            self.sign[row] ^= flips;
+        }
+        self
+    }
+
+    /// compose a Clifford gate after the tableau
+    pub fn apply_instruction(&mut self, inst: &Instruction) -> Result<(), String> {
+        let q = &inst.qubits;
+        match inst.gate {
+            Gate::I => {}
+            Gate::X => {
+                self.apply_pauli(q[0], Pauli::X);
+            }
+            Gate::Y => {
+                self.apply_pauli(q[0], Pauli::Y);
+            }
+            Gate::Z => {
+                self.apply_pauli(q[0], Pauli::Z);
+            }
+            Gate::H => {
+                self.apply_h(q[0]);
+            }
+            Gate::S => {
+                self.apply_s(q[0]);
+            }
+            Gate::Sdg => {
+                self.apply_s(q[0]).apply_pauli(q[0], Pauli::Z);
+            }
+            Gate::Cx => {
+                self.apply_cx(q[0], q[1]);
+            }
+            Gate::Cz => {
+                self.apply_h(q[1]).apply_cx(q[0], q[1]).apply_h(q[1]);
+            }
+            Gate::Swap => {
+                self.apply_swap(q[0], q[1]);
+            }
+            ref gate => return Err(format!("{} is not a tableau Clifford gate", gate.name())),
+        }
+        Ok(())
+    }
+
+    /// circuit of H, S, S†, CX, SWAP and Paulis implementing the tableau
+    ///
+    /// Gates are composed onto a copy until it reduces to the identity, one
+    /// qubit at a time; the circuit is the inverse of that reduction.
+    pub fn to_circuit(&self) -> Circuit {
+        let n = self.num_qubits;
+        let mut work = self.clone();
+        let mut reduction: Vec<(Gate, Vec<usize>)> = Vec::new();
+        let mut apply = |work: &mut Self, gate: Gate, qubits: &[usize]| {
+            let inst = Instruction { gate, qubits: qubits.to_vec() };
+            work.apply_instruction(&inst).expect("reduction uses Clifford gates");
+            reduction.push((inst.gate, inst.qubits));
+        };
+        for i in 0..n {
+            // destabilizer i has support only on qubits ≥ i; move an X onto i
+            if !work.x[i][i] {
+                match (i..n).find(|&j| work.x[i][j]) {
+                    Some(j) => apply(&mut work, Gate::Swap, &[i, j]),
+                    None => {
+                        let j = (i..n).find(|&j| work.z[i][j]).expect("valid tableau");
+                        apply(&mut work, Gate::H, &[j]);
+                        if j != i {
+                            apply(&mut work, Gate::Swap, &[i, j]);
+                        }
+                    }
+                }
+            }
+            // clear every other qubit, leaving ±X_i
+            for j in i + 1..n {
+                if work.x[i][j] && work.z[i][j] {
+                    apply(&mut work, Gate::S, &[j]);
+                }
+                if work.z[i][j] {
+                    apply(&mut work, Gate::H, &[j]);
+                }
+                if work.x[i][j] {
+                    apply(&mut work, Gate::Cx, &[i, j]);
+                }
+            }
+            if work.z[i][i] {
+                apply(&mut work, Gate::S, &[i]);
+            }
+            // stabilizer i: move its other qubits onto Z and fold them into i
+            let row = n + i;
+            for j in i + 1..n {
+                if work.x[row][j] && work.z[row][j] {
+                    apply(&mut work, Gate::S, &[j]);
+                }
+                if work.x[row][j] {
+                    apply(&mut work, Gate::H, &[j]);
+                }
+                if work.z[row][j] {
+                    apply(&mut work, Gate::Cx, &[j, i]);
+                }
+            }
+            if work.x[row][i] {
+                // √X = H·S·H fixes X and takes Y to Z
+                apply(&mut work, Gate::H, &[i]);
+                apply(&mut work, Gate::S, &[i]);
+                apply(&mut work, Gate::H, &[i]);
+            }
+            if work.sign[i] {
+                apply(&mut work, Gate::Z, &[i]);
+            }
+            if work.sign[row] {
+                apply(&mut work, Gate::X, &[i]);
+            }
+        }
+        let mut circuit = Circuit::new(n);
+        for (gate, qubits) in reduction.into_iter().rev() {
+            circuit.push(gate.inverse(), &qubits);
+        }
+        circuit
+    }
+}
+
+impl fmt::Display for CliffordTableau {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for q in 0..self.num_qubits {
+            writeln!(f, "X{} -> {}", q, self.destabilizer(q))?;
+        }
+        for q in 0..self.num_qubits {
+            write!(f, "Z{} -> {}", q, self.stabilizer(q))?;
+            if q + 1 < self.num_qubits {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use crate::simulator::matrix::Matrix;
+
+    #[test]
+    fn test_random_tableaux_are_symplectic_and_round_trip() {
+        let mut rng = Rng::seed_from_u64(84);
+        for n in 1..=6 {
+            for _ in 0..20 {
+                let tableau = CliffordTableau::random(n, &mut rng);
+                assert!(tableau.is_valid(), "{}", tableau);
+                let circuit = tableau.to_circuit();
+                assert_eq!(CliffordTableau::from_circuit(&circuit).unwrap(), tableau);
+            }
+        }
+    }
+
+    #[test]
+    fn test_circuit_conjugates_paulis_as_the_tableau_says() {
+        let mut rng = Rng::seed_from_u64(85);
+        let tableau = CliffordTableau::random(3, &mut rng);
+        let u = tableau.to_circuit().to_unitary();
+        let conjugate = |p: &PauliString| &(&u * &p.matrix()) * &u.dagger();
+        for q in 0..3 {
+            let images = [
+                (PauliString::single(3, q, Pauli::X), tableau.destabilizer(q)),
+                (PauliString::single(3, q, Pauli::Z), tableau.stabilizer(q)),
+            ];
+            for (pauli, image) in images {
+                let (lhs, rhs): (Matrix, Matrix) = (conjugate(&pauli), image.matrix());
+                crate::assert_unitary_eq!(lhs, rhs);
+            }
+        }
+        // signs included: compare entries exactly rather than up to phase
+        let pauli: PauliString = "-XYZ".parse().unwrap();
+        let (lhs, rhs) = (conjugate(&pauli), tableau.conjugate(&pauli).matrix());
+        let diff = lhs.as_slice().iter().zip(rhs.as_slice()).map(|(a, b)| (a - b).norm());
+        assert!(diff.fold(0.0, f64::max) < 1e-9);
+    }
+
+    #[test]
+    fn test_single_qubit_samples_are_uniform_over_24_elements() {
+        let mut rng = Rng::seed_from_u64(86);
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for _ in 0..24_000 {
+            *counts.entry(CliffordTableau::random(1, &mut rng).to_string()).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 24);
+        assert!(counts.values().all(|&c| (c as f64 - 1000.0).abs() < 150.0), "{:?}", counts);
+    }
+}
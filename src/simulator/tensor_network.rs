@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+use num_complex::Complex64;
+use super::circuit::Circuit;
+use super::gates::{x_matrix, z_matrix, Gate, Matrix2};
+
+/// default bound on intermediate tensors (2^24 entries)
+pub const MAX_TENSOR_RANK: usize = 24;
+
+/// dense tensor over binary indices; the first index is the most significant
+/// bit of the flat position
+#[derive(Debug, Clone, PartialEq)]
+struct Tensor {
+    indices: Vec<usize>,
+    data: Vec<Complex64>,
+}
+
+impl Tensor {
+    fn vector(index: usize, bit: bool) -> Tensor {
+        let (zero, one) = (Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0));
+        let data = if bit { vec![zero, one] } else { vec![one, zero] };
+        Tensor { indices: vec![index], data }
+    }
+
+    fn rank(&self) -> usize {
+        self.indices.len()
+    }
+
+    fn conj(&self) -> Tensor {
+        Tensor { indices: self.indices.clone(), data: self.data.iter().map(|a| a.conj()).collect() }
+    }
+
+    /// sum over every index the two tensors share
+    fn contract(&self, other: &Tensor) -> Tensor {
+        let shared: Vec<usize> =
+            self.indices.iter().copied().filter(|i| other.indices.contains(i)).collect();
+        let free_a: Vec<usize> =
+            self.indices.iter().copied().filter(|i| !shared.contains(i)).collect();
+        let free_b: Vec<usize> =
+            other.indices.iter().copied().filter(|i| !shared.contains(i)).collect();
+        // flat-position bit of every index in each tensor
+        let bit = |t: &Tensor, index: usize| {
+            let p = t.indices.iter().position(|&i| i == index).expect("index present");
+            1usize << (t.rank() - 1 - p)
+        };
+        let spread = |t: &Tensor, indices: &[usize], value: usize| {
+            indices.iter().enumerate().fold(0, |acc, (k, &i)| {
+                if value >> (indices.len() - 1 - k) & 1 == 1 { acc | bit(t, i) } else { acc }
+            })
+        };
+        let positions = |t: &Tensor, indices: &[usize]| -> Vec<usize> {
+            (0..1 << indices.len()).map(|v| spread(t, indices, v)).collect()
+        };
+        let (shared_a, shared_b) = (positions(self, &shared), positions(other, &shared));
+        let (free_a_pos, free_b_pos) = (positions(self, &free_a), positions(other, &free_b));
+        let mut data = Vec::with_capacity(free_a_pos.len() * free_b_pos.len());
+        for &a in &free_a_pos {
+            for &b in &free_b_pos {
+                let sum = shared_a
+                    .iter()
+                    .zip(&shared_b)
+                    .map(|(&sa, &sb)| self.data[a | sa] * other.data[b | sb])
+                    .sum();
+                data.push(sum);
+            }
+        }
+        Tensor { indices: free_a.into_iter().chain(free_b).collect(), data }
+    }
+}
+
+/// rank of the result of contracting tensors with these index lists
+fn contracted_rank(a: &[usize], b: &[usize]) -> usize {
+    let shared = a.iter().filter(|i| b.contains(i)).count();
+    a.len() + b.len() - 2 * shared
+}
+
+/// circuit applied to |0…0⟩ as a network of gate tensors with one open index
+/// per qubit
+///
+/// Queries close the open indices and contract greedily, always merging the
+/// pair that grows the network least, so cost follows the treewidth of the
+/// circuit rather than its width: shallow circuits on many qubits stay cheap.
+#[derive(Debug, Clone)]
+pub struct TensorNetwork {
+    tensors: Vec<Tensor>,
+    outputs: Vec<usize>,
+    next_index: usize,
+    max_rank: usize,
+}
+
+impl TensorNetwork {
+    pub fn from_circuit(circuit: &Circuit) -> Self {
+        let n = circuit.num_qubits();
+        let mut network = Self {
+            tensors: Vec::new(),
+            outputs: (0..n).collect(),
+            next_index: n,
+            max_rank: MAX_TENSOR_RANK,
+        };
+        for q in 0..n {
+            network.tensors.push(Tensor::vector(q, false));
+        }
+        for inst in circuit.instructions() {
+            let q = &inst.qubits;
+            match inst.gate {
+                Gate::I => {}
+                Gate::Swap => network.outputs.swap(q[0], q[1]),
+                Gate::Cx => network.push_controlled(&q[..1], q[1], x_matrix()),
+                Gate::Cz => network.push_controlled(&q[..1], q[1], z_matrix()),
+                Gate::Mcx(k) => network.push_controlled(&q[..k], q[k], x_matrix()),
+                Gate::Mcz(k) => network.push_controlled(&q[..k], q[k], z_matrix()),
+                Gate::Mcu(k, ref operator) => {
+                    let matrix = operator.to_matrix2().expect("one-qubit operator");
+                    network.push_controlled(&q[..k], q[k], matrix);
+                }
+                ref gate => {
+                    let matrix = gate.matrix2().expect("single-qubit gate has a matrix");
+                    network.push_controlled(&[], q[0], matrix);
+                }
+            }
+        }
+        network
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn num_tensors(&self) -> usize {
+        self.tensors.len()
+    }
+
+    /// largest intermediate rank a query may create before giving up
+    pub fn set_max_rank(&mut self, rank: usize) -> &mut Self {
+        self.max_rank = rank;
+        self
+    }
+
+    /// tensor [outputs…, inputs…] of a controlled 2×2 gate
+    fn push_controlled(&mut self, controls: &[usize], target: usize, matrix: Matrix2) {
+        let qubits: Vec<usize> = controls.iter().copied().chain([target]).collect();
+        let k = qubits.len();
+        let inputs: Vec<usize> = qubits.iter().map(|&q| self.outputs[q]).collect();
+        let outputs: Vec<usize> = (0..k).map(|j| self.next_index + j).collect();
+        self.next_index += k;
+        let mut data = vec![Complex64::new(0.0, 0.0); 1 << (2 * k)];
+        let control_mask = (1 << k) - 2;
+        for input in 0..1usize << k {
+            // bit k−1−j of `input` is qubits[j]; the target is bit 0
+            if input & control_mask != control_mask {
+                data[(input << k) | input] = Complex64::new(1.0, 0.0);
+                continue;
+            }
+            let t = input & 1;
+            for (out_t, row) in matrix.iter().enumerate() {
+                let output = (input & !1) | out_t;
+                data[(output << k) | input] = row[t];
+            }
+        }
+        for (j, &q) in qubits.iter().enumerate() {
+            self.outputs[q] = outputs[j];
+        }
+        self.tensors.push(Tensor { indices: outputs.into_iter().chain(inputs).collect(), data });
+    }
+
+    /// largest tensor rank an amplitude query would create, found by running
+    /// the greedy contraction on index lists alone
+    pub fn contraction_width(&self) -> usize {
+        let mut lists: Vec<Vec<usize>> = self.tensors.iter().map(|t| t.indices.clone()).collect();
+        lists.extend(self.outputs.iter().map(|&i| vec![i]));
+        let mut width = 0;
+        loop {
+            let views: Vec<&[usize]> = lists.iter().map(Vec::as_slice).collect();
+            let Some((rank, a, b)) = pick_pair(&views) else { break };
+            width = width.max(rank);
+            let lb = lists.swap_remove(b);
+            let la = lists.swap_remove(a);
+            let shared = |i: &usize| la.contains(i) && lb.contains(i);
+            lists.push(la.iter().chain(&lb).copied().filter(|i| !shared(i)).collect());
+        }
+        width
+    }
+
+    /// ⟨bits|C|0…0⟩, `bits[q]` being qubit q
+    pub fn amplitude(&self, bits: &[bool]) -> Result<Complex64, String> {
+        assert_eq!(bits.len(), self.num_qubits(), "one bit per qubit");
+        let mut tensors = self.tensors.clone();
+        for (q, &b) in bits.iter().enumerate() {
+            tensors.push(Tensor::vector(self.outputs[q], b));
+        }
+        contract_all(tensors, self.max_rank)
+    }
+
+    /// probability that `qubits` read `outcome`, summing over all other qubits
+    ///
+    /// Contracts the network against its conjugate with the unmeasured
+    /// outputs joined, so no amplitude over the traced qubits is enumerated.
+    pub fn marginal_probability(&self, qubits: &[usize], outcome: &[bool]) -> Result<f64, String> {
+        assert_eq!(qubits.len(), outcome.len(), "one outcome bit per measured qubit");
+        let offset = self.next_index;
+        let shift = |i: usize| {
+            if self.outputs.contains(&i) && !is_measured(self, qubits, i) { i } else { i + offset }
+        };
+        let mut tensors = self.tensors.clone();
+        for t in &self.tensors {
+            let mut c = t.conj();
+            c.indices = c.indices.iter().map(|&i| shift(i)).collect();
+            tensors.push(c);
+        }
+        for (&q, &b) in qubits.iter().zip(outcome) {
+            tensors.push(Tensor::vector(self.outputs[q], b));
+            tensors.push(Tensor::vector(self.outputs[q] + offset, b));
+        }
+        Ok(contract_all(tensors, self.max_rank)?.re)
+    }
+}
+
+fn is_measured(network: &TensorNetwork, qubits: &[usize], index: usize) -> bool {
+    qubits.iter().any(|&q| network.outputs[q] == index)
+}
+
+/// contract a closed network to a scalar, pair by pair
+/// next pair to contract: the one adding fewest entries, then the smaller
+/// result; returns (result rank, first, second)
+fn pick_pair(indices: &[&[usize]]) -> Option<(usize, usize, usize)> {
+    let mut owners: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (t, list) in indices.iter().enumerate() {
+        for &i in *list {
+            owners.entry(i).or_default().push(t);
+        }
+    }
+    owners
+        .values()
+        .filter(|o| o.len() == 2)
+        .map(|o| {
+            let (a, b) = (indices[o[0]], indices[o[1]]);
+            let rank = contracted_rank(a, b);
+            let growth = 2f64.powi(rank as i32) - 2f64.powi(a.len() as i32)
+                - 2f64.powi(b.len() as i32);
+            (growth, rank, o[0].min(o[1]), o[0].max(o[1]))
+        })
+        .min_by(|x, y| x.partial_cmp(y).expect("growth is finite"))
+        .map(|(_, rank, a, b)| (rank, a, b))
+}
+
+/// contract a closed network to a scalar, pair by pair
+fn contract_all(mut tensors: Vec<Tensor>, max_rank: usize) -> Result<Complex64, String> {
+    let mut scalar = Complex64::new(1.0, 0.0);
+    loop {
+        let lists: Vec<&[usize]> = tensors.iter().map(|t| t.indices.as_slice()).collect();
+        let Some((rank, a, b)) = pick_pair(&lists) else { break };
+        if rank > max_rank {
+            return Err(format!(
+                "contraction needs a rank-{} tensor, above the limit of {}",
+                rank, max_rank
+            ));
+        }
+        let tb = tensors.swap_remove(b);
+        let ta = tensors.swap_remove(a);
+        tensors.push(ta.contract(&tb));
+    }
+    for tensor in tensors {
+        assert_eq!(tensor.rank(), 0, "network has open indices");
+        scalar *= tensor.data[0];
+    }
+    Ok(scalar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::register::Register;
+    use crate::simulator::rng::Rng;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    fn bits_of(index: usize, n: usize) -> Vec<bool> {
+        (0..n).map(|q| index >> q & 1 == 1).collect()
+    }
+
+    #[test]
+    fn test_matches_statevector() {
+        let mut rng = Rng::seed_from_u64(63);
+        let mut circuit = Circuit::new(5);
+        for _ in 0..40 {
+            let (a, b, c) = (rng.gen_range(5), rng.gen_range(5), rng.gen_range(5));
+            match rng.gen_range(6) {
+                0 => circuit.h(a),
+                1 => circuit.t(a),
+                2 => circuit.rx(rng.next_f64() * 3.0, a),
+                3 if a != b => circuit.cx(b, a),
+                4 if a != b && b != c && a != c => circuit.mcz(&[c, a], b),
+                _ if a != b => circuit.swap(a, b),
+                _ => circuit.s(a),
+            };
+        }
+        let network = TensorNetwork::from_circuit(&circuit);
+        let mut register = Register::new(5);
+        register.apply_circuit(&circuit);
+        for index in [0, 7, 19, 31] {
+            let amplitude = network.amplitude(&bits_of(index, 5)).unwrap();
+            assert!((amplitude - register.amplitudes()[index]).norm() < 1e-10);
+        }
+        let p = network.marginal_probability(&[1, 3], &[true, false]).unwrap();
+        let expected: f64 = (0..32)
+            .filter(|i| i >> 1 & 1 == 1 && i >> 3 & 1 == 0)
+            .map(|i| register.amplitudes()[i].norm_sqr())
+            .sum();
+        assert!((p - expected).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_shallow_wide_circuit() {
+        // 80-qubit GHZ ladder: one chain of small tensors
+        let n = 80;
+        let mut circuit = Circuit::new(n);
+        circuit.h(0);
+        for q in 0..n - 1 {
+            circuit.cx(q, q + 1);
+        }
+        let network = TensorNetwork::from_circuit(&circuit);
+        let ones = network.amplitude(&vec![true; n]).unwrap();
+        assert!((ones.re - FRAC_1_SQRT_2).abs() < 1e-12);
+        let mut mixed = vec![false; n];
+        mixed[40] = true;
+        assert!(network.amplitude(&mixed).unwrap().norm() < 1e-12);
+        let p = network.marginal_probability(&[0, 79], &[true, true]).unwrap();
+        assert!((p - 0.5).abs() < 1e-12);
+        assert!(network.contraction_width() <= 3);
+    }
+
+    #[test]
+    fn test_rank_limit_is_reported() {
+        let n = 16;
+        let mut circuit = Circuit::new(n);
+        for q in 0..n {
+            circuit.h(q);
+        }
+        // all-to-all CZs leave no cheap contraction order
+        for a in 0..n {
+            for b in a + 1..n {
+                circuit.cz(a, b);
+            }
+            circuit.t(a).h(a);
+        }
+        let mut network = TensorNetwork::from_circuit(&circuit);
+        network.set_max_rank(6);
+        let error = network.amplitude(&vec![false; n]).unwrap_err();
+        assert!(error.contains("above the limit of 6"));
+    }
+}
@@ -0,0 +1,158 @@
+use num_complex::Complex64;
+#[cfg(test)]
+use super::circuit::Circuit;
+use super::gates::Matrix2;
+use super::operator::Operator;
+use super::register::Register;
+use super::single_qubit::SingleQubit;
+
+/// default tolerance used by the assertion macros
+pub const DEFAULT_TOLERANCE: f64 = 1e-9;
+
+/// approximate equality up to a global phase
+pub trait ApproxEq {
+    fn approx_eq(&self, other: &Self, tolerance: f64) -> bool;
+}
+
+/// elementwise comparison after aligning the global phase of `b` onto `a`
+pub fn approx_eq_up_to_phase(a: &[Complex64], b: &[Complex64], tolerance: f64) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    // align on the largest entry of `a` so the phase estimate is well conditioned
+    let pivot = a
+        .iter()
+        .enumerate()
+        .max_by(|x, y| x.1.norm_sqr().total_cmp(&y.1.norm_sqr()))
+        .map(|(k, _)| k);
+    let phase = match pivot {
+        Some(k) if a[k].norm() > tolerance && b[k].norm() > tolerance => {
+            let ratio = a[k] / b[k];
+            ratio / ratio.norm()
+        }
+        _ => Complex64::new(1.0, 0.0),
+    };
+    a.iter()
+        .zip(b)
+        .all(|(x, y)| (x - y * phase).norm() <= tolerance)
+}
+
+/// two-qubit Bell-state fixture, H then CNOT
+#[cfg(test)]
+pub(crate) fn bell() -> Circuit {
+    let mut circuit = Circuit::new(2);
+    circuit.h(0).cx(0, 1);
+    circuit
+}
+
+/// |Φ+⟩ prepared by [`bell`]
+#[cfg(test)]
+pub(crate) fn bell_state() -> Register {
+    let mut register = Register::new(2);
+    register.apply_circuit(&bell());
+    register
+}
+
+impl ApproxEq for SingleQubit {
+    fn approx_eq(&self, other: &Self, tolerance: f64) -> bool {
+        SingleQubit::approx_eq(self, other, tolerance)
+    }
+}
+
+impl ApproxEq for Register {
+    fn approx_eq(&self, other: &Self, tolerance: f64) -> bool {
+        Register::approx_eq(self, other, tolerance)
+    }
+}
+
+impl ApproxEq for Matrix2 {
+    fn approx_eq(&self, other: &Self, tolerance: f64) -> bool {
+        let a: Vec<Complex64> = self.iter().flatten().copied().collect();
+        let b: Vec<Complex64> = other.iter().flatten().copied().collect();
+        approx_eq_up_to_phase(&a, &b, tolerance)
+    }
+}
+
+impl ApproxEq for Operator {
+    fn approx_eq(&self, other: &Self, tolerance: f64) -> bool {
+        self.matrix().approx_eq(&other.matrix(), tolerance)
+    }
+}
+
+/// assert two states are equal up to global phase
+///
+/// `assert_state_eq!(a, b)` or `assert_state_eq!(a, b, tolerance)`
+#[macro_export]
+macro_rules! assert_state_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_state_eq!($left, $right, $crate::simulator::testing::DEFAULT_TOLERANCE)
+    };
+    ($left:expr, $right:expr, $tol:expr $(,)?) => {{
+        let (left, right) = (&$left, &$right);
+        if !$crate::simulator::testing::ApproxEq::approx_eq(left, right, $tol) {
+            panic!(
+                "assertion failed: states differ beyond global phase (tol {})\n  \
+                 left: {:?}\n right: {:?}",
+                $tol, left, right
+            );
+        }
+    }};
+}
+
+/// assert two unitaries are equal up to global phase
+///
+/// `assert_unitary_eq!(a, b)` or `assert_unitary_eq!(a, b, tolerance)`
+#[macro_export]
+macro_rules! assert_unitary_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_unitary_eq!($left, $right, $crate::simulator::testing::DEFAULT_TOLERANCE)
+    };
+    ($left:expr, $right:expr, $tol:expr $(,)?) => {{
+        let (left, right) = (&$left, &$right);
+        if !$crate::simulator::testing::ApproxEq::approx_eq(left, right, $tol) {
+            panic!(
+                "assertion failed: unitaries differ beyond global phase (tol {})\n  \
+                 left: {:?}\n right: {:?}",
+                $tol, left, right
+            );
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::*;
+
+    #[test]
+    fn test_global_phase_ignored() {
+        let a = SingleQubit::from_amplitudes(Complex64::new(0.6, 0.0), Complex64::new(0.8, 0.0));
+        let phase = Complex64::new(0.0, 1.0);
+        let b = SingleQubit::from_amplitudes(a.alpha * phase, a.beta * phase);
+        assert!(a.approx_eq(&b, 1e-12));
+        assert_state_eq!(a, b);
+    }
+
+    #[test]
+    fn test_relative_phase_detected() {
+        let mut plus = SingleQubit::new();
+        h_gate(&mut plus);
+        let mut minus = SingleQubit::new_one();
+        h_gate(&mut minus);
+        assert!(!plus.approx_eq(&minus, 1e-6));
+    }
+
+    #[test]
+    fn test_unitary_identities() {
+        // HZH = X, and Z = RZ(π) up to global phase
+        let hzh = matmul(&matmul(&h_matrix(), &z_matrix()), &h_matrix());
+        assert_unitary_eq!(hzh, x_matrix());
+        assert_unitary_eq!(rz_matrix(std::f64::consts::PI), z_matrix());
+    }
+
+    #[test]
+    #[should_panic(expected = "unitaries differ")]
+    fn test_unitary_mismatch_panics() {
+        assert_unitary_eq!(x_matrix(), z_matrix());
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use super::circuit::Circuit;
+use super::gates::Gate;
+
+/// gate durations in nanoseconds by arity, with per-name overrides
+#[derive(Debug, Clone, PartialEq)]
+pub struct GateTimes {
+    pub single_qubit: f64,
+    pub two_qubit: f64,
+    /// gates on three or more qubits
+    pub multi_qubit: f64,
+    overrides: BTreeMap<&'static str, f64>,
+}
+
+impl GateTimes {
+    pub fn uniform(single_qubit: f64, two_qubit: f64, multi_qubit: f64) -> Self {
+        Self { single_qubit, two_qubit, multi_qubit, overrides: BTreeMap::new() }
+    }
+
+    /// typical transmon figures: 35 ns pulses, 300 ns entanglers, 600 ns
+    /// Toffoli-class gates, and Z rotations done virtually in 0 ns
+    pub fn superconducting() -> Self {
+        ["z", "s", "sdg", "t", "tdg", "rz", "p"]
+            .into_iter()
+            .fold(Self::uniform(35.0, 300.0, 600.0), |times, name| times.with_gate(name, 0.0))
+    }
+
+    /// fixed duration for every gate named `name` (see `Gate::name`)
+    pub fn with_gate(mut self, name: &'static str, nanoseconds: f64) -> Self {
+        assert!(nanoseconds >= 0.0, "negative duration");
+        self.overrides.insert(name, nanoseconds);
+        self
+    }
+
+    pub fn duration(&self, gate: &Gate) -> f64 {
+        if let Some(&ns) = self.overrides.get(gate.name()) {
+            return ns;
+        }
+        match gate.num_qubits() {
+            1 => self.single_qubit,
+            2 => self.two_qubit,
+            _ => self.multi_qubit,
+        }
+    }
+}
+
+impl Default for GateTimes {
+    fn default() -> Self {
+        Self::superconducting()
+    }
+}
+
+/// where a gate sits within its slack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedulePolicy {
+    /// as soon as its qubits are free
+    Asap,
+    /// as late as the gates after it allow
+    Alap,
+}
+
+/// timing model plus placement policy
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub times: GateTimes,
+    pub policy: SchedulePolicy,
+}
+
+impl Schedule {
+    pub fn asap(times: GateTimes) -> Self {
+        Self { times, policy: SchedulePolicy::Asap }
+    }
+
+    pub fn alap(times: GateTimes) -> Self {
+        Self { times, policy: SchedulePolicy::Alap }
+    }
+}
+
+/// start time and duration of one instruction, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timing {
+    pub start: f64,
+    pub duration: f64,
+}
+
+impl Timing {
+    pub fn end(&self) -> f64 {
+        self.start + self.duration
+    }
+}
+
+/// stretch of time a qubit spends waiting between operations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdleWindow {
+    pub qubit: usize,
+    pub start: f64,
+    pub end: f64,
+    /// instruction index the window precedes, or `None` at the end
+    pub before: Option<usize>,
+}
+
+/// circuit with a start time for every instruction
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledCircuit {
+    pub timings: Vec<Timing>,
+    pub total: f64,
+    qubits: Vec<Vec<usize>>,
+    num_qubits: usize,
+}
+
+impl ScheduledCircuit {
+    /// explicit `timings` for the instructions of `circuit`, e.g. from a pass
+    /// that places extra gates itself
+    pub fn new(circuit: &Circuit, timings: Vec<Timing>, total: f64) -> Self {
+        assert_eq!(timings.len(), circuit.len(), "one timing per instruction");
+        Self {
+            timings,
+            total,
+            qubits: circuit.instructions().iter().map(|inst| inst.qubits.clone()).collect(),
+            num_qubits: circuit.num_qubits(),
+        }
+    }
+
+    /// gaps on each qubit between time 0, its operations and the end of
+    /// the circuit; qubits never used are idle for the whole duration
+    pub fn idle_windows(&self) -> Vec<IdleWindow> {
+        let mut free = vec![0.0; self.num_qubits];
+        let mut windows = Vec::new();
+        for (k, (timing, qubits)) in self.timings.iter().zip(&self.qubits).enumerate() {
+            for &q in qubits {
+                if timing.start > free[q] + 1e-9 {
+                    let (start, end) = (free[q], timing.start);
+                    windows.push(IdleWindow { qubit: q, start, end, before: Some(k) });
+                }
+                free[q] = timing.end();
+            }
+        }
+        for (q, &t) in free.iter().enumerate() {
+            if self.total > t + 1e-9 {
+                windows.push(IdleWindow { qubit: q, start: t, end: self.total, before: None });
+            }
+        }
+        windows
+    }
+
+    /// total idle time of `qubit`
+    pub fn idle_time(&self, qubit: usize) -> f64 {
+        self.idle_windows().iter().filter(|w| w.qubit == qubit).map(|w| w.end - w.start).sum()
+    }
+}
+
+impl Circuit {
+    /// start times under `schedule`; both policies give the same total,
+    /// the critical-path length
+    pub fn schedule(&self, schedule: &Schedule) -> ScheduledCircuit {
+        let durations: Vec<f64> =
+            self.instructions().iter().map(|inst| schedule.times.duration(&inst.gate)).collect();
+        let mut free = vec![0.0f64; self.num_qubits()];
+        let mut starts = Vec::with_capacity(self.len());
+        for (inst, &d) in self.instructions().iter().zip(&durations) {
+            let start = inst.qubits.iter().map(|&q| free[q]).fold(0.0, f64::max);
+            inst.qubits.iter().for_each(|&q| free[q] = start + d);
+            starts.push(start);
+        }
+        let total = free.iter().copied().fold(0.0, f64::max);
+        if schedule.policy == SchedulePolicy::Alap {
+            // latest end each qubit allows, walking backwards from `total`
+            let mut latest = vec![total; self.num_qubits()];
+            for (k, inst) in self.instructions().iter().enumerate().rev() {
+                let end = inst.qubits.iter().map(|&q| latest[q]).fold(f64::INFINITY, f64::min);
+                starts[k] = end - durations[k];
+                inst.qubits.iter().for_each(|&q| latest[q] = starts[k]);
+            }
+        }
+        ScheduledCircuit {
+            timings: starts
+                .into_iter()
+                .zip(durations)
+                .map(|(start, duration)| Timing { start, duration })
+                .collect(),
+            total,
+            qubits: self.instructions().iter().map(|inst| inst.qubits.clone()).collect(),
+            num_qubits: self.num_qubits(),
+        }
+    }
+
+    /// wall-clock time of the circuit in nanoseconds
+    pub fn duration(&self, schedule: &Schedule) -> f64 {
+        self.schedule(schedule).total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_follows_critical_path() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).rz(0.3, 0).cx(0, 1).x(2).cx(1, 2).mcx(&[0, 1], 2);
+        let schedule = Schedule::asap(GateTimes::superconducting());
+        // h 35, rz 0, cx 300, cx 300, ccx 600
+        assert_eq!(circuit.duration(&schedule), 1235.0);
+        let uniform = Schedule::asap(GateTimes::uniform(10.0, 20.0, 40.0).with_gate("x", 5.0));
+        assert_eq!(circuit.duration(&uniform), 10.0 + 10.0 + 20.0 + 20.0 + 40.0);
+    }
+
+    #[test]
+    fn test_alap_moves_slack_to_the_front() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).h(0).h(0).x(1).cx(0, 1);
+        let times = GateTimes::uniform(10.0, 50.0, 100.0);
+        let asap = circuit.schedule(&Schedule::asap(times.clone()));
+        let alap = circuit.schedule(&Schedule::alap(times));
+        assert_eq!((asap.total, alap.total), (80.0, 80.0));
+        assert_eq!((asap.timings[3].start, alap.timings[3].start), (0.0, 20.0));
+        let windows = asap.idle_windows();
+        assert_eq!(windows, [IdleWindow { qubit: 1, start: 10.0, end: 30.0, before: Some(4) }]);
+        assert_eq!((alap.idle_time(1), alap.idle_time(0)), (20.0, 0.0));
+    }
+}
@@ -0,0 +1,339 @@
+use num_complex::Complex64;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f64::consts::{FRAC_PI_4, FRAC_PI_8, SQRT_2};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::{matmul, Gate, Matrix2};
+use crate::simulator::operator::Operator;
+
+/// largest denominator exponent k (entries over √2^k) the checker searches
+pub const MAX_DENOMINATOR_EXPONENT: i32 = 20;
+
+/// a + bω + cω² + dω³ with ω = e^{iπ/4}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ZOmega([i64; 4]);
+
+impl ZOmega {
+    const ZERO: ZOmega = ZOmega([0; 4]);
+
+    fn mul(self, other: ZOmega) -> ZOmega {
+        let mut out = [0i64; 4];
+        for (i, &a) in self.0.iter().enumerate() {
+            for (j, &b) in other.0.iter().enumerate() {
+                // ω⁴ = −1
+                let (power, sign) = ((i + j) % 4, if i + j >= 4 { -1 } else { 1 });
+                out[power] += sign * a * b;
+            }
+        }
+        ZOmega(out)
+    }
+
+    fn add(self, other: ZOmega) -> ZOmega {
+        ZOmega(std::array::from_fn(|i| self.0[i] + other.0[i]))
+    }
+
+    fn neg(self) -> ZOmega {
+        ZOmega(self.0.map(|x| -x))
+    }
+
+    /// multiply by ω^r
+    fn rotate(self, r: usize) -> ZOmega {
+        let mut z = self;
+        for _ in 0..r % 8 {
+            let [a, b, c, d] = z.0;
+            z = ZOmega([-d, a, b, c]);
+        }
+        z
+    }
+
+    /// complex conjugate; ω̄ = −ω³
+    fn conj(self) -> ZOmega {
+        let [a, b, c, d] = self.0;
+        ZOmega([a, -d, -c, -b])
+    }
+
+    /// divisible by √2 = ω − ω³
+    fn sqrt2_divides(self) -> bool {
+        let [a, b, c, d] = self.0;
+        (a - c) % 2 == 0 && (b - d) % 2 == 0
+    }
+
+    fn div_sqrt2(self) -> ZOmega {
+        let doubled = self.mul(ZOmega([0, 1, 0, -1]));
+        ZOmega(doubled.0.map(|x| x / 2))
+    }
+
+    /// times √2 divides z (z ≠ 0)
+    fn sqrt2_valuation(mut self) -> i32 {
+        let mut count = 0;
+        while self != ZOmega::ZERO && self.sqrt2_divides() {
+            self = self.div_sqrt2();
+            count += 1;
+        }
+        count
+    }
+}
+
+/// 2×2 matrix with entries in Z[ω] over √2^k, k minimal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ExactMatrix {
+    entries: [[ZOmega; 2]; 2],
+    k: i32,
+}
+
+impl ExactMatrix {
+    fn reduced(mut self) -> Self {
+        while self.k > 0 && self.entries.iter().flatten().all(|z| z.sqrt2_divides()) {
+            self.entries = self.entries.map(|row| row.map(ZOmega::div_sqrt2));
+            self.k -= 1;
+        }
+        self
+    }
+
+    /// H T^j · self
+    fn step(self, j: usize) -> Self {
+        let [r0, r1] = self.entries;
+        let r1 = r1.map(|z| z.rotate(j));
+        let entries = [
+            std::array::from_fn(|c| r0[c].add(r1[c])),
+            std::array::from_fn(|c| r0[c].add(r1[c].neg())),
+        ];
+        ExactMatrix { entries, k: self.k + 1 }.reduced()
+    }
+
+    /// smallest denominator exponent of |u₀₀|² over √2
+    fn sde_abs2(&self) -> i32 {
+        let z = self.entries[0][0];
+        if z == ZOmega::ZERO {
+            return 0;
+        }
+        2 * self.k - z.mul(z.conj()).sqrt2_valuation()
+    }
+
+    fn is_unitary(&self) -> bool {
+        let [[a, b], [c, d]] = self.entries;
+        let norm = ZOmega([1i64 << self.k, 0, 0, 0]);
+        a.mul(a.conj()).add(c.mul(c.conj())) == norm
+            && b.mul(b.conj()).add(d.mul(d.conj())) == norm
+            && a.conj().mul(b).add(c.conj().mul(d)) == ZOmega::ZERO
+    }
+
+    /// diag(ω^p, ω^q) as (p, q)
+    fn diagonal_powers(&self) -> Option<(usize, usize)> {
+        let [[a, b], [c, d]] = self.entries;
+        if self.k != 0 || b != ZOmega::ZERO || c != ZOmega::ZERO {
+            return None;
+        }
+        let power = |z: ZOmega| (0..8).find(|&r| ZOmega([1, 0, 0, 0]).rotate(r) == z);
+        Some((power(a)?, power(d)?))
+    }
+}
+
+/// integers (a, m) with a + m/√2 ≈ x whose Galois conjugate a − m/√2 is
+/// bounded by `bound`, if there is one
+fn recover_real(x: f64, bound: f64) -> Option<(i64, i64)> {
+    let limit = (bound * SQRT_2).ceil() as i64 + 1;
+    let tolerance = 1e-9 * bound.max(1.0);
+    (-limit..=limit)
+        .filter_map(|m| {
+            let a = (x - m as f64 / SQRT_2).round();
+            let residual = (a + m as f64 / SQRT_2 - x).abs();
+            let conjugate = (a - m as f64 / SQRT_2).abs();
+            (residual < tolerance && conjugate <= bound + 1e-6).then_some((a as i64, m))
+        })
+        .min_by_key(|&(a, m)| a.abs() + m.abs())
+}
+
+fn to_exact(matrix: &Matrix2, k: i32) -> Option<ExactMatrix> {
+    let scale = SQRT_2.powi(k);
+    let mut entries = [[ZOmega::ZERO; 2]; 2];
+    for (row, out) in matrix.iter().zip(entries.iter_mut()) {
+        for (z, slot) in row.iter().zip(out.iter_mut()) {
+            // re = a + (b − d)/√2, im = c + (b + d)/√2
+            let (a, m) = recover_real(z.re * scale, scale)?;
+            let (c, n) = recover_real(z.im * scale, scale)?;
+            if (m + n) % 2 != 0 {
+                return None;
+            }
+            *slot = ZOmega([a, (m + n) / 2, c, (n - m) / 2]);
+        }
+    }
+    let exact = ExactMatrix { entries, k };
+    exact.is_unitary().then(|| exact.reduced())
+}
+
+/// T^r as named gates
+fn t_power(r: usize) -> Vec<Gate> {
+    match r % 8 {
+        0 => vec![],
+        1 => vec![Gate::T],
+        2 => vec![Gate::S],
+        3 => vec![Gate::S, Gate::T],
+        4 => vec![Gate::Z],
+        5 => vec![Gate::Z, Gate::T],
+        6 => vec![Gate::Sdg],
+        _ => vec![Gate::Tdg],
+    }
+}
+
+/// exact Clifford+T word: U = e^{i·global_phase} · gates (applied in order)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliffordTSequence {
+    pub gates: Vec<Gate>,
+    pub global_phase: f64,
+}
+
+impl CliffordTSequence {
+    pub fn t_count(&self) -> usize {
+        self.gates.iter().filter(|g| matches!(g, Gate::T | Gate::Tdg)).count()
+    }
+
+    pub fn circuit(&self) -> Circuit {
+        let mut circuit = Circuit::new(1);
+        for gate in &self.gates {
+            circuit.push(gate.clone(), &[0]);
+        }
+        circuit
+    }
+
+    pub fn matrix(&self) -> Operator {
+        let phase = Complex64::from_polar(1.0, self.global_phase);
+        let identity = [[phase, Complex64::new(0.0, 0.0)], [Complex64::new(0.0, 0.0), phase]];
+        let product = self
+            .gates
+            .iter()
+            .fold(identity, |acc, g| matmul(&g.matrix2().expect("single-qubit gate"), &acc));
+        Operator::from(product)
+    }
+}
+
+/// H T^j moves (j < 8) from `start` to a diagonal diag(ω^p, ω^q), breadth first
+fn search_base(start: ExactMatrix, max_depth: usize) -> Option<(Vec<usize>, (usize, usize))> {
+    let mut parents: HashMap<ExactMatrix, (ExactMatrix, usize)> = HashMap::new();
+    let mut queue = VecDeque::from([(start, 0)]);
+    let mut seen = HashSet::from([start]);
+    while let Some((m, depth)) = queue.pop_front() {
+        if let Some(powers) = m.diagonal_powers() {
+            let mut moves = Vec::new();
+            let mut current = m;
+            while let Some(&(parent, j)) = parents.get(&current) {
+                moves.push(j);
+                current = parent;
+            }
+            moves.reverse();
+            return Some((moves, powers));
+        }
+        if depth == max_depth {
+            continue;
+        }
+        for j in 0..8 {
+            let next = m.step(j);
+            if seen.insert(next) {
+                parents.insert(next, (m, j));
+                queue.push_back((next, depth + 1));
+            }
+        }
+    }
+    None
+}
+
+/// exact synthesis over the ring of Z[ω] up to the denominator search bound
+fn synthesize(exact: ExactMatrix) -> Option<(Vec<Gate>, usize)> {
+    // Kliuchnikov–Maslov–Mosca: some H T^j lowers sde(|u₀₀|²) while it exceeds 3
+    let mut current = exact;
+    let mut moves = Vec::new();
+    while current.sde_abs2() > 3 {
+        let s = current.sde_abs2();
+        let j = (0..4).find(|&j| current.step(j).sde_abs2() == s - 1)?;
+        current = current.step(j);
+        moves.push(j);
+    }
+    let (tail, (p, q)) = search_base(current, 6)?;
+    moves.extend(tail);
+    // H T^{j_n} ⋯ H T^{j_1} U = ω^p T^{q−p},
+    // so U = T^{−j_1} H ⋯ T^{−j_n} H ω^p T^{q−p}
+    let mut gates = t_power(q + 8 - p);
+    for &j in moves.iter().rev() {
+        gates.push(Gate::H);
+        gates.extend(t_power(8 - j));
+    }
+    Some((gates, p))
+}
+
+/// exact Clifford+T sequence for a one-qubit `operator` up to global phase,
+/// if its entries lie in Z[1/√2, i] after removing a phase
+pub fn exact_clifford_t(operator: &Operator) -> Option<CliffordTSequence> {
+    let matrix = operator.to_matrix2()?;
+    let det = matrix[0][0] * matrix[1][1] - matrix[0][1] * matrix[1][0];
+    // exact unitaries have det = ω^r; try every phase that could make it so
+    for r in 0..16 {
+        let phi = -det.arg() / 2.0 + r as f64 * FRAC_PI_8;
+        let phase = Complex64::from_polar(1.0, phi);
+        let shifted = matrix.map(|row| row.map(|z| z * phase));
+        for k in 0..=MAX_DENOMINATOR_EXPONENT {
+            if let Some(exact) = to_exact(&shifted, k) {
+                let (gates, p) = synthesize(exact)?;
+                return Some(CliffordTSequence {
+                    gates,
+                    global_phase: p as f64 * FRAC_PI_4 - phi,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// whether a one-qubit `operator` is exactly a Clifford+T product up to
+/// global phase
+pub fn is_clifford_t(operator: &Operator) -> bool {
+    exact_clifford_t(operator).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::{identity_matrix, rz_matrix};
+    use crate::simulator::rng::Rng;
+
+    fn assert_close(a: &Matrix2, b: &Matrix2) {
+        for (x, y) in a.iter().flatten().zip(b.iter().flatten()) {
+            assert!((x - y).norm() < 1e-9, "{:?} vs {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_ring_arithmetic() {
+        let omega = ZOmega([0, 1, 0, 0]);
+        assert_eq!(omega.mul(omega).mul(omega).mul(omega), ZOmega([-1, 0, 0, 0]));
+        let sqrt2 = ZOmega([0, 1, 0, -1]);
+        assert_eq!(sqrt2.mul(sqrt2), ZOmega([2, 0, 0, 0]));
+        assert_eq!(sqrt2.mul(sqrt2).div_sqrt2(), sqrt2);
+        assert_eq!(omega.mul(omega.conj()), ZOmega([1, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_random_words_round_trip() {
+        let mut rng = Rng::seed_from_u64(55);
+        let alphabet = [Gate::H, Gate::T, Gate::S, Gate::Tdg, Gate::X];
+        for _ in 0..10 {
+            let word: Vec<Gate> = (0..25).map(|_| alphabet[rng.gen_range(5)].clone()).collect();
+            let target = word
+                .iter()
+                .fold(identity_matrix(), |acc, g| matmul(&g.matrix2().unwrap(), &acc));
+            let sequence = exact_clifford_t(&Operator::from(target)).expect("word is Clifford+T");
+            assert_close(&sequence.matrix().to_matrix2().unwrap(), &target);
+            let t_gates = word.iter().filter(|g| matches!(g, Gate::T | Gate::Tdg)).count();
+            assert!(sequence.t_count() <= t_gates);
+        }
+    }
+
+    #[test]
+    fn test_detection() {
+        // Rz(π/4) is T up to phase; H is Clifford; Rx(0.3) is not exact
+        let rz = exact_clifford_t(&Gate::Rz(FRAC_PI_4).operator()).unwrap();
+        assert_eq!(rz.t_count(), 1);
+        assert_close(&rz.matrix().to_matrix2().unwrap(), &rz_matrix(FRAC_PI_4));
+        assert_eq!(exact_clifford_t(&Gate::H.operator()).unwrap().t_count(), 0);
+        assert!(!is_clifford_t(&Gate::Rx(0.3).operator()));
+        assert!(exact_clifford_t(&Gate::Cx.operator()).is_none());
+    }
+}
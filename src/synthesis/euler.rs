@@ -0,0 +1,133 @@
+use num_complex::Complex64;
+use std::f64::consts::FRAC_PI_2;
+use crate::simulator::gates::{matmul, rx_matrix, ry_matrix, rz_matrix, Gate, Matrix2};
+use crate::simulator::operator::Operator;
+
+/// rotation axes of an Euler decomposition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EulerBasis {
+    /// Rz(φ) Ry(θ) Rz(λ)
+    Zyz,
+    /// Rz(φ) Rx(θ) Rz(λ)
+    Zxz,
+}
+
+/// U = e^{iγ} Rz(φ) R(θ) Rz(λ) with θ ∈ [0, π]; λ is applied first
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EulerAngles {
+    pub basis: EulerBasis,
+    pub phi: f64,
+    pub theta: f64,
+    pub lambda: f64,
+    pub global_phase: f64,
+}
+
+/// ZYZ Euler angles of any one-qubit unitary; panics on wider operators
+pub fn decompose_zyz(operator: &Operator) -> EulerAngles {
+    decompose_zyz2(&operator.to_matrix2().expect("one-qubit operator"))
+}
+
+/// `decompose_zyz` of a 2×2 array
+pub(crate) fn decompose_zyz2(matrix: &Matrix2) -> EulerAngles {
+    let det = matrix[0][0] * matrix[1][1] - matrix[0][1] * matrix[1][0];
+    let global_phase = det.arg() / 2.0;
+    let unphase = Complex64::from_polar(1.0, -global_phase);
+    // V ∈ SU(2) has V₁₁ = e^{i(φ+λ)/2} cos(θ/2) and V₁₀ = e^{i(φ−λ)/2} sin(θ/2)
+    let v11 = matrix[1][1] * unphase;
+    let v10 = matrix[1][0] * unphase;
+    let theta = 2.0 * v10.norm().atan2(v11.norm());
+    let half_sum = if v11.norm() > 1e-12 { v11.arg() } else { 0.0 };
+    let half_diff = if v10.norm() > 1e-12 { v10.arg() } else { 0.0 };
+    EulerAngles {
+        basis: EulerBasis::Zyz,
+        phi: half_sum + half_diff,
+        theta,
+        lambda: half_sum - half_diff,
+        global_phase,
+    }
+}
+
+/// ZXZ Euler angles of any one-qubit unitary; panics on wider operators
+pub fn decompose_zxz(operator: &Operator) -> EulerAngles {
+    decompose_zxz2(&operator.to_matrix2().expect("one-qubit operator"))
+}
+
+/// `decompose_zxz` of a 2×2 array
+pub(crate) fn decompose_zxz2(matrix: &Matrix2) -> EulerAngles {
+    // Rx(θ) = Rz(−π/2) Ry(θ) Rz(π/2)
+    let zyz = decompose_zyz2(matrix);
+    EulerAngles {
+        basis: EulerBasis::Zxz,
+        phi: zyz.phi + FRAC_PI_2,
+        lambda: zyz.lambda - FRAC_PI_2,
+        ..zyz
+    }
+}
+
+impl EulerAngles {
+    /// the three rotations in application order, without the global phase
+    pub fn gates(&self) -> [Gate; 3] {
+        let middle = match self.basis {
+            EulerBasis::Zyz => Gate::Ry(self.theta),
+            EulerBasis::Zxz => Gate::Rx(self.theta),
+        };
+        [Gate::Rz(self.lambda), middle, Gate::Rz(self.phi)]
+    }
+
+    /// e^{iγ} Rz(φ) R(θ) Rz(λ)
+    pub fn matrix(&self) -> Operator {
+        Operator::from(self.matrix2())
+    }
+
+    /// `matrix` as a 2×2 array
+    pub(crate) fn matrix2(&self) -> Matrix2 {
+        let middle = match self.basis {
+            EulerBasis::Zyz => ry_matrix(self.theta),
+            EulerBasis::Zxz => rx_matrix(self.theta),
+        };
+        let product = matmul(&rz_matrix(self.phi), &matmul(&middle, &rz_matrix(self.lambda)));
+        let phase = Complex64::from_polar(1.0, self.global_phase);
+        product.map(|row| row.map(|x| x * phase))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::{h_matrix, s_matrix, x_matrix, z_matrix};
+    use crate::simulator::random::random_unitary;
+    use crate::simulator::rng::Rng;
+
+    fn assert_close(a: &Matrix2, b: &Matrix2) {
+        for (x, y) in a.iter().flatten().zip(b.iter().flatten()) {
+            assert!((x - y).norm() < 1e-10, "{:?} vs {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_reconstructs_random_and_special_unitaries() {
+        let mut rng = Rng::seed_from_u64(48);
+        let mut matrices = vec![h_matrix(), x_matrix(), z_matrix(), s_matrix()];
+        for _ in 0..20 {
+            let u = random_unitary(1, &mut rng);
+            matrices.push([[u[(0, 0)], u[(0, 1)]], [u[(1, 0)], u[(1, 1)]]]);
+        }
+        for m in &matrices {
+            let zyz = decompose_zyz(&Operator::from(m));
+            let zxz = decompose_zxz(&Operator::from(m));
+            assert!((0.0..=std::f64::consts::PI).contains(&zyz.theta));
+            assert_close(&zyz.matrix2(), m);
+            assert_close(&zxz.matrix2(), m);
+        }
+    }
+
+    #[test]
+    fn test_known_angles() {
+        // H = i Ry(π/2) Rz(π)
+        let h = decompose_zyz(&Gate::H.operator());
+        assert!((h.theta - FRAC_PI_2).abs() < 1e-12);
+        let x = decompose_zxz(&Gate::X.operator());
+        assert!((x.theta - std::f64::consts::PI).abs() < 1e-12);
+        assert_eq!(x.gates()[1], Gate::Rx(x.theta));
+    }
+}
@@ -0,0 +1,296 @@
+use num_complex::Complex64;
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::{x_matrix, y_matrix, z_matrix, Matrix2};
+use crate::simulator::matrix::Matrix;
+use crate::simulator::operator::Operator;
+use super::euler::decompose_zyz;
+
+/// Cartan decomposition of a two-qubit unitary
+///
+/// U = e^{iφ} (A₁ ⊗ A₀) · exp(i(a XX + b YY + c ZZ)) · (B₁ ⊗ B₀), where
+/// `before[q]` and `after[q]` act on qubit q (qubit 0 is the low bit).
+#[derive(Debug, Clone, PartialEq)]
+pub struct KakDecomposition {
+    pub global_phase: f64,
+    pub before: [Operator; 2],
+    pub interaction: [f64; 3],
+    pub after: [Operator; 2],
+}
+
+fn c(re: f64, im: f64) -> Complex64 {
+    Complex64::new(re, im)
+}
+
+/// magic basis; its columns are the Bell states with phases making
+/// SU(2) ⊗ SU(2) real orthogonal
+fn magic_basis() -> Matrix {
+    let (r, i, z) = (c(FRAC_1_SQRT_2, 0.0), c(0.0, FRAC_1_SQRT_2), c(0.0, 0.0));
+    let columns = [[r, z, z, r], [i, z, z, -i], [z, i, i, z], [z, r, -r, z]];
+    Matrix::from_fn(4, 4, |row, col| columns[col][row])
+}
+
+/// eigenvectors (as columns) of a real symmetric 4×4 matrix by cyclic Jacobi
+fn symmetric_eigenvectors(mut a: [[f64; 4]; 4]) -> [[f64; 4]; 4] {
+    let mut v = [[0.0; 4]; 4];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+    for _sweep in 0..50 {
+        let off: f64 = (0..4)
+            .flat_map(|i| (0..4).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off < 1e-30 {
+            break;
+        }
+        for p in 0..4 {
+            for q in p + 1..4 {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let cos = 1.0 / (t * t + 1.0).sqrt();
+                let sin = t * cos;
+                for row in a.iter_mut().chain(v.iter_mut()) {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = cos * kp - sin * kq;
+                    row[q] = sin * kp + cos * kq;
+                }
+                let (row_p, row_q) = (a[p], a[q]);
+                a[p] = std::array::from_fn(|k| cos * row_p[k] - sin * row_q[k]);
+                a[q] = std::array::from_fn(|k| sin * row_p[k] + cos * row_q[k]);
+            }
+        }
+    }
+    v
+}
+
+/// real orthogonal P with Pᵀ M P diagonal, for a symmetric unitary M
+///
+/// Re M and Im M commute, so a generic combination of them shares their
+/// eigenvectors; the fixed weights avoid accidental degeneracies.
+fn diagonalize_symmetric_unitary(m: &Matrix) -> Matrix {
+    for weight in [0.0, 0.618, 1.37, -2.21, 3.3] {
+        let mut s = [[0.0; 4]; 4];
+        for (i, row) in s.iter_mut().enumerate() {
+            for (j, x) in row.iter_mut().enumerate() {
+                *x = m[(i, j)].re + weight * m[(i, j)].im;
+            }
+        }
+        let v = symmetric_eigenvectors(s);
+        let p = Matrix::from_fn(4, 4, |i, j| c(v[i][j], 0.0));
+        let d = &(&transpose(&p) * m) * &p;
+        let off = (0..16).filter(|k| k / 4 != k % 4).map(|k| d[(k / 4, k % 4)].norm()).sum::<f64>();
+        if off < 1e-9 {
+            return p;
+        }
+    }
+    panic!("could not diagonalize the magic-basis product");
+}
+
+fn transpose(m: &Matrix) -> Matrix {
+    Matrix::from_fn(m.cols(), m.rows(), |i, j| m[(j, i)])
+}
+
+/// factor a 4×4 product into (qubit 1, qubit 0) 2×2 blocks
+fn split_kron(m: &Matrix) -> (Matrix2, Matrix2) {
+    let (r, col) = (0..16)
+        .map(|k| (k / 4, k % 4))
+        .max_by(|&x, &y| m[x].norm().total_cmp(&m[y].norm()))
+        .expect("non-empty matrix");
+    let (hi_r, hi_c) = (r / 2 * 2, col / 2 * 2);
+    let mut low = [[c(0.0, 0.0); 2]; 2];
+    for (k, row) in low.iter_mut().enumerate() {
+        for (l, x) in row.iter_mut().enumerate() {
+            *x = m[(hi_r + k, hi_c + l)];
+        }
+    }
+    let det = low[0][0] * low[1][1] - low[0][1] * low[1][0];
+    let scale = det.sqrt();
+    for x in low.iter_mut().flatten() {
+        *x /= scale;
+    }
+    let pivot = low[r % 2][col % 2];
+    let mut high = [[c(0.0, 0.0); 2]; 2];
+    for (i, row) in high.iter_mut().enumerate() {
+        for (j, x) in row.iter_mut().enumerate() {
+            *x = m[(2 * i + r % 2, 2 * j + col % 2)] / pivot;
+        }
+    }
+    (high, low)
+}
+
+fn local(factors: &[Operator; 2]) -> Matrix {
+    factors[1].kron(&factors[0]).into_matrix()
+}
+
+/// exp(i(a XX + b YY + c ZZ))
+pub fn interaction_matrix(a: f64, b: f64, c: f64) -> Matrix {
+    let pauli = |m: Matrix2, w: f64| {
+        let p = Matrix::from_matrix2(&m);
+        p.kron(&p).scaled(Complex64::new(0.0, w))
+    };
+    let generator = &(&pauli(x_matrix(), a) + &pauli(y_matrix(), b)) + &pauli(z_matrix(), c);
+    generator.expm()
+}
+
+/// exp(i(a XX + b YY + c ZZ)) up to global phase with three CNOTs
+/// (Vatan & Williams)
+pub fn interaction_circuit(a: f64, b: f64, c: f64) -> Circuit {
+    let mut circuit = Circuit::new(2);
+    circuit
+        .rz(FRAC_PI_2, 1)
+        .cx(1, 0)
+        .rz(FRAC_PI_2 - 2.0 * c, 0)
+        .ry(FRAC_PI_2 - 2.0 * a, 1)
+        .cx(0, 1)
+        .ry(2.0 * b - FRAC_PI_2, 1)
+        .cx(1, 0)
+        .rz(-FRAC_PI_2, 0);
+    circuit
+}
+
+/// ZYZ rotations of `m` on qubit `q`
+fn push_rotations(circuit: &mut Circuit, m: &Operator, q: usize) {
+    for gate in decompose_zyz(m).gates() {
+        circuit.push(gate, &[q]);
+    }
+}
+
+impl KakDecomposition {
+    /// decompose a 4×4 unitary; panics if `u` is not unitary
+    pub fn new(u: &Matrix) -> Self {
+        assert!(u.rows() == 4 && u.is_unitary(1e-8), "expected a 4×4 unitary");
+        let special = u.scaled(u.determinant().powf(-0.25));
+        let magic = magic_basis();
+        let u_magic = &(&magic.dagger() * &special) * &magic;
+        let m = &transpose(&u_magic) * &u_magic;
+
+        let mut p = diagonalize_symmetric_unitary(&m);
+        if p.determinant().re < 0.0 {
+            for i in 0..4 {
+                p[(i, 0)] = -p[(i, 0)];
+            }
+        }
+        let d = &(&transpose(&p) * &m) * &p;
+        let mut theta: Vec<f64> = (0..4).map(|k| d[(k, k)].arg() / 2.0).collect();
+        if theta.iter().sum::<f64>().cos() < 0.0 {
+            theta[0] += std::f64::consts::PI;
+        }
+        let inverse_phases = Matrix::from_fn(4, 4, |i, j| {
+            if i == j { Complex64::from_polar(1.0, -theta[i]) } else { c(0.0, 0.0) }
+        });
+        let k1 = &(&u_magic * &p) * &inverse_phases;
+        let k2 = transpose(&p);
+
+        // diagonal signs of XX, YY, ZZ on the four magic-basis states
+        let a = (theta[0] - theta[1] + theta[2] - theta[3]) / 4.0;
+        let b = (-theta[0] + theta[1] + theta[2] - theta[3]) / 4.0;
+        let cc = (theta[0] + theta[1] - theta[2] - theta[3]) / 4.0;
+
+        let (after1, after0) = split_kron(&(&(&magic * &k1) * &magic.dagger()));
+        let (before1, before0) = split_kron(&(&(&magic * &k2) * &magic.dagger()));
+        let mut decomposition = Self {
+            global_phase: 0.0,
+            before: [before0, before1].map(Operator::from),
+            interaction: [a, b, cc],
+            after: [after0, after1].map(Operator::from),
+        };
+        let rebuilt = decomposition.matrix();
+        let overlap: Complex64 = rebuilt
+            .as_slice()
+            .iter()
+            .zip(u.as_slice())
+            .map(|(x, y)| x.conj() * y)
+            .sum();
+        decomposition.global_phase = overlap.arg();
+        decomposition
+    }
+
+    /// e^{iφ} (A₁ ⊗ A₀) · exp(i(a XX + b YY + c ZZ)) · (B₁ ⊗ B₀)
+    pub fn matrix(&self) -> Matrix {
+        let [a, b, c] = self.interaction;
+        let product = &(&local(&self.after) * &interaction_matrix(a, b, c)) * &local(&self.before);
+        product.scaled(Complex64::from_polar(1.0, self.global_phase))
+    }
+
+    /// three-CNOT circuit of Rz/Ry rotations equal to the unitary up to global phase
+    pub fn circuit(&self) -> Circuit {
+        let [a, b, c] = self.interaction;
+        let mut circuit = Circuit::new(2);
+        for (q, m) in self.before.iter().enumerate() {
+            push_rotations(&mut circuit, m, q);
+        }
+        circuit.append(&interaction_circuit(a, b, c));
+        for (q, m) in self.after.iter().enumerate() {
+            push_rotations(&mut circuit, m, q);
+        }
+        circuit
+    }
+}
+
+/// CNOT + single-qubit circuit for an arbitrary two-qubit unitary
+pub fn two_qubit_circuit(u: &Matrix) -> Circuit {
+    KakDecomposition::new(u).circuit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::random::random_unitary;
+    use crate::simulator::rng::Rng;
+    use crate::simulator::testing::ApproxEq;
+
+    fn assert_exact(a: &Matrix, b: &Matrix) {
+        for (x, y) in a.as_slice().iter().zip(b.as_slice()) {
+            assert!((x - y).norm() < 1e-8, "{:?} vs {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_random_unitaries_reconstruct() {
+        let mut rng = Rng::seed_from_u64(47);
+        for _ in 0..20 {
+            let u = random_unitary(2, &mut rng);
+            let kak = KakDecomposition::new(&u);
+            assert_exact(&kak.matrix(), &u);
+            let circuit = kak.circuit();
+            assert_eq!(circuit.two_qubit_count(), 3);
+            assert!(circuit.to_unitary().approx_eq(&u, 1e-8));
+        }
+    }
+
+    #[test]
+    fn test_structured_gates() {
+        // CNOT, SWAP and a local product hit degenerate interaction spectra
+        let mut gates = Vec::new();
+        for build in [
+            |c: &mut Circuit| {
+                c.cx(0, 1);
+            },
+            |c: &mut Circuit| {
+                c.swap(0, 1);
+            },
+            |c: &mut Circuit| {
+                c.h(0).t(1);
+            },
+        ] {
+            let mut circuit = Circuit::new(2);
+            build(&mut circuit);
+            gates.push(circuit.to_unitary());
+        }
+        gates.push(interaction_matrix(0.3, 0.2, 0.1));
+        for u in gates {
+            assert_exact(&KakDecomposition::new(&u).matrix(), &u);
+            assert!(two_qubit_circuit(&u).to_unitary().approx_eq(&u, 1e-8));
+        }
+    }
+
+    #[test]
+    fn test_interaction_circuit() {
+        let circuit = interaction_circuit(0.31, -0.17, 0.23);
+        assert!(circuit.to_unitary().approx_eq(&interaction_matrix(0.31, -0.17, 0.23), 1e-10));
+    }
+}
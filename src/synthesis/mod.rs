@@ -0,0 +1,15 @@
+pub mod clifford_t;
+pub mod euler;
+pub mod kak;
+pub mod multi_controlled;
+pub mod multiplexor;
+pub mod unitary;
+
+pub use clifford_t::{exact_clifford_t, is_clifford_t, CliffordTSequence};
+pub use euler::{decompose_zxz, decompose_zyz, EulerAngles, EulerBasis};
+pub use kak::{interaction_circuit, two_qubit_circuit, KakDecomposition};
+pub use multi_controlled::{
+    controlled_unitary, mcu_recursive, mcu_v_chain, mcx_recursive, mcx_v_chain, toffoli,
+};
+pub use multiplexor::{diagonal, multiplexor, uniformly_controlled_ry, uniformly_controlled_rz};
+pub use unitary::{complete_isometry, isometry_circuit, unitary_circuit, MAX_SYNTHESIS_QUBITS};
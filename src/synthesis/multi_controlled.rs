@@ -0,0 +1,339 @@
+use num_complex::Complex64;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::{Gate, Matrix2};
+use crate::simulator::operator::Operator;
+use super::euler::decompose_zyz;
+
+/// Toffoli as six CNOTs with H, T and T† (Nielsen & Chuang fig. 4.9)
+pub fn toffoli(circuit: &mut Circuit, a: usize, b: usize, target: usize) {
+    circuit
+        .h(target)
+        .cx(b, target)
+        .tdg(target)
+        .cx(a, target)
+        .t(target)
+        .cx(b, target)
+        .tdg(target)
+        .cx(a, target)
+        .t(b)
+        .t(target)
+        .h(target)
+        .cx(a, b)
+        .t(a)
+        .tdg(b)
+        .cx(a, b);
+}
+
+/// singly-controlled U as A·CX·B·CX·C plus a phase on the control
+///
+/// With U = e^{iα} Rz(β) Ry(γ) Rz(δ): A = Rz(β) Ry(γ/2),
+/// B = Ry(−γ/2) Rz(−(δ+β)/2), C = Rz((δ−β)/2), so ABC = I and AXBXC = e^{−iα}U.
+pub fn controlled_unitary(
+    circuit: &mut Circuit,
+    operator: &Operator,
+    control: usize,
+    target: usize,
+) {
+    let euler = decompose_zyz(operator);
+    let (beta, gamma, delta) = (euler.phi, euler.theta, euler.lambda);
+    circuit
+        .rz((delta - beta) / 2.0, target)
+        .cx(control, target)
+        .rz(-(delta + beta) / 2.0, target)
+        .ry(-gamma / 2.0, target)
+        .cx(control, target)
+        .ry(gamma / 2.0, target)
+        .rz(beta, target)
+        .phase(euler.global_phase, control);
+}
+
+/// V with V² = U, from the axis–angle form of U
+fn sqrt_unitary(m: &Matrix2) -> Matrix2 {
+    let det = m[0][0] * m[1][1] - m[0][1] * m[1][0];
+    let alpha = det.arg() / 2.0;
+    let unphase = Complex64::from_polar(1.0, -alpha);
+    let w = m.map(|row| row.map(|x| x * unphase));
+    // W = cos(ω/2) I − i sin(ω/2) n·σ
+    let cos = (w[0][0] + w[1][1]).re / 2.0;
+    let mut axis = [
+        -(w[0][1] + w[1][0]).im / 2.0,
+        (w[1][0] - w[0][1]).re / 2.0,
+        -(w[0][0] - w[1][1]).im / 2.0,
+    ];
+    let sin = axis.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if sin < 1e-12 {
+        axis = [0.0, 0.0, 1.0];
+    } else {
+        axis = axis.map(|x| x / sin);
+    }
+    let half = sin.atan2(cos) / 2.0;
+    let (c, s) = (half.cos(), half.sin());
+    let phase = Complex64::from_polar(1.0, alpha / 2.0);
+    let [nx, ny, nz] = axis;
+    let v = [
+        [Complex64::new(c, -s * nz), Complex64::new(-s * ny, -s * nx)],
+        [Complex64::new(s * ny, -s * nx), Complex64::new(c, s * nz)],
+    ];
+    v.map(|row| row.map(|x| x * phase))
+}
+
+/// n-controlled X with n − 2 dirty ancillas in 4(n − 2) Toffolis (Barenco
+/// et al. lemma 7.2); the ancillas may hold anything and are restored
+fn mcx_dirty(circuit: &mut Circuit, controls: &[usize], target: usize, ancillas: &[usize]) {
+    let n = controls.len();
+    if n <= 2 {
+        mcx_recursive(circuit, controls, target);
+        return;
+    }
+    debug_assert!(ancillas.len() >= n - 2, "lemma 7.2 needs {} ancillas", n - 2);
+    // ancillas[i] collects controls[..i + 2]; the descending half toggles
+    // each ancilla by the one below it, the ascending half computes them
+    let ladder = |circuit: &mut Circuit| {
+        for i in (2..n - 1).rev() {
+            toffoli(circuit, controls[i], ancillas[i - 2], ancillas[i - 1]);
+        }
+        toffoli(circuit, controls[0], controls[1], ancillas[0]);
+        for i in 2..n - 1 {
+            toffoli(circuit, controls[i], ancillas[i - 2], ancillas[i - 1]);
+        }
+    };
+    toffoli(circuit, controls[n - 1], ancillas[n - 3], target);
+    ladder(circuit);
+    toffoli(circuit, controls[n - 1], ancillas[n - 3], target);
+    ladder(circuit);
+}
+
+/// n-controlled X borrowing one dirty qubit `spare` (Barenco et al.
+/// corollary 7.4): each half of the controls borrows the other half
+fn mcx_with_spare(circuit: &mut Circuit, controls: &[usize], target: usize, spare: usize) {
+    if controls.len() <= 2 {
+        mcx_recursive(circuit, controls, target);
+        return;
+    }
+    let (first, second) = controls.split_at(controls.len().div_ceil(2));
+    let mut borrowed: Vec<usize> = second.to_vec();
+    borrowed.push(target);
+    let mut upper: Vec<usize> = second.to_vec();
+    upper.push(spare);
+    for _ in 0..2 {
+        mcx_dirty(circuit, first, spare, &borrowed);
+        mcx_dirty(circuit, &upper, target, first);
+    }
+}
+
+/// n-controlled U without ancillas by Barenco et al. lemma 7.5
+///
+/// The inner (n − 1)-controlled X gates borrow the idle target as a dirty
+/// ancilla, so each costs O(n) gates and the whole gate count grows as n²;
+/// use `mcu_v_chain` when clean ancillas are free.
+pub fn mcu_recursive(
+    circuit: &mut Circuit,
+    operator: &Operator,
+    controls: &[usize],
+    target: usize,
+) {
+    match controls {
+        [] => {
+            circuit.push(Gate::Mcu(0, operator.clone()), &[target]);
+        }
+        [control] => controlled_unitary(circuit, operator, *control, target),
+        [rest @ .., last] => {
+            let v = sqrt_unitary(&operator.to_matrix2().expect("one-qubit operator"));
+            let v = Operator::from(v);
+            controlled_unitary(circuit, &v, *last, target);
+            mcx_with_spare(circuit, rest, *last, target);
+            controlled_unitary(circuit, &v.dagger(), *last, target);
+            mcx_with_spare(circuit, rest, *last, target);
+            mcu_recursive(circuit, &v, rest, target);
+        }
+    }
+}
+
+/// n-controlled X without ancillas, through `mcu_recursive` beyond two controls
+pub fn mcx_recursive(circuit: &mut Circuit, controls: &[usize], target: usize) {
+    match controls {
+        [] => {
+            circuit.x(target);
+        }
+        [control] => {
+            circuit.cx(*control, target);
+        }
+        [a, b] => toffoli(circuit, *a, *b, target),
+        _ => mcu_recursive(circuit, &Gate::X.operator(), controls, target),
+    }
+}
+
+/// AND of `controls` into the last used ancilla through a Toffoli ladder;
+/// returns that ancilla
+fn and_ladder(circuit: &mut Circuit, controls: &[usize], ancillas: &[usize]) -> usize {
+    toffoli(circuit, controls[0], controls[1], ancillas[0]);
+    for i in 2..controls.len() {
+        toffoli(circuit, controls[i], ancillas[i - 2], ancillas[i - 1]);
+    }
+    ancillas[controls.len() - 2]
+}
+
+/// n-controlled X with n − 2 clean ancillas (V-chain of 2n − 3 Toffolis)
+///
+/// The ancillas must start in |0⟩ and are returned to |0⟩.
+pub fn mcx_v_chain(circuit: &mut Circuit, controls: &[usize], target: usize, ancillas: &[usize]) {
+    let n = controls.len();
+    if n <= 2 {
+        mcx_recursive(circuit, controls, target);
+        return;
+    }
+    assert!(ancillas.len() >= n - 2, "V-chain needs {} ancillas", n - 2);
+    let mut ladder = Circuit::new(circuit.num_qubits());
+    let last = and_ladder(&mut ladder, &controls[..n - 1], ancillas);
+    circuit.append(&ladder);
+    toffoli(circuit, controls[n - 1], last, target);
+    circuit.append(&ladder.inverse());
+}
+
+/// n-controlled U with n − 1 clean ancillas, returned to |0⟩
+pub fn mcu_v_chain(
+    circuit: &mut Circuit,
+    operator: &Operator,
+    controls: &[usize],
+    target: usize,
+    ancillas: &[usize],
+) {
+    let n = controls.len();
+    if n <= 1 {
+        mcu_recursive(circuit, operator, controls, target);
+        return;
+    }
+    assert!(ancillas.len() >= n - 1, "V-chain needs {} ancillas", n - 1);
+    let mut ladder = Circuit::new(circuit.num_qubits());
+    let last = and_ladder(&mut ladder, controls, ancillas);
+    circuit.append(&ladder);
+    controlled_unitary(circuit, operator, last, target);
+    circuit.append(&ladder.inverse());
+}
+
+impl Circuit {
+    /// same circuit with every gate of two or more controls lowered to CX
+    /// and single-qubit gates, without ancillas
+    pub fn decompose_multi_controlled(&self) -> Circuit {
+        let mut circuit = Circuit::new(self.num_qubits());
+        for inst in self.instructions() {
+            let (controls, target) = inst.qubits.split_at(inst.qubits.len() - 1);
+            let target = target[0];
+            match inst.gate {
+                Gate::Mcx(n) if n >= 2 => mcx_recursive(&mut circuit, controls, target),
+                Gate::Mcz(n) if n >= 2 => {
+                    circuit.h(target);
+                    mcx_recursive(&mut circuit, controls, target);
+                    circuit.h(target);
+                }
+                Gate::Mcu(n, ref operator) if n >= 1 => {
+                    mcu_recursive(&mut circuit, operator, controls, target)
+                }
+                ref gate => {
+                    circuit.push(gate.clone(), &inst.qubits);
+                }
+            }
+        }
+        circuit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::{h_matrix, matmul, x_matrix};
+    use crate::simulator::random::random_unitary;
+    use crate::simulator::rng::Rng;
+    use crate::simulator::testing::ApproxEq;
+
+    fn random_matrix2(rng: &mut Rng) -> Matrix2 {
+        let u = random_unitary(1, rng);
+        [[u[(0, 0)], u[(0, 1)]], [u[(1, 0)], u[(1, 1)]]]
+    }
+
+    fn max_arity(circuit: &Circuit) -> usize {
+        circuit.instructions().iter().map(|i| i.qubits.len()).max().unwrap_or(0)
+    }
+
+    #[test]
+    fn test_sqrt_unitary_squares_back() {
+        let mut rng = Rng::seed_from_u64(49);
+        for m in [x_matrix(), h_matrix(), random_matrix2(&mut rng)] {
+            let v = sqrt_unitary(&m);
+            let square = matmul(&v, &v);
+            for (a, b) in square.iter().flatten().zip(m.iter().flatten()) {
+                assert!((a - b).norm() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_recursive_decomposition_is_exact() {
+        let mut rng = Rng::seed_from_u64(50);
+        let u = random_matrix2(&mut rng);
+        let mut native = Circuit::new(5);
+        native.h(0).mcx(&[0, 1, 2, 3], 4).mcz(&[4, 1], 2);
+        native.push(Gate::Mcu(3, u.into()), &[3, 0, 4, 1]);
+        native.cp(0.7, 2, 0);
+        let lowered = native.decompose_multi_controlled();
+        assert!(max_arity(&lowered) <= 2);
+        assert!(lowered.to_unitary().approx_eq(&native.to_unitary(), 1e-8));
+    }
+
+    #[test]
+    fn test_dirty_ancillas_are_restored() {
+        // 4 controls, target 4, dirty qubits 5 and 6 in any state
+        let controls = [0, 1, 2, 3];
+        let mut borrowed = Circuit::new(7);
+        mcx_dirty(&mut borrowed, &controls, 4, &[5, 6]);
+        let mut spare = Circuit::new(7);
+        mcx_with_spare(&mut spare, &controls, 4, 6);
+        let mut native = Circuit::new(7);
+        native.mcx(&controls, 4);
+        for lowered in [borrowed, spare] {
+            assert!(max_arity(&lowered) <= 2);
+            assert!(lowered.to_unitary().approx_eq(&native.to_unitary(), 1e-8));
+        }
+    }
+
+    #[test]
+    fn test_recursive_gate_count_is_quadratic() {
+        let counts: Vec<usize> = (2..=9)
+            .map(|k| {
+                let mut circuit = Circuit::new(k + 1);
+                mcu_recursive(&mut circuit, &Gate::H.operator(), &(0..k).collect::<Vec<_>>(), k);
+                circuit.len()
+            })
+            .collect();
+        assert_eq!(counts, vec![26, 72, 208, 524, 1020, 1756, 2732, 3948]);
+        // constant-ish second differences; the old 3^k recursion reached 6472 at k = 7
+        let steps: Vec<usize> = counts.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(steps.windows(2).all(|w| w[1] - w[0] <= 240), "{:?}", steps);
+    }
+
+    #[test]
+    fn test_v_chains_restore_ancillas() {
+        let mut rng = Rng::seed_from_u64(51);
+        let u = random_matrix2(&mut rng);
+        // 4 controls, target 4, ancillas 5..8
+        let controls = [0, 1, 2, 3];
+        let mut x_chain = Circuit::new(8);
+        mcx_v_chain(&mut x_chain, &controls, 4, &[5, 6]);
+        let mut u_chain = Circuit::new(8);
+        mcu_v_chain(&mut u_chain, &Operator::from(u), &controls, 4, &[5, 6, 7]);
+        let mut x_native = Circuit::new(8);
+        x_native.mcx(&controls, 4);
+        let mut u_native = Circuit::new(8);
+        u_native.push(Gate::Mcu(4, u.into()), &[0, 1, 2, 3, 4]);
+        for (chain, native) in [(x_chain, x_native), (u_chain, u_native)] {
+            assert!(max_arity(&chain) <= 2);
+            let (a, b) = (chain.to_unitary(), native.to_unitary());
+            // columns with the ancillas in |0⟩ must agree exactly
+            for k in 0..32 {
+                for i in 0..256 {
+                    assert!((a[(i, k)] - b[(i, k)]).norm() < 1e-8, "column {} row {}", k, i);
+                }
+            }
+        }
+    }
+}
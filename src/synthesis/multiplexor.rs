@@ -0,0 +1,161 @@
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::Gate;
+use crate::simulator::operator::Operator;
+use super::euler::decompose_zyz;
+
+/// k-th Gray code
+fn gray(k: usize) -> usize {
+    k ^ (k >> 1)
+}
+
+/// Gray-code multiplexed rotation with 2^m rotations and 2^m CNOTs
+/// (Möttönen et al.), valid for axes that X-conjugation reverses
+fn uniformly_controlled(
+    circuit: &mut Circuit,
+    rotation: fn(f64) -> Gate,
+    angles: &[f64],
+    controls: &[usize],
+    target: usize,
+) {
+    let m = controls.len();
+    let size = 1usize << m;
+    assert_eq!(angles.len(), size, "need one angle per control pattern");
+    if m == 0 {
+        circuit.push(rotation(angles[0]), &[target]);
+        return;
+    }
+    // rotation i sees the target flipped by parity(k & gray(i)) for control value k
+    for i in 0..size {
+        let theta: f64 = angles
+            .iter()
+            .enumerate()
+            .map(|(k, a)| if (k & gray(i)).count_ones().is_multiple_of(2) { *a } else { -a })
+            .sum::<f64>()
+            / size as f64;
+        circuit.push(rotation(theta), &[target]);
+        let changed = gray(i) ^ gray((i + 1) % size);
+        circuit.cx(controls[changed.trailing_zeros() as usize], target);
+    }
+}
+
+/// Ry(angles[k]) on `target` when the controls read k (controls[0] is the low bit)
+pub fn uniformly_controlled_ry(
+    circuit: &mut Circuit,
+    angles: &[f64],
+    controls: &[usize],
+    target: usize,
+) {
+    uniformly_controlled(circuit, Gate::Ry, angles, controls, target);
+}
+
+/// Rz(angles[k]) on `target` when the controls read k (controls[0] is the low bit)
+pub fn uniformly_controlled_rz(
+    circuit: &mut Circuit,
+    angles: &[f64],
+    controls: &[usize],
+    target: usize,
+) {
+    uniformly_controlled(circuit, Gate::Rz, angles, controls, target);
+}
+
+/// diagonal gate |k⟩ ↦ e^{i·phases[k]}|k⟩ up to global phase, qubits[0] the low bit
+///
+/// Peels the highest qubit off as a uniformly controlled Rz: diag(e^{ia}, e^{ib})
+/// is e^{i(a+b)/2} Rz(b − a), leaving the mean phases for the remaining qubits.
+pub fn diagonal(circuit: &mut Circuit, phases: &[f64], qubits: &[usize]) {
+    assert_eq!(phases.len(), 1 << qubits.len(), "need one phase per basis state");
+    let Some((&top, rest)) = qubits.split_last() else {
+        return;
+    };
+    let half = phases.len() / 2;
+    let (low, high) = phases.split_at(half);
+    let angles: Vec<f64> = low.iter().zip(high).map(|(a, b)| b - a).collect();
+    let means: Vec<f64> = low.iter().zip(high).map(|(a, b)| (a + b) / 2.0).collect();
+    uniformly_controlled_rz(circuit, &angles, rest, top);
+    diagonal(circuit, &means, rest);
+}
+
+/// multiplexed single-qubit gate: gates[k] on `target` when the controls read k,
+/// up to global phase
+///
+/// Each gate is split as e^{iγ} Rz(φ) Ry(θ) Rz(λ), giving three uniformly
+/// controlled rotations and a diagonal on the controls for the phases γ.
+pub fn multiplexor(circuit: &mut Circuit, gates: &[Operator], controls: &[usize], target: usize) {
+    assert_eq!(gates.len(), 1 << controls.len(), "need one gate per control pattern");
+    let euler: Vec<_> = gates.iter().map(decompose_zyz).collect();
+    let lambdas: Vec<f64> = euler.iter().map(|e| e.lambda).collect();
+    let thetas: Vec<f64> = euler.iter().map(|e| e.theta).collect();
+    let phis: Vec<f64> = euler.iter().map(|e| e.phi).collect();
+    let phases: Vec<f64> = euler.iter().map(|e| e.global_phase).collect();
+    uniformly_controlled_rz(circuit, &lambdas, controls, target);
+    uniformly_controlled_ry(circuit, &thetas, controls, target);
+    uniformly_controlled_rz(circuit, &phis, controls, target);
+    diagonal(circuit, &phases, controls);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64;
+    use crate::simulator::matrix::Matrix;
+    use crate::simulator::random::random_unitary;
+    use crate::simulator::rng::Rng;
+    use crate::simulator::testing::ApproxEq;
+
+    /// reference: one fully controlled gate per control pattern
+    fn selected(n: usize, gates: &[Operator], controls: &[usize], target: usize) -> Circuit {
+        let mut circuit = Circuit::new(n);
+        let mut qubits = controls.to_vec();
+        qubits.push(target);
+        for (k, gate) in gates.iter().enumerate() {
+            let mut flips = Circuit::new(n);
+            for (j, &q) in controls.iter().enumerate() {
+                if k >> j & 1 == 0 {
+                    flips.x(q);
+                }
+            }
+            circuit.append(&flips);
+            circuit.push(Gate::Mcu(controls.len(), gate.clone()), &qubits);
+            circuit.append(&flips);
+        }
+        circuit
+    }
+
+    #[test]
+    fn test_uniformly_controlled_rotations_are_exact() {
+        let angles = [0.3, -1.1, 2.0, 0.7, -0.4, 1.9, 0.0, -2.5];
+        let controls = [3, 0, 2];
+        for rotation in [Gate::Ry as fn(f64) -> Gate, Gate::Rz] {
+            let mut circuit = Circuit::new(4);
+            uniformly_controlled(&mut circuit, rotation, &angles, &controls, 1);
+            assert_eq!(circuit.two_qubit_count(), 8);
+            let gates: Vec<Operator> =
+                angles.iter().filter_map(|&a| rotation(a).matrix()).collect();
+            let reference = selected(4, &gates, &controls, 1);
+            let (a, b) = (circuit.to_unitary(), reference.to_unitary());
+            for (x, y) in a.as_slice().iter().zip(b.as_slice()) {
+                assert!((x - y).norm() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiplexor_and_diagonal() {
+        let mut rng = Rng::seed_from_u64(52);
+        let gates: Vec<Operator> =
+            (0..4).map(|_| Operator::new(random_unitary(1, &mut rng))).collect();
+        let mut circuit = Circuit::new(3);
+        multiplexor(&mut circuit, &gates, &[0, 2], 1);
+        let reference = selected(3, &gates, &[0, 2], 1).to_unitary();
+        assert!(circuit.to_unitary().approx_eq(&reference, 1e-9));
+
+        let phases = [0.1, 0.9, -0.4, 2.2, 1.3, -1.7, 0.6, 0.0];
+        let mut diag = Circuit::new(3);
+        diagonal(&mut diag, &phases, &[0, 1, 2]);
+        let u = diag.to_unitary();
+        let reference = Matrix::from_fn(8, 8, |i, j| {
+            if i == j { Complex64::from_polar(1.0, phases[i]) } else { Complex64::new(0.0, 0.0) }
+        });
+        assert!(u.approx_eq(&reference, 1e-9));
+    }
+}
@@ -0,0 +1,286 @@
+use num_complex::Complex64;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::matrix::Matrix;
+use super::euler::decompose_zyz2;
+use super::kak::two_qubit_circuit;
+use super::multiplexor::{uniformly_controlled_ry, uniformly_controlled_rz};
+
+/// widest matrix `unitary_circuit` accepts
+pub const MAX_SYNTHESIS_QUBITS: usize = 6;
+
+/// columns of `a` scaled to unit length, taken in order of decreasing norm and
+/// re-orthogonalized; columns too short to carry a direction are completed
+/// with the basis vectors least covered so far
+fn unit_columns(a: &Matrix) -> Matrix {
+    let dim = a.rows();
+    let norm = |v: &[Complex64]| v.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+    let mut order: Vec<usize> = (0..a.cols()).collect();
+    order.sort_by(|&i, &j| norm(&a.column(j)).total_cmp(&norm(&a.column(i))));
+    let mut columns: Vec<Option<Vec<Complex64>>> = vec![None; a.cols()];
+    let mut basis: Vec<Vec<Complex64>> = Vec::new();
+    let orthogonalize = |mut v: Vec<Complex64>, basis: &[Vec<Complex64>]| {
+        for b in basis {
+            let overlap: Complex64 = b.iter().zip(&v).map(|(x, y)| x.conj() * y).sum();
+            for (x, y) in v.iter_mut().zip(b) {
+                *x -= overlap * y;
+            }
+        }
+        v
+    };
+    for &j in &order {
+        let v = orthogonalize(a.column(j), &basis);
+        let n = norm(&v);
+        if n > 1e-10 {
+            let v: Vec<Complex64> = v.into_iter().map(|x| x / n).collect();
+            basis.push(v.clone());
+            columns[j] = Some(v);
+        }
+    }
+    for column in columns.iter_mut().filter(|c| c.is_none()) {
+        let best = (0..dim)
+            .map(|k| {
+                let mut e = vec![Complex64::new(0.0, 0.0); dim];
+                e[k] = Complex64::new(1.0, 0.0);
+                orthogonalize(e, &basis)
+            })
+            .max_by(|x, y| norm(x).total_cmp(&norm(y)))
+            .expect("dimension is positive");
+        let n = norm(&best);
+        let v: Vec<Complex64> = best.into_iter().map(|x| x / n).collect();
+        basis.push(v.clone());
+        *column = Some(v);
+    }
+    Matrix::from_fn(dim, a.cols(), |i, j| columns[j].as_ref().expect("completed")[i])
+}
+
+/// cosine–sine decomposition of a 2m × 2m unitary split on its top bit,
+/// U = (L0 ⊕ L1)·[[C, −S], [S, C]]·(R0 ⊕ R1), returning (L0, L1, θ, R0, R1)
+/// with C = diag(cos θ) and S = diag(sin θ)
+fn cosine_sine(u: &Matrix) -> (Matrix, Matrix, Vec<f64>, Matrix, Matrix) {
+    let m = u.rows() / 2;
+    let block = |r: usize, c: usize| Matrix::from_fn(m, m, |i, j| u[(r * m + i, c * m + j)]);
+    let (u00, u01, u10, u11) = (block(0, 0), block(0, 1), block(1, 0), block(1, 1));
+    // U00†U00 = R0† C² R0
+    let q = (&u00.dagger() * &u00).eigen().vectors;
+    let (a, b) = (&u00 * &q, &u10 * &q);
+    let length = |x: &Matrix, j: usize| x.column(j).iter().map(|z| z.norm_sqr()).sum::<f64>();
+    let theta: Vec<f64> =
+        (0..m).map(|j| length(&b, j).sqrt().atan2(length(&a, j).sqrt())).collect();
+    let (l0, l1) = (unit_columns(&a), unit_columns(&b));
+    // each row of R1 from whichever of U01 = −L0 S R1, U11 = L1 C R1 divides by more
+    let (from_u01, from_u11) = (&l0.dagger() * &u01, &l1.dagger() * &u11);
+    let r1 = Matrix::from_fn(m, m, |i, j| {
+        let (c, s) = (theta[i].cos(), theta[i].sin());
+        if c >= s {
+            from_u11[(i, j)] / c
+        } else {
+            -from_u01[(i, j)] / s
+        }
+    });
+    (l0, l1, theta, q.dagger(), r1)
+}
+
+/// unitary V and phases φ with A1 = V D W and A2 = V D† W for D = diag(e^{iφ})
+/// and W = D V† A2, from the spectrum of A1 A2†
+///
+/// The Hermitian and anti-Hermitian parts of a unitary commute, so a generic
+/// combination of them shares its eigenvectors; as in the KAK step a few fixed
+/// weights are tried in case one hits an accidental degeneracy.
+fn demultiplex(a1: &Matrix, a2: &Matrix) -> (Matrix, Vec<f64>, Matrix) {
+    let product = a1 * &a2.dagger();
+    let n = product.rows();
+    let adjoint = product.dagger();
+    for weight in [0.618, 1.37, -2.21, 3.3, 0.0] {
+        let mixed = Matrix::from_fn(n, n, |i, j| {
+            let (x, y) = (product[(i, j)], adjoint[(i, j)]);
+            (x + y) * 0.5 + (x - y) * Complex64::new(0.0, -0.5 * weight)
+        });
+        let v = mixed.eigen().vectors;
+        let d = &(&v.dagger() * &product) * &v;
+        let off: f64 =
+            (0..n * n).filter(|k| k / n != k % n).map(|k| d[(k / n, k % n)].norm()).sum();
+        if off < 1e-9 {
+            let phases: Vec<f64> = (0..n).map(|k| d[(k, k)].arg() / 2.0).collect();
+            let half = Matrix::from_fn(n, n, |i, j| {
+                if i == j {
+                    Complex64::from_polar(1.0, phases[i])
+                } else {
+                    Complex64::new(0.0, 0.0)
+                }
+            });
+            let w = &(&half * &v.dagger()) * a2;
+            return (v, phases, w);
+        }
+    }
+    panic!("could not diagonalize the multiplexor block product");
+}
+
+/// A1 ⊕ A2 on `num_qubits` qubits selected by the top one: W on the low
+/// qubits, a uniformly controlled Rz on the top qubit, then V
+fn multiplexed_unitary(circuit: &mut Circuit, a1: &Matrix, a2: &Matrix, num_qubits: usize) {
+    let (v, phases, w) = demultiplex(a1, a2);
+    let low: Vec<usize> = (0..num_qubits - 1).collect();
+    circuit.append(&synthesize(&w, num_qubits - 1));
+    // e^{iφ} ⊕ e^{−iφ} is Rz(−2φ)
+    let angles: Vec<f64> = phases.iter().map(|p| -2.0 * p).collect();
+    uniformly_controlled_rz(circuit, &angles, &low, num_qubits - 1);
+    circuit.append(&synthesize(&v, num_qubits - 1));
+}
+
+/// quantum Shannon decomposition (Shende, Bullock, Markov), up to global phase
+///
+/// The cosine–sine split on the top qubit leaves a uniformly controlled Ry
+/// between two block-diagonal unitaries, and each of those is demultiplexed
+/// into two half-size unitaries around a uniformly controlled Rz. Recursing
+/// down to the two-qubit KAK form gives O(4^n) CNOTs.
+fn shannon_circuit(u: &Matrix, num_qubits: usize) -> Circuit {
+    let (l0, l1, theta, r0, r1) = cosine_sine(u);
+    let low: Vec<usize> = (0..num_qubits - 1).collect();
+    let mut circuit = Circuit::new(num_qubits);
+    multiplexed_unitary(&mut circuit, &r0, &r1, num_qubits);
+    let angles: Vec<f64> = theta.iter().map(|t| 2.0 * t).collect();
+    uniformly_controlled_ry(&mut circuit, &angles, &low, num_qubits - 1);
+    multiplexed_unitary(&mut circuit, &l0, &l1, num_qubits);
+    circuit
+}
+
+fn synthesize(u: &Matrix, num_qubits: usize) -> Circuit {
+    match num_qubits {
+        0 => Circuit::new(0),
+        1 => {
+            let mut circuit = Circuit::new(1);
+            let m = [[u[(0, 0)], u[(0, 1)]], [u[(1, 0)], u[(1, 1)]]];
+            for gate in decompose_zyz2(&m).gates() {
+                circuit.push(gate, &[0]);
+            }
+            circuit
+        }
+        2 => two_qubit_circuit(u),
+        n => shannon_circuit(u, n),
+    }
+}
+
+/// CX + single-qubit circuit equal to `u` up to global phase
+///
+/// One qubit uses ZYZ Euler angles, two qubits the three-CNOT KAK form, and
+/// wider matrices the quantum Shannon decomposition, whose CNOT count grows
+/// as 4^n; it is meant for small black-box blocks.
+pub fn unitary_circuit(u: &Matrix) -> Circuit {
+    let dim = u.rows();
+    assert!(dim.is_power_of_two() && u.cols() == dim, "expected a 2^n × 2^n matrix");
+    assert!(u.is_unitary(1e-8), "matrix is not unitary");
+    let num_qubits = dim.trailing_zeros() as usize;
+    assert!(
+        num_qubits <= MAX_SYNTHESIS_QUBITS,
+        "unitary synthesis is limited to {} qubits",
+        MAX_SYNTHESIS_QUBITS
+    );
+    synthesize(u, num_qubits)
+}
+
+/// unitary whose first columns are those of the isometry `v` (2^n × m)
+///
+/// The remaining columns are greedily orthogonalized basis vectors.
+pub fn complete_isometry(v: &Matrix) -> Matrix {
+    let dim = v.rows();
+    assert!(v.cols() <= dim, "isometry has more columns than rows");
+    let mut columns: Vec<Vec<Complex64>> = (0..v.cols()).map(|j| v.column(j)).collect();
+    while columns.len() < dim {
+        let residual = |k: usize| {
+            let mut e = vec![Complex64::new(0.0, 0.0); dim];
+            e[k] = Complex64::new(1.0, 0.0);
+            for c in &columns {
+                let overlap = c[k].conj();
+                for (x, y) in e.iter_mut().zip(c) {
+                    *x -= overlap * y;
+                }
+            }
+            e
+        };
+        let norm = |e: &[Complex64]| e.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+        let best = (0..dim)
+            .map(residual)
+            .max_by(|a, b| norm(a).total_cmp(&norm(b)))
+            .expect("dimension is positive");
+        let n = norm(&best);
+        columns.push(best.into_iter().map(|x| x / n).collect());
+    }
+    Matrix::from_fn(dim, dim, |i, j| columns[j][i])
+}
+
+/// circuit mapping |k⟩ to column k of the isometry `v` for k < v.cols()
+pub fn isometry_circuit(v: &Matrix) -> Circuit {
+    unitary_circuit(&complete_isometry(v))
+}
+
+impl Circuit {
+    /// append the synthesized gates of `u`, with qubits[0] as its low bit
+    pub fn unitary(&mut self, u: &Matrix, qubits: &[usize]) -> &mut Self {
+        assert_eq!(1 << qubits.len(), u.rows(), "matrix size does not match the qubits");
+        let block = unitary_circuit(u).remapped(self.num_qubits(), qubits);
+        self.append(&block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::Gate;
+    use crate::simulator::random::random_unitary;
+    use crate::simulator::rng::Rng;
+    use crate::simulator::testing::ApproxEq;
+
+    fn max_arity(circuit: &Circuit) -> usize {
+        circuit.instructions().iter().map(|i| i.qubits.len()).max().unwrap_or(0)
+    }
+
+    #[test]
+    fn test_random_unitaries() {
+        let mut rng = Rng::seed_from_u64(53);
+        for n in 1..=4 {
+            let u = random_unitary(n, &mut rng);
+            let circuit = unitary_circuit(&u);
+            assert!(max_arity(&circuit) <= 2);
+            assert!(circuit.to_unitary().approx_eq(&u, 1e-8), "{} qubits", n);
+        }
+    }
+
+    #[test]
+    fn test_shannon_gate_counts_grow_as_four_to_the_n() {
+        let mut rng = Rng::seed_from_u64(55);
+        let counts: Vec<usize> = (3..=5)
+            .map(|n| {
+                let circuit = unitary_circuit(&random_unitary(n, &mut rng));
+                circuit.instructions().iter().filter(|i| i.qubits.len() == 2).count()
+            })
+            .collect();
+        // 4·3 KAK CNOTs plus three 4-CNOT multiplexors at n = 3, then ×4 and +3·2^(n−1)
+        assert_eq!(counts, vec![24, 120, 528]);
+    }
+
+    #[test]
+    fn test_permutation_and_embedding() {
+        // a cyclic shift |k⟩ → |k+1 mod 8⟩ has many exact zeros
+        let shift = Matrix::from_fn(8, 8, |i, j| {
+            Complex64::new(if i == (j + 1) % 8 { 1.0 } else { 0.0 }, 0.0)
+        });
+        let mut circuit = Circuit::new(4);
+        circuit.unitary(&shift, &[3, 0, 2]);
+        let mut expected = Circuit::new(4);
+        expected.push(Gate::Mcx(2), &[3, 0, 2]).cx(3, 0).x(3);
+        assert!(circuit.to_unitary().approx_eq(&expected.to_unitary(), 1e-8));
+    }
+
+    #[test]
+    fn test_isometry() {
+        let mut rng = Rng::seed_from_u64(54);
+        let u = random_unitary(3, &mut rng);
+        let v = Matrix::from_fn(8, 2, |i, j| u[(i, j)]);
+        let full = complete_isometry(&v);
+        assert!(full.is_unitary(1e-10));
+        let circuit_u = isometry_circuit(&v).to_unitary();
+        // the first two columns agree up to one shared phase
+        let block = Matrix::from_fn(8, 2, |i, j| circuit_u[(i, j)]);
+        assert!(block.approx_eq(&v, 1e-8));
+    }
+}
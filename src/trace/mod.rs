@@ -0,0 +1,5 @@
+pub mod span;
+pub mod subscriber;
+
+pub use span::{clear_subscriber, enabled, set_subscriber, span, SpanGuard};
+pub use subscriber::{Recorder, SpanRecord, StderrLogger, Subscriber};
@@ -0,0 +1,110 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Instant;
+use super::subscriber::{SpanRecord, Subscriber};
+
+thread_local! {
+    static SUBSCRIBER: RefCell<Option<Rc<dyn Subscriber>>> = const { RefCell::new(None) };
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// route spans on this thread to `subscriber`
+pub fn set_subscriber(subscriber: Rc<dyn Subscriber>) {
+    SUBSCRIBER.with(|s| *s.borrow_mut() = Some(subscriber));
+}
+
+pub fn clear_subscriber() {
+    SUBSCRIBER.with(|s| *s.borrow_mut() = None);
+}
+
+/// a subscriber is installed on this thread
+pub fn enabled() -> bool {
+    SUBSCRIBER.with(|s| s.borrow().is_some())
+}
+
+/// open span; reports to the subscriber when dropped
+#[must_use = "the span closes when the guard is dropped"]
+pub struct SpanGuard {
+    active: Option<(&'static str, String, usize, Instant)>,
+}
+
+/// start a span; `detail` is only evaluated when a subscriber is installed
+pub fn span(name: &'static str, detail: impl FnOnce() -> String) -> SpanGuard {
+    let subscriber = SUBSCRIBER.with(|s| s.borrow().clone());
+    let Some(subscriber) = subscriber else {
+        return SpanGuard { active: None };
+    };
+    let detail = detail();
+    let depth = DEPTH.with(|d| {
+        let depth = d.get();
+        d.set(depth + 1);
+        depth
+    });
+    subscriber.enter(name, &detail, depth);
+    SpanGuard {
+        active: Some((name, detail, depth, Instant::now())),
+    }
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        let Some((name, detail, depth, start)) = self.active.take() else {
+            return;
+        };
+        DEPTH.with(|d| d.set(depth));
+        let record = SpanRecord {
+            name,
+            detail,
+            depth,
+            elapsed: start.elapsed(),
+        };
+        let subscriber = SUBSCRIBER.with(|s| s.borrow().clone());
+        if let Some(subscriber) = subscriber {
+            subscriber.exit(&record);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::{noisy_counts, NoiseModel};
+    use crate::simulator::circuit::Circuit;
+    use crate::simulator::register::Register;
+    use crate::simulator::rng::Rng;
+    use crate::trace::subscriber::Recorder;
+
+    #[test]
+    fn test_circuit_spans_nest() {
+        let recorder = Rc::new(Recorder::new());
+        set_subscriber(recorder.clone());
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1).swap(0, 1);
+        Register::new(2).apply_circuit(&circuit);
+        clear_subscriber();
+
+        assert_eq!(recorder.count("circuit"), 1);
+        assert_eq!(recorder.count("gate"), 3);
+        // swap has its own kernel; h and cx go through the 2×2 kernel
+        assert_eq!(recorder.count("kernel"), 3);
+        let records = recorder.records();
+        let gate = records.iter().find(|r| r.name == "gate").unwrap();
+        assert_eq!((gate.depth, gate.detail.as_str()), (1, "h [0]"));
+        assert_eq!(records.last().unwrap().depth, 0);
+    }
+
+    #[test]
+    fn test_shot_spans_and_disabled_by_default() {
+        assert!(!enabled());
+        let recorder = Rc::new(Recorder::new());
+        set_subscriber(recorder.clone());
+        let mut circuit = Circuit::new(1);
+        circuit.x(0);
+        let mut rng = Rng::seed_from_u64(1);
+        noisy_counts(&circuit, &NoiseModel::ideal(), 5, &mut rng);
+        clear_subscriber();
+        assert_eq!(recorder.count("shot"), 5);
+        let shot = recorder.records().into_iter().find(|r| r.name == "shot").unwrap();
+        assert_eq!(shot.depth, 0);
+    }
+}
@@ -0,0 +1,77 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+/// one finished span
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanRecord {
+    /// span kind: "circuit", "gate", "kernel", "shot", …
+    pub name: &'static str,
+    pub detail: String,
+    /// nesting depth, 0 for outermost spans
+    pub depth: usize,
+    pub elapsed: Duration,
+}
+
+/// receives span events on the thread it is installed on
+pub trait Subscriber {
+    fn enter(&self, _name: &'static str, _detail: &str, _depth: usize) {}
+    fn exit(&self, record: &SpanRecord);
+}
+
+/// keeps every finished span in memory, in exit order
+#[derive(Debug, Default)]
+pub struct Recorder {
+    records: RefCell<Vec<SpanRecord>>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn records(&self) -> Vec<SpanRecord> {
+        self.records.borrow().clone()
+    }
+
+    /// finished spans with the given name
+    pub fn count(&self, name: &str) -> usize {
+        self.records.borrow().iter().filter(|r| r.name == name).count()
+    }
+
+    /// summed wall time of spans with the given name
+    pub fn total_time(&self, name: &str) -> Duration {
+        self.records
+            .borrow()
+            .iter()
+            .filter(|r| r.name == name)
+            .map(|r| r.elapsed)
+            .sum()
+    }
+}
+
+impl Subscriber for Recorder {
+    fn exit(&self, record: &SpanRecord) {
+        self.records.borrow_mut().push(record.clone());
+    }
+}
+
+/// prints an indented line per finished span to stderr, skipping spans
+/// nested deeper than `max_depth`
+#[derive(Debug, Clone, Copy)]
+pub struct StderrLogger {
+    pub max_depth: usize,
+}
+
+impl Subscriber for StderrLogger {
+    fn exit(&self, record: &SpanRecord) {
+        if record.depth <= self.max_depth {
+            eprintln!(
+                "{}{} {} ({:?})",
+                "  ".repeat(record.depth),
+                record.name,
+                record.detail,
+                record.elapsed
+            );
+        }
+    }
+}
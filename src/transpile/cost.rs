@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+use crate::noise::NoiseModel;
+use crate::simulator::circuit::{Circuit, Instruction};
+use crate::simulator::gates::Gate;
+use super::coupling::CouplingMap;
+
+/// per-qubit and per-edge error probabilities of a device
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceErrors {
+    single: Vec<f64>,
+    readout: Vec<f64>,
+    two: BTreeMap<(usize, usize), f64>,
+}
+
+fn edge(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+impl DeviceErrors {
+    /// the noise model's uniform rates on every qubit and coupling edge
+    pub fn from_noise(noise: &NoiseModel, coupling: &CouplingMap) -> Self {
+        let n = coupling.num_qubits();
+        Self {
+            single: vec![noise.depolarizing_1q; n],
+            readout: vec![noise.readout_error; n],
+            two: coupling
+                .edges()
+                .iter()
+                .map(|&(a, b)| (edge(a, b), noise.depolarizing_2q))
+                .collect(),
+        }
+    }
+
+    pub fn with_qubit_error(mut self, q: usize, p: f64) -> Self {
+        self.single[q] = p;
+        self
+    }
+
+    pub fn with_readout_error(mut self, q: usize, p: f64) -> Self {
+        self.readout[q] = p;
+        self
+    }
+
+    pub fn with_edge_error(mut self, a: usize, b: usize, p: f64) -> Self {
+        assert!(self.two.contains_key(&edge(a, b)), "({}, {}) is not a coupling edge", a, b);
+        self.two.insert(edge(a, b), p);
+        self
+    }
+
+    /// error of a two-qubit gate on a coupling edge; off-edge gates always fail
+    pub fn edge_error(&self, a: usize, b: usize) -> f64 {
+        self.two.get(&edge(a, b)).copied().unwrap_or(1.0)
+    }
+
+    /// error of one physical instruction; a SWAP costs three CNOTs
+    pub fn gate_error(&self, inst: &Instruction) -> f64 {
+        match (&inst.gate, inst.qubits.as_slice()) {
+            (Gate::I, _) => 0.0,
+            (_, &[q]) => self.single[q],
+            (Gate::Swap, &[a, b]) => 1.0 - (1.0 - self.edge_error(a, b)).powi(3),
+            (_, &[a, b]) => self.edge_error(a, b),
+            _ => 1.0,
+        }
+    }
+
+    /// −ln of a success probability, so costs add along a circuit
+    pub fn cost(p: f64) -> f64 {
+        -(1.0 - p).max(1e-300).ln()
+    }
+
+    /// cost of holding and reading out an idle qubit that carries `gates` gates
+    pub fn qubit_cost(&self, q: usize, gates: usize) -> f64 {
+        Self::cost(self.readout[q]) + gates as f64 * Self::cost(self.single[q])
+    }
+
+    /// chance that every gate succeeds and every touched qubit reads out correctly
+    pub fn predicted_fidelity(&self, circuit: &Circuit) -> f64 {
+        let gates: f64 = circuit.instructions().iter().map(|i| 1.0 - self.gate_error(i)).product();
+        let mut touched = vec![false; circuit.num_qubits()];
+        for inst in circuit.instructions() {
+            for &q in &inst.qubits {
+                touched[q] = true;
+            }
+        }
+        let readout: f64 = (0..circuit.num_qubits())
+            .filter(|&q| touched[q])
+            .map(|q| 1.0 - self.readout[q])
+            .product();
+        gates * readout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predicted_fidelity_multiplies_gate_successes() {
+        let coupling = CouplingMap::line(3);
+        let errors = DeviceErrors::from_noise(&NoiseModel::depolarizing(0.01, 0.05), &coupling)
+            .with_edge_error(1, 2, 0.1)
+            .with_readout_error(0, 0.02);
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).cx(0, 1).swap(2, 1);
+        let expected = 0.99 * 0.95 * 0.9f64.powi(3) * 0.98;
+        assert!((errors.predicted_fidelity(&circuit) - expected).abs() < 1e-12);
+        assert_eq!(errors.edge_error(0, 2), 1.0);
+    }
+}
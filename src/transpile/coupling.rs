@@ -0,0 +1,191 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// physical connectivity: the qubit pairs that support two-qubit gates
+#[derive(Debug, Clone, PartialEq)]
+pub struct CouplingMap {
+    num_qubits: usize,
+    edges: Vec<(usize, usize)>,
+}
+
+/// Dijkstra frontier entry, ordered so the heap pops the cheapest first
+#[derive(PartialEq)]
+struct Frontier(f64, usize);
+
+impl Eq for Frontier {}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then(other.1.cmp(&self.1))
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl CouplingMap {
+    /// undirected `edges` between `num_qubits` physical qubits
+    pub fn new(num_qubits: usize, edges: &[(usize, usize)]) -> Self {
+        for &(a, b) in edges {
+            assert!(a < num_qubits && b < num_qubits && a != b, "invalid edge ({}, {})", a, b);
+        }
+        Self {
+            num_qubits,
+            edges: edges.to_vec(),
+        }
+    }
+
+    /// chain 0–1–…–(n−1)
+    pub fn line(num_qubits: usize) -> Self {
+        let edges: Vec<(usize, usize)> = (1..num_qubits).map(|q| (q - 1, q)).collect();
+        Self::new(num_qubits, &edges)
+    }
+
+    /// chain closed into a cycle
+    pub fn ring(num_qubits: usize) -> Self {
+        let mut edges: Vec<(usize, usize)> = (1..num_qubits).map(|q| (q - 1, q)).collect();
+        if num_qubits > 2 {
+            edges.push((num_qubits - 1, 0));
+        }
+        Self::new(num_qubits, &edges)
+    }
+
+    /// `rows` × `cols` lattice, qubit r·cols + c at row r, column c
+    pub fn grid(rows: usize, cols: usize) -> Self {
+        let mut edges = Vec::new();
+        for r in 0..rows {
+            for c in 0..cols {
+                let q = r * cols + c;
+                if c + 1 < cols {
+                    edges.push((q, q + 1));
+                }
+                if r + 1 < rows {
+                    edges.push((q, q + cols));
+                }
+            }
+        }
+        Self::new(rows * cols, &edges)
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.edges.iter().any(|&e| e == (a, b) || e == (b, a))
+    }
+
+    pub fn neighbours(&self, q: usize) -> Vec<usize> {
+        self.edges
+            .iter()
+            .filter_map(|&(a, b)| {
+                if a == q {
+                    Some(b)
+                } else if b == q {
+                    Some(a)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// cheapest path from `a` to `b`, both ends included, under `weight(edge)`
+    pub fn shortest_path(
+        &self,
+        a: usize,
+        b: usize,
+        weight: impl Fn(usize, usize) -> f64,
+    ) -> Option<Vec<usize>> {
+        let mut dist = vec![f64::INFINITY; self.num_qubits];
+        let mut prev = vec![None; self.num_qubits];
+        let mut heap = BinaryHeap::new();
+        dist[a] = 0.0;
+        heap.push(Frontier(0.0, a));
+        while let Some(Frontier(d, q)) = heap.pop() {
+            if q == b {
+                break;
+            }
+            if d > dist[q] {
+                continue;
+            }
+            for next in self.neighbours(q) {
+                let candidate = d + weight(q, next);
+                if candidate < dist[next] {
+                    dist[next] = candidate;
+                    prev[next] = Some(q);
+                    heap.push(Frontier(candidate, next));
+                }
+            }
+        }
+        if dist[b].is_infinite() {
+            return None;
+        }
+        let mut path = vec![b];
+        while let Some(p) = prev[*path.last().unwrap()] {
+            path.push(p);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// all-pairs cheapest path costs under `weight(edge)`
+    pub fn distances(&self, weight: impl Fn(usize, usize) -> f64) -> Vec<Vec<f64>> {
+        let n = self.num_qubits;
+        let mut dist = vec![vec![f64::INFINITY; n]; n];
+        for (q, row) in dist.iter_mut().enumerate() {
+            row[q] = 0.0;
+        }
+        for &(a, b) in &self.edges {
+            dist[a][b] = dist[a][b].min(weight(a, b));
+            dist[b][a] = dist[b][a].min(weight(b, a));
+        }
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    let through = dist[i][k] + dist[k][j];
+                    if through < dist[i][j] {
+                        dist[i][j] = through;
+                    }
+                }
+            }
+        }
+        dist
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_topologies() {
+        assert_eq!(CouplingMap::line(4).edges().len(), 3);
+        assert_eq!(CouplingMap::ring(4).edges().len(), 4);
+        let grid = CouplingMap::grid(2, 3);
+        assert_eq!(grid.edges().len(), 7);
+        assert!(grid.connected(4, 1) && !grid.connected(0, 4));
+        let mut around = grid.neighbours(1);
+        around.sort();
+        assert_eq!(around, vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_weighted_paths_avoid_expensive_edges() {
+        let ring = CouplingMap::ring(4);
+        let cheap = |a: usize, b: usize| if (a.min(b), a.max(b)) == (0, 1) { 10.0 } else { 1.0 };
+        assert_eq!(ring.shortest_path(0, 2, cheap), Some(vec![0, 3, 2]));
+        assert_eq!(ring.shortest_path(0, 1, cheap), Some(vec![0, 3, 2, 1]));
+        let hops = ring.distances(|_, _| 1.0);
+        assert_eq!((hops[0][2], hops[1][3], hops[0][3]), (2.0, 2.0, 1.0));
+        let split = CouplingMap::new(3, &[(0, 1)]);
+        assert_eq!(split.shortest_path(0, 2, |_, _| 1.0), None);
+    }
+}
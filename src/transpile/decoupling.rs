@@ -0,0 +1,134 @@
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::Gate;
+use crate::simulator::timing::{Schedule, ScheduledCircuit, Timing};
+
+/// pulse train filling an idle window; each multiplies to the identity up to
+/// global phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DdSequence {
+    /// X X, a spin echo split in two
+    Xx,
+    /// X Y X Y, which also cancels pulse errors to first order
+    Xy4,
+}
+
+impl DdSequence {
+    pub fn gates(&self) -> &'static [Gate] {
+        match self {
+            DdSequence::Xx => &[Gate::X, Gate::X],
+            DdSequence::Xy4 => &[Gate::X, Gate::Y, Gate::X, Gate::Y],
+        }
+    }
+}
+
+/// circuit with DD pulses in its idle windows and the schedule placing them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoupled {
+    pub circuit: Circuit,
+    pub schedule: ScheduledCircuit,
+    /// idle windows that received a sequence
+    pub windows_filled: usize,
+}
+
+/// fill every idle window of `circuit` under `schedule` that is long enough
+/// for `sequence`; n pulses sit at the centres of n equal slices, so the
+/// free evolution before, between and after them is τ/2, τ, …, τ, τ/2
+pub fn insert_dd(circuit: &Circuit, schedule: &Schedule, sequence: DdSequence) -> Decoupled {
+    let scheduled = circuit.schedule(schedule);
+    let gates = sequence.gates();
+    let durations: Vec<f64> = gates.iter().map(|g| schedule.times.duration(g)).collect();
+    let slots = gates.len() as f64;
+    // pulses to place before instruction k, or at the end for `None`
+    let mut pending: Vec<(Option<usize>, usize, Gate, Timing)> = Vec::new();
+    let mut windows_filled = 0;
+    for window in scheduled.idle_windows() {
+        let length = window.end - window.start;
+        let slice = length / slots;
+        if durations.iter().any(|&d| d > slice) {
+            continue;
+        }
+        windows_filled += 1;
+        for (k, (gate, &duration)) in gates.iter().zip(&durations).enumerate() {
+            let centre = window.start + slice * (k as f64 + 0.5);
+            let timing = Timing { start: centre - duration / 2.0, duration };
+            pending.push((window.before, window.qubit, gate.clone(), timing));
+        }
+    }
+    let mut decoupled = Circuit::new(circuit.num_qubits());
+    let mut timings = Vec::new();
+    let place = |before: Option<usize>, decoupled: &mut Circuit, timings: &mut Vec<Timing>| {
+        for &(_, qubit, ref gate, timing) in pending.iter().filter(|p| p.0 == before) {
+            decoupled.push(gate.clone(), &[qubit]);
+            timings.push(timing);
+        }
+    };
+    for (k, inst) in circuit.instructions().iter().enumerate() {
+        place(Some(k), &mut decoupled, &mut timings);
+        decoupled.push(inst.gate.clone(), &inst.qubits);
+        timings.push(scheduled.timings[k]);
+    }
+    place(None, &mut decoupled, &mut timings);
+    Decoupled {
+        schedule: ScheduledCircuit::new(&decoupled, timings, scheduled.total),
+        circuit: decoupled,
+        windows_filled,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::{scheduled_counts, NoiseModel};
+    use crate::simulator::rng::Rng;
+    use crate::simulator::timing::GateTimes;
+
+    /// qubit 0 waits in |+⟩ while qubits 1 and 2 run eight CNOTs
+    fn idle_superposition() -> Circuit {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).x(1);
+        for _ in 0..8 {
+            circuit.cx(1, 2);
+        }
+        circuit.cx(1, 0).h(0);
+        circuit
+    }
+
+    #[test]
+    fn test_pulses_fill_only_long_windows() {
+        let circuit = idle_superposition();
+        let schedule = Schedule::asap(GateTimes::superconducting());
+        let decoupled = insert_dd(&circuit, &schedule, DdSequence::Xy4);
+        // qubit 0 idles 2400 ns and qubit 2 idles 335 ns at the end; the
+        // 35 ns gaps are too short for four 35 ns pulses
+        assert_eq!(decoupled.windows_filled, 2);
+        assert_eq!(decoupled.circuit.len(), circuit.len() + 8);
+        assert_eq!(decoupled.schedule.total, circuit.duration(&schedule));
+        assert!(decoupled.schedule.idle_time(0) < circuit.schedule(&schedule).idle_time(0));
+        crate::assert_unitary_eq!(decoupled.circuit.to_unitary(), circuit.to_unitary());
+    }
+
+    #[test]
+    fn test_echo_pulses_refocus_frequency_noise() {
+        let circuit = idle_superposition();
+        let times = GateTimes::superconducting();
+        let schedule = Schedule::asap(times.clone());
+        let noise = NoiseModel::ideal()
+            .with_relaxation(100_000.0, 100_000.0, times)
+            .with_frequency_noise(0.001);
+        let shots = 1000;
+        let ground = |counts: &std::collections::BTreeMap<usize, usize>| {
+            counts.iter().filter(|(k, _)| *k & 1 == 0).map(|(_, n)| n).sum::<usize>() as f64
+                / shots as f64
+        };
+        let mut rng = Rng::seed_from_u64(81);
+        let bare = circuit.schedule(&schedule);
+        let bare = ground(&scheduled_counts(&circuit, &bare, &noise, shots, &mut rng));
+        assert!(bare < 0.7, "bare {}", bare);
+        // only the 335 ns spent in cx(1, 0) and h(0) stays unrefocused
+        for sequence in [DdSequence::Xx, DdSequence::Xy4] {
+            let dd = insert_dd(&circuit, &schedule, sequence);
+            let counts = scheduled_counts(&dd.circuit, &dd.schedule, &noise, shots, &mut rng);
+            assert!(ground(&counts) > 0.92, "{:?} {}", sequence, ground(&counts));
+        }
+    }
+}
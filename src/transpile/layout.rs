@@ -0,0 +1,201 @@
+use std::cmp::Reverse;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::rng::Rng;
+use super::coupling::CouplingMap;
+use super::routing::{route_with_layout, CostModel};
+
+/// simulated annealing schedule for the layout search
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnealConfig {
+    pub iterations: usize,
+    /// starting temperature in units of the cost model's score
+    pub initial_temperature: f64,
+    /// temperature factor applied after every move
+    pub cooling: f64,
+    pub seed: u64,
+}
+
+impl Default for AnnealConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 500,
+            initial_temperature: 1.0,
+            cooling: 0.99,
+            seed: 0,
+        }
+    }
+}
+
+/// how the transpiler assigns logical to physical qubits before routing
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutStage {
+    /// logical qubit q on physical qubit q
+    Trivial,
+    Greedy,
+    /// greedy start refined by simulated annealing on the routed score
+    Anneal(AnnealConfig),
+}
+
+impl LayoutStage {
+    pub fn run(
+        &self,
+        circuit: &Circuit,
+        coupling: &CouplingMap,
+        cost: &CostModel,
+    ) -> Result<Vec<usize>, String> {
+        if circuit.num_qubits() > coupling.num_qubits() {
+            return Err(format!(
+                "{} logical qubits do not fit on {} physical qubits",
+                circuit.num_qubits(),
+                coupling.num_qubits()
+            ));
+        }
+        match self {
+            LayoutStage::Trivial => Ok((0..circuit.num_qubits()).collect()),
+            LayoutStage::Greedy => Ok(greedy_layout(circuit, coupling, cost)),
+            LayoutStage::Anneal(config) => anneal_layout(circuit, coupling, cost, config),
+        }
+    }
+}
+
+/// greedy placement: busiest logical qubits first, each on the free physical
+/// qubit nearest (under the cost model) to its already placed partners
+pub fn greedy_layout(circuit: &Circuit, coupling: &CouplingMap, cost: &CostModel) -> Vec<usize> {
+    let (n, m) = (circuit.num_qubits(), coupling.num_qubits());
+    let mut interactions = vec![vec![0usize; n]; n];
+    let mut gates = vec![0usize; n];
+    for inst in circuit.instructions() {
+        for &q in &inst.qubits {
+            gates[q] += 1;
+        }
+        if let [a, b] = *inst.qubits.as_slice() {
+            interactions[a][b] += 1;
+            interactions[b][a] += 1;
+        }
+    }
+    let dist = coupling.distances(|a, b| cost.gate_cost(a, b));
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|&l| Reverse(interactions[l].iter().sum::<usize>()));
+    let mut layout: Vec<Option<usize>> = vec![None; n];
+    let mut used = vec![false; m];
+    for &l in &order {
+        let site_cost = |p: usize| {
+            let mut total = cost.qubit_cost(p, gates[l]);
+            // unplaced partners are assumed to land on the best free neighbour
+            let best_edge = coupling
+                .neighbours(p)
+                .into_iter()
+                .filter(|&r| !used[r])
+                .map(|r| cost.gate_cost(p, r))
+                .fold(f64::INFINITY, f64::min);
+            for (other, &weight) in interactions[l].iter().enumerate().filter(|(_, &w)| w > 0) {
+                total += weight as f64 * layout[other].map_or(best_edge, |r| dist[p][r]);
+            }
+            total
+        };
+        let site = (0..m)
+            .filter(|&p| !used[p])
+            .min_by(|&p, &r| site_cost(p).total_cmp(&site_cost(r)))
+            .expect("device has enough qubits");
+        layout[l] = Some(site);
+        used[site] = true;
+    }
+    layout.into_iter().map(|p| p.expect("every qubit placed")).collect()
+}
+
+/// simulated annealing over layouts, each scored by actually routing it;
+/// a move exchanges the contents of two physical qubits, used or free
+pub fn anneal_layout(
+    circuit: &Circuit,
+    coupling: &CouplingMap,
+    cost: &CostModel,
+    config: &AnnealConfig,
+) -> Result<Vec<usize>, String> {
+    let m = coupling.num_qubits();
+    let score = |layout: &[usize]| -> Result<f64, String> {
+        Ok(cost.score(&route_with_layout(circuit, coupling, cost, layout)?))
+    };
+    let mut current = greedy_layout(circuit, coupling, cost);
+    let mut current_score = score(&current)?;
+    let mut best = (current_score, current.clone());
+    let mut rng = Rng::seed_from_u64(config.seed);
+    let mut temperature = config.initial_temperature;
+    for _ in 0..config.iterations {
+        if m < 2 || best.0 == 0.0 {
+            break;
+        }
+        let a = rng.gen_range(m);
+        let b = (a + 1 + rng.gen_range(m - 1)) % m;
+        let mut candidate = current.clone();
+        for p in candidate.iter_mut() {
+            if *p == a {
+                *p = b;
+            } else if *p == b {
+                *p = a;
+            }
+        }
+        let candidate_score = score(&candidate)?;
+        let delta = candidate_score - current_score;
+        if delta <= 0.0 || rng.gen_bool((-delta / temperature).exp()) {
+            current = candidate;
+            current_score = candidate_score;
+            if current_score < best.0 {
+                best = (current_score, current.clone());
+            }
+        }
+        temperature *= config.cooling;
+    }
+    Ok(best.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transpile::routing::transpile;
+
+    fn long_range_circuit() -> Circuit {
+        // a ring of interactions 0–3–1–4–2–0 laid out badly on a line
+        let mut circuit = Circuit::new(5);
+        for _ in 0..3 {
+            circuit.cx(0, 3).cx(3, 1).cx(1, 4).cx(4, 2);
+        }
+        circuit
+    }
+
+    #[test]
+    fn test_stages_return_valid_layouts() {
+        let coupling = CouplingMap::grid(2, 3);
+        let circuit = long_range_circuit();
+        let cost = CostModel::SwapCount;
+        let trivial = LayoutStage::Trivial.run(&circuit, &coupling, &cost).unwrap();
+        assert_eq!(trivial, vec![0, 1, 2, 3, 4]);
+        let stage = LayoutStage::Anneal(AnnealConfig::default());
+        for layout in [
+            LayoutStage::Greedy.run(&circuit, &coupling, &cost).unwrap(),
+            stage.run(&circuit, &coupling, &cost).unwrap(),
+        ] {
+            let mut sorted = layout.clone();
+            sorted.sort();
+            sorted.dedup();
+            assert_eq!(sorted.len(), 5);
+            assert!(sorted.iter().all(|&p| p < 6));
+        }
+        assert!(LayoutStage::Trivial.run(&circuit, &CouplingMap::line(4), &cost).is_err());
+    }
+
+    #[test]
+    fn test_annealing_removes_routing_overhead() {
+        let coupling = CouplingMap::line(5);
+        let circuit = long_range_circuit();
+        let cost = CostModel::SwapCount;
+        let swaps = |stage: &LayoutStage| {
+            transpile(&circuit, &coupling, &cost, stage).unwrap().swaps
+        };
+        let trivial = swaps(&LayoutStage::Trivial);
+        let annealed = swaps(&LayoutStage::Anneal(AnnealConfig::default()));
+        assert!(trivial > 0);
+        // the interaction chain 0–3–1–4–2 fits the line exactly
+        assert_eq!(annealed, 0);
+        assert!(annealed <= swaps(&LayoutStage::Greedy));
+    }
+}
@@ -0,0 +1,11 @@
+pub mod cost;
+pub mod coupling;
+pub mod decoupling;
+pub mod layout;
+pub mod routing;
+
+pub use cost::DeviceErrors;
+pub use coupling::CouplingMap;
+pub use decoupling::{insert_dd, DdSequence, Decoupled};
+pub use layout::{anneal_layout, greedy_layout, AnnealConfig, LayoutStage};
+pub use routing::{route, route_with_layout, transpile, CostModel, Routed};
@@ -0,0 +1,233 @@
+use crate::simulator::circuit::Circuit;
+use super::coupling::CouplingMap;
+use super::cost::DeviceErrors;
+use super::layout::{greedy_layout, LayoutStage};
+
+/// what layout selection and SWAP routing minimise
+#[derive(Debug, Clone, PartialEq)]
+pub enum CostModel {
+    /// inserted SWAPs, every edge alike
+    SwapCount,
+    /// predicted error from per-gate and readout error rates
+    Noise(DeviceErrors),
+}
+
+impl CostModel {
+    /// cost of one two-qubit gate on a coupling edge
+    pub(super) fn gate_cost(&self, a: usize, b: usize) -> f64 {
+        match self {
+            CostModel::SwapCount => 1.0,
+            CostModel::Noise(errors) => DeviceErrors::cost(errors.edge_error(a, b)),
+        }
+    }
+
+    /// cost of keeping a logical qubit with `gates` single-qubit gates on `q`
+    pub(super) fn qubit_cost(&self, q: usize, gates: usize) -> f64 {
+        match self {
+            CostModel::SwapCount => 0.0,
+            CostModel::Noise(errors) => errors.qubit_cost(q, gates),
+        }
+    }
+
+    /// lower is better
+    pub(super) fn score(&self, routed: &Routed) -> f64 {
+        match self {
+            CostModel::SwapCount => routed.swaps as f64,
+            CostModel::Noise(errors) => -errors.predicted_fidelity(&routed.circuit).ln(),
+        }
+    }
+}
+
+/// a circuit rewritten onto physical qubits
+#[derive(Debug, Clone, PartialEq)]
+pub struct Routed {
+    /// two-qubit gates act only on coupling edges
+    pub circuit: Circuit,
+    /// physical qubit holding each logical qubit at the start
+    pub initial_layout: Vec<usize>,
+    /// physical qubit holding each logical qubit after the inserted SWAPs
+    pub final_layout: Vec<usize>,
+    pub swaps: usize,
+}
+
+/// route from a fixed initial layout, moving the first qubit of each distant
+/// pair along the cheapest path until the two are adjacent
+pub fn route_with_layout(
+    circuit: &Circuit,
+    coupling: &CouplingMap,
+    cost: &CostModel,
+    layout: &[usize],
+) -> Result<Routed, String> {
+    let m = coupling.num_qubits();
+    if layout.len() != circuit.num_qubits() {
+        let n = circuit.num_qubits();
+        return Err(format!("layout has {} entries for {} qubits", layout.len(), n));
+    }
+    let mut occupant: Vec<Option<usize>> = vec![None; m];
+    for (l, &p) in layout.iter().enumerate() {
+        if p >= m || occupant[p].is_some() {
+            return Err(format!("invalid or repeated physical qubit {} in layout", p));
+        }
+        occupant[p] = Some(l);
+    }
+    let initial_layout = layout.to_vec();
+    let mut layout = layout.to_vec();
+    let mut routed = Circuit::new(m);
+    let mut swaps = 0;
+    for inst in circuit.instructions() {
+        match *inst.qubits.as_slice() {
+            [q] => {
+                routed.push(inst.gate.clone(), &[layout[q]]);
+            }
+            [a, b] => {
+                let (pa, pb) = (layout[a], layout[b]);
+                if !coupling.connected(pa, pb) {
+                    let weight = |x, y| 3.0 * cost.gate_cost(x, y);
+                    let path = coupling.shortest_path(pa, pb, weight).ok_or_else(|| {
+                        format!("physical qubits {} and {} are not connected", pa, pb)
+                    })?;
+                    for hop in path[..path.len() - 1].windows(2) {
+                        routed.swap(hop[0], hop[1]);
+                        swaps += 1;
+                        occupant.swap(hop[0], hop[1]);
+                        for p in [hop[0], hop[1]] {
+                            if let Some(l) = occupant[p] {
+                                layout[l] = p;
+                            }
+                        }
+                    }
+                }
+                routed.push(inst.gate.clone(), &[layout[a], layout[b]]);
+            }
+            _ => {
+                return Err(format!(
+                    "{} acts on {} qubits; decompose it before routing",
+                    inst.gate.name(),
+                    inst.qubits.len()
+                ))
+            }
+        }
+    }
+    Ok(Routed {
+        circuit: routed,
+        initial_layout,
+        final_layout: layout,
+        swaps,
+    })
+}
+
+/// choose between the trivial and greedy layouts, routing both and keeping
+/// whichever the cost model scores lower
+pub fn route(
+    circuit: &Circuit,
+    coupling: &CouplingMap,
+    cost: &CostModel,
+) -> Result<Routed, String> {
+    if circuit.num_qubits() > coupling.num_qubits() {
+        return Err(format!(
+            "{} logical qubits do not fit on {} physical qubits",
+            circuit.num_qubits(),
+            coupling.num_qubits()
+        ));
+    }
+    let trivial: Vec<usize> = (0..circuit.num_qubits()).collect();
+    let mut best: Option<(f64, Routed)> = None;
+    for layout in [trivial, greedy_layout(circuit, coupling, cost)] {
+        let routed = route_with_layout(circuit, coupling, cost, &layout)?;
+        let score = cost.score(&routed);
+        if best.as_ref().is_none_or(|(b, _)| score < *b) {
+            best = Some((score, routed));
+        }
+    }
+    Ok(best.expect("at least one candidate layout").1)
+}
+
+/// route from the layout `stage` picks
+pub fn transpile(
+    circuit: &Circuit,
+    coupling: &CouplingMap,
+    cost: &CostModel,
+    stage: &LayoutStage,
+) -> Result<Routed, String> {
+    let layout = stage.run(circuit, coupling, cost)?;
+    route_with_layout(circuit, coupling, cost, &layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::NoiseModel;
+    use crate::simulator::register::Register;
+
+    /// state of `routed` with the qubits swapped back to the initial layout
+    fn restored_state(routed: &Routed) -> Register {
+        let mut register = Register::new(routed.circuit.num_qubits());
+        register.apply_circuit(&routed.circuit);
+        let mut current = routed.final_layout.clone();
+        for l in 0..current.len() {
+            let target = routed.initial_layout[l];
+            if current[l] != target {
+                register.apply_swap(current[l], target);
+                if let Some(other) = current.iter().position(|&p| p == target) {
+                    current[other] = current[l];
+                }
+                current[l] = target;
+            }
+        }
+        register
+    }
+
+    #[test]
+    fn test_routed_circuit_matches_original() {
+        let coupling = CouplingMap::line(5);
+        let mut circuit = Circuit::new(4);
+        circuit.h(0).cx(0, 3).ry(0.3, 3).cx(1, 2).t(1).cx(3, 1).cx(2, 0);
+        let routed = route(&circuit, &coupling, &CostModel::SwapCount).unwrap();
+        for inst in routed.circuit.instructions().iter().filter(|i| i.qubits.len() == 2) {
+            assert!(coupling.connected(inst.qubits[0], inst.qubits[1]));
+        }
+        assert!(routed.swaps > 0);
+        let mut expected = Register::new(5);
+        expected.apply_circuit(&circuit.remapped(5, &routed.initial_layout));
+        crate::assert_state_eq!(restored_state(&routed), expected);
+    }
+
+    #[test]
+    fn test_noise_aware_layout_prefers_the_good_edge() {
+        let coupling = CouplingMap::line(3);
+        let errors = DeviceErrors::from_noise(&NoiseModel::depolarizing(0.001, 0.01), &coupling)
+            .with_edge_error(0, 1, 0.2);
+        let mut circuit = Circuit::new(2);
+        for _ in 0..5 {
+            circuit.cx(0, 1);
+        }
+        let by_swaps = route(&circuit, &coupling, &CostModel::SwapCount).unwrap();
+        let by_noise = route(&circuit, &coupling, &CostModel::Noise(errors.clone())).unwrap();
+        assert_eq!((by_swaps.swaps, by_noise.swaps), (0, 0));
+        let mut sites = by_noise.initial_layout.clone();
+        sites.sort();
+        assert_eq!(sites, vec![1, 2]);
+        let (noisy, aware) = (
+            errors.predicted_fidelity(&by_swaps.circuit),
+            errors.predicted_fidelity(&by_noise.circuit),
+        );
+        assert!(aware > noisy + 0.5, "{} vs {}", aware, noisy);
+    }
+
+    #[test]
+    fn test_noise_aware_swaps_detour_around_bad_edges() {
+        let coupling = CouplingMap::ring(4);
+        let errors = DeviceErrors::from_noise(&NoiseModel::depolarizing(0.0, 0.01), &coupling)
+            .with_edge_error(0, 1, 0.3)
+            .with_edge_error(1, 2, 0.3);
+        let mut circuit = Circuit::new(4);
+        circuit.cx(0, 2);
+        let cost = CostModel::Noise(errors);
+        let routed = route_with_layout(&circuit, &coupling, &cost, &[0, 1, 2, 3]).unwrap();
+        assert_eq!(routed.swaps, 1);
+        assert_eq!(routed.circuit.instructions()[0].qubits, vec![0, 3]);
+        let mut wide = Circuit::new(3);
+        wide.mcx(&[0, 1], 2);
+        assert!(route(&wide, &coupling, &CostModel::SwapCount).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::ml::feature_maps::{Entanglement, RotationAxis};
+use crate::simulator::circuit::Circuit;
+
+/// layered parameterized circuit: rotation layers separated by CNOT entanglers
+///
+/// `reps` entangling blocks give `reps + 1` rotation layers; parameters are
+/// consumed layer by layer, rotation by rotation, qubit by qubit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ansatz {
+    pub num_qubits: usize,
+    pub reps: usize,
+    pub rotations: Vec<RotationAxis>,
+    pub entanglement: Entanglement,
+}
+
+impl Ansatz {
+    /// RY layers with CNOT ladders (real amplitudes only)
+    pub fn hardware_efficient(num_qubits: usize, reps: usize, entanglement: Entanglement) -> Self {
+        Self {
+            num_qubits,
+            reps,
+            rotations: vec![RotationAxis::Y],
+            entanglement,
+        }
+    }
+
+    /// RY then RZ on every qubit per layer, reaching any single-qubit state
+    pub fn efficient_su2(num_qubits: usize, reps: usize, entanglement: Entanglement) -> Self {
+        Self {
+            num_qubits,
+            reps,
+            rotations: vec![RotationAxis::Y, RotationAxis::Z],
+            entanglement,
+        }
+    }
+
+    pub fn num_parameters(&self) -> usize {
+        self.num_qubits * self.rotations.len() * (self.reps + 1)
+    }
+
+    /// bind `params` and build the circuit
+    pub fn circuit(&self, params: &[f64]) -> Circuit {
+        assert_eq!(params.len(), self.num_parameters(), "wrong parameter count");
+        let mut circuit = Circuit::new(self.num_qubits);
+        let mut params = params.iter().copied();
+        for layer in 0..=self.reps {
+            for axis in &self.rotations {
+                for q in 0..self.num_qubits {
+                    let theta = params.next().expect("parameter count checked");
+                    match axis {
+                        RotationAxis::X => circuit.rx(theta, q),
+                        RotationAxis::Y => circuit.ry(theta, q),
+                        RotationAxis::Z => circuit.rz(theta, q),
+                    };
+                }
+            }
+            if layer < self.reps {
+                for (control, target) in self.entanglement.pairs(self.num_qubits) {
+                    circuit.cx(control, target);
+                }
+            }
+        }
+        circuit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::register::Register;
+
+    #[test]
+    fn test_parameter_counts() {
+        let hea = Ansatz::hardware_efficient(4, 2, Entanglement::Linear);
+        assert_eq!(hea.num_parameters(), 12);
+        // 12 RY + 2 blocks of 3 CNOTs
+        assert_eq!(hea.circuit(&[0.1; 12]).len(), 18);
+        let su2 = Ansatz::efficient_su2(3, 1, Entanglement::Full);
+        assert_eq!(su2.num_parameters(), 12);
+        assert_eq!(su2.circuit(&[0.0; 12]).len(), 15);
+    }
+
+    #[test]
+    fn test_zero_parameters_keep_ground_state() {
+        let ansatz = Ansatz::efficient_su2(3, 2, Entanglement::Circular);
+        let mut register = Register::new(3);
+        register.apply_circuit(&ansatz.circuit(&vec![0.0; ansatz.num_parameters()]));
+        crate::assert_state_eq!(register, Register::new(3));
+    }
+
+    #[test]
+    fn test_entangler_produces_bell_state() {
+        // RY(π/2) on qubit 0 followed by the CNOT ladder gives (|00⟩ + |11⟩)/√2
+        let ansatz = Ansatz::hardware_efficient(2, 1, Entanglement::Linear);
+        let mut register = Register::new(2);
+        register.apply_circuit(&ansatz.circuit(&[std::f64::consts::FRAC_PI_2, 0.0, 0.0, 0.0]));
+        let probs = register.probabilities();
+        assert!((probs[0] - 0.5).abs() < 1e-12 && (probs[3] - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong parameter count")]
+    fn test_parameter_count_checked() {
+        Ansatz::hardware_efficient(2, 1, Entanglement::Linear).circuit(&[0.0; 3]);
+    }
+}
@@ -0,0 +1,194 @@
+use num_complex::Complex64;
+use crate::simulator::hamiltonian::Hamiltonian;
+use crate::simulator::matrix::Matrix;
+use crate::simulator::register::Register;
+use super::ansatz::Ansatz;
+
+/// Taylor terms below this norm end the series for e^{−τH}
+const TAYLOR_CUTOFF: f64 = 1e-14;
+/// central-difference step for state derivatives
+const DERIVATIVE_STEP: f64 = 1e-5;
+/// eigenvalues of the metric below this are dropped from its pseudo-inverse
+const METRIC_CUTOFF: f64 = 1e-8;
+
+/// imaginary-time step size and number of steps
+#[derive(Debug, Clone)]
+pub struct ImaginaryTimeConfig {
+    pub step: f64,
+    pub steps: usize,
+}
+
+impl Default for ImaginaryTimeConfig {
+    fn default() -> Self {
+        Self {
+            step: 0.05,
+            steps: 200,
+        }
+    }
+}
+
+/// ⟨H⟩ after every step, `energies[0]` being the initial state's
+#[derive(Debug, Clone)]
+pub struct ImaginaryTimeResult {
+    pub state: Register,
+    pub energies: Vec<f64>,
+}
+
+/// evolved ansatz parameters with ⟨H⟩ after every step
+#[derive(Debug, Clone)]
+pub struct VariationalImaginaryTimeResult {
+    pub params: Vec<f64>,
+    pub energies: Vec<f64>,
+}
+
+fn inner(a: &[Complex64], b: &[Complex64]) -> Complex64 {
+    a.iter().zip(b).map(|(x, y)| x.conj() * y).sum()
+}
+
+/// e^{−τH}|ψ⟩ by a Taylor series of matrix-free products, renormalized
+fn propagate(h: &Hamiltonian, amplitudes: &[Complex64], tau: f64) -> Vec<Complex64> {
+    let mut result = amplitudes.to_vec();
+    let mut term = amplitudes.to_vec();
+    for k in 1..64 {
+        term = h.apply(&term).into_iter().map(|a| a * (-tau / k as f64)).collect();
+        result.iter_mut().zip(&term).for_each(|(r, t)| *r += t);
+        if term.iter().map(|a| a.norm_sqr()).sum::<f64>().sqrt() < TAYLOR_CUTOFF {
+            break;
+        }
+    }
+    let norm = inner(&result, &result).re.sqrt();
+    result.into_iter().map(|a| a / norm).collect()
+}
+
+/// |ψ(τ)⟩ = e^{−τH}|ψ₀⟩/‖·‖, which decays onto the ground state of H as τ
+/// grows, provided `initial` overlaps it
+pub fn imaginary_time_evolution(
+    h: &Hamiltonian,
+    initial: &Register,
+    config: &ImaginaryTimeConfig,
+) -> ImaginaryTimeResult {
+    assert_eq!(h.num_qubits(), initial.num_qubits(), "qubit count mismatch");
+    let mut state = initial.clone();
+    let mut energies = vec![h.expectation(&state)];
+    for _ in 0..config.steps {
+        state = Register::from_amplitudes(propagate(h, state.amplitudes(), config.step));
+        energies.push(h.expectation(&state));
+    }
+    ImaginaryTimeResult { state, energies }
+}
+
+fn ansatz_state(ansatz: &Ansatz, params: &[f64]) -> Vec<Complex64> {
+    let mut register = Register::new(ansatz.num_qubits);
+    register.apply_circuit(&ansatz.circuit(params));
+    register.amplitudes().to_vec()
+}
+
+/// variational imaginary time (McLachlan): each step solves A·θ̇ = C with
+/// A_ij = Re(⟨∂_iψ|∂_jψ⟩ − ⟨∂_iψ|ψ⟩⟨ψ|∂_jψ⟩) and
+/// C_i = −Re(⟨∂_iψ|H|ψ⟩ − ⟨∂_iψ|ψ⟩·E), then takes an Euler step in θ
+///
+/// The circuit stays shallow, unlike the exact e^{−τH}, at the price of
+/// only reaching the ground state the ansatz can express.
+pub fn variational_imaginary_time(
+    h: &Hamiltonian,
+    ansatz: &Ansatz,
+    initial_params: &[f64],
+    config: &ImaginaryTimeConfig,
+) -> VariationalImaginaryTimeResult {
+    assert_eq!(h.num_qubits(), ansatz.num_qubits, "qubit count mismatch");
+    let count = ansatz.num_parameters();
+    assert_eq!(initial_params.len(), count, "wrong parameter count");
+    let mut params = initial_params.to_vec();
+    let energy = |params: &[f64]| {
+        h.expectation(&Register::from_amplitudes(ansatz_state(ansatz, params)))
+    };
+    let mut energies = vec![energy(&params)];
+    for _ in 0..config.steps {
+        let psi = ansatz_state(ansatz, &params);
+        let h_psi = h.apply(&psi);
+        let e = inner(&psi, &h_psi).re;
+        let derivatives: Vec<Vec<Complex64>> = (0..count)
+            .map(|i| {
+                let mut plus = params.clone();
+                let mut minus = params.clone();
+                plus[i] += DERIVATIVE_STEP;
+                minus[i] -= DERIVATIVE_STEP;
+                let (a, b) = (ansatz_state(ansatz, &plus), ansatz_state(ansatz, &minus));
+                a.iter().zip(&b).map(|(x, y)| (x - y) / (2.0 * DERIVATIVE_STEP)).collect()
+            })
+            .collect();
+        let overlaps: Vec<Complex64> = derivatives.iter().map(|d| inner(d, &psi)).collect();
+        let metric = Matrix::from_fn(count, count, |i, j| {
+            let value = inner(&derivatives[i], &derivatives[j]) - overlaps[i] * overlaps[j].conj();
+            Complex64::new(value.re, 0.0)
+        });
+        let force: Vec<Complex64> = derivatives
+            .iter()
+            .zip(&overlaps)
+            .map(|(d, &o)| Complex64::new(-(inner(d, &h_psi) - o * e).re, 0.0))
+            .collect();
+        // pseudo-inverse: the metric is singular along redundant parameters
+        let inverse = metric.eigen().map(|x| if x > METRIC_CUTOFF { 1.0 / x } else { 0.0 });
+        let velocity = inverse.apply(&force);
+        for (theta, v) in params.iter_mut().zip(&velocity) {
+            *theta += config.step * v.re;
+        }
+        energies.push(energy(&params));
+    }
+    VariationalImaginaryTimeResult { params, energies }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ml::feature_maps::Entanglement;
+    use crate::simulator::circuit::Circuit;
+
+    /// −ZZ − 0.5(XI + IX), ground energy −√2
+    fn ising() -> Hamiltonian {
+        let mut h = Hamiltonian::new(2);
+        h.add_term(Complex64::new(-1.0, 0.0), &"ZZ".parse().unwrap());
+        h.add_term(Complex64::new(-0.5, 0.0), &"XI".parse().unwrap());
+        h.add_term(Complex64::new(-0.5, 0.0), &"IX".parse().unwrap());
+        h
+    }
+
+    #[test]
+    fn test_exact_evolution_reaches_ground_state() {
+        let h = ising();
+        let mut initial = Register::new(2);
+        initial.apply_circuit(Circuit::new(2).h(0).h(1));
+        let result = imaginary_time_evolution(&h, &initial, &ImaginaryTimeConfig::default());
+        assert!(result.energies.windows(2).all(|w| w[1] <= w[0] + 1e-12));
+        let last = *result.energies.last().unwrap();
+        assert!((last + 2f64.sqrt()).abs() < 1e-6, "{}", last);
+        let (_, ground) = h.ground_state();
+        assert!(result.state.inner(&ground).norm() > 1.0 - 1e-6);
+    }
+
+    #[test]
+    fn test_evolution_cannot_leave_an_eigenstate() {
+        // an eigenstate only rescales, so the normalized state stays put
+        let h = Hamiltonian::from_pauli(Complex64::new(1.0, 0.0), &"ZZ".parse().unwrap());
+        let mut initial = Register::new(2);
+        initial.apply_circuit(Circuit::new(2).x(0));
+        let result = imaginary_time_evolution(&h, &initial, &ImaginaryTimeConfig::default());
+        assert!(result.state.approx_eq(&initial, 1e-12));
+        assert!(result.energies.iter().all(|&e| (e + 1.0).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_variational_evolution_approaches_ground_energy() {
+        let h = ising();
+        let ansatz = Ansatz::hardware_efficient(2, 1, Entanglement::Linear);
+        let initial = vec![0.1; ansatz.num_parameters()];
+        let config = ImaginaryTimeConfig {
+            step: 0.1,
+            steps: 150,
+        };
+        let result = variational_imaginary_time(&h, &ansatz, &initial, &config);
+        assert!(result.energies.windows(2).all(|w| w[1] <= w[0] + 1e-6));
+        let last = *result.energies.last().unwrap();
+        assert!((last + 2f64.sqrt()).abs() < 1e-3, "{}", last);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod ansatz;
+pub mod imaginary_time;
+
+pub use ansatz::Ansatz;
+pub use imaginary_time::{
+    imaginary_time_evolution, variational_imaginary_time, ImaginaryTimeConfig,
+    ImaginaryTimeResult, VariationalImaginaryTimeResult,
+};
@@ -0,0 +1,218 @@
+use std::fmt::Write;
+use crate::interop::Json;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::Gate;
+use crate::simulator::single_qubit::SingleQubit;
+
+/// Bloch vector recorded after one step
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlochPoint {
+    pub label: String,
+    pub vector: [f64; 3],
+}
+
+/// path of a single qubit on the Bloch sphere, one point per gate
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BlochTrajectory {
+    points: Vec<BlochPoint>,
+}
+
+impl BlochTrajectory {
+    /// trajectory starting at `qubit`
+    pub fn new(qubit: &SingleQubit) -> Self {
+        let mut trajectory = Self::default();
+        trajectory.record("initial", qubit);
+        trajectory
+    }
+
+    /// run a one-qubit circuit from |0⟩, recording after every gate
+    pub fn from_circuit(circuit: &Circuit) -> Self {
+        assert_eq!(circuit.num_qubits(), 1, "Bloch trajectories need a one-qubit circuit");
+        let mut qubit = SingleQubit::new();
+        let mut trajectory = Self::new(&qubit);
+        for inst in circuit.instructions() {
+            trajectory.apply(&mut qubit, inst.gate.clone());
+        }
+        trajectory
+    }
+
+    pub fn record(&mut self, label: &str, qubit: &SingleQubit) {
+        self.points.push(BlochPoint {
+            label: label.to_string(),
+            vector: qubit.bloch_vector(),
+        });
+    }
+
+    /// apply a single-qubit `gate` to `qubit` and record the new vector
+    pub fn apply(&mut self, qubit: &mut SingleQubit, gate: Gate) {
+        qubit.apply_matrix2(gate.matrix2().expect("single-qubit gate"));
+        self.record(&gate.label(), qubit);
+    }
+
+    pub fn points(&self) -> &[BlochPoint] {
+        &self.points
+    }
+
+    /// `step,label,x,y,z` rows with a header; labels are quoted
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("step,label,x,y,z\n");
+        for (step, p) in self.points.iter().enumerate() {
+            let [x, y, z] = p.vector;
+            let label = p.label.replace('"', "\"\"");
+            writeln!(out, "{},\"{}\",{:.6},{:.6},{:.6}", step, label, x, y, z).unwrap();
+        }
+        out
+    }
+
+    /// JSON array of `{"step", "label", "x", "y", "z"}` objects
+    pub fn to_json(&self) -> String {
+        let entries: Vec<String> = self
+            .points
+            .iter()
+            .enumerate()
+            .map(|(step, p)| {
+                let [x, y, z] = p.vector;
+                let label = Json::from(p.label.as_str());
+                format!(
+                    "{{\"step\":{},\"label\":{},\"x\":{:.6},\"y\":{:.6},\"z\":{:.6}}}",
+                    step, label, x, y, z
+                )
+            })
+            .collect();
+        format!("[{}]", entries.join(","))
+    }
+
+    /// SVG of the path in an oblique projection of the sphere; `animated`
+    /// adds a marker that moves along the path
+    pub fn to_svg(&self, animated: bool) -> String {
+        const SIZE: f64 = 300.0;
+        const RADIUS: f64 = 120.0;
+        let centre = SIZE / 2.0;
+        // x axis drawn receding to the lower left, y to the right, z up
+        let project = |[x, y, z]: [f64; 3]| {
+            (
+                centre + RADIUS * (y - 0.35 * x),
+                centre - RADIUS * (z - 0.35 * x),
+            )
+        };
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" \
+             viewBox=\"0 0 {0} {0}\">",
+            SIZE
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<circle cx=\"{0}\" cy=\"{0}\" r=\"{1}\" fill=\"none\" stroke=\"#999\"/>",
+            centre, RADIUS
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<ellipse cx=\"{0}\" cy=\"{0}\" rx=\"{1}\" ry=\"{2}\" fill=\"none\" \
+             stroke=\"#ccc\" stroke-dasharray=\"4\"/>",
+            centre,
+            RADIUS,
+            RADIUS * 0.35
+        )
+        .unwrap();
+        let axes = [([1.0, 0.0, 0.0], "x"), ([0.0, 1.0, 0.0], "y"), ([0.0, 0.0, 1.0], "z")];
+        for (axis, name) in axes {
+            let (x, y) = project(axis);
+            writeln!(
+                svg,
+                "<line x1=\"{0}\" y1=\"{0}\" x2=\"{1:.1}\" y2=\"{2:.1}\" stroke=\"#666\"/>\
+                 <text x=\"{1:.1}\" y=\"{2:.1}\" font-size=\"12\">{3}</text>",
+                centre, x, y, name
+            )
+            .unwrap();
+        }
+        let path: Vec<String> = self
+            .points
+            .iter()
+            .map(|p| {
+                let (x, y) = project(p.vector);
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect();
+        writeln!(
+            svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"#c03\" stroke-width=\"2\"/>",
+            path.join(" ")
+        )
+        .unwrap();
+        for p in &self.points {
+            let (x, y) = project(p.vector);
+            let label = p.label.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+            writeln!(
+                svg,
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"#c03\">\
+                 <title>{}</title></circle>",
+                x, y, label
+            )
+            .unwrap();
+        }
+        if animated && !path.is_empty() {
+            writeln!(
+                svg,
+                "<circle r=\"6\" fill=\"#06c\"><animateMotion dur=\"{}s\" \
+                 repeatCount=\"indefinite\" path=\"M{}\"/></circle>",
+                self.points.len(),
+                path.join(" L")
+            )
+            .unwrap();
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_rotation_path() {
+        let mut circuit = Circuit::new(1);
+        circuit.ry(PI / 2.0, 0).rz(PI / 2.0, 0);
+        let trajectory = BlochTrajectory::from_circuit(&circuit);
+        let points = trajectory.points();
+        assert_eq!(points.len(), 3);
+        // |0⟩ → |+⟩ → |+i⟩
+        let expected = [[0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        for (p, e) in points.iter().zip(expected) {
+            for (a, b) in p.vector.iter().zip(e) {
+                assert!((a - b).abs() < 1e-12, "{:?}", p);
+            }
+        }
+        assert_eq!(points[1].label, "ry(1.571)");
+    }
+
+    #[test]
+    fn test_exports() {
+        let mut qubit = SingleQubit::new();
+        let mut trajectory = BlochTrajectory::new(&qubit);
+        trajectory.apply(&mut qubit, Gate::H);
+        let csv = trajectory.to_csv();
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.lines().nth(2).unwrap().starts_with("1,\"h\",1.000000,0.000000,"));
+        let json = trajectory.to_json();
+        assert!(json.starts_with("[{\"step\":0,\"label\":\"initial\""));
+        let svg = trajectory.to_svg(true);
+        assert!(svg.contains("<polyline") && svg.contains("animateMotion"));
+        assert!(!trajectory.to_svg(false).contains("animateMotion"));
+        // labels with separators, quotes or markup stay one field / one text node
+        trajectory.record("a,\"b\" <c&d>", &qubit);
+        let csv = trajectory.to_csv();
+        assert!(csv.lines().last().unwrap().starts_with("2,\"a,\"\"b\"\" <c&d>\","));
+        assert!(trajectory.to_svg(false).contains("<title>a,\"b\" &lt;c&amp;d&gt;</title>"));
+        // control characters are escaped, so the export still parses
+        trajectory.record("two\nlines\t\u{1}", &qubit);
+        let parsed = Json::parse(&trajectory.to_json()).unwrap();
+        let last = parsed.as_array().unwrap().last().unwrap();
+        assert_eq!(last.get("label").and_then(Json::as_str), Some("two\nlines\t\u{1}"));
+    }
+}
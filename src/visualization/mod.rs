@@ -0,0 +1,11 @@
+pub mod bloch;
+pub mod phase_space;
+#[cfg(feature = "plotting")]
+pub mod plot;
+pub mod rich;
+pub mod text;
+pub mod tui;
+
+pub use bloch::{BlochPoint, BlochTrajectory};
+pub use phase_space::{qubit_husimi, qubit_wigner, qudit_wigner, PhaseSpace};
+pub use tui::{render_frame, Stepper};
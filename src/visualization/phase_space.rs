@@ -0,0 +1,216 @@
+use std::f64::consts::PI;
+use std::fmt::{self, Write};
+use num_complex::Complex64;
+use crate::simulator::qudit::QuditState;
+use crate::simulator::single_qubit::SingleQubit;
+
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// quasi-probability over a discrete phase space, `values[row][col]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseSpace {
+    pub title: String,
+    pub row_labels: Vec<String>,
+    pub col_labels: Vec<String>,
+    pub values: Vec<Vec<f64>>,
+}
+
+impl PhaseSpace {
+    pub fn total(&self) -> f64 {
+        self.values.iter().flatten().sum()
+    }
+
+    /// Σ |W| over negative points, zero for classical-looking states
+    pub fn negativity(&self) -> f64 {
+        self.values.iter().flatten().filter(|&&v| v < 0.0).map(|v| -v).sum()
+    }
+
+    /// text grid: each point's value with a shade for its magnitude,
+    /// negative points marked by `-`
+    pub fn render(&self) -> String {
+        let max = self.values.iter().flatten().fold(0.0f64, |m, v| m.max(v.abs()));
+        let label_width = self.row_labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut out = format!("{}\n{:label_width$} ", self.title, "");
+        for label in &self.col_labels {
+            write!(out, " {:>8}", label).unwrap();
+        }
+        for (label, row) in self.row_labels.iter().zip(&self.values) {
+            write!(out, "\n{:>label_width$} ", label).unwrap();
+            for &v in row {
+                let level = if max > 0.0 { (v.abs() / max * 4.0).round() as usize } else { 0 };
+                let mark = if v < -1e-12 { '-' } else { SHADES[level] };
+                write!(out, " {:+.3}{}{}", v, mark, SHADES[level]).unwrap();
+            }
+        }
+        out
+    }
+}
+
+impl fmt::Display for PhaseSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+/// Wootters' 2×2 discrete Wigner function of a qubit with Bloch vector r:
+/// W(a, b) = (1 + (−1)^a·z + (−1)^b·x + (−1)^{a+b}·y) / 4
+///
+/// Rows are the Z outcome a, columns the X outcome b, so summing a row gives
+/// the probability of a in the Z basis and summing a column that of b in X.
+pub fn qubit_wigner(bloch: [f64; 3]) -> PhaseSpace {
+    let [x, y, z] = bloch;
+    let sign = |k: usize| if k == 0 { 1.0 } else { -1.0 };
+    let values = (0..2)
+        .map(|a| {
+            (0..2)
+                .map(|b| (1.0 + sign(a) * z + sign(b) * x + sign(a) * sign(b) * y) / 4.0)
+                .collect()
+        })
+        .collect();
+    PhaseSpace {
+        title: "discrete Wigner W(z, x)".to_string(),
+        row_labels: vec!["z=0".to_string(), "z=1".to_string()],
+        col_labels: vec!["x=+".to_string(), "x=-".to_string()],
+        values,
+    }
+}
+
+/// Husimi Q(θ, φ) = (1 + r·n)/2, the overlap with the spin coherent state
+/// along n, sampled on a `rows` × `cols` grid of polar and azimuthal angles
+pub fn qubit_husimi(bloch: [f64; 3], rows: usize, cols: usize) -> PhaseSpace {
+    let theta = |i: usize| PI * (i as f64 + 0.5) / rows as f64;
+    let phi = |j: usize| 2.0 * PI * j as f64 / cols as f64;
+    let values = (0..rows)
+        .map(|i| {
+            (0..cols)
+                .map(|j| {
+                    let (t, p) = (theta(i), phi(j));
+                    let n = [t.sin() * p.cos(), t.sin() * p.sin(), t.cos()];
+                    (1.0 + n.iter().zip(&bloch).map(|(a, b)| a * b).sum::<f64>()) / 2.0
+                })
+                .collect()
+        })
+        .collect();
+    PhaseSpace {
+        title: "Husimi Q(θ, φ)".to_string(),
+        row_labels: (0..rows).map(|i| format!("θ={:.0}°", theta(i).to_degrees())).collect(),
+        col_labels: (0..cols).map(|j| format!("φ={:.0}°", phi(j).to_degrees())).collect(),
+        values,
+    }
+}
+
+/// discrete Wigner function of one qudit of odd dimension d,
+/// W(q, p) = (1/d)·Σ_y ω^{2py}·ψ(q − y)·ψ*(q + y) with ω = e^{2πi/d}
+///
+/// Rows are positions q (computational basis), columns momenta p; rows sum to
+/// the computational-basis probabilities.
+pub fn qudit_wigner(state: &QuditState) -> Result<PhaseSpace, String> {
+    let d = state.dim();
+    if state.num_qudits() != 1 {
+        return Err(format!("Wigner grid needs one qudit, got {}", state.num_qudits()));
+    }
+    if d.is_multiple_of(2) {
+        return Err(format!("discrete Wigner function needs odd dimension, got {}", d));
+    }
+    let psi = state.amplitudes();
+    let omega = |k: usize| Complex64::from_polar(1.0, 2.0 * PI * (k % d) as f64 / d as f64);
+    let values = (0..d)
+        .map(|q| {
+            (0..d)
+                .map(|p| {
+                    let sum: Complex64 = (0..d)
+                        .map(|y| {
+                            omega(2 * p * y) * psi[(q + d - y) % d] * psi[(q + y) % d].conj()
+                        })
+                        .sum();
+                    sum.re / d as f64
+                })
+                .collect()
+        })
+        .collect();
+    Ok(PhaseSpace {
+        title: format!("discrete Wigner W(q, p), d = {}", d),
+        row_labels: (0..d).map(|q| format!("q={}", q)).collect(),
+        col_labels: (0..d).map(|p| format!("p={}", p)).collect(),
+        values,
+    })
+}
+
+impl SingleQubit {
+    /// print the discrete Wigner function and a coarse Husimi Q grid
+    pub fn display_phase_space(&self) {
+        println!("{}", qubit_wigner(self.bloch_vector()));
+        println!("{}", qubit_husimi(self.bloch_vector(), 4, 6));
+    }
+}
+
+impl QuditState {
+    /// print the discrete Wigner function of a single odd-dimensional qudit
+    pub fn display_wigner(&self) {
+        match qudit_wigner(self) {
+            Ok(grid) => println!("{}", grid),
+            Err(message) => println!("{}", message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::gates::h_matrix;
+    use crate::simulator::qudit::qudit_fourier;
+
+    #[test]
+    fn test_qubit_wigner_marginals_and_magic_negativity() {
+        let mut qubit = SingleQubit::new();
+        qubit.apply_gate(h_matrix()).unwrap();
+        let w = qubit_wigner(qubit.bloch_vector());
+        assert!((w.total() - 1.0).abs() < 1e-12);
+        // |+⟩: the Z outcome is uniform, the X outcome certain
+        let row: f64 = w.values[0].iter().sum();
+        let col: f64 = w.values.iter().map(|r| r[0]).sum();
+        assert!((row - 0.5).abs() < 1e-12 && (col - 1.0).abs() < 1e-12);
+        assert!(w.negativity() < 1e-12);
+        // the magic state along (1, 1, −1)/√3 has a negative point
+        let c = 1.0 / 3f64.sqrt();
+        let magic = [c, c, -c];
+        assert!(qubit_wigner(magic).negativity() > 0.1);
+        assert!(w.render().contains("x=+"));
+    }
+
+    #[test]
+    fn test_husimi_peaks_at_the_bloch_vector() {
+        let q = qubit_husimi([0.0, 0.0, 1.0], 6, 4);
+        assert!(q.values[0].iter().all(|&v| v > 0.9));
+        assert!(q.values[5].iter().all(|&v| v < 0.1));
+        assert!(q.negativity() < 1e-12);
+    }
+
+    #[test]
+    fn test_qudit_wigner_is_normalized_with_correct_marginals() {
+        let d = 3;
+        let mut amplitudes = vec![Complex64::new(0.0, 0.0); d];
+        amplitudes[1] = Complex64::new(1.0, 0.0);
+        let basis = QuditState::from_amplitudes(d, amplitudes.clone());
+        let w = qudit_wigner(&basis).unwrap();
+        assert!((w.total() - 1.0).abs() < 1e-12);
+        // a computational basis state is a line of 1/d along q = 1
+        for (q, row) in w.values.iter().enumerate() {
+            let expected = if q == 1 { 1.0 / d as f64 } else { 0.0 };
+            assert!(row.iter().all(|v| (v - expected).abs() < 1e-12));
+        }
+        // the Fourier transform of a basis state is a stabilizer state: W ≥ 0
+        let f = qudit_fourier(d).apply(&amplitudes);
+        let w = qudit_wigner(&QuditState::from_amplitudes(d, f)).unwrap();
+        assert!(w.negativity() < 1e-12);
+        // the strange state (|1⟩ − |2⟩)/√2 is Wigner-negative
+        let strange = vec![
+            Complex64::new(0.0, 0.0),
+            Complex64::new(1.0, 0.0),
+            Complex64::new(-1.0, 0.0),
+        ];
+        let w = qudit_wigner(&QuditState::from_amplitudes(d, strange)).unwrap();
+        assert!(w.negativity() > 0.1);
+        assert!(qudit_wigner(&QuditState::new(4, 1)).is_err());
+    }
+}
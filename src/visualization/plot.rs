@@ -0,0 +1,638 @@
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::simulator::distribution::Distribution;
+
+const WIDTH: f64 = 480.0;
+const HEIGHT: f64 = 320.0;
+const MARGIN: f64 = 48.0;
+const COLORS: [&str; 6] = ["#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b"];
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// drawing primitive, in pixels from the top-left corner
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    /// filled bar with a hover title
+    Bar { x: f64, y: f64, w: f64, h: f64, color: &'static str, title: String },
+    /// unfilled rectangle
+    Frame { x: f64, y: f64, w: f64, h: f64 },
+    Line { from: (f64, f64), to: (f64, f64) },
+    Polyline { points: Vec<(f64, f64)>, color: &'static str, width: f64 },
+    Circle { centre: (f64, f64), r: f64 },
+    /// text, drawn rotated a quarter turn when `vertical`
+    Text {
+        at: (f64, f64),
+        size: f64,
+        anchor: &'static str,
+        color: Option<&'static str>,
+        text: String,
+        vertical: bool,
+    },
+}
+
+/// chart as a list of shapes, rendered to SVG or PNG
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    width: f64,
+    height: f64,
+    shapes: Vec<Shape>,
+}
+
+impl Chart {
+    fn new(width: f64, height: f64, title: &str) -> Self {
+        let title = Shape::Text {
+            at: (width / 2.0, 20.0),
+            size: 14.0,
+            anchor: "middle",
+            color: None,
+            text: title.to_string(),
+            vertical: false,
+        };
+        Self { width, height, shapes: vec![title] }
+    }
+
+    fn text(&mut self, at: (f64, f64), size: f64, anchor: &'static str, text: impl Into<String>) {
+        let text = text.into();
+        self.shapes.push(Shape::Text { at, size, anchor, color: None, text, vertical: false });
+    }
+
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+             viewBox=\"0 0 {0} {1}\" font-family=\"sans-serif\">",
+            self.width, self.height
+        )
+        .unwrap();
+        writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>").unwrap();
+        for shape in &self.shapes {
+            match shape {
+                Shape::Bar { x, y, w, h, color, title } => writeln!(
+                    svg,
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\">\
+                     <title>{}</title></rect>",
+                    x,
+                    y,
+                    w,
+                    h,
+                    color,
+                    escape(title)
+                ),
+                Shape::Frame { x, y, w, h } => writeln!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" \
+                     stroke=\"black\"/>",
+                    x, y, w, h
+                ),
+                Shape::Line { from, to } => writeln!(
+                    svg,
+                    "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\"/>",
+                    from.0, from.1, to.0, to.1
+                ),
+                Shape::Polyline { points, color, width } => {
+                    let path: Vec<String> =
+                        points.iter().map(|(x, y)| format!("{:.1},{:.1}", x, y)).collect();
+                    writeln!(
+                        svg,
+                        "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                        path.join(" "),
+                        color,
+                        width
+                    )
+                }
+                Shape::Circle { centre, r } => writeln!(
+                    svg,
+                    "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"none\" stroke=\"#999\"/>",
+                    centre.0, centre.1, r
+                ),
+                Shape::Text { at, size, anchor, color, text, vertical } => {
+                    let fill = color.map(|c| format!(" fill=\"{}\"", c)).unwrap_or_default();
+                    let rotate = if *vertical {
+                        format!(" transform=\"rotate(-90 {:.1} {:.1})\"", at.0, at.1)
+                    } else {
+                        String::new()
+                    };
+                    writeln!(
+                        svg,
+                        "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"{}\" text-anchor=\"{}\"{}{}>\
+                         {}</text>",
+                        at.0,
+                        at.1,
+                        size,
+                        anchor,
+                        fill,
+                        rotate,
+                        escape(text)
+                    )
+                }
+            }
+            .unwrap();
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// 8-bit RGB PNG of the chart, with text in a 5x7 bitmap font
+    pub fn to_png(&self) -> Vec<u8> {
+        self.raster().encode_png()
+    }
+
+    fn raster(&self) -> Canvas {
+        let mut canvas = Canvas::new(self.width as usize, self.height as usize);
+        for shape in &self.shapes {
+            match shape {
+                Shape::Bar { x, y, w, h, color, .. } => canvas.fill(*x, *y, *w, *h, rgb(color)),
+                Shape::Frame { x, y, w, h } => {
+                    let corners = [(*x, *y), (x + w, *y), (x + w, y + h), (*x, y + h), (*x, *y)];
+                    canvas.polyline(&corners, [0; 3], 1.0);
+                }
+                Shape::Line { from, to } => canvas.polyline(&[*from, *to], [0; 3], 1.0),
+                Shape::Polyline { points, color, width } => {
+                    canvas.polyline(points, rgb(color), *width)
+                }
+                Shape::Circle { centre, r } => {
+                    let points: Vec<(f64, f64)> = (0..=360)
+                        .map(|d| (d as f64).to_radians())
+                        .map(|t| (centre.0 + r * t.cos(), centre.1 + r * t.sin()))
+                        .collect();
+                    canvas.polyline(&points, rgb("#999"), 1.0);
+                }
+                Shape::Text { at, size, anchor, color, text, vertical } => {
+                    let color = color.map_or([0; 3], rgb);
+                    canvas.text(*at, *size, anchor, color, text, *vertical);
+                }
+            }
+        }
+        canvas
+    }
+
+    /// write the chart as PNG or SVG, chosen by the file extension
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("png") => fs::write(path, self.to_png()),
+            Some("svg") => fs::write(path, self.to_svg()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{}: expected a .png or .svg file", path.display()),
+            )),
+        }
+    }
+}
+
+/// "#rrggbb" as bytes
+fn rgb(color: &str) -> [u8; 3] {
+    let hex = color.trim_start_matches('#');
+    let hex: String =
+        if hex.len() == 3 { hex.chars().flat_map(|c| [c, c]).collect() } else { hex.into() };
+    let channel = |i: usize| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap_or(0);
+    [channel(0), channel(1), channel(2)]
+}
+
+/// 5x7 glyphs for printable ASCII, one byte per column with the top row in bit 0
+const FONT: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // space
+    [0x00, 0x00, 0x5f, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7f, 0x14, 0x7f, 0x14], // #
+    [0x24, 0x2a, 0x7f, 0x2a, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1c, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1c, 0x00], // )
+    [0x08, 0x2a, 0x1c, 0x2a, 0x08], // *
+    [0x08, 0x08, 0x3e, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3e, 0x51, 0x49, 0x45, 0x3e], // 0
+    [0x00, 0x42, 0x7f, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4b, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7f, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3c, 0x4a, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1e], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3e], // @
+    [0x7e, 0x11, 0x11, 0x11, 0x7e], // A
+    [0x7f, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3e, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7f, 0x41, 0x41, 0x22, 0x1c], // D
+    [0x7f, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7f, 0x09, 0x09, 0x01, 0x01], // F
+    [0x3e, 0x41, 0x41, 0x51, 0x32], // G
+    [0x7f, 0x08, 0x08, 0x08, 0x7f], // H
+    [0x00, 0x41, 0x7f, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3f, 0x01], // J
+    [0x7f, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7f, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7f, 0x02, 0x04, 0x02, 0x7f], // M
+    [0x7f, 0x04, 0x08, 0x10, 0x7f], // N
+    [0x3e, 0x41, 0x41, 0x41, 0x3e], // O
+    [0x7f, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3e, 0x41, 0x51, 0x21, 0x5e], // Q
+    [0x7f, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7f, 0x01, 0x01], // T
+    [0x3f, 0x40, 0x40, 0x40, 0x3f], // U
+    [0x1f, 0x20, 0x40, 0x20, 0x1f], // V
+    [0x7f, 0x20, 0x18, 0x20, 0x7f], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x03, 0x04, 0x78, 0x04, 0x03], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7f, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // \\
+    [0x00, 0x41, 0x41, 0x7f, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7f, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7f], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7e, 0x09, 0x01, 0x02], // f
+    [0x08, 0x14, 0x54, 0x54, 0x3c], // g
+    [0x7f, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7d, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3d, 0x00], // j
+    [0x00, 0x7f, 0x10, 0x28, 0x44], // k
+    [0x00, 0x41, 0x7f, 0x40, 0x00], // l
+    [0x7c, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7c, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7c, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7c], // q
+    [0x7c, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3f, 0x44, 0x40, 0x20], // t
+    [0x3c, 0x40, 0x40, 0x20, 0x7c], // u
+    [0x1c, 0x20, 0x40, 0x20, 0x1c], // v
+    [0x3c, 0x40, 0x30, 0x40, 0x3c], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0c, 0x50, 0x50, 0x50, 0x3c], // y
+    [0x44, 0x64, 0x54, 0x4c, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7f, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
+
+/// glyph columns for `c`; a few symbols used in chart labels, a box otherwise
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        ' '..='~' => FONT[c as usize - 0x20],
+        'θ' => [0x3e, 0x49, 0x49, 0x49, 0x3e],
+        '⟨' => [0x00, 0x08, 0x14, 0x22, 0x00],
+        '⟩' => [0x00, 0x22, 0x14, 0x08, 0x00],
+        _ => [0x7f, 0x41, 0x41, 0x41, 0x7f],
+    }
+}
+
+/// white RGB raster
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self { width, height, pixels: vec![[255; 3]; width * height] }
+    }
+
+    fn fill(&mut self, x: f64, y: f64, w: f64, h: f64, color: [u8; 3]) {
+        let clamp = |v: f64, max: usize| (v.round().max(0.0) as usize).min(max);
+        let (x0, x1) = (clamp(x, self.width), clamp(x + w, self.width));
+        let (y0, y1) = (clamp(y, self.height), clamp(y + h, self.height));
+        for row in y0..y1 {
+            self.pixels[row * self.width + x0..row * self.width + x1].fill(color);
+        }
+    }
+
+    /// `text` with its baseline at `at`, scaled to roughly `size` pixels and
+    /// turned a quarter counter-clockwise about `at` when `vertical`
+    fn text(
+        &mut self,
+        at: (f64, f64),
+        size: f64,
+        anchor: &str,
+        color: [u8; 3],
+        text: &str,
+        vertical: bool,
+    ) {
+        let scale = (size / 9.0).round().max(1.0);
+        let advance = 6.0 * scale;
+        let width = advance * text.chars().count() as f64 - scale;
+        let start = match anchor {
+            "middle" => -width / 2.0,
+            "end" => -width,
+            _ => 0.0,
+        };
+        for (i, c) in text.chars().enumerate() {
+            for (col, bits) in glyph(c).into_iter().enumerate() {
+                for row in (0..7).filter(|row| bits >> row & 1 != 0) {
+                    let dx = start + i as f64 * advance + col as f64 * scale;
+                    let dy = (row as f64 - 7.0) * scale;
+                    let (x, y) = if vertical {
+                        (at.0 + dy, at.1 - dx - scale)
+                    } else {
+                        (at.0 + dx, at.1 + dy)
+                    };
+                    self.fill(x, y, scale, scale, color);
+                }
+            }
+        }
+    }
+
+    /// segments stamped with a square pen every half pixel
+    fn polyline(&mut self, points: &[(f64, f64)], color: [u8; 3], width: f64) {
+        let pen = width.max(1.0);
+        for pair in points.windows(2) {
+            let ((xa, ya), (xb, yb)) = (pair[0], pair[1]);
+            let steps = (2.0 * (xb - xa).hypot(yb - ya)).ceil().max(1.0) as usize;
+            for k in 0..=steps {
+                let t = k as f64 / steps as f64;
+                let (x, y) = (xa + t * (xb - xa), ya + t * (yb - ya));
+                self.fill(x - pen / 2.0, y - pen / 2.0, pen, pen, color);
+            }
+        }
+    }
+
+    fn encode_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+        let mut header = Vec::new();
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        push_chunk(&mut png, b"IHDR", &header);
+        push_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        push_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn push_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// zlib stream of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = data.chunks(0xffff).collect();
+    for (i, block) in blocks.iter().enumerate() {
+        out.push(u8::from(i + 1 == blocks.len()));
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    if blocks.is_empty() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    out.extend_from_slice(&(b << 16 | a).to_be_bytes());
+    out
+}
+
+/// bar chart of `values` with one label per bar
+pub fn histogram(title: &str, labels: &[String], values: &[f64]) -> Chart {
+    assert_eq!(labels.len(), values.len(), "one label per bar");
+    let max = values.iter().cloned().fold(0.0, f64::max).max(1e-12);
+    let plot_w = WIDTH - 2.0 * MARGIN;
+    let plot_h = HEIGHT - 2.0 * MARGIN;
+    let slot = plot_w / values.len().max(1) as f64;
+    let mut chart = Chart::new(WIDTH, HEIGHT, title);
+    let base = HEIGHT - MARGIN;
+    chart.shapes.push(Shape::Line { from: (MARGIN, base), to: (WIDTH - MARGIN, base) });
+    for (i, (label, &v)) in labels.iter().zip(values).enumerate() {
+        let h = plot_h * v / max;
+        let x = MARGIN + i as f64 * slot;
+        chart.shapes.push(Shape::Bar {
+            x: x + 0.1 * slot,
+            y: base - h,
+            w: 0.8 * slot,
+            h,
+            color: COLORS[0],
+            title: format!("{}: {:.4}", label, v),
+        });
+        chart.text((x + slot / 2.0, base + 14.0), 10.0, "middle", label.as_str());
+    }
+    chart
+}
+
+/// histogram of the outcome probabilities above `threshold`
+pub fn distribution_chart(title: &str, distribution: &Distribution, threshold: f64) -> Chart {
+    let (labels, values): (Vec<String>, Vec<f64>) = distribution
+        .probabilities()
+        .iter()
+        .enumerate()
+        .filter(|(_, &p)| p > threshold)
+        .map(|(k, &p)| (distribution.format_outcome(k), p))
+        .unzip();
+    histogram(title, &labels, &values)
+}
+
+/// line chart of named (x, y) series, e.g. an expectation against a parameter
+pub fn line_chart(
+    title: &str,
+    x_label: &str,
+    y_label: &str,
+    series: &[(&str, Vec<(f64, f64)>)],
+) -> Chart {
+    let points = series.iter().flat_map(|(_, s)| s.iter());
+    let (mut x0, mut x1, mut y0, mut y1) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+    for &(x, y) in points {
+        x0 = x0.min(x);
+        x1 = x1.max(x);
+        y0 = y0.min(y);
+        y1 = y1.max(y);
+    }
+    if x1 <= x0 {
+        x1 = x0 + 1.0;
+    }
+    if y1 <= y0 {
+        y1 = y0 + 1.0;
+    }
+    let sx = |x: f64| MARGIN + (WIDTH - 2.0 * MARGIN) * (x - x0) / (x1 - x0);
+    let sy = |y: f64| HEIGHT - MARGIN - (HEIGHT - 2.0 * MARGIN) * (y - y0) / (y1 - y0);
+    let mut chart = Chart::new(WIDTH, HEIGHT, title);
+    chart.shapes.push(Shape::Frame {
+        x: MARGIN,
+        y: MARGIN,
+        w: WIDTH - 2.0 * MARGIN,
+        h: HEIGHT - 2.0 * MARGIN,
+    });
+    for (value, x, anchor) in [(x0, MARGIN, "start"), (x1, WIDTH - MARGIN, "end")] {
+        chart.text((x, HEIGHT - MARGIN + 14.0), 10.0, anchor, format!("{:.3}", value));
+    }
+    for (value, y) in [(y0, HEIGHT - MARGIN), (y1, MARGIN + 10.0)] {
+        chart.text((MARGIN - 4.0, y), 10.0, "end", format!("{:.3}", value));
+    }
+    chart.text((WIDTH / 2.0, HEIGHT - 12.0), 12.0, "middle", x_label);
+    chart.shapes.push(Shape::Text {
+        at: (12.0, HEIGHT / 2.0),
+        size: 12.0,
+        anchor: "middle",
+        color: None,
+        text: y_label.to_string(),
+        vertical: true,
+    });
+    for (i, (name, data)) in series.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+        let points = data.iter().map(|&(x, y)| (sx(x), sy(y))).collect();
+        chart.shapes.push(Shape::Polyline { points, color, width: 2.0 });
+        chart.shapes.push(Shape::Text {
+            at: (WIDTH - MARGIN + 4.0, MARGIN + 14.0 * (i as f64 + 1.0)),
+            size: 11.0,
+            anchor: "start",
+            color: Some(color),
+            text: name.to_string(),
+            vertical: false,
+        });
+    }
+    chart
+}
+
+/// XY, XZ and YZ projections of Bloch vectors side by side
+pub fn bloch_projections(title: &str, vectors: &[[f64; 3]]) -> Chart {
+    let panel = 160.0;
+    let radius = 64.0;
+    let mut chart = Chart::new(3.0 * panel, panel + 30.0, title);
+    for (k, (a, b, name)) in [(0, 1, "XY"), (0, 2, "XZ"), (1, 2, "YZ")].into_iter().enumerate() {
+        let (cx, cy) = (panel * (k as f64 + 0.5), 30.0 + panel / 2.0);
+        chart.shapes.push(Shape::Circle { centre: (cx, cy), r: radius });
+        chart.text((cx, cy + radius + 14.0), 11.0, "middle", name);
+        let points = vectors.iter().map(|v| (cx + radius * v[a], cy - radius * v[b])).collect();
+        chart.shapes.push(Shape::Polyline { points, color: COLORS[1], width: 1.5 });
+    }
+    chart
+}
+
+/// `distribution_chart` as an SVG document
+pub fn distribution_svg(title: &str, distribution: &Distribution, threshold: f64) -> String {
+    distribution_chart(title, distribution, threshold).to_svg()
+}
+
+/// `line_chart` as an SVG document
+pub fn line_chart_svg(
+    title: &str,
+    x_label: &str,
+    y_label: &str,
+    series: &[(&str, Vec<(f64, f64)>)],
+) -> String {
+    line_chart(title, x_label, y_label, series).to_svg()
+}
+
+/// `bloch_projections` as an SVG document
+pub fn bloch_projections_svg(title: &str, vectors: &[[f64; 3]]) -> String {
+    bloch_projections(title, vectors).to_svg()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_bars() {
+        let dist = Distribution::new(2, vec![0.5, 0.0, 0.0, 0.5]);
+        let svg = distribution_svg("Bell <state>", &dist, 1e-9);
+        assert_eq!(svg.matches("<rect x=").count(), 2);
+        assert!(svg.contains("Bell &lt;state&gt;") && svg.contains(">11</text>"));
+    }
+
+    #[test]
+    fn test_line_chart_and_projection_files() {
+        let curve: Vec<(f64, f64)> =
+            (0..20).map(|k| k as f64 * 0.3).map(|t| (t, t.cos())).collect();
+        let svg = line_chart_svg("⟨Z⟩ vs θ", "θ", "⟨Z⟩", &[("exact", curve)]);
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        let bloch = bloch_projections_svg("path", &[[0.0, 0.0, 1.0], [1.0, 0.0, 0.0]]);
+        assert_eq!(bloch.matches("<polyline").count(), 3);
+    }
+
+    #[test]
+    fn test_png_draws_text() {
+        let dark = |chart: &Chart, rows: std::ops::Range<usize>| {
+            let canvas = chart.raster();
+            rows.flat_map(|r| &canvas.pixels[r * canvas.width..(r + 1) * canvas.width])
+                .filter(|&&p| p != [255; 3])
+                .count()
+        };
+        let titled = histogram("Bell state", &["00".into(), "11".into()], &[0.5, 0.5]);
+        let untitled = histogram("", &["00".into(), "11".into()], &[0.5, 0.5]);
+        assert!(dark(&titled, 0..24) > 0);
+        assert_eq!(dark(&untitled, 0..24), 0);
+        // tick labels sit below the axis
+        assert!(dark(&untitled, 274..290) > 0);
+        let labelled = line_chart("", "", "energy", &[("", vec![(0.0, 0.0), (1.0, 1.0)])]);
+        let bare = line_chart("", "", "", &[("", vec![(0.0, 0.0), (1.0, 1.0)])]);
+        assert!(dark(&labelled, 0..320) > dark(&bare, 0..320));
+    }
+
+    #[test]
+    fn test_png_output() {
+        let dist = Distribution::new(1, vec![0.25, 0.75]);
+        let chart = distribution_chart("counts", &dist, 0.0);
+        let png = chart.to_png();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[16..24], [0, 0, 1, 224, 0, 0, 1, 64]);
+        // IHDR chunk CRC over type and data
+        assert_eq!(png[29..33], crc32(&png[12..29]).to_be_bytes());
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+        // raw scanlines: filter byte plus RGB for every pixel
+        let idat = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        let raw_len: usize = 320 * (1 + 3 * 480);
+        assert_eq!(idat, 2 + 5 * raw_len.div_ceil(0xffff) + raw_len + 4);
+        let dir = std::env::temp_dir();
+        let (png_path, svg_path) = (dir.join("memqsim_plot.png"), dir.join("memqsim_plot.svg"));
+        chart.save(&png_path).unwrap();
+        chart.save(&svg_path).unwrap();
+        assert_eq!(fs::read(&png_path).unwrap(), png);
+        assert_eq!(fs::read_to_string(&svg_path).unwrap(), chart.to_svg());
+        assert!(chart.save(dir.join("memqsim_plot.gif")).is_err());
+        fs::remove_file(png_path).unwrap();
+        fs::remove_file(svg_path).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+use num_complex::Complex64;
+use std::fmt::Write;
+use crate::simulator::circuit::Circuit;
+use crate::simulator::register::Register;
+use crate::visualization::text::cell;
+
+/// amplitudes below this probability are left out of ket expansions
+const HIDE: f64 = 1e-10;
+
+fn amplitude_latex(a: Complex64) -> String {
+    match (a.re.abs() < 1e-10, a.im.abs() < 1e-10) {
+        (_, true) => format!("{:.3}", a.re),
+        (true, false) => format!("{:.3}i", a.im),
+        _ => format!("({:.3}{:+.3}i)", a.re, a.im),
+    }
+}
+
+impl Register {
+    /// ket expansion as display-math LaTeX
+    pub fn to_latex(&self) -> String {
+        let n = self.num_qubits();
+        let terms: Vec<String> = self
+            .amplitudes()
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.norm_sqr() > HIDE)
+            .map(|(k, &a)| format!("{}\\,|{:0width$b}\\rangle", amplitude_latex(a), k, width = n))
+            .collect();
+        format!("$${}$$", terms.join(" + ").replace("+ -", "- "))
+    }
+
+    /// amplitude and probability table as an HTML fragment
+    pub fn to_html(&self) -> String {
+        let n = self.num_qubits();
+        let mut html = String::from(
+            "<table class=\"memqsim-state\"><tr><th>basis</th><th>amplitude</th>\
+             <th>probability</th></tr>",
+        );
+        for (k, a) in self.amplitudes().iter().enumerate() {
+            let p = a.norm_sqr();
+            if p <= HIDE {
+                continue;
+            }
+            write!(
+                html,
+                "<tr><td>|{:0width$b}&rang;</td><td>{:.4}</td><td>\
+                 <div style=\"background:#4c72b0;height:0.8em;width:{:.0}px\"></div>\
+                 {:.2}%</td></tr>",
+                k,
+                a,
+                120.0 * p,
+                100.0 * p,
+                width = n
+            )
+            .unwrap();
+        }
+        html.push_str("</table>");
+        html
+    }
+}
+
+impl Circuit {
+    /// operator product as display-math LaTeX, first gate rightmost; rotation
+    /// angles follow the gate name
+    pub fn to_latex(&self) -> String {
+        if self.is_empty() {
+            return "$$I$$".to_string();
+        }
+        let factors: Vec<String> = self
+            .instructions()
+            .iter()
+            .rev()
+            .map(|inst| {
+                let qubits: Vec<String> = inst.qubits.iter().map(usize::to_string).collect();
+                let name = inst.gate.name();
+                let angle = &inst.gate.label()[name.len()..];
+                format!("\\mathrm{{{}}}{}_{{{}}}", name.to_uppercase(), angle, qubits.join(","))
+            })
+            .collect();
+        format!("$${}$$", factors.join("\\,"))
+    }
+
+    /// wire diagram as an HTML table, one row per qubit and one column per
+    /// instruction, with the symbols of the text diagram
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(
+            "<table class=\"memqsim-circuit\" style=\"border-collapse:collapse;\
+             font-family:monospace\">",
+        );
+        for q in 0..self.num_qubits() {
+            write!(html, "<tr><td>q{}</td>", q).unwrap();
+            for inst in self.instructions() {
+                let symbol = match inst.qubits.iter().position(|&x| x == q) {
+                    None => "─".to_string(),
+                    Some(k) => cell(inst, k),
+                };
+                write!(html, "<td style=\"text-align:center;padding:0 4px\">{}</td>", symbol)
+                    .unwrap();
+            }
+            html.push_str("</tr>");
+        }
+        html.push_str("</table>");
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_latex_and_html() {
+        let mut register = Register::new(2);
+        register.apply_circuit(Circuit::new(2).x(0).h(1).z(1));
+        assert_eq!(register.to_latex(), "$$0.707\\,|01\\rangle - 0.707\\,|11\\rangle$$");
+        let html = register.to_html();
+        assert_eq!(html.matches("<tr><td>").count(), 2);
+        assert!(html.contains("|11&rang;") && html.contains("50.00%"));
+    }
+
+    #[test]
+    fn test_circuit_latex_and_html() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1).rz(0.5, 1).swap(0, 1);
+        assert_eq!(
+            circuit.to_latex(),
+            "$$\\mathrm{SWAP}_{0,1}\\,\\mathrm{RZ}(0.500)_{1}\\,\\mathrm{CX}_{0,1}\\,\
+             \\mathrm{H}_{0}$$"
+        );
+        let html = circuit.to_html();
+        assert!(html.contains("<td>q0</td><td style=\"text-align:center;padding:0 4px\">[h]</td>"));
+        assert!(html.contains("●") && html.contains("⊕") && html.contains("[rz(0.500)]"));
+        assert_eq!(html.matches("×").count(), 2);
+    }
+}
@@ -0,0 +1,152 @@
+use std::fmt;
+use crate::simulator::circuit::{Circuit, Instruction, MarkerKind};
+use crate::simulator::gates::Gate;
+
+/// symbol drawn on the `k`-th qubit of `inst`
+pub(crate) fn cell(inst: &Instruction, k: usize) -> String {
+    let last = k + 1 == inst.qubits.len();
+    match inst.gate {
+        Gate::Cx | Gate::Mcx(_) if last => "⊕".to_string(),
+        Gate::Cz | Gate::Mcz(_) => "●".to_string(),
+        Gate::Swap => "×".to_string(),
+        Gate::Mcu(_, _) if last => "[u]".to_string(),
+        _ if !last => "●".to_string(),
+        ref gate => format!("[{}]", gate.label()),
+    }
+}
+
+/// text diagram with one column per moment, most significant qubit last;
+/// rows are labelled with the qubit names where given
+///
+/// A moment whose multi-qubit gates span over one another's rows is spread
+/// over several columns so no wire hides a gate.
+///
+/// Barriers are drawn as a `░` column on their qubits. Annotations go on an
+/// extra top line, starting above the column where they occur.
+pub fn draw(circuit: &Circuit) -> String {
+    let n = circuit.num_qubits();
+    let labels = circuit.qubit_labels();
+    let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let mut rows: Vec<String> =
+        labels.iter().map(|l| format!("{:<w$} ─", l, w = label_width)).collect();
+    let mut notes = String::new();
+    for (segment, markers) in circuit.split_at_markers() {
+        for moment in segment.layers() {
+            let mut cells: Vec<Option<String>> = vec![None; n];
+            for inst in &moment {
+                let lo = *inst.qubits.iter().min().expect("instruction has qubits");
+                let hi = *inst.qubits.iter().max().expect("instruction has qubits");
+                // a wire may not cross a cell already drawn in this column
+                if cells[lo..=hi].iter().any(Option::is_some) {
+                    push_column(&mut rows, &cells);
+                    cells = vec![None; n];
+                }
+                for c in &mut cells[lo..=hi] {
+                    *c = Some("│".to_string());
+                }
+                for (k, &q) in inst.qubits.iter().enumerate() {
+                    cells[q] = Some(cell(inst, k));
+                }
+            }
+            push_column(&mut rows, &cells);
+        }
+        for marker in markers {
+            match &marker.kind {
+                MarkerKind::Barrier(qubits) => {
+                    let mut cells: Vec<Option<String>> = vec![None; n];
+                    for &q in qubits {
+                        cells[q] = Some("░".to_string());
+                    }
+                    push_column(&mut rows, &cells);
+                }
+                MarkerKind::Annotation(text) => {
+                    let column = rows.first().map_or(0, |row| row.chars().count());
+                    let used = notes.chars().count();
+                    let gap = if used == 0 { column } else { column.saturating_sub(used).max(1) };
+                    notes.push_str(&" ".repeat(gap));
+                    notes.push_str(text);
+                }
+            }
+        }
+    }
+    if !notes.is_empty() {
+        rows.insert(0, notes);
+    }
+    rows.join("\n")
+}
+
+/// one centred column of cells, wires filled with `─`
+fn push_column(rows: &mut [String], cells: &[Option<String>]) {
+    let width = cells.iter().flatten().map(|c| c.chars().count()).max().unwrap_or(1);
+    for (row, c) in rows.iter_mut().zip(cells) {
+        let text = match c {
+            Some(text) if text == "│" => String::from("┼"),
+            Some(text) => text.clone(),
+            None => String::new(),
+        };
+        let pad = width - text.chars().count();
+        let left = pad / 2;
+        row.push_str(&"─".repeat(left));
+        row.push_str(&text);
+        row.push_str(&"─".repeat(pad - left));
+        row.push('─');
+    }
+}
+
+impl fmt::Display for Circuit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", draw(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bell_diagram() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).cx(0, 1);
+        let text = circuit.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines, vec!["q0 ─[h]─●─", "q1 ─────⊕─"]);
+        circuit.name_qubit(0, "alice").name_qubit(1, "bob");
+        let text = circuit.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines, vec!["alice ─[h]─●─", "bob   ─────⊕─"]);
+    }
+
+    #[test]
+    fn test_moments_share_columns() {
+        let mut circuit = Circuit::new(3);
+        circuit.x(0).rz(0.5, 2).cz(0, 2);
+        let text = draw(&circuit);
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].contains("[x]") && lines[2].contains("[rz(0.500)]"));
+        // the cz wire crosses qubit 1
+        assert!(lines[1].contains('┼'));
+        assert_eq!(lines[0].chars().count(), lines[2].chars().count());
+    }
+
+    #[test]
+    fn test_spanning_gate_gets_its_own_column() {
+        let mut circuit = Circuit::new(3);
+        circuit.x(1).cx(0, 2);
+        let text = draw(&circuit);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines, vec!["q0 ─────●─", "q1 ─[x]─┼─", "q2 ─────⊕─"]);
+    }
+
+    #[test]
+    fn test_barriers_and_annotations() {
+        let mut circuit = Circuit::new(2);
+        circuit.annotate("prepare").h(0).barrier(&[]).annotate("entangle").cx(0, 1);
+        let text = draw(&circuit);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines, vec![
+            "    prepare entangle",
+            "q0 ─[h]─░─●─",
+            "q1 ─────░─⊕─",
+        ]);
+    }
+}
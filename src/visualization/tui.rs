@@ -0,0 +1,146 @@
+use std::fmt::Write;
+use crate::simulator::circuit::{Circuit, Instruction};
+use crate::simulator::register::Register;
+
+const BAR_WIDTH: usize = 30;
+
+/// steps a circuit one instruction at a time, keeping the current state
+#[derive(Debug, Clone)]
+pub struct Stepper {
+    circuit: Circuit,
+    position: usize,
+    register: Register,
+}
+
+impl Stepper {
+    pub fn new(circuit: Circuit) -> Self {
+        let mut register = Register::new(circuit.num_qubits());
+        register.start_recording();
+        Self {
+            circuit,
+            position: 0,
+            register,
+        }
+    }
+
+    pub fn circuit(&self) -> &Circuit {
+        &self.circuit
+    }
+
+    /// number of instructions already applied
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn register(&self) -> &Register {
+        &self.register
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.circuit.len()
+    }
+
+    /// apply the next instruction; false at the end of the circuit
+    pub fn step(&mut self) -> bool {
+        match self.circuit.instructions().get(self.position) {
+            Some(inst) => {
+                self.register.apply_instruction(inst);
+                self.position += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// undo the last instruction by applying its inverse; false at the start
+    pub fn back(&mut self) -> bool {
+        if self.register.undo().is_none() {
+            return false;
+        }
+        self.position -= 1;
+        true
+    }
+
+    pub fn reset(&mut self) {
+        self.register = Register::new(self.circuit.num_qubits());
+        self.register.start_recording();
+        self.position = 0;
+    }
+}
+
+fn instruction_text(inst: &Instruction) -> String {
+    let qubits: Vec<String> = inst.qubits.iter().map(|q| format!("q{}", q)).collect();
+    format!("{} {}", inst.gate.name(), qubits.join(", "))
+}
+
+/// one screen: gate list with a cursor, amplitudes and probability bars
+pub fn render_frame(stepper: &Stepper) -> String {
+    let mut out = String::new();
+    let circuit = stepper.circuit();
+    writeln!(
+        out,
+        "memqsim ─ {} qubits, step {}/{}",
+        circuit.num_qubits(),
+        stepper.position(),
+        circuit.len()
+    )
+    .unwrap();
+    writeln!(out, "\nCircuit").unwrap();
+    for (k, inst) in circuit.instructions().iter().enumerate() {
+        let marker = if k + 1 == stepper.position() { "▶" } else { " " };
+        let done = if k < stepper.position() { "✓" } else { " " };
+        writeln!(out, " {}{} {:>3}: {}", marker, done, k, instruction_text(inst)).unwrap();
+    }
+    let n = circuit.num_qubits();
+    writeln!(out, "\nState").unwrap();
+    for (k, a) in stepper.register().amplitudes().iter().enumerate() {
+        let p = a.norm_sqr();
+        if p < 1e-10 {
+            continue;
+        }
+        let filled = (p * BAR_WIDTH as f64).round() as usize;
+        writeln!(
+            out,
+            " |{:0width$b}⟩ {:>16} {}{} {:5.1}%",
+            k,
+            format!("{:.3}", a),
+            "█".repeat(filled),
+            "·".repeat(BAR_WIDTH - filled),
+            100.0 * p,
+            width = n
+        )
+        .unwrap();
+    }
+    writeln!(out, "\n[enter] step  [b] back  [r] reset  [q] quit").unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::testing::bell;
+
+    #[test]
+    fn test_step_and_back() {
+        let mut stepper = Stepper::new(bell());
+        assert!(stepper.step() && stepper.step());
+        assert!(!stepper.step() && stepper.is_finished());
+        assert!((stepper.register().probabilities()[3] - 0.5).abs() < 1e-12);
+        assert!(stepper.back());
+        assert_eq!(stepper.position(), 1);
+        assert!((stepper.register().probabilities()[1] - 0.5).abs() < 1e-12);
+        stepper.reset();
+        assert!(!stepper.back());
+    }
+
+    #[test]
+    fn test_frame_contents() {
+        let mut stepper = Stepper::new(bell());
+        stepper.step();
+        let frame = render_frame(&stepper);
+        assert!(frame.contains("step 1/2"));
+        assert!(frame.contains("▶✓   0: h q0"));
+        assert!(frame.contains("|01⟩") && !frame.contains("|11⟩"));
+        assert!(frame.contains(" 50.0%"));
+    }
+}
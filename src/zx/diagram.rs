@@ -0,0 +1,446 @@
+use num_complex::Complex64;
+use std::collections::{BTreeMap, HashMap};
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::Gate;
+use crate::simulator::matrix::Matrix;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexKind {
+    Boundary,
+    Z,
+    X,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    Simple,
+    Hadamard,
+}
+
+impl EdgeKind {
+    /// kind of two edges composed through an identity spider
+    pub fn compose(self, other: EdgeKind) -> EdgeKind {
+        if self == other { EdgeKind::Simple } else { EdgeKind::Hadamard }
+    }
+
+    pub fn toggled(self) -> EdgeKind {
+        self.compose(EdgeKind::Hadamard)
+    }
+}
+
+/// phase reduced to [0, 2π)
+pub(crate) fn normalize_phase(phase: f64) -> f64 {
+    let p = phase.rem_euclid(2.0 * PI);
+    if 2.0 * PI - p < 1e-9 { 0.0 } else { p }
+}
+
+/// ZX diagram: spiders joined by plain or Hadamard edges, with one boundary
+/// vertex per input and output wire
+///
+/// Scalars are not tracked, so every statement about a diagram holds up to a
+/// nonzero global factor.
+#[derive(Debug, Clone)]
+pub struct ZxDiagram {
+    kinds: Vec<Option<VertexKind>>,
+    phases: Vec<f64>,
+    adjacency: Vec<BTreeMap<usize, EdgeKind>>,
+    inputs: Vec<usize>,
+    outputs: Vec<usize>,
+}
+
+/// open end of every wire while translating a circuit
+struct Wires {
+    last: Vec<usize>,
+    /// Hadamards toggle the kind of the wire's next edge
+    pending: Vec<EdgeKind>,
+}
+
+impl Wires {
+    fn spider(&mut self, d: &mut ZxDiagram, q: usize, kind: VertexKind, phase: f64) -> usize {
+        let v = d.add_vertex(kind, phase);
+        d.set_edge(self.last[q], v, self.pending[q]);
+        self.pending[q] = EdgeKind::Simple;
+        self.last[q] = v;
+        v
+    }
+}
+
+impl ZxDiagram {
+    pub fn new() -> Self {
+        Self {
+            kinds: Vec::new(),
+            phases: Vec::new(),
+            adjacency: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    pub fn add_vertex(&mut self, kind: VertexKind, phase: f64) -> usize {
+        self.kinds.push(Some(kind));
+        self.phases.push(normalize_phase(phase));
+        self.adjacency.push(BTreeMap::new());
+        self.kinds.len() - 1
+    }
+
+    pub fn remove_vertex(&mut self, v: usize) {
+        let neighbors: Vec<usize> = self.adjacency[v].keys().copied().collect();
+        for w in neighbors {
+            self.adjacency[w].remove(&v);
+        }
+        self.adjacency[v].clear();
+        self.kinds[v] = None;
+    }
+
+    /// plain edge insertion; replaces an existing edge between the two
+    pub fn set_edge(&mut self, a: usize, b: usize, kind: EdgeKind) {
+        assert_ne!(a, b, "self-loops are not stored");
+        self.adjacency[a].insert(b, kind);
+        self.adjacency[b].insert(a, kind);
+    }
+
+    pub fn remove_edge(&mut self, a: usize, b: usize) {
+        self.adjacency[a].remove(&b);
+        self.adjacency[b].remove(&a);
+    }
+
+    /// add an edge between Z spiders, resolving parallel edges and loops
+    ///
+    /// A Hadamard self-loop adds π; two parallel Hadamard edges cancel (Hopf
+    /// law); a plain edge absorbs a parallel one, and a parallel Hadamard edge
+    /// becomes a π once the plain edge is fused.
+    pub(crate) fn add_edge_smart(&mut self, a: usize, b: usize, kind: EdgeKind) {
+        if a == b {
+            if kind == EdgeKind::Hadamard {
+                self.add_phase(a, PI);
+            }
+            return;
+        }
+        match (self.edge(a, b), kind) {
+            (None, _) => self.set_edge(a, b, kind),
+            (Some(EdgeKind::Hadamard), EdgeKind::Hadamard) => self.remove_edge(a, b),
+            (Some(EdgeKind::Simple), EdgeKind::Simple) => {}
+            (Some(_), _) => {
+                self.set_edge(a, b, EdgeKind::Simple);
+                self.add_phase(a, PI);
+            }
+        }
+    }
+
+    pub fn kind(&self, v: usize) -> Option<VertexKind> {
+        self.kinds[v]
+    }
+
+    pub(crate) fn set_kind(&mut self, v: usize, kind: VertexKind) {
+        self.kinds[v] = Some(kind);
+    }
+
+    pub fn phase(&self, v: usize) -> f64 {
+        self.phases[v]
+    }
+
+    pub fn set_phase(&mut self, v: usize, phase: f64) {
+        self.phases[v] = normalize_phase(phase);
+    }
+
+    pub fn add_phase(&mut self, v: usize, phase: f64) {
+        self.set_phase(v, self.phases[v] + phase);
+    }
+
+    pub fn edge(&self, a: usize, b: usize) -> Option<EdgeKind> {
+        self.adjacency[a].get(&b).copied()
+    }
+
+    pub fn neighbors(&self, v: usize) -> impl Iterator<Item = (usize, EdgeKind)> + '_ {
+        self.adjacency[v].iter().map(|(&w, &k)| (w, k))
+    }
+
+    pub fn degree(&self, v: usize) -> usize {
+        self.adjacency[v].len()
+    }
+
+    /// live vertex ids
+    pub fn vertices(&self) -> Vec<usize> {
+        (0..self.kinds.len()).filter(|&v| self.kinds[v].is_some()).collect()
+    }
+
+    pub fn num_spiders(&self) -> usize {
+        self.kinds
+            .iter()
+            .filter(|k| matches!(k, Some(VertexKind::Z) | Some(VertexKind::X)))
+            .count()
+    }
+
+    pub fn num_edges(&self) -> usize {
+        self.adjacency.iter().map(|a| a.len()).sum::<usize>() / 2
+    }
+
+    pub fn inputs(&self) -> &[usize] {
+        &self.inputs
+    }
+
+    pub fn outputs(&self) -> &[usize] {
+        &self.outputs
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_boundary(&self, v: usize) -> bool {
+        self.kinds[v] == Some(VertexKind::Boundary)
+    }
+
+    /// diagram of a circuit over H, X, Y, Z, S, T, their inverses, Rx, Rz,
+    /// Phase, CX, CZ and SWAP
+    pub fn from_circuit(circuit: &Circuit) -> Result<Self, String> {
+        let n = circuit.num_qubits();
+        let mut d = ZxDiagram::new();
+        let mut last: Vec<usize> = Vec::with_capacity(n);
+        for _ in 0..n {
+            let v = d.add_vertex(VertexKind::Boundary, 0.0);
+            d.inputs.push(v);
+            last.push(v);
+        }
+        let pending = vec![EdgeKind::Simple; n];
+        let mut wires = Wires { last, pending };
+        for inst in circuit.instructions() {
+            let q = &inst.qubits;
+            let z_phase = match inst.gate {
+                Gate::Z | Gate::Mcz(0) => Some(PI),
+                Gate::S => Some(PI / 2.0),
+                Gate::Sdg => Some(-PI / 2.0),
+                Gate::T => Some(PI / 4.0),
+                Gate::Tdg => Some(-PI / 4.0),
+                Gate::Rz(theta) => Some(theta),
+                Gate::Phase(lambda) => Some(lambda),
+                _ => None,
+            };
+            if let Some(phase) = z_phase {
+                wires.spider(&mut d, q[0], VertexKind::Z, phase);
+                continue;
+            }
+            match inst.gate {
+                Gate::I => {}
+                Gate::H => wires.pending[q[0]] = wires.pending[q[0]].toggled(),
+                Gate::X | Gate::Mcx(0) => {
+                    wires.spider(&mut d, q[0], VertexKind::X, PI);
+                }
+                Gate::Rx(theta) => {
+                    wires.spider(&mut d, q[0], VertexKind::X, theta);
+                }
+                Gate::Y => {
+                    // Y ∝ X·Z
+                    wires.spider(&mut d, q[0], VertexKind::Z, PI);
+                    wires.spider(&mut d, q[0], VertexKind::X, PI);
+                }
+                Gate::Cx | Gate::Mcx(1) => {
+                    let c = wires.spider(&mut d, q[0], VertexKind::Z, 0.0);
+                    let t = wires.spider(&mut d, q[1], VertexKind::X, 0.0);
+                    d.set_edge(c, t, EdgeKind::Simple);
+                }
+                Gate::Cz | Gate::Mcz(1) => {
+                    let a = wires.spider(&mut d, q[0], VertexKind::Z, 0.0);
+                    let b = wires.spider(&mut d, q[1], VertexKind::Z, 0.0);
+                    d.set_edge(a, b, EdgeKind::Hadamard);
+                }
+                Gate::Swap => {
+                    wires.last.swap(q[0], q[1]);
+                    wires.pending.swap(q[0], q[1]);
+                }
+                ref other => return Err(format!("gate {} has no ZX translation", other.name())),
+            }
+        }
+        for q in 0..n {
+            let v = d.add_vertex(VertexKind::Boundary, 0.0);
+            d.set_edge(wires.last[q], v, wires.pending[q]);
+            d.outputs.push(v);
+        }
+        Ok(d)
+    }
+
+    /// linear map of the diagram by tensor contraction, rescaled to unit
+    /// operator norm per column on average; exponential in the qubit count
+    pub fn to_matrix(&self) -> Matrix {
+        let n = self.num_qubits();
+        let dim = 1usize << n;
+        // expand Hadamard edges into explicit H vertices of degree two
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        let mut h_nodes: Vec<usize> = Vec::new();
+        let mut next = self.kinds.len();
+        for v in self.vertices() {
+            for (w, kind) in self.neighbors(v) {
+                if w < v {
+                    continue;
+                }
+                if kind == EdgeKind::Simple {
+                    edges.push((v, w));
+                } else {
+                    edges.push((v, next));
+                    edges.push((next, w));
+                    h_nodes.push(next);
+                    next += 1;
+                }
+            }
+        }
+        let mut incident: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (e, &(a, b)) in edges.iter().enumerate() {
+            incident.entry(a).or_default().push(e);
+            incident.entry(b).or_default().push(e);
+        }
+        let output_index: HashMap<usize, usize> =
+            self.outputs.iter().enumerate().map(|(q, &v)| (v, q)).collect();
+        let input_index: HashMap<usize, usize> =
+            self.inputs.iter().enumerate().map(|(q, &v)| (v, q)).collect();
+
+        // greedy contraction order: inputs first, then whichever vertex
+        // leaves the fewest open edges
+        let mut order: Vec<usize> = self.inputs.clone();
+        let mut remaining: Vec<usize> = self
+            .vertices()
+            .into_iter()
+            .chain(h_nodes.iter().copied())
+            .filter(|v| !input_index.contains_key(v) && !output_index.contains_key(v))
+            .collect();
+        let mut touched = vec![0u8; edges.len()];
+        for v in &order {
+            for &e in incident.get(v).map(Vec::as_slice).unwrap_or_default() {
+                touched[e] += 1;
+            }
+        }
+        while !remaining.is_empty() {
+            let score = |v: &usize| {
+                let legs = incident.get(v).map(Vec::as_slice).unwrap_or_default();
+                let closing = legs.iter().filter(|&&e| touched[e] == 1).count() as isize;
+                legs.len() as isize - 2 * closing
+            };
+            let (k, _) = remaining
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, v)| score(v))
+                .expect("remaining is not empty");
+            let v = remaining.swap_remove(k);
+            for &e in incident.get(&v).map(Vec::as_slice).unwrap_or_default() {
+                touched[e] += 1;
+            }
+            order.push(v);
+        }
+
+        let mut result = Matrix::zeros(dim, dim);
+        for column in 0..dim {
+            // open edge ids → amplitudes keyed by their bit values
+            let mut front: Vec<usize> = Vec::new();
+            let mut states: HashMap<Vec<bool>, Complex64> = HashMap::from([(vec![], 1.0.into())]);
+            let mut seen = vec![0u8; edges.len()];
+            for &v in &order {
+                let legs = incident.get(&v).cloned().unwrap_or_default();
+                let new_legs: Vec<usize> = legs.iter().copied().filter(|&e| seen[e] == 0).collect();
+                let mut next_front = front.clone();
+                next_front.extend(&new_legs);
+                for &e in &legs {
+                    seen[e] += 1;
+                }
+                let keep: Vec<bool> = next_front.iter().map(|&e| seen[e] < 2).collect();
+                let mut next_states: HashMap<Vec<bool>, Complex64> = HashMap::new();
+                for (values, amp) in &states {
+                    for bits in 0..1usize << new_legs.len() {
+                        let mut all = values.clone();
+                        all.extend((0..new_legs.len()).map(|k| bits >> k & 1 == 1));
+                        let leg_values: Vec<bool> = legs
+                            .iter()
+                            .map(|e| all[next_front.iter().position(|f| f == e).unwrap()])
+                            .collect();
+                        let weight = if let Some(&q) = input_index.get(&v) {
+                            let bit = column >> q & 1 == 1;
+                            if leg_values[0] == bit { 1.0.into() } else { 0.0.into() }
+                        } else if v >= self.kinds.len() {
+                            let sign = if leg_values[0] && leg_values[1] { -1.0 } else { 1.0 };
+                            Complex64::new(sign * FRAC_1_SQRT_2, 0.0)
+                        } else {
+                            self.spider_weight(v, &leg_values)
+                        };
+                        if weight.norm() == 0.0 {
+                            continue;
+                        }
+                        let key: Vec<bool> =
+                            all.iter().zip(&keep).filter(|(_, &k)| k).map(|(&b, _)| b).collect();
+                        *next_states.entry(key).or_default() += amp * weight;
+                    }
+                }
+                front = next_front
+                    .into_iter()
+                    .zip(&keep)
+                    .filter(|(_, &k)| k)
+                    .map(|(e, _)| e)
+                    .collect();
+                states = next_states;
+            }
+            for (values, amp) in states {
+                let mut row = 0;
+                for (&e, &bit) in front.iter().zip(&values) {
+                    let (a, b) = edges[e];
+                    let out = output_index.get(&a).or_else(|| output_index.get(&b));
+                    if bit {
+                        row |= 1 << out.expect("open edges end at outputs");
+                    }
+                }
+                result[(row, column)] += amp;
+            }
+        }
+        let norm = result.as_slice().iter().map(|x| x.norm_sqr()).sum::<f64>() / dim as f64;
+        if norm > 0.0 {
+            result = result.scaled(Complex64::new(1.0 / norm.sqrt(), 0.0));
+        }
+        result
+    }
+
+    fn spider_weight(&self, v: usize, legs: &[bool]) -> Complex64 {
+        let phase = Complex64::from_polar(1.0, self.phases[v]);
+        match self.kinds[v].expect("live vertex") {
+            VertexKind::Z => {
+                if legs.iter().all(|&b| !b) {
+                    1.0.into()
+                } else if legs.iter().all(|&b| b) {
+                    phase
+                } else {
+                    0.0.into()
+                }
+            }
+            VertexKind::X => {
+                let odd = legs.iter().filter(|&&b| b).count() % 2 == 1;
+                let sum = if odd { 1.0 - phase } else { 1.0 + phase };
+                sum * FRAC_1_SQRT_2.powi(legs.len() as i32)
+            }
+            VertexKind::Boundary => unreachable!("boundaries are handled by the caller"),
+        }
+    }
+}
+
+impl Default for ZxDiagram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::testing::ApproxEq;
+
+    #[test]
+    fn test_circuit_translation_matches_unitary() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).cx(0, 1).t(1).rx(0.4, 2).cz(1, 2).swap(0, 2).y(1).sdg(0).cx(2, 1).h(2);
+        let diagram = ZxDiagram::from_circuit(&circuit).unwrap();
+        assert_eq!(diagram.num_qubits(), 3);
+        assert!(diagram.to_matrix().approx_eq(&circuit.to_unitary(), 1e-9));
+    }
+
+    #[test]
+    fn test_unsupported_gate() {
+        let mut circuit = Circuit::new(3);
+        circuit.mcx(&[0, 1], 2);
+        assert!(ZxDiagram::from_circuit(&circuit).unwrap_err().contains("mcx"));
+    }
+}
@@ -0,0 +1,217 @@
+use crate::simulator::circuit::Circuit;
+use crate::simulator::gates::Gate;
+use super::diagram::{EdgeKind, VertexKind, ZxDiagram};
+
+impl ZxDiagram {
+    /// spider behind boundary `b` after making their edge plain and giving the
+    /// spider no other boundary, inserting identity spiders where needed;
+    /// `None` for a bare wire between two boundaries
+    fn isolate_boundary(&mut self, b: usize) -> Option<usize> {
+        let (n, kind) = self.neighbors(b).next().expect("boundary has one edge");
+        if self.is_boundary(n) {
+            return None;
+        }
+        let shared = self.neighbors(n).any(|(w, _)| w != b && self.is_boundary(w));
+        if kind == EdgeKind::Simple && !shared {
+            return Some(n);
+        }
+        // b −k− n becomes b − a −H− n, or b − a −H− m −H− n when k is plain
+        self.remove_edge(b, n);
+        let a = self.add_vertex(VertexKind::Z, 0.0);
+        self.set_edge(b, a, EdgeKind::Simple);
+        if kind == EdgeKind::Hadamard {
+            self.set_edge(a, n, EdgeKind::Hadamard);
+        } else {
+            let m = self.add_vertex(VertexKind::Z, 0.0);
+            self.set_edge(a, m, EdgeKind::Hadamard);
+            self.set_edge(m, n, EdgeKind::Hadamard);
+        }
+        Some(a)
+    }
+
+    fn inner_neighbors(&self, v: usize) -> Vec<usize> {
+        self.neighbors(v).map(|(w, _)| w).filter(|&w| !self.is_boundary(w)).collect()
+    }
+
+    /// circuit implementing the diagram, up to global phase
+    ///
+    /// Works on a graph-like copy and peels gates off the outputs (Backens et
+    /// al., "There and back again"): frontier phases become phase gates,
+    /// edges between frontier spiders CZs, and row operations on the GF(2)
+    /// adjacency of the frontier to its neighbourhood CNOTs, until a frontier
+    /// spider has a single neighbour and is replaced by it through a Hadamard.
+    /// Fails when the diagram has no such flow, which never happens after
+    /// `clifford_simplify` of a translated circuit.
+    pub fn extract_circuit(&self) -> Result<Circuit, String> {
+        let mut d = self.clone();
+        d.to_graph_like();
+        let n = d.num_qubits();
+        let (inputs, outputs) = (d.inputs().to_vec(), d.outputs().to_vec());
+        let mut frontier: Vec<Option<usize>> =
+            outputs.iter().map(|&o| d.isolate_boundary(o)).collect();
+        for &i in &inputs {
+            d.isolate_boundary(i);
+        }
+        // gates in the order they leave the outputs, i.e. last gate first
+        let mut peeled: Vec<(Gate, Vec<usize>)> = Vec::new();
+
+        loop {
+            for (q, f) in frontier.iter().enumerate() {
+                if let Some(f) = *f {
+                    if d.phase(f) != 0.0 {
+                        peeled.push((Gate::Phase(d.phase(f)), vec![q]));
+                        d.set_phase(f, 0.0);
+                    }
+                }
+            }
+            for a in 0..n {
+                for b in a + 1..n {
+                    if let (Some(fa), Some(fb)) = (frontier[a], frontier[b]) {
+                        if d.edge(fa, fb).is_some() {
+                            d.remove_edge(fa, fb);
+                            peeled.push((Gate::Cz, vec![a, b]));
+                        }
+                    }
+                }
+            }
+
+            let rows: Vec<usize> = (0..n)
+                .filter(|&q| frontier[q].is_some_and(|f| !d.inner_neighbors(f).is_empty()))
+                .collect();
+            if rows.is_empty() {
+                break;
+            }
+            let spiders: Vec<usize> = rows.iter().map(|&q| frontier[q].unwrap()).collect();
+            let mut columns: Vec<usize> =
+                spiders.iter().flat_map(|&f| d.inner_neighbors(f)).collect();
+            columns.sort_unstable();
+            columns.dedup();
+            let mut matrix: Vec<Vec<bool>> = spiders
+                .iter()
+                .map(|&f| columns.iter().map(|&w| d.edge(f, w).is_some()).collect())
+                .collect();
+
+            // Gauss-Jordan elimination; adding row p to row r is a CNOT on the
+            // outputs that toggles r's edges to p's neighbours. A spider on an
+            // input would hand its input edge over too, so it is never a pivot.
+            let on_input: Vec<bool> = spiders
+                .iter()
+                .map(|&f| d.neighbors(f).any(|(w, _)| inputs.contains(&w)))
+                .collect();
+            let mut used = on_input.clone();
+            for c in 0..columns.len() {
+                let Some(p) = (0..rows.len()).find(|&r| !used[r] && matrix[r][c]) else {
+                    continue;
+                };
+                used[p] = true;
+                for r in 0..rows.len() {
+                    if r == p || !matrix[r][c] {
+                        continue;
+                    }
+                    for k in 0..columns.len() {
+                        if matrix[p][k] {
+                            matrix[r][k] = !matrix[r][k];
+                            let (f, w) = (spiders[r], columns[k]);
+                            if matrix[r][k] {
+                                d.set_edge(f, w, EdgeKind::Hadamard);
+                            } else {
+                                d.remove_edge(f, w);
+                            }
+                        }
+                    }
+                    peeled.push((Gate::Cx, vec![rows[r], rows[p]]));
+                }
+            }
+
+            let mut progress = false;
+            for (r, &q) in rows.iter().enumerate() {
+                let ones: Vec<usize> = (0..columns.len()).filter(|&k| matrix[r][k]).collect();
+                if on_input[r] || ones.len() != 1 {
+                    continue;
+                }
+                let w = columns[ones[0]];
+                if frontier.contains(&Some(w)) {
+                    continue;
+                }
+                peeled.push((Gate::H, vec![q]));
+                d.remove_vertex(spiders[r]);
+                d.set_edge(outputs[q], w, EdgeKind::Simple);
+                frontier[q] = Some(w);
+                progress = true;
+            }
+            if !progress {
+                return Err("diagram has no flow to extract a circuit from".into());
+            }
+        }
+
+        // what remains is a permutation of the inputs, Hadamards on bare wires
+        let mut source = vec![0; n];
+        let mut hadamards = Vec::new();
+        for q in 0..n {
+            let (w, kind) = match frontier[q] {
+                Some(f) => {
+                    let (w, kind) = d
+                        .neighbors(f)
+                        .find(|&(w, _)| inputs.contains(&w))
+                        .ok_or("output is not connected to an input")?;
+                    (w, kind)
+                }
+                None => d.neighbors(outputs[q]).next().expect("boundary has one edge"),
+            };
+            source[q] = inputs.iter().position(|&i| i == w).ok_or("output wired to output")?;
+            if kind == EdgeKind::Hadamard {
+                hadamards.push(q);
+            }
+        }
+        let mut circuit = Circuit::new(n);
+        let mut position: Vec<usize> = (0..n).collect();
+        let mut holder: Vec<usize> = (0..n).collect();
+        for q in 0..n {
+            let p = position[source[q]];
+            if p != q {
+                circuit.swap(p, q);
+                let displaced = holder[q];
+                holder[p] = displaced;
+                position[displaced] = p;
+                holder[q] = source[q];
+                position[source[q]] = q;
+            }
+        }
+        for q in hadamards {
+            circuit.h(q);
+        }
+        for (gate, qubits) in peeled.iter().rev() {
+            circuit.push(gate.clone(), qubits);
+        }
+        Ok(circuit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::rng::Rng;
+    use crate::simulator::testing::ApproxEq;
+
+    #[test]
+    fn test_extraction_preserves_the_unitary() {
+        let mut rng = Rng::seed_from_u64(59);
+        for _ in 0..20 {
+            let mut circuit = Circuit::new(4);
+            for _ in 0..30 {
+                let (a, b) = (rng.gen_range(4), rng.gen_range(4));
+                match rng.gen_range(5) {
+                    0 => circuit.h(a),
+                    1 => circuit.s(a),
+                    2 => circuit.t(a),
+                    _ if a != b => circuit.cx(a, b),
+                    _ => circuit.x(a),
+                };
+            }
+            let mut diagram = ZxDiagram::from_circuit(&circuit).unwrap();
+            diagram.clifford_simplify();
+            let extracted = diagram.extract_circuit().unwrap();
+            assert!(extracted.to_unitary().approx_eq(&circuit.to_unitary(), 1e-9));
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod diagram;
+pub mod extract;
+pub mod pipeline;
+pub mod simplify;
+
+pub use diagram::{EdgeKind, VertexKind, ZxDiagram};
+pub use pipeline::zx_equivalent;
@@ -0,0 +1,147 @@
+use crate::optimize::pipeline::cost;
+use crate::optimize::{OptimizationReport, OptimizationResult};
+use crate::simulator::circuit::Circuit;
+use crate::simulator::equivalence::EXACT_EQUIVALENCE_QUBITS;
+use super::diagram::{EdgeKind, ZxDiagram};
+
+impl Circuit {
+    /// ZX route: translate, Clifford-simplify, extract, then run the gate-level
+    /// `optimize` pipeline on the extracted circuit; equal to the input up to
+    /// global phase
+    ///
+    /// Each stretch between markers goes through the route on its own and the
+    /// markers are put back between them, so nothing crosses a barrier. A
+    /// stretch whose extracted form is longer or costs more than it is kept
+    /// as it was, so the result is never worse than the input.
+    pub fn zx_optimize(&self) -> Result<OptimizationResult, String> {
+        let mut error = None;
+        let circuit = self.map_segments(|segment| {
+            let extracted = ZxDiagram::from_circuit(segment).and_then(|mut diagram| {
+                diagram.clifford_simplify();
+                diagram.extract_circuit()
+            });
+            match extracted.map(|c| c.optimize().circuit) {
+                Ok(c) if c.len() <= segment.len()
+                    && cost(&c.metrics()) <= cost(&segment.metrics()) => c,
+                Ok(_) => segment.clone(),
+                Err(e) => {
+                    error.get_or_insert(e);
+                    segment.clone()
+                }
+            }
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+        Ok(OptimizationResult {
+            report: OptimizationReport::new("zx", self, &circuit),
+            circuit,
+        })
+    }
+}
+
+/// decide `a == b` up to global phase by simplifying `b† a` in the ZX calculus
+///
+/// `Some(true)` when the composite reduces to bare wires on their own qubits,
+/// `Some(false)` when it reduces to bare wires that permute or carry a
+/// Hadamard. Clifford simplification alone is a weak, one-sided check: it
+/// often gets stuck on equivalent circuits with T gates or gates it cannot
+/// translate. Those are then compared densely with
+/// [`Circuit::equivalent_to`] up to [`EXACT_EQUIVALENCE_QUBITS`] qubits, and
+/// give `None` beyond that.
+pub fn zx_equivalent(a: &Circuit, b: &Circuit) -> Option<bool> {
+    if a.num_qubits() != b.num_qubits() {
+        return Some(false);
+    }
+    zx_wires(a, b).or_else(|| {
+        (a.num_qubits() <= EXACT_EQUIVALENCE_QUBITS).then(|| a.equivalent_to(b, 1e-9))
+    })
+}
+
+/// the ZX verdict of `zx_equivalent`, `None` when simplification is stuck
+fn zx_wires(a: &Circuit, b: &Circuit) -> Option<bool> {
+    let mut composite = a.clone();
+    composite.append(&b.inverse());
+    let mut diagram = ZxDiagram::from_circuit(&composite).ok()?;
+    diagram.clifford_simplify();
+    let mut identity = true;
+    for (q, &o) in diagram.outputs().iter().enumerate() {
+        let (w, kind) = diagram.neighbors(o).next()?;
+        let i = diagram.inputs().iter().position(|&i| i == w)?;
+        identity &= i == q && kind == EdgeKind::Simple;
+    }
+    Some(identity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::property::{check, clifford_circuits, Strategy};
+    use crate::simulator::rng::Rng;
+    use crate::simulator::testing::ApproxEq;
+
+    #[test]
+    fn test_zx_optimize_shrinks_clifford_t_circuit() {
+        let mut circuit = Circuit::new(3);
+        circuit.h(0).cx(0, 1).t(1).cx(0, 1).h(0).s(2).cx(2, 1).h(1).h(1).cx(2, 1);
+        circuit.t(1).cx(0, 2).tdg(1).cx(0, 2).sdg(2);
+        let result = circuit.zx_optimize().unwrap();
+        assert!(result.circuit.to_unitary().approx_eq(&circuit.to_unitary(), 1e-9));
+        assert!(result.circuit.len() < circuit.len());
+        assert_eq!(result.report.pass, "zx");
+    }
+
+    #[test]
+    fn test_zx_optimize_keeps_markers() {
+        let mut circuit = Circuit::new(2);
+        circuit.t(0).barrier(&[0]).tdg(0).annotate("done").h(1).h(1);
+        let result = circuit.zx_optimize().unwrap();
+        assert_eq!(result.circuit.markers().len(), 2);
+        assert_eq!(result.circuit.markers()[0].kind, circuit.markers()[0].kind);
+        // T and T† stay on either side of the barrier instead of cancelling
+        let barrier = result.circuit.markers()[0].position;
+        assert!(barrier > 0 && barrier < result.circuit.markers()[1].position);
+        assert!(result.circuit.to_unitary().approx_eq(&circuit.to_unitary(), 1e-9));
+    }
+
+    #[test]
+    fn test_zx_equivalence() {
+        let mut a = Circuit::new(2);
+        a.h(0).cx(0, 1).t(1).cx(0, 1).h(0);
+        let mut b = Circuit::new(2);
+        b.h(0).h(1).cz(0, 1).h(1).t(1).h(1).cz(0, 1).h(1).h(0);
+        assert_eq!(zx_equivalent(&a, &b), Some(true));
+
+        let mut swapped = Circuit::new(2);
+        swapped.swap(0, 1);
+        assert_eq!(zx_equivalent(&Circuit::new(2), &swapped), Some(false));
+        // no ZX translation for Toffoli, so the dense check decides
+        let mut toffoli = Circuit::new(3);
+        toffoli.mcx(&[0, 1], 2);
+        assert_eq!(zx_wires(&toffoli, &toffoli), None);
+        assert_eq!(zx_equivalent(&toffoli, &toffoli), Some(true));
+        let mut other = Circuit::new(3);
+        other.mcx(&[0, 2], 1);
+        assert_eq!(zx_equivalent(&toffoli, &other), Some(false));
+    }
+
+    #[test]
+    fn test_zx_optimize_never_grows_a_circuit() {
+        // Clifford circuits with T gates sprinkled in
+        let strategy = |rng: &mut Rng| {
+            let mut circuit = Circuit::new(3);
+            for layer in clifford_circuits(3, 12).generate(rng).instructions() {
+                circuit.push(layer.gate.clone(), &layer.qubits);
+                if rng.gen_bool(0.3) {
+                    circuit.t(rng.gen_range(3));
+                }
+            }
+            circuit
+        };
+        check(60, 155, &strategy, |circuit| {
+            let result = circuit.zx_optimize().unwrap();
+            result.circuit.len() <= circuit.len()
+                && result.circuit.to_unitary().approx_eq(&circuit.to_unitary(), 1e-9)
+        });
+    }
+}
@@ -0,0 +1,233 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+use super::diagram::{EdgeKind, VertexKind, ZxDiagram};
+
+fn near(phase: f64, target: f64) -> bool {
+    let d = (phase - target).rem_euclid(2.0 * PI);
+    d < 1e-9 || 2.0 * PI - d < 1e-9
+}
+
+fn is_pauli(phase: f64) -> bool {
+    near(phase, 0.0) || near(phase, PI)
+}
+
+fn is_proper_clifford(phase: f64) -> bool {
+    near(phase, FRAC_PI_2) || near(phase, 3.0 * FRAC_PI_2)
+}
+
+impl ZxDiagram {
+    /// turn every X spider into a Z spider by toggling its edges
+    pub fn color_change(&mut self) {
+        for v in self.vertices() {
+            if self.kind(v) != Some(VertexKind::X) {
+                continue;
+            }
+            self.set_kind(v, VertexKind::Z);
+            let neighbors: Vec<(usize, EdgeKind)> = self.neighbors(v).collect();
+            for (w, kind) in neighbors {
+                self.set_edge(v, w, kind.toggled());
+            }
+        }
+    }
+
+    /// fuse Z spiders joined by plain edges; returns whether anything changed
+    pub fn fuse_spiders(&mut self) -> bool {
+        let mut changed = false;
+        loop {
+            let pair = self.vertices().into_iter().find_map(|v| {
+                if self.kind(v) != Some(VertexKind::Z) {
+                    return None;
+                }
+                self.neighbors(v)
+                    .find(|&(w, k)| k == EdgeKind::Simple && self.kind(w) == Some(VertexKind::Z))
+                    .map(|(w, _)| (v, w))
+            });
+            let Some((keep, gone)) = pair else {
+                return changed;
+            };
+            changed = true;
+            self.remove_edge(keep, gone);
+            self.add_phase(keep, self.phase(gone));
+            let neighbors: Vec<(usize, EdgeKind)> = self.neighbors(gone).collect();
+            self.remove_vertex(gone);
+            for (w, kind) in neighbors {
+                if self.is_boundary(w) {
+                    self.set_edge(keep, w, kind);
+                } else {
+                    self.add_edge_smart(keep, w, kind);
+                }
+            }
+        }
+    }
+
+    /// drop phase-free Z spiders of degree two, joining their neighbors
+    pub fn remove_identities(&mut self) -> bool {
+        let mut changed = false;
+        for v in self.vertices() {
+            if self.kind(v) != Some(VertexKind::Z) || self.degree(v) != 2 || self.phase(v) != 0.0
+            {
+                continue;
+            }
+            let ends: Vec<(usize, EdgeKind)> = self.neighbors(v).collect();
+            let ((a, ka), (b, kb)) = (ends[0], ends[1]);
+            self.remove_vertex(v);
+            let kind = ka.compose(kb);
+            if self.is_boundary(a) || self.is_boundary(b) {
+                self.set_edge(a, b, kind);
+            } else {
+                self.add_edge_smart(a, b, kind);
+            }
+            changed = true;
+        }
+        changed
+    }
+
+    /// color change, fusion and identity removal until only Z spiders joined
+    /// by Hadamard edges remain
+    pub fn to_graph_like(&mut self) {
+        self.color_change();
+        while self.fuse_spiders() | self.remove_identities() {}
+    }
+
+    /// Z spider whose neighbors are all Z spiders over Hadamard edges
+    fn is_interior(&self, v: usize) -> bool {
+        self.kind(v) == Some(VertexKind::Z)
+            && self
+                .neighbors(v)
+                .all(|(w, k)| k == EdgeKind::Hadamard && self.kind(w) == Some(VertexKind::Z))
+    }
+
+    fn toggle_hadamard(&mut self, a: usize, b: usize) {
+        self.add_edge_smart(a, b, EdgeKind::Hadamard);
+    }
+
+    /// remove an interior ±π/2 spider by local complementation of its neighbors
+    pub fn local_complement(&mut self) -> bool {
+        let Some(v) = self
+            .vertices()
+            .into_iter()
+            .find(|&v| self.is_interior(v) && is_proper_clifford(self.phase(v)))
+        else {
+            return false;
+        };
+        let phase = self.phase(v);
+        let neighbors: Vec<usize> = self.neighbors(v).map(|(w, _)| w).collect();
+        self.remove_vertex(v);
+        for (i, &a) in neighbors.iter().enumerate() {
+            self.add_phase(a, -phase);
+            for &b in &neighbors[i + 1..] {
+                self.toggle_hadamard(a, b);
+            }
+        }
+        true
+    }
+
+    /// remove two adjacent interior Pauli spiders by pivoting on their edge
+    pub fn pivot(&mut self) -> bool {
+        let found = self.vertices().into_iter().find_map(|u| {
+            if !self.is_interior(u) || !is_pauli(self.phase(u)) {
+                return None;
+            }
+            self.neighbors(u)
+                .map(|(w, _)| w)
+                .find(|&w| w > u && self.is_interior(w) && is_pauli(self.phase(w)))
+                .map(|w| (u, w))
+        });
+        let Some((u, v)) = found else {
+            return false;
+        };
+        let (pu, pv) = (self.phase(u), self.phase(v));
+        let nu: Vec<usize> = self.neighbors(u).map(|(w, _)| w).filter(|&w| w != v).collect();
+        let nv: Vec<usize> = self.neighbors(v).map(|(w, _)| w).filter(|&w| w != u).collect();
+        let only_u: Vec<usize> = nu.iter().copied().filter(|w| !nv.contains(w)).collect();
+        let only_v: Vec<usize> = nv.iter().copied().filter(|w| !nu.contains(w)).collect();
+        let both: Vec<usize> = nu.iter().copied().filter(|w| nv.contains(w)).collect();
+        self.remove_vertex(u);
+        self.remove_vertex(v);
+        for (xs, ys) in [(&only_u, &only_v), (&only_u, &both), (&only_v, &both)] {
+            for &x in xs {
+                for &y in ys {
+                    self.toggle_hadamard(x, y);
+                }
+            }
+        }
+        for &w in &only_u {
+            self.add_phase(w, pv);
+        }
+        for &w in &only_v {
+            self.add_phase(w, pu);
+        }
+        for &w in &both {
+            self.add_phase(w, pu + pv + PI);
+        }
+        true
+    }
+
+    /// graph-like form followed by local complementation and pivoting to a
+    /// fixed point (the Clifford simplification of Duncan et al.)
+    pub fn clifford_simplify(&mut self) {
+        self.to_graph_like();
+        loop {
+            let mut changed = false;
+            while self.local_complement() {
+                changed = true;
+            }
+            while self.pivot() {
+                changed = true;
+            }
+            changed |= self.remove_identities();
+            if !changed {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::circuit::Circuit;
+    use crate::simulator::rng::Rng;
+    use crate::simulator::testing::ApproxEq;
+
+    fn random_clifford_t(n: usize, len: usize, rng: &mut Rng) -> Circuit {
+        let mut circuit = Circuit::new(n);
+        for _ in 0..len {
+            let (a, b) = (rng.gen_range(n), rng.gen_range(n));
+            match rng.gen_range(6) {
+                0 => circuit.h(a),
+                1 => circuit.s(a),
+                2 => circuit.t(a),
+                3 if a != b => circuit.cz(a, b),
+                _ if a != b => circuit.cx(a, b),
+                _ => circuit.x(a),
+            };
+        }
+        circuit
+    }
+
+    #[test]
+    fn test_simplification_preserves_the_map() {
+        let mut rng = Rng::seed_from_u64(58);
+        for _ in 0..10 {
+            let circuit = random_clifford_t(3, 25, &mut rng);
+            let mut diagram = ZxDiagram::from_circuit(&circuit).unwrap();
+            diagram.to_graph_like();
+            assert!(diagram.to_matrix().approx_eq(&circuit.to_unitary(), 1e-9));
+            diagram.clifford_simplify();
+            assert!(diagram.to_matrix().approx_eq(&circuit.to_unitary(), 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_clifford_circuit_shrinks() {
+        let mut circuit = Circuit::new(2);
+        circuit.h(0).s(0).cx(0, 1).h(1).s(1).cz(0, 1).h(0).cx(1, 0).s(0).h(1);
+        let mut diagram = ZxDiagram::from_circuit(&circuit).unwrap();
+        let before = diagram.num_spiders();
+        diagram.clifford_simplify();
+        assert!(diagram.num_spiders() < before);
+        // no interior spiders survive Clifford simplification
+        assert!(diagram.vertices().iter().all(|&v| diagram.is_boundary(v)
+            || diagram.neighbors(v).any(|(w, _)| diagram.is_boundary(w))));
+    }
+}